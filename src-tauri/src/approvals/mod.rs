@@ -0,0 +1,121 @@
+use std::collections::{hash_map, HashMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+const MAX_RECENT_APPROVALS: usize = 100;
+
+/// A decision made in a chat channel, polled by the frontend via
+/// `get_slack_approvals` / `get_telegram_approvals`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentApproval {
+    pub action_id: String,
+    pub approved: bool,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    pub recommendation: serde_json::Value,
+    pub timestamp: u64,
+}
+
+/// Approval requests a channel is waiting on, keyed by action id, and the
+/// most recent decisions. `P` is whatever the channel needs to update the
+/// request message once it's decided.
+#[derive(Debug)]
+pub struct ApprovalQueue<P> {
+    pending: HashMap<String, P>,
+    recent: VecDeque<RecentApproval>,
+}
+
+impl<P> Default for ApprovalQueue<P> {
+    fn default() -> Self {
+        Self {
+            pending: HashMap::new(),
+            recent: VecDeque::new(),
+        }
+    }
+}
+
+impl<P> ApprovalQueue<P> {
+    pub fn add_pending(&mut self, action_id: String, pending: P) {
+        self.pending.insert(action_id, pending);
+    }
+
+    /// Take a pending request; `None` once it has been decided
+    pub fn take_pending(&mut self, action_id: &str) -> Option<P> {
+        self.pending.remove(action_id)
+    }
+
+    pub fn pending(&self) -> hash_map::Iter<'_, String, P> {
+        self.pending.iter()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn clear_pending(&mut self) {
+        self.pending.clear();
+    }
+
+    /// Remember a decision, dropping the oldest past the limit
+    pub fn record(&mut self, approval: RecentApproval) {
+        self.recent.push_back(approval);
+        while self.recent.len() > MAX_RECENT_APPROVALS {
+            self.recent.pop_front();
+        }
+    }
+
+    /// Decisions made after `since` (epoch milliseconds), oldest first
+    pub fn decided_since(&self, since: u64) -> Vec<&RecentApproval> {
+        self.recent.iter().filter(|a| a.timestamp > since).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approval(action_id: &str, timestamp: u64) -> RecentApproval {
+        RecentApproval {
+            action_id: action_id.to_string(),
+            approved: true,
+            session_id: "s1".to_string(),
+            server_id: "srv".to_string(),
+            project_name: None,
+            recommendation: serde_json::json!({}),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_pending_is_taken_once() {
+        let mut queue = ApprovalQueue::default();
+        queue.add_pending("a1".to_string(), "message");
+
+        assert_eq!(queue.pending_count(), 1);
+        assert_eq!(queue.take_pending("a1"), Some("message"));
+        assert_eq!(queue.take_pending("a1"), None);
+    }
+
+    #[test]
+    fn test_recent_decisions_are_bounded() {
+        let mut queue = ApprovalQueue::<()>::default();
+        for i in 0..(MAX_RECENT_APPROVALS as u64 + 5) {
+            queue.record(approval(&format!("a{}", i), i + 1));
+        }
+
+        let all = queue.decided_since(0);
+        assert_eq!(all.len(), MAX_RECENT_APPROVALS);
+        assert_eq!(all[0].action_id, "a5");
+        let ids: Vec<_> = queue.decided_since(103).iter().map(|a| a.action_id.as_str()).collect();
+        assert_eq!(ids, vec!["a103", "a104"]);
+    }
+
+    #[test]
+    fn test_recent_approval_uses_camel_case() {
+        let json = serde_json::to_value(approval("a1", 7)).unwrap();
+        assert_eq!(json["actionId"], "a1");
+        assert_eq!(json["sessionId"], "s1");
+    }
+}
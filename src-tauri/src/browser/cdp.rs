@@ -0,0 +1,166 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+/// How long the browser gets to answer a command
+pub const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type Pending = Arc<std::sync::Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// An event from the browser; `session_id` is set for a page's events
+#[derive(Debug, Clone)]
+pub struct CdpEvent {
+    pub method: String,
+    pub params: Value,
+    pub session_id: Option<String>,
+}
+
+/// A message from the browser: the reply to a command, or an event
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Incoming {
+    id: Option<u64>,
+    result: Option<Value>,
+    error: Option<CdpError>,
+    method: Option<String>,
+    params: Option<Value>,
+    session_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CdpError {
+    code: i64,
+    message: String,
+}
+
+/// A Chrome DevTools Protocol connection to a browser. Pages are driven
+/// through the same connection by passing their session ID.
+pub struct CdpConnection {
+    write: Mutex<SplitSink<Socket, Message>>,
+    next_id: AtomicU64,
+    pending: Pending,
+    events: broadcast::Sender<CdpEvent>,
+    reader: JoinHandle<()>,
+}
+
+impl CdpConnection {
+    /// Connect to the browser's DevTools WebSocket
+    pub async fn connect(url: &str) -> Result<Self, String> {
+        let (socket, _) = connect_async(url).await.map_err(|e| format!("Failed to connect to browser: {}", e))?;
+        let (write, mut read) = socket.split();
+        let pending = Pending::default();
+        let (events, _) = broadcast::channel(1024);
+
+        let reader = tokio::spawn({
+            let pending = pending.clone();
+            let events = events.clone();
+            async move {
+                while let Some(Ok(message)) = read.next().await {
+                    let Message::Text(text) = message else { continue };
+                    match serde_json::from_str::<Incoming>(&text) {
+                        Ok(incoming) => dispatch(incoming, &pending, &events),
+                        Err(e) => println!("[Browser] Unreadable CDP message: {}", e),
+                    }
+                }
+                // Dropping the senders fails the commands still waiting
+                pending.lock().unwrap().clear();
+            }
+        });
+
+        Ok(Self { write: Mutex::new(write), next_id: AtomicU64::new(1), pending, events, reader })
+    }
+
+    /// Send `method` to the browser, or to a page when `session_id` is
+    /// given, and wait for its result
+    pub async fn call(&self, method: &str, params: Value, session_id: Option<&str>) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut message = json!({ "id": id, "method": method, "params": params });
+        if let Some(session_id) = session_id {
+            message["sessionId"] = json!(session_id);
+        }
+
+        let (reply, result) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, reply);
+        if let Err(e) = self.write.lock().await.send(Message::Text(message.to_string())).await {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(format!("Failed to send {} to the browser: {}", method, e));
+        }
+        match tokio::time::timeout(COMMAND_TIMEOUT, result).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(format!("Browser closed before answering {}", method)),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(format!("Browser took too long to answer {}", method))
+            }
+        }
+    }
+
+    /// Events sent from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<CdpEvent> {
+        self.events.subscribe()
+    }
+}
+
+impl Drop for CdpConnection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+/// Hand a reply to the command waiting for it, or broadcast an event
+fn dispatch(incoming: Incoming, pending: &Pending, events: &broadcast::Sender<CdpEvent>) {
+    if let Some(id) = incoming.id {
+        let Some(reply) = pending.lock().unwrap().remove(&id) else { return };
+        let result = match incoming.error {
+            Some(error) => Err(format!("Browser error {}: {}", error.code, error.message)),
+            None => Ok(incoming.result.unwrap_or(Value::Null)),
+        };
+        let _ = reply.send(result);
+    } else if let Some(method) = incoming.method {
+        let params = incoming.params.unwrap_or(Value::Null);
+        let _ = events.send(CdpEvent { method, params, session_id: incoming.session_id });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn incoming(text: &str) -> Incoming {
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn test_dispatch_replies_and_events() {
+        let pending = Pending::default();
+        let (events, mut received) = broadcast::channel(8);
+        let (ok_reply, mut ok) = oneshot::channel();
+        let (err_reply, mut err) = oneshot::channel();
+        pending.lock().unwrap().insert(1, ok_reply);
+        pending.lock().unwrap().insert(2, err_reply);
+
+        dispatch(incoming(r#"{"id":1,"result":{"frameId":"F1"}}"#), &pending, &events);
+        dispatch(incoming(r#"{"id":2,"error":{"code":-32000,"message":"Cannot navigate to invalid URL"}}"#), &pending, &events);
+        dispatch(incoming(r#"{"id":3,"result":{}}"#), &pending, &events);
+        dispatch(incoming(r#"{"method":"Page.loadEventFired","params":{"timestamp":1.5},"sessionId":"S1"}"#), &pending, &events);
+
+        assert_eq!(ok.try_recv().unwrap().unwrap()["frameId"], "F1");
+        assert_eq!(err.try_recv().unwrap().unwrap_err(), "Browser error -32000: Cannot navigate to invalid URL");
+        assert!(pending.lock().unwrap().is_empty());
+        let event = received.try_recv().unwrap();
+        assert_eq!(event.method, "Page.loadEventFired");
+        assert_eq!(event.session_id.as_deref(), Some("S1"));
+        assert_eq!(event.params["timestamp"], 1.5);
+    }
+}
@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Longest text, HTML or outline returned for a page
+pub const MAX_CONTENT_CHARS: usize = 100_000;
+
+/// Roles that only group other nodes, left out of the outline unless named
+const STRUCTURAL_ROLES: &[&str] = &["none", "generic", "presentation", "InlineTextBox", "LineBreak"];
+
+/// Script returning the text and cleaned HTML of the element matching
+/// `selector`, or of the body, without scripts, styles and inline handlers
+pub fn extraction_script(selector: Option<&str>) -> String {
+    // A JSON string is also a JavaScript string literal
+    let selector = serde_json::to_string(&selector).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"(() => {{
+  const selector = {selector};
+  const root = selector === null ? document.body : document.querySelector(selector);
+  if (!root) return null;
+  const clone = root.cloneNode(true);
+  clone.querySelectorAll('script, style, noscript, template, link, meta').forEach(el => el.remove());
+  clone.querySelectorAll('svg').forEach(el => el.replaceChildren());
+  for (const el of [clone, ...clone.querySelectorAll('*')]) {{
+    for (const attr of [...el.attributes]) {{
+      if (attr.name === 'style' || attr.name.startsWith('on')) el.removeAttribute(attr.name);
+    }}
+  }}
+  return {{ text: root.innerText, html: clone.outerHTML, url: location.href, title: document.title }};
+}})()"#
+    )
+}
+
+/// Text without trailing spaces or runs of blank lines
+pub fn clean_text(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut blank = false;
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() {
+            blank = !cleaned.is_empty();
+            continue;
+        }
+        if blank {
+            cleaned.push('\n');
+            blank = false;
+        }
+        if !cleaned.is_empty() {
+            cleaned.push('\n');
+        }
+        cleaned.push_str(line);
+    }
+    cleaned
+}
+
+/// `text` cut to `max` characters, and whether it was
+pub fn truncate(text: String, max: usize) -> (String, bool) {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => (text[..end].to_string(), true),
+        None => (text, false),
+    }
+}
+
+/// An indented `role "name"` outline of `Accessibility.getFullAXTree`
+/// nodes, from the node for DOM node `root` when given. Ignored nodes and
+/// unnamed wrappers are skipped, their children shown in their place.
+pub fn accessibility_outline(nodes: &[Value], root: Option<i64>) -> String {
+    let by_id: HashMap<&str, &Value> = nodes.iter().filter_map(|node| Some((node["nodeId"].as_str()?, node))).collect();
+    let start = match root {
+        Some(backend_id) => nodes.iter().find(|node| node["backendDOMNodeId"].as_i64() == Some(backend_id)),
+        None => nodes.first(),
+    };
+    let mut outline = String::new();
+    if let Some(start) = start {
+        outline_node(start, &by_id, 0, &mut outline);
+    }
+    outline
+}
+
+fn outline_node(node: &Value, by_id: &HashMap<&str, &Value>, depth: usize, outline: &mut String) {
+    let role = node["role"]["value"].as_str().unwrap_or_default();
+    let name = node["name"]["value"].as_str().unwrap_or_default().trim();
+    let shown = !node["ignored"].as_bool().unwrap_or(false)
+        && !role.is_empty()
+        && (!name.is_empty() || !STRUCTURAL_ROLES.contains(&role));
+    let child_depth = if shown {
+        outline.push_str(&"  ".repeat(depth));
+        outline.push_str(role);
+        if !name.is_empty() {
+            outline.push_str(&format!(" {:?}", name));
+        }
+        outline.push('\n');
+        depth + 1
+    } else {
+        depth
+    };
+
+    let children = node["childIds"].as_array().map(Vec::as_slice).unwrap_or_default();
+    // StaticText repeats its parent's name when it's all the parent holds
+    let only_text = children.len() == 1 && !name.is_empty();
+    for child in children.iter().filter_map(|id| by_id.get(id.as_str()?)) {
+        if only_text && child["role"]["value"] == "StaticText" && child["name"]["value"].as_str().map(str::trim) == Some(name) {
+            continue;
+        }
+        outline_node(child, by_id, child_depth, outline);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extraction_script_quotes_selector() {
+        assert!(extraction_script(None).contains("const selector = null;"));
+        assert!(extraction_script(Some(r#"a[href="/cart"]"#)).contains(r#"const selector = "a[href=\"/cart\"]";"#));
+    }
+
+    #[test]
+    fn test_clean_text_and_truncate() {
+        assert_eq!(clean_text("\n  Cart  \n\n\n\nCheckout\t\n  \n"), "  Cart\n\nCheckout");
+        assert_eq!(truncate("héllo".to_string(), 2), ("hé".to_string(), true));
+        assert_eq!(truncate("hi".to_string(), 2), ("hi".to_string(), false));
+    }
+
+    #[test]
+    fn test_accessibility_outline() {
+        let nodes = vec![
+            json!({ "nodeId": "1", "role": { "value": "RootWebArea" }, "name": { "value": "Shop" }, "childIds": ["2"], "backendDOMNodeId": 1 }),
+            json!({ "nodeId": "2", "role": { "value": "generic" }, "name": { "value": "" }, "childIds": ["3", "5", "6"], "backendDOMNodeId": 7 }),
+            json!({ "nodeId": "3", "role": { "value": "button" }, "name": { "value": "Add to cart" }, "childIds": ["4"], "backendDOMNodeId": 8 }),
+            json!({ "nodeId": "4", "role": { "value": "StaticText" }, "name": { "value": "Add to cart" }, "childIds": [] }),
+            json!({ "nodeId": "5", "ignored": true, "role": { "value": "none" }, "childIds": [] }),
+            json!({ "nodeId": "6", "role": { "value": "heading" }, "name": { "value": "Cart" }, "childIds": [] }),
+        ];
+        assert_eq!(accessibility_outline(&nodes, None), "RootWebArea \"Shop\"\n  button \"Add to cart\"\n  heading \"Cart\"\n");
+        assert_eq!(accessibility_outline(&nodes, Some(8)), "button \"Add to cart\"\n");
+        assert_eq!(accessibility_outline(&nodes, Some(99)), "");
+    }
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// How long `wait_for` waits when no timeout is given
+pub const DEFAULT_WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often the page is checked while waiting
+pub const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Start of a script finding the first visible element matching `selector`
+/// whose text contains `has_text`, as `el`, or returning null
+fn find_element(selector: &str, has_text: Option<&str>) -> String {
+    // JSON strings are also JavaScript string literals
+    let selector = serde_json::to_string(selector).unwrap_or_default();
+    let has_text = serde_json::to_string(&has_text).unwrap_or_else(|_| "null".to_string());
+    format!(
+        r#"const hasText = {has_text};
+  const visible = el => {{
+    const rect = el.getBoundingClientRect();
+    return rect.width > 0 && rect.height > 0 && getComputedStyle(el).visibility !== 'hidden';
+  }};
+  const el = [...document.querySelectorAll({selector})]
+    .find(el => visible(el) && (hasText === null || el.innerText.includes(hasText)));
+  if (!el) return null;"#
+    )
+}
+
+/// Script scrolling the element into view and returning its centre, or
+/// null while there's no such element
+pub fn locate_script(selector: &str, has_text: Option<&str>) -> String {
+    format!(
+        r#"(() => {{
+  {}
+  el.scrollIntoView({{ block: 'center', inline: 'center' }});
+  const rect = el.getBoundingClientRect();
+  return {{ x: rect.left + rect.width / 2, y: rect.top + rect.height / 2 }};
+}})()"#,
+        find_element(selector, has_text)
+    )
+}
+
+/// Script focusing the element, emptying it first when `clear`, and
+/// returning true, or null while there's no such element
+pub fn focus_script(selector: &str, clear: bool) -> String {
+    format!(
+        r#"(() => {{
+  {}
+  el.focus();
+  if ({clear}) {{
+    if ('value' in el) el.value = '';
+    else if (el.isContentEditable) el.textContent = '';
+    el.dispatchEvent(new Event('input', {{ bubbles: true }}));
+  }}
+  return true;
+}})()"#,
+        find_element(selector, None)
+    )
+}
+
+/// `Input.dispatchKeyEvent` parameters for pressing and releasing `key`,
+/// a key name like `Enter` or a single character
+pub fn key_events(key: &str) -> Result<[Value; 2], String> {
+    let (code, key_code, text) = match key {
+        "Enter" => ("Enter".to_string(), 13, Some("\r".to_string())),
+        "Tab" => ("Tab".to_string(), 9, None),
+        "Escape" => ("Escape".to_string(), 27, None),
+        "Backspace" => ("Backspace".to_string(), 8, None),
+        "Delete" => ("Delete".to_string(), 46, None),
+        "ArrowUp" => ("ArrowUp".to_string(), 38, None),
+        "ArrowDown" => ("ArrowDown".to_string(), 40, None),
+        "ArrowLeft" => ("ArrowLeft".to_string(), 37, None),
+        "ArrowRight" => ("ArrowRight".to_string(), 39, None),
+        " " | "Space" => ("Space".to_string(), 32, Some(" ".to_string())),
+        _ => {
+            let mut chars = key.chars();
+            let (Some(c), None) = (chars.next(), chars.next()) else {
+                return Err(format!("Unknown key: {}", key));
+            };
+            let upper = c.to_ascii_uppercase();
+            let code = match c {
+                'a'..='z' | 'A'..='Z' => format!("Key{}", upper),
+                '0'..='9' => format!("Digit{}", c),
+                _ => String::new(),
+            };
+            (code, upper as u32, Some(c.to_string()))
+        }
+    };
+    let key = if key == "Space" { " " } else { key };
+    let mut down = json!({ "type": "keyDown", "key": key, "code": code, "windowsVirtualKeyCode": key_code });
+    if let Some(text) = text {
+        down["text"] = json!(text);
+    }
+    let up = json!({ "type": "keyUp", "key": key, "code": code, "windowsVirtualKeyCode": key_code });
+    Ok([down, up])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scripts_quote_their_arguments() {
+        let script = locate_script(r#"button[name="save"]"#, Some("Save 'draft'"));
+        assert!(script.contains(r#"document.querySelectorAll("button[name=\"save\"]")"#));
+        assert!(script.contains(r#"const hasText = "Save 'draft'";"#));
+        assert!(locate_script("h1", None).contains("const hasText = null;"));
+        assert!(focus_script("#email", true).contains("if (true)"));
+    }
+
+    #[test]
+    fn test_key_events() {
+        let [down, up] = key_events("Enter").unwrap();
+        assert_eq!(down["type"], "keyDown");
+        assert_eq!(down["text"], "\r");
+        assert_eq!(down["windowsVirtualKeyCode"], 13);
+        assert_eq!(up["type"], "keyUp");
+        assert!(up.get("text").is_none());
+
+        let [down, _] = key_events("k").unwrap();
+        assert_eq!((down["code"].as_str(), down["windowsVirtualKeyCode"].as_u64()), (Some("KeyK"), Some(75)));
+        assert_eq!(key_events("Space").unwrap()[0]["key"], " ");
+        assert!(key_events("Hyper").is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use uuid::Uuid;
+
+pub const NOT_INSTALLED: &str = "No Chrome, Chromium or Edge found; set CHROME_PATH to the browser's executable";
+
+/// How long the browser gets to open its DevTools endpoint
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A browser process with remote debugging on
+pub struct LaunchedBrowser {
+    pub child: Child,
+    /// The browser's DevTools WebSocket
+    pub websocket_url: String,
+    /// Profile created for this browser, removed once it closes
+    pub user_data_dir: PathBuf,
+}
+
+/// Find a Chromium-based browser: `CHROME_PATH` when set, then the usual
+/// install locations and PATH. GUI apps on macOS don't inherit the shell
+/// PATH, so the app bundles are checked first.
+pub fn locate_binary() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os("CHROME_PATH").map(PathBuf::from) {
+        return path.is_file().then_some(path);
+    }
+
+    let mut known = vec![
+        PathBuf::from("/Applications/Google Chrome.app/Contents/MacOS/Google Chrome"),
+        PathBuf::from("/Applications/Chromium.app/Contents/MacOS/Chromium"),
+        PathBuf::from("/Applications/Microsoft Edge.app/Contents/MacOS/Microsoft Edge"),
+        PathBuf::from("/Applications/Brave Browser.app/Contents/MacOS/Brave Browser"),
+    ];
+    for var in ["ProgramFiles", "ProgramFiles(x86)", "LocalAppData"] {
+        if let Some(dir) = std::env::var_os(var).map(PathBuf::from) {
+            known.push(dir.join(r"Google\Chrome\Application\chrome.exe"));
+            known.push(dir.join(r"Microsoft\Edge\Application\msedge.exe"));
+        }
+    }
+
+    let names = ["google-chrome", "google-chrome-stable", "chromium", "chromium-browser", "microsoft-edge", "chrome"];
+    let path_dirs = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let on_path = path_dirs.iter().flat_map(|dir| {
+        names.iter().map(move |name| dir.join(if cfg!(windows) { format!("{}.exe", name) } else { name.to_string() }))
+    });
+
+    known.into_iter().chain(on_path).find(|path| path.is_file())
+}
+
+/// Start `binary` with a fresh profile and remote debugging on a free port
+pub async fn launch(binary: &Path, headless: bool) -> Result<LaunchedBrowser, String> {
+    let user_data_dir = std::env::temp_dir().join(format!("ninjasquad-browser-{}", Uuid::new_v4()));
+    let mut command = Command::new(binary);
+    command
+        .arg("--remote-debugging-port=0")
+        .arg(format!("--user-data-dir={}", user_data_dir.display()))
+        .args(["--no-first-run", "--no-default-browser-check", "--disable-sync", "--disable-background-networking"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if headless {
+        command.args(["--headless=new", "--hide-scrollbars", "--mute-audio"]);
+    }
+    command.arg("about:blank");
+
+    let mut child = command.spawn().map_err(|e| format!("Failed to launch {}: {}", binary.display(), e))?;
+    let stderr = child.stderr.take().ok_or("Browser has no stderr")?;
+    let mut lines = BufReader::new(stderr).lines();
+    let websocket_url = tokio::time::timeout(LAUNCH_TIMEOUT, async {
+        while let Ok(Some(line)) = lines.next_line().await {
+            if let Some(url) = parse_devtools_url(&line) {
+                return Some(url);
+            }
+        }
+        None
+    })
+    .await;
+
+    match websocket_url {
+        Ok(Some(websocket_url)) => {
+            // Keep reading so the browser never blocks on a full pipe
+            tokio::spawn(async move { while let Ok(Some(_)) = lines.next_line().await {} });
+            Ok(LaunchedBrowser { child, websocket_url, user_data_dir })
+        }
+        _ => {
+            let _ = child.kill().await;
+            let _ = std::fs::remove_dir_all(&user_data_dir);
+            Err(format!("{} did not open a DevTools endpoint", binary.display()))
+        }
+    }
+}
+
+/// The WebSocket in Chrome's `DevTools listening on ws://...` line
+pub fn parse_devtools_url(line: &str) -> Option<String> {
+    line.trim().strip_prefix("DevTools listening on ").map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_devtools_url() {
+        assert_eq!(
+            parse_devtools_url("DevTools listening on ws://127.0.0.1:38211/devtools/browser/6f1c-42\n"),
+            Some("ws://127.0.0.1:38211/devtools/browser/6f1c-42".to_string())
+        );
+        assert_eq!(parse_devtools_url("[1015/101112.1:ERROR:bus.cc(407)] Failed to connect to the bus"), None);
+    }
+}
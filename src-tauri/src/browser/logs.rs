@@ -0,0 +1,212 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::Utc;
+use serde_json::Value;
+
+use super::cdp::CdpEvent;
+use super::types::{BrowserLogs, ConsoleMessage, FailedRequest};
+
+/// Console messages and failed requests kept per session, each
+pub const MAX_LOG_ENTRIES: usize = 1000;
+
+/// A session's most recent console messages and failed requests
+#[derive(Debug, Default)]
+pub struct LogBuffer {
+    console: VecDeque<ConsoleMessage>,
+    network: VecDeque<FailedRequest>,
+}
+
+impl LogBuffer {
+    pub fn push_console(&mut self, message: ConsoleMessage) {
+        if self.console.len() == MAX_LOG_ENTRIES {
+            self.console.pop_front();
+        }
+        self.console.push_back(message);
+    }
+
+    pub fn push_failed(&mut self, request: FailedRequest) {
+        if self.network.len() == MAX_LOG_ENTRIES {
+            self.network.pop_front();
+        }
+        self.network.push_back(request);
+    }
+
+    pub fn snapshot(&self) -> BrowserLogs {
+        BrowserLogs { console: self.console.iter().cloned().collect(), network: self.network.iter().cloned().collect() }
+    }
+
+    pub fn clear(&mut self) {
+        self.console.clear();
+        self.network.clear();
+    }
+}
+
+/// A console call, uncaught exception or browser log entry as a message
+pub fn console_message(session_id: &str, event: &CdpEvent) -> Option<ConsoleMessage> {
+    let params = &event.params;
+    let (level, source, text, frame) = match event.method.as_str() {
+        "Runtime.consoleAPICalled" => {
+            let args = params["args"].as_array().map(Vec::as_slice).unwrap_or_default();
+            let text = args.iter().map(remote_object_text).collect::<Vec<_>>().join(" ");
+            (params["type"].as_str()?.to_string(), "console", text, &params["stackTrace"]["callFrames"][0])
+        }
+        "Runtime.exceptionThrown" => {
+            let details = &params["exceptionDetails"];
+            let text = details["exception"]["description"].as_str().or(details["text"].as_str())?.to_string();
+            ("error".to_string(), "exception", text, details)
+        }
+        "Log.entryAdded" => {
+            let entry = &params["entry"];
+            (entry["level"].as_str()?.to_string(), "browser", entry["text"].as_str()?.to_string(), entry)
+        }
+        _ => return None,
+    };
+    Some(ConsoleMessage {
+        session_id: session_id.to_string(),
+        level,
+        source: source.to_string(),
+        text,
+        url: frame["url"].as_str().filter(|url| !url.is_empty()).map(str::to_string),
+        line: frame["lineNumber"].as_u64().map(|line| line + 1),
+        timestamp: Utc::now().to_rfc3339(),
+    })
+}
+
+/// How a console argument prints: strings as they are, other values as
+/// JSON, objects by their description
+fn remote_object_text(object: &Value) -> String {
+    match &object["value"] {
+        Value::String(text) => text.clone(),
+        Value::Null => object["description"]
+            .as_str()
+            .or(object["unserializableValue"].as_str())
+            .or(object["type"].as_str())
+            .unwrap_or_default()
+            .to_string(),
+        value => value.to_string(),
+    }
+}
+
+/// Follows a page's requests to report the ones that fail or get an error
+/// status
+#[derive(Debug, Default)]
+pub struct NetworkTracker {
+    /// URL and method of requests still in flight, by request ID
+    pending: HashMap<String, (String, String)>,
+}
+
+impl NetworkTracker {
+    pub fn handle(&mut self, session_id: &str, event: &CdpEvent) -> Option<FailedRequest> {
+        let params = &event.params;
+        let request_id = params["requestId"].as_str()?.to_string();
+        let (request, status, error) = match event.method.as_str() {
+            "Network.requestWillBeSent" => {
+                let request = &params["request"];
+                let url = request["url"].as_str().unwrap_or_default().to_string();
+                let method = request["method"].as_str().unwrap_or("GET").to_string();
+                self.pending.insert(request_id, (url, method));
+                return None;
+            }
+            "Network.responseReceived" => {
+                let response = &params["response"];
+                let status = response["status"].as_u64()? as u16;
+                if status < 400 {
+                    return None;
+                }
+                let status_text = response["statusText"].as_str().unwrap_or_default();
+                let request = self.pending.get(&request_id).cloned().unwrap_or_else(|| {
+                    (response["url"].as_str().unwrap_or_default().to_string(), "GET".to_string())
+                });
+                (request, Some(status), format!("{} {}", status, status_text).trim_end().to_string())
+            }
+            "Network.loadingFailed" => {
+                let request = self.pending.remove(&request_id).unwrap_or_default();
+                // Aborted by the page or a navigation, not an error
+                if params["canceled"].as_bool() == Some(true) {
+                    return None;
+                }
+                (request, None, params["errorText"].as_str().unwrap_or("Failed").to_string())
+            }
+            "Network.loadingFinished" => {
+                self.pending.remove(&request_id);
+                return None;
+            }
+            _ => return None,
+        };
+        let (url, method) = request;
+        Some(FailedRequest {
+            session_id: session_id.to_string(),
+            url,
+            method,
+            resource_type: params["type"].as_str().map(str::to_string),
+            status,
+            error,
+            timestamp: Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn event(method: &str, params: Value) -> CdpEvent {
+        CdpEvent { method: method.to_string(), params, session_id: Some("S1".to_string()) }
+    }
+
+    #[test]
+    fn test_console_messages() {
+        let logged = event("Runtime.consoleAPICalled", json!({
+            "type": "warning",
+            "args": [{ "type": "string", "value": "Cart total" }, { "type": "number", "value": 3 }, { "type": "object", "description": "Object" }],
+            "stackTrace": { "callFrames": [{ "url": "http://localhost:5173/src/cart.ts", "lineNumber": 41 }] }
+        }));
+        let message = console_message("b1", &logged).unwrap();
+        assert_eq!((message.level.as_str(), message.source.as_str()), ("warning", "console"));
+        assert_eq!(message.text, "Cart total 3 Object");
+        assert_eq!(message.url.as_deref(), Some("http://localhost:5173/src/cart.ts"));
+        assert_eq!(message.line, Some(42));
+
+        let thrown = event("Runtime.exceptionThrown", json!({
+            "exceptionDetails": { "text": "Uncaught", "url": "", "exception": { "description": "TypeError: x is undefined" } }
+        }));
+        let message = console_message("b1", &thrown).unwrap();
+        assert_eq!((message.level.as_str(), message.text.as_str()), ("error", "TypeError: x is undefined"));
+        assert_eq!(message.url, None);
+
+        assert!(console_message("b1", &event("Page.loadEventFired", json!({}))).is_none());
+    }
+
+    #[test]
+    fn test_network_tracker_reports_failures() {
+        let mut tracker = NetworkTracker::default();
+        let sent = |id: &str, url: &str| event("Network.requestWillBeSent", json!({ "requestId": id, "request": { "url": url, "method": "POST" } }));
+        assert!(tracker.handle("b1", &sent("1", "http://localhost:4000/api/cart")).is_none());
+        assert!(tracker.handle("b1", &sent("2", "http://localhost:5173/logo.png")).is_none());
+        assert!(tracker.handle("b1", &sent("3", "http://localhost:5173/app.js")).is_none());
+
+        let error = event("Network.responseReceived", json!({
+            "requestId": "1", "type": "Fetch", "response": { "url": "http://localhost:4000/api/cart", "status": 500, "statusText": "Internal Server Error" }
+        }));
+        let failed = tracker.handle("b1", &error).unwrap();
+        assert_eq!((failed.method.as_str(), failed.status), ("POST", Some(500)));
+        assert_eq!(failed.error, "500 Internal Server Error");
+        assert_eq!(failed.resource_type.as_deref(), Some("Fetch"));
+
+        let refused = event("Network.loadingFailed", json!({ "requestId": "2", "errorText": "net::ERR_CONNECTION_REFUSED", "type": "Image" }));
+        let failed_image = tracker.handle("b1", &refused).unwrap();
+        assert_eq!(failed_image.url, "http://localhost:5173/logo.png");
+        assert_eq!(failed_image.error, "net::ERR_CONNECTION_REFUSED");
+        let canceled = event("Network.loadingFailed", json!({ "requestId": "3", "errorText": "net::ERR_ABORTED", "canceled": true }));
+        assert!(tracker.handle("b1", &canceled).is_none());
+        assert!(tracker.handle("b1", &event("Network.loadingFinished", json!({ "requestId": "1" }))).is_none());
+        assert!(tracker.pending.is_empty());
+
+        let mut logs = LogBuffer::default();
+        logs.push_failed(failed);
+        assert_eq!(logs.snapshot().network.len(), 1);
+        logs.clear();
+        assert!(logs.snapshot().network.is_empty());
+    }
+}
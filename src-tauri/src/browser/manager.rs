@@ -0,0 +1,706 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tokio::process::Child;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::{oneshot, Mutex as AsyncMutex, RwLock};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::cdp::{CdpConnection, CdpEvent};
+use super::content::{accessibility_outline, clean_text, extraction_script, truncate, MAX_CONTENT_CHARS};
+use super::interact::{focus_script, key_events, locate_script, DEFAULT_WAIT_TIMEOUT, POLL_INTERVAL};
+use super::launcher::{self, LaunchedBrowser, NOT_INSTALLED};
+use super::logs::{console_message, LogBuffer, NetworkTracker};
+use super::pool::{Pool, PooledBrowser, WarmContext, REAP_INTERVAL, WARM_CONTEXTS};
+use super::screenshot;
+use super::types::{BrowserLogs, BrowserSession, BrowserStep, PageContent, ScriptReport, Screenshot, StepResult};
+
+/// How long a page gets to fire its load event
+const NAVIGATION_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long a closed browser gets to exit before it is killed
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Entry {
+    session: BrowserSession,
+    /// Its own ID, or the pooled browser's
+    browser_id: String,
+    cdp: Arc<CdpConnection>,
+    /// CDP session of the page being driven
+    page: String,
+    /// Browser context of a pooled session
+    context: Option<String>,
+    logs: Arc<Mutex<LogBuffer>>,
+    close: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Browsers started by the app, driven natively over the Chrome DevTools
+/// Protocol so verification works without Node or Playwright. A session
+/// ends when it's closed or the user closes its window. Headless sessions
+/// share a pooled browser, each in a context of its own.
+#[derive(Clone, Default)]
+pub struct BrowserManager {
+    sessions: Arc<RwLock<HashMap<String, Entry>>>,
+    pool: Arc<AsyncMutex<Pool>>,
+}
+
+impl BrowserManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A pooled session when `headless`, otherwise a browser of its own,
+    /// opening `url` if given
+    pub async fn start(&self, app: AppHandle, url: Option<&str>, headless: bool) -> Result<BrowserSession, String> {
+        if !headless {
+            return self.launch(app, url, false).await;
+        }
+        let session = self.acquire(app).await?;
+        let Some(url) = url else { return Ok(session) };
+        self.navigate_or_close(&session.id, url).await
+    }
+
+    /// Start a browser with a fresh profile, opening `url` if given. Its
+    /// page's console messages are emitted as `browser-console` and failed
+    /// requests as `browser-request-failed`, and both are kept.
+    pub async fn launch(&self, app: AppHandle, url: Option<&str>, headless: bool) -> Result<BrowserSession, String> {
+        let binary = launcher::locate_binary().ok_or(NOT_INSTALLED)?;
+        let LaunchedBrowser { child, websocket_url, user_data_dir } = launcher::launch(&binary, headless).await?;
+        let id = Uuid::new_v4().to_string();
+        let pid = child.id();
+        // Owns the process from here on, so a failed setup cleans up too
+        let (close, close_rx) = oneshot::channel();
+        let task = tokio::spawn(self.clone().supervise(id.clone(), child, user_data_dir, close_rx));
+
+        let setup = async {
+            let cdp = Arc::new(CdpConnection::connect(&websocket_url).await?);
+            let page = attach_first_page(&cdp).await?;
+            enable_page(&cdp, &page).await?;
+            let version = cdp.call("Browser.getVersion", json!({}), None).await?;
+            Ok::<_, String>((cdp, page, version["product"].as_str().unwrap_or_default().to_string()))
+        };
+        let (cdp, page, browser) = match setup.await {
+            Ok(setup) => setup,
+            Err(e) => {
+                let _ = close.send(());
+                let _ = task.await;
+                return Err(e);
+            }
+        };
+
+        let session = BrowserSession {
+            id: id.clone(),
+            pid,
+            headless,
+            pooled: false,
+            browser,
+            url: None,
+            title: None,
+            started_at: Utc::now().to_rfc3339(),
+        };
+        {
+            // Removing it takes this lock, so a browser that exited during
+            // setup is caught here rather than left listed
+            let mut sessions = self.sessions.write().await;
+            if task.is_finished() {
+                return Err("Browser exited while starting".to_string());
+            }
+            let mut entry = new_entry(app, session.clone(), id.clone(), cdp, page, None);
+            entry.close = Some(close);
+            entry.task = Some(task);
+            sessions.insert(id.clone(), entry);
+        }
+        println!("[Browser] Started {} (pid {:?}) as session {}", session.browser, pid, id);
+
+        let Some(url) = url else { return Ok(session) };
+        self.navigate_or_close(&id, url).await
+    }
+
+    async fn navigate_or_close(&self, id: &str, url: &str) -> Result<BrowserSession, String> {
+        match self.navigate(id, url).await {
+            Ok(session) => Ok(session),
+            Err(e) => {
+                let _ = self.close(id).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// A headless session in a fresh context of the pooled browser, started
+    /// on first use. Contexts share no cookies or storage, and closing the
+    /// session disposes of its context.
+    pub async fn acquire(&self, app: AppHandle) -> Result<BrowserSession, String> {
+        let mut pool = self.pool.lock().await;
+        let (browser_id, cdp, pid, product) = self.pooled_browser(&mut pool).await?;
+        let warm = match pool.warm.pop_front() {
+            Some(warm) => warm,
+            None => new_context(&cdp).await?,
+        };
+        pool.last_used = Some(Instant::now());
+
+        let id = Uuid::new_v4().to_string();
+        let session = BrowserSession {
+            id: id.clone(),
+            pid,
+            headless: true,
+            pooled: true,
+            browser: product,
+            url: None,
+            title: None,
+            started_at: Utc::now().to_rfc3339(),
+        };
+        let entry = new_entry(app, session.clone(), browser_id, cdp, warm.page, Some(warm.context_id));
+        self.sessions.write().await.insert(id, entry);
+        drop(pool);
+
+        tokio::spawn(self.clone().warm_up());
+        Ok(session)
+    }
+
+    /// The pooled browser's ID, connection, pid and product, starting it if
+    /// it isn't running
+    async fn pooled_browser(&self, pool: &mut Pool) -> Result<(String, Arc<CdpConnection>, Option<u32>, String), String> {
+        if let Some(browser) = &pool.browser {
+            return Ok((browser.id.clone(), browser.cdp.clone(), browser.pid, browser.product.clone()));
+        }
+
+        let binary = launcher::locate_binary().ok_or(NOT_INSTALLED)?;
+        let LaunchedBrowser { child, websocket_url, user_data_dir } = launcher::launch(&binary, true).await?;
+        let id = Uuid::new_v4().to_string();
+        let pid = child.id();
+        let (close, close_rx) = oneshot::channel();
+        let task = tokio::spawn(self.clone().supervise(id.clone(), child, user_data_dir, close_rx));
+        let setup = async {
+            let cdp = Arc::new(CdpConnection::connect(&websocket_url).await?);
+            let version = cdp.call("Browser.getVersion", json!({}), None).await?;
+            Ok::<_, String>((cdp, version["product"].as_str().unwrap_or_default().to_string()))
+        };
+        let (cdp, product) = match setup.await {
+            Ok(setup) => setup,
+            Err(e) => {
+                // Not awaited: it takes the pool lock held here
+                let _ = close.send(());
+                return Err(e);
+            }
+        };
+
+        println!("[Browser] Started pooled {} (pid {:?})", product, pid);
+        pool.browser = Some(PooledBrowser { id: id.clone(), cdp: cdp.clone(), pid, product: product.clone(), close, task });
+        tokio::spawn(self.clone().reap(id.clone()));
+        Ok((id, cdp, pid, product))
+    }
+
+    /// Top the pool up to `WARM_CONTEXTS` ready contexts
+    async fn warm_up(self) {
+        let mut pool = self.pool.lock().await;
+        let Some(cdp) = pool.browser.as_ref().map(|browser| browser.cdp.clone()) else { return };
+        while pool.warm.len() < WARM_CONTEXTS {
+            match new_context(&cdp).await {
+                Ok(warm) => pool.warm.push_back(warm),
+                Err(e) => {
+                    println!("[Browser] Failed to warm a pooled context: {}", e);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Close the pooled browser once it's gone unused for `IDLE_TIMEOUT`
+    async fn reap(self, browser_id: String) {
+        loop {
+            tokio::time::sleep(REAP_INTERVAL).await;
+            let active = self.sessions.read().await.values().filter(|entry| entry.browser_id == browser_id).count();
+            let browser = {
+                let mut pool = self.pool.lock().await;
+                if pool.browser.as_ref().is_none_or(|browser| browser.id != browser_id) {
+                    return;
+                }
+                if !pool.is_idle(Instant::now(), active) {
+                    continue;
+                }
+                pool.warm.clear();
+                pool.browser.take()
+            };
+            if let Some(browser) = browser {
+                println!("[Browser] Closing idle pooled browser");
+                close_pooled(browser).await;
+            }
+            return;
+        }
+    }
+
+    async fn supervise(self, id: String, mut child: Child, user_data_dir: PathBuf, close: oneshot::Receiver<()>) {
+        tokio::select! {
+            _ = child.wait() => {}
+            _ = close => {
+                if tokio::time::timeout(CLOSE_TIMEOUT, child.wait()).await.is_err() {
+                    let _ = child.kill().await;
+                }
+            }
+        }
+        self.sessions.write().await.retain(|_, entry| entry.browser_id != id);
+        {
+            let mut pool = self.pool.lock().await;
+            if pool.browser.as_ref().is_some_and(|browser| browser.id == id) {
+                pool.browser = None;
+                pool.warm.clear();
+            }
+        }
+        let _ = std::fs::remove_dir_all(&user_data_dir);
+        println!("[Browser] Browser {} closed", id);
+    }
+
+    /// The connection and page session of a browser
+    async fn page(&self, id: &str) -> Result<(Arc<CdpConnection>, String), String> {
+        let sessions = self.sessions.read().await;
+        let entry = sessions.get(id).ok_or_else(|| format!("Browser session not found: {}", id))?;
+        Ok((entry.cdp.clone(), entry.page.clone()))
+    }
+
+    /// Send a DevTools command to the session's page
+    pub async fn call(&self, id: &str, method: &str, params: Value) -> Result<Value, String> {
+        let (cdp, page) = self.page(id).await?;
+        cdp.call(method, params, Some(&page)).await
+    }
+
+    /// Open `url` and wait for the page to load
+    pub async fn navigate(&self, id: &str, url: &str) -> Result<BrowserSession, String> {
+        let (cdp, page) = self.page(id).await?;
+        let mut events = cdp.subscribe();
+        let result = cdp.call("Page.navigate", json!({ "url": url }), Some(&page)).await?;
+        if let Some(error) = result["errorText"].as_str() {
+            return Err(format!("Failed to open {}: {}", url, error));
+        }
+
+        // Navigating within the page, e.g. to an anchor, doesn't load again
+        if result.get("loaderId").is_some() {
+            let loaded = async {
+                loop {
+                    match events.recv().await {
+                        Ok(event) if event.method == "Page.loadEventFired" && event.session_id.as_deref() == Some(&page) => {
+                            return true
+                        }
+                        Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return false,
+                    }
+                }
+            };
+            match tokio::time::timeout(NAVIGATION_TIMEOUT, loaded).await {
+                Ok(true) => {}
+                Ok(false) => return Err(format!("Browser closed while loading {}", url)),
+                Err(_) => return Err(format!("{} took too long to load", url)),
+            }
+        }
+
+        let location = self.evaluate(id, "({ url: location.href, title: document.title })").await?;
+        let mut sessions = self.sessions.write().await;
+        let entry = sessions.get_mut(id).ok_or_else(|| format!("Browser session not found: {}", id))?;
+        entry.session.url = location["url"].as_str().map(str::to_string);
+        entry.session.title = location["title"].as_str().map(str::to_string);
+        Ok(entry.session.clone())
+    }
+
+    /// Run `expression` in the page, awaiting it if it's a promise, and
+    /// return its value as JSON
+    pub async fn evaluate(&self, id: &str, expression: &str) -> Result<Value, String> {
+        let params = json!({ "expression": expression, "returnByValue": true, "awaitPromise": true });
+        let result = self.call(id, "Runtime.evaluate", params).await?;
+        evaluation_value(result)
+    }
+
+    /// Wait for a visible element matching `selector`, and containing
+    /// `has_text` if given, scrolled into view; returns its centre
+    pub async fn wait_for(&self, id: &str, selector: &str, has_text: Option<&str>, timeout: Option<Duration>) -> Result<(f64, f64), String> {
+        let timeout = timeout.unwrap_or(DEFAULT_WAIT_TIMEOUT);
+        let script = locate_script(selector, has_text);
+        let started = Instant::now();
+        loop {
+            // An invalid selector throws, failing at once
+            let point = self.evaluate(id, &script).await?;
+            if let (Some(x), Some(y)) = (point["x"].as_f64(), point["y"].as_f64()) {
+                return Ok((x, y));
+            }
+            if started.elapsed() >= timeout {
+                let target = match has_text {
+                    Some(text) => format!("{} with text {:?}", selector, text),
+                    None => selector.to_string(),
+                };
+                return Err(format!("No visible {} after {:?}", target, timeout));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Click the middle of an element with the mouse, as a user would
+    pub async fn click(&self, id: &str, selector: &str, has_text: Option<&str>) -> Result<(), String> {
+        let (x, y) = self.wait_for(id, selector, has_text, None).await?;
+        self.call(id, "Input.dispatchMouseEvent", json!({ "type": "mouseMoved", "x": x, "y": y })).await?;
+        for kind in ["mousePressed", "mouseReleased"] {
+            let event = json!({ "type": kind, "x": x, "y": y, "button": "left", "buttons": 1, "clickCount": 1 });
+            self.call(id, "Input.dispatchMouseEvent", event).await?;
+        }
+        Ok(())
+    }
+
+    /// Focus an element and type `text` into it
+    pub async fn type_text(&self, id: &str, selector: &str, text: &str, clear: bool) -> Result<(), String> {
+        self.wait_for(id, selector, None, None).await?;
+        if self.evaluate(id, &focus_script(selector, clear)).await?.is_null() {
+            return Err(format!("{} went away before it could be typed into", selector));
+        }
+        self.call(id, "Input.insertText", json!({ "text": text })).await?;
+        Ok(())
+    }
+
+    /// Press and release a key in whatever has focus
+    pub async fn press_key(&self, id: &str, key: &str) -> Result<(), String> {
+        for event in key_events(key)? {
+            self.call(id, "Input.dispatchKeyEvent", event).await?;
+        }
+        Ok(())
+    }
+
+    /// Run steps in order, stopping at the first that fails
+    pub async fn run_script(&self, id: &str, steps: Vec<BrowserStep>) -> Result<ScriptReport, String> {
+        if self.get(id).await.is_none() {
+            return Err(format!("Browser session not found: {}", id));
+        }
+        let mut results = Vec::new();
+        for step in steps {
+            let started = Instant::now();
+            let outcome = match &step {
+                BrowserStep::Navigate { url } => self.navigate(id, url).await.map(|_| ()),
+                BrowserStep::Click { selector, has_text } => self.click(id, selector, has_text.as_deref()).await,
+                BrowserStep::Type { selector, text, clear } => self.type_text(id, selector, text, *clear).await,
+                BrowserStep::Press { key } => self.press_key(id, key).await,
+                BrowserStep::WaitFor { selector, has_text, timeout_ms } => self
+                    .wait_for(id, selector, has_text.as_deref(), timeout_ms.map(Duration::from_millis))
+                    .await
+                    .map(|_| ()),
+            };
+            let failed = outcome.is_err();
+            results.push(StepResult { step, error: outcome.err(), duration_ms: started.elapsed().as_millis() as u64 });
+            if failed {
+                break;
+            }
+        }
+
+        let url = self.evaluate(id, "location.href").await.ok().and_then(|url| url.as_str().map(str::to_string));
+        Ok(ScriptReport {
+            session_id: id.to_string(),
+            passed: results.iter().all(|result| result.error.is_none()),
+            steps: results,
+            url,
+        })
+    }
+
+    /// A PNG of the session's page: what's in view, or all of it when
+    /// `full_page`
+    pub async fn screenshot(&self, id: &str, full_page: bool) -> Result<Vec<u8>, String> {
+        let layout = if full_page { Some(self.call(id, "Page.getLayoutMetrics", json!({})).await?) } else { None };
+        let result = self.call(id, "Page.captureScreenshot", screenshot::capture_params(layout.as_ref())).await?;
+        screenshot::decode(&result)
+    }
+
+    /// The session, after opening `url` in it if given, or a pooled session
+    /// opened just for `url`; the bool is true for the latter,
+    /// which `done_with` closes
+    async fn open(&self, app: AppHandle, url: Option<&str>, session_id: Option<&str>) -> Result<(BrowserSession, bool), String> {
+        match (session_id, url) {
+            (Some(id), Some(url)) => Ok((self.navigate(id, url).await?, false)),
+            (Some(id), None) => Ok((self.get(id).await.ok_or_else(|| format!("Browser session not found: {}", id))?, false)),
+            (None, Some(url)) => Ok((self.start(app, Some(url), true).await?, true)),
+            (None, None) => Err("A URL or browser session is needed".to_string()),
+        }
+    }
+
+    async fn done_with(&self, session: &BrowserSession, temporary: bool) {
+        if !temporary {
+            return;
+        }
+        if let Err(e) = self.close(&session.id).await {
+            println!("[Browser] Failed to close session {}: {}", session.id, e);
+        }
+    }
+
+    /// Screenshot a session, after opening `url` in it if given, or `url`
+    /// in a pooled session opened just for it
+    pub async fn capture(
+        &self,
+        app: AppHandle,
+        url: Option<&str>,
+        session_id: Option<&str>,
+        full_page: bool,
+    ) -> Result<Screenshot, String> {
+        let (session, temporary) = self.open(app, url, session_id).await?;
+        let png = self.screenshot(&session.id, full_page).await;
+        self.done_with(&session, temporary).await;
+        let png = png?;
+        let path = screenshot::save(&png)?;
+        Ok(Screenshot {
+            path: path.to_string_lossy().to_string(),
+            session_id: session_id.map(str::to_string),
+            url: session.url,
+            full_page,
+            size: png.len(),
+            captured_at: Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// The text, cleaned HTML and accessibility outline of a page, or of
+    /// the element matching `selector`. Pages are opened as for `capture`.
+    pub async fn extract(
+        &self,
+        app: AppHandle,
+        url: Option<&str>,
+        session_id: Option<&str>,
+        selector: Option<&str>,
+    ) -> Result<PageContent, String> {
+        let (session, temporary) = self.open(app, url, session_id).await?;
+        let content = self.page_content(&session.id, selector).await;
+        self.done_with(&session, temporary).await;
+        content
+    }
+
+    async fn page_content(&self, id: &str, selector: Option<&str>) -> Result<PageContent, String> {
+        let page = self.evaluate(id, &extraction_script(selector)).await?;
+        if page.is_null() {
+            return Err(format!("Nothing on the page matches {}", selector.unwrap_or("the body")));
+        }
+
+        let root = match selector {
+            Some(selector) => Some(self.backend_node_id(id, selector).await?),
+            None => None,
+        };
+        let tree = self.call(id, "Accessibility.getFullAXTree", json!({})).await?;
+        let nodes = tree["nodes"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+        let (text, text_cut) = truncate(clean_text(page["text"].as_str().unwrap_or_default()), MAX_CONTENT_CHARS);
+        let (html, html_cut) = truncate(page["html"].as_str().unwrap_or_default().to_string(), MAX_CONTENT_CHARS);
+        let (accessibility, outline_cut) = truncate(accessibility_outline(nodes, root), MAX_CONTENT_CHARS);
+        Ok(PageContent {
+            url: page["url"].as_str().unwrap_or_default().to_string(),
+            title: page["title"].as_str().unwrap_or_default().to_string(),
+            selector: selector.map(str::to_string),
+            text,
+            html,
+            accessibility,
+            truncated: text_cut || html_cut || outline_cut,
+        })
+    }
+
+    /// The DOM node ID the accessibility tree refers to the element by
+    async fn backend_node_id(&self, id: &str, selector: &str) -> Result<i64, String> {
+        let expression = format!("document.querySelector({})", serde_json::to_string(selector).map_err(|e| e.to_string())?);
+        let element = self.call(id, "Runtime.evaluate", json!({ "expression": expression })).await?;
+        let object_id = element["result"]["objectId"].as_str().ok_or_else(|| format!("Nothing on the page matches {}", selector))?;
+        let node = self.call(id, "DOM.describeNode", json!({ "objectId": object_id })).await?;
+        node["node"]["backendNodeId"].as_i64().ok_or_else(|| format!("Browser did not describe {}", selector))
+    }
+
+    pub async fn logs(&self, id: &str) -> Result<BrowserLogs, String> {
+        let sessions = self.sessions.read().await;
+        let entry = sessions.get(id).ok_or_else(|| format!("Browser session not found: {}", id))?;
+        let logs = entry.logs.lock().unwrap().snapshot();
+        Ok(logs)
+    }
+
+    pub async fn clear_logs(&self, id: &str) -> Result<(), String> {
+        let sessions = self.sessions.read().await;
+        let entry = sessions.get(id).ok_or_else(|| format!("Browser session not found: {}", id))?;
+        entry.logs.lock().unwrap().clear();
+        Ok(())
+    }
+
+    pub async fn get(&self, id: &str) -> Option<BrowserSession> {
+        self.sessions.read().await.get(id).map(|entry| entry.session.clone())
+    }
+
+    /// Oldest first
+    pub async fn list(&self) -> Vec<BrowserSession> {
+        let mut sessions: Vec<BrowserSession> =
+            self.sessions.read().await.values().map(|entry| entry.session.clone()).collect();
+        sessions.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        sessions
+    }
+
+    /// Dispose of a pooled session's context, or close a browser of its
+    /// own and wait for it to exit, killing it after `CLOSE_TIMEOUT`
+    pub async fn close(&self, id: &str) -> Result<(), String> {
+        let (cdp, context, close, task) = {
+            let mut sessions = self.sessions.write().await;
+            let entry = sessions.get_mut(id).ok_or_else(|| format!("Browser session not found: {}", id))?;
+            let parts = (entry.cdp.clone(), entry.context.clone(), entry.close.take(), entry.task.take());
+            if parts.1.is_some() {
+                sessions.remove(id);
+            }
+            parts
+        };
+        if let Some(context) = context {
+            // Closes its page and drops its cookies and storage
+            let dispose = json!({ "browserContextId": context });
+            if let Err(e) = cdp.call("Target.disposeBrowserContext", dispose, None).await {
+                println!("[Browser] Failed to dispose of context {}: {}", context, e);
+            }
+            self.pool.lock().await.last_used = Some(Instant::now());
+            return Ok(());
+        }
+        // Closing through the protocol lets the browser stop its helper processes
+        let _ = cdp.call("Browser.close", json!({}), None).await;
+        if let Some(close) = close {
+            let _ = close.send(());
+        }
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+        Ok(())
+    }
+
+    /// Close every session and the pooled browser
+    pub async fn close_all(&self) {
+        for session in self.list().await {
+            if let Err(e) = self.close(&session.id).await {
+                println!("[Browser] Failed to close session {}: {}", session.id, e);
+            }
+        }
+        let browser = {
+            let mut pool = self.pool.lock().await;
+            pool.warm.clear();
+            pool.browser.take()
+        };
+        if let Some(browser) = browser {
+            close_pooled(browser).await;
+        }
+    }
+}
+
+/// A session's entry, forwarding its page's events from now on
+fn new_entry(
+    app: AppHandle,
+    session: BrowserSession,
+    browser_id: String,
+    cdp: Arc<CdpConnection>,
+    page: String,
+    context: Option<String>,
+) -> Entry {
+    let logs = Arc::new(Mutex::new(LogBuffer::default()));
+    tokio::spawn(forward_events(app, session.id.clone(), page.clone(), cdp.subscribe(), logs.clone()));
+    Entry { session, browser_id, cdp, page, context, logs, close: None, task: None }
+}
+
+/// Close the pooled browser and wait for it to exit. Its supervisor takes
+/// the pool lock, which mustn't be held here.
+async fn close_pooled(browser: PooledBrowser) {
+    let _ = browser.cdp.call("Browser.close", json!({}), None).await;
+    let _ = browser.close.send(());
+    let _ = browser.task.await;
+}
+
+/// Keep and emit the page's console messages and failed requests until
+/// the browser's connection closes
+async fn forward_events(
+    app: AppHandle,
+    id: String,
+    page: String,
+    mut events: broadcast::Receiver<CdpEvent>,
+    logs: Arc<Mutex<LogBuffer>>,
+) {
+    let mut network = NetworkTracker::default();
+    loop {
+        let event = match events.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                println!("[Browser] Session {} dropped {} events", id, missed);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        if event.session_id.as_deref() != Some(page.as_str()) {
+            continue;
+        }
+        if let Some(message) = console_message(&id, &event) {
+            logs.lock().unwrap().push_console(message.clone());
+            let _ = app.emit("browser-console", message);
+        } else if let Some(failed) = network.handle(&id, &event) {
+            logs.lock().unwrap().push_failed(failed.clone());
+            let _ = app.emit("browser-request-failed", failed);
+        }
+    }
+}
+
+/// Attach to the browser's first tab, opening one if there's none
+async fn attach_first_page(cdp: &CdpConnection) -> Result<String, String> {
+    let targets = cdp.call("Target.getTargets", json!({}), None).await?;
+    let existing = targets["targetInfos"]
+        .as_array()
+        .and_then(|targets| targets.iter().find(|target| target["type"] == "page"))
+        .and_then(|target| target["targetId"].as_str())
+        .map(str::to_string);
+    let target_id = match existing {
+        Some(target_id) => target_id,
+        None => {
+            let created = cdp.call("Target.createTarget", json!({ "url": "about:blank" }), None).await?;
+            created["targetId"].as_str().ok_or("Browser did not open a page")?.to_string()
+        }
+    };
+    attach(cdp, &target_id).await
+}
+
+/// A CDP session for driving the target's page
+async fn attach(cdp: &CdpConnection, target_id: &str) -> Result<String, String> {
+    let attached = cdp.call("Target.attachToTarget", json!({ "targetId": target_id, "flatten": true }), None).await?;
+    attached["sessionId"].as_str().map(str::to_string).ok_or_else(|| "Browser did not attach to its page".to_string())
+}
+
+/// Turn on the events navigation and logging rely on
+async fn enable_page(cdp: &CdpConnection, page: &str) -> Result<(), String> {
+    for method in ["Page.enable", "Runtime.enable", "Log.enable", "Network.enable"] {
+        cdp.call(method, json!({}), Some(page)).await?;
+    }
+    Ok(())
+}
+
+/// A new browser context with a blank page, attached and enabled
+async fn new_context(cdp: &CdpConnection) -> Result<WarmContext, String> {
+    let created = cdp.call("Target.createBrowserContext", json!({ "disposeOnDetach": true }), None).await?;
+    let context_id = created["browserContextId"].as_str().ok_or("Browser did not create a context")?.to_string();
+    let target = json!({ "url": "about:blank", "browserContextId": context_id });
+    let created = cdp.call("Target.createTarget", target, None).await?;
+    let target_id = created["targetId"].as_str().ok_or("Browser did not open a page")?;
+    let page = attach(cdp, target_id).await?;
+    enable_page(cdp, &page).await?;
+    Ok(WarmContext { context_id, page })
+}
+
+/// The value of a `Runtime.evaluate` result, or the exception it threw
+fn evaluation_value(result: Value) -> Result<Value, String> {
+    if let Some(details) = result.get("exceptionDetails") {
+        let message = details["exception"]["description"].as_str().or(details["text"].as_str());
+        return Err(message.unwrap_or("Script threw an exception").to_string());
+    }
+    Ok(result["result"]["value"].clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluation_value() {
+        let result = json!({ "result": { "type": "object", "value": { "title": "Shop" } } });
+        assert_eq!(evaluation_value(result).unwrap()["title"], "Shop");
+        assert_eq!(evaluation_value(json!({ "result": { "type": "undefined" } })).unwrap(), Value::Null);
+
+        let thrown = json!({
+            "result": { "type": "object", "subtype": "error" },
+            "exceptionDetails": { "text": "Uncaught", "exception": { "description": "ReferenceError: app is not defined" } }
+        });
+        assert_eq!(evaluation_value(thrown).unwrap_err(), "ReferenceError: app is not defined");
+    }
+}
@@ -0,0 +1,12 @@
+pub mod cdp;
+pub mod content;
+pub mod interact;
+pub mod launcher;
+pub mod logs;
+pub mod manager;
+pub mod pool;
+pub mod screenshot;
+pub mod types;
+
+pub use manager::BrowserManager;
+pub use types::*;
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+use super::cdp::CdpConnection;
+
+/// Fresh contexts kept ready in the pooled browser
+pub const WARM_CONTEXTS: usize = 2;
+/// The pooled browser closes after going unused this long
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+/// How often the pooled browser is checked for being idle
+pub const REAP_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A browser context with a blank page, ready to hand out
+pub struct WarmContext {
+    pub context_id: String,
+    /// CDP session of its page
+    pub page: String,
+}
+
+/// The headless browser shared by pooled sessions
+pub struct PooledBrowser {
+    pub id: String,
+    pub cdp: Arc<CdpConnection>,
+    pub pid: Option<u32>,
+    pub product: String,
+    pub close: oneshot::Sender<()>,
+    pub task: JoinHandle<()>,
+}
+
+/// Automation gets a context in one warm headless browser instead of
+/// waiting for a browser to start
+#[derive(Default)]
+pub struct Pool {
+    pub browser: Option<PooledBrowser>,
+    pub warm: VecDeque<WarmContext>,
+    /// When a context was last handed out or given back
+    pub last_used: Option<Instant>,
+}
+
+impl Pool {
+    /// Whether the browser can be closed, given how many sessions use it
+    pub fn is_idle(&self, now: Instant, active: usize) -> bool {
+        active == 0 && self.last_used.is_none_or(|used| now.duration_since(used) >= IDLE_TIMEOUT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_idles_after_timeout_without_sessions() {
+        let now = Instant::now();
+        let mut pool = Pool::default();
+        assert!(pool.is_idle(now, 0));
+        pool.last_used = Some(now);
+        assert!(!pool.is_idle(now + Duration::from_secs(60), 0));
+        assert!(pool.is_idle(now + IDLE_TIMEOUT, 0));
+        assert!(!pool.is_idle(now + IDLE_TIMEOUT, 1));
+    }
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use base64::Engine;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Tallest full-page capture; Chrome fails on much larger surfaces
+const MAX_FULL_PAGE_HEIGHT: f64 = 16_384.0;
+
+/// `Page.captureScreenshot` parameters for a PNG of the viewport, or of
+/// the whole page given its `Page.getLayoutMetrics`
+pub fn capture_params(layout: Option<&Value>) -> Value {
+    let Some(layout) = layout else { return json!({ "format": "png" }) };
+    let size = &layout["cssContentSize"];
+    let width = size["width"].as_f64().unwrap_or(0.0).ceil();
+    let height = size["height"].as_f64().unwrap_or(0.0).ceil().min(MAX_FULL_PAGE_HEIGHT);
+    json!({
+        "format": "png",
+        "captureBeyondViewport": true,
+        "clip": { "x": 0, "y": 0, "width": width, "height": height, "scale": 1 },
+    })
+}
+
+/// The PNG in a `Page.captureScreenshot` result
+pub fn decode(result: &Value) -> Result<Vec<u8>, String> {
+    let data = result["data"].as_str().ok_or("Browser returned no screenshot")?;
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .map_err(|e| format!("Failed to decode screenshot: {}", e))
+}
+
+/// Write a PNG to the app's temp folder, returning its path
+pub fn save(png: &[u8]) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir().join("ninjasquad-screenshots");
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("{}.png", Uuid::new_v4()));
+    std::fs::write(&path, png).map_err(|e| format!("Failed to save screenshot: {}", e))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_params_and_decode() {
+        assert_eq!(capture_params(None), json!({ "format": "png" }));
+
+        let layout = json!({ "cssContentSize": { "x": 0, "y": 0, "width": 1279.5, "height": 40000 } });
+        let params = capture_params(Some(&layout));
+        assert_eq!(params["captureBeyondViewport"], true);
+        assert_eq!(params["clip"]["width"], 1280.0);
+        assert_eq!(params["clip"]["height"], MAX_FULL_PAGE_HEIGHT);
+
+        assert_eq!(decode(&json!({ "data": "iVBORw==" })).unwrap(), vec![0x89, b'P', b'N', b'G']);
+        assert!(decode(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+/// A browser started by the app and driven over the DevTools protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserSession {
+    pub id: String,
+    pub pid: Option<u32>,
+    pub headless: bool,
+    /// In a context of the shared pooled browser rather than a browser of
+    /// its own
+    pub pooled: bool,
+    /// Product and version, e.g. `HeadlessChrome/131.0.6778.85`
+    pub browser: String,
+    /// Where its page is, as of the last navigation
+    pub url: Option<String>,
+    pub title: Option<String>,
+    pub started_at: String,
+}
+
+/// A screenshot saved to a temp file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Screenshot {
+    pub path: String,
+    /// None when a browser was started just for this screenshot
+    pub session_id: Option<String>,
+    pub url: Option<String>,
+    pub full_page: bool,
+    pub size: usize,
+    pub captured_at: String,
+}
+
+/// What a page shows, for feeding to an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageContent {
+    pub url: String,
+    pub title: String,
+    /// Element the content was taken from; the whole body when None
+    pub selector: Option<String>,
+    /// Visible text
+    pub text: String,
+    /// HTML without scripts, styles or inline handlers
+    pub html: String,
+    /// Indented outline of roles and names
+    pub accessibility: String,
+    /// Something was cut to `MAX_CONTENT_CHARS`
+    pub truncated: bool,
+}
+
+/// A console message, uncaught exception or browser warning from a
+/// session's page, emitted as `browser-console`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleMessage {
+    pub session_id: String,
+    /// `log`, `warning`, `error` and so on
+    pub level: String,
+    /// `console`, `exception` or `browser`
+    pub source: String,
+    pub text: String,
+    /// Script it came from
+    pub url: Option<String>,
+    pub line: Option<u64>,
+    pub timestamp: String,
+}
+
+/// A request from a session's page that failed or got an error status,
+/// emitted as `browser-request-failed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedRequest {
+    pub session_id: String,
+    pub url: String,
+    pub method: String,
+    /// `Document`, `Fetch`, `Script` and so on
+    pub resource_type: Option<String>,
+    /// None when no response came back
+    pub status: Option<u16>,
+    pub error: String,
+    pub timestamp: String,
+}
+
+/// What a session's page has logged, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserLogs {
+    pub console: Vec<ConsoleMessage>,
+    pub network: Vec<FailedRequest>,
+}
+
+/// One step of a browser script. Element steps wait for a visible match
+/// first, up to `DEFAULT_WAIT_TIMEOUT`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum BrowserStep {
+    Navigate {
+        url: String,
+    },
+    Click {
+        selector: String,
+        /// Only an element whose text contains this
+        #[serde(default)]
+        has_text: Option<String>,
+    },
+    Type {
+        selector: String,
+        text: String,
+        /// Empty the field first
+        #[serde(default)]
+        clear: bool,
+    },
+    /// A key name like `Enter` or `Tab`, or a single character
+    Press {
+        key: String,
+    },
+    /// Fails unless a matching element shows up in time, so it doubles as
+    /// an assertion
+    WaitFor {
+        selector: String,
+        #[serde(default)]
+        has_text: Option<String>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepResult {
+    pub step: BrowserStep,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// How far a browser script got; it stops at the first failed step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptReport {
+    pub session_id: String,
+    pub passed: bool,
+    /// The steps that ran, the last one failed unless `passed`
+    pub steps: Vec<StepResult>,
+    /// Where the page was when the script ended
+    pub url: Option<String>,
+}
@@ -0,0 +1,241 @@
+use crate::database::conversation::ConversationMessage;
+use crate::database::DatabaseManager;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Plugin id given to sessions imported from the Claude CLI
+pub const IMPORTED_PLUGIN_ID: &str = "claude-code";
+
+/// Status of imported sessions, so they can be told apart from live ones
+pub const IMPORTED_STATUS: &str = "imported";
+
+const TITLE_MAX_CHARS: usize = 80;
+
+/// A Claude CLI session read from its JSONL transcript
+#[derive(Debug, Clone)]
+pub struct ImportedSession {
+    pub id: String,
+    pub title: String,
+    pub model: String,
+    pub messages: Vec<ConversationMessage>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub sessions_found: usize,
+    pub sessions_created: usize,
+    pub messages_imported: usize,
+}
+
+/// Where the Claude CLI keeps per-project transcripts
+pub fn claude_projects_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".claude").join("projects"))
+}
+
+/// The CLI names each project's folder after its path with every character
+/// other than ASCII letters, digits and `-` replaced by `-`
+pub fn encode_project_path(path: &str) -> String {
+    path.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '-' })
+        .collect()
+}
+
+/// Flatten message content into text. Tool calls become a short marker and
+/// tool results are dropped, since they're rarely useful as history.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(blocks) => blocks
+            .iter()
+            .filter_map(|block| match block["type"].as_str() {
+                Some("text") => block["text"].as_str().map(str::to_string),
+                Some("tool_use") => Some(format!("[tool: {}]", block["name"].as_str().unwrap_or("unknown"))),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn title_from(text: &str) -> String {
+    let line = text.lines().find(|l| !l.trim().is_empty()).unwrap_or("").trim();
+    if line.chars().count() > TITLE_MAX_CHARS {
+        format!("{}…", line.chars().take(TITLE_MAX_CHARS).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
+
+/// Parse a transcript, keeping the main thread's user and assistant text.
+/// Returns `None` when it holds no messages.
+pub fn parse_session(session_id: &str, content: &str) -> Option<ImportedSession> {
+    let mut summary = None;
+    let mut model = None;
+    let mut messages = Vec::new();
+
+    for line in content.lines() {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+
+        match entry["type"].as_str() {
+            Some("summary") => {
+                summary = entry["summary"].as_str().map(str::to_string);
+            }
+            Some(role @ ("user" | "assistant")) => {
+                if entry["isSidechain"].as_bool() == Some(true) || entry["isMeta"].as_bool() == Some(true) {
+                    continue;
+                }
+                let (Some(id), Some(timestamp)) = (entry["uuid"].as_str(), entry["timestamp"].as_str()) else {
+                    continue;
+                };
+
+                let text = content_text(&entry["message"]["content"]);
+                if text.trim().is_empty() {
+                    continue;
+                }
+                if model.is_none() {
+                    model = entry["message"]["model"].as_str().map(str::to_string);
+                }
+
+                messages.push(ConversationMessage {
+                    id: id.to_string(),
+                    session_id: session_id.to_string(),
+                    role: role.to_string(),
+                    content: text,
+                    timestamp: timestamp.to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if messages.is_empty() {
+        return None;
+    }
+
+    let title = summary
+        .or_else(|| messages.iter().find(|m| m.role == "user").map(|m| title_from(&m.content)))
+        .unwrap_or_else(|| "Imported session".to_string());
+
+    Some(ImportedSession {
+        id: session_id.to_string(),
+        title,
+        model: model.unwrap_or_else(|| "unknown".to_string()),
+        messages,
+    })
+}
+
+/// Import every transcript the Claude CLI has for `project_path`. Sessions
+/// keep their CLI ids, so importing again only adds new messages.
+pub fn import_project(db: &DatabaseManager, project_id: &str, project_path: &str) -> Result<ImportReport, String> {
+    let mut report = ImportReport::default();
+
+    let dir = claude_projects_dir()
+        .ok_or("Could not determine home directory")?
+        .join(encode_project_path(project_path));
+    if !dir.is_dir() {
+        return Ok(report);
+    }
+
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+            continue;
+        }
+        let Some(session_id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                println!("Skipping {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let Some(session) = parse_session(session_id, &content) else {
+            continue;
+        };
+
+        report.sessions_found += 1;
+        let (created, imported) = db
+            .with_connection(|conn| {
+                let tx = conn.unchecked_transaction()?;
+                let first = &session.messages[0].timestamp;
+                let last = &session.messages[session.messages.len() - 1].timestamp;
+
+                let created = tx.execute(
+                    "INSERT OR IGNORE INTO plugin_sessions (
+                        id, project_id, plugin_id, title, working_directory,
+                        model, permission_mode, created_at, last_active, status, config
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'default', ?7, ?8, ?9, ?10)",
+                    params![
+                        session.id,
+                        project_id,
+                        IMPORTED_PLUGIN_ID,
+                        session.title,
+                        project_path,
+                        session.model,
+                        first,
+                        last,
+                        IMPORTED_STATUS,
+                        serde_json::json!({ "imported_from": path.to_string_lossy() }).to_string(),
+                    ],
+                )?;
+
+                let mut imported = 0;
+                for message in &session.messages {
+                    imported += tx.execute(
+                        "INSERT OR IGNORE INTO conversation_messages (id, session_id, role, content, timestamp)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![message.id, message.session_id, message.role, message.content, message.timestamp],
+                    )?;
+                }
+
+                tx.commit()?;
+                Ok((created, imported))
+            })
+            .map_err(|e| format!("Failed to import session {}: {}", session.id, e))?;
+
+        report.sessions_created += created;
+        report.messages_imported += imported;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_project_path() {
+        assert_eq!(encode_project_path("/Users/me/my_app.v2"), "-Users-me-my-app-v2");
+    }
+
+    #[test]
+    fn test_parse_session_keeps_main_thread_text() {
+        let transcript = [
+            r#"{"type":"user","uuid":"u1","timestamp":"2024-01-01T00:00:00Z","message":{"role":"user","content":"Fix the auth bug\nin login"}}"#,
+            r#"{"type":"assistant","uuid":"a1","timestamp":"2024-01-01T00:00:05Z","message":{"role":"assistant","model":"claude-sonnet-4","content":[{"type":"text","text":"Looking now."},{"type":"tool_use","name":"Read","input":{}}]}}"#,
+            r#"{"type":"user","uuid":"u2","timestamp":"2024-01-01T00:00:06Z","message":{"role":"user","content":[{"type":"tool_result","content":"file"}]}}"#,
+            r#"{"type":"assistant","uuid":"s1","isSidechain":true,"timestamp":"2024-01-01T00:00:07Z","message":{"content":"subagent"}}"#,
+            "not json",
+        ]
+        .join("\n");
+
+        let session = parse_session("abc", &transcript).unwrap();
+        assert_eq!(session.title, "Fix the auth bug");
+        assert_eq!(session.model, "claude-sonnet-4");
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session.messages[1].content, "Looking now.\n[tool: Read]");
+
+        let with_summary = format!("{}\n{}", r#"{"type":"summary","summary":"Auth fix"}"#, transcript);
+        assert_eq!(parse_session("abc", &with_summary).unwrap().title, "Auth fix");
+
+        assert!(parse_session("empty", r#"{"type":"summary","summary":"x"}"#).is_none());
+    }
+}
@@ -1,4 +1,6 @@
 use super::types::*;
+use crate::database::usage::{UsageSample, UsageTracker};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -15,26 +17,56 @@ pub struct ClaudeProcess {
 
 pub struct ClaudeProcessManager {
     processes: Arc<RwLock<HashMap<String, ClaudeProcess>>>,
+    usage: UsageTracker,
+}
+
+/// Final message of `claude --print --output-format json`
+#[derive(Deserialize)]
+struct PrintResult {
+    result: String,
+    #[serde(default)]
+    total_cost_usd: f64,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default)]
+    usage: PrintUsage,
+}
+
+#[derive(Default, Deserialize)]
+struct PrintUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
 }
 
 impl ClaudeProcessManager {
     pub fn new() -> Self {
         Self {
             processes: Arc::new(RwLock::new(HashMap::new())),
+            usage: UsageTracker::new(),
         }
     }
 
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = usage;
+        self
+    }
+
     pub async fn create_session(
         &self,
         project_id: String,
         working_directory: Option<String>,
         model: Option<String>,
     ) -> Result<String, String> {
-        // Check if a session for this project already exists
+        // Check if a session for this project (and directory, if given) already exists
         let existing_session = {
             let processes = self.processes.read().await;
             processes.iter()
-                .find(|(_, p)| p.session.project_id == project_id)
+                .find(|(_, p)| {
+                    p.session.project_id == project_id
+                        && (working_directory.is_none() || p.session.working_directory == working_directory)
+                })
                 .map(|(id, _)| id.clone())
         };
 
@@ -92,6 +124,8 @@ impl ClaudeProcessManager {
         // Build the Claude command - use --continue to maintain conversation context
         let mut cmd = Command::new("claude");
         cmd.arg("--print");
+        // JSON output carries token usage and cost alongside the reply
+        cmd.arg("--output-format").arg("json");
 
         // Use --continue to resume the most recent conversation
         // This is simpler than managing session IDs and avoids "already in use" errors
@@ -123,6 +157,8 @@ impl ClaudeProcessManager {
 
         println!("[ClaudeManager] Executing Claude command with --print and session context");
 
+        let started = std::time::Instant::now();
+
         // Spawn the process
         let mut child = cmd.spawn()
             .map_err(|e| format!("Failed to spawn Claude process: {}", e))?;
@@ -150,9 +186,32 @@ impl ClaudeProcessManager {
             return Err(format!("Claude error: {}", error));
         }
 
-        let response = String::from_utf8(output.stdout)
+        let stdout = String::from_utf8(output.stdout)
             .map_err(|e| format!("Failed to parse output: {}", e))?;
 
+        let mut sample = UsageSample {
+            project_id: Some(process.session.project_id.clone()),
+            source: "claude".to_string(),
+            tasks: 1,
+            agent_time_ms: started.elapsed().as_millis() as u64,
+            ..Default::default()
+        };
+
+        // Older CLIs ignore --output-format and print plain text
+        let response = match serde_json::from_str::<PrintResult>(&stdout) {
+            Ok(result) => {
+                sample.input_tokens = result.usage.input_tokens;
+                sample.output_tokens = result.usage.output_tokens;
+                sample.cost_usd = result.total_cost_usd;
+                if result.duration_ms > 0 {
+                    sample.agent_time_ms = result.duration_ms;
+                }
+                result.result
+            }
+            Err(_) => stdout,
+        };
+        self.usage.record(sample);
+
         if response.is_empty() {
             return Err("No response received from Claude".to_string());
         }
@@ -238,4 +297,29 @@ impl ClaudeProcessManager {
             }
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_sessions_are_reused_per_project_and_directory() {
+        let manager = ClaudeProcessManager::new();
+        let main = manager.create_session("p1".to_string(), Some("/work/app".to_string()), None).await.unwrap();
+
+        assert_eq!(manager.create_session("p1".to_string(), None, None).await.unwrap(), main);
+        assert_eq!(manager.create_session("p1".to_string(), Some("/work/app".to_string()), None).await.unwrap(), main);
+
+        // An isolated session lives in its own worktree
+        let isolated = manager
+            .create_session("p1".to_string(), Some("/work/app/.git/ninjasquad-worktrees/claude-1".to_string()), None)
+            .await
+            .unwrap();
+        assert_ne!(isolated, main);
+        assert_eq!(
+            manager.get_session(&isolated).await.unwrap().working_directory.as_deref(),
+            Some("/work/app/.git/ninjasquad-worktrees/claude-1")
+        );
+        assert_ne!(manager.create_session("p2".to_string(), None, None).await.unwrap(), main);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod import;
 pub mod manager;
 pub mod types;
 pub mod service;
@@ -0,0 +1,85 @@
+use rusqlite::{Connection, Result, params};
+use serde::{Deserialize, Serialize};
+
+/// A completed or failed unit of agent work, used for notification digests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentActivity {
+    pub id: String,
+    pub project_name: String,
+    pub session_id: Option<String>,
+    pub summary: String,
+    pub success: bool,
+    pub created_at: String,
+}
+
+/// Record an agent activity entry
+pub fn record_activity(conn: &Connection, activity: &AgentActivity) -> Result<()> {
+    conn.execute(
+        "INSERT INTO agent_activity (id, project_name, session_id, summary, success, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            activity.id,
+            activity.project_name,
+            activity.session_id,
+            activity.summary,
+            activity.success,
+            activity.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Get all activity recorded after `since` (RFC 3339), oldest first
+pub fn list_activity_since(conn: &Connection, since: &str) -> Result<Vec<AgentActivity>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_name, session_id, summary, success, created_at
+         FROM agent_activity
+         WHERE created_at > ?1
+         ORDER BY created_at ASC",
+    )?;
+
+    let activity = stmt
+        .query_map([since], |row| {
+            Ok(AgentActivity {
+                id: row.get(0)?,
+                project_name: row.get(1)?,
+                session_id: row.get(2)?,
+                summary: row.get(3)?,
+                success: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(activity)
+}
+
+/// Delete activity older than `before` (RFC 3339)
+pub fn prune_activity(conn: &Connection, before: &str) -> Result<usize> {
+    conn.execute("DELETE FROM agent_activity WHERE created_at < ?1", [before])
+}
+
+/// Get all activity for a project, oldest first
+pub fn list_project_activity(conn: &Connection, project_name: &str) -> Result<Vec<AgentActivity>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_name, session_id, summary, success, created_at
+         FROM agent_activity
+         WHERE project_name = ?1
+         ORDER BY created_at ASC",
+    )?;
+
+    let activity = stmt
+        .query_map([project_name], |row| {
+            Ok(AgentActivity {
+                id: row.get(0)?,
+                project_name: row.get(1)?,
+                session_id: row.get(2)?,
+                summary: row.get(3)?,
+                success: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(activity)
+}
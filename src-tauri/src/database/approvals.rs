@@ -0,0 +1,273 @@
+use rusqlite::{Connection, Result, params, types::ToSql};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tokio::sync::RwLock;
+
+/// One approval request and, once made, its decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub id: String,
+    /// Channel the request went out on (`slack`, `telegram`)
+    pub channel: String,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    pub recommendation: serde_json::Value,
+    pub tool_use: Option<serde_json::Value>,
+    /// Epoch milliseconds
+    pub requested_at: i64,
+    pub decision: Option<String>,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<i64>,
+    pub latency_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalFilter {
+    pub channel: Option<String>,
+    pub project_name: Option<String>,
+    pub session_id: Option<String>,
+    /// `approved`, `denied` or `pending`
+    pub decision: Option<String>,
+    /// Epoch milliseconds, inclusive
+    pub since: Option<i64>,
+    /// Epoch milliseconds, exclusive
+    pub until: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// Write to the audit log from a channel's service (`Slack`, `Telegram`),
+/// logging rather than failing so a missing database never blocks a decision
+pub async fn audit<F>(app_handle: &RwLock<Option<AppHandle>>, channel: &str, f: F)
+where
+    F: FnOnce(&Connection) -> Result<()>,
+{
+    let service = format!("{} service", channel);
+    if let Err(e) = super::with_app_db(app_handle, &service, f).await {
+        println!("[{}] Failed to write approval audit log: {}", channel, e);
+    }
+}
+
+/// Record a newly sent approval request
+pub fn record_request(conn: &Connection, record: &ApprovalRecord) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO approval_audit
+         (id, channel, session_id, server_id, project_name, recommendation, tool_use, requested_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            record.id,
+            record.channel,
+            record.session_id,
+            record.server_id,
+            record.project_name,
+            record.recommendation.to_string(),
+            record.tool_use.as_ref().map(|v| v.to_string()),
+            record.requested_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Record the decision for a pending request. Returns false if the request
+/// is unknown or was already decided.
+pub fn record_decision(
+    conn: &Connection,
+    id: &str,
+    approved: bool,
+    decided_by: &str,
+    decided_at: i64,
+) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE approval_audit
+         SET decision = ?2, decided_by = ?3, decided_at = ?4, latency_ms = ?4 - requested_at
+         WHERE id = ?1 AND decision IS NULL",
+        params![id, if approved { "approved" } else { "denied" }, decided_by, decided_at],
+    )?;
+    Ok(updated > 0)
+}
+
+/// List approval records matching the filter, newest first
+pub fn list_approvals(conn: &Connection, filter: &ApprovalFilter) -> Result<Vec<ApprovalRecord>> {
+    let mut sql = String::from(
+        "SELECT id, channel, session_id, server_id, project_name, recommendation, tool_use,
+                requested_at, decision, decided_by, decided_at, latency_ms
+         FROM approval_audit WHERE 1 = 1",
+    );
+    let mut args: Vec<Box<dyn ToSql>> = Vec::new();
+
+    if let Some(channel) = &filter.channel {
+        args.push(Box::new(channel.clone()));
+        sql.push_str(&format!(" AND channel = ?{}", args.len()));
+    }
+    if let Some(project_name) = &filter.project_name {
+        args.push(Box::new(project_name.clone()));
+        sql.push_str(&format!(" AND project_name = ?{}", args.len()));
+    }
+    if let Some(session_id) = &filter.session_id {
+        args.push(Box::new(session_id.clone()));
+        sql.push_str(&format!(" AND session_id = ?{}", args.len()));
+    }
+    match filter.decision.as_deref() {
+        Some("pending") => sql.push_str(" AND decision IS NULL"),
+        Some(decision) => {
+            args.push(Box::new(decision.to_string()));
+            sql.push_str(&format!(" AND decision = ?{}", args.len()));
+        }
+        None => {}
+    }
+    if let Some(since) = filter.since {
+        args.push(Box::new(since));
+        sql.push_str(&format!(" AND requested_at >= ?{}", args.len()));
+    }
+    if let Some(until) = filter.until {
+        args.push(Box::new(until));
+        sql.push_str(&format!(" AND requested_at < ?{}", args.len()));
+    }
+
+    sql.push_str(" ORDER BY requested_at DESC");
+    if let Some(limit) = filter.limit {
+        sql.push_str(&format!(" LIMIT {}", limit));
+    }
+
+    let mut stmt = conn.prepare(&sql)?;
+    let records = stmt
+        .query_map(rusqlite::params_from_iter(args.iter().map(|a| a.as_ref())), |row| {
+            let recommendation: String = row.get(5)?;
+            let tool_use: Option<String> = row.get(6)?;
+            Ok(ApprovalRecord {
+                id: row.get(0)?,
+                channel: row.get(1)?,
+                session_id: row.get(2)?,
+                server_id: row.get(3)?,
+                project_name: row.get(4)?,
+                recommendation: serde_json::from_str(&recommendation).unwrap_or(serde_json::Value::Null),
+                tool_use: tool_use.and_then(|t| serde_json::from_str(&t).ok()),
+                requested_at: row.get(7)?,
+                decision: row.get(8)?,
+                decided_by: row.get(9)?,
+                decided_at: row.get(10)?,
+                latency_ms: row.get(11)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(records)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Render records as CSV for export
+pub fn to_csv(records: &[ApprovalRecord]) -> String {
+    let mut csv = String::from(
+        "id,channel,project_name,session_id,server_id,requested_at,decision,decided_by,decided_at,latency_ms,recommendation,tool_use\n",
+    );
+
+    for record in records {
+        let fields = [
+            record.id.clone(),
+            record.channel.clone(),
+            record.project_name.clone().unwrap_or_default(),
+            record.session_id.clone(),
+            record.server_id.clone(),
+            record.requested_at.to_string(),
+            record.decision.clone().unwrap_or_else(|| "pending".to_string()),
+            record.decided_by.clone().unwrap_or_default(),
+            record.decided_at.map(|t| t.to_string()).unwrap_or_default(),
+            record.latency_ms.map(|l| l.to_string()).unwrap_or_default(),
+            record.recommendation.to_string(),
+            record.tool_use.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+        ];
+        csv.push_str(&fields.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(","));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(id: &str, channel: &str, project: &str, requested_at: i64) -> ApprovalRecord {
+        ApprovalRecord {
+            id: id.to_string(),
+            channel: channel.to_string(),
+            session_id: format!("session-{}", id),
+            server_id: "srv".to_string(),
+            project_name: Some(project.to_string()),
+            recommendation: json!({ "recommendation": "Run the tests", "confidence": 0.9 }),
+            tool_use: None,
+            requested_at,
+            decision: None,
+            decided_by: None,
+            decided_at: None,
+            latency_ms: None,
+        }
+    }
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        record_request(&conn, &record("a1", "slack", "web", 1_000)).unwrap();
+        record_request(&conn, &record("a2", "telegram", "web", 2_000)).unwrap();
+        record_request(&conn, &record("a3", "slack", "api", 3_000)).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_record_decision_only_once() {
+        let conn = setup();
+
+        assert!(record_decision(&conn, "a1", true, "U1", 1_250).unwrap());
+        assert!(!record_decision(&conn, "a1", false, "U2", 1_500).unwrap());
+        assert!(!record_decision(&conn, "missing", true, "U1", 1_500).unwrap());
+
+        let filter = ApprovalFilter { session_id: Some("session-a1".to_string()), ..Default::default() };
+        let decided = &list_approvals(&conn, &filter).unwrap()[0];
+        assert_eq!(decided.decision.as_deref(), Some("approved"));
+        assert_eq!(decided.decided_by.as_deref(), Some("U1"));
+        assert_eq!(decided.latency_ms, Some(250));
+        assert_eq!(decided.recommendation["confidence"], 0.9);
+    }
+
+    #[test]
+    fn test_list_filters_newest_first() {
+        let conn = setup();
+        record_decision(&conn, "a2", false, "@sam", 2_100).unwrap();
+
+        let ids = |filter: ApprovalFilter| -> Vec<String> {
+            list_approvals(&conn, &filter).unwrap().into_iter().map(|r| r.id).collect()
+        };
+
+        assert_eq!(ids(ApprovalFilter::default()), vec!["a3", "a2", "a1"]);
+        assert_eq!(ids(ApprovalFilter { channel: Some("slack".to_string()), ..Default::default() }), vec!["a3", "a1"]);
+        assert_eq!(ids(ApprovalFilter { project_name: Some("web".to_string()), ..Default::default() }), vec!["a2", "a1"]);
+        assert_eq!(ids(ApprovalFilter { decision: Some("denied".to_string()), ..Default::default() }), vec!["a2"]);
+        assert_eq!(ids(ApprovalFilter { decision: Some("pending".to_string()), ..Default::default() }), vec!["a3", "a1"]);
+        assert_eq!(ids(ApprovalFilter { since: Some(2_000), until: Some(3_000), ..Default::default() }), vec!["a2"]);
+        assert_eq!(ids(ApprovalFilter { limit: Some(1), ..Default::default() }), vec!["a3"]);
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let mut approval = record("a1", "slack", "web, \"beta\"", 1_000);
+        approval.decision = Some("approved".to_string());
+        approval.decided_by = Some("U1".to_string());
+
+        let csv = to_csv(&[approval, record("a2", "telegram", "api", 2_000)]);
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("id,channel,project_name,"));
+        assert!(lines[1].starts_with("a1,slack,\"web, \"\"beta\"\"\",session-a1,srv,1000,approved,U1,,,"));
+        assert!(lines[1].ends_with(",\"{\"\"confidence\"\":0.9,\"\"recommendation\"\":\"\"Run the tests\"\"}\","));
+        assert!(lines[2].starts_with("a2,telegram,api,session-a2,srv,2000,pending,"));
+    }
+}
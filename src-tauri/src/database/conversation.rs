@@ -1,7 +1,7 @@
 use rusqlite::{Connection, Result, params};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationMessage {
     pub id: String,
     pub session_id: String,
@@ -10,6 +10,27 @@ pub struct ConversationMessage {
     pub timestamp: String,
 }
 
+/// A plugin session's conversation, as listed by `list_conversations`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    pub session_id: String,
+    pub project_id: String,
+    pub title: String,
+    pub status: String,
+    pub message_count: usize,
+    pub last_message_at: Option<String>,
+    pub created_at: String,
+}
+
+/// One page of results and the total across all pages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
 /// Add a message to the conversation history
 pub fn add_message(
     conn: &Connection,
@@ -54,6 +75,86 @@ pub fn get_session_messages(
     Ok(messages)
 }
 
+/// Get a page of a session's messages, oldest first
+pub fn get_messages_page(
+    conn: &Connection,
+    session_id: &str,
+    limit: usize,
+    offset: usize,
+) -> Result<Page<ConversationMessage>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, session_id, role, content, timestamp
+         FROM conversation_messages
+         WHERE session_id = ?1
+         ORDER BY timestamp ASC
+         LIMIT ?2 OFFSET ?3",
+    )?;
+
+    let items = stmt
+        .query_map(params![session_id, limit, offset], |row| {
+            Ok(ConversationMessage {
+                id: row.get(0)?,
+                session_id: row.get(1)?,
+                role: row.get(2)?,
+                content: row.get(3)?,
+                timestamp: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Page {
+        items,
+        total: count_messages(conn, session_id)?,
+        offset,
+        limit,
+    })
+}
+
+/// List conversations, most recently active first, optionally for one
+/// project. Archived sessions are left out unless `include_archived` is set.
+pub fn list_conversations(
+    conn: &Connection,
+    project_id: Option<&str>,
+    include_archived: bool,
+    limit: usize,
+    offset: usize,
+) -> Result<Page<ConversationSummary>> {
+    let filter = "(?1 IS NULL OR s.project_id = ?1) AND (?2 OR COALESCE(s.status, 'active') != 'archived')";
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT s.id, s.project_id, s.title, COALESCE(s.status, 'active'), COUNT(m.id), MAX(m.timestamp), s.created_at
+         FROM plugin_sessions s
+         LEFT JOIN conversation_messages m ON m.session_id = s.id
+         WHERE {}
+         GROUP BY s.id
+         ORDER BY COALESCE(MAX(m.timestamp), s.last_active, s.created_at) DESC
+         LIMIT ?3 OFFSET ?4",
+        filter
+    ))?;
+
+    let items = stmt
+        .query_map(params![project_id, include_archived, limit, offset], |row| {
+            Ok(ConversationSummary {
+                session_id: row.get(0)?,
+                project_id: row.get(1)?,
+                title: row.get(2)?,
+                status: row.get(3)?,
+                message_count: row.get(4)?,
+                last_message_at: row.get(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let total: usize = conn.query_row(
+        &format!("SELECT COUNT(*) FROM plugin_sessions s WHERE {}", filter),
+        params![project_id, include_archived],
+        |row| row.get(0),
+    )?;
+
+    Ok(Page { items, total, offset, limit })
+}
+
 /// Get recent messages for a session (with limit)
 pub fn get_recent_messages(
     conn: &Connection,
@@ -123,3 +224,70 @@ pub fn trim_session_messages(
     )?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'web', '/work/web'), ('p2', 'api', '/work/api');",
+        ).unwrap();
+        conn
+    }
+
+    fn add_session(conn: &Connection, id: &str, project_id: &str, status: &str, created_at: &str) {
+        conn.execute(
+            "INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model, status, created_at)
+             VALUES (?1, ?2, 'claude-agent', ?1, '/work', 'sonnet', ?3, ?4)",
+            params![id, project_id, status, created_at],
+        ).unwrap();
+    }
+
+    #[test]
+    fn test_get_messages_page() {
+        let conn = setup();
+        add_session(&conn, "s1", "p1", "active", "2024-01-01T00:00:00Z");
+        for i in 0..5 {
+            let timestamp = format!("2024-01-01T00:00:0{}Z", i);
+            add_message(&conn, &format!("m{}", i), "s1", "user", &format!("message {}", i), &timestamp).unwrap();
+        }
+
+        let page = get_messages_page(&conn, "s1", 2, 3).unwrap();
+        let ids: Vec<_> = page.items.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m3", "m4"]);
+        assert_eq!((page.total, page.offset, page.limit), (5, 3, 2));
+
+        let past_end = get_messages_page(&conn, "s1", 2, 10).unwrap();
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 5);
+    }
+
+    #[test]
+    fn test_list_conversations_filters_and_orders() {
+        let conn = setup();
+        add_session(&conn, "quiet", "p1", "active", "2024-01-03T00:00:00Z");
+        add_session(&conn, "busy", "p1", "active", "2024-01-01T00:00:00Z");
+        add_session(&conn, "old", "p1", "archived", "2024-01-02T00:00:00Z");
+        add_session(&conn, "other", "p2", "active", "2024-01-01T00:00:00Z");
+        add_message(&conn, "m1", "busy", "user", "hi", "2024-01-04T00:00:00Z").unwrap();
+        add_message(&conn, "m2", "busy", "assistant", "hello", "2024-01-04T00:00:01Z").unwrap();
+
+        let page = list_conversations(&conn, Some("p1"), false, 10, 0).unwrap();
+        let ids: Vec<_> = page.items.iter().map(|c| c.session_id.as_str()).collect();
+        assert_eq!(ids, vec!["busy", "quiet"]);
+        assert_eq!(page.total, 2);
+        assert_eq!(page.items[0].message_count, 2);
+        assert_eq!(page.items[0].last_message_at.as_deref(), Some("2024-01-04T00:00:01Z"));
+        assert_eq!((page.items[1].message_count, page.items[1].last_message_at.as_deref()), (0, None));
+
+        let with_archived = list_conversations(&conn, Some("p1"), true, 10, 0).unwrap();
+        assert_eq!(with_archived.total, 3);
+        assert_eq!(with_archived.items[2].status, "archived");
+
+        let everything = list_conversations(&conn, None, false, 1, 1).unwrap();
+        assert_eq!((everything.items.len(), everything.total), (1, 3));
+    }
+}
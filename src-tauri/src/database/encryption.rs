@@ -0,0 +1,106 @@
+use rusqlite::{ffi, params, Connection, Error, Result};
+use std::io::Read;
+use std::path::Path;
+
+const KEYCHAIN_SERVICE: &str = "ninjasquad";
+const KEYCHAIN_ACCOUNT: &str = "database-key";
+
+/// Every unencrypted SQLite file starts with this header
+const PLAINTEXT_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+fn failure(code: i32, message: String) -> Error {
+    Error::SqliteFailure(ffi::Error::new(code), Some(message))
+}
+
+/// Fetch the database key from the OS keychain, generating and storing one
+/// on first use
+pub fn database_key() -> Result<String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| failure(ffi::SQLITE_AUTH, format!("Failed to open keychain entry: {}", e)))?;
+
+    match entry.get_password() {
+        Ok(key) => Ok(key),
+        Err(keyring::Error::NoEntry) => {
+            let bytes: [u8; 32] = rand::random();
+            let key: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+            entry
+                .set_password(&key)
+                .map_err(|e| failure(ffi::SQLITE_AUTH, format!("Failed to store database key in keychain: {}", e)))?;
+            Ok(key)
+        }
+        Err(e) => Err(failure(ffi::SQLITE_AUTH, format!("Failed to read database key from keychain: {}", e))),
+    }
+}
+
+/// Whether the file at `path` is an unencrypted SQLite database
+pub fn is_plaintext(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| &header == PLAINTEXT_HEADER)
+        .unwrap_or(false)
+}
+
+/// Rewrite a plaintext database as an encrypted one, replacing it in place
+pub fn encrypt_plaintext(path: &Path, key: &str) -> Result<()> {
+    let encrypted_path = path.with_extension("db.encrypting");
+    let _ = std::fs::remove_file(&encrypted_path);
+
+    {
+        let conn = Connection::open(path)?;
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            params![encrypted_path.to_string_lossy(), key],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+    }
+
+    std::fs::rename(&encrypted_path, path).map_err(|e| {
+        failure(ffi::SQLITE_CANTOPEN, format!("Failed to replace {}: {}", path.display(), e))
+    })
+}
+
+/// Open the database with the keychain key, encrypting it first if it was
+/// created by a build without encryption
+pub fn open(path: &Path) -> Result<Connection> {
+    let key = database_key()?;
+
+    if is_plaintext(path) {
+        println!("Encrypting existing database at {}", path.display());
+        encrypt_plaintext(path, &key)?;
+    }
+
+    let conn = Connection::open(path)?;
+    conn.pragma_update(None, "key", &key)?;
+    // The key isn't checked until the first read; a wrong one fails here
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))?;
+
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_plaintext_database() {
+        let path = std::env::temp_dir().join(format!("ninjasquad-encryption-{}.db", uuid::Uuid::new_v4()));
+        {
+            let conn = Connection::open(&path).unwrap();
+            conn.execute_batch("CREATE TABLE notes (body TEXT); INSERT INTO notes VALUES ('secret');")
+                .unwrap();
+        }
+        assert!(is_plaintext(&path));
+
+        encrypt_plaintext(&path, "test-key").unwrap();
+        assert!(!is_plaintext(&path));
+
+        let conn = Connection::open(&path).unwrap();
+        conn.pragma_update(None, "key", "test-key").unwrap();
+        let body: String = conn.query_row("SELECT body FROM notes", [], |row| row.get(0)).unwrap();
+        assert_eq!(body, "secret");
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
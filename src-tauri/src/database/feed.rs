@@ -0,0 +1,185 @@
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    SessionCreated,
+    TaskCompleted,
+    TaskFailed,
+    ApprovalRequested,
+    ApprovalDecided,
+}
+
+/// One entry in a project's activity feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub kind: ActivityKind,
+    pub title: String,
+    pub detail: Option<String>,
+    pub session_id: Option<String>,
+    /// RFC 3339
+    pub timestamp: String,
+}
+
+/// History tables store times as RFC 3339, SQLite's `CURRENT_TIMESTAMP`
+/// format, or epoch milliseconds; bring them to one sortable form
+fn normalize(raw: &str) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(raw) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S") {
+        return Some(Utc.from_utc_datetime(&time));
+    }
+    raw.parse::<i64>().ok().and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+}
+
+struct Row {
+    kind: ActivityKind,
+    title: String,
+    detail: Option<String>,
+    session_id: Option<String>,
+    time: String,
+}
+
+fn query(conn: &Connection, sql: &str, params: impl rusqlite::Params, out: &mut Vec<Row>) -> Result<()> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params, |row| {
+        let kind: String = row.get(0)?;
+        Ok(Row {
+            kind: match kind.as_str() {
+                "session_created" => ActivityKind::SessionCreated,
+                "task_completed" => ActivityKind::TaskCompleted,
+                "task_failed" => ActivityKind::TaskFailed,
+                "approval_requested" => ActivityKind::ApprovalRequested,
+                _ => ActivityKind::ApprovalDecided,
+            },
+            title: row.get(1)?,
+            detail: row.get(2)?,
+            session_id: row.get(3)?,
+            time: row.get(4)?,
+        })
+    })?;
+    for row in rows {
+        out.push(row?);
+    }
+    Ok(())
+}
+
+/// Newest-first feed of a project's sessions, agent and queue tasks and
+/// approvals. Dev server restarts aren't persisted, so they don't appear.
+pub fn project_activity(conn: &Connection, project_id: &str, project_name: &str, limit: usize) -> Result<Vec<ActivityEvent>> {
+    let mut rows = Vec::new();
+
+    query(
+        conn,
+        "SELECT 'session_created', title, plugin_id || ' · ' || model, id, CAST(created_at AS TEXT)
+         FROM plugin_sessions WHERE project_id = ?1
+         ORDER BY created_at DESC LIMIT ?2",
+        params![project_id, limit],
+        &mut rows,
+    )?;
+
+    query(
+        conn,
+        "SELECT CASE WHEN success THEN 'task_completed' ELSE 'task_failed' END, summary, NULL, session_id, created_at
+         FROM agent_activity WHERE project_name = ?1
+         ORDER BY created_at DESC LIMIT ?2",
+        params![project_name, limit],
+        &mut rows,
+    )?;
+
+    query(
+        conn,
+        "SELECT CASE WHEN r.success THEN 'task_completed' ELSE 'task_failed' END,
+                t.task_type || ' task', r.error, NULL, r.completed_at
+         FROM queue_tasks t JOIN queue_task_results r ON r.task_id = t.id
+         WHERE json_extract(t.payload, '$.project_id') = ?1
+         ORDER BY r.completed_at DESC LIMIT ?2",
+        params![project_id, limit],
+        &mut rows,
+    )?;
+
+    query(
+        conn,
+        "SELECT 'approval_requested', recommendation, tool_use, session_id, CAST(requested_at AS TEXT)
+         FROM approval_audit WHERE project_name = ?1
+         ORDER BY requested_at DESC LIMIT ?2",
+        params![project_name, limit],
+        &mut rows,
+    )?;
+
+    query(
+        conn,
+        "SELECT 'approval_decided', 'Approval ' || decision, decided_by, session_id, CAST(decided_at AS TEXT)
+         FROM approval_audit WHERE project_name = ?1 AND decided_at IS NOT NULL
+         ORDER BY decided_at DESC LIMIT ?2",
+        params![project_name, limit],
+        &mut rows,
+    )?;
+
+    let mut events: Vec<(DateTime<Utc>, ActivityEvent)> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let time = normalize(&row.time)?;
+            Some((
+                time,
+                ActivityEvent {
+                    kind: row.kind,
+                    title: row.title,
+                    detail: row.detail,
+                    session_id: row.session_id,
+                    timestamp: time.to_rfc3339(),
+                },
+            ))
+        })
+        .collect();
+
+    events.sort_by_key(|(time, _)| std::cmp::Reverse(*time));
+    events.truncate(limit);
+    Ok(events.into_iter().map(|(_, event)| event).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_merges_sources_newest_first() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api'), ('p2', 'web', '/tmp/web');
+             INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model, created_at)
+             VALUES ('s1', 'p1', 'claude', 'Fix auth', '/tmp/api', 'sonnet', '2024-01-01 09:00:00'),
+                    ('s2', 'p2', 'claude', 'Other', '/tmp/web', 'sonnet', '2024-01-01 09:30:00');
+             INSERT INTO agent_activity (id, project_name, session_id, summary, success, created_at)
+             VALUES ('a1', 'api', 's1', 'Fixed token expiry', 1, '2024-01-01T10:00:00+00:00');
+             INSERT INTO queue_tasks (id, task_type, payload, priority, max_retries, created_at)
+             VALUES ('t1', 'execute_code', '{\"project_id\":\"p1\"}', 1, 0, '2024-01-01T10:30:00Z');
+             INSERT INTO queue_task_results (task_id, worker_id, success, error, execution_time_ms, completed_at)
+             VALUES ('t1', 'w1', 0, 'exit 1', 5, '2024-01-01T10:45:00Z');
+             INSERT INTO approval_audit (id, channel, session_id, server_id, project_name, recommendation, requested_at, decision, decided_by, decided_at)
+             VALUES ('ap1', 'slack', 's1', 'srv', 'api', 'Run migrations', 1704106800000, 'approved', 'sam', 1704110400000);",
+        )
+        .unwrap();
+
+        let feed = project_activity(&conn, "p1", "api", 10).unwrap();
+        let kinds: Vec<_> = feed.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ActivityKind::ApprovalDecided,
+                ActivityKind::ApprovalRequested,
+                ActivityKind::TaskFailed,
+                ActivityKind::TaskCompleted,
+                ActivityKind::SessionCreated,
+            ]
+        );
+        assert_eq!(feed[0].title, "Approval approved");
+        assert_eq!(feed[4].timestamp, "2024-01-01T09:00:00+00:00");
+
+        assert_eq!(project_activity(&conn, "p1", "api", 2).unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,128 @@
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use super::migrations;
+
+/// Row count for one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableStats {
+    pub name: String,
+    pub rows: u64,
+}
+
+/// Result of `check_database`, for the diagnostics panel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseHealth {
+    pub ok: bool,
+    /// "quick_check" or "integrity_check"
+    pub check: String,
+    /// Problems reported by the check; empty when `ok`
+    pub messages: Vec<String>,
+    pub failing_tables: Vec<String>,
+    pub size_bytes: u64,
+    pub page_size: u64,
+    pub page_count: u64,
+    pub freelist_count: u64,
+    pub schema_version: u32,
+    pub tables: Vec<TableStats>,
+}
+
+fn pragma_u64(conn: &Connection, name: &str) -> Result<u64> {
+    conn.pragma_query_value(None, name, |row| row.get::<_, i64>(0))
+        .map(|value| value as u64)
+}
+
+/// Run a check pragma, optionally limited to one table, and return its
+/// messages; a healthy database reports the single message "ok"
+fn run_check(conn: &Connection, check: &str, table: Option<&str>) -> Result<Vec<String>> {
+    let sql = match table {
+        Some(table) => format!("PRAGMA {}(\"{}\")", check, table.replace('"', "\"\"")),
+        None => format!("PRAGMA {}", check),
+    };
+    let mut stmt = conn.prepare(&sql)?;
+    let messages = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(messages.into_iter().filter(|m| m != "ok").collect())
+}
+
+fn table_names(conn: &Connection) -> Result<Vec<String>> {
+    // FTS shadow tables are checked through their virtual table
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master
+         WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '%\\_fts\\_%' ESCAPE '\\'
+         ORDER BY name",
+    )?;
+    let names = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(names)
+}
+
+/// Check integrity and gather size and table statistics. `full` runs
+/// `integrity_check`, which also verifies indexes against their tables;
+/// otherwise the faster `quick_check` is used.
+pub fn check(conn: &Connection, full: bool) -> Result<DatabaseHealth> {
+    let check = if full { "integrity_check" } else { "quick_check" };
+    let messages = run_check(conn, check, None)?;
+
+    let names = table_names(conn)?;
+    let mut failing_tables = Vec::new();
+    if !messages.is_empty() {
+        for name in &names {
+            if !run_check(conn, check, Some(name))?.is_empty() {
+                failing_tables.push(name.clone());
+            }
+        }
+    }
+
+    let mut tables = Vec::new();
+    for name in names {
+        let rows: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+            [],
+            |row| row.get(0),
+        )?;
+        tables.push(TableStats { name, rows: rows as u64 });
+    }
+
+    let page_size = pragma_u64(conn, "page_size")?;
+    let page_count = pragma_u64(conn, "page_count")?;
+
+    Ok(DatabaseHealth {
+        ok: messages.is_empty(),
+        check: check.to_string(),
+        messages,
+        failing_tables,
+        size_bytes: page_size * page_count,
+        page_size,
+        page_count,
+        freelist_count: pragma_u64(conn, "freelist_count")?,
+        schema_version: migrations::current_version(conn)?,
+        tables,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_healthy_database() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run(&conn).unwrap();
+        conn.execute("INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api')", [])
+            .unwrap();
+
+        for full in [false, true] {
+            let health = check(&conn, full).unwrap();
+            assert!(health.ok, "{:?}", health.messages);
+            assert!(health.failing_tables.is_empty());
+            assert_eq!(health.schema_version, migrations::latest_version());
+            assert_eq!(health.size_bytes, health.page_size * health.page_count);
+            let projects = health.tables.iter().find(|t| t.name == "projects").unwrap();
+            assert_eq!(projects.rows, 1);
+            assert!(health.tables.iter().any(|t| t.name == "history_fts"));
+        }
+    }
+}
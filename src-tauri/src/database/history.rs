@@ -0,0 +1,169 @@
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+
+/// A ranked full-text match from conversations, agent task summaries or
+/// queued tasks and their results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMatch {
+    /// "message", "task" or "result"
+    pub source: String,
+    pub source_id: String,
+    pub session_id: Option<String>,
+    pub project_id: Option<String>,
+    pub snippet: String,
+    pub rank: f64,
+    pub created_at: String,
+}
+
+/// Create the `history_fts` index, the triggers that keep it in sync, and
+/// index existing rows
+pub fn create_search_index(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+            content,
+            source UNINDEXED,
+            source_id UNINDEXED,
+            session_id UNINDEXED,
+            project_id UNINDEXED,
+            created_at UNINDEXED,
+            tokenize = 'porter unicode61'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS conversation_messages_fts_insert
+        AFTER INSERT ON conversation_messages BEGIN
+            INSERT INTO history_fts (content, source, source_id, session_id, project_id, created_at)
+            VALUES (
+                new.content, 'message', new.id, new.session_id,
+                (SELECT project_id FROM plugin_sessions WHERE id = new.session_id),
+                new.timestamp
+            );
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS conversation_messages_fts_delete
+        AFTER DELETE ON conversation_messages BEGIN
+            DELETE FROM history_fts WHERE source = 'message' AND source_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS agent_activity_fts_insert
+        AFTER INSERT ON agent_activity BEGIN
+            INSERT INTO history_fts (content, source, source_id, session_id, project_id, created_at)
+            VALUES (
+                new.summary, 'task', new.id, new.session_id,
+                (SELECT id FROM projects WHERE name = new.project_name LIMIT 1),
+                new.created_at
+            );
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS agent_activity_fts_delete
+        AFTER DELETE ON agent_activity BEGIN
+            DELETE FROM history_fts WHERE source = 'task' AND source_id = old.id;
+        END;
+
+        INSERT INTO history_fts (content, source, source_id, session_id, project_id, created_at)
+        SELECT m.content, 'message', m.id, m.session_id, s.project_id, m.timestamp
+        FROM conversation_messages m
+        LEFT JOIN plugin_sessions s ON s.id = m.session_id;
+
+        INSERT INTO history_fts (content, source, source_id, session_id, project_id, created_at)
+        SELECT a.summary, 'task', a.id, a.session_id,
+            (SELECT id FROM projects WHERE name = a.project_name LIMIT 1), a.created_at
+        FROM agent_activity a;",
+    )
+}
+
+/// Turn free text into an FTS5 query that matches every word, so quotes,
+/// colons and operators in the input can't produce a syntax error
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Search conversations and task history, best matches first
+pub fn search_history(
+    conn: &Connection,
+    query: &str,
+    project_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<HistoryMatch>> {
+    let Some(query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+
+    let mut stmt = conn.prepare(
+        "SELECT source, source_id, session_id, project_id,
+                snippet(history_fts, 0, '[', ']', '…', 16), bm25(history_fts), created_at
+         FROM history_fts
+         WHERE history_fts MATCH ?1 AND (?2 IS NULL OR project_id = ?2)
+         ORDER BY bm25(history_fts)
+         LIMIT ?3",
+    )?;
+
+    let matches = stmt
+        .query_map(params![query, project_id, limit], |row| {
+            Ok(HistoryMatch {
+                source: row.get(0)?,
+                source_id: row.get(1)?,
+                session_id: row.get(2)?,
+                project_id: row.get(3)?,
+                snippet: row.get(4)?,
+                // bm25 scores are negative, lower being better
+                rank: -row.get::<_, f64>(5)?,
+                created_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{conversation, migrations};
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api'), ('p2', 'web', '/tmp/web');
+             INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model)
+             VALUES ('s1', 'p1', 'claude', 'Auth', '/tmp/api', 'sonnet'),
+                    ('s2', 'p2', 'claude', 'Styles', '/tmp/web', 'sonnet');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(fts_query("auth bug").unwrap(), "\"auth\" \"bug\"");
+        assert_eq!(fts_query("say \"hi\":").unwrap(), "\"say\" \"\"\"hi\"\":\"");
+        assert!(fts_query("   ").is_none());
+    }
+
+    #[test]
+    fn test_search_finds_messages_by_project() {
+        let conn = setup();
+        conversation::add_message(&conn, "m1", "s1", "assistant", "Fixed the auth bug in login", "2024-01-01T00:00:00Z").unwrap();
+        conversation::add_message(&conn, "m2", "s2", "assistant", "Fixed the auth header styling", "2024-01-02T00:00:00Z").unwrap();
+
+        let all = search_history(&conn, "fixed auth", None, 10).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let api = search_history(&conn, "auth", Some("p1"), 10).unwrap();
+        assert_eq!(api.len(), 1);
+        assert_eq!(api[0].source_id, "m1");
+        assert!(api[0].snippet.contains("[auth]"));
+
+        conversation::delete_session_messages(&conn, "s1").unwrap();
+        assert!(search_history(&conn, "auth", Some("p1"), 10).unwrap().is_empty());
+    }
+}
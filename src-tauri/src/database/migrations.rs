@@ -0,0 +1,136 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{history, project_commands, project_tags, project_templates, schema, standups, tasks, usage, workspaces};
+
+/// A forward-only schema change. Versions are applied in order, once each.
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub apply: fn(&Connection) -> Result<()>,
+}
+
+/// Every migration, oldest first. Append new ones with the next version;
+/// never edit or reorder one that has shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    // Tables created before versioning; idempotent, so existing databases
+    // pick up any table they're missing
+    Migration { version: 1, name: "initial schema", apply: schema::initialize },
+    Migration { version: 2, name: "history search index", apply: history::create_search_index },
+    Migration { version: 3, name: "task and worker history", apply: tasks::create_tables },
+    Migration { version: 4, name: "usage statistics", apply: usage::create_tables },
+    Migration { version: 5, name: "project tags", apply: project_tags::create_tables },
+    Migration { version: 6, name: "project templates", apply: project_templates::create_tables },
+    Migration { version: 7, name: "workspaces", apply: workspaces::create_tables },
+    Migration { version: 8, name: "archived projects", apply: schema::add_project_archived },
+    Migration { version: 9, name: "saved project commands", apply: project_commands::create_tables },
+    Migration { version: 10, name: "missing projects", apply: schema::add_project_missing },
+    Migration { version: 11, name: "standup summaries", apply: standups::create_tables },
+    Migration { version: 12, name: "project-scoped task search", apply: tasks::index_task_projects },
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaVersion {
+    pub current: u32,
+    pub latest: u32,
+}
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+fn create_migrations_table(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            applied_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+pub fn current_version(conn: &Connection) -> Result<u32> {
+    create_migrations_table(conn)?;
+    let version: Option<u32> = conn
+        .query_row("SELECT MAX(version) FROM schema_migrations", [], |row| row.get(0))
+        .optional()?
+        .flatten();
+    Ok(version.unwrap_or(0))
+}
+
+/// Apply pending migrations, each in its own transaction, and return the
+/// resulting version
+pub fn run(conn: &Connection) -> Result<u32> {
+    run_migrations(conn, MIGRATIONS)
+}
+
+fn run_migrations(conn: &Connection, migrations: &[Migration]) -> Result<u32> {
+    let mut version = current_version(conn)?;
+
+    if let Some(latest) = migrations.last().filter(|m| m.version < version) {
+        println!("Database schema version {} is newer than this build ({}); leaving it as is", version, latest.version);
+    }
+
+    let applied = version;
+    for migration in migrations.iter().filter(|m| m.version > applied) {
+        let tx = conn.unchecked_transaction()?;
+        (migration.apply)(&tx)?;
+        tx.execute(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES (?1, ?2, ?3)",
+            params![migration.version, migration.name, Utc::now().to_rfc3339()],
+        )?;
+        tx.commit()?;
+
+        println!("Applied database migration {}: {}", migration.version, migration.name);
+        version = migration.version;
+    }
+
+    Ok(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_notes(conn: &Connection) -> Result<()> {
+        conn.execute("ALTER TABLE projects ADD COLUMN notes TEXT", [])?;
+        Ok(())
+    }
+
+    fn broken(conn: &Connection) -> Result<()> {
+        conn.execute("ALTER TABLE missing ADD COLUMN x TEXT", [])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrations_apply_once_in_order() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(run(&conn).unwrap(), latest_version());
+
+        let next = latest_version() + 1;
+        let mut migrations: Vec<Migration> = MIGRATIONS
+            .iter()
+            .map(|m| Migration { version: m.version, name: m.name, apply: m.apply })
+            .collect();
+        migrations.push(Migration { version: next, name: "project notes", apply: add_notes });
+        assert_eq!(run_migrations(&conn, &migrations).unwrap(), next);
+        // Re-running would fail on the duplicate column if it applied again
+        assert_eq!(run_migrations(&conn, &migrations).unwrap(), next);
+        assert_eq!(current_version(&conn).unwrap(), next);
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let conn = Connection::open_in_memory().unwrap();
+        let migrations = [
+            Migration { version: 1, name: "initial schema", apply: schema::initialize },
+            Migration { version: 2, name: "broken", apply: broken },
+        ];
+
+        assert!(run_migrations(&conn, &migrations).is_err());
+        assert_eq!(current_version(&conn).unwrap(), 1);
+    }
+}
@@ -1,39 +1,159 @@
 use rusqlite::{Connection, Result};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tauri::{AppHandle, Manager};
 
 pub mod schema;
+pub mod migrations;
 pub mod conversation;
+#[cfg(feature = "encryption")]
+pub mod encryption;
+pub mod feed;
+pub mod health;
+pub mod history;
+pub mod profiles;
+pub mod project_commands;
+pub mod project_tags;
+pub mod project_templates;
+pub mod settings;
+pub mod activity;
+pub mod approvals;
+pub mod retention;
+pub mod slack_threads;
+pub mod standups;
+pub mod wezterm;
+pub mod tmux;
+pub mod tasks;
+pub mod usage;
+pub mod workspaces;
+
+/// Setting holding how long a write waits on a locked database before
+/// failing with SQLITE_BUSY
+pub const BUSY_TIMEOUT_SETTING: &str = "database_busy_timeout_ms";
+pub const DEFAULT_BUSY_TIMEOUT_MS: u64 = 5000;
 
 pub struct DatabaseManager {
     conn: Arc<Mutex<Connection>>,
+    app_dir: PathBuf,
+    profile: Mutex<String>,
 }
 
 impl DatabaseManager {
-    pub fn new(app_handle: &AppHandle) -> Result<Self> {
+    pub fn new(app_handle: &AppHandle) -> std::result::Result<Self, String> {
         // Get the app data directory
         let app_dir = app_handle
             .path()
             .app_data_dir()
-            .expect("Failed to get app data directory");
+            .map_err(|e| format!("Failed to get app data directory: {}", e))?;
 
         // Ensure the directory exists
-        std::fs::create_dir_all(&app_dir).expect("Failed to create app data directory");
+        std::fs::create_dir_all(&app_dir)
+            .map_err(|e| format!("Failed to create app data directory: {}", e))?;
+
+        // Open the profile that was active last time
+        let state = profiles::load_state(&app_dir);
+        let profile = if state.profiles.contains(&state.active) {
+            state.active
+        } else {
+            profiles::DEFAULT_PROFILE.to_string()
+        };
+        let conn = Self::open(&app_dir, &profile)?;
 
-        // Create database path
-        let db_path = app_dir.join("ninjasquad.db");
+        let manager = Self {
+            conn: Arc::new(Mutex::new(conn)),
+            app_dir,
+            profile: Mutex::new(profile),
+        };
+        manager.apply_busy_timeout().map_err(|e| e.to_string())?;
 
+        Ok(manager)
+    }
+
+    /// A migrated in-memory database for tests
+    #[cfg(test)]
+    pub(crate) fn in_memory() -> Self {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+        migrations::run(&conn).unwrap();
+        Self {
+            conn: Arc::new(Mutex::new(conn)),
+            app_dir: std::env::temp_dir(),
+            profile: Mutex::new(profiles::DEFAULT_PROFILE.to_string()),
+        }
+    }
+
+    /// Open a profile's database and bring its schema up to date
+    fn open(app_dir: &Path, profile: &str) -> std::result::Result<Connection, String> {
+        std::fs::create_dir_all(profiles::data_dir(app_dir, profile))
+            .map_err(|e| format!("Failed to create profile data directory: {}", e))?;
+        Self::open_at(&profiles::db_path(app_dir, profile)).map_err(|e| e.to_string())
+    }
+
+    fn open_at(db_path: &Path) -> Result<Connection> {
         // Open connection
+        #[cfg(feature = "encryption")]
+        let conn = encryption::open(db_path)?;
+        #[cfg(not(feature = "encryption"))]
         let conn = Connection::open(db_path)?;
 
+        // WAL lets readers run alongside a writer; NORMAL sync is safe with it
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(Duration::from_millis(DEFAULT_BUSY_TIMEOUT_MS))?;
+
         // Enable foreign keys
         conn.execute("PRAGMA foreign_keys = ON", [])?;
 
-        // Initialize schema
-        schema::initialize(&conn)?;
+        // Bring the schema up to date
+        migrations::run(&conn)?;
 
-        Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
+        Ok(conn)
+    }
+
+    pub fn app_dir(&self) -> &Path {
+        &self.app_dir
+    }
+
+    /// Name of the open profile
+    pub fn profile(&self) -> String {
+        self.profile.lock().unwrap().clone()
+    }
+
+    /// Directory for the open profile's files
+    pub fn data_dir(&self) -> PathBuf {
+        profiles::data_dir(&self.app_dir, &self.profile())
+    }
+
+    /// Close the current database and open another profile's in its place.
+    /// Every holder of `connection()` sees the new database from then on;
+    /// services that loaded state from the old one have to reload it.
+    pub fn switch_profile(&self, name: &str) -> std::result::Result<(), String> {
+        profiles::validate_name(name)?;
+
+        let mut state = profiles::load_state(&self.app_dir);
+        if !state.profiles.iter().any(|p| p == name) {
+            return Err(format!("Profile '{}' does not exist", name));
+        }
+
+        // Opened before the swap so a broken database leaves the current one in place
+        let conn = Self::open(&self.app_dir, name)?;
+        {
+            let mut current = self.conn.lock().unwrap();
+            *current = conn;
+            *self.profile.lock().unwrap() = name.to_string();
+        }
+        self.apply_busy_timeout().map_err(|e| e.to_string())?;
+
+        state.active = name.to_string();
+        profiles::save_state(&self.app_dir, &state)
+    }
+
+    /// Apply the busy timeout from the settings store
+    pub fn apply_busy_timeout(&self) -> Result<()> {
+        self.with_connection(|conn| {
+            let ms = settings::get_or(conn, BUSY_TIMEOUT_SETTING, DEFAULT_BUSY_TIMEOUT_MS)?;
+            conn.busy_timeout(Duration::from_millis(ms))
         })
     }
 
@@ -41,9 +161,18 @@ impl DatabaseManager {
         Arc::clone(&self.conn)
     }
 
-    pub fn migrate(&self) -> Result<()> {
-        // Run any migrations here in the future
-        Ok(())
+    /// Apply pending migrations and return the schema version
+    pub fn migrate(&self) -> Result<u32> {
+        self.with_connection(migrations::run)
+    }
+
+    pub fn schema_version(&self) -> Result<migrations::SchemaVersion> {
+        self.with_connection(|conn| {
+            Ok(migrations::SchemaVersion {
+                current: migrations::current_version(conn)?,
+                latest: migrations::latest_version(),
+            })
+        })
     }
 
     pub fn with_connection<F, R>(&self, f: F) -> Result<R>
@@ -53,4 +182,103 @@ impl DatabaseManager {
         let conn = self.conn.lock().unwrap();
         f(&*conn)
     }
-}
\ No newline at end of file
+}
+
+/// The app handle a background service was started with, once the database
+/// is available on it; `service` names the service in the error otherwise
+pub async fn app_with_database(
+    app_handle: &tokio::sync::RwLock<Option<AppHandle>>,
+    service: &str,
+) -> anyhow::Result<AppHandle> {
+    let handle = app_handle.read().await.clone()
+        .ok_or_else(|| anyhow::anyhow!("{} not started", service))?;
+    if handle.try_state::<DatabaseManager>().is_none() {
+        return Err(anyhow::anyhow!("Database not available"));
+    }
+    Ok(handle)
+}
+
+/// Run `f` on the database of the app a background service was started with
+pub async fn with_app_db<F, R>(
+    app_handle: &tokio::sync::RwLock<Option<AppHandle>>,
+    service: &str,
+    f: F,
+) -> anyhow::Result<R>
+where
+    F: FnOnce(&Connection) -> Result<R>,
+{
+    let handle = app_with_database(app_handle, service).await?;
+    let db = handle.state::<DatabaseManager>();
+    Ok(db.with_connection(f)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pragma<T: rusqlite::types::FromSql>(conn: &Connection, name: &str) -> T {
+        conn.pragma_query_value(None, name, |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_open_uses_wal_and_the_configured_busy_timeout() {
+        let app_dir = std::env::temp_dir().join(format!("ninjasquad-db-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&app_dir).unwrap();
+
+        let conn = DatabaseManager::open(&app_dir, profiles::DEFAULT_PROFILE).unwrap();
+        assert_eq!(pragma::<String>(&conn, "journal_mode"), "wal");
+        assert_eq!(pragma::<i64>(&conn, "synchronous"), 1);
+        assert_eq!(pragma::<i64>(&conn, "foreign_keys"), 1);
+        assert_eq!(pragma::<u64>(&conn, "busy_timeout"), DEFAULT_BUSY_TIMEOUT_MS);
+
+        let manager = DatabaseManager {
+            conn: Arc::new(Mutex::new(conn)),
+            app_dir: app_dir.clone(),
+            profile: Mutex::new(profiles::DEFAULT_PROFILE.to_string()),
+        };
+        manager.with_connection(|conn| settings::set_json(conn, BUSY_TIMEOUT_SETTING, &250)).unwrap();
+        manager.apply_busy_timeout().unwrap();
+        assert_eq!(manager.with_connection(|conn| Ok(pragma::<u64>(conn, "busy_timeout"))).unwrap(), 250);
+
+        manager.with_connection(|conn| settings::delete_setting(conn, BUSY_TIMEOUT_SETTING)).unwrap();
+        manager.apply_busy_timeout().unwrap();
+        assert_eq!(
+            manager.with_connection(|conn| Ok(pragma::<u64>(conn, "busy_timeout"))).unwrap(),
+            DEFAULT_BUSY_TIMEOUT_MS
+        );
+
+        drop(manager);
+        let _ = std::fs::remove_dir_all(&app_dir);
+    }
+
+    #[test]
+    fn test_switch_profile_swaps_the_open_database() {
+        let app_dir = std::env::temp_dir().join(format!("ninjasquad-db-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&app_dir).unwrap();
+        let manager = DatabaseManager {
+            conn: Arc::new(Mutex::new(DatabaseManager::open(&app_dir, profiles::DEFAULT_PROFILE).unwrap())),
+            app_dir: app_dir.clone(),
+            profile: Mutex::new(profiles::DEFAULT_PROFILE.to_string()),
+        };
+        let held = manager.connection();
+        manager.with_connection(|conn| settings::set_json(conn, "marker", &"default")).unwrap();
+
+        profiles::create(&app_dir, "work").unwrap();
+        manager.switch_profile("work").unwrap();
+        assert_eq!(manager.profile(), "work");
+        assert_eq!(manager.data_dir(), profiles::data_dir(&app_dir, "work"));
+        // Connections handed out earlier see the new profile's database
+        assert_eq!(settings::get_json::<String>(&held.lock().unwrap(), "marker").unwrap(), None);
+        assert!(profiles::list(&app_dir).iter().any(|p| p.name == "work" && p.active));
+
+        assert!(manager.switch_profile("missing").is_err());
+        assert_eq!(manager.profile(), "work");
+
+        manager.switch_profile(profiles::DEFAULT_PROFILE).unwrap();
+        let marker = manager.with_connection(|conn| settings::get_json::<String>(conn, "marker")).unwrap();
+        assert_eq!(marker.as_deref(), Some("default"));
+
+        drop((manager, held));
+        let _ = std::fs::remove_dir_all(&app_dir);
+    }
+}
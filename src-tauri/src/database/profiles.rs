@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Profile whose database is the original `ninjasquad.db` in the app data
+/// directory
+pub const DEFAULT_PROFILE: &str = "default";
+
+const STATE_FILE: &str = "profiles.json";
+
+/// Which profiles exist and which one is open, stored in the app data
+/// directory outside any profile's database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileState {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+impl Default for ProfileState {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_PROFILE.to_string(),
+            profiles: vec![DEFAULT_PROFILE.to_string()],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub data_dir: String,
+    pub db_path: String,
+    pub active: bool,
+}
+
+/// Profile names become file names, so keep them to letters, digits, `-`
+/// and `_`
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 64 {
+        return Err("Profile name must be 1-64 characters".to_string());
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid profile name '{}': use letters, digits, '-' or '_'", name));
+    }
+    Ok(())
+}
+
+/// Directory holding a profile's database and other files, like exports
+pub fn data_dir(app_dir: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        app_dir.to_path_buf()
+    } else {
+        app_dir.join("profiles").join(name)
+    }
+}
+
+pub fn db_path(app_dir: &Path, name: &str) -> PathBuf {
+    if name == DEFAULT_PROFILE {
+        app_dir.join("ninjasquad.db")
+    } else {
+        data_dir(app_dir, name).join(format!("ninjasquad-{}.db", name))
+    }
+}
+
+pub fn load_state(app_dir: &Path) -> ProfileState {
+    std::fs::read_to_string(app_dir.join(STATE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_state(app_dir: &Path, state: &ProfileState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    std::fs::write(app_dir.join(STATE_FILE), content)
+        .map_err(|e| format!("Failed to save profiles: {}", e))
+}
+
+pub fn list(app_dir: &Path) -> Vec<ProfileInfo> {
+    let state = load_state(app_dir);
+    state
+        .profiles
+        .iter()
+        .map(|name| ProfileInfo {
+            name: name.clone(),
+            data_dir: data_dir(app_dir, name).to_string_lossy().to_string(),
+            db_path: db_path(app_dir, name).to_string_lossy().to_string(),
+            active: *name == state.active,
+        })
+        .collect()
+}
+
+/// Register a new profile and create its data directory. The database is
+/// created the first time the profile is opened.
+pub fn create(app_dir: &Path, name: &str) -> Result<ProfileInfo, String> {
+    validate_name(name)?;
+
+    let mut state = load_state(app_dir);
+    if state.profiles.iter().any(|p| p == name) {
+        return Err(format!("Profile '{}' already exists", name));
+    }
+
+    let dir = data_dir(app_dir, name);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    state.profiles.push(name.to_string());
+    save_state(app_dir, &state)?;
+
+    Ok(ProfileInfo {
+        name: name.to_string(),
+        data_dir: dir.to_string_lossy().to_string(),
+        db_path: db_path(app_dir, name).to_string_lossy().to_string(),
+        active: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("client-x_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../work").is_err());
+        assert!(validate_name("my profile").is_err());
+    }
+
+    #[test]
+    fn test_create_and_list_profiles() {
+        let app_dir = std::env::temp_dir().join(format!("ninjasquad-profiles-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&app_dir).unwrap();
+
+        assert_eq!(db_path(&app_dir, DEFAULT_PROFILE), app_dir.join("ninjasquad.db"));
+
+        let work = create(&app_dir, "work").unwrap();
+        assert!(work.db_path.ends_with("ninjasquad-work.db"));
+        assert!(Path::new(&work.data_dir).is_dir());
+        assert!(create(&app_dir, "work").is_err());
+
+        let profiles = list(&app_dir);
+        assert_eq!(profiles.len(), 2);
+        assert!(profiles.iter().any(|p| p.name == DEFAULT_PROFILE && p.active));
+
+        let _ = std::fs::remove_dir_all(&app_dir);
+    }
+}
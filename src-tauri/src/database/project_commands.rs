@@ -0,0 +1,153 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a saved command runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandTarget {
+    /// Run to completion in the project directory, capturing output
+    Shell,
+    /// Typed into the project's tmux session
+    Tmux,
+    /// Started in the background as a dev server
+    DevServer,
+}
+
+impl CommandTarget {
+    fn as_str(self) -> &'static str {
+        match self {
+            CommandTarget::Shell => "shell",
+            CommandTarget::Tmux => "tmux",
+            CommandTarget::DevServer => "dev_server",
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value {
+            "tmux" => CommandTarget::Tmux,
+            "dev_server" => CommandTarget::DevServer,
+            _ => CommandTarget::Shell,
+        }
+    }
+}
+
+/// A named command kept with a project, e.g. "test" or "migrate"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedCommand {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub command: String,
+    pub target: CommandTarget,
+    pub created_at: String,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS project_commands (
+            id TEXT PRIMARY KEY,
+            project_id TEXT NOT NULL,
+            name TEXT NOT NULL COLLATE NOCASE,
+            command TEXT NOT NULL,
+            target TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            UNIQUE (project_id, name),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );",
+    )
+}
+
+fn row_to_command(row: &Row) -> Result<SavedCommand> {
+    let target: String = row.get(4)?;
+    Ok(SavedCommand {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        name: row.get(2)?,
+        command: row.get(3)?,
+        target: CommandTarget::parse(&target),
+        created_at: row.get(5)?,
+    })
+}
+
+/// Save a command, replacing any of the project's commands with the same name
+pub fn save_command(
+    conn: &Connection,
+    project_id: &str,
+    name: &str,
+    command: &str,
+    target: CommandTarget,
+) -> Result<SavedCommand> {
+    conn.execute(
+        "INSERT INTO project_commands (id, project_id, name, command, target, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(project_id, name) DO UPDATE SET
+            command = excluded.command,
+            target = excluded.target",
+        params![Uuid::new_v4().to_string(), project_id, name, command, target.as_str(), Utc::now().to_rfc3339()],
+    )?;
+
+    get_command(conn, project_id, name)?.ok_or(rusqlite::Error::QueryReturnedNoRows)
+}
+
+pub fn get_command(conn: &Connection, project_id: &str, name: &str) -> Result<Option<SavedCommand>> {
+    conn.query_row(
+        "SELECT id, project_id, name, command, target, created_at
+         FROM project_commands WHERE project_id = ?1 AND name = ?2",
+        params![project_id, name],
+        row_to_command,
+    )
+    .optional()
+}
+
+pub fn list_commands(conn: &Connection, project_id: &str) -> Result<Vec<SavedCommand>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, project_id, name, command, target, created_at
+         FROM project_commands WHERE project_id = ?1 ORDER BY name",
+    )?;
+    let commands = stmt
+        .query_map([project_id], row_to_command)?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(commands)
+}
+
+pub fn delete_command(conn: &Connection, project_id: &str, name: &str) -> Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM project_commands WHERE project_id = ?1 AND name = ?2",
+        params![project_id, name],
+    )?;
+    Ok(rows > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_replaces_by_project_and_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api'), ('p2', 'web', '/tmp/web');",
+        )
+        .unwrap();
+
+        let first = save_command(&conn, "p1", "test", "cargo test", CommandTarget::Shell).unwrap();
+        let second = save_command(&conn, "p1", "Test", "cargo nextest run", CommandTarget::Tmux).unwrap();
+        save_command(&conn, "p2", "test", "npm test", CommandTarget::Shell).unwrap();
+        save_command(&conn, "p1", "dev", "cargo watch -x run", CommandTarget::DevServer).unwrap();
+
+        assert_eq!(first.id, second.id);
+        let commands = list_commands(&conn, "p1").unwrap();
+        let names: Vec<_> = commands.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["dev", "test"]);
+        assert_eq!(commands[0].target, CommandTarget::DevServer);
+        assert_eq!(commands[1].command, "cargo nextest run");
+        assert_eq!(commands[1].target, CommandTarget::Tmux);
+
+        assert!(delete_command(&conn, "p1", "TEST").unwrap());
+        assert!(get_command(&conn, "p1", "test").unwrap().is_none());
+        assert!(get_command(&conn, "p2", "test").unwrap().is_some());
+    }
+}
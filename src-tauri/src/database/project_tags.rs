@@ -0,0 +1,95 @@
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A tag and how many projects carry it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub projects: usize,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS project_tags (
+            project_id TEXT NOT NULL,
+            tag TEXT NOT NULL COLLATE NOCASE,
+            PRIMARY KEY (project_id, tag),
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_project_tags_tag ON project_tags(tag);",
+    )
+}
+
+/// Tag a project; tags compare case-insensitively, so re-adding one in a
+/// different case is a no-op
+pub fn add_tag(conn: &Connection, project_id: &str, tag: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO project_tags (project_id, tag) VALUES (?1, ?2)",
+        params![project_id, tag],
+    )?;
+    Ok(())
+}
+
+pub fn remove_tag(conn: &Connection, project_id: &str, tag: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM project_tags WHERE project_id = ?1 AND tag = ?2",
+        params![project_id, tag],
+    )?;
+    Ok(())
+}
+
+/// Every project's tags, sorted, keyed by project id
+pub fn all_project_tags(conn: &Connection) -> Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn.prepare("SELECT project_id, tag FROM project_tags ORDER BY tag")?;
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for row in stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))? {
+        let (project_id, tag) = row?;
+        tags.entry(project_id).or_default().push(tag);
+    }
+    Ok(tags)
+}
+
+pub fn list_tags(conn: &Connection) -> Result<Vec<TagCount>> {
+    let mut stmt = conn.prepare(
+        "SELECT tag, COUNT(*) FROM project_tags GROUP BY tag ORDER BY tag",
+    )?;
+    let tags = stmt
+        .query_map([], |row| {
+            Ok(TagCount {
+                tag: row.get(0)?,
+                projects: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tags_are_case_insensitive() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api'), ('p2', 'web', '/tmp/web');",
+        )
+        .unwrap();
+
+        add_tag(&conn, "p1", "Rust").unwrap();
+        add_tag(&conn, "p1", "rust").unwrap();
+        add_tag(&conn, "p1", "acme").unwrap();
+        add_tag(&conn, "p2", "acme").unwrap();
+
+        let tags = all_project_tags(&conn).unwrap();
+        assert_eq!(tags["p1"], vec!["acme", "Rust"]);
+
+        remove_tag(&conn, "p1", "RUST").unwrap();
+        let counts = list_tags(&conn).unwrap();
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].projects, 2);
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::projects::types::ProjectSettings;
+
+/// Saved color and settings that new projects can start from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub settings: ProjectSettings,
+    pub created_at: String,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS project_templates (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT,
+            color TEXT,
+            settings TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );",
+    )
+}
+
+fn row_to_template(row: &Row) -> Result<ProjectTemplate> {
+    let settings: String = row.get(4)?;
+    Ok(ProjectTemplate {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        color: row.get(3)?,
+        settings: serde_json::from_str(&settings).unwrap_or_default(),
+        created_at: row.get(5)?,
+    })
+}
+
+/// Save a template, replacing any existing one with the same name
+pub fn save_template(
+    conn: &Connection,
+    name: &str,
+    description: Option<&str>,
+    color: Option<&str>,
+    settings: &ProjectSettings,
+) -> Result<ProjectTemplate> {
+    let settings = serde_json::to_string(settings)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO project_templates (id, name, description, color, settings, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+         ON CONFLICT(name) DO UPDATE SET
+            description = excluded.description,
+            color = excluded.color,
+            settings = excluded.settings",
+        params![Uuid::new_v4().to_string(), name, description, color, settings, Utc::now().to_rfc3339()],
+    )?;
+
+    conn.query_row(
+        "SELECT id, name, description, color, settings, created_at
+         FROM project_templates WHERE name = ?1",
+        [name],
+        row_to_template,
+    )
+}
+
+/// Add a template brought in from another machine, keeping any local
+/// template of the same name. Returns whether it was added.
+pub fn import_template(conn: &Connection, template: &ProjectTemplate) -> Result<bool> {
+    let settings = serde_json::to_string(&template.settings)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let rows = conn.execute(
+        "INSERT OR IGNORE INTO project_templates (id, name, description, color, settings, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            Uuid::new_v4().to_string(),
+            template.name,
+            template.description,
+            template.color,
+            settings,
+            template.created_at
+        ],
+    )?;
+    Ok(rows > 0)
+}
+
+pub fn get_template(conn: &Connection, id: &str) -> Result<Option<ProjectTemplate>> {
+    conn.query_row(
+        "SELECT id, name, description, color, settings, created_at
+         FROM project_templates WHERE id = ?1",
+        [id],
+        row_to_template,
+    )
+    .optional()
+}
+
+pub fn list_templates(conn: &Connection) -> Result<Vec<ProjectTemplate>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, description, color, settings, created_at
+         FROM project_templates ORDER BY name",
+    )?;
+    let templates = stmt
+        .query_map([], row_to_template)?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(templates)
+}
+
+pub fn delete_template(conn: &Connection, id: &str) -> Result<bool> {
+    let rows = conn.execute("DELETE FROM project_templates WHERE id = ?1", [id])?;
+    Ok(rows > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_replaces_by_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+
+        let settings = ProjectSettings {
+            default_plugin: Some("claude".to_string()),
+            dev_server_command: Some("npm run dev".to_string()),
+            ..ProjectSettings::default()
+        };
+        let first = save_template(&conn, "Web app", None, Some("#3b82f6"), &settings).unwrap();
+        let second = save_template(&conn, "web APP", Some("Vite + React"), None, &settings).unwrap();
+
+        assert_eq!(first.id, second.id);
+        let templates = list_templates(&conn).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].description.as_deref(), Some("Vite + React"));
+        assert_eq!(templates[0].settings.dev_server_command.as_deref(), Some("npm run dev"));
+
+        assert!(delete_template(&conn, &first.id).unwrap());
+        assert!(get_template(&conn, &first.id).unwrap().is_none());
+    }
+}
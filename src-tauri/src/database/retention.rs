@@ -0,0 +1,151 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{Connection, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{activity, settings, tasks};
+
+pub const POLICY_SETTING: &str = "retention_policy";
+
+/// How often the scheduled cleanup runs
+pub const CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 60 * 60);
+
+/// Days to keep each kind of record; `None` keeps it forever. Task and worker
+/// history use their own `task_history_retention_days` setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    pub archived_sessions_days: Option<u32>,
+    pub activity_days: Option<u32>,
+    pub approvals_days: Option<u32>,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            archived_sessions_days: Some(90),
+            activity_days: Some(30),
+            approvals_days: Some(90),
+        }
+    }
+}
+
+/// Rows deleted and space reclaimed by a cleanup run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub archived_sessions: usize,
+    pub activity: usize,
+    pub approvals: usize,
+    pub task_history: usize,
+    pub bytes_reclaimed: u64,
+    pub ran_at: String,
+}
+
+pub fn get_policy(conn: &Connection) -> Result<RetentionPolicy> {
+    settings::get_or(conn, POLICY_SETTING, RetentionPolicy::default())
+}
+
+pub fn set_policy(conn: &Connection, policy: &RetentionPolicy) -> Result<()> {
+    settings::set_json(conn, POLICY_SETTING, policy)
+}
+
+fn cutoff(now: DateTime<Utc>, days: u32) -> DateTime<Utc> {
+    now - Duration::days(days as i64)
+}
+
+/// Delete records older than the policy allows
+pub fn prune(conn: &Connection, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<RetentionReport> {
+    let mut report = RetentionReport {
+        ran_at: now.to_rfc3339(),
+        ..Default::default()
+    };
+
+    if let Some(days) = policy.archived_sessions_days {
+        // Messages go with their session through ON DELETE CASCADE
+        report.archived_sessions = conn.execute(
+            "DELETE FROM plugin_sessions
+             WHERE status = 'archived' AND COALESCE(last_active, created_at) < ?1",
+            [cutoff(now, days).to_rfc3339()],
+        )?;
+    }
+
+    if let Some(days) = policy.activity_days {
+        report.activity = activity::prune_activity(conn, &cutoff(now, days).to_rfc3339())?;
+    }
+
+    if let Some(days) = policy.approvals_days {
+        report.approvals = conn.execute(
+            "DELETE FROM approval_audit WHERE requested_at < ?1",
+            [cutoff(now, days).timestamp_millis()],
+        )?;
+    }
+
+    report.task_history = tasks::apply_retention(conn)?;
+
+    Ok(report)
+}
+
+/// Return free pages to the filesystem and the number of bytes reclaimed.
+/// The first run switches the database to incremental auto-vacuum, which
+/// needs one full VACUUM.
+pub fn vacuum(conn: &Connection) -> Result<u64> {
+    let page_count = |conn: &Connection| -> Result<i64> {
+        conn.pragma_query_value(None, "page_count", |row| row.get(0))
+    };
+
+    let page_size: i64 = conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+    let before = page_count(conn)?;
+
+    let auto_vacuum: i64 = conn.pragma_query_value(None, "auto_vacuum", |row| row.get(0))?;
+    if auto_vacuum == 2 {
+        conn.execute_batch("PRAGMA incremental_vacuum;")?;
+    } else {
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+    }
+
+    let after = page_count(conn)?;
+    Ok(((before - after).max(0) * page_size) as u64)
+}
+
+/// Apply the stored policy, then vacuum
+pub fn run(conn: &Connection) -> Result<RetentionReport> {
+    let policy = get_policy(conn)?;
+    let mut report = prune(conn, &policy, Utc::now())?;
+    report.bytes_reclaimed = vacuum(conn)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations;
+
+    #[test]
+    fn test_prune_respects_policy() {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run(&conn).unwrap();
+
+        let now = Utc::now();
+        let old = (now - Duration::days(120)).to_rfc3339();
+        let recent = (now - Duration::days(5)).to_rfc3339();
+        conn.execute_batch(&format!(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/tmp/api');
+             INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model, status, last_active)
+             VALUES ('old', 'p1', 'claude', 'Old', '/tmp/api', 'sonnet', 'archived', '{old}'),
+                    ('kept', 'p1', 'claude', 'Active', '/tmp/api', 'sonnet', 'active', '{old}');
+             INSERT INTO agent_activity (id, project_name, summary, success, created_at)
+             VALUES ('a1', 'api', 'old', 1, '{old}'), ('a2', 'api', 'recent', 1, '{recent}');"
+        ))
+        .unwrap();
+
+        let report = prune(&conn, &RetentionPolicy::default(), now).unwrap();
+        assert_eq!(report.archived_sessions, 1);
+        assert_eq!(report.activity, 1);
+
+        let policy = RetentionPolicy { activity_days: None, ..Default::default() };
+        assert_eq!(prune(&conn, &policy, now + Duration::days(60)).unwrap().activity, 0);
+
+        vacuum(&conn).unwrap();
+        let mode: i64 = conn.pragma_query_value(None, "auto_vacuum", |row| row.get(0)).unwrap();
+        assert_eq!(mode, 2);
+    }
+}
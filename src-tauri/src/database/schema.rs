@@ -1,5 +1,19 @@
 use rusqlite::{Connection, Result};
 
+/// Archived projects stay in the database but are hidden from listings
+pub fn add_project_archived(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "ALTER TABLE projects ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT 0;
+         CREATE INDEX IF NOT EXISTS idx_projects_archived ON projects(is_archived);",
+    )
+}
+
+/// Projects whose folder has disappeared are flagged until relocated
+pub fn add_project_missing(conn: &Connection) -> Result<()> {
+    conn.execute("ALTER TABLE projects ADD COLUMN is_missing BOOLEAN NOT NULL DEFAULT 0", [])?;
+    Ok(())
+}
+
 pub fn initialize(conn: &Connection) -> Result<()> {
     // Create projects table
     conn.execute(
@@ -91,6 +105,108 @@ pub fn initialize(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    // Create agent activity table (feeds notification digests)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_activity (
+            id TEXT PRIMARY KEY,
+            project_name TEXT NOT NULL,
+            session_id TEXT,
+            summary TEXT NOT NULL,
+            success BOOLEAN NOT NULL,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create approval audit log
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS approval_audit (
+            id TEXT PRIMARY KEY,
+            channel TEXT NOT NULL,
+            session_id TEXT NOT NULL,
+            server_id TEXT NOT NULL,
+            project_name TEXT,
+            recommendation TEXT NOT NULL,
+            tool_use TEXT,
+            requested_at INTEGER NOT NULL,
+            decision TEXT,
+            decided_by TEXT,
+            decided_at INTEGER,
+            latency_ms INTEGER
+        )",
+        [],
+    )?;
+
+    // Create Slack thread-per-session mapping
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS slack_threads (
+            session_id TEXT PRIMARY KEY,
+            channel TEXT NOT NULL,
+            thread_ts TEXT NOT NULL,
+            project_name TEXT,
+            created_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    // Create per-project WezTerm pane layouts
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_terminal_layouts (
+            project_id TEXT PRIMARY KEY,
+            layout TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Create per-project WezTerm window geometry
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS project_window_geometry (
+            project_id TEXT PRIMARY KEY,
+            x INTEGER NOT NULL,
+            y INTEGER NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Track WezTerm windows and mirrors so they can be reattached after a restart
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS wezterm_windows (
+            window_id TEXT PRIMARY KEY,
+            project_id TEXT,
+            pane_id TEXT NOT NULL,
+            data TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS wezterm_mirrors (
+            mirror_id TEXT PRIMARY KEY,
+            pane_id TEXT NOT NULL,
+            data TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
+    // Track tmux sessions so monitoring resumes after a restart
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS tmux_sessions (
+            session_id TEXT PRIMARY KEY,
+            project_path TEXT NOT NULL,
+            data TEXT NOT NULL,
+            updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        )",
+        [],
+    )?;
+
     // Create indexes for better performance
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_servers_project ON servers(project_id)",
@@ -127,5 +243,15 @@ pub fn initialize(conn: &Connection) -> Result<()> {
         [],
     )?;
 
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_agent_activity_created ON agent_activity(created_at)",
+        [],
+    )?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_approval_audit_requested ON approval_audit(requested_at)",
+        [],
+    )?;
+
     Ok(())
 }
\ No newline at end of file
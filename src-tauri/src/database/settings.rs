@@ -0,0 +1,105 @@
+use rusqlite::{Connection, OptionalExtension, Result, params};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Emitted as `setting-changed` whenever a setting is set or deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingChanged {
+    pub key: String,
+    /// `None` when the setting was deleted
+    pub value: Option<serde_json::Value>,
+}
+
+/// Parse a stored value as JSON, treating anything else as a plain string
+pub fn parse_value(raw: &str) -> serde_json::Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| serde_json::Value::String(raw.to_string()))
+}
+
+/// Get a raw setting value
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT value FROM app_settings WHERE key = ?1",
+        [key],
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Insert or replace a setting value
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO app_settings (key, value, updated_at)
+         VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        params![key, value],
+    )?;
+    Ok(())
+}
+
+/// Delete a setting
+pub fn delete_setting(conn: &Connection, key: &str) -> Result<()> {
+    conn.execute("DELETE FROM app_settings WHERE key = ?1", [key])?;
+    Ok(())
+}
+
+/// Get a setting stored as JSON
+pub fn get_json<T: DeserializeOwned>(conn: &Connection, key: &str) -> Result<Option<T>> {
+    match get_setting(conn, key)? {
+        Some(value) => serde_json::from_str(&value)
+            .map(Some)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))),
+        None => Ok(None),
+    }
+}
+
+/// Store a setting as JSON
+pub fn set_json<T: Serialize>(conn: &Connection, key: &str, value: &T) -> Result<()> {
+    let value = serde_json::to_string(value)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    set_setting(conn, key, &value)
+}
+
+/// Get a typed setting, falling back to `default` when it isn't set
+pub fn get_or<T: DeserializeOwned>(conn: &Connection, key: &str, default: T) -> Result<T> {
+    Ok(get_json(conn, key)?.unwrap_or(default))
+}
+
+/// Get a setting as a JSON value
+pub fn get_value(conn: &Connection, key: &str) -> Result<Option<serde_json::Value>> {
+    Ok(get_setting(conn, key)?.as_deref().map(parse_value))
+}
+
+/// Get every setting as JSON values, keyed by name
+pub fn get_all(conn: &Connection) -> Result<BTreeMap<String, serde_json::Value>> {
+    let mut stmt = conn.prepare("SELECT key, value FROM app_settings WHERE value IS NOT NULL")?;
+
+    let settings = stmt
+        .query_map([], |row| {
+            let value: String = row.get(1)?;
+            Ok((row.get(0)?, parse_value(&value)))
+        })?
+        .collect::<Result<BTreeMap<_, _>>>()?;
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_round_trip_as_json() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::schema::initialize(&conn).unwrap();
+
+        set_json(&conn, "server.port", &4096).unwrap();
+        set_setting(&conn, "last_digest", "2024-01-01T00:00:00Z").unwrap();
+
+        assert_eq!(get_or(&conn, "server.port", 0u16).unwrap(), 4096);
+        assert_eq!(get_or(&conn, "missing", 7u16).unwrap(), 7);
+
+        let all = get_all(&conn).unwrap();
+        assert_eq!(all["server.port"], serde_json::json!(4096));
+        assert_eq!(all["last_digest"], serde_json::json!("2024-01-01T00:00:00Z"));
+    }
+}
@@ -0,0 +1,89 @@
+use rusqlite::{Connection, OptionalExtension, Result, params};
+use serde::{Deserialize, Serialize};
+
+/// Slack thread that collects all updates for one session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackThread {
+    pub session_id: String,
+    pub channel: String,
+    pub thread_ts: String,
+    pub project_name: Option<String>,
+    pub created_at: String,
+}
+
+/// Get the thread for a session
+pub fn get_thread(conn: &Connection, session_id: &str) -> Result<Option<SlackThread>> {
+    conn.query_row(
+        "SELECT session_id, channel, thread_ts, project_name, created_at
+         FROM slack_threads
+         WHERE session_id = ?1",
+        [session_id],
+        |row| {
+            Ok(SlackThread {
+                session_id: row.get(0)?,
+                channel: row.get(1)?,
+                thread_ts: row.get(2)?,
+                project_name: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        },
+    )
+    .optional()
+}
+
+/// Save the thread for a session, replacing any previous mapping
+pub fn save_thread(conn: &Connection, thread: &SlackThread) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO slack_threads (session_id, channel, thread_ts, project_name, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            thread.session_id,
+            thread.channel,
+            thread.thread_ts,
+            thread.project_name,
+            thread.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Delete the thread mapping for a session
+pub fn delete_thread(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM slack_threads WHERE session_id = ?1", [session_id])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread(session_id: &str, channel: &str, thread_ts: &str) -> SlackThread {
+        SlackThread {
+            session_id: session_id.to_string(),
+            channel: channel.to_string(),
+            thread_ts: thread_ts.to_string(),
+            project_name: Some("demo".to_string()),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_save_replaces_and_delete_removes() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+
+        assert!(get_thread(&conn, "s1").unwrap().is_none());
+
+        save_thread(&conn, &thread("s1", "C1", "1.1")).unwrap();
+        save_thread(&conn, &thread("s2", "C1", "2.2")).unwrap();
+        save_thread(&conn, &thread("s1", "C2", "3.3")).unwrap();
+
+        let saved = get_thread(&conn, "s1").unwrap().unwrap();
+        assert_eq!((saved.channel.as_str(), saved.thread_ts.as_str()), ("C2", "3.3"));
+        assert_eq!(saved.project_name.as_deref(), Some("demo"));
+
+        delete_thread(&conn, "s1").unwrap();
+        assert!(get_thread(&conn, "s1").unwrap().is_none());
+        assert!(get_thread(&conn, "s2").unwrap().is_some());
+    }
+}
@@ -0,0 +1,139 @@
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+
+/// One project's part of a standup: what its agents finished, what was
+/// committed and what it cost
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectStandup {
+    /// Empty for activity recorded under a name no project has any more
+    pub project_id: Option<String>,
+    pub project_name: String,
+    /// First line of each completed task's summary
+    pub completed: Vec<String>,
+    pub failed: Vec<String>,
+    /// `abc1234 Summary (Author)`, newest first
+    pub commits: Vec<String>,
+    pub cost_usd: f64,
+    pub tokens: u64,
+}
+
+/// A day's standup as generated, with where it was posted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandupSummary {
+    /// The day summarised, `YYYY-MM-DD`
+    pub day: String,
+    pub projects: Vec<ProjectStandup>,
+    /// The formatted text that was posted
+    pub text: String,
+    /// `slack` and/or `discord`
+    pub posted_to: Vec<String>,
+    pub created_at: String,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS standup_summaries (
+            day TEXT PRIMARY KEY,
+            projects TEXT NOT NULL,
+            text TEXT NOT NULL,
+            posted_to TEXT NOT NULL,
+            created_at TEXT NOT NULL
+        );",
+    )
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(row: &Row, index: usize) -> Result<T> {
+    let value: String = row.get(index)?;
+    serde_json::from_str(&value)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn row_to_summary(row: &Row) -> Result<StandupSummary> {
+    Ok(StandupSummary {
+        day: row.get(0)?,
+        projects: json_column(row, 1)?,
+        text: row.get(2)?,
+        posted_to: json_column(row, 3)?,
+        created_at: row.get(4)?,
+    })
+}
+
+/// Save a standup, replacing one generated earlier for the same day
+pub fn save_summary(conn: &Connection, summary: &StandupSummary) -> Result<()> {
+    let projects = serde_json::to_string(&summary.projects).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    let posted_to = serde_json::to_string(&summary.posted_to).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+    conn.execute(
+        "INSERT INTO standup_summaries (day, projects, text, posted_to, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(day) DO UPDATE SET
+            projects = excluded.projects,
+            text = excluded.text,
+            posted_to = excluded.posted_to,
+            created_at = excluded.created_at",
+        params![summary.day, projects, summary.text, posted_to, summary.created_at],
+    )?;
+    Ok(())
+}
+
+pub fn get_summary(conn: &Connection, day: &str) -> Result<Option<StandupSummary>> {
+    conn.query_row(
+        "SELECT day, projects, text, posted_to, created_at FROM standup_summaries WHERE day = ?1",
+        [day],
+        row_to_summary,
+    )
+    .optional()
+}
+
+/// The most recent standups, newest day first
+pub fn list_summaries(conn: &Connection, limit: usize) -> Result<Vec<StandupSummary>> {
+    let mut stmt = conn.prepare(
+        "SELECT day, projects, text, posted_to, created_at
+         FROM standup_summaries ORDER BY day DESC LIMIT ?1",
+    )?;
+    let summaries = stmt
+        .query_map([limit as i64], row_to_summary)?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(day: &str, text: &str) -> StandupSummary {
+        StandupSummary {
+            day: day.to_string(),
+            projects: vec![ProjectStandup {
+                project_id: Some("p1".to_string()),
+                project_name: "api".to_string(),
+                completed: vec!["Fix login".to_string()],
+                cost_usd: 1.5,
+                ..Default::default()
+            }],
+            text: text.to_string(),
+            posted_to: vec!["slack".to_string()],
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_save_replaces_by_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+
+        save_summary(&conn, &summary("2024-01-01", "first")).unwrap();
+        save_summary(&conn, &summary("2024-01-02", "second")).unwrap();
+        save_summary(&conn, &summary("2024-01-01", "regenerated")).unwrap();
+
+        let summaries = list_summaries(&conn, 10).unwrap();
+        let days: Vec<_> = summaries.iter().map(|s| s.day.as_str()).collect();
+        assert_eq!(days, vec!["2024-01-02", "2024-01-01"]);
+
+        let first = get_summary(&conn, "2024-01-01").unwrap().unwrap();
+        assert_eq!(first.text, "regenerated");
+        assert_eq!(first.projects[0].completed, vec!["Fix login"]);
+        assert_eq!(first.posted_to, vec!["slack"]);
+        assert_eq!(list_summaries(&conn, 1).unwrap().len(), 1);
+        assert!(get_summary(&conn, "2024-01-03").unwrap().is_none());
+    }
+}
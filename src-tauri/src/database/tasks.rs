@@ -0,0 +1,455 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::queue::{TaskMessage, TaskResult, TaskType, WorkerInfo};
+
+/// Setting holding how many days of task and worker history to keep
+pub const RETENTION_SETTING: &str = "task_history_retention_days";
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// A queued task and, once it has finished, its result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub id: String,
+    pub task_type: TaskType,
+    pub payload: serde_json::Value,
+    pub priority: u8,
+    pub max_retries: u32,
+    /// "queued", "completed" or "failed"
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub result: Option<TaskResult>,
+}
+
+/// A worker registration and its last known state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerRecord {
+    pub id: String,
+    pub hostname: String,
+    pub ip_address: String,
+    pub port: u16,
+    pub capabilities: Vec<String>,
+    pub max_concurrent_tasks: usize,
+    pub status: String,
+    pub registered_at: DateTime<Utc>,
+    pub last_heartbeat: DateTime<Utc>,
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+/// Create the task, result and worker tables and index task text for
+/// history search
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS queue_tasks (
+            id TEXT PRIMARY KEY,
+            task_type TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            priority INTEGER NOT NULL,
+            max_retries INTEGER NOT NULL,
+            status TEXT NOT NULL DEFAULT 'queued',
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS queue_task_results (
+            task_id TEXT PRIMARY KEY,
+            worker_id TEXT NOT NULL,
+            success BOOLEAN NOT NULL,
+            result TEXT,
+            error TEXT,
+            execution_time_ms INTEGER NOT NULL,
+            completed_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS queue_workers (
+            id TEXT PRIMARY KEY,
+            hostname TEXT NOT NULL,
+            ip_address TEXT NOT NULL,
+            port INTEGER NOT NULL,
+            capabilities TEXT NOT NULL,
+            max_concurrent_tasks INTEGER NOT NULL,
+            status TEXT NOT NULL,
+            registered_at TEXT NOT NULL,
+            last_heartbeat TEXT NOT NULL,
+            removed_at TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_queue_tasks_created ON queue_tasks(created_at);
+
+        CREATE TRIGGER IF NOT EXISTS queue_tasks_fts_insert
+        AFTER INSERT ON queue_tasks BEGIN
+            INSERT INTO history_fts (content, source, source_id, created_at)
+            VALUES (new.payload, 'task', new.id, new.created_at);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS queue_tasks_fts_delete
+        AFTER DELETE ON queue_tasks BEGIN
+            DELETE FROM history_fts WHERE source = 'task' AND source_id = old.id;
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS queue_task_results_fts_insert
+        AFTER INSERT ON queue_task_results BEGIN
+            INSERT INTO history_fts (content, source, source_id, created_at)
+            VALUES (COALESCE(new.error, new.result, ''), 'result', new.task_id, new.completed_at);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS queue_task_results_fts_delete
+        AFTER DELETE ON queue_task_results BEGIN
+            DELETE FROM history_fts WHERE source = 'result' AND source_id = old.task_id;
+        END;",
+    )
+}
+
+/// Index task prompts and results under the project named in the task's
+/// payload, so project-scoped history search finds them
+pub fn index_task_projects(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS queue_tasks_fts_insert;
+        CREATE TRIGGER queue_tasks_fts_insert
+        AFTER INSERT ON queue_tasks BEGIN
+            INSERT INTO history_fts (content, source, source_id, project_id, created_at)
+            VALUES (new.payload, 'task', new.id, json_extract(new.payload, '$.project_id'), new.created_at);
+        END;
+
+        DROP TRIGGER IF EXISTS queue_task_results_fts_insert;
+        CREATE TRIGGER queue_task_results_fts_insert
+        AFTER INSERT ON queue_task_results BEGIN
+            INSERT INTO history_fts (content, source, source_id, project_id, created_at)
+            VALUES (
+                COALESCE(new.error, new.result, ''), 'result', new.task_id,
+                (SELECT json_extract(payload, '$.project_id') FROM queue_tasks WHERE id = new.task_id),
+                new.completed_at
+            );
+        END;
+
+        UPDATE history_fts
+        SET project_id = (SELECT json_extract(t.payload, '$.project_id') FROM queue_tasks t WHERE t.id = history_fts.source_id)
+        WHERE source IN ('task', 'result') AND source_id IN (SELECT id FROM queue_tasks);",
+    )
+}
+
+fn json_column<T: DeserializeOwned>(row: &Row, idx: usize) -> Result<T> {
+    let text: String = row.get(idx)?;
+    serde_json::from_str(&text)
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e)))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// The payload as stored in history: project variables, which can hold
+/// secrets, are kept by name only so they never reach the database or the
+/// search index
+fn stored_payload(payload: &serde_json::Value) -> serde_json::Value {
+    let mut payload = payload.clone();
+    if let Some(env) = payload["env"].as_object() {
+        let names: Vec<&String> = env.keys().collect();
+        payload["env"] = serde_json::json!(names);
+    }
+    payload
+}
+
+/// Record a task as it is published
+pub fn record_task(conn: &Connection, task: &TaskMessage) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO queue_tasks (id, task_type, payload, priority, max_retries, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            task.id,
+            to_json(&task.task_type)?,
+            to_json(&stored_payload(&task.payload))?,
+            task.priority,
+            task.max_retries,
+            task.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Record a task's result and mark the task completed or failed
+pub fn record_result(conn: &Connection, result: &TaskResult) -> Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO queue_task_results
+         (task_id, worker_id, success, result, error, execution_time_ms, completed_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            result.task_id,
+            result.worker_id,
+            result.success,
+            result.result.as_ref().map(to_json).transpose()?,
+            result.error,
+            result.execution_time_ms as i64,
+            result.completed_at,
+        ],
+    )?;
+    conn.execute(
+        "UPDATE queue_tasks SET status = ?2 WHERE id = ?1",
+        params![result.task_id, if result.success { "completed" } else { "failed" }],
+    )?;
+    Ok(())
+}
+
+fn row_to_result(row: &Row, offset: usize) -> Result<TaskResult> {
+    let result: Option<String> = row.get(offset + 3)?;
+    Ok(TaskResult {
+        task_id: row.get(offset)?,
+        worker_id: row.get(offset + 1)?,
+        success: row.get(offset + 2)?,
+        result: result
+            .map(|text| serde_json::from_str(&text))
+            .transpose()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(offset + 3, rusqlite::types::Type::Text, Box::new(e)))?,
+        error: row.get(offset + 4)?,
+        execution_time_ms: row.get::<_, i64>(offset + 5)? as u64,
+        completed_at: row.get(offset + 6)?,
+    })
+}
+
+/// Get a stored result, including ones already consumed from the queue
+pub fn get_result(conn: &Connection, task_id: &str) -> Result<Option<TaskResult>> {
+    conn.query_row(
+        "SELECT task_id, worker_id, success, result, error, execution_time_ms, completed_at
+         FROM queue_task_results WHERE task_id = ?1",
+        [task_id],
+        |row| row_to_result(row, 0),
+    )
+    .optional()
+}
+
+/// List tasks with their results, newest first
+pub fn list_tasks(conn: &Connection, limit: usize, offset: usize) -> Result<Vec<TaskRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT t.id, t.task_type, t.payload, t.priority, t.max_retries, t.status, t.created_at,
+                r.task_id, r.worker_id, r.success, r.result, r.error, r.execution_time_ms, r.completed_at
+         FROM queue_tasks t
+         LEFT JOIN queue_task_results r ON r.task_id = t.id
+         ORDER BY t.created_at DESC
+         LIMIT ?1 OFFSET ?2",
+    )?;
+
+    let tasks = stmt
+        .query_map(params![limit, offset], |row| {
+            let has_result = row.get::<_, Option<String>>(7)?.is_some();
+            Ok(TaskRecord {
+                id: row.get(0)?,
+                task_type: json_column(row, 1)?,
+                payload: json_column(row, 2)?,
+                priority: row.get(3)?,
+                max_retries: row.get(4)?,
+                status: row.get(5)?,
+                created_at: row.get(6)?,
+                result: if has_result { Some(row_to_result(row, 7)?) } else { None },
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(tasks)
+}
+
+/// Record a worker registration, replacing any earlier one with the same id
+pub fn upsert_worker(conn: &Connection, worker: &WorkerInfo) -> Result<()> {
+    conn.execute(
+        "INSERT INTO queue_workers
+         (id, hostname, ip_address, port, capabilities, max_concurrent_tasks, status, registered_at, last_heartbeat)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+         ON CONFLICT(id) DO UPDATE SET
+            hostname = excluded.hostname,
+            ip_address = excluded.ip_address,
+            port = excluded.port,
+            capabilities = excluded.capabilities,
+            max_concurrent_tasks = excluded.max_concurrent_tasks,
+            status = excluded.status,
+            last_heartbeat = excluded.last_heartbeat,
+            removed_at = NULL",
+        params![
+            worker.id,
+            worker.hostname,
+            worker.ip_address,
+            worker.port,
+            to_json(&worker.capabilities)?,
+            worker.max_concurrent_tasks as i64,
+            format!("{:?}", worker.status),
+            worker.last_heartbeat,
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn touch_worker(conn: &Connection, worker_id: &str, at: DateTime<Utc>) -> Result<()> {
+    conn.execute(
+        "UPDATE queue_workers SET last_heartbeat = ?2 WHERE id = ?1",
+        params![worker_id, at],
+    )?;
+    Ok(())
+}
+
+/// Mark a worker as removed, keeping its registration for history
+pub fn remove_worker(conn: &Connection, worker_id: &str, at: DateTime<Utc>) -> Result<()> {
+    conn.execute(
+        "UPDATE queue_workers SET status = 'Offline', removed_at = ?2 WHERE id = ?1",
+        params![worker_id, at],
+    )?;
+    Ok(())
+}
+
+/// List every worker that has registered, most recently seen first
+pub fn list_workers(conn: &Connection) -> Result<Vec<WorkerRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, hostname, ip_address, port, capabilities, max_concurrent_tasks, status,
+                registered_at, last_heartbeat, removed_at
+         FROM queue_workers
+         ORDER BY last_heartbeat DESC",
+    )?;
+
+    let workers = stmt
+        .query_map([], |row| {
+            Ok(WorkerRecord {
+                id: row.get(0)?,
+                hostname: row.get(1)?,
+                ip_address: row.get(2)?,
+                port: row.get(3)?,
+                capabilities: json_column(row, 4)?,
+                max_concurrent_tasks: row.get::<_, i64>(5)? as usize,
+                status: row.get(6)?,
+                registered_at: row.get(7)?,
+                last_heartbeat: row.get(8)?,
+                removed_at: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(workers)
+}
+
+/// Delete tasks, results and removed workers older than `before`, returning
+/// the number of rows deleted
+pub fn prune_history(conn: &Connection, before: DateTime<Utc>) -> Result<usize> {
+    let tasks = conn.execute("DELETE FROM queue_tasks WHERE created_at < ?1", [before])?;
+    let results = conn.execute("DELETE FROM queue_task_results WHERE completed_at < ?1", [before])?;
+    let workers = conn.execute(
+        "DELETE FROM queue_workers WHERE removed_at IS NOT NULL AND removed_at < ?1",
+        [before],
+    )?;
+    Ok(tasks + results + workers)
+}
+
+/// Apply the configured retention, returning the number of rows deleted
+pub fn apply_retention(conn: &Connection) -> Result<usize> {
+    let days = super::settings::get_or(conn, RETENTION_SETTING, DEFAULT_RETENTION_DAYS)?;
+    prune_history(conn, Utc::now() - chrono::Duration::days(days as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::migrations;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        migrations::run(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_task_and_result_round_trip() {
+        let conn = setup();
+        let task = TaskMessage::new(TaskType::Custom("deploy".to_string()), serde_json::json!({"env": "staging"}));
+        record_task(&conn, &task).unwrap();
+
+        let listed = list_tasks(&conn, 10, 0).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].status, "queued");
+        assert!(listed[0].result.is_none());
+
+        record_result(&conn, &TaskResult {
+            task_id: task.id.clone(),
+            worker_id: "w1".to_string(),
+            success: false,
+            result: None,
+            error: Some("staging is down".to_string()),
+            execution_time_ms: 42,
+            completed_at: Utc::now(),
+        })
+        .unwrap();
+
+        let listed = list_tasks(&conn, 10, 0).unwrap();
+        assert_eq!(listed[0].status, "failed");
+        assert!(matches!(&listed[0].task_type, TaskType::Custom(name) if name == "deploy"));
+        assert_eq!(listed[0].result.as_ref().unwrap().execution_time_ms, 42);
+        assert_eq!(get_result(&conn, &task.id).unwrap().unwrap().error.as_deref(), Some("staging is down"));
+
+        let found = crate::database::history::search_history(&conn, "staging down", None, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].source, "result");
+    }
+
+    #[test]
+    fn test_task_env_values_are_not_stored() {
+        let conn = setup();
+        let task = TaskMessage::new(
+            TaskType::ExecuteCode,
+            serde_json::json!({"code": "make deploy", "env": {"API_KEY": "sk-live-hunter2"}}),
+        );
+        record_task(&conn, &task).unwrap();
+
+        let listed = list_tasks(&conn, 10, 0).unwrap();
+        assert_eq!(listed[0].payload["env"], serde_json::json!(["API_KEY"]));
+        assert_eq!(listed[0].payload["code"], "make deploy");
+        assert!(crate::database::history::search_history(&conn, "hunter2", None, 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_prune_history_keeps_recent_rows() {
+        let conn = setup();
+        let mut old = TaskMessage::new(TaskType::HealthCheck, serde_json::json!({}));
+        old.created_at = Utc::now() - chrono::Duration::days(60);
+        record_task(&conn, &old).unwrap();
+        record_task(&conn, &TaskMessage::new(TaskType::HealthCheck, serde_json::json!({}))).unwrap();
+
+        assert_eq!(apply_retention(&conn).unwrap(), 1);
+        assert_eq!(list_tasks(&conn, 10, 0).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_task_history_is_searchable_by_project() {
+        let conn = setup();
+        let task = TaskMessage::new(
+            TaskType::ExecuteCode,
+            serde_json::json!({"code": "cargo build", "project_id": "p1"}),
+        );
+        record_task(&conn, &task).unwrap();
+        record_result(&conn, &TaskResult {
+            task_id: task.id.clone(),
+            worker_id: "w1".to_string(),
+            success: false,
+            result: None,
+            error: Some("cargo build failed".to_string()),
+            execution_time_ms: 42,
+            completed_at: Utc::now(),
+        })
+        .unwrap();
+        record_task(&conn, &TaskMessage::new(TaskType::ExecuteCode, serde_json::json!({"code": "cargo build"}))).unwrap();
+
+        let search = |project_id| crate::database::history::search_history(&conn, "cargo build", project_id, 10).unwrap();
+        let mut sources: Vec<_> = search(Some("p1")).into_iter().map(|hit| hit.source).collect();
+        sources.sort();
+        assert_eq!(sources, vec!["result", "task"]);
+        assert!(search(Some("p2")).is_empty());
+        assert_eq!(search(None).len(), 3);
+    }
+
+    #[test]
+    fn test_index_task_projects_fills_existing_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        for migration in migrations::MIGRATIONS.iter().take_while(|m| m.version <= 3) {
+            (migration.apply)(&conn).unwrap();
+        }
+        let task = TaskMessage::new(TaskType::ExecuteCode, serde_json::json!({"code": "npm test", "project_id": "p1"}));
+        record_task(&conn, &task).unwrap();
+
+        index_task_projects(&conn).unwrap();
+        let found = crate::database::history::search_history(&conn, "npm test", Some("p1"), 10).unwrap();
+        assert_eq!(found.len(), 1);
+    }
+}
@@ -0,0 +1,105 @@
+use rusqlite::{Connection, Result, params};
+
+use crate::tmux::types::TmuxSession;
+
+/// Insert or update a tracked tmux session
+pub fn save_session(conn: &Connection, session: &TmuxSession) -> Result<()> {
+    let data = serde_json::to_string(session)
+        .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
+    conn.execute(
+        "INSERT INTO tmux_sessions (session_id, project_path, data, updated_at)
+         VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+         ON CONFLICT(session_id) DO UPDATE SET
+            project_path = excluded.project_path,
+            data = excluded.data,
+            updated_at = CURRENT_TIMESTAMP",
+        params![session.id, session.project_path, data],
+    )?;
+    Ok(())
+}
+
+pub fn delete_session(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM tmux_sessions WHERE session_id = ?1", [session_id])?;
+    Ok(())
+}
+
+/// All tracked sessions. Rows that no longer deserialize are skipped.
+pub fn list_sessions(conn: &Connection) -> Result<Vec<TmuxSession>> {
+    let mut stmt = conn.prepare("SELECT data FROM tmux_sessions ORDER BY updated_at")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut sessions = Vec::new();
+    for data in rows {
+        if let Ok(session) = serde_json::from_str(&data?) {
+            sessions.push(session);
+        }
+    }
+    Ok(sessions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, project_path: &str) -> TmuxSession {
+        TmuxSession {
+            id: id.to_string(),
+            name: format!("ninja-{}", id),
+            project_path: project_path.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            is_active: true,
+            window_count: 1,
+            pane_count: 1,
+            external: false,
+            cols: None,
+            rows: None,
+            host: None,
+            agent_pane: None,
+            auto_restart: false,
+        }
+    }
+
+    #[test]
+    fn test_save_updates_and_delete_removes() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+
+        save_session(&conn, &session("s1", "/work/app")).unwrap();
+        save_session(&conn, &session("s2", "/work/api")).unwrap();
+        let mut moved = session("s1", "/work/app2");
+        moved.cols = Some(120);
+        save_session(&conn, &moved).unwrap();
+
+        let sessions = list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 2);
+        let s1 = sessions.iter().find(|s| s.id == "s1").unwrap();
+        assert_eq!((s1.project_path.as_str(), s1.cols), ("/work/app2", Some(120)));
+
+        delete_session(&conn, "s1").unwrap();
+        let ids: Vec<_> = list_sessions(&conn).unwrap().into_iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec!["s2"]);
+    }
+
+    #[test]
+    fn test_list_skips_unreadable_rows_and_fills_defaults() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+
+        // Saved before the optional fields existed
+        conn.execute(
+            "INSERT INTO tmux_sessions (session_id, project_path, data) VALUES ('old', '/work', ?1)",
+            [r#"{"id":"old","name":"ninja-old","project_path":"/work","created_at":"","is_active":true,"window_count":2,"pane_count":3}"#],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO tmux_sessions (session_id, project_path, data) VALUES ('bad', '/work', 'not json')",
+            [],
+        ).unwrap();
+
+        let sessions = list_sessions(&conn).unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].window_count, 2);
+        assert!(!sessions[0].external && !sessions[0].auto_restart);
+        assert!(sessions[0].host.is_none());
+    }
+}
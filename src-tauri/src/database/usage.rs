@@ -0,0 +1,216 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// One unit of work to add to the daily totals
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageSample {
+    pub project_id: Option<String>,
+    /// Where the work ran: "claude", "orchestrator" or "queue"
+    pub source: String,
+    pub tasks: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub agent_time_ms: u64,
+}
+
+/// Inclusive range of days (`YYYY-MM-DD`); open ends are unbounded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageRange {
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGrouping {
+    #[default]
+    Day,
+    Project,
+    Source,
+}
+
+impl UsageGrouping {
+    fn column(self) -> &'static str {
+        match self {
+            UsageGrouping::Day => "day",
+            UsageGrouping::Project => "project_id",
+            UsageGrouping::Source => "source",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub tasks: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    pub agent_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRow {
+    /// The day, project id (empty when unknown) or source for this row
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub range: UsageRange,
+    pub group_by: UsageGrouping,
+    pub rows: Vec<UsageRow>,
+    pub totals: UsageTotals,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usage_daily (
+            day TEXT NOT NULL,
+            project_id TEXT NOT NULL DEFAULT '',
+            source TEXT NOT NULL,
+            tasks INTEGER NOT NULL DEFAULT 0,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cost_usd REAL NOT NULL DEFAULT 0,
+            agent_time_ms INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, project_id, source)
+        );",
+    )
+}
+
+/// Add a sample to the totals for `day`
+pub fn record_usage(conn: &Connection, day: &str, sample: &UsageSample) -> Result<()> {
+    conn.execute(
+        "INSERT INTO usage_daily
+         (day, project_id, source, tasks, input_tokens, output_tokens, cost_usd, agent_time_ms)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(day, project_id, source) DO UPDATE SET
+            tasks = tasks + excluded.tasks,
+            input_tokens = input_tokens + excluded.input_tokens,
+            output_tokens = output_tokens + excluded.output_tokens,
+            cost_usd = cost_usd + excluded.cost_usd,
+            agent_time_ms = agent_time_ms + excluded.agent_time_ms",
+        params![
+            day,
+            sample.project_id.as_deref().unwrap_or(""),
+            sample.source,
+            sample.tasks as i64,
+            sample.input_tokens as i64,
+            sample.output_tokens as i64,
+            sample.cost_usd,
+            sample.agent_time_ms as i64,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Sum usage over `range`, one row per day, project or source
+pub fn get_report(conn: &Connection, range: &UsageRange, group_by: UsageGrouping) -> Result<UsageReport> {
+    let column = group_by.column();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {column}, SUM(tasks), SUM(input_tokens), SUM(output_tokens), SUM(cost_usd), SUM(agent_time_ms)
+         FROM usage_daily
+         WHERE (?1 IS NULL OR day >= ?1) AND (?2 IS NULL OR day <= ?2)
+         GROUP BY {column}
+         ORDER BY {column}"
+    ))?;
+
+    let rows = stmt
+        .query_map(params![range.start, range.end], |row| {
+            Ok(UsageRow {
+                key: row.get(0)?,
+                totals: UsageTotals {
+                    tasks: row.get::<_, i64>(1)? as u64,
+                    input_tokens: row.get::<_, i64>(2)? as u64,
+                    output_tokens: row.get::<_, i64>(3)? as u64,
+                    cost_usd: row.get(4)?,
+                    agent_time_ms: row.get::<_, i64>(5)? as u64,
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let totals = rows.iter().fold(UsageTotals::default(), |mut acc, row| {
+        acc.tasks += row.totals.tasks;
+        acc.input_tokens += row.totals.input_tokens;
+        acc.output_tokens += row.totals.output_tokens;
+        acc.cost_usd += row.totals.cost_usd;
+        acc.agent_time_ms += row.totals.agent_time_ms;
+        acc
+    });
+
+    Ok(UsageReport { range: range.clone(), group_by, rows, totals })
+}
+
+/// Shared handle the claude, session and queue modules record usage through.
+/// Those are built before the database opens, so the connection is attached
+/// during app setup and samples before then are dropped.
+#[derive(Clone, Default)]
+pub struct UsageTracker {
+    conn: Arc<OnceLock<Arc<Mutex<Connection>>>>,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attach(&self, conn: Arc<Mutex<Connection>>) {
+        if self.conn.set(conn).is_err() {
+            println!("Usage tracker database already attached");
+        }
+    }
+
+    pub fn record(&self, sample: UsageSample) {
+        let Some(conn) = self.conn.get() else {
+            return;
+        };
+        let day = Utc::now().format("%Y-%m-%d").to_string();
+        let conn = conn.lock().unwrap();
+        if let Err(e) = record_usage(&conn, &day, &sample) {
+            println!("Failed to record usage: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_groups_and_filters_by_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        let claude = UsageSample {
+            project_id: Some("p1".to_string()),
+            source: "claude".to_string(),
+            tasks: 1,
+            input_tokens: 100,
+            output_tokens: 50,
+            cost_usd: 0.25,
+            agent_time_ms: 1000,
+        };
+        record_usage(&conn, "2024-01-01", &claude).unwrap();
+        record_usage(&conn, "2024-01-01", &claude).unwrap();
+        record_usage(&conn, "2024-01-02", &UsageSample { source: "queue".to_string(), tasks: 3, ..Default::default() }).unwrap();
+
+        let by_source = get_report(&conn, &UsageRange::default(), UsageGrouping::Source).unwrap();
+        assert_eq!(by_source.rows.len(), 2);
+        assert_eq!(by_source.rows[0].key, "claude");
+        assert_eq!(by_source.rows[0].totals.input_tokens, 200);
+        assert_eq!(by_source.totals.tasks, 5);
+
+        let range = UsageRange { start: Some("2024-01-02".to_string()), end: None };
+        let by_day = get_report(&conn, &range, UsageGrouping::Day).unwrap();
+        assert_eq!(by_day.rows.len(), 1);
+        assert_eq!(by_day.totals.tasks, 3);
+        assert_eq!(by_day.totals.cost_usd, 0.0);
+    }
+}
@@ -0,0 +1,133 @@
+use rusqlite::{Connection, OptionalExtension, Result, params};
+
+use crate::wezterm::mirror::WezTermMirror;
+use crate::wezterm::types::{PaneLayout, WezTermWindow, WindowGeometry};
+
+/// Get the saved pane layout for a project
+pub fn get_layout(conn: &Connection, project_id: &str) -> Result<Option<PaneLayout>> {
+    let layout: Option<String> = conn
+        .query_row(
+            "SELECT layout FROM project_terminal_layouts WHERE project_id = ?1",
+            [project_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    Ok(layout.and_then(|l| serde_json::from_str(&l).ok()))
+}
+
+/// Save the pane layout for a project
+pub fn save_layout(conn: &Connection, project_id: &str, layout: &PaneLayout) -> Result<()> {
+    let layout = to_json(layout)?;
+
+    conn.execute(
+        "INSERT INTO project_terminal_layouts (project_id, layout, updated_at)
+         VALUES (?1, ?2, CURRENT_TIMESTAMP)
+         ON CONFLICT(project_id) DO UPDATE SET layout = excluded.layout, updated_at = CURRENT_TIMESTAMP",
+        params![project_id, layout],
+    )?;
+    Ok(())
+}
+
+/// Get the saved window geometry for a project
+pub fn get_geometry(conn: &Connection, project_id: &str) -> Result<Option<WindowGeometry>> {
+    conn.query_row(
+        "SELECT x, y, width, height FROM project_window_geometry WHERE project_id = ?1",
+        [project_id],
+        |row| Ok(WindowGeometry {
+            x: row.get(0)?,
+            y: row.get(1)?,
+            width: row.get(2)?,
+            height: row.get(3)?,
+        }),
+    )
+    .optional()
+}
+
+/// Save the window geometry for a project
+pub fn save_geometry(conn: &Connection, project_id: &str, geometry: &WindowGeometry) -> Result<()> {
+    conn.execute(
+        "INSERT INTO project_window_geometry (project_id, x, y, width, height, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, CURRENT_TIMESTAMP)
+         ON CONFLICT(project_id) DO UPDATE SET
+            x = excluded.x,
+            y = excluded.y,
+            width = excluded.width,
+            height = excluded.height,
+            updated_at = CURRENT_TIMESTAMP",
+        params![project_id, geometry.x, geometry.y, geometry.width, geometry.height],
+    )?;
+    Ok(())
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// Insert or update a tracked WezTerm window
+pub fn save_window(conn: &Connection, window: &WezTermWindow) -> Result<()> {
+    conn.execute(
+        "INSERT INTO wezterm_windows (window_id, project_id, pane_id, data, updated_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(window_id) DO UPDATE SET
+            project_id = excluded.project_id,
+            pane_id = excluded.pane_id,
+            data = excluded.data,
+            updated_at = CURRENT_TIMESTAMP",
+        params![window.window_id, window.project_id, window.pane_id, to_json(window)?],
+    )?;
+    Ok(())
+}
+
+pub fn delete_window(conn: &Connection, window_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM wezterm_windows WHERE window_id = ?1", [window_id])?;
+    Ok(())
+}
+
+/// All tracked windows. Rows that no longer deserialize are skipped.
+pub fn list_windows(conn: &Connection) -> Result<Vec<WezTermWindow>> {
+    let mut stmt = conn.prepare("SELECT data FROM wezterm_windows ORDER BY updated_at")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut windows = Vec::new();
+    for data in rows {
+        if let Ok(window) = serde_json::from_str(&data?) {
+            windows.push(window);
+        }
+    }
+    Ok(windows)
+}
+
+/// Insert or update a mirror. The captured screen content is not stored.
+pub fn save_mirror(conn: &Connection, mirror: &WezTermMirror) -> Result<()> {
+    let mirror = WezTermMirror { last_content: String::new(), ..mirror.clone() };
+
+    conn.execute(
+        "INSERT INTO wezterm_mirrors (mirror_id, pane_id, data, updated_at)
+         VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)
+         ON CONFLICT(mirror_id) DO UPDATE SET
+            pane_id = excluded.pane_id,
+            data = excluded.data,
+            updated_at = CURRENT_TIMESTAMP",
+        params![mirror.id, mirror.pane_id, to_json(&mirror)?],
+    )?;
+    Ok(())
+}
+
+pub fn delete_mirror(conn: &Connection, mirror_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM wezterm_mirrors WHERE mirror_id = ?1", [mirror_id])?;
+    Ok(())
+}
+
+pub fn list_mirrors(conn: &Connection) -> Result<Vec<WezTermMirror>> {
+    let mut stmt = conn.prepare("SELECT data FROM wezterm_mirrors ORDER BY updated_at")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut mirrors = Vec::new();
+    for data in rows {
+        if let Ok(mirror) = serde_json::from_str(&data?) {
+            mirrors.push(mirror);
+        }
+    }
+    Ok(mirrors)
+}
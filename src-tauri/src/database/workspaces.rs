@@ -0,0 +1,157 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A named group of projects worked on together, e.g. frontend, backend
+/// and infra repos of one product
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub project_ids: Vec<String>,
+}
+
+pub fn create_tables(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS workspaces (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+            description TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE TABLE IF NOT EXISTS workspace_projects (
+            workspace_id TEXT NOT NULL,
+            project_id TEXT NOT NULL,
+            added_at TEXT NOT NULL,
+            PRIMARY KEY (workspace_id, project_id),
+            FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+            FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_workspace_projects_project ON workspace_projects(project_id);",
+    )
+}
+
+fn row_to_workspace(row: &Row) -> Result<Workspace> {
+    Ok(Workspace {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        created_at: row.get(3)?,
+        project_ids: Vec::new(),
+    })
+}
+
+/// Member project ids in the order they were added
+fn project_ids(conn: &Connection, workspace_id: &str) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT project_id FROM workspace_projects WHERE workspace_id = ?1 ORDER BY added_at, rowid",
+    )?;
+    let ids = stmt
+        .query_map([workspace_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ids)
+}
+
+pub fn create_workspace(conn: &Connection, name: &str, description: Option<&str>) -> Result<Workspace> {
+    let workspace = Workspace {
+        id: Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        description: description.map(str::to_string),
+        created_at: Utc::now().to_rfc3339(),
+        project_ids: Vec::new(),
+    };
+    conn.execute(
+        "INSERT INTO workspaces (id, name, description, created_at) VALUES (?1, ?2, ?3, ?4)",
+        params![workspace.id, workspace.name, workspace.description, workspace.created_at],
+    )?;
+    Ok(workspace)
+}
+
+pub fn get_workspace(conn: &Connection, id: &str) -> Result<Option<Workspace>> {
+    let workspace = conn
+        .query_row(
+            "SELECT id, name, description, created_at FROM workspaces WHERE id = ?1",
+            [id],
+            row_to_workspace,
+        )
+        .optional()?;
+
+    match workspace {
+        Some(mut workspace) => {
+            workspace.project_ids = project_ids(conn, id)?;
+            Ok(Some(workspace))
+        }
+        None => Ok(None),
+    }
+}
+
+pub fn list_workspaces(conn: &Connection) -> Result<Vec<Workspace>> {
+    let mut stmt = conn.prepare("SELECT id, name, description, created_at FROM workspaces ORDER BY name")?;
+    let mut workspaces = stmt
+        .query_map([], row_to_workspace)?
+        .collect::<Result<Vec<_>>>()?;
+    for workspace in &mut workspaces {
+        workspace.project_ids = project_ids(conn, &workspace.id)?;
+    }
+    Ok(workspaces)
+}
+
+pub fn delete_workspace(conn: &Connection, id: &str) -> Result<bool> {
+    let rows = conn.execute("DELETE FROM workspaces WHERE id = ?1", [id])?;
+    Ok(rows > 0)
+}
+
+/// Add a project to a workspace; adding a member again is a no-op
+pub fn add_project(conn: &Connection, workspace_id: &str, project_id: &str) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO workspace_projects (workspace_id, project_id, added_at) VALUES (?1, ?2, ?3)",
+        params![workspace_id, project_id, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+pub fn remove_project(conn: &Connection, workspace_id: &str, project_id: &str) -> Result<bool> {
+    let rows = conn.execute(
+        "DELETE FROM workspace_projects WHERE workspace_id = ?1 AND project_id = ?2",
+        params![workspace_id, project_id],
+    )?;
+    Ok(rows > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workspace_membership() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES
+                ('web', 'web', '/tmp/web'), ('api', 'api', '/tmp/api'), ('infra', 'infra', '/tmp/infra');",
+        )
+        .unwrap();
+
+        let workspace = create_workspace(&conn, "Shop", Some("Storefront")).unwrap();
+        for project in ["web", "api", "infra", "api"] {
+            add_project(&conn, &workspace.id, project).unwrap();
+        }
+        assert!(create_workspace(&conn, "shop", None).is_err());
+
+        let loaded = get_workspace(&conn, &workspace.id).unwrap().unwrap();
+        assert_eq!(loaded.project_ids, vec!["web", "api", "infra"]);
+
+        assert!(remove_project(&conn, &workspace.id, "api").unwrap());
+        conn.execute("DELETE FROM projects WHERE id = 'infra'", []).unwrap();
+        assert_eq!(list_workspaces(&conn).unwrap()[0].project_ids, vec!["web"]);
+
+        assert!(delete_workspace(&conn, &workspace.id).unwrap());
+        assert!(get_workspace(&conn, &workspace.id).unwrap().is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use super::types::DevServerHealth;
+
+/// How often the readiness URL is polled while the server starts up
+pub const STARTUP_INTERVAL: Duration = Duration::from_secs(1);
+/// And once it has been healthy
+pub const INTERVAL: Duration = Duration::from_secs(5);
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Failed checks before a starting server counts as unhealthy
+const STARTUP_FAILURES: u32 = 60;
+/// Failed checks in a row before a healthy server counts as unhealthy
+const FAILURES: u32 = 3;
+
+/// Health of one run of a server, from the results of its checks
+#[derive(Debug)]
+pub struct HealthCheck {
+    pub health: DevServerHealth,
+    failures: u32,
+}
+
+impl Default for HealthCheck {
+    fn default() -> Self {
+        Self { health: DevServerHealth::Starting, failures: 0 }
+    }
+}
+
+impl HealthCheck {
+    /// Record a check, returning the new health if it changed
+    pub fn record(&mut self, ok: bool) -> Option<DevServerHealth> {
+        self.failures = if ok { 0 } else { self.failures + 1 };
+        let next = match (self.health, ok) {
+            (_, true) => DevServerHealth::Healthy,
+            (DevServerHealth::Starting, false) if self.failures >= STARTUP_FAILURES => DevServerHealth::Unhealthy,
+            (DevServerHealth::Healthy, false) if self.failures >= FAILURES => DevServerHealth::Unhealthy,
+            (current, false) => current,
+        };
+        if next == self.health {
+            return None;
+        }
+        self.health = next;
+        Some(next)
+    }
+
+    pub fn interval(&self) -> Duration {
+        match self.health {
+            DevServerHealth::Starting => STARTUP_INTERVAL,
+            _ => INTERVAL,
+        }
+    }
+}
+
+/// Whether `url` answers without a server error. Redirects and client
+/// errors still show the server is up.
+pub async fn probe(client: &reqwest::Client, url: &str) -> bool {
+    match client.get(url).send().await {
+        Ok(response) => !response.status().is_server_error(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_health_transitions() {
+        let mut check = HealthCheck::default();
+        assert_eq!(check.record(false), None);
+        assert_eq!(check.interval(), STARTUP_INTERVAL);
+        assert_eq!(check.record(true), Some(DevServerHealth::Healthy));
+        assert_eq!(check.record(true), None);
+        assert_eq!(check.interval(), INTERVAL);
+
+        assert_eq!(check.record(false), None);
+        assert_eq!(check.record(false), None);
+        assert_eq!(check.record(false), Some(DevServerHealth::Unhealthy));
+        assert_eq!(check.record(false), None);
+        assert_eq!(check.record(true), Some(DevServerHealth::Healthy));
+
+        let mut never_up = HealthCheck::default();
+        let changes: Vec<_> = (0..STARTUP_FAILURES).filter_map(|_| never_up.record(false)).collect();
+        assert_eq!(changes, vec![DevServerHealth::Unhealthy]);
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::VecDeque;
+
+use super::types::{LogLine, LogStream};
+
+/// Output kept per dev server
+pub const DEFAULT_LOG_CAPACITY: usize = 256 * 1024;
+
+/// The most recent output lines of a dev server, dropping the oldest once
+/// their text passes `capacity` bytes
+#[derive(Debug)]
+pub struct LogBuffer {
+    lines: VecDeque<LogLine>,
+    bytes: usize,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_LOG_CAPACITY)
+    }
+}
+
+impl LogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { lines: VecDeque::new(), bytes: 0, capacity }
+    }
+
+    pub fn push(&mut self, stream: LogStream, text: String) {
+        self.bytes += text.len();
+        self.lines.push_back(LogLine { stream, text, timestamp: chrono::Utc::now().to_rfc3339() });
+        // The newest line is kept even if it alone is over capacity
+        while self.bytes > self.capacity && self.lines.len() > 1 {
+            if let Some(line) = self.lines.pop_front() {
+                self.bytes -= line.text.len();
+            }
+        }
+    }
+
+    /// The last `count` lines, oldest first; all of them when None
+    pub fn tail(&self, count: Option<usize>) -> Vec<LogLine> {
+        let skip = count.map_or(0, |count| self.lines.len().saturating_sub(count));
+        self.lines.iter().skip(skip).cloned().collect()
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.bytes = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_drops_oldest_lines_past_capacity() {
+        let mut logs = LogBuffer::with_capacity(10);
+        logs.push(LogStream::Stdout, "one".to_string());
+        logs.push(LogStream::Stderr, "two".to_string());
+        logs.push(LogStream::Stdout, "three".to_string());
+        let texts: Vec<_> = logs.tail(None).into_iter().map(|l| l.text).collect();
+        assert_eq!(texts, vec!["two", "three"]);
+
+        logs.push(LogStream::Stdout, "a long line past capacity".to_string());
+        assert_eq!(logs.tail(None).len(), 1);
+
+        logs.push(LogStream::Stderr, "x".to_string());
+        let last = logs.tail(Some(1));
+        assert_eq!(last.len(), 1);
+        assert_eq!(last[0].stream, LogStream::Stderr);
+        assert_eq!(logs.tail(Some(5)).len(), 1);
+
+        logs.clear();
+        assert!(logs.tail(None).is_empty());
+    }
+}
@@ -0,0 +1,566 @@
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use chrono::Utc;
+use regex::Regex;
+use futures::future::BoxFuture;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+use tokio::sync::{oneshot, RwLock};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use super::health::{probe, HealthCheck, REQUEST_TIMEOUT};
+use super::logs::LogBuffer;
+use super::policy::{backoff, ALERT_AFTER_CRASHES, STABLE_AFTER};
+use super::shell::{shell_command, terminate};
+use super::types::{
+    DevServer, DevServerExit, DevServerHealth, DevServerHealthChange, DevServerReady, DevServerSpec, DevServerStatus, LogLine,
+    LogStream, RestartPolicy,
+};
+use super::url::detect_url;
+use crate::database::DatabaseManager;
+use crate::email::{EmailNotifier, TaskNotification};
+use crate::slack::{SlackErrorAlert, SlackService};
+use crate::telegram::{TelegramMessage, TelegramService};
+use crate::tmux::capture::strip_ansi;
+
+/// The task waiting on a running server's process
+struct Supervisor {
+    stop: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+struct Entry {
+    server: DevServer,
+    spec: DevServerSpec,
+    supervisor: Option<Supervisor>,
+    /// Kept across restarts
+    logs: Arc<Mutex<LogBuffer>>,
+    started: Instant,
+    /// Exits in a row without running for `STABLE_AFTER`
+    crashes: u32,
+}
+
+/// Where a server that keeps crashing is reported
+#[derive(Clone)]
+struct Alerts {
+    slack: Arc<SlackService>,
+    telegram: Arc<TelegramService>,
+    email: Arc<EmailNotifier>,
+}
+
+/// Dev servers started from the app. Exited servers stay listed until
+/// removed so their exit code can be shown.
+#[derive(Clone, Default)]
+pub struct DevServerManager {
+    servers: Arc<RwLock<HashMap<String, Entry>>>,
+    alerts: Option<Alerts>,
+}
+
+impl DevServerManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_alerts(mut self, slack: Arc<SlackService>, telegram: Arc<TelegramService>, email: Arc<EmailNotifier>) -> Self {
+        self.alerts = Some(Alerts { slack, telegram, email });
+        self
+    }
+
+    /// Run the spec's command through the platform's shell. Its output is emitted line by
+    /// line as `dev-server-output` and `dev-server-error`,
+    /// `dev-server-ready` once it passes the ready check, and
+    /// `dev-server-exited` when it ends. The restart policy decides whether
+    /// it's then started again. With a readiness URL, its health is polled
+    /// and changes emitted as `dev-server-healthy` and
+    /// `dev-server-unhealthy`.
+    pub async fn start(&self, app: AppHandle, spec: DevServerSpec) -> Result<DevServer, String> {
+        let logs = Arc::new(Mutex::new(LogBuffer::default()));
+        self.launch(app, Uuid::new_v4().to_string(), spec, logs, 0).await
+    }
+
+    /// Start `spec` under `id`. `crashes` is non-zero for an automatic
+    /// restart, which is counted; other restarts start a new streak.
+    fn launch(
+        &self,
+        app: AppHandle,
+        id: String,
+        spec: DevServerSpec,
+        logs: Arc<Mutex<LogBuffer>>,
+        crashes: u32,
+    ) -> BoxFuture<'_, Result<DevServer, String>> {
+        // Boxed because the task it spawns may call it again to restart
+        Box::pin(async move {
+            let mut command = shell_command(&spec.command);
+            command
+                .current_dir(&spec.working_dir)
+                .envs(&spec.env)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+
+            let mut child = command.spawn().map_err(|e| format!("Failed to spawn dev server: {}", e))?;
+            let mut server = DevServer {
+                id: id.clone(),
+                project_id: spec.project_id.clone(),
+                profile: spec.profile.clone(),
+                command: spec.command.clone(),
+                working_dir: spec.working_dir.clone(),
+                pid: child.id(),
+                status: DevServerStatus::Running,
+                url: None,
+                ready: false,
+                ready_url: spec.ready_url.clone(),
+                health: spec.ready_url.as_ref().map(|_| DevServerHealth::Starting),
+                restart_policy: spec.restart_policy,
+                restart_count: 0,
+                exit_code: None,
+                started_at: Utc::now().to_rfc3339(),
+                exited_at: None,
+            };
+            println!("Started dev server {} (pid {:?}): {}", id, server.pid, spec.command);
+
+            // Held while the tasks start so a server that prints its URL or
+            // exits at once still finds its entry
+            let mut servers = self.servers.write().await;
+            if let Some(previous) = servers.get(&id) {
+                server.restart_count = previous.server.restart_count + u32::from(crashes > 0);
+            }
+            let pattern = spec.ready_pattern.clone();
+            if let Some(stdout) = child.stdout.take() {
+                let output = self.clone().forward_lines(stdout, LogStream::Stdout, id.clone(), server.pid, pattern.clone(), app.clone(), logs.clone());
+                tokio::spawn(output);
+            }
+            if let Some(stderr) = child.stderr.take() {
+                let output = self.clone().forward_lines(stderr, LogStream::Stderr, id.clone(), server.pid, pattern, app.clone(), logs.clone());
+                tokio::spawn(output);
+            }
+            if let Some(url) = spec.ready_url.clone() {
+                tokio::spawn(self.clone().watch_health(app.clone(), id.clone(), server.pid, url));
+            }
+            let (stop, stop_rx) = oneshot::channel();
+            let task = tokio::spawn(self.clone().supervise(app, id.clone(), child, stop_rx));
+            let supervisor = Some(Supervisor { stop, task });
+            servers.insert(id, Entry { server: server.clone(), spec, supervisor, logs, started: Instant::now(), crashes });
+            Ok(server)
+        })
+    }
+
+    async fn supervise(self, app: AppHandle, id: String, mut child: Child, stop: oneshot::Receiver<()>) {
+        let (status, mut stopped) = tokio::select! {
+            status = child.wait() => (status, false),
+            _ = stop => (terminate(&mut child).await, true),
+        };
+        let exit_code = status.ok().and_then(|s| s.code());
+
+        let project_id = {
+            let mut servers = self.servers.write().await;
+            let Some(entry) = servers.get_mut(&id) else { return };
+            // A stop that raced the exit still counts as one
+            stopped |= entry.supervisor.take().is_none();
+            entry.server.status = if stopped { DevServerStatus::Stopped } else { DevServerStatus::Exited };
+            entry.server.exit_code = exit_code;
+            entry.server.exited_at = Some(Utc::now().to_rfc3339());
+            entry.server.health = None;
+            entry.server.project_id.clone()
+        };
+        println!("Dev server {} exited with code {:?}", id, exit_code);
+        let _ = app.emit("dev-server-exited", DevServerExit { id: id.clone(), project_id, exit_code, stopped });
+        if !stopped {
+            self.after_crash(app, id, exit_code).await;
+        }
+    }
+
+    /// Apply the server's restart policy after it exited on its own
+    async fn after_crash(&self, app: AppHandle, id: String, exit_code: Option<i32>) {
+        let (server, spec, logs, crashes, restart) = {
+            let mut servers = self.servers.write().await;
+            let Some(entry) = servers.get_mut(&id) else { return };
+            entry.crashes = if entry.started.elapsed() >= STABLE_AFTER { 1 } else { entry.crashes + 1 };
+            let restart = entry.spec.restart_policy.should_restart(exit_code, entry.crashes);
+            if restart {
+                entry.server.status = DevServerStatus::Restarting;
+            }
+            (entry.server.clone(), entry.spec.clone(), entry.logs.clone(), entry.crashes, restart)
+        };
+
+        let failed = exit_code != Some(0);
+        let gave_up = !restart && server.restart_policy != RestartPolicy::Never;
+        if failed && (crashes == ALERT_AFTER_CRASHES || gave_up) {
+            self.alert_crashing(&app, &server, crashes, gave_up).await;
+        }
+        if !restart {
+            return;
+        }
+
+        let delay = backoff(crashes);
+        println!("Restarting dev server {} in {:?} (exit {} in a row)", id, delay, crashes);
+        tokio::time::sleep(delay).await;
+
+        // Stopped, removed or restarted by hand while waiting
+        let still_waiting = self.get(&id).await.is_some_and(|current| {
+            current.status == DevServerStatus::Restarting && current.pid == server.pid
+        });
+        if !still_waiting {
+            return;
+        }
+        if let Err(e) = self.launch(app, id.clone(), spec, logs, crashes).await {
+            println!("Failed to restart dev server {}: {}", id, e);
+            if let Some(entry) = self.servers.write().await.get_mut(&id) {
+                entry.server.status = DevServerStatus::Exited;
+            }
+        }
+    }
+
+    async fn alert_crashing(&self, app: &AppHandle, server: &DevServer, crashes: u32, gave_up: bool) {
+        let Some(alerts) = &self.alerts else { return };
+        let project_name = server.project_id.as_deref().and_then(|id| {
+            let db = app.state::<DatabaseManager>();
+            crate::projects::manager::ProjectsManager::new(&db).get(id).ok().flatten().map(|p| p.name)
+        });
+        let title = if gave_up {
+            "Dev server stopped restarting".to_string()
+        } else {
+            "Dev server keeps crashing".to_string()
+        };
+        let error = format!(
+            "`{}` in {} exited with {} {} time(s) in a row",
+            server.command,
+            server.working_dir,
+            server.exit_code.map_or("a signal".to_string(), |code| format!("code {}", code)),
+            crashes
+        );
+
+        let alert = SlackErrorAlert { title: title.clone(), error: error.clone(), project_name: project_name.clone(), session_id: None };
+        if let Err(e) = alerts.slack.send_error_alert(alert).await {
+            println!("Failed to report dev server {} to Slack: {}", server.id, e);
+        }
+        let text = match &project_name {
+            Some(name) => format!("{} ({}): {}", title, name, error),
+            None => format!("{}: {}", title, error),
+        };
+        if let Err(e) = alerts.telegram.send_message(TelegramMessage { text }).await {
+            println!("Failed to report dev server {} to Telegram: {}", server.id, e);
+        }
+        let notification = TaskNotification {
+            project_name: project_name.unwrap_or_else(|| server.working_dir.clone()),
+            session_id: None,
+            summary: format!("{}: {}", title, error),
+            success: false,
+        };
+        if let Err(e) = alerts.email.notify_task(notification).await {
+            println!("Failed to email about dev server {}: {}", server.id, e);
+        }
+    }
+
+    /// Buffer each line and emit it as `dev-server-output` or
+    /// `dev-server-error`, watching for the server's URL and ready check
+    #[allow(clippy::too_many_arguments)]
+    async fn forward_lines(
+        self,
+        stream: impl AsyncRead + Unpin,
+        kind: LogStream,
+        id: String,
+        pid: Option<u32>,
+        pattern: Option<Regex>,
+        app: AppHandle,
+        logs: Arc<Mutex<LogBuffer>>,
+    ) {
+        let event = match kind {
+            LogStream::Stdout => "dev-server-output",
+            LogStream::Stderr => "dev-server-error",
+        };
+        let (mut url_found, mut ready) = (false, false);
+        let mut lines = BufReader::new(stream).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if !url_found || !ready {
+                let url = if url_found { None } else { detect_url(&line) };
+                let passed = match &pattern {
+                    Some(pattern) => !ready && pattern.is_match(&strip_ansi(&line)),
+                    None => url.is_some(),
+                };
+                url_found |= url.is_some();
+                ready |= passed;
+                if url.is_some() || passed {
+                    self.update_readiness(&app, &id, pid, url, passed).await;
+                }
+            }
+            logs.lock().unwrap().push(kind, line.clone());
+            let _ = app.emit(event, line);
+        }
+    }
+
+    /// Record the server's URL and whether it passed its ready check,
+    /// unless the other stream got there first or the server was restarted
+    /// since
+    async fn update_readiness(&self, app: &AppHandle, id: &str, pid: Option<u32>, url: Option<String>, passed: bool) {
+        let ready = {
+            let mut servers = self.servers.write().await;
+            let Some(entry) = servers.get_mut(id) else { return };
+            if entry.server.pid != pid {
+                return;
+            }
+            if entry.server.url.is_none() {
+                entry.server.url = url;
+            }
+            if !passed || entry.server.ready {
+                return;
+            }
+            entry.server.ready = true;
+            DevServerReady {
+                id: id.to_string(),
+                project_id: entry.server.project_id.clone(),
+                profile: entry.server.profile.clone(),
+                url: entry.server.url.clone(),
+            }
+        };
+        println!("Dev server {} is ready at {:?}", id, ready.url);
+        let _ = app.emit("dev-server-ready", ready);
+    }
+
+    /// Poll the server's readiness URL until it exits or is restarted
+    async fn watch_health(self, app: AppHandle, id: String, pid: Option<u32>, url: String) {
+        let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default();
+        let mut check = HealthCheck::default();
+        loop {
+            tokio::time::sleep(check.interval()).await;
+            if !self.is_running(&id, pid).await {
+                return;
+            }
+            let Some(health) = check.record(probe(&client, &url).await) else { continue };
+
+            let change = {
+                let mut servers = self.servers.write().await;
+                let Some(entry) = servers.get_mut(&id) else { return };
+                // Exited while the request was in flight
+                if entry.server.pid != pid || entry.server.status != DevServerStatus::Running {
+                    return;
+                }
+                entry.server.health = Some(health);
+                DevServerHealthChange {
+                    id: id.clone(),
+                    project_id: entry.server.project_id.clone(),
+                    profile: entry.server.profile.clone(),
+                    health,
+                    url: url.clone(),
+                }
+            };
+            println!("Dev server {} is {:?} at {}", id, health, url);
+            let event = match health {
+                DevServerHealth::Healthy => "dev-server-healthy",
+                _ => "dev-server-unhealthy",
+            };
+            let _ = app.emit(event, change);
+        }
+    }
+
+    async fn is_running(&self, id: &str, pid: Option<u32>) -> bool {
+        self.get(id).await.is_some_and(|server| server.pid == pid && server.status == DevServerStatus::Running)
+    }
+
+    pub async fn get(&self, id: &str) -> Option<DevServer> {
+        self.servers.read().await.get(id).map(|entry| entry.server.clone())
+    }
+
+    /// Oldest first, only the project's when `project_id` is given
+    pub async fn list(&self, project_id: Option<&str>) -> Vec<DevServer> {
+        let mut servers: Vec<DevServer> = self
+            .servers
+            .read()
+            .await
+            .values()
+            .filter(|entry| project_id.is_none_or(|id| entry.server.project_id.as_deref() == Some(id)))
+            .map(|entry| entry.server.clone())
+            .collect();
+        servers.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+        servers
+    }
+
+    /// The running (or restarting) server started from a project's profile
+    pub async fn find_profile(&self, project_id: &str, profile: &str) -> Option<DevServer> {
+        self.list(Some(project_id)).await.into_iter().rev().find(|server| {
+            server.profile.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(profile))
+                && matches!(server.status, DevServerStatus::Running | DevServerStatus::Restarting)
+        })
+    }
+
+    /// Stop a server and wait for it to exit, see `shell::terminate`
+    pub async fn stop(&self, id: &str) -> Result<DevServer, String> {
+        let supervisor = {
+            let mut servers = self.servers.write().await;
+            let entry = servers.get_mut(id).ok_or_else(|| format!("Dev server not found: {}", id))?;
+            if entry.server.status == DevServerStatus::Restarting {
+                entry.server.status = DevServerStatus::Stopped;
+            }
+            entry.supervisor.take()
+        };
+        if let Some(supervisor) = supervisor {
+            let _ = supervisor.stop.send(());
+            let _ = supervisor.task.await;
+        }
+        self.get(id).await.ok_or_else(|| format!("Dev server not found: {}", id))
+    }
+
+    /// Stop a server if it's running and start its command again under the
+    /// same ID
+    pub async fn restart(&self, app: AppHandle, id: &str) -> Result<DevServer, String> {
+        let (spec, logs) = self
+            .servers
+            .read()
+            .await
+            .get(id)
+            .map(|entry| (entry.spec.clone(), entry.logs.clone()))
+            .ok_or_else(|| format!("Dev server not found: {}", id))?;
+        self.stop(id).await?;
+        self.launch(app, id.to_string(), spec, logs, 0).await
+    }
+
+    pub async fn set_restart_policy(&self, id: &str, policy: RestartPolicy) -> Result<DevServer, String> {
+        let mut servers = self.servers.write().await;
+        let entry = servers.get_mut(id).ok_or_else(|| format!("Dev server not found: {}", id))?;
+        entry.spec.restart_policy = policy;
+        entry.server.restart_policy = policy;
+        Ok(entry.server.clone())
+    }
+
+    /// The server's last `lines` lines of output, or all that's buffered
+    pub async fn logs(&self, id: &str, lines: Option<usize>) -> Result<Vec<LogLine>, String> {
+        let servers = self.servers.read().await;
+        let entry = servers.get(id).ok_or_else(|| format!("Dev server not found: {}", id))?;
+        let logs = entry.logs.lock().unwrap().tail(lines);
+        Ok(logs)
+    }
+
+    pub async fn clear_logs(&self, id: &str) -> Result<(), String> {
+        let servers = self.servers.read().await;
+        let entry = servers.get(id).ok_or_else(|| format!("Dev server not found: {}", id))?;
+        entry.logs.lock().unwrap().clear();
+        Ok(())
+    }
+
+    /// Stop a server if needed and forget it
+    pub async fn remove(&self, id: &str) -> Result<bool, String> {
+        if self.get(id).await.is_none() {
+            return Ok(false);
+        }
+        self.stop(id).await?;
+        Ok(self.servers.write().await.remove(id).is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, project_id: &str, profile: &str, status: DevServerStatus, started_at: &str) -> DevServer {
+        DevServer {
+            id: id.to_string(),
+            project_id: Some(project_id.to_string()),
+            profile: Some(profile.to_string()),
+            command: "npm run dev".to_string(),
+            working_dir: "/tmp".to_string(),
+            pid: None,
+            status,
+            url: None,
+            ready: false,
+            ready_url: None,
+            health: None,
+            restart_policy: RestartPolicy::default(),
+            restart_count: 0,
+            exit_code: None,
+            started_at: started_at.to_string(),
+            exited_at: None,
+        }
+    }
+
+    /// Track `server` as if it had been launched, with a supervisor that
+    /// marks it stopped once told to stop
+    async fn insert(manager: &DevServerManager, server: DevServer) {
+        let supervisor = (server.status == DevServerStatus::Running).then(|| {
+            let (stop, stopped) = oneshot::channel();
+            let servers = manager.servers.clone();
+            let id = server.id.clone();
+            let task = tokio::spawn(async move {
+                let _ = stopped.await;
+                if let Some(entry) = servers.write().await.get_mut(&id) {
+                    entry.server.status = DevServerStatus::Stopped;
+                }
+            });
+            Supervisor { stop, task }
+        });
+        let entry = Entry {
+            spec: DevServerSpec::default(),
+            supervisor,
+            logs: Arc::new(Mutex::new(LogBuffer::default())),
+            started: Instant::now(),
+            crashes: 0,
+            server,
+        };
+        manager.servers.write().await.insert(entry.server.id.clone(), entry);
+    }
+
+    #[tokio::test]
+    async fn test_list_per_project_oldest_first() {
+        let manager = DevServerManager::new();
+        insert(&manager, server("b", "p1", "web", DevServerStatus::Running, "2024-01-01T00:00:02Z")).await;
+        insert(&manager, server("a", "p1", "web", DevServerStatus::Exited, "2024-01-01T00:00:01Z")).await;
+        insert(&manager, server("c", "p2", "api", DevServerStatus::Running, "2024-01-01T00:00:00Z")).await;
+
+        let ids = |servers: Vec<DevServer>| servers.into_iter().map(|s| s.id).collect::<Vec<_>>();
+        assert_eq!(ids(manager.list(None).await), vec!["c", "a", "b"]);
+        assert_eq!(ids(manager.list(Some("p1")).await), vec!["a", "b"]);
+        assert!(manager.list(Some("p3")).await.is_empty());
+
+        assert_eq!(manager.find_profile("p1", "WEB").await.map(|s| s.id).as_deref(), Some("b"));
+        assert!(manager.find_profile("p2", "web").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stop_waits_for_the_supervisor() {
+        let manager = DevServerManager::new();
+        insert(&manager, server("run", "p1", "web", DevServerStatus::Running, "2024-01-01T00:00:00Z")).await;
+        insert(&manager, server("retry", "p1", "api", DevServerStatus::Restarting, "2024-01-01T00:00:01Z")).await;
+
+        assert_eq!(manager.stop("run").await.unwrap().status, DevServerStatus::Stopped);
+        // A crashed server isn't started again once stopped
+        assert_eq!(manager.stop("retry").await.unwrap().status, DevServerStatus::Stopped);
+        // Stopping again is a no-op
+        assert_eq!(manager.stop("run").await.unwrap().status, DevServerStatus::Stopped);
+        assert_eq!(manager.stop("missing").await.unwrap_err(), "Dev server not found: missing");
+    }
+
+    #[tokio::test]
+    async fn test_remove() {
+        let manager = DevServerManager::new();
+        insert(&manager, server("c", "p2", "web", DevServerStatus::Running, "2024-01-01T00:00:02Z")).await;
+
+        assert_eq!(manager.remove("c").await, Ok(true));
+        assert!(manager.get("c").await.is_none());
+        assert_eq!(manager.remove("c").await, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn test_policy_and_logs_of_a_tracked_server() {
+        let manager = DevServerManager::new();
+        insert(&manager, server("a", "p1", "web", DevServerStatus::Exited, "2024-01-01T00:00:00Z")).await;
+
+        let policy = RestartPolicy::OnFailure { max_retries: 3 };
+        assert_eq!(manager.set_restart_policy("a", policy).await.unwrap().restart_policy, policy);
+        assert_eq!(manager.servers.read().await["a"].spec.restart_policy, policy);
+        assert!(manager.set_restart_policy("missing", policy).await.is_err());
+
+        manager.servers.read().await["a"].logs.lock().unwrap().push(LogStream::Stdout, "one".to_string());
+        manager.servers.read().await["a"].logs.lock().unwrap().push(LogStream::Stderr, "two".to_string());
+        let texts = |lines: Vec<LogLine>| lines.into_iter().map(|l| l.text).collect::<Vec<_>>();
+        assert_eq!(texts(manager.logs("a", None).await.unwrap()), vec!["one", "two"]);
+        assert_eq!(texts(manager.logs("a", Some(1)).await.unwrap()), vec!["two"]);
+
+        manager.clear_logs("a").await.unwrap();
+        assert!(manager.logs("a", None).await.unwrap().is_empty());
+        assert!(manager.logs("missing", None).await.is_err());
+    }
+}
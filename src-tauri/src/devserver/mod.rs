@@ -0,0 +1,11 @@
+pub mod health;
+pub mod logs;
+pub mod manager;
+pub mod policy;
+pub mod profiles;
+pub mod shell;
+pub mod types;
+pub mod url;
+
+pub use manager::DevServerManager;
+pub use types::*;
@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use super::types::RestartPolicy;
+
+/// Wait before the first automatic restart, doubled for each crash in a row
+const BASE_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A server that ran at least this long before exiting starts a new streak
+pub const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+/// Crashes in a row before the alerting channels hear about it
+pub const ALERT_AFTER_CRASHES: u32 = 3;
+
+impl RestartPolicy {
+    /// Whether to start a server again after it exited with `exit_code`,
+    /// `crashes` being its exits in a row including this one. Being killed
+    /// by a signal counts as a failure.
+    pub fn should_restart(&self, exit_code: Option<i32>, crashes: u32) -> bool {
+        match self {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure { max_retries } => exit_code != Some(0) && crashes <= *max_retries,
+            RestartPolicy::Always => true,
+        }
+    }
+}
+
+/// How long to wait before restarting after `crashes` exits in a row
+pub fn backoff(crashes: u32) -> Duration {
+    let doublings = crashes.saturating_sub(1).min(16);
+    (BASE_BACKOFF * 2u32.pow(doublings)).min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_policies_and_backoff() {
+        let on_failure = RestartPolicy::OnFailure { max_retries: 2 };
+        assert!(on_failure.should_restart(Some(1), 1));
+        assert!(on_failure.should_restart(None, 2));
+        assert!(!on_failure.should_restart(Some(1), 3));
+        assert!(!on_failure.should_restart(Some(0), 1));
+        assert!(RestartPolicy::Always.should_restart(Some(0), 50));
+        assert!(!RestartPolicy::Never.should_restart(Some(1), 1));
+
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(7), Duration::from_secs(60));
+        assert_eq!(backoff(u32::MAX), Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,108 @@
+use regex::Regex;
+use std::path::Path;
+
+use super::types::DevServerSpec;
+use crate::projects::env::{project_env, read_dotenv, secret_values};
+use crate::projects::types::{DevServerProfile, Project};
+
+/// The project's profile called `name`, ignoring case
+pub fn find<'a>(project: &'a Project, name: &str) -> Result<&'a DevServerProfile, String> {
+    project
+        .settings
+        .as_ref()
+        .and_then(|settings| settings.dev_servers.iter().find(|p| p.name.eq_ignore_ascii_case(name)))
+        .ok_or_else(|| format!("Project {} has no dev server profile {}", project.name, name))
+}
+
+/// What to run for `profile`. Variables are layered: the project's, then
+/// the profile's `.env` file, its own variables and its secrets.
+pub fn spec(project: &Project, profile: &DevServerProfile) -> Result<DevServerSpec, String> {
+    let working_dir = match &profile.cwd {
+        Some(cwd) => Path::new(&project.path).join(cwd).to_string_lossy().to_string(),
+        None => project.path.clone(),
+    };
+    let ready_pattern = profile
+        .ready_pattern
+        .as_deref()
+        .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid ready pattern for {}: {}", profile.name, e)))
+        .transpose()?;
+
+    let mut env = project_env(project);
+    if let Some(env_file) = &profile.env_file {
+        env.extend(read_dotenv(&Path::new(&project.path).join(env_file))?);
+    }
+    env.extend(profile.env.clone());
+    env.extend(secret_values(project, &profile.secret_env));
+    Ok(DevServerSpec {
+        command: profile.command.clone(),
+        working_dir,
+        project_id: Some(project.id.clone()),
+        profile: Some(profile.name.clone()),
+        env,
+        restart_policy: profile.restart_policy,
+        ready_pattern,
+        ready_url: profile.ready_url.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::devserver::RestartPolicy;
+    use crate::projects::types::ProjectSettings;
+
+    fn project(path: &str) -> Project {
+        Project {
+            id: "p1".to_string(),
+            name: "shop".to_string(),
+            path: path.to_string(),
+            description: None,
+            color: None,
+            created_at: "2024-01-01".to_string(),
+            last_accessed: None,
+            is_favorite: false,
+            settings: Some(ProjectSettings {
+                env: [("API_URL".to_string(), "http://localhost:4000".to_string()), ("PORT".to_string(), "3000".to_string())].into(),
+                dev_servers: vec![DevServerProfile {
+                    name: "storybook".to_string(),
+                    command: "npm run storybook".to_string(),
+                    cwd: Some("packages/ui".to_string()),
+                    env_file: Some(".env.storybook".to_string()),
+                    env: [("PORT".to_string(), "6006".to_string())].into(),
+                    secret_env: Vec::new(),
+                    ready_pattern: Some(r"Storybook \d+ started".to_string()),
+                    ready_url: Some("http://localhost:6006/index.json".to_string()),
+                    restart_policy: RestartPolicy::OnFailure { max_retries: 3 },
+                }],
+                ..ProjectSettings::default()
+            }),
+            is_archived: false,
+            is_missing: false,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_spec_from_profile() {
+        let root = std::env::temp_dir().join(format!("ninjasquad-profile-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join(".env.storybook"), "PORT=7000\nAPI_URL=http://api.test\nTHEME=dark\n").unwrap();
+        let project = project(&root.to_string_lossy());
+        let profile = find(&project, "Storybook").unwrap();
+        let spec = spec(&project, profile).unwrap();
+
+        assert_eq!(spec.working_dir, root.join("packages/ui").to_string_lossy());
+        assert_eq!(spec.profile.as_deref(), Some("storybook"));
+        assert_eq!(spec.env["PORT"], "6006");
+        assert_eq!(spec.env["API_URL"], "http://api.test");
+        assert_eq!(spec.env["THEME"], "dark");
+        assert_eq!(spec.restart_policy, RestartPolicy::OnFailure { max_retries: 3 });
+        assert!(spec.ready_pattern.unwrap().is_match("Storybook 8 started"));
+        assert_eq!(spec.ready_url.as_deref(), Some("http://localhost:6006/index.json"));
+        assert!(find(&project, "web").is_err());
+
+        std::fs::remove_file(root.join(".env.storybook")).unwrap();
+        assert!(super::spec(&project, profile).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
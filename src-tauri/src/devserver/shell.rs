@@ -0,0 +1,118 @@
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use tokio::process::{Child, Command};
+
+/// How long a stopped server gets to shut down before it is killed
+pub const STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[cfg(windows)]
+const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+
+/// The shell commands run through: `sh`, or on Windows `%COMSPEC%`, which
+/// is normally cmd.exe
+pub fn default_shell() -> String {
+    #[cfg(windows)]
+    {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+    }
+    #[cfg(not(windows))]
+    {
+        "sh".to_string()
+    }
+}
+
+/// `shell`'s file name, lowercased and without `.exe`
+fn shell_name(shell: &str) -> String {
+    // Split on both separators so Windows paths are handled on any host
+    let file_name = shell.rsplit(['/', '\\']).next().unwrap_or(shell).to_lowercase();
+    file_name.strip_suffix(".exe").unwrap_or(&file_name).to_string()
+}
+
+/// Arguments making `shell` run `command` and exit
+pub fn shell_args(shell: &str, command: &str) -> Vec<String> {
+    match shell_name(shell).as_str() {
+        "powershell" | "pwsh" => vec!["-NoLogo".to_string(), "-NoProfile".to_string(), "-Command".to_string(), command.to_string()],
+        "cmd" => vec!["/C".to_string(), command.to_string()],
+        _ => vec!["-c".to_string(), command.to_string()],
+    }
+}
+
+/// The default shell followed by the arguments to run `command`, for
+/// handing to another program such as a terminal emulator
+pub fn invocation(command: &str) -> Vec<String> {
+    let shell = default_shell();
+    let mut args = shell_args(&shell, command);
+    args.insert(0, shell);
+    args
+}
+
+/// `command` run through the default shell in a process group of its own,
+/// so stopping it reaches everything the shell started
+pub fn shell_command(command: &str) -> Command {
+    let shell = default_shell();
+    let mut cmd = Command::new(&shell);
+    #[cfg(windows)]
+    {
+        // cmd parses the rest of its command line itself, so quoting added
+        // for it would end up in the command
+        if shell_name(&shell) == "cmd" {
+            cmd.arg("/C").raw_arg(command);
+        } else {
+            cmd.args(shell_args(&shell, command));
+        }
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+    #[cfg(not(windows))]
+    cmd.args(shell_args(&shell, command));
+    #[cfg(unix)]
+    cmd.process_group(0);
+    cmd
+}
+
+/// Stop a process started by `shell_command` along with what it started:
+/// SIGTERM to its group, or on Windows `taskkill` on its tree, then killed
+/// outright after `STOP_TIMEOUT`
+pub async fn terminate(child: &mut Child) -> std::io::Result<ExitStatus> {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        let group = format!("-{}", pid);
+        let _ = Command::new("kill").args(["-TERM", &group]).output().await;
+        if let Ok(status) = tokio::time::timeout(STOP_TIMEOUT, child.wait()).await {
+            return status;
+        }
+        let _ = Command::new("kill").args(["-KILL", &group]).output().await;
+    }
+    #[cfg(windows)]
+    if let Some(pid) = child.id() {
+        let pid = pid.to_string();
+        // Console programs refuse a polite taskkill, so only wait if it worked
+        let asked = Command::new("taskkill").args(["/PID", &pid, "/T"]).output().await;
+        if asked.is_ok_and(|output| output.status.success()) {
+            if let Ok(status) = tokio::time::timeout(STOP_TIMEOUT, child.wait()).await {
+                return status;
+            }
+        }
+        let _ = Command::new("taskkill").args(["/PID", &pid, "/T", "/F"]).output().await;
+    }
+    child.kill().await?;
+    child.wait().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_args_per_shell() {
+        assert_eq!(shell_args("sh", "npm run dev"), vec!["-c", "npm run dev"]);
+        assert_eq!(shell_args("/bin/bash", "npm run dev"), vec!["-c", "npm run dev"]);
+        assert_eq!(shell_args(r"C:\Windows\System32\cmd.exe", "npm run dev"), vec!["/C", "npm run dev"]);
+        assert_eq!(shell_args("pwsh", "npm run dev"), vec!["-NoLogo", "-NoProfile", "-Command", "npm run dev"]);
+        assert_eq!(shell_args("PowerShell.EXE", "npm run dev")[3], "npm run dev");
+
+        let invocation = invocation("npm run dev");
+        assert_eq!(invocation[0], default_shell());
+        assert_eq!(invocation.last().unwrap(), "npm run dev");
+    }
+}
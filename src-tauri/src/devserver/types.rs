@@ -0,0 +1,126 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DevServerStatus {
+    Running,
+    /// Stopped from the app
+    Stopped,
+    /// Exited on its own
+    Exited,
+    /// Crashed and waiting to be started again
+    Restarting,
+}
+
+/// Whether a dev server answers on its readiness URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DevServerHealth {
+    /// Not answered yet since it started
+    Starting,
+    Healthy,
+    /// Stopped answering, or never did
+    Unhealthy,
+}
+
+/// What to do when a dev server exits on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    /// Restart after a non-zero exit, giving up after `max_retries` in a row
+    OnFailure { max_retries: u32 },
+    Always,
+}
+
+/// What to run for a dev server, kept to start it again
+#[derive(Clone, Default)]
+pub struct DevServerSpec {
+    pub command: String,
+    pub working_dir: String,
+    pub project_id: Option<String>,
+    /// Name of the project profile it was started from
+    pub profile: Option<String>,
+    pub env: HashMap<String, String>,
+    pub restart_policy: RestartPolicy,
+    /// Output line marking the server as ready; its first URL when None
+    pub ready_pattern: Option<Regex>,
+    /// Polled for the server's health while it runs
+    pub ready_url: Option<String>,
+}
+
+/// A dev server started from the app
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevServer {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub profile: Option<String>,
+    pub command: String,
+    pub working_dir: String,
+    pub pid: Option<u32>,
+    pub status: DevServerStatus,
+    /// Where it's serving, once its output says
+    pub url: Option<String>,
+    /// Its output matched the ready check
+    pub ready: bool,
+    pub ready_url: Option<String>,
+    /// From polling `ready_url`; None without one or once it's not running
+    pub health: Option<DevServerHealth>,
+    pub restart_policy: RestartPolicy,
+    /// Times it was restarted automatically
+    pub restart_count: u32,
+    /// None while running, or when killed by a signal
+    pub exit_code: Option<i32>,
+    pub started_at: String,
+    pub exited_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of a dev server's output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub stream: LogStream,
+    pub text: String,
+    pub timestamp: String,
+}
+
+/// Emitted as `dev-server-ready` when a dev server's output first passes
+/// its ready check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevServerReady {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub profile: Option<String>,
+    /// None if the server hasn't printed a URL
+    pub url: Option<String>,
+}
+
+/// Emitted as `dev-server-healthy` or `dev-server-unhealthy` when a dev
+/// server's health changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevServerHealthChange {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub profile: Option<String>,
+    pub health: DevServerHealth,
+    pub url: String,
+}
+
+/// Emitted as `dev-server-exited` when a dev server's process ends
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DevServerExit {
+    pub id: String,
+    pub project_id: Option<String>,
+    pub exit_code: Option<i32>,
+    /// True when stopped from the app rather than exiting on its own
+    pub stopped: bool,
+}
@@ -0,0 +1,69 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+use crate::tmux::capture::strip_ansi;
+
+/// A full URL on a local or named host, e.g. Vite's `Local: http://localhost:5173/`
+fn url_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"https?://(?:\[[0-9a-fA-F:]+\]|[\w.-]+):\d{2,5}[^\s'\x22<>)]*").unwrap())
+}
+
+/// A host and port without a scheme, e.g. `listening on 0.0.0.0:8000`
+fn address_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| Regex::new(r"\b(localhost|127\.0\.0\.1|0\.0\.0\.0):(\d{2,5})\b").unwrap())
+}
+
+/// Just a port, e.g. Express's `Server listening on port 3000`
+fn port_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)\b(?:listening|running|started|serving|available)\b.*\bport\s*:?\s*(\d{2,5})\b").unwrap()
+    })
+}
+
+/// The address a dev server says it's serving on, if `line` mentions one.
+/// Wildcard hosts are replaced with localhost so the URL can be opened.
+pub fn detect_url(line: &str) -> Option<String> {
+    let line = strip_ansi(line);
+    if let Some(found) = url_pattern().find(&line) {
+        let url = found.as_str().trim_end_matches(['.', ',', ';']);
+        return Some(url.replace("://0.0.0.0:", "://localhost:").replace("://[::]:", "://localhost:"));
+    }
+    if let Some(captures) = address_pattern().captures(&line) {
+        let host = match &captures[1] {
+            "0.0.0.0" => "localhost",
+            host => host,
+        };
+        return Some(format!("http://{}:{}", host, &captures[2]));
+    }
+    port_pattern()
+        .captures(&line)
+        .map(|captures| format!("http://localhost:{}", &captures[1]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_url_from_common_dev_servers() {
+        assert_eq!(
+            detect_url("  \x1b[32m➜\x1b[39m  \x1b[1mLocal\x1b[22m:   \x1b[36mhttp://localhost:\x1b[1m5173\x1b[22m/\x1b[39m"),
+            Some("http://localhost:5173/".to_string())
+        );
+        assert_eq!(
+            detect_url("   - Local:        http://127.0.0.1:3000"),
+            Some("http://127.0.0.1:3000".to_string())
+        );
+        assert_eq!(
+            detect_url("Uvicorn running on http://0.0.0.0:8000 (Press CTRL+C to quit)"),
+            Some("http://localhost:8000".to_string())
+        );
+        assert_eq!(detect_url("Listening at [::]:4000 and 0.0.0.0:4000"), Some("http://localhost:4000".to_string()));
+        assert_eq!(detect_url("Server listening on port 3001"), Some("http://localhost:3001".to_string()));
+        assert_eq!(detect_url("Compiled successfully in 1.2s"), None);
+        assert_eq!(detect_url("See https://vitejs.dev/config for options"), None);
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use crate::database::activity::AgentActivity;
+
+/// Render the plain-text body of a daily digest, grouped by project
+pub fn render_digest(activity: &[AgentActivity]) -> String {
+    let mut by_project: BTreeMap<&str, Vec<&AgentActivity>> = BTreeMap::new();
+    for entry in activity {
+        by_project.entry(entry.project_name.as_str()).or_default().push(entry);
+    }
+
+    let mut body = format!(
+        "SensAI daily digest - {} tasks across {} projects\n",
+        activity.len(),
+        by_project.len()
+    );
+
+    for (project, entries) in &by_project {
+        let succeeded = entries.iter().filter(|e| e.success).count();
+        let failed = entries.len() - succeeded;
+
+        body.push_str(&format!(
+            "\n{} ({} completed, {} failed)\n",
+            project, succeeded, failed
+        ));
+        for entry in entries {
+            body.push_str(&format!(
+                "  {} {}\n",
+                if entry.success { "[ok]  " } else { "[fail]" },
+                entry.summary.lines().next().unwrap_or_default()
+            ));
+        }
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn activity(project: &str, summary: &str, success: bool) -> AgentActivity {
+        AgentActivity {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_name: project.to_string(),
+            session_id: None,
+            summary: summary.to_string(),
+            success,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_render_digest_groups_by_project() {
+        let body = render_digest(&[
+            activity("beta", "Fix login", true),
+            activity("alpha", "Add tests\nwith details", true),
+            activity("beta", "Deploy", false),
+        ]);
+
+        assert!(body.starts_with("SensAI daily digest - 3 tasks across 2 projects"));
+        assert!(body.find("alpha (1 completed, 0 failed)").unwrap() < body.find("beta (1 completed, 1 failed)").unwrap());
+        assert!(body.contains("[fail] Deploy"));
+        assert!(!body.contains("with details"));
+    }
+}
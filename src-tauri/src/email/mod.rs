@@ -0,0 +1,394 @@
+pub mod digest;
+pub mod types;
+
+pub use types::*;
+
+use std::sync::Arc;
+use anyhow::Result;
+use chrono::{Local, Timelike, Utc};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::database::{self, activity, settings, DatabaseManager};
+
+pub const EMAIL_CONFIG_KEY: &str = "email_config";
+#[cfg(feature = "keychain")]
+const KEYCHAIN_ACCOUNT: &str = "email:smtp_password";
+const LAST_DIGEST_KEY: &str = "email_last_digest_at";
+const ACTIVITY_RETENTION_DAYS: i64 = 30;
+
+/// SMTP notifier for task results and the optional daily digest
+#[derive(Clone)]
+pub struct EmailNotifier {
+    config: Arc<RwLock<Option<EmailConfig>>>,
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+    digest_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl Default for EmailNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmailNotifier {
+    pub fn new() -> Self {
+        Self {
+            config: Arc::new(RwLock::new(None)),
+            app_handle: Arc::new(RwLock::new(None)),
+            digest_task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Load the stored config and start the digest scheduler
+    pub async fn start(&self, app_handle: &AppHandle) -> Result<()> {
+        *self.app_handle.write().await = Some(app_handle.clone());
+
+        let db = app_handle.state::<DatabaseManager>();
+        let stored: Option<serde_json::Value> = db.with_connection(|conn| settings::get_json(conn, EMAIL_CONFIG_KEY))?;
+        let config = match stored {
+            Some(mut stored) => {
+                // Configs saved before the keychain held the password inline
+                if let Some(password) = take_inline_password(&mut stored) {
+                    store_password(Some(&password)).map_err(anyhow::Error::msg)?;
+                    db.with_connection(|conn| settings::set_json(conn, EMAIL_CONFIG_KEY, &stored))?;
+                    println!("[Email] Moved the SMTP password to the keychain");
+                }
+                Some(serde_json::from_value(stored)?)
+            }
+            None => None,
+        };
+        *self.config.write().await = config;
+
+        let notifier = self.clone();
+        let mut task = self.digest_task.lock().await;
+        if let Some(existing) = task.take() {
+            existing.abort();
+        }
+        *task = Some(tokio::spawn(async move {
+            notifier.run_digest_scheduler().await;
+        }));
+
+        println!("[Email] Email notifier started");
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(task) = self.digest_task.lock().await.take() {
+            task.abort();
+        }
+    }
+
+    pub async fn get_config(&self) -> Option<EmailConfig> {
+        self.config.read().await.clone()
+    }
+
+    /// The config for the settings UI, without the password
+    pub async fn get_config_view(&self) -> Result<Option<EmailConfigView>> {
+        let Some(config) = self.get_config().await else { return Ok(None) };
+        let password_set = load_password().map_err(anyhow::Error::msg)?.is_some();
+        Ok(Some(EmailConfigView { config, password_set }))
+    }
+
+    /// Save `config`; `password` replaces the stored one when given, and an
+    /// empty one removes it
+    pub async fn update_config(&self, config: EmailConfig, password: Option<String>) -> Result<()> {
+        if let Some(password) = password {
+            store_password(Some(password.as_str()).filter(|p| !p.is_empty())).map_err(anyhow::Error::msg)?;
+        }
+        self.with_db(|conn| settings::set_json(conn, EMAIL_CONFIG_KEY, &config)).await?;
+        *self.config.write().await = Some(config);
+        Ok(())
+    }
+
+    /// Record a task result for the digest and email it immediately if configured to
+    pub async fn notify_task(&self, notification: TaskNotification) -> Result<()> {
+        let entry = activity::AgentActivity {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_name: notification.project_name.clone(),
+            session_id: notification.session_id.clone(),
+            summary: notification.summary.clone(),
+            success: notification.success,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        self.with_db(|conn| activity::record_activity(conn, &entry)).await?;
+
+        let Some(config) = self.get_config().await else { return Ok(()) };
+        let wanted = if notification.success { config.notify_on_completion } else { config.notify_on_failure };
+        if !config.enabled || !wanted {
+            return Ok(());
+        }
+
+        let (subject, body) = task_email(&notification);
+        self.send(&config, &subject, body).await
+    }
+
+    /// Send a digest of all activity since the previous digest.
+    /// Returns the number of activity entries included.
+    pub async fn send_digest(&self) -> Result<usize> {
+        let config = self.get_config().await
+            .filter(|c| c.enabled)
+            .ok_or_else(|| anyhow::anyhow!("Email notifications are not enabled"))?;
+
+        let since = self.with_db(|conn| settings::get_setting(conn, LAST_DIGEST_KEY)).await?
+            .unwrap_or_else(|| (Utc::now() - chrono::Duration::days(1)).to_rfc3339());
+        let entries = self.with_db(|conn| activity::list_activity_since(conn, &since)).await?;
+
+        if !entries.is_empty() {
+            let subject = format!("[SensAI] Daily digest for {}", Local::now().format("%Y-%m-%d"));
+            self.send(&config, &subject, digest::render_digest(&entries)).await?;
+        }
+
+        let now = Utc::now();
+        let cutoff = (now - chrono::Duration::days(ACTIVITY_RETENTION_DAYS)).to_rfc3339();
+        self.with_db(|conn| {
+            settings::set_setting(conn, LAST_DIGEST_KEY, &now.to_rfc3339())?;
+            activity::prune_activity(conn, &cutoff)
+        }).await?;
+
+        println!("[Email] Digest processed with {} entries", entries.len());
+        Ok(entries.len())
+    }
+
+    pub async fn send_test(&self) -> Result<()> {
+        let config = self.get_config().await
+            .ok_or_else(|| anyhow::anyhow!("Email is not configured"))?;
+
+        self.send(
+            &config,
+            "[SensAI] Test email",
+            "SensAI email notifications are configured correctly.".to_string(),
+        ).await
+    }
+
+    async fn run_digest_scheduler(&self) {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            let Some(config) = self.get_config().await else { continue };
+            if !config.enabled || !config.digest_enabled || Local::now().hour() != config.digest_hour {
+                continue;
+            }
+
+            // Only one digest per local day
+            let last = self.with_db(|conn| settings::get_setting(conn, LAST_DIGEST_KEY)).await.ok().flatten();
+            let sent_today = last
+                .and_then(|ts| chrono::DateTime::parse_from_rfc3339(&ts).ok())
+                .map(|ts| ts.with_timezone(&Local).date_naive() == Local::now().date_naive())
+                .unwrap_or(false);
+            if sent_today {
+                continue;
+            }
+
+            if let Err(e) = self.send_digest().await {
+                println!("[Email] Failed to send digest: {}", e);
+            }
+        }
+    }
+
+    async fn send(&self, config: &EmailConfig, subject: &str, body: String) -> Result<()> {
+        let message = build_message(config, subject, body)?;
+        let password = load_password().map_err(anyhow::Error::msg)?;
+
+        let mut transport = if config.implicit_tls {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+        }
+        .port(config.smtp_port);
+        if let Some(credentials) = credentials(&config.username, password) {
+            transport = transport.credentials(credentials);
+        }
+        let transport = transport.build();
+
+        transport.send(message).await
+            .map_err(|e| anyhow::anyhow!("Failed to send email: {}", e))?;
+
+        println!("[Email] Sent '{}' to {} recipients", subject, config.to.len());
+        Ok(())
+    }
+
+    async fn with_db<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<R>,
+    {
+        database::with_app_db(&self.app_handle, "Email notifier", f).await
+    }
+}
+
+/// SMTP login, only with both a username and a password; relays that
+/// don't offer AUTH reject any attempt at it
+fn credentials(username: &str, password: Option<String>) -> Option<Credentials> {
+    match password {
+        Some(password) if !username.trim().is_empty() => Some(Credentials::new(username.to_string(), password)),
+        _ => None,
+    }
+}
+
+/// Subject and body of the email for a task result
+pub fn task_email(notification: &TaskNotification) -> (String, String) {
+    let subject = format!(
+        "[SensAI] {} {} in {}",
+        if notification.success { "✅" } else { "❌" },
+        if notification.success { "Task completed" } else { "Task failed" },
+        notification.project_name
+    );
+    let mut body = format!("Project: {}\n", notification.project_name);
+    if let Some(session_id) = &notification.session_id {
+        body.push_str(&format!("Session: {}\n", session_id));
+    }
+    body.push_str(&format!("\n{}\n", notification.summary));
+    (subject, body)
+}
+
+/// A plain-text message from the configured sender to every recipient
+pub fn build_message(config: &EmailConfig, subject: &str, body: String) -> Result<Message> {
+    if config.to.is_empty() {
+        return Err(anyhow::anyhow!("No email recipients configured"));
+    }
+
+    let mut builder = Message::builder()
+        .from(config.from.parse()?)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN);
+    for to in &config.to {
+        builder = builder.to(to.parse()?);
+    }
+    Ok(builder.body(body)?)
+}
+
+/// Remove the password a stored config held before it moved to the
+/// keychain, returning it when there was one
+fn take_inline_password(stored: &mut serde_json::Value) -> Option<String> {
+    let password = stored.as_object_mut()?.remove("password")?;
+    password.as_str().filter(|p| !p.is_empty()).map(str::to_string)
+}
+
+#[cfg(feature = "keychain")]
+fn password_entry() -> Result<keyring::Entry, String> {
+    keyring::Entry::new(crate::projects::env::KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT)
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))
+}
+
+/// Store the SMTP password in the keychain, or remove it with `None`
+#[cfg(feature = "keychain")]
+fn store_password(password: Option<&str>) -> Result<(), String> {
+    let entry = password_entry()?;
+    match password {
+        Some(password) => entry
+            .set_password(password)
+            .map_err(|e| format!("Failed to store SMTP password in keychain: {}", e)),
+        None => match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Failed to delete SMTP password from keychain: {}", e)),
+        },
+    }
+}
+
+#[cfg(feature = "keychain")]
+fn load_password() -> Result<Option<String>, String> {
+    match password_entry()?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read SMTP password from keychain: {}", e)),
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+fn store_password(password: Option<&str>) -> Result<(), String> {
+    match password {
+        Some(_) => Err("Storing the SMTP password needs a build with the keychain feature".to_string()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+fn load_password() -> Result<Option<String>, String> {
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> EmailConfig {
+        serde_json::from_value(json!({
+            "enabled": true,
+            "smtp_host": "smtp.example.com",
+            "smtp_port": 587,
+            "username": "bot",
+            "from": "SensAI <bot@example.com>",
+            "to": ["dev@example.com", "ops@example.com"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_config_round_trip_keeps_password_out() {
+        let mut stored = json!({
+            "enabled": true,
+            "smtp_host": "smtp.example.com",
+            "smtp_port": 465,
+            "username": "bot",
+            "password": "hunter2",
+            "from": "bot@example.com",
+            "to": ["dev@example.com"],
+            "implicit_tls": true
+        });
+        assert_eq!(take_inline_password(&mut stored).as_deref(), Some("hunter2"));
+        assert_eq!(take_inline_password(&mut stored), None);
+
+        let config: EmailConfig = serde_json::from_value(stored).unwrap();
+        assert!(config.implicit_tls && config.notify_on_completion && !config.digest_enabled);
+        assert_eq!(config.digest_hour, 18);
+
+        let saved = serde_json::to_value(&config).unwrap();
+        assert!(saved.get("password").is_none());
+        let reloaded: EmailConfig = serde_json::from_value(saved).unwrap();
+        assert_eq!((reloaded.smtp_port, reloaded.to.clone()), (465, vec!["dev@example.com".to_string()]));
+
+        let view = serde_json::to_value(EmailConfigView { config: reloaded, password_set: true }).unwrap();
+        assert_eq!((view["password_set"].as_bool(), view["smtp_host"].as_str()), (Some(true), Some("smtp.example.com")));
+        assert!(view.get("password").is_none());
+    }
+
+    #[test]
+    fn test_build_message() {
+        let (subject, body) = task_email(&TaskNotification {
+            project_name: "api".to_string(),
+            session_id: Some("s-1".to_string()),
+            summary: "Fixed login".to_string(),
+            success: false,
+        });
+        assert_eq!(subject, "[SensAI] ❌ Task failed in api");
+        assert_eq!(body, "Project: api\nSession: s-1\n\nFixed login\n");
+
+        let message = build_message(&config(), "Digest", "Hello".to_string()).unwrap();
+        let formatted = String::from_utf8(message.formatted()).unwrap();
+        assert!(formatted.contains("From: SensAI <bot@example.com>"));
+        assert!(formatted.contains("To: dev@example.com, ops@example.com"));
+        assert!(formatted.contains("Subject: Digest"));
+        assert!(formatted.ends_with("Hello"));
+
+        let mut no_recipients = config();
+        no_recipients.to.clear();
+        assert!(build_message(&no_recipients, "Digest", String::new()).is_err());
+        let mut bad_sender = config();
+        bad_sender.from = "not an address".to_string();
+        assert!(build_message(&bad_sender, "Digest", String::new()).is_err());
+    }
+
+    #[test]
+    fn test_credentials_need_username_and_password() {
+        assert!(credentials("bot", Some("hunter2".to_string())) == Some(Credentials::new("bot".to_string(), "hunter2".to_string())));
+        assert!(credentials("bot", None).is_none());
+        assert!(credentials(" ", Some("hunter2".to_string())).is_none());
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+/// SMTP notifier configuration, stored in the settings table under
+/// `email_config`. The password is kept in the OS keychain instead.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub username: String,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Use implicit TLS instead of STARTTLS
+    #[serde(default)]
+    pub implicit_tls: bool,
+    #[serde(default = "default_true")]
+    pub notify_on_completion: bool,
+    #[serde(default = "default_true")]
+    pub notify_on_failure: bool,
+    #[serde(default)]
+    pub digest_enabled: bool,
+    /// Local hour (0-23) at which the daily digest is sent
+    #[serde(default = "default_digest_hour")]
+    pub digest_hour: u32,
+}
+
+/// The config as shown in settings, with whether a password is stored
+/// rather than the password itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailConfigView {
+    #[serde(flatten)]
+    pub config: EmailConfig,
+    pub password_set: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_digest_hour() -> u32 {
+    18
+}
+
+/// A task completion or failure reported by the frontend or an agent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskNotification {
+    pub project_name: String,
+    pub session_id: Option<String>,
+    pub summary: String,
+    pub success: bool,
+}
@@ -0,0 +1,1337 @@
+use git2::build::CheckoutBuilder;
+use git2::{
+    ApplyLocation, BlameOptions, BranchType, Cred, CredentialType, Delta, Diff, DiffFindOptions, DiffFormat,
+    DiffOptions, FetchOptions, Patch, PushOptions, RemoteCallbacks, Repository, Sort, StashApplyOptions, StashFlags,
+    Status, StatusOptions, Worktree, WorktreeAddOptions, WorktreeLockStatus, WorktreePruneOptions,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::types::{
+    BlameHunk, BranchInfo, CommitInfo, ConflictFile, ConflictHunk, ConflictKind, ConflictReport, DiffHunk, DiffLine,
+    FileChange, FileDiff, FileStat, FileStatus, GitStatus, HunkSelection, LineKind, MergeOutcome, StashEntry,
+    TransferProgress, WorktreeInfo,
+};
+
+/// Folder inside the git directory that holds worktrees created by the app
+pub const WORKTREE_DIR: &str = "ninjasquad-worktrees";
+
+/// Prefix of the branches created for new worktrees
+pub const WORKTREE_BRANCH_PREFIX: &str = "ninjasquad/";
+
+/// Minimum gap between progress reports of a push or fetch
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Repositories opened through libgit2, cached by the working directory
+/// they were looked up from. A working directory below the repository
+/// root resolves to the enclosing repository.
+pub struct GitManager {
+    repos: Mutex<HashMap<PathBuf, Arc<Mutex<Repository>>>>,
+}
+
+impl Default for GitManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitManager {
+    pub fn new() -> Self {
+        Self {
+            repos: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn open(&self, working_dir: &str) -> Result<Arc<Mutex<Repository>>, String> {
+        let key = PathBuf::from(working_dir.trim_end_matches('/'));
+        let mut repos = self.repos.lock().unwrap();
+        if let Some(repo) = repos.get(&key) {
+            return Ok(repo.clone());
+        }
+
+        let repo = Repository::discover(&key)
+            .map_err(|e| format!("Not a git repository: {} ({})", working_dir, e.message()))?;
+        let repo = Arc::new(Mutex::new(repo));
+        repos.insert(key, repo.clone());
+        Ok(repo)
+    }
+
+    /// Run `f` against the repository containing `working_dir`
+    pub fn with_repo<T>(
+        &self,
+        working_dir: &str,
+        f: impl FnOnce(&Repository) -> Result<T, git2::Error>,
+    ) -> Result<T, String> {
+        self.with_repo_mut(working_dir, |repo| f(repo))
+    }
+
+    /// `with_repo` for the operations libgit2 needs exclusive access for
+    pub fn with_repo_mut<T>(
+        &self,
+        working_dir: &str,
+        f: impl FnOnce(&mut Repository) -> Result<T, git2::Error>,
+    ) -> Result<T, String> {
+        let repo = self.open(working_dir)?;
+        let mut repo = repo.lock().unwrap();
+        f(&mut repo).map_err(|e| format!("Git error in {}: {}", working_dir, e.message()))
+    }
+
+    /// Drop the cached repository for `working_dir`, e.g. after it moved
+    pub fn forget(&self, working_dir: &str) {
+        self.repos.lock().unwrap().remove(Path::new(working_dir.trim_end_matches('/')));
+    }
+
+    /// Unstaged changes as patch text, like `git diff [-- path]`
+    pub fn diff(&self, working_dir: &str, path: Option<&str>) -> Result<String, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut options = DiffOptions::new();
+            if let Some(path) = path {
+                options.pathspec(repo_path(repo, working_dir, path));
+            }
+            let diff = repo.diff_index_to_workdir(None, Some(&mut options))?;
+            patch_text(&diff)
+        })
+    }
+
+    /// Staged changes as patch text, like `git diff --cached`
+    pub fn staged_diff(&self, working_dir: &str) -> Result<String, String> {
+        self.with_repo(working_dir, |repo| {
+            let head_tree = head_tree(repo)?;
+            patch_text(&repo.diff_tree_to_index(head_tree.as_ref(), None, None)?)
+        })
+    }
+
+    /// Stage `paths`, relative to `working_dir`. Deleted files are removed
+    /// from the index.
+    pub fn stage(&self, working_dir: &str, paths: &[String]) -> Result<(), String> {
+        self.with_repo(working_dir, |repo| {
+            let mut index = repo.index()?;
+            for path in paths {
+                let relative = repo_path(repo, working_dir, path);
+                if Path::new(working_dir).join(path).exists() {
+                    index.add_path(Path::new(&relative))?;
+                } else {
+                    index.remove_path(Path::new(&relative))?;
+                }
+            }
+            index.write()
+        })
+    }
+
+    /// Commit the index on top of HEAD as the user configured in git
+    pub fn commit(&self, working_dir: &str, message: &str) -> Result<CommitInfo, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut index = repo.index()?;
+            let tree = repo.find_tree(index.write_tree()?)?;
+            let parent = match repo.head() {
+                Ok(head) => Some(head.peel_to_commit()?),
+                Err(e) if e.code() == git2::ErrorCode::UnbornBranch => None,
+                Err(e) => return Err(e),
+            };
+            if parent.as_ref().map(|p| p.tree_id()) == Some(tree.id()) {
+                return Err(git2::Error::from_str("Nothing to commit"));
+            }
+
+            let signature = repo.signature()?;
+            let parents: Vec<_> = parent.iter().collect();
+            let id = repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &parents)?;
+            Ok(commit_info(&repo.find_commit(id)?))
+        })
+    }
+
+    /// Staged, unstaged, untracked and conflicted paths
+    pub fn status(&self, working_dir: &str) -> Result<Vec<FileStatus>, String> {
+        self.with_repo(working_dir, file_statuses)
+    }
+
+    /// Changes from `from_ref` to `to_ref`, or to the working tree when
+    /// `to_ref` is None, parsed into hunks
+    pub fn diff_refs(
+        &self,
+        working_dir: &str,
+        from_ref: &str,
+        to_ref: Option<&str>,
+        path: Option<&str>,
+    ) -> Result<Vec<FileDiff>, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut options = DiffOptions::new();
+            if let Some(path) = path {
+                options.pathspec(repo_path(repo, working_dir, path));
+            }
+            let from = repo.revparse_single(from_ref)?.peel_to_tree()?;
+            let mut diff = match to_ref {
+                Some(to_ref) => {
+                    let to = repo.revparse_single(to_ref)?.peel_to_tree()?;
+                    repo.diff_tree_to_tree(Some(&from), Some(&to), Some(&mut options))?
+                }
+                None => {
+                    options
+                        .include_untracked(true)
+                        .recurse_untracked_dirs(true)
+                        .show_untracked_content(true);
+                    repo.diff_tree_to_workdir_with_index(Some(&from), Some(&mut options))?
+                }
+            };
+            diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+            file_diffs(&diff)
+        })
+    }
+
+    /// `path`'s staged (HEAD to index) or unstaged (index to working tree)
+    /// hunks, numbered the way `stage_hunks` and `unstage_hunks` expect
+    pub fn file_hunks(&self, working_dir: &str, path: &str, staged: bool) -> Result<Option<FileDiff>, String> {
+        self.with_repo(working_dir, |repo| staging_diff(repo, working_dir, path, staged))
+    }
+
+    /// Stage the selected unstaged hunks or lines of `path`
+    pub fn stage_hunks(&self, working_dir: &str, path: &str, selection: &[HunkSelection]) -> Result<(), String> {
+        self.apply_to_index(working_dir, path, selection, false)
+    }
+
+    /// Move the selected staged hunks or lines of `path` back out of the index
+    pub fn unstage_hunks(&self, working_dir: &str, path: &str, selection: &[HunkSelection]) -> Result<(), String> {
+        self.apply_to_index(working_dir, path, selection, true)
+    }
+
+    fn apply_to_index(&self, working_dir: &str, path: &str, selection: &[HunkSelection], staged: bool) -> Result<(), String> {
+        let file = self
+            .file_hunks(working_dir, path, staged)?
+            .ok_or_else(|| format!("{} has no {} changes", path, if staged { "staged" } else { "unstaged" }))?;
+        let Some(patch) = super::patch::partial_patch(&file, selection, staged)? else {
+            return Ok(());
+        };
+        self.with_repo(working_dir, |repo| {
+            let diff = Diff::from_buffer(patch.as_bytes())?;
+            repo.apply(&diff, ApplyLocation::Index, None)
+        })
+    }
+
+    /// Files `head` changes since it forked from `base`, like
+    /// `git diff base...head`
+    pub fn branch_changes(&self, working_dir: &str, base: &str, head: &str) -> Result<Vec<FileStat>, String> {
+        self.with_repo(working_dir, |repo| {
+            let head = repo.revparse_single(head)?.peel_to_commit()?;
+            let base = repo.revparse_single(base)?.peel_to_commit()?;
+            let fork = repo.find_commit(repo.merge_base(base.id(), head.id())?)?;
+            let mut diff = repo.diff_tree_to_tree(Some(&fork.tree()?), Some(&head.tree()?), None)?;
+            diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+            Ok(file_diffs(&diff)?
+                .into_iter()
+                .map(|file| {
+                    let lines = file.hunks.iter().flat_map(|hunk| &hunk.lines);
+                    let (additions, deletions) = lines.fold((0, 0), |(add, del), line| match line.kind {
+                        LineKind::Addition => (add + 1, del),
+                        LineKind::Deletion => (add, del + 1),
+                        LineKind::Context => (add, del),
+                    });
+                    FileStat { path: file.path, change: file.change, additions, deletions }
+                })
+                .collect())
+        })
+    }
+
+    /// Which of `paths`, relative to `working_dir`, .gitignore rules exclude
+    pub fn ignored(&self, working_dir: &str, paths: &[String]) -> Result<Vec<String>, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut ignored = Vec::new();
+            for path in paths {
+                if repo.is_path_ignored(repo_path(repo, working_dir, path))? {
+                    ignored.push(path.clone());
+                }
+            }
+            Ok(ignored)
+        })
+    }
+
+    pub fn remote_url(&self, working_dir: &str, remote: &str) -> Result<Option<String>, String> {
+        self.with_repo(working_dir, |repo| Ok(repo.find_remote(remote)?.url().map(str::to_string)))
+    }
+
+    /// Newest-first commits, limited to those that changed `path` if given
+    pub fn log(&self, working_dir: &str, path: Option<&str>, limit: usize) -> Result<Vec<CommitInfo>, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut walk = repo.revwalk()?;
+            match walk.push_head() {
+                Ok(()) => {}
+                Err(e) if e.code() == git2::ErrorCode::UnbornBranch => return Ok(Vec::new()),
+                Err(e) => return Err(e),
+            }
+            walk.set_sorting(Sort::TIME)?;
+
+            let path = path.map(|p| PathBuf::from(repo_path(repo, working_dir, p)));
+            let mut commits = Vec::new();
+            for oid in walk {
+                if commits.len() >= limit {
+                    break;
+                }
+                let commit = repo.find_commit(oid?)?;
+                if let Some(path) = &path {
+                    if !touches(&commit, path)? {
+                        continue;
+                    }
+                }
+                commits.push(commit_info(&commit));
+            }
+            Ok(commits)
+        })
+    }
+
+    /// Who last changed each line of `path` as it is in the working tree
+    pub fn blame(&self, working_dir: &str, path: &str) -> Result<Vec<BlameHunk>, String> {
+        self.with_repo(working_dir, |repo| {
+            let relative = PathBuf::from(repo_path(repo, working_dir, path));
+            let workdir = repo.workdir().ok_or_else(|| git2::Error::from_str("Repository has no working tree"))?;
+            let contents = std::fs::read(workdir.join(&relative)).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            let text = String::from_utf8_lossy(&contents);
+            let lines: Vec<&str> = text.lines().collect();
+
+            let committed = repo.blame_file(&relative, Some(&mut BlameOptions::new()))?;
+            let blame = committed.blame_buffer(&contents)?;
+
+            let mut commits: HashMap<git2::Oid, CommitInfo> = HashMap::new();
+            let mut hunks = Vec::new();
+            for hunk in blame.iter() {
+                let id = hunk.final_commit_id();
+                let commit = if id.is_zero() {
+                    None
+                } else if let Some(info) = commits.get(&id) {
+                    Some(info.clone())
+                } else {
+                    let info = commit_info(&repo.find_commit(id)?);
+                    commits.insert(id, info.clone());
+                    Some(info)
+                };
+
+                let start = hunk.final_start_line();
+                hunks.push(BlameHunk {
+                    commit,
+                    start_line: start,
+                    lines: hunk_lines(&lines, start, hunk.lines_in_hunk()),
+                });
+            }
+            Ok(hunks)
+        })
+    }
+
+    /// Status grouped into staged, unstaged, untracked and conflicted
+    /// files, each with added and deleted line counts
+    pub fn status_summary(&self, working_dir: &str) -> Result<GitStatus, String> {
+        self.with_repo(working_dir, |repo| {
+            let head_tree = head_tree(repo)?;
+            let staged_stats = line_stats(&repo.diff_tree_to_index(head_tree.as_ref(), None, None)?)?;
+
+            let mut options = DiffOptions::new();
+            options
+                .include_untracked(true)
+                .recurse_untracked_dirs(true)
+                .show_untracked_content(true);
+            let unstaged_stats = line_stats(&repo.diff_index_to_workdir(None, Some(&mut options))?)?;
+
+            Ok(group_status(branch_name(repo)?, file_statuses(repo)?, &staged_stats, &unstaged_stats))
+        })
+    }
+
+    /// The checked-out branch, `None` on a detached or unborn HEAD
+    pub fn current_branch(&self, working_dir: &str) -> Result<Option<String>, String> {
+        self.with_repo(working_dir, branch_name)
+    }
+
+    pub fn branches(&self, working_dir: &str) -> Result<Vec<BranchInfo>, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut branches = Vec::new();
+            for entry in repo.branches(Some(BranchType::Local))? {
+                let (branch, _) = entry?;
+                let Some(name) = branch.name()?.map(str::to_string) else { continue };
+
+                let commit = branch.get().peel_to_commit().ok();
+                let upstream = branch.upstream().ok();
+                let (ahead, behind) = match (&commit, upstream.as_ref().and_then(|u| u.get().target())) {
+                    (Some(commit), Some(upstream)) => repo.graph_ahead_behind(commit.id(), upstream)?,
+                    _ => (0, 0),
+                };
+
+                branches.push(BranchInfo {
+                    name,
+                    is_head: branch.is_head(),
+                    upstream: upstream.and_then(|u| u.name().ok().flatten().map(str::to_string)),
+                    ahead,
+                    behind,
+                    commit_id: commit.as_ref().map(|c| c.id().to_string()),
+                    commit_summary: commit.as_ref().and_then(|c| c.summary().map(str::to_string)),
+                });
+            }
+            Ok(branches)
+        })
+    }
+
+    /// Create a branch at HEAD, optionally switching to it
+    pub fn create_branch(&self, working_dir: &str, name: &str, checkout: bool) -> Result<(), String> {
+        self.with_repo(working_dir, |repo| {
+            let commit = repo.head()?.peel_to_commit()?;
+            let branch = repo.branch(name, &commit, false)?;
+            if checkout {
+                let refname = branch.get().name().ok_or_else(|| git2::Error::from_str("Invalid branch name"))?;
+                repo.set_head(refname)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Switch to a local branch. Fails rather than overwrite local changes.
+    pub fn checkout_branch(&self, working_dir: &str, name: &str) -> Result<(), String> {
+        self.with_repo(working_dir, |repo| {
+            let branch = repo.find_branch(name, BranchType::Local)?;
+            let reference = branch.get();
+            let refname = reference.name().ok_or_else(|| git2::Error::from_str("Invalid branch name"))?;
+            let tree = reference.peel_to_tree()?;
+
+            repo.checkout_tree(tree.as_object(), Some(CheckoutBuilder::new().safe()))?;
+            repo.set_head(refname)
+        })
+    }
+
+    /// Check out a new worktree called `name` on `branch`, which is created
+    /// from HEAD if needed and defaults to `ninjasquad/<name>`
+    pub fn create_worktree(&self, working_dir: &str, name: &str, branch: Option<&str>) -> Result<WorktreeInfo, String> {
+        self.with_repo(working_dir, |repo| {
+            let branch_name = branch
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{}{}", WORKTREE_BRANCH_PREFIX, name));
+            let branch = match repo.find_branch(&branch_name, BranchType::Local) {
+                Ok(branch) => branch,
+                Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                    let head = repo.head()?.peel_to_commit()?;
+                    repo.branch(&branch_name, &head, false)?
+                }
+                Err(e) => return Err(e),
+            };
+            let reference = branch.into_reference();
+
+            let path = repo.commondir().join(WORKTREE_DIR).join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|e| git2::Error::from_str(&e.to_string()))?;
+            }
+            let mut options = WorktreeAddOptions::new();
+            options.reference(Some(&reference));
+            let worktree = repo.worktree(name, &path, Some(&options))?;
+            worktree_info(&worktree)
+        })
+    }
+
+    pub fn list_worktrees(&self, working_dir: &str) -> Result<Vec<WorktreeInfo>, String> {
+        self.with_repo(working_dir, |repo| {
+            let mut worktrees = Vec::new();
+            for name in repo.worktrees()?.iter().flatten() {
+                worktrees.push(worktree_info(&repo.find_worktree(name)?)?);
+            }
+            Ok(worktrees)
+        })
+    }
+
+    /// Delete a worktree's folder and metadata; its branch is kept. Without
+    /// `force` a worktree with uncommitted changes is left alone.
+    pub fn remove_worktree(&self, working_dir: &str, name: &str, force: bool) -> Result<(), String> {
+        let path = self.with_repo(working_dir, |repo| {
+            let worktree = repo.find_worktree(name)?;
+            if !force && worktree.validate().is_ok() {
+                let checkout = Repository::open_from_worktree(&worktree)?;
+                let mut options = StatusOptions::new();
+                options.include_untracked(true);
+                if !checkout.statuses(Some(&mut options))?.is_empty() {
+                    return Err(git2::Error::from_str(&format!("Worktree {} has uncommitted changes", name)));
+                }
+            }
+            worktree.prune(Some(WorktreePruneOptions::new().valid(true).locked(force).working_tree(true)))?;
+            Ok(worktree.path().to_string_lossy().to_string())
+        })?;
+        self.forget(&path);
+        Ok(())
+    }
+
+    /// Remove the worktree checked out at `dir` if the app created it.
+    /// Returns whether there was one to remove.
+    pub fn remove_worktree_at(&self, dir: &str) -> Result<bool, String> {
+        let managed = self.with_repo(dir, |repo| {
+            let Some(workdir) = repo.workdir().filter(|_| repo.is_worktree()) else {
+                return Ok(None);
+            };
+            let main = repo.commondir().to_path_buf();
+            Ok(managed_worktree_name(&main, workdir).map(|name| (main, name)))
+        })?;
+
+        let Some((common_dir, name)) = managed else {
+            return Ok(false);
+        };
+        self.forget(dir);
+        self.remove_worktree(&common_dir.to_string_lossy(), &name, false)?;
+        Ok(true)
+    }
+
+    /// Push `branch` (default: the current one) to `remote`, setting it as
+    /// the upstream if there is none yet
+    pub fn push(
+        &self,
+        working_dir: &str,
+        remote: &str,
+        branch: Option<&str>,
+        token: Option<String>,
+        mut progress: impl FnMut(TransferProgress),
+    ) -> Result<(), String> {
+        self.with_repo(working_dir, |repo| {
+            let branch = branch_or_head(repo, branch)?;
+            let mut rejected = None;
+            {
+                let report = throttled(working_dir, "push", &mut progress);
+                let mut callbacks = RemoteCallbacks::new();
+                callbacks
+                    .credentials(credentials(token))
+                    .push_transfer_progress(report)
+                    .push_update_reference(|refname, status| {
+                        if let Some(status) = status {
+                            rejected = Some(format!("Push of {} rejected: {}", refname, status));
+                        }
+                        Ok(())
+                    });
+                let mut options = PushOptions::new();
+                options.remote_callbacks(callbacks);
+                let refspec = format!("refs/heads/{0}:refs/heads/{0}", branch);
+                repo.find_remote(remote)?.push(&[refspec.as_str()], Some(&mut options))?;
+            }
+            if let Some(rejected) = rejected {
+                return Err(git2::Error::from_str(&rejected));
+            }
+
+            let mut local = repo.find_branch(&branch, BranchType::Local)?;
+            if local.upstream().is_err() {
+                local.set_upstream(Some(&format!("{}/{}", remote, branch)))?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Fetch `branch` (default: the current one) from `remote` and bring
+    /// it into HEAD
+    pub fn pull(
+        &self,
+        working_dir: &str,
+        remote: &str,
+        branch: Option<&str>,
+        token: Option<String>,
+        mut progress: impl FnMut(TransferProgress),
+    ) -> Result<MergeOutcome, String> {
+        self.with_repo(working_dir, |repo| {
+            let branch = branch_or_head(repo, branch)?;
+            {
+                let mut report = throttled(working_dir, "fetch", &mut progress);
+                let mut callbacks = RemoteCallbacks::new();
+                callbacks.credentials(credentials(token)).transfer_progress(move |stats| {
+                    report(stats.received_objects(), stats.total_objects(), stats.received_bytes());
+                    true
+                });
+                let mut options = FetchOptions::new();
+                options.remote_callbacks(callbacks);
+                repo.find_remote(remote)?.fetch(&[branch.as_str()], Some(&mut options), None)?;
+            }
+
+            let theirs = repo.find_reference("FETCH_HEAD")?.peel_to_commit()?;
+            integrate(repo, &theirs, &format!("Merge branch '{}' of {}", branch, remote))
+        })
+    }
+
+    /// Merge local `branch` into HEAD
+    pub fn merge_branch(&self, working_dir: &str, branch: &str) -> Result<MergeOutcome, String> {
+        self.with_repo(working_dir, |repo| {
+            let theirs = repo.revparse_single(branch)?.peel_to_commit()?;
+            integrate(repo, &theirs, &format!("Merge branch '{}'", branch))
+        })
+    }
+
+    /// Whether merging HEAD into `target_branch` would conflict, worked
+    /// out in memory without touching either branch or the working tree
+    pub fn detect_conflicts(&self, working_dir: &str, target_branch: &str) -> Result<ConflictReport, String> {
+        self.with_repo(working_dir, |repo| {
+            let source = repo.head()?.peel_to_commit()?;
+            let target = repo.revparse_single(target_branch)?.peel_to_commit()?;
+            let index = repo.merge_commits(&target, &source, None)?;
+            Ok(ConflictReport {
+                source: branch_name(repo)?.unwrap_or_else(|| source.id().to_string()),
+                target: target_branch.to_string(),
+                files: if index.has_conflicts() { conflict_files(repo, &index)? } else { Vec::new() },
+            })
+        })
+    }
+
+    /// Stash local changes, untracked files included if asked. Returns None
+    /// when there was nothing to stash.
+    pub fn stash_save(
+        &self,
+        working_dir: &str,
+        message: Option<&str>,
+        include_untracked: bool,
+    ) -> Result<Option<StashEntry>, String> {
+        self.with_repo_mut(working_dir, |repo| {
+            let signature = repo
+                .signature()
+                .or_else(|_| git2::Signature::now("NinjaSquad", "ninjasquad@localhost"))?;
+            let flags = if include_untracked { StashFlags::INCLUDE_UNTRACKED } else { StashFlags::DEFAULT };
+            match repo.stash_save2(&signature, message, Some(flags)) {
+                Ok(_) => Ok(stash_entries(repo)?.into_iter().next()),
+                Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+                Err(e) => Err(e),
+            }
+        })
+    }
+
+    pub fn stashes(&self, working_dir: &str) -> Result<Vec<StashEntry>, String> {
+        self.with_repo_mut(working_dir, stash_entries)
+    }
+
+    /// Apply the stash at `index`, dropping it afterwards if `pop`. Fails
+    /// without touching the stash if it would overwrite local changes.
+    pub fn stash_apply(&self, working_dir: &str, index: usize, pop: bool) -> Result<(), String> {
+        self.with_repo_mut(working_dir, |repo| {
+            let mut options = StashApplyOptions::new();
+            options.reinstantiate_index();
+            if pop {
+                repo.stash_pop(index, Some(&mut options))
+            } else {
+                repo.stash_apply(index, Some(&mut options))
+            }
+        })
+    }
+
+    pub fn stash_drop(&self, working_dir: &str, index: usize) -> Result<(), String> {
+        self.with_repo_mut(working_dir, |repo| repo.stash_drop(index))
+    }
+
+    /// Pop the stash with commit `id`, wherever it has moved to in the list
+    pub fn stash_restore(&self, working_dir: &str, id: &str) -> Result<(), String> {
+        let index = stash_index(&self.stashes(working_dir)?, id)?;
+        self.stash_apply(working_dir, index, true)
+    }
+}
+
+/// Bring `theirs` into HEAD: a fast-forward when possible, otherwise a
+/// merge commit. A merge that would conflict is reported and nothing is
+/// changed.
+fn integrate(repo: &Repository, theirs: &git2::Commit, message: &str) -> Result<MergeOutcome, git2::Error> {
+    let mut head = repo.head()?;
+    let ours = head.peel_to_commit()?;
+    if ours.id() == theirs.id() || repo.graph_descendant_of(ours.id(), theirs.id())? {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    if repo.graph_descendant_of(theirs.id(), ours.id())? {
+        repo.checkout_tree(theirs.as_object(), Some(CheckoutBuilder::new().safe()))?;
+        head.set_target(theirs.id(), message)?;
+        return Ok(MergeOutcome::FastForward { commit: commit_info(theirs) });
+    }
+
+    let mut index = repo.merge_commits(&ours, theirs, None)?;
+    if index.has_conflicts() {
+        return Ok(MergeOutcome::Conflicted { files: conflict_files(repo, &index)? });
+    }
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    repo.checkout_tree(tree.as_object(), Some(CheckoutBuilder::new().safe()))?;
+    let signature = repo.signature()?;
+    let id = repo.commit(Some("HEAD"), &signature, &signature, message, &tree, &[&ours, theirs])?;
+    Ok(MergeOutcome::Merged { commit: commit_info(&repo.find_commit(id)?) })
+}
+
+fn conflict_files(repo: &Repository, index: &git2::Index) -> Result<Vec<ConflictFile>, git2::Error> {
+    let mut files = Vec::new();
+    for conflict in index.conflicts()? {
+        let conflict = conflict?;
+        let Some(entry) = conflict.our.as_ref().or(conflict.their.as_ref()).or(conflict.ancestor.as_ref()) else {
+            continue;
+        };
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+
+        let (kind, hunks) = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+            (Some(base), Some(ours), Some(theirs)) => {
+                let merged = repo.merge_file_from_index(base, ours, theirs, None)?;
+                (ConflictKind::BothModified, conflict_hunks(&String::from_utf8_lossy(merged.content())))
+            }
+            (None, Some(_), Some(_)) => (ConflictKind::BothAdded, Vec::new()),
+            (_, None, _) => (ConflictKind::DeletedByUs, Vec::new()),
+            (_, _, None) => (ConflictKind::DeletedByThem, Vec::new()),
+        };
+        files.push(ConflictFile { path, kind, hunks });
+    }
+    Ok(files)
+}
+
+/// The conflict regions of a file merged with conflict markers. Lines are
+/// numbered as in our side, which is the merged text without their lines.
+fn conflict_hunks(merged: &str) -> Vec<ConflictHunk> {
+    enum Section {
+        Clean,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut hunks = Vec::new();
+    let mut section = Section::Clean;
+    let mut line_no = 0;
+    for line in merged.lines() {
+        match section {
+            Section::Clean if line.starts_with("<<<<<<<") => {
+                hunks.push(ConflictHunk { start_line: line_no + 1, ours: Vec::new(), theirs: Vec::new() });
+                section = Section::Ours;
+            }
+            Section::Clean => line_no += 1,
+            Section::Ours | Section::Base if line.starts_with("=======") => section = Section::Theirs,
+            Section::Ours if line.starts_with("|||||||") => section = Section::Base,
+            Section::Ours => {
+                line_no += 1;
+                if let Some(hunk) = hunks.last_mut() {
+                    hunk.ours.push(line.to_string());
+                }
+            }
+            Section::Base => {}
+            Section::Theirs if line.starts_with(">>>>>>>") => section = Section::Clean,
+            Section::Theirs => {
+                if let Some(hunk) = hunks.last_mut() {
+                    hunk.theirs.push(line.to_string());
+                }
+            }
+        }
+    }
+    hunks
+}
+
+fn branch_or_head(repo: &Repository, branch: Option<&str>) -> Result<String, git2::Error> {
+    match branch {
+        Some(branch) => Ok(branch.to_string()),
+        None => branch_name(repo)?.ok_or_else(|| git2::Error::from_str("HEAD is not on a branch")),
+    }
+}
+
+/// Credentials for a remote: the SSH agent for SSH URLs, `token` as the
+/// password for HTTPS ones. Each is offered once, since libgit2 keeps
+/// asking for as long as authentication fails.
+fn credentials(token: Option<String>) -> impl FnMut(&str, Option<&str>, CredentialType) -> Result<Cred, git2::Error> {
+    let mut attempts = CredentialAttempts::default();
+    move |_url, username, allowed| match (attempts.next(allowed, token.is_some()), token.as_deref()) {
+        (Some(CredentialKind::SshAgent), _) => Cred::ssh_key_from_agent(username.unwrap_or("git")),
+        (Some(CredentialKind::Token), Some(token)) => Cred::userpass_plaintext(username.unwrap_or("x-access-token"), token),
+        (Some(CredentialKind::Default), _) => Cred::default(),
+        _ => Err(git2::Error::from_str("Authentication failed: no SSH agent key or GIT_TOKEN secret was accepted")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CredentialKind {
+    SshAgent,
+    Token,
+    Default,
+}
+
+/// Which kinds of credential have been offered for a remote
+#[derive(Debug, Default)]
+struct CredentialAttempts {
+    agent: bool,
+    token: bool,
+    default: bool,
+}
+
+impl CredentialAttempts {
+    /// The next kind to offer out of those `allowed`, or `None` once every
+    /// one that applies has been tried
+    fn next(&mut self, allowed: CredentialType, has_token: bool) -> Option<CredentialKind> {
+        if allowed.contains(CredentialType::SSH_KEY) && !self.agent {
+            self.agent = true;
+            return Some(CredentialKind::SshAgent);
+        }
+        if has_token && allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !self.token {
+            self.token = true;
+            return Some(CredentialKind::Token);
+        }
+        if allowed.contains(CredentialType::DEFAULT) && !self.default {
+            self.default = true;
+            return Some(CredentialKind::Default);
+        }
+        None
+    }
+}
+
+/// Wrap `progress` so it's called at most every `PROGRESS_INTERVAL`, plus
+/// once when the transfer completes
+fn throttled<'a>(
+    working_dir: &'a str,
+    operation: &'a str,
+    progress: &'a mut impl FnMut(TransferProgress),
+) -> impl FnMut(usize, usize, usize) + 'a {
+    let mut last: Option<Instant> = None;
+    move |current, total, bytes| {
+        if current < total && last.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        last = Some(Instant::now());
+        progress(TransferProgress {
+            working_dir: working_dir.to_string(),
+            operation: operation.to_string(),
+            current,
+            total,
+            bytes,
+        });
+    }
+}
+
+fn stash_entries(repo: &mut Repository) -> Result<Vec<StashEntry>, git2::Error> {
+    let mut entries = Vec::new();
+    repo.stash_foreach(|index, message, id| {
+        entries.push(StashEntry { index, message: message.to_string(), id: id.to_string() });
+        true
+    })?;
+    Ok(entries)
+}
+
+/// Current position of the stash with commit `id`; stashes saved since
+/// push it down the list
+fn stash_index(stashes: &[StashEntry], id: &str) -> Result<usize, String> {
+    stashes
+        .iter()
+        .find(|stash| stash.id == id)
+        .map(|stash| stash.index)
+        .ok_or_else(|| format!("Stash {} not found", id))
+}
+
+fn worktree_info(worktree: &Worktree) -> Result<WorktreeInfo, git2::Error> {
+    let valid = worktree.validate().is_ok();
+    let branch = if valid {
+        Repository::open_from_worktree(worktree)
+            .ok()
+            .and_then(|repo| branch_name(&repo).ok().flatten())
+    } else {
+        None
+    };
+    Ok(WorktreeInfo {
+        name: worktree.name().unwrap_or_default().to_string(),
+        path: worktree.path().to_string_lossy().to_string(),
+        branch,
+        locked: matches!(worktree.is_locked()?, WorktreeLockStatus::Locked(_)),
+        valid,
+    })
+}
+
+/// Name of the worktree checked out at `workdir` when it's one of those
+/// `create_worktree` puts under the repository's git directory
+fn managed_worktree_name(common_dir: &Path, workdir: &Path) -> Option<String> {
+    workdir
+        .parent()
+        .filter(|parent| *parent == common_dir.join(WORKTREE_DIR))
+        .and_then(|_| workdir.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+}
+
+/// `path` as libgit2 expects it: relative to the repository root. Relative
+/// paths are taken from `working_dir`, as the git CLI would.
+fn repo_path(repo: &Repository, working_dir: &str, path: &str) -> String {
+    let Some(root) = repo.workdir() else {
+        return path.to_string();
+    };
+    let full = Path::new(working_dir).join(path);
+    full.strip_prefix(root)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+/// HEAD's tree, `None` before the first commit
+fn head_tree(repo: &Repository) -> Result<Option<git2::Tree<'_>>, git2::Error> {
+    match repo.head() {
+        Ok(head) => head.peel_to_tree().map(Some),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub(crate) fn commit_info(commit: &git2::Commit) -> CommitInfo {
+    let author = commit.author();
+    CommitInfo {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        email: author.email().unwrap_or_default().to_string(),
+        time: chrono::DateTime::from_timestamp(commit.time().seconds(), 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default(),
+    }
+}
+
+/// Whether `commit` changed `path` relative to its parents. Like
+/// `git log -- path`, a merge counts only if it differs from every parent.
+fn touches(commit: &git2::Commit, path: &Path) -> Result<bool, git2::Error> {
+    let entry = |tree: git2::Tree| tree.get_path(path).ok().map(|e| e.id());
+    let parents = (0..commit.parent_count())
+        .map(|i| Ok(entry(commit.parent(i)?.tree()?)))
+        .collect::<Result<Vec<_>, git2::Error>>()?;
+    Ok(differs_from_parents(entry(commit.tree()?), &parents))
+}
+
+/// Whether a path's entry in a commit differs from its entry in every parent.
+/// `None` means the path isn't there; a root commit touches what it adds.
+fn differs_from_parents<T: PartialEq>(current: Option<T>, parents: &[Option<T>]) -> bool {
+    if parents.is_empty() {
+        return current.is_some();
+    }
+    parents.iter().all(|parent| *parent != current)
+}
+
+/// Lines `start..start + count` (1-based) of `lines`, clamped to the file
+fn hunk_lines(lines: &[&str], start: usize, count: usize) -> Vec<String> {
+    let end = (start.saturating_sub(1) + count).min(lines.len());
+    lines[start.saturating_sub(1).min(end)..end].iter().map(|l| l.to_string()).collect()
+}
+
+fn branch_name(repo: &Repository) -> Result<Option<String>, git2::Error> {
+    match repo.head() {
+        Ok(head) if head.is_branch() => Ok(head.shorthand().map(str::to_string)),
+        Ok(_) => Ok(None),
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn file_statuses(repo: &Repository) -> Result<Vec<FileStatus>, git2::Error> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .renames_head_to_index(true);
+
+    let mut files = Vec::new();
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let Some(path) = entry.path() else { continue };
+        for (change, staged) in changes(entry.status()) {
+            // A rename is reported under its old path; list it under the new one
+            let delta = if staged { entry.head_to_index() } else { entry.index_to_workdir() };
+            let path = delta
+                .and_then(|d| d.new_file().path().map(|p| p.to_string_lossy().to_string()))
+                .unwrap_or_else(|| path.to_string());
+            files.push(FileStatus { path, change, staged });
+        }
+    }
+    Ok(files)
+}
+
+/// Sort file statuses into `git status` groups, with line counts from the
+/// staged or unstaged diff stats
+fn group_status(
+    branch: Option<String>,
+    files: Vec<FileStatus>,
+    staged_stats: &HashMap<String, (usize, usize)>,
+    unstaged_stats: &HashMap<String, (usize, usize)>,
+) -> GitStatus {
+    let mut status = GitStatus { branch, ..GitStatus::default() };
+    for file in files {
+        let stats = if file.staged { staged_stats } else { unstaged_stats };
+        let (additions, deletions) = stats.get(&file.path).copied().unwrap_or_default();
+        let stat = FileStat {
+            path: file.path,
+            change: file.change,
+            additions,
+            deletions,
+        };
+        match (file.change, file.staged) {
+            (FileChange::Conflicted, _) => status.conflicted.push(stat),
+            (FileChange::Untracked, _) => status.untracked.push(stat),
+            (_, true) => status.staged.push(stat),
+            (_, false) => status.unstaged.push(stat),
+        }
+    }
+    status
+}
+
+/// Added and deleted lines per path in `diff`. Binary files are left out.
+fn line_stats(diff: &Diff) -> Result<HashMap<String, (usize, usize)>, git2::Error> {
+    let mut stats = HashMap::new();
+    for idx in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, idx)? else { continue };
+        let Some(path) = patch.delta().new_file().path().map(|p| p.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let (_, additions, deletions) = patch.line_stats()?;
+        stats.insert(path, (additions, deletions));
+    }
+    Ok(stats)
+}
+
+fn staging_diff(repo: &Repository, working_dir: &str, path: &str, staged: bool) -> Result<Option<FileDiff>, git2::Error> {
+    let mut options = DiffOptions::new();
+    options.pathspec(repo_path(repo, working_dir, path));
+    let diff = if staged {
+        repo.diff_tree_to_index(head_tree(repo)?.as_ref(), None, Some(&mut options))?
+    } else {
+        repo.diff_index_to_workdir(None, Some(&mut options))?
+    };
+    Ok(file_diffs(&diff)?.into_iter().next())
+}
+
+fn file_diffs(diff: &Diff) -> Result<Vec<FileDiff>, git2::Error> {
+    let mut files = Vec::new();
+    for idx in 0..diff.deltas().len() {
+        let Some(patch) = Patch::from_diff(diff, idx)? else { continue };
+        let delta = patch.delta();
+        let path_of = |file: git2::DiffFile| file.path().map(|p| p.to_string_lossy().to_string());
+        let (old_path, new_path) = (path_of(delta.old_file()), path_of(delta.new_file()));
+        let Some(path) = new_path.clone().or_else(|| old_path.clone()) else { continue };
+
+        let mut hunks = Vec::new();
+        for hunk_idx in 0..patch.num_hunks() {
+            let (hunk, line_count) = patch.hunk(hunk_idx)?;
+            let mut lines = Vec::with_capacity(line_count);
+            for line_idx in 0..line_count {
+                let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+                let Some(kind) = line_kind(line.origin()) else { continue };
+                lines.push(DiffLine {
+                    kind,
+                    content: String::from_utf8_lossy(line.content()).to_string(),
+                    old_line: line.old_lineno(),
+                    new_line: line.new_lineno(),
+                });
+            }
+            hunks.push(DiffHunk {
+                header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+        }
+
+        let change = delta_change(delta.status());
+        files.push(FileDiff {
+            old_path: old_path.filter(|old| change == FileChange::Renamed && *old != path),
+            path,
+            change,
+            binary: delta.old_file().is_binary() || delta.new_file().is_binary(),
+            hunks,
+        });
+    }
+    Ok(files)
+}
+
+/// `origin` of a patch line. The end-of-file newline markers aren't lines;
+/// a last line without a newline just has none at the end of its content.
+fn line_kind(origin: char) -> Option<LineKind> {
+    match origin {
+        '+' => Some(LineKind::Addition),
+        '-' => Some(LineKind::Deletion),
+        ' ' => Some(LineKind::Context),
+        _ => None,
+    }
+}
+
+fn delta_change(delta: Delta) -> FileChange {
+    match delta {
+        Delta::Added => FileChange::Added,
+        Delta::Deleted => FileChange::Deleted,
+        Delta::Renamed | Delta::Copied => FileChange::Renamed,
+        Delta::Typechange => FileChange::TypeChange,
+        Delta::Untracked => FileChange::Untracked,
+        Delta::Conflicted => FileChange::Conflicted,
+        _ => FileChange::Modified,
+    }
+}
+
+fn patch_text(diff: &Diff) -> Result<String, git2::Error> {
+    let mut text = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            text.push(line.origin());
+        }
+        text.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })?;
+    Ok(text)
+}
+
+/// Split a status into its staged and unstaged changes
+fn changes(status: Status) -> Vec<(FileChange, bool)> {
+    if status.is_conflicted() {
+        return vec![(FileChange::Conflicted, false)];
+    }
+
+    let staged = [
+        (Status::INDEX_NEW, FileChange::Added),
+        (Status::INDEX_MODIFIED, FileChange::Modified),
+        (Status::INDEX_DELETED, FileChange::Deleted),
+        (Status::INDEX_RENAMED, FileChange::Renamed),
+        (Status::INDEX_TYPECHANGE, FileChange::TypeChange),
+    ];
+    let unstaged = [
+        (Status::WT_NEW, FileChange::Untracked),
+        (Status::WT_MODIFIED, FileChange::Modified),
+        (Status::WT_DELETED, FileChange::Deleted),
+        (Status::WT_RENAMED, FileChange::Renamed),
+        (Status::WT_TYPECHANGE, FileChange::TypeChange),
+    ];
+
+    let staged = staged.into_iter().find(|(flag, _)| status.contains(*flag)).map(|(_, change)| (change, true));
+    let unstaged = unstaged.into_iter().find(|(flag, _)| status.contains(*flag)).map(|(_, change)| (change, false));
+    staged.into_iter().chain(unstaged).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_splits_staged_and_unstaged() {
+        assert_eq!(changes(Status::INDEX_NEW | Status::WT_MODIFIED), vec![
+            (FileChange::Added, true),
+            (FileChange::Modified, false),
+        ]);
+        assert_eq!(changes(Status::WT_NEW), vec![(FileChange::Untracked, false)]);
+        assert_eq!(changes(Status::CONFLICTED | Status::INDEX_MODIFIED), vec![(FileChange::Conflicted, false)]);
+        assert!(changes(Status::CURRENT).is_empty());
+    }
+
+    #[test]
+    fn test_group_status() {
+        let file = |path: &str, change, staged| FileStatus { path: path.to_string(), change, staged };
+        let stat = |path: &str, change, additions, deletions| FileStat { path: path.to_string(), change, additions, deletions };
+        let staged_stats = HashMap::from([("src/lib.rs".to_string(), (3, 1))]);
+        let unstaged_stats = HashMap::from([("src/lib.rs".to_string(), (2, 0)), ("todo.txt".to_string(), (4, 0))]);
+
+        let status = group_status(
+            Some("main".to_string()),
+            vec![
+                file("src/lib.rs", FileChange::Modified, true),
+                file("src/lib.rs", FileChange::Modified, false),
+                file("todo.txt", FileChange::Untracked, false),
+                file("merge.rs", FileChange::Conflicted, false),
+                file("old.rs", FileChange::Deleted, true),
+            ],
+            &staged_stats,
+            &unstaged_stats,
+        );
+
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.staged, vec![
+            stat("src/lib.rs", FileChange::Modified, 3, 1),
+            stat("old.rs", FileChange::Deleted, 0, 0),
+        ]);
+        assert_eq!(status.unstaged, vec![stat("src/lib.rs", FileChange::Modified, 2, 0)]);
+        assert_eq!(status.untracked, vec![stat("todo.txt", FileChange::Untracked, 4, 0)]);
+        assert_eq!(status.conflicted, vec![stat("merge.rs", FileChange::Conflicted, 0, 0)]);
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["staged"][0]["additions"], 3);
+    }
+
+    #[test]
+    fn test_managed_worktree_name() {
+        let common_dir = Path::new("/work/app/.git");
+
+        assert_eq!(
+            managed_worktree_name(common_dir, Path::new("/work/app/.git/ninjasquad-worktrees/claude-1234/")),
+            Some("claude-1234".to_string())
+        );
+        assert_eq!(managed_worktree_name(Path::new("/work/app/.git/"), Path::new("/work/app/.git/ninjasquad-worktrees/b")), Some("b".to_string()));
+        assert_eq!(managed_worktree_name(common_dir, Path::new("/work/app/")), None);
+        assert_eq!(managed_worktree_name(common_dir, Path::new("/work/app/.git/ninjasquad-worktrees/a/b")), None);
+        assert_eq!(managed_worktree_name(common_dir, Path::new("/elsewhere/ninjasquad-worktrees/a")), None);
+    }
+
+    #[test]
+    fn test_differs_from_parents() {
+        assert!(differs_from_parents(Some(1), &[]));
+        assert!(!differs_from_parents(None::<i32>, &[]));
+        assert!(differs_from_parents(Some(2), &[Some(1)]));
+        assert!(differs_from_parents(None, &[Some(1)]));
+        assert!(!differs_from_parents(Some(1), &[Some(1)]));
+        // A merge taking one side's version unchanged doesn't count
+        assert!(!differs_from_parents(Some(2), &[Some(1), Some(2)]));
+        assert!(differs_from_parents(Some(3), &[Some(1), Some(2)]));
+    }
+
+    #[test]
+    fn test_hunk_lines() {
+        let lines = ["a", "b", "c"];
+
+        assert_eq!(hunk_lines(&lines, 2, 2), vec!["b", "c"]);
+        assert_eq!(hunk_lines(&lines, 1, 1), vec!["a"]);
+        // Blame of the committed file can run past a shorter working copy
+        assert_eq!(hunk_lines(&lines, 3, 5), vec!["c"]);
+        assert!(hunk_lines(&lines, 5, 2).is_empty());
+    }
+
+    #[test]
+    fn test_stash_index_follows_the_stash() {
+        let stash = |index, id: &str| StashEntry { index, message: format!("On main: {}", id), id: id.to_string() };
+        let before = [stash(0, "aaa")];
+        let after = [stash(0, "bbb"), stash(1, "aaa")];
+
+        assert_eq!(stash_index(&before, "aaa"), Ok(0));
+        assert_eq!(stash_index(&after, "aaa"), Ok(1));
+        assert_eq!(stash_index(&after, "ccc"), Err("Stash ccc not found".to_string()));
+        assert_eq!(stash_index(&[], "aaa"), Err("Stash aaa not found".to_string()));
+    }
+
+    #[test]
+    fn test_credentials_are_each_offered_once() {
+        let https = CredentialType::USER_PASS_PLAINTEXT | CredentialType::DEFAULT;
+
+        let mut attempts = CredentialAttempts::default();
+        assert_eq!(attempts.next(https, true), Some(CredentialKind::Token));
+        assert_eq!(attempts.next(https, true), Some(CredentialKind::Default));
+        assert_eq!(attempts.next(https, true), None);
+
+        let mut attempts = CredentialAttempts::default();
+        assert_eq!(attempts.next(CredentialType::USER_PASS_PLAINTEXT, false), None);
+
+        let mut attempts = CredentialAttempts::default();
+        assert_eq!(attempts.next(CredentialType::SSH_KEY, true), Some(CredentialKind::SshAgent));
+        assert_eq!(attempts.next(CredentialType::SSH_KEY, true), None);
+    }
+
+    #[test]
+    fn test_progress_is_throttled_until_complete() {
+        let mut reports = Vec::new();
+        {
+            let mut progress = |p: TransferProgress| reports.push((p.operation, p.current, p.total));
+            let mut report = throttled("/work/app", "push", &mut progress);
+            report(1, 10, 100);
+            report(2, 10, 200);
+            report(10, 10, 1000);
+        }
+
+        assert_eq!(reports, vec![("push".to_string(), 1, 10), ("push".to_string(), 10, 10)]);
+    }
+
+    #[test]
+    fn test_conflict_hunks_number_lines_on_our_side() {
+        let merged = "a\n<<<<<<< ours\nb\nc\n||||||| base\nx\n=======\nd\n>>>>>>> theirs\ne\n<<<<<<< ours\n=======\nf\n>>>>>>> theirs\n";
+        assert_eq!(conflict_hunks(merged), vec![
+            ConflictHunk { start_line: 2, ours: vec!["b".to_string(), "c".to_string()], theirs: vec!["d".to_string()] },
+            ConflictHunk { start_line: 5, ours: Vec::new(), theirs: vec!["f".to_string()] },
+        ]);
+        assert!(conflict_hunks("no conflicts\n").is_empty());
+    }
+
+    #[test]
+    fn test_line_kind_and_delta_change() {
+        assert_eq!(line_kind('+'), Some(LineKind::Addition));
+        assert_eq!(line_kind('-'), Some(LineKind::Deletion));
+        assert_eq!(line_kind(' '), Some(LineKind::Context));
+        assert_eq!(line_kind('<'), None);
+        assert_eq!(delta_change(Delta::Copied), FileChange::Renamed);
+        assert_eq!(delta_change(Delta::Unmodified), FileChange::Modified);
+    }
+
+    #[test]
+    fn test_status_and_branches_of_new_repository() {
+        let dir = std::env::temp_dir().join(format!("ninjasquad-git-{}", uuid::Uuid::new_v4()));
+        let repo = Repository::init(&dir).unwrap();
+        let mut config = repo.config().unwrap();
+        config.set_str("user.name", "Test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        std::fs::write(dir.join("README.md"), "hello\n").unwrap();
+
+        let mut index = repo.index().unwrap();
+        index.add_path(Path::new("README.md")).unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("Test", "test@example.com").unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "Initial commit", &tree, &[]).unwrap();
+
+        std::fs::write(dir.join("README.md"), "hello\nworld\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "todo\n").unwrap();
+
+        let manager = GitManager::new();
+        let working_dir = dir.to_string_lossy().to_string();
+
+        let mut status = manager.status(&working_dir).unwrap();
+        status.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(status, vec![
+            FileStatus { path: "README.md".to_string(), change: FileChange::Modified, staged: false },
+            FileStatus { path: "notes.txt".to_string(), change: FileChange::Untracked, staged: false },
+        ]);
+        let summary = manager.status_summary(&working_dir).unwrap();
+        assert!(summary.staged.is_empty() && summary.conflicted.is_empty());
+        assert_eq!(summary.unstaged, vec![FileStat {
+            path: "README.md".to_string(),
+            change: FileChange::Modified,
+            additions: 1,
+            deletions: 0,
+        }]);
+        assert_eq!(summary.untracked[0].additions, 1);
+        assert!(manager.diff(&working_dir, Some("README.md")).unwrap().contains("+world"));
+        let files = manager.diff_refs(&working_dir, "HEAD", None, Some("README.md")).unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!((files[0].hunks[0].new_start, files[0].hunks[0].new_lines), (1, 2));
+        assert!(files[0].hunks[0].lines.iter().any(|l| l.kind == LineKind::Addition && l.content == "world\n"));
+
+        manager.stage(&working_dir, &["README.md".to_string()]).unwrap();
+        assert!(manager.staged_diff(&working_dir).unwrap().contains("+world"));
+        let commit = manager.commit(&working_dir, "Add world\n\nMore detail").unwrap();
+        assert_eq!(commit.summary, "Add world");
+        let history = manager.log(&working_dir, Some("README.md"), 10).unwrap();
+        assert_eq!(history.iter().map(|c| c.summary.as_str()).collect::<Vec<_>>(), vec!["Add world", "Initial commit"]);
+        assert!(manager.log(&working_dir, Some("notes.txt"), 10).unwrap().is_empty());
+        std::fs::write(dir.join("README.md"), "hello\nworld\nagain\n").unwrap();
+        let blame = manager.blame(&working_dir, "README.md").unwrap();
+        assert_eq!(blame.len(), 3);
+        assert_eq!(blame[1].commit.as_ref().map(|c| c.summary.as_str()), Some("Add world"));
+        assert!(blame[2].commit.is_none());
+        std::fs::write(dir.join("README.md"), "hello\nworld\n").unwrap();
+        assert!(manager.commit(&working_dir, "Empty").is_err());
+
+        let remote_dir = dir.with_extension("git");
+        let remote = Repository::init_bare(&remote_dir).unwrap();
+        repo.remote("origin", &remote_dir.to_string_lossy()).unwrap();
+        let mut reports = Vec::new();
+        manager.push(&working_dir, "origin", None, None, |p| reports.push(p)).unwrap();
+        let branch = manager.current_branch(&working_dir).unwrap().unwrap();
+        assert_eq!(remote.find_branch(&branch, BranchType::Local).unwrap().get().target(), Some(repo.head().unwrap().target().unwrap()));
+        assert!(reports.iter().all(|p| p.operation == "push"));
+        assert!(matches!(manager.pull(&working_dir, "origin", None, None, |_| {}).unwrap(), MergeOutcome::UpToDate));
+        std::fs::write(dir.join("README.md"), "hello\nworld\nfirst\n").unwrap();
+        let unstaged = manager.file_hunks(&working_dir, "README.md", false).unwrap().unwrap();
+        manager.stage_hunks(&working_dir, "README.md", &[HunkSelection { hunk: 0, lines: None }]).unwrap();
+        assert!(manager.file_hunks(&working_dir, "README.md", false).unwrap().is_none());
+        assert_eq!(manager.file_hunks(&working_dir, "README.md", true).unwrap().unwrap().hunks, unstaged.hunks);
+        manager.unstage_hunks(&working_dir, "README.md", &[HunkSelection { hunk: 0, lines: None }]).unwrap();
+        assert!(manager.file_hunks(&working_dir, "README.md", true).unwrap().is_none());
+        std::fs::write(dir.join("README.md"), "hello\nworld\n").unwrap();
+
+        let changes = manager.branch_changes(&working_dir, "HEAD~1", "HEAD").unwrap();
+        assert_eq!((changes[0].path.as_str(), changes[0].additions, changes[0].deletions), ("README.md", 1, 0));
+        assert!(manager.detect_conflicts(&working_dir, "HEAD~1").unwrap().files.is_empty());
+        assert!(matches!(manager.merge_branch(&working_dir, "HEAD~1").unwrap(), MergeOutcome::UpToDate));
+
+        let worktree = manager.create_worktree(&working_dir, "session-1", None).unwrap();
+        assert_eq!(worktree.branch.as_deref(), Some("ninjasquad/session-1"));
+        assert!(Path::new(&worktree.path).join("README.md").exists());
+        assert_eq!(manager.list_worktrees(&working_dir).unwrap().len(), 1);
+        assert!(!manager.remove_worktree_at(&working_dir).unwrap());
+        assert!(manager.remove_worktree_at(&worktree.path).unwrap());
+        assert!(manager.list_worktrees(&working_dir).unwrap().is_empty());
+        assert!(!Path::new(&worktree.path).exists());
+
+        std::fs::write(dir.join("scratch.txt"), "wip\n").unwrap();
+        let stash = manager.stash_save(&working_dir, Some("before agent"), true).unwrap().unwrap();
+        assert!(!dir.join("scratch.txt").exists());
+        assert_eq!(manager.stashes(&working_dir).unwrap().len(), 1);
+        manager.stash_restore(&working_dir, &stash.id).unwrap();
+        assert!(dir.join("scratch.txt").exists());
+        assert!(manager.stashes(&working_dir).unwrap().is_empty());
+        std::fs::remove_file(dir.join("scratch.txt")).unwrap();
+
+        manager.create_branch(&working_dir, "feature", true).unwrap();
+        assert_eq!(manager.current_branch(&working_dir).unwrap().as_deref(), Some("feature"));
+        let branches = manager.branches(&working_dir).unwrap();
+        assert_eq!(branches.len(), 2);
+        assert!(branches.iter().any(|b| b.name == "feature" && b.is_head));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&remote_dir);
+    }
+}
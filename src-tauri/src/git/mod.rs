@@ -0,0 +1,8 @@
+pub mod manager;
+pub mod patch;
+pub mod types;
+pub mod watcher;
+
+pub use manager::GitManager;
+pub use types::*;
+pub use watcher::RepoWatcher;
@@ -0,0 +1,164 @@
+use super::types::{DiffHunk, DiffLine, FileChange, FileDiff, HunkSelection, LineKind};
+
+/// Patch text applying only the selected parts of `file`, or with
+/// `reverse` undoing them (for unstaging from a staged diff). None when the
+/// selection changes nothing.
+///
+/// Unselected additions are left out and unselected deletions become
+/// context, so the side being patched is unchanged and each hunk's
+/// position only shifts by the hunks applied before it.
+pub fn partial_patch(file: &FileDiff, selection: &[HunkSelection], reverse: bool) -> Result<Option<String>, String> {
+    if file.binary || file.change != FileChange::Modified {
+        return Err(format!("Only parts of modified text files can be staged; stage {} as a whole", file.path));
+    }
+    if let Some(missing) = selection.iter().find(|s| s.hunk >= file.hunks.len()) {
+        return Err(format!("{} has no hunk {}", file.path, missing.hunk));
+    }
+
+    let mut text = format!("diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n", file.path);
+    let mut offset: i64 = 0;
+    let mut changed = false;
+    for (index, hunk) in file.hunks.iter().enumerate() {
+        let Some(selected) = selection.iter().find(|s| s.hunk == index) else { continue };
+        let hunk = if reverse { reversed(hunk) } else { hunk.clone() };
+
+        let mut body = String::new();
+        let mut new_lines: u32 = 0;
+        let mut hunk_changed = false;
+        for (i, line) in hunk.lines.iter().enumerate() {
+            let chosen = selected.lines.as_ref().is_none_or(|lines| lines.contains(&i));
+            let prefix = match (line.kind, chosen) {
+                (LineKind::Addition, false) => continue,
+                (LineKind::Addition, true) => '+',
+                (LineKind::Deletion, true) => '-',
+                (LineKind::Context, _) | (LineKind::Deletion, false) => ' ',
+            };
+            if prefix != '-' {
+                new_lines += 1;
+            }
+            hunk_changed |= prefix != ' ';
+            body.push(prefix);
+            body.push_str(&line.content);
+            if !line.content.ends_with('\n') {
+                body.push_str("\n\\ No newline at end of file\n");
+            }
+        }
+        if !hunk_changed {
+            continue;
+        }
+
+        // An empty side is numbered by the line before it
+        let start = hunk.old_start as i64 + offset;
+        let new_start = if hunk.old_lines == 0 {
+            start + 1
+        } else if new_lines == 0 {
+            start - 1
+        } else {
+            start
+        };
+        text.push_str(&format!("@@ -{},{} +{},{} @@\n", hunk.old_start, hunk.old_lines, new_start.max(0), new_lines));
+        text.push_str(&body);
+        offset += new_lines as i64 - hunk.old_lines as i64;
+        changed = true;
+    }
+
+    Ok(changed.then_some(text))
+}
+
+/// The hunk going the other way: additions become deletions and the old
+/// and new sides swap
+fn reversed(hunk: &DiffHunk) -> DiffHunk {
+    DiffHunk {
+        header: hunk.header.clone(),
+        old_start: hunk.new_start,
+        old_lines: hunk.new_lines,
+        new_start: hunk.old_start,
+        new_lines: hunk.old_lines,
+        lines: hunk
+            .lines
+            .iter()
+            .map(|line| DiffLine {
+                kind: match line.kind {
+                    LineKind::Addition => LineKind::Deletion,
+                    LineKind::Deletion => LineKind::Addition,
+                    LineKind::Context => LineKind::Context,
+                },
+                content: line.content.clone(),
+                old_line: line.new_line,
+                new_line: line.old_line,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(kind: LineKind, content: &str, old_line: Option<u32>, new_line: Option<u32>) -> DiffLine {
+        DiffLine { kind, content: format!("{}\n", content), old_line, new_line }
+    }
+
+    /// `a b c` -> `a B c d`, and further down `x` -> `y`
+    fn file() -> FileDiff {
+        FileDiff {
+            path: "notes.txt".to_string(),
+            old_path: None,
+            change: FileChange::Modified,
+            binary: false,
+            hunks: vec![
+                DiffHunk {
+                    header: "@@ -1,3 +1,4 @@".to_string(),
+                    old_start: 1,
+                    old_lines: 3,
+                    new_start: 1,
+                    new_lines: 4,
+                    lines: vec![
+                        line(LineKind::Context, "a", Some(1), Some(1)),
+                        line(LineKind::Deletion, "b", Some(2), None),
+                        line(LineKind::Addition, "B", None, Some(2)),
+                        line(LineKind::Context, "c", Some(3), Some(3)),
+                        line(LineKind::Addition, "d", None, Some(4)),
+                    ],
+                },
+                DiffHunk {
+                    header: "@@ -20,1 +21,1 @@".to_string(),
+                    old_start: 20,
+                    old_lines: 1,
+                    new_start: 21,
+                    new_lines: 1,
+                    lines: vec![
+                        line(LineKind::Deletion, "x", Some(20), None),
+                        line(LineKind::Addition, "y", None, Some(21)),
+                    ],
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_partial_patch_keeps_unselected_lines_out() {
+        let selection = vec![
+            HunkSelection { hunk: 0, lines: Some(vec![4]) },
+            HunkSelection { hunk: 1, lines: None },
+        ];
+        let patch = partial_patch(&file(), &selection, false).unwrap().unwrap();
+        assert_eq!(
+            patch,
+            "diff --git a/notes.txt b/notes.txt\n--- a/notes.txt\n+++ b/notes.txt\n\
+             @@ -1,3 +1,4 @@\n a\n b\n c\n+d\n\
+             @@ -20,1 +21,1 @@\n-x\n+y\n"
+        );
+
+        let only_context = vec![HunkSelection { hunk: 0, lines: Some(vec![0, 3]) }];
+        assert_eq!(partial_patch(&file(), &only_context, false).unwrap(), None);
+        assert!(partial_patch(&file(), &[HunkSelection { hunk: 2, lines: None }], false).is_err());
+    }
+
+    #[test]
+    fn test_reverse_patch_undoes_selected_lines() {
+        let selection = vec![HunkSelection { hunk: 0, lines: Some(vec![1, 2]) }];
+        let patch = partial_patch(&file(), &selection, true).unwrap().unwrap();
+        assert!(patch.ends_with("@@ -1,4 +1,4 @@\n a\n+b\n-B\n c\n d\n"));
+    }
+}
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    TypeChange,
+    Untracked,
+    Conflicted,
+}
+
+/// A changed path. A file with both staged and unstaged edits is listed
+/// once for each.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStatus {
+    pub path: String,
+    pub change: FileChange,
+    pub staged: bool,
+}
+
+/// A changed file with its line counts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStat {
+    pub path: String,
+    pub change: FileChange,
+    pub additions: usize,
+    pub deletions: usize,
+}
+
+/// Working tree status grouped the way `git status` shows it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub staged: Vec<FileStat>,
+    pub unstaged: Vec<FileStat>,
+    pub untracked: Vec<FileStat>,
+    pub conflicted: Vec<FileStat>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineKind {
+    Context,
+    Addition,
+    Deletion,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: LineKind,
+    /// Ends without a newline if the file does
+    pub content: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    /// The `@@ -a,b +c,d @@` line
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Part of a hunk to stage or unstage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HunkSelection {
+    /// Index into `FileDiff::hunks`
+    pub hunk: usize,
+    /// Indexes into the hunk's `lines`; the whole hunk when None
+    #[serde(default)]
+    pub lines: Option<Vec<usize>>,
+}
+
+/// One file's changes, parsed into hunks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path: String,
+    /// Set when the file was renamed
+    pub old_path: Option<String>,
+    pub change: FileChange,
+    /// Binary files have no hunks
+    pub binary: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitInfo {
+    pub id: String,
+    pub summary: String,
+    pub author: String,
+    pub email: String,
+    /// RFC 3339
+    pub time: String,
+}
+
+/// A run of consecutive lines last changed by the same commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameHunk {
+    /// None for lines that aren't committed yet
+    pub commit: Option<CommitInfo>,
+    /// 1-based
+    pub start_line: usize,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StashEntry {
+    /// Position in the stash list, 0 being the newest
+    pub index: usize,
+    pub message: String,
+    pub id: String,
+}
+
+/// Objects sent or received so far by a push or fetch, emitted as
+/// `git-progress` events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferProgress {
+    pub working_dir: String,
+    /// "push" or "fetch"
+    pub operation: String,
+    pub current: usize,
+    pub total: usize,
+    pub bytes: usize,
+}
+
+/// What bringing another commit into HEAD did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum MergeOutcome {
+    UpToDate,
+    FastForward { commit: CommitInfo },
+    Merged { commit: CommitInfo },
+    /// Nothing was changed
+    Conflicted { files: Vec<ConflictFile> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictKind {
+    BothModified,
+    BothAdded,
+    DeletedByUs,
+    DeletedByThem,
+}
+
+/// One `<<<<<<< ... >>>>>>>` region of a conflicting file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictHunk {
+    /// 1-based line in our version of the file
+    pub start_line: usize,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictFile {
+    pub path: String,
+    pub kind: ConflictKind,
+    /// Only content conflicts have hunks
+    pub hunks: Vec<ConflictHunk>,
+}
+
+/// Result of merging `source` into `target` in memory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConflictReport {
+    pub source: String,
+    pub target: String,
+    /// Empty when the merge would be clean
+    pub files: Vec<ConflictFile>,
+}
+
+/// What `git_commit` did: committed, or only drafted a message for the
+/// user to confirm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CommitOutcome {
+    Committed { commit: CommitInfo },
+    Draft { message: String },
+}
+
+/// A linked working tree of a repository
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorktreeInfo {
+    pub name: String,
+    pub path: String,
+    pub branch: Option<String>,
+    pub locked: bool,
+    /// False once the worktree's folder is gone
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangedPath {
+    /// Relative to the project folder
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+/// Files that changed in a watched project since the last event, emitted
+/// as `repo-changed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoChange {
+    pub project_id: String,
+    pub paths: Vec<ChangedPath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub is_head: bool,
+    pub upstream: Option<String>,
+    /// Commits ahead of and behind the upstream
+    pub ahead: usize,
+    pub behind: usize,
+    pub commit_id: Option<String>,
+    pub commit_summary: Option<String>,
+}
@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use super::types::{ChangeKind, ChangedPath, RepoChange};
+use super::GitManager;
+
+/// Quiet period after the last change before an event goes out
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Longest a batch is held while changes keep arriving, e.g. during a build
+const MAX_BATCH_DELAY: Duration = Duration::from_secs(2);
+
+/// Files under `.git` worth reporting: the index changes on stage and
+/// commit, HEAD on checkout
+const GIT_PATHS: [&str; 2] = ["index", "HEAD"];
+
+struct Watch {
+    root: PathBuf,
+    _watcher: Arc<Mutex<RecommendedWatcher>>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// One filesystem watcher per project, emitting debounced `repo-changed`
+/// events for changes in its folder that .gitignore doesn't exclude.
+/// Ignored folders such as `node_modules` and `target` are never watched,
+/// so large projects stay well under the platform's watch limit.
+pub struct RepoWatcher {
+    git: Arc<GitManager>,
+    watches: Mutex<HashMap<String, Watch>>,
+}
+
+impl RepoWatcher {
+    pub fn new(git: Arc<GitManager>) -> Self {
+        Self {
+            git,
+            watches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start watching `path` for `project_id`, replacing any watch on a
+    /// different folder
+    pub async fn watch(&self, app: AppHandle, project_id: &str, path: &str, debounce: Duration) -> Result<(), String> {
+        let root = PathBuf::from(path.trim_end_matches('/'));
+        if self.watches.lock().unwrap().get(project_id).is_some_and(|w| w.root == root) {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => println!("File watcher error: {}", e),
+        })
+        .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+        watcher
+            .watch(&root, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root.display(), e))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        // Checking ignore rules goes through libgit2, so the walk is blocking
+        let dirs = {
+            let (git, root) = (self.git.clone(), root.clone());
+            tokio::task::spawn_blocking(move || watch_dirs(&root, &root, |paths| ignored(&git, &root, paths)))
+                .await
+                .map_err(|e| format!("Failed to list folders to watch: {}", e))?
+        };
+        watch_all(&watcher, &dirs[1..]);
+
+        let task = tokio::spawn(forward_changes(
+            rx,
+            app,
+            self.git.clone(),
+            watcher.clone(),
+            project_id.to_string(),
+            root.clone(),
+            debounce,
+        ));
+        println!("Watching {} folders in {} for project {}", dirs.len(), root.display(), project_id);
+        self.watches.lock().unwrap().insert(project_id.to_string(), Watch { root, _watcher: watcher, task });
+        Ok(())
+    }
+
+    /// Stop watching a project. Returns false if it wasn't watched.
+    pub fn unwatch(&self, project_id: &str) -> bool {
+        self.watches.lock().unwrap().remove(project_id).is_some()
+    }
+
+    /// IDs of the projects being watched
+    pub fn watched(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.watches.lock().unwrap().keys().cloned().collect();
+        ids.sort();
+        ids
+    }
+}
+
+/// Collect events into batches, each sent once `debounce` passes without a
+/// new change or `MAX_BATCH_DELAY` after its first change
+async fn forward_changes(
+    mut events: UnboundedReceiver<Event>,
+    app: AppHandle,
+    git: Arc<GitManager>,
+    watcher: Arc<Mutex<RecommendedWatcher>>,
+    project_id: String,
+    root: PathBuf,
+    debounce: Duration,
+) {
+    while let Some(event) = events.recv().await {
+        let mut batch = BTreeMap::new();
+        add_event(&mut batch, &root, &event);
+        let deadline = Instant::now() + MAX_BATCH_DELAY;
+        let mut closed = false;
+        loop {
+            let wait = debounce.min(deadline.saturating_duration_since(Instant::now()));
+            match tokio::time::timeout(wait, events.recv()).await {
+                Ok(Some(event)) => add_event(&mut batch, &root, &event),
+                Ok(None) => {
+                    closed = true;
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        // New folders are watched too, unless they're ignored
+        let paths = {
+            let (git, watcher, root) = (git.clone(), watcher.clone(), root.clone());
+            tokio::task::spawn_blocking(move || {
+                let paths = unignored(&git, &root, batch);
+                for path in paths.iter().filter(|p| p.kind == ChangeKind::Created) {
+                    let dir = root.join(&path.path);
+                    if dir.is_dir() {
+                        watch_all(&watcher, &watch_dirs(&root, &dir, |paths| ignored(&git, &root, paths)));
+                    }
+                }
+                paths
+            })
+            .await
+            .unwrap_or_default()
+        };
+        if !paths.is_empty() {
+            let _ = app.emit("repo-changed", RepoChange { project_id: project_id.clone(), paths });
+        }
+        if closed {
+            break;
+        }
+    }
+}
+
+fn add_event(batch: &mut BTreeMap<String, ChangeKind>, root: &Path, event: &Event) {
+    let Some(kind) = change_kind(&event.kind) else { return };
+    for path in event.paths.iter().filter_map(|p| relevant_path(root, p)) {
+        let kind = match batch.get(&path) {
+            Some(previous) => merge_kind(*previous, kind),
+            None => kind,
+        };
+        batch.insert(path, kind);
+    }
+}
+
+/// Which of `paths`, relative to `root`, are ignored. Folders that aren't
+/// repositories have nothing ignored.
+fn ignored(git: &GitManager, root: &Path, paths: &[String]) -> Vec<String> {
+    git.ignored(&root.to_string_lossy(), paths).unwrap_or_default()
+}
+
+/// The batch without ignored files
+fn unignored(git: &GitManager, root: &Path, batch: BTreeMap<String, ChangeKind>) -> Vec<ChangedPath> {
+    let candidates: Vec<String> = batch.keys().filter(|p| !p.starts_with(".git/")).cloned().collect();
+    let ignored = ignored(git, root, &candidates);
+    batch
+        .into_iter()
+        .filter(|(path, _)| !ignored.contains(path))
+        .map(|(path, kind)| ChangedPath { path, kind })
+        .collect()
+}
+
+/// `dir` and the folders below it to watch, each on its own: those
+/// `ignored` doesn't exclude, given paths relative to `root`. `.git` is
+/// included for `GIT_PATHS` but not descended into. Symlinked folders are
+/// left out so a link can't pull in a tree outside the project.
+fn watch_dirs(root: &Path, dir: &Path, ignored: impl Fn(&[String]) -> Vec<String>) -> Vec<PathBuf> {
+    let mut dirs = vec![dir.to_path_buf()];
+    let mut next = 0;
+    while next < dirs.len() {
+        let parent = dirs[next].clone();
+        next += 1;
+        if parent.strip_prefix(root).is_ok_and(|p| p.starts_with(".git")) {
+            continue;
+        }
+        let Ok(entries) = std::fs::read_dir(&parent) else { continue };
+        let children: Vec<String> = entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| entry.path().strip_prefix(root).ok()?.to_str().map(str::to_string))
+            .collect();
+        let ignored = ignored(&children);
+        dirs.extend(children.into_iter().filter(|c| !ignored.contains(c)).map(|c| root.join(c)));
+    }
+    dirs
+}
+
+/// Add a non-recursive watch on each folder. One can vanish before its
+/// watch is added; its parent's watch reports that.
+fn watch_all(watcher: &Mutex<RecommendedWatcher>, dirs: &[PathBuf]) {
+    let mut watcher = watcher.lock().unwrap();
+    for dir in dirs {
+        let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+    }
+}
+
+/// What a notify event means for the files it names. Access events and
+/// ones the platform can't classify are dropped.
+fn change_kind(kind: &EventKind) -> Option<ChangeKind> {
+    match kind {
+        EventKind::Create(_) => Some(ChangeKind::Created),
+        EventKind::Remove(_) => Some(ChangeKind::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => Some(ChangeKind::Removed),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => Some(ChangeKind::Created),
+        EventKind::Modify(ModifyKind::Name(_)) => Some(ChangeKind::Renamed),
+        EventKind::Modify(_) | EventKind::Any => Some(ChangeKind::Modified),
+        EventKind::Access(_) | EventKind::Other => None,
+    }
+}
+
+/// Fold a path's changes within one batch into the one to report
+fn merge_kind(previous: ChangeKind, next: ChangeKind) -> ChangeKind {
+    match (previous, next) {
+        // Editors save by replacing the file
+        (ChangeKind::Removed, ChangeKind::Created) => ChangeKind::Modified,
+        (ChangeKind::Created, ChangeKind::Modified) => ChangeKind::Created,
+        (_, next) => next,
+    }
+}
+
+/// `path` relative to the watched folder, or None for the folder itself and
+/// for git's own files other than `GIT_PATHS`
+fn relevant_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    match components.next()? {
+        Component::Normal(first) if first == ".git" => {
+            let rest = components.as_path().to_str()?;
+            if !GIT_PATHS.contains(&rest) {
+                return None;
+            }
+        }
+        Component::Normal(_) => {}
+        _ => return None,
+    }
+    Some(relative.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, RemoveKind};
+
+    #[test]
+    fn test_change_kinds_fold_within_a_batch() {
+        assert_eq!(change_kind(&EventKind::Create(CreateKind::File)), Some(ChangeKind::Created));
+        assert_eq!(change_kind(&EventKind::Modify(ModifyKind::Data(DataChange::Content))), Some(ChangeKind::Modified));
+        assert_eq!(change_kind(&EventKind::Modify(ModifyKind::Name(RenameMode::Both))), Some(ChangeKind::Renamed));
+        assert_eq!(change_kind(&EventKind::Access(AccessKind::Read)), None);
+
+        let root = Path::new("/work/api");
+        let event = |kind, path: &str| Event { kind, paths: vec![root.join(path)], attrs: Default::default() };
+        let mut batch = BTreeMap::new();
+        add_event(&mut batch, root, &event(EventKind::Create(CreateKind::File), "new.rs"));
+        add_event(&mut batch, root, &event(EventKind::Modify(ModifyKind::Any), "new.rs"));
+        add_event(&mut batch, root, &event(EventKind::Remove(RemoveKind::File), "lib.rs"));
+        add_event(&mut batch, root, &event(EventKind::Modify(ModifyKind::Name(RenameMode::To)), "lib.rs"));
+        add_event(&mut batch, root, &event(EventKind::Remove(RemoveKind::File), "old.rs"));
+
+        let kinds: Vec<_> = batch.into_iter().collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("lib.rs".to_string(), ChangeKind::Modified),
+                ("new.rs".to_string(), ChangeKind::Created),
+                ("old.rs".to_string(), ChangeKind::Removed),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_relevant_path_skips_git_internals() {
+        let root = Path::new("/work/api");
+        assert_eq!(relevant_path(root, Path::new("/work/api/src/main.rs")), Some("src/main.rs".to_string()));
+        assert_eq!(relevant_path(root, Path::new("/work/api/.git/index")), Some(".git/index".to_string()));
+        assert_eq!(relevant_path(root, Path::new("/work/api/.git/HEAD")), Some(".git/HEAD".to_string()));
+        assert_eq!(relevant_path(root, Path::new("/work/api/.git/objects/ab/cdef")), None);
+        assert_eq!(relevant_path(root, Path::new("/work/api/.git/index.lock")), None);
+        assert_eq!(relevant_path(root, Path::new("/work/api")), None);
+        assert_eq!(relevant_path(root, Path::new("/work/web/index.html")), None);
+    }
+
+    #[test]
+    fn test_watch_dirs_skips_ignored_folders() {
+        let root = std::env::temp_dir().join(format!("ninjasquad-watch-{}", uuid::Uuid::new_v4()));
+        for dir in ["src/bin", "node_modules/react", "target/debug", ".git/objects", "docs"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+
+        let ignored = |paths: &[String]| -> Vec<String> {
+            paths.iter().filter(|p| *p == "node_modules" || *p == "target").cloned().collect()
+        };
+        let mut dirs: Vec<_> = watch_dirs(&root, &root, ignored)
+            .iter()
+            .map(|d| d.strip_prefix(&root).unwrap().to_string_lossy().to_string())
+            .collect();
+        dirs.sort();
+        assert_eq!(dirs, vec!["", ".git", "docs", "src", "src/bin"]);
+
+        // A folder created later is walked from there
+        let dirs = watch_dirs(&root, &root.join("src"), ignored);
+        assert_eq!(dirs, vec![root.join("src"), root.join("src/bin")]);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
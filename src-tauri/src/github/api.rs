@@ -0,0 +1,204 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::types::{GitHubIssue, PullRequest};
+
+const GITHUB_API_BASE: &str = "https://api.github.com";
+
+/// Minimal GitHub REST API client
+#[derive(Debug, Clone)]
+pub struct GitHubApiClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for GitHubApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitHubApiClient {
+    pub fn new() -> Self {
+        Self::with_base_url(GITHUB_API_BASE)
+    }
+
+    /// For GitHub Enterprise, e.g. `https://github.example.com/api/v3`
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .user_agent("ninjasquad")
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(&self, token: &str, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self.client
+            .request(method, &url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json");
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("GitHub API request {} failed: {}", path, e.without_url()))?;
+        let status = response.status();
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse GitHub API response for {}: {}", path, e))?;
+
+        if !status.is_success() {
+            let message = value["message"].as_str().unwrap_or("unknown error");
+            let details: Vec<&str> = value["errors"]
+                .as_array()
+                .map(|errors| errors.iter().filter_map(|e| e["message"].as_str()).collect())
+                .unwrap_or_default();
+            return Err(if details.is_empty() {
+                anyhow::anyhow!("GitHub API {} returned {}: {}", path, status, message)
+            } else {
+                anyhow::anyhow!("GitHub API {} returned {}: {} ({})", path, status, message, details.join("; "))
+            });
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub async fn default_branch(&self, token: &str, repo: &str) -> Result<String> {
+        let repository: Value = self.request(token, reqwest::Method::GET, &format!("/repos/{}", repo), None).await?;
+        repository["default_branch"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("GitHub repository {} has no default branch", repo))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_pull_request(
+        &self,
+        token: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+        draft: bool,
+    ) -> Result<PullRequest> {
+        let created: Value = self.request(token, reqwest::Method::POST, &format!("/repos/{}/pulls", repo), Some(json!({
+            "head": head,
+            "base": base,
+            "title": title,
+            "body": body,
+            "draft": draft,
+        }))).await?;
+
+        Ok(PullRequest {
+            number: created["number"].as_u64().unwrap_or_default(),
+            url: created["html_url"].as_str().unwrap_or_default().to_string(),
+            title: created["title"].as_str().unwrap_or(title).to_string(),
+            head: head.to_string(),
+            base: base.to_string(),
+            draft: created["draft"].as_bool().unwrap_or(draft),
+        })
+    }
+
+    /// Open issues of the repository assigned to the token's user; pull
+    /// requests, which the endpoint also returns, are left out
+    pub async fn assigned_issues(&self, token: &str, repo: &str) -> Result<Vec<GitHubIssue>> {
+        let user: Value = self.request(token, reqwest::Method::GET, "/user", None).await?;
+        // An empty assignee would list everyone's issues
+        let login = user["login"].as_str()
+            .filter(|login| !login.is_empty())
+            .ok_or_else(|| anyhow::anyhow!("GitHub didn't say which user the token belongs to"))?;
+        let issues: Vec<Value> = self.request(
+            token,
+            reqwest::Method::GET,
+            &format!("/repos/{}/issues?state=open&assignee={}&sort=updated&per_page=50", repo, login),
+            None,
+        ).await?;
+        Ok(issues.iter().filter(|i| i.get("pull_request").is_none()).filter_map(issue_from_json).collect())
+    }
+
+    pub async fn issue(&self, token: &str, repo: &str, number: u64) -> Result<GitHubIssue> {
+        let issue: Value = self.request(token, reqwest::Method::GET, &format!("/repos/{}/issues/{}", repo, number), None).await?;
+        issue_from_json(&issue).ok_or_else(|| anyhow::anyhow!("GitHub returned an unreadable issue for {}#{}", repo, number))
+    }
+
+    pub async fn comment_on_issue(&self, token: &str, repo: &str, number: u64, body: &str) -> Result<()> {
+        let _: Value = self.request(
+            token,
+            reqwest::Method::POST,
+            &format!("/repos/{}/issues/{}/comments", repo, number),
+            Some(json!({ "body": body })),
+        ).await?;
+        Ok(())
+    }
+
+    /// Close the issue as completed, or reopen it
+    pub async fn set_issue_open(&self, token: &str, repo: &str, number: u64, open: bool) -> Result<()> {
+        let body = if open { json!({ "state": "open" }) } else { json!({ "state": "closed", "state_reason": "completed" }) };
+        let _: Value = self.request(token, reqwest::Method::PATCH, &format!("/repos/{}/issues/{}", repo, number), Some(body)).await?;
+        Ok(())
+    }
+}
+
+fn issue_from_json(issue: &Value) -> Option<GitHubIssue> {
+    Some(GitHubIssue {
+        number: issue["number"].as_u64()?,
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        body: issue["body"].as_str().filter(|b| !b.trim().is_empty()).map(str::to_string),
+        state: issue["state"].as_str().unwrap_or("open").to_string(),
+        url: issue["html_url"].as_str().unwrap_or_default().to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_assigned_issues_are_the_token_users() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "login": "octocat" })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/web/issues"))
+            .and(query_param("assignee", "octocat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                { "number": 1, "title": "Bug", "state": "open", "html_url": "https://github.com/acme/web/issues/1" },
+                { "number": 2, "title": "PR", "state": "open", "pull_request": {} },
+            ])))
+            .mount(&server)
+            .await;
+
+        let issues = GitHubApiClient::with_base_url(&server.uri()).assigned_issues("token", "acme/web").await.unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].number, 1);
+    }
+
+    #[tokio::test]
+    async fn test_assigned_issues_needs_a_login() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/user"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+            .mount(&server)
+            .await;
+
+        let result = GitHubApiClient::with_base_url(&server.uri()).assigned_issues("token", "acme/web").await;
+        assert!(result.unwrap_err().to_string().contains("which user"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+}
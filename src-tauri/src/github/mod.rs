@@ -0,0 +1,89 @@
+pub mod api;
+pub mod types;
+
+pub use api::GitHubApiClient;
+pub use types::*;
+
+use crate::git::FileStat;
+
+/// Longest PR title derived from a task prompt
+const MAX_TITLE_CHARS: usize = 72;
+
+/// `owner/repo` of a GitHub remote URL, in SSH or HTTPS form
+pub fn parse_repo(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.split_once("github.com/").map(|(_, path)| path))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, repo) = path.split_once('/')?;
+    if owner.is_empty() || repo.is_empty() || repo.contains('/') {
+        return None;
+    }
+    Some(format!("{}/{}", owner, repo))
+}
+
+/// First line of the prompt, shortened to fit a PR title
+pub fn title_from_prompt(prompt: &str) -> String {
+    let line = prompt.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("Agent changes");
+    if line.chars().count() <= MAX_TITLE_CHARS {
+        return line.to_string();
+    }
+    let cut: String = line.chars().take(MAX_TITLE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// PR description: the task prompt, the files the branch changes and the
+/// agent session it came from
+pub fn pull_request_body(prompt: &str, files: &[FileStat], session: Option<&str>, session_url: Option<&str>) -> String {
+    let mut body = String::from("## Task\n\n");
+    for line in prompt.trim().lines() {
+        body.push_str("> ");
+        body.push_str(line);
+        body.push('\n');
+    }
+
+    body.push_str(&format!("\n## Files changed ({})\n\n", files.len()));
+    for file in files {
+        body.push_str(&format!("- `{}` (+{} −{})\n", file.path, file.additions, file.deletions));
+    }
+
+    match (session, session_url) {
+        (Some(session), Some(url)) => body.push_str(&format!("\n## Agent session\n\n[{}]({})\n", session, url)),
+        (Some(session), None) => body.push_str(&format!("\n## Agent session\n\n`{}`\n", session)),
+        (None, Some(url)) => body.push_str(&format!("\n## Agent session\n\n{}\n", url)),
+        (None, None) => {}
+    }
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::FileChange;
+
+    #[test]
+    fn test_parse_repo_from_remote_urls() {
+        assert_eq!(parse_repo("git@github.com:brad07/ninjasquad.git").as_deref(), Some("brad07/ninjasquad"));
+        assert_eq!(parse_repo("https://github.com/brad07/ninjasquad").as_deref(), Some("brad07/ninjasquad"));
+        assert_eq!(parse_repo("ssh://git@github.com/brad07/ninjasquad.git/").as_deref(), Some("brad07/ninjasquad"));
+        assert_eq!(parse_repo("https://gitlab.com/brad07/ninjasquad.git"), None);
+        assert_eq!(parse_repo("https://github.com/brad07"), None);
+    }
+
+    #[test]
+    fn test_title_and_body_from_task() {
+        assert_eq!(title_from_prompt("\n  Fix the login redirect\nDetails"), "Fix the login redirect");
+        assert_eq!(title_from_prompt(&"x".repeat(100)).chars().count(), MAX_TITLE_CHARS);
+
+        let files = vec![FileStat {
+            path: "src/auth.rs".to_string(),
+            change: FileChange::Modified,
+            additions: 12,
+            deletions: 3,
+        }];
+        let body = pull_request_body("Fix the login redirect\nKeep the session", &files, Some("claude · sonnet"), None);
+        assert!(body.contains("> Fix the login redirect\n> Keep the session\n"));
+        assert!(body.contains("## Files changed (1)\n\n- `src/auth.rs` (+12 −3)\n"));
+        assert!(body.ends_with("`claude · sonnet`\n"));
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// What to open a pull request for: a completed task's branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequestRequest {
+    pub project_id: String,
+    pub branch: String,
+    /// Defaults to the project's `github_base_branch`, then the repository's
+    /// default branch
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Defaults to the first line of the prompt
+    #[serde(default)]
+    pub title: Option<String>,
+    pub prompt: String,
+    /// Plugin session the work was done in
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub session_url: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: String,
+    pub title: String,
+    pub head: String,
+    pub base: String,
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubIssue {
+    pub number: u64,
+    pub title: String,
+    pub body: Option<String>,
+    /// `open` or `closed`
+    pub state: String,
+    pub url: String,
+}
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::types::{GitLabIssue, MergeRequest};
+
+const GITLAB_URL: &str = "https://gitlab.com";
+
+/// Minimal GitLab REST API (v4) client
+#[derive(Debug, Clone)]
+pub struct GitLabApiClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for GitLabApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GitLabApiClient {
+    pub fn new() -> Self {
+        Self::with_instance_url(GITLAB_URL)
+    }
+
+    /// For self-managed GitLab, e.g. `https://gitlab.example.com`
+    pub fn with_instance_url(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .user_agent("ninjasquad")
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            base_url: format!("{}/api/v4", url.trim_end_matches('/')),
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(&self, token: &str, method: reqwest::Method, path: &str, body: Option<Value>) -> Result<T> {
+        let url = format!("{}{}", self.base_url, path);
+        let mut request = self.client
+            .request(method, &url)
+            .header("PRIVATE-TOKEN", token);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("GitLab API request {} failed: {}", path, e.without_url()))?;
+        let status = response.status();
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse GitLab API response for {}: {}", path, e))?;
+
+        if !status.is_success() {
+            // `message` is a string, a list, or field errors keyed by name
+            let message = match &value["message"] {
+                Value::String(message) => message.clone(),
+                Value::Null => value["error"].as_str().unwrap_or("unknown error").to_string(),
+                message => message.to_string(),
+            };
+            return Err(anyhow::anyhow!("GitLab API {} returned {}: {}", path, status, message));
+        }
+
+        Ok(serde_json::from_value(value)?)
+    }
+
+    pub async fn default_branch(&self, token: &str, project: &str) -> Result<String> {
+        let found: Value = self.request(token, reqwest::Method::GET, &format!("/projects/{}", super::encode_path(project)), None).await?;
+        found["default_branch"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("GitLab project {} has no default branch", project))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_merge_request(
+        &self,
+        token: &str,
+        project: &str,
+        source: &str,
+        target: &str,
+        title: &str,
+        description: &str,
+        draft: bool,
+    ) -> Result<MergeRequest> {
+        // Drafts are marked by their title
+        let title = if draft { format!("Draft: {}", title) } else { title.to_string() };
+        let created: Value = self.request(
+            token,
+            reqwest::Method::POST,
+            &format!("/projects/{}/merge_requests", super::encode_path(project)),
+            Some(json!({
+                "source_branch": source,
+                "target_branch": target,
+                "title": title,
+                "description": description,
+                "remove_source_branch": true,
+            })),
+        ).await?;
+
+        Ok(MergeRequest {
+            iid: created["iid"].as_u64().unwrap_or_default(),
+            url: created["web_url"].as_str().unwrap_or_default().to_string(),
+            title: created["title"].as_str().unwrap_or(&title).to_string(),
+            source_branch: source.to_string(),
+            target_branch: target.to_string(),
+            draft: created["draft"].as_bool().unwrap_or(draft),
+        })
+    }
+
+    /// Open issues of the project assigned to the token's user
+    pub async fn assigned_issues(&self, token: &str, project: &str) -> Result<Vec<GitLabIssue>> {
+        let issues: Vec<Value> = self.request(
+            token,
+            reqwest::Method::GET,
+            &format!("/projects/{}/issues?state=opened&scope=assigned_to_me&order_by=updated_at&per_page=50", super::encode_path(project)),
+            None,
+        ).await?;
+        Ok(issues.iter().filter_map(issue_from_json).collect())
+    }
+
+    pub async fn issue(&self, token: &str, project: &str, iid: u64) -> Result<GitLabIssue> {
+        let issue: Value = self.request(
+            token,
+            reqwest::Method::GET,
+            &format!("/projects/{}/issues/{}", super::encode_path(project), iid),
+            None,
+        ).await?;
+        issue_from_json(&issue).ok_or_else(|| anyhow::anyhow!("GitLab returned an unreadable issue for {}#{}", project, iid))
+    }
+
+    pub async fn add_issue_note(&self, token: &str, project: &str, iid: u64, body: &str) -> Result<()> {
+        let _: Value = self.request(
+            token,
+            reqwest::Method::POST,
+            &format!("/projects/{}/issues/{}/notes", super::encode_path(project), iid),
+            Some(json!({ "body": body })),
+        ).await?;
+        Ok(())
+    }
+
+    /// Close the issue, or reopen it
+    pub async fn set_issue_open(&self, token: &str, project: &str, iid: u64, open: bool) -> Result<()> {
+        let _: Value = self.request(
+            token,
+            reqwest::Method::PUT,
+            &format!("/projects/{}/issues/{}", super::encode_path(project), iid),
+            Some(json!({ "state_event": if open { "reopen" } else { "close" } })),
+        ).await?;
+        Ok(())
+    }
+}
+
+fn issue_from_json(issue: &Value) -> Option<GitLabIssue> {
+    Some(GitLabIssue {
+        iid: issue["iid"].as_u64()?,
+        title: issue["title"].as_str().unwrap_or_default().to_string(),
+        description: issue["description"].as_str().filter(|d| !d.trim().is_empty()).map(str::to_string),
+        state: issue["state"].as_str().unwrap_or("opened").to_string(),
+        url: issue["web_url"].as_str().unwrap_or_default().to_string(),
+    })
+}
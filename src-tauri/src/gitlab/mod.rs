@@ -0,0 +1,139 @@
+pub mod api;
+pub mod types;
+
+pub use api::GitLabApiClient;
+pub use types::*;
+
+use crate::git::FileStat;
+
+/// Merge request description used when the project has no template of its
+/// own; see `description_from_template` for the placeholders
+pub const DEFAULT_DESCRIPTION_TEMPLATE: &str = "## Task\n\n{{prompt}}\n\n## Changes\n\n{{diff_summary}}\n\n{{files}}\n\n{{session}}";
+
+/// Project path (`group/subgroup/project`) of a remote URL on `host`, in SSH
+/// or HTTPS form
+pub fn parse_project(url: &str, host: &str) -> Option<String> {
+    let path = url
+        .strip_prefix(&format!("git@{}:", host))
+        .or_else(|| url.split_once(&format!("{}/", host)).map(|(_, path)| path))?;
+    let path = path.trim_end_matches('/').trim_end_matches(".git");
+    let segments: Vec<&str> = path.split('/').collect();
+    if segments.len() < 2 || segments.iter().any(|s| s.is_empty()) {
+        return None;
+    }
+    Some(path.to_string())
+}
+
+/// Host of a GitLab instance URL, e.g. `gitlab.example.com`
+pub fn instance_host(url: &str) -> &str {
+    let host = url.trim().trim_start_matches("https://").trim_start_matches("http://");
+    host.split('/').next().unwrap_or_default()
+}
+
+/// A project path as the URL-encoded ID the API takes
+pub fn encode_path(project: &str) -> String {
+    project
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// `3 files changed, +20 −4`
+pub fn diff_summary(files: &[FileStat]) -> String {
+    let additions: usize = files.iter().map(|f| f.additions).sum();
+    let deletions: usize = files.iter().map(|f| f.deletions).sum();
+    let noun = if files.len() == 1 { "file" } else { "files" };
+    format!("{} {} changed, +{} −{}", files.len(), noun, additions, deletions)
+}
+
+/// Merge request description from `template`, filling in `{{title}}`,
+/// `{{prompt}}` (quoted), `{{branch}}`, `{{diff_summary}}`, `{{files}}` (a
+/// list) and `{{session}}`. Blank runs left by empty placeholders are
+/// collapsed.
+pub fn description_from_template(
+    template: &str,
+    title: &str,
+    prompt: &str,
+    branch: &str,
+    files: &[FileStat],
+    session: Option<&str>,
+    session_url: Option<&str>,
+) -> String {
+    let quoted: Vec<String> = prompt.trim().lines().map(|line| format!("> {}", line)).collect();
+    let file_list: Vec<String> = files
+        .iter()
+        .map(|f| format!("- `{}` (+{} −{})", f.path, f.additions, f.deletions))
+        .collect();
+    let session = match (session, session_url) {
+        (Some(session), Some(url)) => format!("Agent session: [{}]({})", session, url),
+        (Some(session), None) => format!("Agent session: `{}`", session),
+        (None, Some(url)) => format!("Agent session: {}", url),
+        (None, None) => String::new(),
+    };
+
+    let filled = template
+        .replace("{{title}}", title)
+        .replace("{{prompt}}", &quoted.join("\n"))
+        .replace("{{branch}}", branch)
+        .replace("{{diff_summary}}", &diff_summary(files))
+        .replace("{{files}}", &file_list.join("\n"))
+        .replace("{{session}}", &session);
+
+    let mut description = String::new();
+    let mut blank_lines = 0;
+    for line in filled.trim().lines() {
+        blank_lines = if line.trim().is_empty() { blank_lines + 1 } else { 0 };
+        if blank_lines < 2 {
+            description.push_str(line.trim_end());
+            description.push('\n');
+        }
+    }
+    description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::FileChange;
+
+    #[test]
+    fn test_parse_project_from_remote_urls() {
+        assert_eq!(parse_project("git@gitlab.com:acme/shop.git", "gitlab.com").as_deref(), Some("acme/shop"));
+        assert_eq!(parse_project("https://gitlab.com/acme/web/shop", "gitlab.com").as_deref(), Some("acme/web/shop"));
+        assert_eq!(
+            parse_project("git@gitlab.example.com:platform/api.git", instance_host("https://gitlab.example.com/")).as_deref(),
+            Some("platform/api")
+        );
+        assert_eq!(parse_project("https://github.com/acme/shop.git", "gitlab.com"), None);
+        assert_eq!(parse_project("https://gitlab.com/acme", "gitlab.com"), None);
+        assert_eq!(encode_path("acme/web-app/shop.v2"), "acme%2Fweb-app%2Fshop.v2");
+    }
+
+    #[test]
+    fn test_description_from_template() {
+        let files = vec![
+            FileStat { path: "src/cart.ts".to_string(), change: FileChange::Modified, additions: 12, deletions: 3 },
+            FileStat { path: "src/cart.test.ts".to_string(), change: FileChange::Added, additions: 30, deletions: 0 },
+        ];
+        let description = description_from_template(
+            DEFAULT_DESCRIPTION_TEMPLATE,
+            "Fix cart totals",
+            "Fix cart totals\nRound to cents",
+            "agent/cart",
+            &files,
+            None,
+            None,
+        );
+        assert_eq!(
+            description,
+            "## Task\n\n> Fix cart totals\n> Round to cents\n\n## Changes\n\n2 files changed, +42 −3\n\n- `src/cart.ts` (+12 −3)\n- `src/cart.test.ts` (+30 −0)\n"
+        );
+
+        let custom = description_from_template("{{title}} from `{{branch}}`\n{{session}}", "Fix", "Fix", "agent/cart", &[], Some("claude"), None);
+        assert_eq!(custom, "Fix from `agent/cart`\nAgent session: `claude`\n");
+        assert_eq!(diff_summary(&files[..1]), "1 file changed, +12 −3");
+    }
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// What to open a merge request for: a completed task's branch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRequestRequest {
+    pub project_id: String,
+    pub branch: String,
+    /// Defaults to the project's `gitlab_target_branch`, then the GitLab
+    /// project's default branch
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Defaults to the first line of the prompt
+    #[serde(default)]
+    pub title: Option<String>,
+    pub prompt: String,
+    /// Plugin session the work was done in
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub session_url: Option<String>,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeRequest {
+    /// Number within the GitLab project, as in `!12`
+    pub iid: u64,
+    pub url: String,
+    pub title: String,
+    pub source_branch: String,
+    pub target_branch: String,
+    pub draft: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitLabIssue {
+    /// Number within the GitLab project, as in `#12`
+    pub iid: u64,
+    pub title: String,
+    pub description: Option<String>,
+    /// `opened` or `closed`
+    pub state: String,
+    pub url: String,
+}
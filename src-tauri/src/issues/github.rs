@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+
+use super::{Issue, IssueState, IssueTracker};
+use crate::github::{GitHubApiClient, GitHubIssue};
+
+/// Issues of one GitHub repository. GitHub issues are only open or closed,
+/// so moving one to in progress reopens it.
+pub struct GitHubTracker {
+    api: GitHubApiClient,
+    token: String,
+    /// `owner/repo`
+    repo: String,
+}
+
+impl GitHubTracker {
+    pub fn new(token: &str, repo: &str) -> Self {
+        Self { api: GitHubApiClient::new(), token: token.to_string(), repo: repo.to_string() }
+    }
+
+    fn issue(&self, issue: GitHubIssue) -> Issue {
+        Issue {
+            id: issue.number.to_string(),
+            key: format!("#{}", issue.number),
+            title: issue.title,
+            description: issue.body,
+            state: if issue.state == "closed" { IssueState::Done } else { IssueState::Todo },
+            status: issue.state,
+            url: Some(issue.url).filter(|url| !url.is_empty()),
+            tracker: "github".to_string(),
+        }
+    }
+}
+
+/// An issue number, with or without its `#`
+pub fn issue_number(id: &str) -> Result<u64, String> {
+    id.trim().trim_start_matches('#').parse().map_err(|_| format!("Not an issue number: {}", id))
+}
+
+#[async_trait]
+impl IssueTracker for GitHubTracker {
+    fn kind(&self) -> &'static str {
+        "github"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        let issues = self.api.assigned_issues(&self.token, &self.repo).await.map_err(|e| e.to_string())?;
+        Ok(issues.into_iter().map(|issue| self.issue(issue)).collect())
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<Issue, String> {
+        let issue = self.api.issue(&self.token, &self.repo, issue_number(id)?).await.map_err(|e| e.to_string())?;
+        Ok(self.issue(issue))
+    }
+
+    async fn comment(&self, id: &str, body: &str) -> Result<(), String> {
+        self.api.comment_on_issue(&self.token, &self.repo, issue_number(id)?, body).await.map_err(|e| e.to_string())
+    }
+
+    async fn transition(&self, id: &str, state: IssueState) -> Result<(), String> {
+        let open = state != IssueState::Done;
+        self.api.set_issue_open(&self.token, &self.repo, issue_number(id)?, open).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,59 @@
+use async_trait::async_trait;
+
+use super::github::issue_number;
+use super::{Issue, IssueState, IssueTracker};
+use crate::gitlab::{GitLabApiClient, GitLabIssue};
+
+/// Issues of one GitLab project. Like GitHub's they're only opened or
+/// closed, so moving one to in progress reopens it.
+pub struct GitLabTracker {
+    api: GitLabApiClient,
+    token: String,
+    /// `group/project`
+    project: String,
+}
+
+impl GitLabTracker {
+    pub fn new(api: GitLabApiClient, token: &str, project: &str) -> Self {
+        Self { api, token: token.to_string(), project: project.to_string() }
+    }
+
+    fn issue(&self, issue: GitLabIssue) -> Issue {
+        Issue {
+            id: issue.iid.to_string(),
+            key: format!("#{}", issue.iid),
+            title: issue.title,
+            description: issue.description,
+            state: if issue.state == "closed" { IssueState::Done } else { IssueState::Todo },
+            status: issue.state,
+            url: Some(issue.url).filter(|url| !url.is_empty()),
+            tracker: "gitlab".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl IssueTracker for GitLabTracker {
+    fn kind(&self) -> &'static str {
+        "gitlab"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        let issues = self.api.assigned_issues(&self.token, &self.project).await.map_err(|e| e.to_string())?;
+        Ok(issues.into_iter().map(|issue| self.issue(issue)).collect())
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<Issue, String> {
+        let issue = self.api.issue(&self.token, &self.project, issue_number(id)?).await.map_err(|e| e.to_string())?;
+        Ok(self.issue(issue))
+    }
+
+    async fn comment(&self, id: &str, body: &str) -> Result<(), String> {
+        self.api.add_issue_note(&self.token, &self.project, issue_number(id)?, body).await.map_err(|e| e.to_string())
+    }
+
+    async fn transition(&self, id: &str, state: IssueState) -> Result<(), String> {
+        let open = state != IssueState::Done;
+        self.api.set_issue_open(&self.token, &self.project, issue_number(id)?, open).await.map_err(|e| e.to_string())
+    }
+}
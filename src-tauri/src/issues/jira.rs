@@ -0,0 +1,98 @@
+use async_trait::async_trait;
+
+use super::{Issue, IssueState, IssueTracker};
+use crate::jira::{JiraApiClient, JiraAuth, JiraIssue, JiraTransition};
+
+/// Jira Cloud, through an account's API token
+pub struct JiraTracker {
+    api: JiraApiClient,
+    auth: JiraAuth,
+    /// Limits listed issues to one Jira project
+    project_key: Option<String>,
+}
+
+impl JiraTracker {
+    pub fn new(api: JiraApiClient, auth: JiraAuth, project_key: Option<String>) -> Self {
+        Self { api, auth, project_key }
+    }
+}
+
+/// Jira's status category for `state`
+fn status_category(state: IssueState) -> &'static str {
+    match state {
+        IssueState::Todo => "new",
+        IssueState::InProgress => "indeterminate",
+        IssueState::Done => "done",
+    }
+}
+
+/// The first transition leading to a status in `state`'s category
+pub fn transition_to(transitions: &[JiraTransition], state: IssueState) -> Option<&JiraTransition> {
+    transitions.iter().find(|t| t.to_category == status_category(state))
+}
+
+impl From<JiraIssue> for Issue {
+    fn from(issue: JiraIssue) -> Self {
+        let state = match issue.status_category.as_str() {
+            "indeterminate" => IssueState::InProgress,
+            "done" => IssueState::Done,
+            _ => IssueState::Todo,
+        };
+        Issue {
+            id: issue.key.clone(),
+            key: issue.key,
+            title: issue.summary,
+            description: issue.description,
+            status: issue.status,
+            state,
+            url: Some(issue.url),
+            tracker: "jira".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl IssueTracker for JiraTracker {
+    fn kind(&self) -> &'static str {
+        "jira"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        let issues = self.api.assigned_issues(&self.auth, self.project_key.as_deref()).await.map_err(|e| e.to_string())?;
+        Ok(issues.into_iter().map(Issue::from).collect())
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<Issue, String> {
+        self.api.get_issue(&self.auth, id).await.map(Issue::from).map_err(|e| e.to_string())
+    }
+
+    async fn comment(&self, id: &str, body: &str) -> Result<(), String> {
+        self.api.add_comment(&self.auth, id, body).await.map_err(|e| e.to_string())
+    }
+
+    async fn transition(&self, id: &str, state: IssueState) -> Result<(), String> {
+        let transitions = self.api.transitions(&self.auth, id).await.map_err(|e| e.to_string())?;
+        let transition = transition_to(&transitions, state)
+            .ok_or_else(|| format!("{} has no transition to a {} status", id, status_category(state)))?;
+        self.api.transition(&self.auth, id, &transition.id).await.map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transition_to_state() {
+        let transition = |id: &str, to: &str, category: &str| JiraTransition {
+            id: id.to_string(),
+            name: to.to_string(),
+            to_status: to.to_string(),
+            to_category: category.to_string(),
+        };
+        let transitions = vec![transition("11", "In Progress", "indeterminate"), transition("21", "In Review", "indeterminate"), transition("31", "Done", "done")];
+        assert_eq!(transition_to(&transitions, IssueState::InProgress).map(|t| t.id.as_str()), Some("11"));
+        assert_eq!(transition_to(&transitions, IssueState::Done).map(|t| t.id.as_str()), Some("31"));
+        assert!(transition_to(&transitions, IssueState::Todo).is_none());
+    }
+}
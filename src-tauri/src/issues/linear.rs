@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+
+use super::{Issue, IssueState, IssueTracker};
+use crate::linear::{state_of_kind, LinearApiClient, LinearIssue};
+
+/// Linear, through a personal API key
+pub struct LinearTracker {
+    api: LinearApiClient,
+    api_key: String,
+    /// Limits listed issues to one team
+    team_id: Option<String>,
+}
+
+impl LinearTracker {
+    pub fn new(api_key: &str, team_id: Option<String>) -> Self {
+        Self { api: LinearApiClient::new(), api_key: api_key.to_string(), team_id }
+    }
+}
+
+/// Linear's workflow state type for `state`
+fn state_type(state: IssueState) -> &'static str {
+    match state {
+        IssueState::Todo => "unstarted",
+        IssueState::InProgress => "started",
+        IssueState::Done => "completed",
+    }
+}
+
+impl From<LinearIssue> for Issue {
+    fn from(issue: LinearIssue) -> Self {
+        let state = match issue.state_type.as_deref() {
+            Some("started") => IssueState::InProgress,
+            Some("completed" | "canceled") => IssueState::Done,
+            _ => IssueState::Todo,
+        };
+        Issue {
+            id: issue.id,
+            key: issue.identifier,
+            title: issue.title,
+            description: issue.description,
+            status: issue.state.unwrap_or_default(),
+            state,
+            url: issue.url,
+            tracker: "linear".to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl IssueTracker for LinearTracker {
+    fn kind(&self) -> &'static str {
+        "linear"
+    }
+
+    async fn list_issues(&self) -> Result<Vec<Issue>, String> {
+        let issues = self.api.assigned_issues(&self.api_key, self.team_id.as_deref()).await.map_err(|e| e.to_string())?;
+        Ok(issues.into_iter().map(Issue::from).collect())
+    }
+
+    async fn get_issue(&self, id: &str) -> Result<Issue, String> {
+        self.api.issue(&self.api_key, id).await.map(Issue::from).map_err(|e| e.to_string())
+    }
+
+    async fn comment(&self, id: &str, body: &str) -> Result<(), String> {
+        self.api.comment(&self.api_key, id, body).await.map_err(|e| e.to_string())
+    }
+
+    async fn transition(&self, id: &str, state: IssueState) -> Result<(), String> {
+        let issue = self.api.issue(&self.api_key, id).await.map_err(|e| e.to_string())?;
+        let states = self.api.workflow_states(&self.api_key, &issue.team_id).await.map_err(|e| e.to_string())?;
+        let kind = state_type(state);
+        let target = state_of_kind(&states, kind).ok_or_else(|| format!("{}'s team has no {} state", issue.identifier, kind))?;
+        self.api.set_state(&self.api_key, &issue.id, &target.id).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,92 @@
+pub mod github;
+pub mod gitlab;
+pub mod jira;
+pub mod linear;
+pub mod pipeline;
+pub mod registry;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Where an issue stands, in terms every tracker can map to its own
+/// workflow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueState {
+    Todo,
+    InProgress,
+    Done,
+}
+
+/// An issue from any tracker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Issue {
+    /// What the tracker's `get_issue` takes
+    pub id: String,
+    /// As people refer to it, e.g. `ENG-42` or `#12`
+    pub key: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// The tracker's own name for the issue's status
+    pub status: String,
+    pub state: IssueState,
+    pub url: Option<String>,
+    /// Kind of tracker it came from, e.g. `linear`
+    pub tracker: String,
+}
+
+/// An issue tracker a project's agents take work from and report back to
+#[async_trait]
+pub trait IssueTracker: Send + Sync {
+    /// e.g. `linear`, `github`, `jira` or `gitlab`
+    fn kind(&self) -> &'static str;
+
+    /// Open issues assigned to the tracker's account
+    async fn list_issues(&self) -> Result<Vec<Issue>, String>;
+
+    async fn get_issue(&self, id: &str) -> Result<Issue, String>;
+
+    async fn comment(&self, id: &str, body: &str) -> Result<(), String>;
+
+    /// Move the issue to the tracker's first status for `state`
+    async fn transition(&self, id: &str, state: IssueState) -> Result<(), String>;
+}
+
+/// Prompt for the agent taking on `issue`
+pub fn task_prompt(issue: &Issue) -> String {
+    let mut prompt = format!("{}: {}", issue.key, issue.title);
+    if let Some(description) = &issue.description {
+        prompt.push_str("\n\n");
+        prompt.push_str(description.trim());
+    }
+    if let Some(url) = &issue.url {
+        prompt.push_str(&format!("\n\nIssue: {}", url));
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_task_prompt() {
+        let mut issue = Issue {
+            id: "issue-1".to_string(),
+            key: "ENG-42".to_string(),
+            title: "Fix login".to_string(),
+            description: Some("Redirect loops\n".to_string()),
+            status: "Todo".to_string(),
+            state: IssueState::Todo,
+            url: Some("https://linear.app/acme/issue/ENG-42/fix-login".to_string()),
+            tracker: "linear".to_string(),
+        };
+        assert_eq!(
+            task_prompt(&issue),
+            "ENG-42: Fix login\n\nRedirect loops\n\nIssue: https://linear.app/acme/issue/ENG-42/fix-login"
+        );
+        issue.description = None;
+        issue.url = None;
+        assert_eq!(task_prompt(&issue), "ENG-42: Fix login");
+    }
+}
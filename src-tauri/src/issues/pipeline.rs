@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use super::{task_prompt, Issue, IssueState, IssueTracker};
+use crate::session::{SessionManager, SessionStatus};
+
+/// How often a dispatched task is checked for progress
+const TASK_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// Longest a dispatched task is followed before it's reported as failed
+const MAX_TASK_DURATION: Duration = Duration::from_secs(6 * 60 * 60);
+/// Longest task result quoted in the completion comment
+const MAX_RESULT_CHARS: usize = 4000;
+
+/// How a dispatched task ended
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskOutcome {
+    Completed,
+    Failed(String),
+}
+
+/// Hand `issue` to an agent session, then move it to in progress and say
+/// so on the issue. A task that couldn't be handed out is reported on the
+/// issue too.
+pub async fn start(tracker: &dyn IssueTracker, sessions: &SessionManager, issue: &Issue) -> Result<String, String> {
+    let task_id = match sessions.distribute_task(task_prompt(issue)).await {
+        Ok(task_id) => task_id,
+        Err(e) => {
+            report(tracker, issue, &format!("Couldn't hand this issue to an agent: {}", e)).await;
+            return Err(e);
+        }
+    };
+    println!("[Issues] Dispatched {} {} as task {}", tracker.kind(), issue.key, task_id);
+
+    move_to(tracker, issue, IssueState::InProgress).await;
+    report(tracker, issue, &format!("🥷 An agent picked this up as task `{}`.", task_id)).await;
+    Ok(task_id)
+}
+
+/// Wait for the task to finish, then report its result on the issue and
+/// move the issue to done, or back to to-do when the task failed
+pub async fn follow(tracker: &dyn IssueTracker, sessions: &SessionManager, issue: &Issue, task_id: &str) -> TaskOutcome {
+    let started = tokio::time::Instant::now();
+    let (outcome, comment) = loop {
+        tokio::time::sleep(TASK_POLL_INTERVAL).await;
+        if started.elapsed() > MAX_TASK_DURATION {
+            let reason = format!("Task `{}` didn't finish within {} hours.", task_id, MAX_TASK_DURATION.as_secs() / 3600);
+            break (TaskOutcome::Failed(reason.clone()), format!("❌ {}", reason));
+        }
+        match sessions.task_status(task_id).await {
+            Some((SessionStatus::Completed, task)) => {
+                break (TaskOutcome::Completed, completion_comment(task.result.as_deref()));
+            }
+            Some((SessionStatus::Failed(reason), _)) => {
+                let comment = format!("❌ The agent's session failed: {}", reason);
+                break (TaskOutcome::Failed(reason), comment);
+            }
+            Some(_) => continue,
+            None => {
+                let reason = format!("Task `{}` is no longer assigned to an agent.", task_id);
+                break (TaskOutcome::Failed(reason.clone()), format!("❌ {}", reason));
+            }
+        }
+    };
+    println!("[Issues] Task {} for {} {} ended: {:?}", task_id, tracker.kind(), issue.key, outcome);
+
+    report(tracker, issue, &comment).await;
+    let state = if outcome == TaskOutcome::Completed { IssueState::Done } else { IssueState::Todo };
+    move_to(tracker, issue, state).await;
+    outcome
+}
+
+fn completion_comment(result: Option<&str>) -> String {
+    let mut comment = "✅ The agent finished this task.".to_string();
+    if let Some(result) = result.map(str::trim).filter(|r| !r.is_empty()) {
+        let quoted: String = result.chars().take(MAX_RESULT_CHARS).collect();
+        let ellipsis = if quoted.len() < result.len() { "…" } else { "" };
+        comment.push_str(&format!("\n\n{}{}", quoted, ellipsis));
+    }
+    comment
+}
+
+async fn report(tracker: &dyn IssueTracker, issue: &Issue, body: &str) {
+    if let Err(e) = tracker.comment(&issue.id, body).await {
+        println!("[Issues] Failed to comment on {} {}: {}", tracker.kind(), issue.key, e);
+    }
+}
+
+async fn move_to(tracker: &dyn IssueTracker, issue: &Issue, state: IssueState) {
+    if let Err(e) = tracker.transition(&issue.id, state).await {
+        println!("[Issues] Failed to move {} {} to {:?}: {}", tracker.kind(), issue.key, state, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_comment() {
+        assert_eq!(completion_comment(None), "✅ The agent finished this task.");
+        assert_eq!(completion_comment(Some("  ")), "✅ The agent finished this task.");
+        assert_eq!(completion_comment(Some("Fixed the redirect\n")), "✅ The agent finished this task.\n\nFixed the redirect");
+        assert!(completion_comment(Some(&"é".repeat(MAX_RESULT_CHARS + 1))).ends_with("é…"));
+    }
+}
@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use super::github::GitHubTracker;
+use super::gitlab::GitLabTracker;
+use super::jira::JiraTracker;
+use super::linear::LinearTracker;
+use super::IssueTracker;
+use crate::projects::env::get_secret;
+use crate::projects::types::Project;
+
+/// Project secret holding the Linear API key
+pub const LINEAR_API_KEY_SECRET: &str = "LINEAR_API_KEY";
+
+/// The tracker the project's `issue_tracker` setting names, built from its
+/// settings and secrets. `origin_url` is the project's origin remote, for
+/// finding the GitHub repository or GitLab project when the settings don't
+/// name one.
+pub fn from_settings(project: &Project, origin_url: Option<&str>) -> Result<Arc<dyn IssueTracker>, String> {
+    // The first of the project's secrets that's set
+    let secret = |names: &[&str]| names.iter().find_map(|name| get_secret(&project.id, name).ok().flatten());
+    build(project, origin_url, secret)
+}
+
+fn build(
+    project: &Project,
+    origin_url: Option<&str>,
+    secret: impl Fn(&[&str]) -> Option<String>,
+) -> Result<Arc<dyn IssueTracker>, String> {
+    let settings = project.settings.clone().unwrap_or_default();
+    match settings.issue_tracker.as_deref() {
+        Some("linear") => {
+            let api_key = secret(&[LINEAR_API_KEY_SECRET])
+                .ok_or_else(|| format!("Add a {} secret to the project to use Linear", LINEAR_API_KEY_SECRET))?;
+            Ok(Arc::new(LinearTracker::new(&api_key, settings.linear_team_id)))
+        }
+        Some("github") => {
+            let token = secret(&["GITHUB_TOKEN", "GIT_TOKEN"])
+                .ok_or_else(|| "Add a GITHUB_TOKEN secret to the project to use GitHub issues".to_string())?;
+            let repo = settings
+                .github_repo
+                .or_else(|| origin_url.and_then(crate::github::parse_repo))
+                .ok_or_else(|| "Set the project's GitHub repository; origin is not a GitHub remote".to_string())?;
+            Ok(Arc::new(GitHubTracker::new(&token, &repo)))
+        }
+        Some("gitlab") => {
+            let token = secret(&["GITLAB_TOKEN", "GIT_TOKEN"])
+                .ok_or_else(|| "Add a GITLAB_TOKEN secret to the project to use GitLab issues".to_string())?;
+            let host = crate::gitlab::instance_host(settings.gitlab_url.as_deref().unwrap_or("gitlab.com")).to_string();
+            let gitlab_project = settings
+                .gitlab_project
+                .or_else(|| origin_url.and_then(|url| crate::gitlab::parse_project(url, &host)))
+                .ok_or_else(|| format!("Set the project's GitLab project; origin is not a {} remote", host))?;
+            let api = match settings.gitlab_url.as_deref() {
+                Some(url) => crate::gitlab::GitLabApiClient::with_instance_url(url),
+                None => crate::gitlab::GitLabApiClient::new(),
+            };
+            Ok(Arc::new(GitLabTracker::new(api, &token, &gitlab_project)))
+        }
+        Some("jira") => {
+            let (api, auth, project_key) = crate::jira::project_client(project)?;
+            Ok(Arc::new(JiraTracker::new(api, auth, project_key)))
+        }
+        Some(other) => Err(format!("Unknown issue tracker: {}", other)),
+        None => Err(format!("Choose an issue tracker for {}", project.name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::types::ProjectSettings;
+
+    fn project(settings: ProjectSettings) -> Project {
+        Project {
+            id: "p1".to_string(),
+            name: "web".to_string(),
+            path: "/tmp/web".to_string(),
+            description: None,
+            color: None,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            last_accessed: None,
+            is_favorite: false,
+            settings: Some(settings),
+            is_archived: false,
+            is_missing: false,
+            tags: Vec::new(),
+        }
+    }
+
+    fn tracker(name: &str) -> ProjectSettings {
+        ProjectSettings { issue_tracker: Some(name.to_string()), ..Default::default() }
+    }
+
+    /// Secrets lookup that knows only `names`
+    fn secrets(names: &'static [&'static str]) -> impl Fn(&[&str]) -> Option<String> {
+        move |wanted: &[&str]| wanted.iter().find(|name| names.contains(name)).map(|name| format!("{}-value", name))
+    }
+
+    fn kind(result: Result<Arc<dyn IssueTracker>, String>) -> Result<&'static str, String> {
+        result.map(|tracker| tracker.kind())
+    }
+
+    #[test]
+    fn test_builds_the_configured_tracker() {
+        assert_eq!(kind(build(&project(tracker("linear")), None, secrets(&[LINEAR_API_KEY_SECRET]))), Ok("linear"));
+        assert_eq!(
+            kind(build(&project(tracker("github")), Some("git@github.com:acme/web.git"), secrets(&["GIT_TOKEN"]))),
+            Ok("github")
+        );
+        assert_eq!(
+            kind(build(&project(tracker("gitlab")), Some("https://gitlab.com/acme/web.git"), secrets(&["GITLAB_TOKEN"]))),
+            Ok("gitlab")
+        );
+
+        let settings = ProjectSettings { github_repo: Some("acme/web".to_string()), ..tracker("github") };
+        assert_eq!(kind(build(&project(settings), None, secrets(&["GITHUB_TOKEN"]))), Ok("github"));
+    }
+
+    #[test]
+    fn test_explains_what_is_missing() {
+        let error = |settings: ProjectSettings, origin: Option<&str>, names: &'static [&'static str]| {
+            kind(build(&project(settings), origin, secrets(names))).unwrap_err()
+        };
+
+        assert_eq!(error(ProjectSettings::default(), None, &[]), "Choose an issue tracker for web");
+        assert_eq!(error(tracker("trello"), None, &[]), "Unknown issue tracker: trello");
+        assert!(error(tracker("linear"), None, &[]).contains(LINEAR_API_KEY_SECRET));
+        assert!(error(tracker("github"), None, &[]).contains("GITHUB_TOKEN"));
+        assert!(error(tracker("github"), Some("https://gitlab.com/acme/web.git"), &["GITHUB_TOKEN"])
+            .contains("not a GitHub remote"));
+        assert!(error(tracker("gitlab"), Some("git@github.com:acme/web.git"), &["GITLAB_TOKEN"])
+            .contains("not a gitlab.com remote"));
+        assert!(error(tracker("jira"), None, &[]).contains("Jira site"));
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use super::types::{JiraAuth, JiraIssue, JiraTransition};
+
+/// Issue fields fetched for a `JiraIssue`
+const ISSUE_FIELDS: &str = "summary,description,status,issuetype,priority";
+const MAX_RESULTS: u32 = 50;
+
+/// Minimal Jira Cloud REST API (v3) client for one site
+#[derive(Debug, Clone)]
+pub struct JiraApiClient {
+    client: reqwest::Client,
+    site_url: String,
+}
+
+impl JiraApiClient {
+    /// `site_url` like `https://acme.atlassian.net`
+    pub fn new(site_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .user_agent("ninjasquad")
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            site_url: site_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn request(
+        &self,
+        auth: &JiraAuth,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<Value>,
+    ) -> Result<Value> {
+        let url = format!("{}/rest/api/3{}", self.site_url, path);
+        let mut request = self.client
+            .request(method, &url)
+            .basic_auth(&auth.email, Some(&auth.api_token))
+            .header("Accept", "application/json")
+            .query(query);
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Jira API request {} failed: {}", path, e.without_url()))?;
+        let status = response.status();
+        let text = response
+            .text()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read Jira API response for {}: {}", path, e))?;
+        // Transitions and some updates answer 204 with no body
+        let value: Value = if text.trim().is_empty() { Value::Null } else { serde_json::from_str(&text).unwrap_or(Value::String(text)) };
+
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("Jira API {} returned {}: {}", path, status, super::error_message(&value)));
+        }
+        Ok(value)
+    }
+
+    /// Open issues assigned to the token's account, most recently updated
+    /// first, from `project_key` when given
+    pub async fn assigned_issues(&self, auth: &JiraAuth, project_key: Option<&str>) -> Result<Vec<JiraIssue>> {
+        let jql = super::assigned_jql(project_key);
+        let max_results = MAX_RESULTS.to_string();
+        let found = self.request(
+            auth,
+            reqwest::Method::GET,
+            "/search/jql",
+            &[("jql", &jql), ("fields", ISSUE_FIELDS), ("maxResults", &max_results)],
+            None,
+        ).await?;
+        Ok(found["issues"]
+            .as_array()
+            .map(|issues| issues.iter().filter_map(|issue| super::parse_issue(&self.site_url, issue)).collect())
+            .unwrap_or_default())
+    }
+
+    pub async fn get_issue(&self, auth: &JiraAuth, key: &str) -> Result<JiraIssue> {
+        let issue = self.request(auth, reqwest::Method::GET, &format!("/issue/{}", key), &[("fields", ISSUE_FIELDS)], None).await?;
+        super::parse_issue(&self.site_url, &issue).ok_or_else(|| anyhow::anyhow!("Jira returned an unreadable issue for {}", key))
+    }
+
+    pub async fn transitions(&self, auth: &JiraAuth, key: &str) -> Result<Vec<JiraTransition>> {
+        let found = self.request(auth, reqwest::Method::GET, &format!("/issue/{}/transitions", key), &[], None).await?;
+        Ok(found["transitions"]
+            .as_array()
+            .map(|transitions| {
+                transitions
+                    .iter()
+                    .filter_map(|t| {
+                        Some(JiraTransition {
+                            id: t["id"].as_str()?.to_string(),
+                            name: t["name"].as_str()?.to_string(),
+                            to_status: t["to"]["name"].as_str().unwrap_or_default().to_string(),
+                            to_category: t["to"]["statusCategory"]["key"].as_str().unwrap_or_default().to_string(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    pub async fn transition(&self, auth: &JiraAuth, key: &str, transition_id: &str) -> Result<()> {
+        self.request(
+            auth,
+            reqwest::Method::POST,
+            &format!("/issue/{}/transitions", key),
+            &[],
+            Some(json!({ "transition": { "id": transition_id } })),
+        ).await?;
+        Ok(())
+    }
+
+    pub async fn add_comment(&self, auth: &JiraAuth, key: &str, text: &str) -> Result<()> {
+        self.request(
+            auth,
+            reqwest::Method::POST,
+            &format!("/issue/{}/comment", key),
+            &[],
+            Some(json!({ "body": super::adf_document(text) })),
+        ).await?;
+        Ok(())
+    }
+}
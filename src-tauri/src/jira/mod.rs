@@ -0,0 +1,198 @@
+pub mod api;
+pub mod types;
+
+pub use api::JiraApiClient;
+pub use types::*;
+
+use serde_json::{json, Value};
+
+use crate::projects::types::Project;
+
+/// Project secret holding the Jira Cloud API token
+pub const API_TOKEN_SECRET: &str = "JIRA_API_TOKEN";
+
+/// Client, credentials and project key from the project's Jira settings and
+/// JIRA_API_TOKEN secret
+pub fn project_client(project: &Project) -> Result<(JiraApiClient, JiraAuth, Option<String>), String> {
+    let settings = project.settings.clone().unwrap_or_default();
+    let (Some(site), Some(email)) = (settings.jira_site, settings.jira_email) else {
+        return Err("Set the project's Jira site and account email".to_string());
+    };
+    let api_token = crate::projects::env::get_secret(&project.id, API_TOKEN_SECRET)?
+        .ok_or_else(|| format!("Add a {} secret to the project to use Jira", API_TOKEN_SECRET))?;
+    Ok((JiraApiClient::new(&site_url(&site)), JiraAuth { email, api_token }, settings.jira_project_key))
+}
+
+/// `https://<site>.atlassian.net` from a site name, host or URL
+pub fn site_url(site: &str) -> String {
+    let host = site.trim().trim_start_matches("https://").trim_start_matches("http://");
+    let host = host.split('/').next().unwrap_or_default();
+    if host.contains('.') {
+        format!("https://{}", host)
+    } else {
+        format!("https://{}.atlassian.net", host)
+    }
+}
+
+/// JQL for unresolved issues assigned to the caller
+pub fn assigned_jql(project_key: Option<&str>) -> String {
+    let mut jql = "assignee = currentUser() AND statusCategory != Done".to_string();
+    if let Some(key) = project_key.map(str::trim).filter(|key| !key.is_empty()) {
+        jql.push_str(&format!(" AND project = \"{}\"", key.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    jql.push_str(" ORDER BY updated DESC");
+    jql
+}
+
+pub fn parse_issue(site_url: &str, issue: &Value) -> Option<JiraIssue> {
+    let key = issue["key"].as_str()?;
+    let fields = &issue["fields"];
+    Some(JiraIssue {
+        id: issue["id"].as_str()?.to_string(),
+        key: key.to_string(),
+        summary: fields["summary"].as_str().unwrap_or_default().to_string(),
+        description: Some(adf_text(&fields["description"])).filter(|d| !d.is_empty()),
+        status: fields["status"]["name"].as_str().unwrap_or_default().to_string(),
+        status_category: fields["status"]["statusCategory"]["key"].as_str().unwrap_or_default().to_string(),
+        issue_type: fields["issuetype"]["name"].as_str().map(str::to_string),
+        priority: fields["priority"]["name"].as_str().map(str::to_string),
+        url: format!("{}/browse/{}", site_url.trim_end_matches('/'), key),
+    })
+}
+
+/// Plain text of an Atlassian Document Format node, paragraphs on their
+/// own lines
+pub fn adf_text(node: &Value) -> String {
+    fn collect(node: &Value, out: &mut String) {
+        match node["type"].as_str() {
+            Some("text") => out.push_str(node["text"].as_str().unwrap_or_default()),
+            Some("hardBreak") => out.push('\n'),
+            Some("mention") => out.push_str(node["attrs"]["text"].as_str().unwrap_or_default()),
+            _ => {}
+        }
+        for child in node["content"].as_array().map(Vec::as_slice).unwrap_or_default() {
+            collect(child, out);
+        }
+        if matches!(node["type"].as_str(), Some("paragraph" | "heading" | "codeBlock" | "listItem")) && !out.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    let mut text = String::new();
+    collect(node, &mut text);
+    text.trim().to_string()
+}
+
+/// An Atlassian Document Format document of `text`: a paragraph per block
+/// of lines, with line breaks kept
+pub fn adf_document(text: &str) -> Value {
+    let paragraphs: Vec<Value> = text
+        .trim()
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            let mut content = Vec::new();
+            for (i, line) in block.trim_matches('\n').lines().enumerate() {
+                if i > 0 {
+                    content.push(json!({ "type": "hardBreak" }));
+                }
+                if !line.is_empty() {
+                    content.push(json!({ "type": "text", "text": line }));
+                }
+            }
+            json!({ "type": "paragraph", "content": content })
+        })
+        .collect();
+    json!({ "type": "doc", "version": 1, "content": paragraphs })
+}
+
+/// The transition named `target`, or else the one leading to a status of
+/// that name, ignoring case
+pub fn find_transition<'a>(transitions: &'a [JiraTransition], target: &str) -> Option<&'a JiraTransition> {
+    let target = target.trim();
+    transitions
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(target))
+        .or_else(|| transitions.iter().find(|t| t.to_status.eq_ignore_ascii_case(target)))
+}
+
+/// The messages of a Jira error response
+pub fn error_message(value: &Value) -> String {
+    let mut messages: Vec<String> = value["errorMessages"]
+        .as_array()
+        .map(|m| m.iter().filter_map(|m| m.as_str().map(str::to_string)).collect())
+        .unwrap_or_default();
+    if let Some(errors) = value["errors"].as_object() {
+        messages.extend(errors.iter().map(|(field, e)| format!("{}: {}", field, e.as_str().unwrap_or_default())));
+    }
+    match (messages.is_empty(), value.as_str()) {
+        (false, _) => messages.join("; "),
+        (true, Some(text)) => text.chars().take(200).collect(),
+        (true, None) => "unknown error".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_site_url_and_jql() {
+        assert_eq!(site_url("acme"), "https://acme.atlassian.net");
+        assert_eq!(site_url("https://acme.atlassian.net/jira/software"), "https://acme.atlassian.net");
+        assert_eq!(site_url("jira.acme.com"), "https://jira.acme.com");
+        assert_eq!(assigned_jql(None), "assignee = currentUser() AND statusCategory != Done ORDER BY updated DESC");
+        assert_eq!(
+            assigned_jql(Some(" SHOP ")),
+            "assignee = currentUser() AND statusCategory != Done AND project = \"SHOP\" ORDER BY updated DESC"
+        );
+    }
+
+    #[test]
+    fn test_parse_issue_and_description() {
+        let issue = json!({
+            "id": "10042",
+            "key": "SHOP-7",
+            "fields": {
+                "summary": "Checkout fails for guests",
+                "status": { "name": "To Do", "statusCategory": { "key": "new" } },
+                "issuetype": { "name": "Bug" },
+                "priority": null,
+                "description": { "type": "doc", "version": 1, "content": [
+                    { "type": "paragraph", "content": [{ "type": "text", "text": "Steps:" }, { "type": "hardBreak" }, { "type": "text", "text": "Pay as guest" }] },
+                    { "type": "paragraph", "content": [{ "type": "mention", "attrs": { "text": "@sam" } }, { "type": "text", "text": " saw it" }] }
+                ]}
+            }
+        });
+        let issue = parse_issue("https://acme.atlassian.net/", &issue).unwrap();
+        assert_eq!(issue.url, "https://acme.atlassian.net/browse/SHOP-7");
+        assert_eq!((issue.status.as_str(), issue.status_category.as_str()), ("To Do", "new"));
+        assert_eq!(issue.description.as_deref(), Some("Steps:\nPay as guest\n@sam saw it"));
+        assert_eq!(issue.priority, None);
+    }
+
+    #[test]
+    fn test_adf_document() {
+        let doc = adf_document("Done.\nBranch agent/shop-7\n\nAll tests pass");
+        assert_eq!(doc["content"].as_array().unwrap().len(), 2);
+        assert_eq!(doc["content"][0]["content"][1], json!({ "type": "hardBreak" }));
+        assert_eq!(adf_text(&doc), "Done.\nBranch agent/shop-7\nAll tests pass");
+    }
+
+    #[test]
+    fn test_find_transition_and_errors() {
+        let transition = |id: &str, name: &str, to: &str| JiraTransition {
+            id: id.into(),
+            name: name.into(),
+            to_status: to.into(),
+            to_category: String::new(),
+        };
+        let transitions = vec![transition("11", "Start work", "In Progress"), transition("31", "Done", "Done")];
+        assert_eq!(find_transition(&transitions, "start work").map(|t| t.id.as_str()), Some("11"));
+        assert_eq!(find_transition(&transitions, "In progress").map(|t| t.id.as_str()), Some("11"));
+        assert!(find_transition(&transitions, "Review").is_none());
+
+        let error = json!({ "errorMessages": ["Issue does not exist"], "errors": { "comment": "Comment body can not be empty!" } });
+        assert_eq!(error_message(&error), "Issue does not exist; comment: Comment body can not be empty!");
+        assert_eq!(error_message(&Value::Null), "unknown error");
+    }
+}
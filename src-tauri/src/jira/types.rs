@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// Account a Jira Cloud API token belongs to; Jira takes the pair as basic
+/// auth
+#[derive(Debug, Clone)]
+pub struct JiraAuth {
+    pub email: String,
+    pub api_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraIssue {
+    pub id: String,
+    /// e.g. `SHOP-123`
+    pub key: String,
+    pub summary: String,
+    /// Plain text of the issue's description
+    pub description: Option<String>,
+    pub status: String,
+    /// `new`, `indeterminate` or `done`
+    pub status_category: String,
+    pub issue_type: Option<String>,
+    pub priority: Option<String>,
+    pub url: String,
+}
+
+/// A move the issue's workflow allows from its current status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JiraTransition {
+    pub id: String,
+    pub name: String,
+    /// Status the issue ends up in
+    pub to_status: String,
+    /// `new`, `indeterminate` or `done`
+    #[serde(default)]
+    pub to_category: String,
+}
@@ -3,25 +3,48 @@ pub mod session;
 pub mod wezterm;
 pub mod tmux;
 pub mod pty;
+pub mod git;
 pub mod database;
 pub mod projects;
 pub mod queue;
 pub mod plugins;
 pub mod claude;
 pub mod slack;
+pub mod email;
+pub mod standup;
+pub mod telegram;
+pub mod github;
+pub mod gitlab;
+pub mod devserver;
+pub mod browser;
+pub mod linear;
+pub mod jira;
+pub mod issues;
+pub mod approvals;
 
 #[cfg(feature = "tauri-app")]
 mod tauri_app {
-    use crate::opencode::{OpenCodeServer, OpenCodeService};
+    use crate::opencode::{OpenCodeServer, OpenCodeService, ServerStatus};
     use crate::session::{SessionManager, OrchestratorSession};
-    use crate::wezterm::{WezTermController, WezTermWindow, MirrorManager, WezTermMirror};
-    use crate::tmux::{TmuxManager, TmuxSession};
-    use crate::pty::{PtyManager, TerminalSession};
+    use crate::wezterm::{WezTermController, WezTermWindow, MirrorManager, WezTermMirror, MirrorScrollback, PaneLayout, SplitDirection, PaneDirection, CliPaneSize, WindowGeometry, WezTermCli, WezTermStatus, KeyModifier, ProjectTerminalCleanup};
+    use crate::tmux::{TmuxManager, TmuxSession, TmuxSessionInfo, TmuxWindow, TmuxPane, TmuxKey, CaptureOptions, PaneCapture, PaneSearchMatch};
+    use crate::git::{GitManager, RepoWatcher, GitStatus, BranchInfo, BlameHunk, CommitInfo, CommitOutcome, FileDiff, MergeOutcome, StashEntry, WorktreeInfo};
+    use crate::pty::{PtyManager, TerminalSession, TerminalOptions, TerminalInfo, ThrottleConfig, TerminalSearchMatch};
     use crate::database::DatabaseManager;
-    use crate::queue::{QueueClient, WorkerService, QueueConfig, WorkerInfo, TaskMessage, TaskType, TaskResult, LocalTestMode};
+    use crate::projects::launch;
+    use crate::projects::types::{DeleteProjectOptions, ProjectDeletionReport, SavedCommandRun};
+    use crate::queue::{QueueClient, WorkerService, QueueConfig, WorkerInfo, TaskMessage, TaskType, TaskResult, LocalTestMode, RecordingQueueClient, TaskHistory};
     use crate::plugins::manager::PluginManager;
     use crate::claude::{ClaudeProcessManager, ClaudeSession, ClaudeAgentService};
-    use crate::slack::{SlackService, SlackConfig, SlackApprovalRequest, SlackMessage};
+    use crate::telegram::{TelegramService, TelegramConfig, TelegramApprovalRequest, TelegramMessage};
+    use crate::linear::{LinearConfig, LinearDispatch, LinearService};
+    use crate::issues::{Issue, IssueState, IssueTracker};
+    use crate::browser::{BrowserLogs, BrowserManager, BrowserSession, BrowserStep, PageContent, ScriptReport, Screenshot};
+    use crate::devserver::{DevServer, DevServerManager, DevServerSpec, DevServerStatus, LogLine, RestartPolicy};
+    use crate::email::{EmailNotifier, EmailConfig, EmailConfigView, TaskNotification};
+    use crate::standup::{StandupConfig, StandupService};
+    use crate::database::standups::StandupSummary;
+    use crate::slack::{SlackService, SlackConfig, SlackApprovalRequest, SlackMessage, SlackTaskCompletion, SlackErrorAlert, SlackSessionUpdate};
     use std::sync::{Arc, Mutex};
     use tokio::sync::Mutex as AsyncMutex;
     use tauri::{Manager, State, Emitter};
@@ -34,26 +57,47 @@ mod tauri_app {
         session_manager: Arc<SessionManager>,
         claude_manager: Arc<ClaudeProcessManager>,
         pty_manager: Arc<Mutex<PtyManager>>,
+        git_manager: Arc<GitManager>,
+        repo_watcher: Arc<RepoWatcher>,
+        dev_server_manager: Arc<DevServerManager>,
+        browser_manager: Arc<BrowserManager>,
         queue_client: Arc<dyn QueueClient>,
         worker_service: Option<Arc<WorkerService>>,
         local_test_mode: Arc<AsyncMutex<Option<LocalTestMode>>>,
         plugin_manager: Arc<AsyncMutex<PluginManager>>,
         slack_service: Arc<SlackService>,
+        email_notifier: Arc<EmailNotifier>,
+        standup_service: Arc<StandupService>,
+        telegram_service: Arc<TelegramService>,
+        linear_service: Arc<LinearService>,
         claude_agent_service: Arc<ClaudeAgentService>,
     }
 
     #[tauri::command]
-    async fn spawn_opencode_server(port: u16, working_dir: Option<String>, state: State<'_, AppState>) -> Result<OpenCodeServer, String> {
-        state.opencode_service.spawn_server(port, working_dir).await
+    async fn spawn_opencode_server(port: Option<u16>, working_dir: Option<String>, project_id: Option<String>, state: State<'_, AppState>, db: State<'_, DatabaseManager>) -> Result<OpenCodeServer, String> {
+        let config = launch::resolve_for(&db, launch::LaunchRequest { project_id, port, working_dir, ..Default::default() })?;
+        let env = launch::env(&db, &config);
+        state.opencode_service.spawn_server_with_env(config.port, config.working_dir, env).await
     }
     #[tauri::command]
-    async fn spawn_opencode_sdk_server(port: u16, model: Option<String>, working_dir: Option<String>, state: State<'_, AppState>) -> Result<OpenCodeServer, String> {
-        state.opencode_service.spawn_sdk_server(port, model, working_dir).await
+    async fn spawn_opencode_sdk_server(port: Option<u16>, model: Option<String>, working_dir: Option<String>, project_id: Option<String>, state: State<'_, AppState>, db: State<'_, DatabaseManager>) -> Result<OpenCodeServer, String> {
+        let config = launch::resolve_for(&db, launch::LaunchRequest { project_id, port, model, working_dir, ..Default::default() })?;
+        let env = launch::env(&db, &config);
+        state.opencode_service.spawn_sdk_server_with_env(config.port, config.model, config.working_dir, env).await
     }
 
     #[tauri::command]
-    async fn spawn_opencode_tui_server(port: u16, model: Option<String>, working_dir: Option<String>, state: State<'_, AppState>) -> Result<OpenCodeServer, String> {
-        state.opencode_service.spawn_tui_server(port, model, working_dir).await
+    async fn spawn_opencode_tui_server(port: Option<u16>, model: Option<String>, working_dir: Option<String>, project_id: Option<String>, state: State<'_, AppState>, db: State<'_, DatabaseManager>) -> Result<OpenCodeServer, String> {
+        let config = launch::resolve_for(&db, launch::LaunchRequest { project_id, port, model, working_dir, ..Default::default() })?;
+        let env = launch::env(&db, &config);
+        state.opencode_service.spawn_tui_server_with_env(config.port, config.model, config.working_dir, env).await
+    }
+
+    /// Environment variables of the project a process is started in
+    fn project_env_for(db: &DatabaseManager, working_dir: Option<&str>) -> std::collections::HashMap<String, String> {
+        working_dir
+            .map(|dir| crate::projects::env::env_for_dir(db, dir))
+            .unwrap_or_default()
     }
 
     #[tauri::command]
@@ -176,19 +220,90 @@ mod tauri_app {
         result
     }
 
+    #[tauri::command]
+    async fn complete_task(task_id: String, result: Option<String>, state: State<'_, AppState>) -> Result<(), String> {
+        state.session_manager.complete_task(&task_id, result).await.map(|_| ())
+    }
+
     #[tauri::command]
     async fn create_terminal(
         rows: u16,
         cols: u16,
         server_id: Option<String>,
         session_id: Option<String>,
+        options: Option<TerminalOptions>,
         state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
     ) -> Result<TerminalSession, String> {
+        let mut options = options.unwrap_or_default();
+
+        // Open in the directory of the server or session the terminal belongs to
+        if options.cwd.is_none() {
+            options.cwd = resolve_terminal_cwd(&state, &db, server_id.as_deref(), session_id.as_deref()).await
+                .filter(|dir| std::path::Path::new(dir).is_dir());
+        }
+        if options.project_id.is_none() {
+            if let Some(cwd) = &options.cwd {
+                options.project_id = crate::projects::manager::ProjectsManager::new(&db)
+                    .get_by_path(cwd)
+                    .ok()
+                    .flatten()
+                    .map(|project| project.id);
+            }
+        }
+
+        // Project variables first, so ones passed explicitly win
+        let mut env = match &options.project_id {
+            Some(project_id) => crate::projects::env::env_for_project(&db, project_id),
+            None => project_env_for(&db, options.cwd.as_deref()),
+        };
+        env.extend(std::mem::take(&mut options.env));
+        options.env = env;
+
         // Clone the Arc to avoid holding the lock across await
         let pty_manager = state.pty_manager.clone();
         let pty = pty_manager.lock().unwrap();
         // Call the synchronous version
-        pty.create_terminal_sync(rows, cols, server_id, session_id)
+        pty.create_terminal_sync(rows, cols, server_id, session_id, options)
+    }
+
+    /// Working directory of a plugin session, an orchestrator session's
+    /// server, or an OpenCode server, checked in that order
+    async fn resolve_terminal_cwd(
+        state: &AppState,
+        db: &DatabaseManager,
+        server_id: Option<&str>,
+        session_id: Option<&str>,
+    ) -> Option<String> {
+        let mut server_id = server_id.map(str::to_string);
+
+        if let Some(session_id) = session_id {
+            let plugin_session = crate::plugins::sessions::PluginSessionManager::new(db)
+                .get(session_id)
+                .ok()
+                .flatten();
+            if let Some(session) = plugin_session {
+                return Some(session.working_directory);
+            }
+
+            if let Some(session) = state.session_manager.get_session_state(session_id).await {
+                server_id.get_or_insert(session.opencode_server_id);
+            }
+        }
+
+        state.opencode_service.get_server(server_id.as_deref()?).await?.working_dir
+    }
+
+    #[tauri::command]
+    async fn find_terminals(
+        server_id: Option<String>,
+        session_id: Option<String>,
+        project_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TerminalInfo>, String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        Ok(pty.find_terminals_sync(server_id.as_deref(), session_id.as_deref(), project_id.as_deref()))
     }
 
     #[tauri::command]
@@ -204,6 +319,29 @@ mod tauri_app {
         pty.write_to_terminal_sync(&terminal_id, &data)
     }
 
+    /// Write base64-encoded bytes, for input that isn't valid UTF-8
+    #[tauri::command]
+    async fn write_to_terminal_bytes(
+        terminal_id: String,
+        base64_data: String,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        pty.write_base64_sync(&terminal_id, &base64_data)
+    }
+
+    #[tauri::command]
+    async fn set_terminal_throttle(
+        terminal_id: String,
+        config: ThrottleConfig,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        pty.set_throttle_sync(&terminal_id, config)
+    }
+
     #[tauri::command]
     async fn resize_terminal(
         terminal_id: String,
@@ -218,6 +356,59 @@ mod tauri_app {
         pty.resize_terminal_sync(&terminal_id, cols, rows)
     }
 
+    #[tauri::command]
+    async fn search_terminal(
+        terminal_id: String,
+        query: String,
+        regex: bool,
+        case_sensitive: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TerminalSearchMatch>, String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        pty.search_terminal_sync(&terminal_id, &query, regex, case_sensitive.unwrap_or(false))
+    }
+
+    #[tauri::command]
+    async fn list_terminals(state: State<'_, AppState>) -> Result<Vec<TerminalInfo>, String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        Ok(pty.list_terminals_sync())
+    }
+
+    #[tauri::command]
+    async fn kill_project_pty_terminals(
+        project_path: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<String>, String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        Ok(pty.kill_project_terminals_sync(&project_path))
+    }
+
+    /// Kill terminals idle for `timeout_secs`; `None` disables reaping
+    #[tauri::command]
+    async fn set_terminal_idle_timeout(
+        timeout_secs: Option<u64>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        pty.set_idle_timeout(timeout_secs.map(std::time::Duration::from_secs));
+        Ok(())
+    }
+
+    #[tauri::command]
+    async fn get_terminal_buffer(
+        terminal_id: String,
+        lines: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        let pty_manager = state.pty_manager.clone();
+        let pty = pty_manager.lock().unwrap();
+        pty.get_terminal_buffer_sync(&terminal_id, lines)
+    }
+
     #[tauri::command]
     async fn kill_terminal(
         terminal_id: String,
@@ -257,7 +448,7 @@ mod tauri_app {
     }
 
     #[tauri::command]
-    async fn publish_task(task_type: String, payload: serde_json::Value, state: State<'_, AppState>) -> Result<String, String> {
+    async fn publish_task(task_type: String, payload: serde_json::Value, state: State<'_, AppState>, db: State<'_, DatabaseManager>) -> Result<String, String> {
         let task_type = match task_type.as_str() {
             "run_command" => TaskType::RunCommand,
             "create_session" => TaskType::CreateSession,
@@ -267,6 +458,17 @@ mod tauri_app {
             custom => TaskType::Custom(custom.to_string()),
         };
 
+        // Code run for a project gets its environment on the worker; other
+        // task types don't start processes there. History keeps only the
+        // variable names.
+        let mut payload = payload;
+        if let (TaskType::ExecuteCode, Some(project_id)) = (&task_type, payload["project_id"].as_str()) {
+            let env = crate::projects::env::env_for_project(&db, project_id);
+            if !env.is_empty() {
+                payload["env"] = serde_json::json!(env);
+            }
+        }
+
         let task = TaskMessage::new(task_type, payload);
         let task_id = task.id.clone();
         state.queue_client.publish_task(task).await?;
@@ -274,8 +476,81 @@ mod tauri_app {
     }
 
     #[tauri::command]
-    async fn get_task_result(task_id: String, state: State<'_, AppState>) -> Result<Option<TaskResult>, String> {
-        state.queue_client.consume_result(&task_id).await
+    async fn get_task_result(task_id: String, state: State<'_, AppState>, db: State<'_, DatabaseManager>) -> Result<Option<TaskResult>, String> {
+        if let Some(result) = state.queue_client.consume_result(&task_id).await? {
+            return Ok(Some(result));
+        }
+        // Already consumed, or finished on a previous run
+        db.with_connection(|conn| crate::database::tasks::get_result(conn, &task_id))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_task_history(
+        db: State<'_, DatabaseManager>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<crate::database::tasks::TaskRecord>, String> {
+        db.with_connection(|conn| crate::database::tasks::list_tasks(conn, limit.unwrap_or(100), offset.unwrap_or(0)))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_worker_history(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Vec<crate::database::tasks::WorkerRecord>, String> {
+        db.with_connection(crate::database::tasks::list_workers)
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_usage_report(
+        db: State<'_, DatabaseManager>,
+        range: Option<crate::database::usage::UsageRange>,
+        group_by: Option<crate::database::usage::UsageGrouping>,
+    ) -> Result<crate::database::usage::UsageReport, String> {
+        db.with_connection(|conn| {
+            crate::database::usage::get_report(conn, &range.unwrap_or_default(), group_by.unwrap_or_default())
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_retention_policy(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::database::retention::RetentionPolicy, String> {
+        db.with_connection(crate::database::retention::get_policy)
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn set_retention_policy(
+        db: State<'_, DatabaseManager>,
+        policy: crate::database::retention::RetentionPolicy,
+    ) -> Result<(), String> {
+        db.with_connection(|conn| crate::database::retention::set_policy(conn, &policy))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn run_retention_cleanup(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::database::retention::RetentionReport, String> {
+        db.with_connection(crate::database::retention::run)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Keep task and worker history for `days`, pruning anything older now
+    #[tauri::command]
+    async fn set_task_history_retention(
+        db: State<'_, DatabaseManager>,
+        days: u32,
+    ) -> Result<usize, String> {
+        db.with_connection(|conn| {
+            crate::database::settings::set_json(conn, crate::database::tasks::RETENTION_SETTING, &days)?;
+            crate::database::tasks::apply_retention(conn)
+        })
+        .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
@@ -359,327 +634,1867 @@ mod tauri_app {
         project_id: String,
         working_dir: String,
         state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
     ) -> Result<WezTermWindow, String> {
-        state.wezterm_controller.spawn_window_for_project(&project_id, &working_dir).await
+        let layout = db.with_connection(|conn| {
+            crate::database::wezterm::get_layout(conn, &project_id)
+        })
+        .map_err(|e| e.to_string())?;
+
+        let geometry = db.with_connection(|conn| {
+            crate::database::wezterm::get_geometry(conn, &project_id)
+        })
+        .map_err(|e| e.to_string())?;
+
+        let mut window = state.wezterm_controller.spawn_window_for_project(&project_id, &working_dir, layout.as_ref()).await?;
+
+        // Put the window back where it was last left
+        if let Some(geometry) = geometry {
+            match state.wezterm_controller.set_window_geometry(&window.window_id, &geometry).await {
+                Ok(updated) => window = updated,
+                Err(e) => println!("Failed to restore geometry for project {}: {}", project_id, e),
+            }
+        }
+
+        if let Err(e) = db.with_connection(|conn| crate::database::wezterm::save_window(conn, &window)) {
+            println!("Failed to persist WezTerm window {}: {}", window.window_id, e);
+        }
+        Ok(window)
     }
 
     #[tauri::command]
-    async fn list_project_wezterm_windows(
+    async fn get_project_terminal_layout(
         project_id: String,
-        state: State<'_, AppState>,
-    ) -> Result<Vec<WezTermWindow>, String> {
-        state.wezterm_controller.list_project_windows(&project_id).await
+        db: State<'_, DatabaseManager>,
+    ) -> Result<PaneLayout, String> {
+        db.with_connection(|conn| {
+            crate::database::wezterm::get_layout(conn, &project_id)
+        })
+        .map(|layout| layout.unwrap_or_default())
+        .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn close_wezterm_window(
-        window_id: String,
-        state: State<'_, AppState>,
+    async fn save_project_terminal_layout(
+        project_id: String,
+        layout: PaneLayout,
+        db: State<'_, DatabaseManager>,
     ) -> Result<(), String> {
-        state.wezterm_controller.close_window(&window_id).await
+        db.with_connection(|conn| {
+            crate::database::wezterm::save_layout(conn, &project_id, &layout)
+        })
+        .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn send_text_to_wezterm(
-        window_id: String,
-        text: String,
-        state: State<'_, AppState>,
-    ) -> Result<(), String> {
-        state.wezterm_controller.send_text_to_window(&window_id, &text).await
+    async fn get_project_window_geometry(
+        project_id: String,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Option<WindowGeometry>, String> {
+        db.with_connection(|conn| {
+            crate::database::wezterm::get_geometry(conn, &project_id)
+        })
+        .map_err(|e| e.to_string())
     }
 
+    /// Move/resize a project window and remember the geometry for its project
     #[tauri::command]
-    async fn execute_command_in_wezterm(
+    async fn set_wezterm_window_geometry(
         window_id: String,
-        command: String,
+        geometry: WindowGeometry,
         state: State<'_, AppState>,
-    ) -> Result<String, String> {
-        state.wezterm_controller.execute_command_with_output(&window_id, &command).await
+        db: State<'_, DatabaseManager>,
+    ) -> Result<WezTermWindow, String> {
+        let window = state.wezterm_controller.set_window_geometry(&window_id, &geometry).await?;
+        save_window_geometry(&db, &window, &geometry)?;
+        Ok(window)
     }
 
+    /// Remember where a project window currently is, for the next spawn
     #[tauri::command]
-    async fn focus_wezterm_window(
+    async fn save_wezterm_window_geometry(
+        window_id: String,
         state: State<'_, AppState>,
-    ) -> Result<(), String> {
-        state.wezterm_controller.focus_wezterm_window().await
+        db: State<'_, DatabaseManager>,
+    ) -> Result<WindowGeometry, String> {
+        let geometry = state.wezterm_controller.get_window_geometry(&window_id).await?;
+        let window = state.wezterm_controller.get_window(&window_id).await
+            .ok_or_else(|| format!("Window {} not found", window_id))?;
+        save_window_geometry(&db, &window, &geometry)?;
+        Ok(geometry)
     }
 
-    #[tauri::command]
-    async fn list_all_wezterm_windows(
-        state: State<'_, AppState>,
-    ) -> Result<Vec<WezTermWindow>, String> {
-        state.wezterm_controller.list_all_windows().await
+    fn save_window_geometry(db: &DatabaseManager, window: &WezTermWindow, geometry: &WindowGeometry) -> Result<(), String> {
+        db.with_connection(|conn| {
+            crate::database::wezterm::save_window(conn, window)?;
+            if let Some(project_id) = &window.project_id {
+                crate::database::wezterm::save_geometry(conn, project_id, geometry)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| e.to_string())
     }
 
-    // WezTerm Mirror Commands
     #[tauri::command]
-    async fn start_wezterm_mirror(
-        project_path: String,
+    #[allow(clippy::too_many_arguments)]
+    async fn split_wezterm_pane(
+        window_id: String,
+        role: String,
+        split_from: Option<String>,
+        direction: SplitDirection,
+        percent: u8,
+        command: Option<String>,
         state: State<'_, AppState>,
-    ) -> Result<WezTermMirror, String> {
-        let mirror_manager = state.wezterm_mirror_manager.lock().await;
-        mirror_manager.create_mirror(&project_path).await
+        db: State<'_, DatabaseManager>,
+    ) -> Result<String, String> {
+        let pane_id = state.wezterm_controller.split_window_pane(
+            &window_id,
+            &role,
+            split_from.as_deref(),
+            direction,
+            percent,
+            command.as_deref(),
+        ).await?;
+
+        if let Some(window) = state.wezterm_controller.get_window(&window_id).await {
+            if let Err(e) = db.with_connection(|conn| crate::database::wezterm::save_window(conn, &window)) {
+                println!("Failed to persist WezTerm window {}: {}", window_id, e);
+            }
+        }
+        Ok(pane_id)
     }
 
     #[tauri::command]
-    async fn stop_wezterm_mirror(
-        mirror_id: String,
+    async fn resize_wezterm_pane(
+        pane_id: String,
+        direction: PaneDirection,
+        amount: u16,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        let mirror_manager = state.wezterm_mirror_manager.lock().await;
-        mirror_manager.stop_mirror(&mirror_id).await
+        state.wezterm_controller.resize_pane(&pane_id, direction, amount).await
     }
 
     #[tauri::command]
-    async fn send_input_to_mirror(
-        mirror_id: String,
-        text: String,
+    async fn activate_wezterm_pane(
+        pane_id: String,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        let mirror_manager = state.wezterm_mirror_manager.lock().await;
-        mirror_manager.send_input(&mirror_id, &text).await
+        state.wezterm_controller.activate_pane(&pane_id).await
     }
 
     #[tauri::command]
-    async fn get_mirror_content(
-        mirror_id: String,
+    async fn list_project_wezterm_windows(
+        project_id: String,
         state: State<'_, AppState>,
-    ) -> Result<String, String> {
-        let mirror_manager = state.wezterm_mirror_manager.lock().await;
-        mirror_manager.get_mirror_content(&mirror_id).await
+    ) -> Result<Vec<WezTermWindow>, String> {
+        state.wezterm_controller.list_project_windows(&project_id).await
     }
 
     #[tauri::command]
-    async fn list_mirrors(
+    async fn close_wezterm_window(
+        window_id: String,
         state: State<'_, AppState>,
-    ) -> Result<Vec<WezTermMirror>, String> {
-        let mirror_manager = state.wezterm_mirror_manager.lock().await;
-        Ok(mirror_manager.list_mirrors().await)
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let result = state.wezterm_controller.close_window(&window_id).await;
+
+        if let Err(e) = db.with_connection(|conn| crate::database::wezterm::delete_window(conn, &window_id)) {
+            println!("Failed to forget WezTerm window {}: {}", window_id, e);
+        }
+        result
     }
 
-    // Tmux Commands
     #[tauri::command]
-    async fn create_tmux_session(
-        project_path: String,
+    async fn send_text_to_wezterm(
+        window_id: String,
+        text: String,
         state: State<'_, AppState>,
-    ) -> Result<TmuxSession, String> {
-        let tmux_manager = state.tmux_manager.lock().await;
-        tmux_manager.create_session(&project_path).await
+    ) -> Result<(), String> {
+        state.wezterm_controller.send_text_to_window(&window_id, &text).await
     }
 
     #[tauri::command]
-    async fn kill_tmux_session(
-        session_id: String,
+    async fn send_key_to_window(
+        window_id: String,
+        key: String,
+        modifiers: Option<Vec<KeyModifier>>,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        let tmux_manager = state.tmux_manager.lock().await;
-        tmux_manager.kill_session(&session_id).await
+        state.wezterm_controller.send_key_to_window(&window_id, &key, &modifiers.unwrap_or_default()).await
     }
 
     #[tauri::command]
-    async fn send_tmux_keys(
-        session_id: String,
-        keys: String,
+    async fn execute_command_in_wezterm(
+        window_id: String,
+        command: String,
         state: State<'_, AppState>,
-    ) -> Result<(), String> {
-        let tmux_manager = state.tmux_manager.lock().await;
-        tmux_manager.send_keys(&session_id, &keys).await
+    ) -> Result<String, String> {
+        state.wezterm_controller.execute_command_with_output(&window_id, &command).await
     }
 
     #[tauri::command]
-    async fn send_tmux_command(
-        session_id: String,
-        command: String,
+    async fn focus_wezterm_window(
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        let tmux_manager = state.tmux_manager.lock().await;
-        tmux_manager.send_command(&session_id, &command).await
+        state.wezterm_controller.focus_wezterm_window().await
     }
 
+    /// Close all windows and mirrors of a project and forget panes that
+    /// disappeared on their own
     #[tauri::command]
-    async fn capture_tmux_pane(
-        session_id: String,
+    async fn close_project_terminals(
+        project_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<ProjectTerminalCleanup, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?;
+
+        teardown_project_terminals(&state, &db, &project_id, project.as_ref().map(|p| p.path.as_str())).await
+    }
+
+    /// Delete a project and close everything running in it: WezTerm
+    /// windows, PTY terminals, tmux sessions and OpenCode servers. A dry run
+    /// only reports what would be affected.
+    #[tauri::command]
+    async fn delete_project(
+        id: String,
+        options: Option<DeleteProjectOptions>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<ProjectDeletionReport, String> {
+        let options = options.unwrap_or_default();
+        let manager = crate::projects::manager::ProjectsManager::new(&db);
+        let sessions = crate::plugins::sessions::PluginSessionManager::new(&db);
+        let project = manager.get(&id).map_err(|e| e.to_string())?;
+        let path = project.as_ref().map(|p| p.path.as_str());
+
+        let mut report = if options.keep_processes {
+            ProjectDeletionReport::default()
+        } else {
+            project_processes(&state, &id, path).await
+        };
+        report.dry_run = options.dry_run;
+        report.plugin_sessions = sessions
+            .list_by_project(&id, None)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|session| session.id)
+            .collect();
+
+        if options.dry_run {
+            report.archived = project.is_some() && options.archive_history;
+            report.deleted = project.is_some() && !options.archive_history;
+            return Ok(report);
+        }
+
+        if !options.keep_processes {
+            match teardown_project_terminals(&state, &db, &id, path).await {
+                Ok(cleanup) => {
+                    report.wezterm_windows = cleanup.closed_windows;
+                    report.wezterm_mirrors = cleanup.stopped_mirrors;
+                    report.terminals = cleanup.killed_terminals;
+                }
+                Err(e) => println!("Failed to close terminals for project {}: {}", id, e),
+            }
+            stop_project_processes(&state, &db, &report).await;
+        }
+
+        state.repo_watcher.unwatch(&id);
+
+        if options.archive_history {
+            for session_id in &report.plugin_sessions {
+                sessions.archive(session_id).map_err(|e| e.to_string())?;
+            }
+            report.archived = manager.set_archived(&id, true).map_err(|e| e.to_string())?.is_some();
+            return Ok(report);
+        }
+
+        // Secrets live in the keychain, outside the cascade
+        let secrets = project.as_ref().and_then(|p| p.settings.as_ref()).map(|s| s.secret_env.clone()).unwrap_or_default();
+        for name in secrets {
+            if let Err(e) = crate::projects::env::delete_secret(&id, &name) {
+                println!("Failed to delete secret {} for project {}: {}", name, id, e);
+            }
+        }
+
+        report.deleted = manager.delete(&id).map_err(|e| e.to_string())?;
+        Ok(report)
+    }
+
+    /// Windows, mirrors, terminals, tmux sessions and running OpenCode
+    /// servers belonging to a project
+    async fn project_processes(state: &AppState, project_id: &str, project_path: Option<&str>) -> ProjectDeletionReport {
+        use crate::pty::manager::path_within;
+
+        let within = |dir: Option<&str>| match (dir, project_path) {
+            (Some(dir), Some(path)) => path_within(dir, path),
+            _ => false,
+        };
+
+        let wezterm_windows = state.wezterm_controller.list_project_windows(project_id).await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|w| w.window_id)
+            .collect();
+        let wezterm_mirrors = state.wezterm_mirror_manager.lock().await.list_mirrors().await
+            .into_iter()
+            .filter(|m| project_path.is_some_and(|path| m.project_path.trim_end_matches('/') == path.trim_end_matches('/')))
+            .map(|m| m.id)
+            .collect();
+        let terminals = state.pty_manager.lock().unwrap().list_terminals_sync()
+            .into_iter()
+            .filter(|t| within(t.cwd.as_deref()))
+            .map(|t| t.id)
+            .collect();
+        let tmux_sessions = state.tmux_manager.lock().await.list_sessions().await
+            .into_iter()
+            .filter(|s| within(Some(&s.project_path)))
+            .map(|s| s.id)
+            .collect();
+        let servers = state.opencode_service.list_servers().await
+            .into_iter()
+            .filter(|s| s.status != ServerStatus::Stopped && within(s.working_dir.as_deref()))
+            .map(|s| s.id)
+            .collect();
+        let dev_servers = state.dev_server_manager.list(None).await
+            .into_iter()
+            // A crashed server waiting to restart would come back otherwise
+            .filter(|s| matches!(s.status, DevServerStatus::Running | DevServerStatus::Restarting))
+            .filter(|s| s.project_id.as_deref() == Some(project_id) || within(Some(&s.working_dir)))
+            .map(|s| s.id)
+            .collect();
+
+        ProjectDeletionReport {
+            wezterm_windows,
+            wezterm_mirrors,
+            terminals,
+            tmux_sessions,
+            servers,
+            dev_servers,
+            ..Default::default()
+        }
+    }
+
+    /// Kill the tmux sessions and stop the servers listed in `report`.
+    /// Sessions attached from outside the app are detached, not killed.
+    async fn stop_project_processes(state: &AppState, db: &DatabaseManager, report: &ProjectDeletionReport) {
+        let tmux_manager = state.tmux_manager.lock().await;
+        let external: Vec<String> = tmux_manager.list_sessions().await
+            .into_iter()
+            .filter(|s| s.external)
+            .map(|s| s.id)
+            .collect();
+        for session_id in &report.tmux_sessions {
+            let result = if external.contains(session_id) {
+                tmux_manager.detach_session(session_id).await
+            } else {
+                tmux_manager.kill_session(session_id).await
+            };
+            match result {
+                Ok(()) => {
+                    if let Err(e) = db.with_connection(|conn| crate::database::tmux::delete_session(conn, session_id)) {
+                        println!("Failed to forget tmux session {}: {}", session_id, e);
+                    }
+                }
+                Err(e) => println!("Failed to close tmux session {}: {}", session_id, e),
+            }
+        }
+        drop(tmux_manager);
+
+        for server_id in &report.servers {
+            if let Err(e) = state.opencode_service.stop_server(server_id).await {
+                println!("Failed to stop server {}: {}", server_id, e);
+            }
+        }
+
+        for server_id in &report.dev_servers {
+            if let Err(e) = state.dev_server_manager.stop(server_id).await {
+                println!("Failed to stop dev server {}: {}", server_id, e);
+            }
+        }
+    }
+
+    async fn teardown_project_terminals(
+        state: &AppState,
+        db: &DatabaseManager,
+        project_id: &str,
+        project_path: Option<&str>,
+    ) -> Result<ProjectTerminalCleanup, String> {
+        let closed_windows = state.wezterm_controller.close_project_windows(project_id).await;
+        let pruned_windows = state.wezterm_controller.prune_orphaned_windows().await;
+
+        let (stopped_mirrors, pruned_mirrors) = {
+            let mirror_manager = state.wezterm_mirror_manager.lock().await;
+            let stopped = match project_path {
+                Some(path) => mirror_manager.stop_project_mirrors(path).await,
+                None => Vec::new(),
+            };
+            (stopped, mirror_manager.prune_orphaned_mirrors().await)
+        };
+
+        db.with_connection(|conn| {
+            for window_id in closed_windows.iter().chain(&pruned_windows) {
+                crate::database::wezterm::delete_window(conn, window_id)?;
+            }
+            for mirror_id in stopped_mirrors.iter().chain(&pruned_mirrors) {
+                crate::database::wezterm::delete_mirror(conn, mirror_id)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| e.to_string())?;
+
+        let killed_terminals = match project_path {
+            Some(path) => state.pty_manager.lock().unwrap().kill_project_terminals_sync(path),
+            None => Vec::new(),
+        };
+
+        Ok(ProjectTerminalCleanup {
+            closed_windows,
+            stopped_mirrors,
+            pruned_windows,
+            pruned_mirrors,
+            killed_terminals,
+        })
+    }
+
+    #[tauri::command]
+    async fn list_all_wezterm_windows(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<WezTermWindow>, String> {
+        state.wezterm_controller.list_all_windows().await
+    }
+
+    /// Reattach persisted WezTerm windows and mirrors whose panes survived,
+    /// and forget the rest
+    async fn restore_wezterm_state(handle: &tauri::AppHandle) {
+        use crate::database::wezterm;
+
+        let db = handle.state::<DatabaseManager>();
+        let state: State<AppState> = handle.state();
+
+        let saved_windows = db.with_connection(wezterm::list_windows).unwrap_or_default();
+        let saved_mirrors = db.with_connection(wezterm::list_mirrors).unwrap_or_default();
+        if saved_windows.is_empty() && saved_mirrors.is_empty() {
+            return;
+        }
+
+        // Without WezTerm there's no telling which panes survived, so keep
+        // everything for the next start
+        if !state.wezterm_controller.cli().is_running().await {
+            println!("WezTerm is not running, keeping {} saved window(s) and {} mirror(s)",
+                saved_windows.len(), saved_mirrors.len());
+            return;
+        }
+
+        let windows = match state.wezterm_controller.restore_windows(saved_windows.clone()).await {
+            Ok(windows) => windows,
+            Err(e) => {
+                println!("Failed to list WezTerm panes, keeping saved windows: {}", e);
+                return;
+            }
+        };
+        let mirrors = match state.wezterm_mirror_manager.lock().await.restore_mirrors(saved_mirrors.clone()).await {
+            Ok(mirrors) => mirrors,
+            Err(e) => {
+                println!("Failed to list WezTerm panes, keeping saved mirrors: {}", e);
+                return;
+            }
+        };
+        println!("Reattached {} WezTerm window(s) and {} mirror(s)", windows.len(), mirrors.len());
+
+        // Drop rows whose pane is gone and re-save the rest, since a window's
+        // id can change between runs
+        let result = db.with_connection(|conn| {
+            for window in saved_windows.iter().filter(|w| !windows.iter().any(|r| r.window_id == w.window_id)) {
+                wezterm::delete_window(conn, &window.window_id)?;
+            }
+            for window in &windows {
+                wezterm::save_window(conn, window)?;
+            }
+            for mirror in saved_mirrors.iter().filter(|m| !mirrors.iter().any(|r| r.id == m.id)) {
+                wezterm::delete_mirror(conn, &mirror.id)?;
+            }
+            for mirror in &mirrors {
+                wezterm::save_mirror(conn, mirror)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            println!("Failed to update saved WezTerm state: {}", e);
+        }
+    }
+
+    /// Whether WezTerm is installed and which CLI features it supports
+    #[tauri::command]
+    async fn get_wezterm_status(
+        refresh: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<WezTermStatus, String> {
+        let cli = state.wezterm_controller.cli();
+        match cli.status() {
+            Some(status) if !refresh.unwrap_or(false) => Ok(status),
+            _ => Ok(cli.probe().await),
+        }
+    }
+
+    // WezTerm Mirror Commands
+    #[tauri::command]
+    async fn start_wezterm_mirror(
+        project_path: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<WezTermMirror, String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        let mirror = mirror_manager.create_mirror(&project_path).await?;
+
+        if let Err(e) = db.with_connection(|conn| crate::database::wezterm::save_mirror(conn, &mirror)) {
+            println!("Failed to persist mirror {}: {}", mirror.id, e);
+        }
+        Ok(mirror)
+    }
+
+    #[tauri::command]
+    async fn stop_wezterm_mirror(
+        mirror_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        mirror_manager.stop_mirror(&mirror_id).await?;
+
+        db.with_connection(|conn| crate::database::wezterm::delete_mirror(conn, &mirror_id))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_input_to_mirror(
+        mirror_id: String,
+        text: String,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        mirror_manager.send_input(&mirror_id, &text).await
+    }
+
+    #[tauri::command]
+    async fn resize_mirror(
+        mirror_id: String,
+        cols: u16,
+        rows: u16,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<CliPaneSize, String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        let size = mirror_manager.resize_mirror(&mirror_id, cols, rows).await?;
+
+        if let Some(mirror) = mirror_manager.get_mirror(&mirror_id).await {
+            if let Err(e) = db.with_connection(|conn| crate::database::wezterm::save_mirror(conn, &mirror)) {
+                println!("Failed to persist mirror {}: {}", mirror_id, e);
+            }
+        }
+        Ok(size)
+    }
+
+    #[tauri::command]
+    async fn get_mirror_content(
+        mirror_id: String,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        mirror_manager.get_mirror_content(&mirror_id).await
+    }
+
+    #[tauri::command]
+    async fn get_mirror_scrollback(
+        mirror_id: String,
+        offset: u32,
+        lines: u32,
+        state: State<'_, AppState>,
+    ) -> Result<MirrorScrollback, String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        mirror_manager.get_mirror_scrollback(&mirror_id, offset, lines).await
+    }
+
+    #[tauri::command]
+    async fn list_mirrors(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<WezTermMirror>, String> {
+        let mirror_manager = state.wezterm_mirror_manager.lock().await;
+        Ok(mirror_manager.list_mirrors().await)
+    }
+
+    /// Re-register persisted tmux sessions that still exist and forget the rest
+    async fn restore_tmux_sessions(handle: &tauri::AppHandle) {
+        use crate::database::tmux;
+
+        let db = handle.state::<DatabaseManager>();
+        let state: State<AppState> = handle.state();
+
+        let saved = db.with_connection(tmux::list_sessions).unwrap_or_default();
+        if saved.is_empty() {
+            return;
+        }
+
+        let restored = state.tmux_manager.lock().await.restore_sessions(saved.clone()).await;
+        println!("Restored {} of {} tmux session(s)", restored.len(), saved.len());
+
+        let result = db.with_connection(|conn| {
+            for session in saved.iter().filter(|s| !restored.iter().any(|r| r.id == s.id)) {
+                tmux::delete_session(conn, &session.id)?;
+            }
+            for session in &restored {
+                tmux::save_session(conn, session)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = result {
+            println!("Failed to update saved tmux sessions: {}", e);
+        }
+    }
+
+    // Tmux Commands
+    #[tauri::command]
+    async fn create_tmux_session(
+        project_path: String,
+        host: Option<String>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<TmuxSession, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        let session = tmux_manager.create_session(&project_path, host.as_deref()).await?;
+
+        if let Err(e) = db.with_connection(|conn| crate::database::tmux::save_session(conn, &session)) {
+            println!("Failed to persist tmux session {}: {}", session.id, e);
+        }
+        Ok(session)
+    }
+
+    #[tauri::command]
+    async fn kill_tmux_session(
+        session_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.kill_session(&session_id).await?;
+
+        db.with_connection(|conn| crate::database::tmux::delete_session(conn, &session_id))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_tmux_keys(
+        session_id: String,
+        keys: String,
+        pane_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.send_keys(&session_id, pane_id.as_deref(), &keys).await
+    }
+
+    #[tauri::command]
+    async fn send_tmux_special_keys(
+        session_id: String,
+        keys: Vec<TmuxKey>,
+        pane_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.send_special_keys(&session_id, pane_id.as_deref(), &keys).await
+    }
+
+    #[tauri::command]
+    async fn send_tmux_command(
+        session_id: String,
+        command: String,
+        pane_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.send_command(&session_id, pane_id.as_deref(), &command).await
+    }
+
+    #[tauri::command]
+    async fn capture_tmux_pane(
+        session_id: String,
+        pane_id: Option<String>,
+        options: Option<CaptureOptions>,
+        state: State<'_, AppState>,
+    ) -> Result<PaneCapture, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        let options = options.unwrap_or_default();
+        tmux_manager.capture_pane(&session_id, pane_id.as_deref(), &options).await
+    }
+
+    #[tauri::command]
+    async fn search_tmux_pane(
+        session_id: String,
+        pattern: String,
+        max_matches: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<PaneSearchMatch>, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.search_pane(&session_id, &pattern, max_matches.unwrap_or(50)).await
+    }
+
+    #[tauri::command]
+    async fn get_tmux_truncated_history(
+        session_id: String,
+        max_bytes: Option<usize>,
         state: State<'_, AppState>,
     ) -> Result<String, String> {
         let tmux_manager = state.tmux_manager.lock().await;
-        tmux_manager.capture_pane(&session_id).await
+        tmux_manager.get_truncated_history(&session_id, max_bytes).await
+    }
+
+    #[tauri::command]
+    async fn set_tmux_history_limit(
+        bytes: usize,
+        state: State<'_, AppState>,
+    ) -> Result<usize, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        Ok(tmux_manager.set_history_limit(bytes))
+    }
+
+    #[tauri::command]
+    async fn resize_tmux_session(
+        session_id: String,
+        cols: u16,
+        rows: u16,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.resize_session(&session_id, cols, rows).await?;
+
+        if let Some(session) = tmux_manager.list_sessions().await.into_iter().find(|s| s.id == session_id) {
+            if let Err(e) = db.with_connection(|conn| crate::database::tmux::save_session(conn, &session)) {
+                println!("Failed to persist tmux session {}: {}", session_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    #[tauri::command]
+    async fn create_tmux_window(
+        session_id: String,
+        name: Option<String>,
+        command: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<TmuxWindow, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.create_window(&session_id, name.as_deref(), command.as_deref()).await
+    }
+
+    #[tauri::command]
+    async fn split_tmux_pane(
+        session_id: String,
+        pane_id: Option<String>,
+        horizontal: bool,
+        percent: Option<u8>,
+        command: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<TmuxPane, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.split_pane(&session_id, pane_id.as_deref(), horizontal, percent, command.as_deref()).await
+    }
+
+    #[tauri::command]
+    async fn kill_tmux_pane(
+        session_id: String,
+        pane_id: String,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.kill_pane(&session_id, &pane_id).await
+    }
+
+    #[tauri::command]
+    async fn list_tmux_windows(
+        session_id: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TmuxWindow>, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.list_windows(&session_id).await
+    }
+
+    #[tauri::command]
+    async fn list_tmux_panes(
+        session_id: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TmuxPane>, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.list_panes(&session_id).await
+    }
+
+    #[tauri::command]
+    async fn discover_tmux_sessions(
+        host: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TmuxSessionInfo>, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.discover_sessions(host.as_deref()).await
+    }
+
+    #[tauri::command]
+    async fn attach_tmux_session(
+        name: String,
+        host: Option<String>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<TmuxSession, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        let session = tmux_manager.attach_session(&name, host.as_deref()).await?;
+
+        if let Err(e) = db.with_connection(|conn| crate::database::tmux::save_session(conn, &session)) {
+            println!("Failed to persist tmux session {}: {}", session.id, e);
+        }
+        Ok(session)
+    }
+
+    #[tauri::command]
+    async fn set_tmux_auto_restart(
+        session_id: String,
+        enabled: bool,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<TmuxSession, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        let session = tmux_manager.set_auto_restart(&session_id, enabled).await?;
+
+        if let Err(e) = db.with_connection(|conn| crate::database::tmux::save_session(conn, &session)) {
+            println!("Failed to persist tmux session {}: {}", session.id, e);
+        }
+        Ok(session)
+    }
+
+    #[tauri::command]
+    async fn detach_tmux_session(
+        session_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        tmux_manager.detach_session(&session_id).await?;
+
+        db.with_connection(|conn| crate::database::tmux::delete_session(conn, &session_id))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn list_tmux_sessions(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<TmuxSession>, String> {
+        let tmux_manager = state.tmux_manager.lock().await;
+        Ok(tmux_manager.list_sessions().await)
+    }
+
+    // Git Commands
+    #[tauri::command]
+    async fn get_git_diff(
+        file_path: Option<String>,
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<String, String> {
+        state.git_manager.diff(&working_dir, file_path.as_deref())
+    }
+
+    #[tauri::command]
+    async fn get_git_status(
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<GitStatus, String> {
+        state.git_manager.status_summary(&working_dir)
+    }
+
+    /// Longest staged diff sent to an agent when drafting a commit message
+    const COMMIT_DRAFT_DIFF_CHARS: usize = 20_000;
+
+    /// Commit `files`, or whatever is staged, with `message`. With
+    /// `draft_message` the staged diff goes to the active agent plugin
+    /// instead and its suggested message comes back for confirmation;
+    /// `files` are staged but nothing is committed.
+    #[tauri::command]
+    async fn git_commit(
+        working_dir: String,
+        message: Option<String>,
+        files: Option<Vec<String>>,
+        draft_message: Option<bool>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<CommitOutcome, String> {
+        if let Some(files) = &files {
+            state.git_manager.stage(&working_dir, files)?;
+        }
+
+        if draft_message.unwrap_or(false) {
+            let diff = state.git_manager.staged_diff(&working_dir)?;
+            if diff.is_empty() {
+                return Err("Nothing staged to describe".to_string());
+            }
+            let diff: String = diff.chars().take(COMMIT_DRAFT_DIFF_CHARS).collect();
+            let prompt = format!(
+                "Write a git commit message for the following staged changes. \
+                 Use a short imperative summary line, then a blank line and a brief body if needed. \
+                 Reply with the message only.\n\n{}",
+                diff
+            );
+
+            let config = launch::resolve_for(&db, launch::LaunchRequest {
+                working_dir: Some(working_dir.clone()),
+                ..Default::default()
+            })?;
+            // Don't hold the plugin manager while the agent works
+            let pm = state.plugin_manager.lock().await.clone();
+            let message = pm.prompt(&prompt, Some(working_dir), config.port).await?;
+            return Ok(CommitOutcome::Draft { message: message.trim().to_string() });
+        }
+
+        let message = message
+            .filter(|m| !m.trim().is_empty())
+            .ok_or_else(|| "Commit message cannot be empty".to_string())?;
+        let commit = state.git_manager.commit(&working_dir, &message)?;
+        Ok(CommitOutcome::Committed { commit })
+    }
+
+    /// Structured diff between two refs, or from a ref to the working tree
+    #[tauri::command]
+    async fn get_diff(
+        working_dir: String,
+        from_ref: String,
+        to_ref: Option<String>,
+        path: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<FileDiff>, String> {
+        state.git_manager.diff_refs(&working_dir, &from_ref, to_ref.as_deref(), path.as_deref())
+    }
+
+    #[tauri::command]
+    async fn git_log(
+        working_dir: String,
+        path: Option<String>,
+        limit: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<CommitInfo>, String> {
+        state.git_manager.log(&working_dir, path.as_deref(), limit.unwrap_or(50))
+    }
+
+    #[tauri::command]
+    async fn git_blame(
+        working_dir: String,
+        path: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<BlameHunk>, String> {
+        state.git_manager.blame(&working_dir, &path)
+    }
+
+    #[tauri::command]
+    async fn git_stash_save(
+        working_dir: String,
+        message: Option<String>,
+        include_untracked: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<Option<StashEntry>, String> {
+        state
+            .git_manager
+            .stash_save(&working_dir, message.as_deref(), include_untracked.unwrap_or(true))
+    }
+
+    #[tauri::command]
+    async fn list_git_stashes(
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<StashEntry>, String> {
+        state.git_manager.stashes(&working_dir)
+    }
+
+    #[tauri::command]
+    async fn git_stash_apply(
+        working_dir: String,
+        index: usize,
+        pop: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.stash_apply(&working_dir, index, pop.unwrap_or(false))
+    }
+
+    #[tauri::command]
+    async fn git_stash_drop(
+        working_dir: String,
+        index: usize,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.stash_drop(&working_dir, index)
+    }
+
+    /// Project secrets checked, in order, for an HTTPS token to push and pull with
+    const GIT_TOKEN_SECRETS: [&str; 2] = ["GIT_TOKEN", "GITHUB_TOKEN"];
+
+    fn git_token(db: &DatabaseManager, working_dir: &str) -> Option<String> {
+        let project = crate::projects::manager::ProjectsManager::new(db)
+            .get_by_path(working_dir)
+            .ok()
+            .flatten()?;
+        project_git_token(&project.id)
+    }
+
+    fn project_git_token(project_id: &str) -> Option<String> {
+        GIT_TOKEN_SECRETS
+            .iter()
+            .find_map(|name| crate::projects::env::get_secret(project_id, name).ok().flatten())
+    }
+
+    #[tauri::command]
+    async fn git_push(
+        app: tauri::AppHandle,
+        working_dir: String,
+        remote: Option<String>,
+        branch: Option<String>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let token = git_token(&db, &working_dir);
+        let git = state.git_manager.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let remote = remote.as_deref().unwrap_or("origin");
+            git.push(&working_dir, remote, branch.as_deref(), token, |progress| {
+                let _ = app.emit("git-progress", progress);
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    #[tauri::command]
+    async fn git_pull(
+        app: tauri::AppHandle,
+        working_dir: String,
+        remote: Option<String>,
+        branch: Option<String>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<MergeOutcome, String> {
+        let token = git_token(&db, &working_dir);
+        let git = state.git_manager.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let remote = remote.as_deref().unwrap_or("origin");
+            git.pull(&working_dir, remote, branch.as_deref(), token, |progress| {
+                let _ = app.emit("git-progress", progress);
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    #[tauri::command]
+    async fn get_git_file_hunks(
+        working_dir: String,
+        path: String,
+        staged: bool,
+        state: State<'_, AppState>,
+    ) -> Result<Option<FileDiff>, String> {
+        state.git_manager.file_hunks(&working_dir, &path, staged)
+    }
+
+    #[tauri::command]
+    async fn stage_git_hunks(
+        working_dir: String,
+        path: String,
+        selection: Vec<crate::git::HunkSelection>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.stage_hunks(&working_dir, &path, &selection)
+    }
+
+    #[tauri::command]
+    async fn unstage_git_hunks(
+        working_dir: String,
+        path: String,
+        selection: Vec<crate::git::HunkSelection>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.unstage_hunks(&working_dir, &path, &selection)
+    }
+
+    #[tauri::command]
+    async fn detect_git_conflicts(
+        working_dir: String,
+        target_branch: String,
+        state: State<'_, AppState>,
+    ) -> Result<crate::git::ConflictReport, String> {
+        state.git_manager.detect_conflicts(&working_dir, &target_branch)
+    }
+
+    /// Merge an agent's branch into the current checkout. If it would
+    /// conflict nothing is merged and Slack is told instead.
+    #[tauri::command]
+    async fn merge_git_branch(
+        working_dir: String,
+        branch: String,
+        project_name: Option<String>,
+        session_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<MergeOutcome, String> {
+        let outcome = state.git_manager.merge_branch(&working_dir, &branch)?;
+        if let MergeOutcome::Conflicted { files } = &outcome {
+            let paths: Vec<&str> = files.iter().map(|f| f.path.as_str()).collect();
+            let alert = SlackErrorAlert {
+                title: format!("Merge of {} blocked by conflicts", branch),
+                error: format!("{} conflicting file(s): {}", files.len(), paths.join(", ")),
+                project_name,
+                session_id,
+            };
+            if let Err(e) = state.slack_service.send_error_alert(alert).await {
+                println!("Failed to report merge conflicts to Slack: {}", e);
+            }
+        }
+        Ok(outcome)
+    }
+
+    /// Push a completed task's branch and open a GitHub pull request for it,
+    /// using the project's GITHUB_TOKEN (or GIT_TOKEN) secret
+    #[tauri::command]
+    async fn create_github_pull_request(
+        app: tauri::AppHandle,
+        request: crate::github::PullRequestRequest,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::github::PullRequest, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&request.project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", request.project_id))?;
+        let settings = project.settings.clone().unwrap_or_default();
+        let token = crate::projects::env::get_secret(&project.id, "GITHUB_TOKEN")
+            .ok()
+            .flatten()
+            .or_else(|| project_git_token(&project.id))
+            .ok_or_else(|| "Add a GITHUB_TOKEN secret to the project to open pull requests".to_string())?;
+
+        let repo = match settings.github_repo.clone() {
+            Some(repo) => repo,
+            None => state
+                .git_manager
+                .remote_url(&project.path, "origin")?
+                .as_deref()
+                .and_then(crate::github::parse_repo)
+                .ok_or_else(|| "Set the project's GitHub repository; origin is not a GitHub remote".to_string())?,
+        };
+
+        let git = state.git_manager.clone();
+        let (path, branch, push_token) = (project.path.clone(), request.branch.clone(), token.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            git.push(&path, "origin", Some(&branch), Some(push_token), |progress| {
+                let _ = app.emit("git-progress", progress);
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        let github = crate::github::GitHubApiClient::new();
+        let base = match request.base.clone().or(settings.github_base_branch.clone()) {
+            Some(base) => base,
+            None => github.default_branch(&token, &repo).await.map_err(|e| e.to_string())?,
+        };
+
+        let files = state
+            .git_manager
+            .branch_changes(&project.path, &format!("origin/{}", base), &request.branch)
+            .or_else(|_| state.git_manager.branch_changes(&project.path, &base, &request.branch))
+            .unwrap_or_else(|e| {
+                println!("Could not list changes of {} against {}: {}", request.branch, base, e);
+                Vec::new()
+            });
+        let session = request.session_id.as_deref().map(|id| {
+            match crate::plugins::sessions::PluginSessionManager::new(&db).get(id).ok().flatten() {
+                Some(session) => format!("{} ({} · {}, {})", session.title, session.plugin_id, session.model, session.id),
+                None => id.to_string(),
+            }
+        });
+
+        let title = request.title.clone().unwrap_or_else(|| crate::github::title_from_prompt(&request.prompt));
+        let body = crate::github::pull_request_body(&request.prompt, &files, session.as_deref(), request.session_url.as_deref());
+        let pull_request = github
+            .create_pull_request(&token, &repo, &request.branch, &base, &title, &body, request.draft)
+            .await
+            .map_err(|e| e.to_string())?;
+        println!("Opened pull request #{} for {} on {}", pull_request.number, request.branch, repo);
+        Ok(pull_request)
+    }
+
+    /// Push a completed task's branch and open a GitLab merge request for
+    /// it, using the project's GITLAB_TOKEN (or GIT_TOKEN) secret
+    #[tauri::command]
+    async fn create_gitlab_merge_request(
+        app: tauri::AppHandle,
+        request: crate::gitlab::MergeRequestRequest,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::gitlab::MergeRequest, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&request.project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", request.project_id))?;
+        let settings = project.settings.clone().unwrap_or_default();
+        let token = crate::projects::env::get_secret(&project.id, "GITLAB_TOKEN")
+            .ok()
+            .flatten()
+            .or_else(|| crate::projects::env::get_secret(&project.id, "GIT_TOKEN").ok().flatten())
+            .ok_or_else(|| "Add a GITLAB_TOKEN secret to the project to open merge requests".to_string())?;
+
+        let gitlab = match settings.gitlab_url.as_deref() {
+            Some(url) => crate::gitlab::GitLabApiClient::with_instance_url(url),
+            None => crate::gitlab::GitLabApiClient::new(),
+        };
+        let host = crate::gitlab::instance_host(settings.gitlab_url.as_deref().unwrap_or("gitlab.com"));
+        let gitlab_project = match settings.gitlab_project.clone() {
+            Some(gitlab_project) => gitlab_project,
+            None => state
+                .git_manager
+                .remote_url(&project.path, "origin")?
+                .and_then(|url| crate::gitlab::parse_project(&url, host))
+                .ok_or_else(|| format!("Set the project's GitLab project; origin is not a {} remote", host))?,
+        };
+
+        let git = state.git_manager.clone();
+        let (path, branch, push_token) = (project.path.clone(), request.branch.clone(), token.clone());
+        tauri::async_runtime::spawn_blocking(move || {
+            git.push(&path, "origin", Some(&branch), Some(push_token), |progress| {
+                let _ = app.emit("git-progress", progress);
+            })
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+
+        let target = match request.target.clone().or(settings.gitlab_target_branch.clone()) {
+            Some(target) => target,
+            None => gitlab.default_branch(&token, &gitlab_project).await.map_err(|e| e.to_string())?,
+        };
+
+        let files = state
+            .git_manager
+            .branch_changes(&project.path, &format!("origin/{}", target), &request.branch)
+            .or_else(|_| state.git_manager.branch_changes(&project.path, &target, &request.branch))
+            .unwrap_or_else(|e| {
+                println!("Could not list changes of {} against {}: {}", request.branch, target, e);
+                Vec::new()
+            });
+        let session = request.session_id.as_deref().map(|id| {
+            match crate::plugins::sessions::PluginSessionManager::new(&db).get(id).ok().flatten() {
+                Some(session) => format!("{} ({} · {}, {})", session.title, session.plugin_id, session.model, session.id),
+                None => id.to_string(),
+            }
+        });
+
+        let title = request.title.clone().unwrap_or_else(|| crate::github::title_from_prompt(&request.prompt));
+        let template = settings.gitlab_mr_template.as_deref().unwrap_or(crate::gitlab::DEFAULT_DESCRIPTION_TEMPLATE);
+        let description = crate::gitlab::description_from_template(
+            template,
+            &title,
+            &request.prompt,
+            &request.branch,
+            &files,
+            session.as_deref(),
+            request.session_url.as_deref(),
+        );
+        let merge_request = gitlab
+            .create_merge_request(&token, &gitlab_project, &request.branch, &target, &title, &description, request.draft)
+            .await
+            .map_err(|e| e.to_string())?;
+        println!("Opened merge request !{} for {} on {}", merge_request.iid, request.branch, gitlab_project);
+        Ok(merge_request)
+    }
+
+    /// Jira client and credentials from the project's Jira settings and
+    /// JIRA_API_TOKEN secret
+    fn project_jira(db: &DatabaseManager, project_id: &str) -> Result<(crate::jira::JiraApiClient, crate::jira::JiraAuth, Option<String>), String> {
+        let project = crate::projects::manager::ProjectsManager::new(db)
+            .get(project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        crate::jira::project_client(&project)
+    }
+
+    /// Unresolved Jira issues assigned to the project's Jira account
+    #[tauri::command]
+    async fn list_jira_issues(
+        project_id: String,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Vec<crate::jira::JiraIssue>, String> {
+        let (jira, auth, project_key) = project_jira(&db, &project_id)?;
+        jira.assigned_issues(&auth, project_key.as_deref()).await.map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_jira_issue(
+        project_id: String,
+        issue_key: String,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::jira::JiraIssue, String> {
+        let (jira, auth, _) = project_jira(&db, &project_id)?;
+        jira.get_issue(&auth, &issue_key).await.map_err(|e| e.to_string())
+    }
+
+    /// Move a Jira issue by the name of a transition or of the status it
+    /// leads to, e.g. "In Progress"
+    #[tauri::command]
+    async fn transition_jira_issue(
+        project_id: String,
+        issue_key: String,
+        target: String,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::jira::JiraTransition, String> {
+        let (jira, auth, _) = project_jira(&db, &project_id)?;
+        let transitions = jira.transitions(&auth, &issue_key).await.map_err(|e| e.to_string())?;
+        let transition = crate::jira::find_transition(&transitions, &target).cloned().ok_or_else(|| {
+            let available: Vec<&str> = transitions.iter().map(|t| t.name.as_str()).collect();
+            format!("{} has no transition to {}; available: {}", issue_key, target, available.join(", "))
+        })?;
+        jira.transition(&auth, &issue_key, &transition.id).await.map_err(|e| e.to_string())?;
+        println!("Moved {} to {}", issue_key, transition.to_status);
+        Ok(transition)
+    }
+
+    /// Comment on a Jira issue, e.g. with an agent's results
+    #[tauri::command]
+    async fn comment_on_jira_issue(
+        project_id: String,
+        issue_key: String,
+        body: String,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let (jira, auth, _) = project_jira(&db, &project_id)?;
+        jira.add_comment(&auth, &issue_key, &body).await.map_err(|e| e.to_string())
+    }
+
+    /// The issue tracker the project's settings describe
+    async fn project_tracker(state: &AppState, db: &DatabaseManager, project_id: &str) -> Result<Arc<dyn IssueTracker>, String> {
+        let project = crate::projects::manager::ProjectsManager::new(db)
+            .get(project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let origin = state.git_manager.remote_url(&project.path, "origin").ok().flatten();
+        crate::issues::registry::from_settings(&project, origin.as_deref())
+    }
+
+    #[tauri::command]
+    async fn list_project_issues(
+        project_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Vec<Issue>, String> {
+        project_tracker(&state, &db, &project_id).await?.list_issues().await
+    }
+
+    #[tauri::command]
+    async fn get_project_issue(
+        project_id: String,
+        issue_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Issue, String> {
+        project_tracker(&state, &db, &project_id).await?.get_issue(&issue_id).await
+    }
+
+    #[tauri::command]
+    async fn comment_on_project_issue(
+        project_id: String,
+        issue_id: String,
+        body: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        project_tracker(&state, &db, &project_id).await?.comment(&issue_id, &body).await
+    }
+
+    #[tauri::command]
+    async fn transition_project_issue(
+        project_id: String,
+        issue_id: String,
+        issue_state: IssueState,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        project_tracker(&state, &db, &project_id).await?.transition(&issue_id, issue_state).await
+    }
+
+    /// Hand an issue from the project's tracker to an agent session; the
+    /// issue is commented on and moved along as the task goes
+    #[tauri::command]
+    async fn dispatch_project_issue(
+        project_id: String,
+        issue_id: String,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<String, String> {
+        let tracker = project_tracker(&state, &db, &project_id).await?;
+        let issue = tracker.get_issue(&issue_id).await?;
+        let task_id = crate::issues::pipeline::start(tracker.as_ref(), &state.session_manager, &issue).await?;
+
+        let session_manager = state.session_manager.clone();
+        let follow_id = task_id.clone();
+        tauri::async_runtime::spawn(async move {
+            crate::issues::pipeline::follow(tracker.as_ref(), &session_manager, &issue, &follow_id).await;
+        });
+        Ok(task_id)
+    }
+
+    /// Emit `repo-changed` events as files in the project's folder change
+    #[tauri::command]
+    async fn watch_project_repo(
+        app: tauri::AppHandle,
+        project_id: String,
+        debounce_ms: Option<u64>,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<(), String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let debounce = debounce_ms.map(std::time::Duration::from_millis).unwrap_or(crate::git::watcher::DEFAULT_DEBOUNCE);
+        state.repo_watcher.watch(app, &project.id, &project.path, debounce).await
+    }
+
+    #[tauri::command]
+    async fn unwatch_project_repo(
+        project_id: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        Ok(state.repo_watcher.unwatch(&project_id))
+    }
+
+    #[tauri::command]
+    async fn get_git_current_branch(
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<Option<String>, String> {
+        state.git_manager.current_branch(&working_dir)
+    }
+
+    #[tauri::command]
+    async fn list_git_branches(
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<BranchInfo>, String> {
+        state.git_manager.branches(&working_dir)
+    }
+
+    #[tauri::command]
+    async fn create_git_branch(
+        working_dir: String,
+        name: String,
+        checkout: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.create_branch(&working_dir, &name, checkout.unwrap_or(false))
+    }
+
+    #[tauri::command]
+    async fn checkout_git_branch(
+        working_dir: String,
+        name: String,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.checkout_branch(&working_dir, &name)
+    }
+
+    #[tauri::command]
+    async fn create_git_worktree(
+        working_dir: String,
+        name: String,
+        branch: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<WorktreeInfo, String> {
+        state.git_manager.create_worktree(&working_dir, &name, branch.as_deref())
+    }
+
+    #[tauri::command]
+    async fn list_git_worktrees(
+        working_dir: String,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<WorktreeInfo>, String> {
+        state.git_manager.list_worktrees(&working_dir)
+    }
+
+    #[tauri::command]
+    async fn remove_git_worktree(
+        working_dir: String,
+        name: String,
+        force: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.git_manager.remove_worktree(&working_dir, &name, force.unwrap_or(false))
+    }
+
+    // Browser Automation
+    /// Open `url` in a managed browser window
+    #[tauri::command]
+    async fn open_browser(url: String, app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<BrowserSession, String> {
+        state.browser_manager.launch(app_handle, Some(&url), false).await
+    }
+
+    #[tauri::command]
+    async fn launch_playwright_browser(
+        url: String,
+        headless: bool,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<BrowserSession, String> {
+        state.browser_manager.start(app_handle, Some(&url), headless).await
+    }
+
+    #[tauri::command]
+    async fn launch_browser_session(
+        url: Option<String>,
+        headless: Option<bool>,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<BrowserSession, String> {
+        state.browser_manager.start(app_handle, url.as_deref(), headless.unwrap_or(true)).await
+    }
+
+    #[tauri::command]
+    async fn list_browser_sessions(state: State<'_, AppState>) -> Result<Vec<BrowserSession>, String> {
+        Ok(state.browser_manager.list().await)
+    }
+
+    #[tauri::command]
+    async fn navigate_browser_session(session_id: String, url: String, state: State<'_, AppState>) -> Result<BrowserSession, String> {
+        state.browser_manager.navigate(&session_id, &url).await
+    }
+
+    /// Run JavaScript in the session's page and return its value
+    #[tauri::command]
+    async fn evaluate_in_browser_session(
+        session_id: String,
+        expression: String,
+        state: State<'_, AppState>,
+    ) -> Result<serde_json::Value, String> {
+        state.browser_manager.evaluate(&session_id, &expression).await
     }
 
     #[tauri::command]
-    async fn list_tmux_sessions(
+    async fn browser_click(
+        session_id: String,
+        selector: String,
+        has_text: Option<String>,
         state: State<'_, AppState>,
-    ) -> Result<Vec<TmuxSession>, String> {
-        let tmux_manager = state.tmux_manager.lock().await;
-        Ok(tmux_manager.list_sessions().await)
+    ) -> Result<(), String> {
+        state.browser_manager.click(&session_id, &selector, has_text.as_deref()).await
     }
 
-    // Git Commands
     #[tauri::command]
-    async fn get_git_diff(
-        file_path: Option<String>,
-        working_dir: String,
-    ) -> Result<String, String> {
-        use std::process::Command;
-
-        let mut cmd = Command::new("git");
-        cmd.arg("diff");
+    async fn browser_type(
+        session_id: String,
+        selector: String,
+        text: String,
+        clear: Option<bool>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.browser_manager.type_text(&session_id, &selector, &text, clear.unwrap_or(false)).await
+    }
 
-        // Add specific file if provided
-        if let Some(path) = file_path {
-            cmd.arg("--").arg(path);
-        }
+    #[tauri::command]
+    async fn browser_press_key(session_id: String, key: String, state: State<'_, AppState>) -> Result<(), String> {
+        state.browser_manager.press_key(&session_id, &key).await
+    }
 
-        // Set working directory
-        cmd.current_dir(&working_dir);
+    #[tauri::command]
+    async fn browser_wait_for_selector(
+        session_id: String,
+        selector: String,
+        has_text: Option<String>,
+        timeout_ms: Option<u64>,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        let timeout = timeout_ms.map(std::time::Duration::from_millis);
+        state.browser_manager.wait_for(&session_id, &selector, has_text.as_deref(), timeout).await.map(|_| ())
+    }
 
-        // Execute git diff
-        let output = cmd.output()
-            .map_err(|e| format!("Failed to execute git diff: {}", e))?;
+    /// Run navigate/click/type/press/wait steps in a browser session,
+    /// stopping at the first that fails
+    #[tauri::command]
+    async fn run_browser_script(
+        session_id: String,
+        steps: Vec<BrowserStep>,
+        state: State<'_, AppState>,
+    ) -> Result<ScriptReport, String> {
+        state.browser_manager.run_script(&session_id, steps).await
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Git diff failed: {}", stderr));
-        }
+    /// Save a PNG of a browser session's page, or of `url` opened headlessly,
+    /// to a temp file
+    #[tauri::command]
+    async fn capture_screenshot(
+        url: Option<String>,
+        browser_session_id: Option<String>,
+        full_page: Option<bool>,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<Screenshot, String> {
+        state
+            .browser_manager
+            .capture(app_handle, url.as_deref(), browser_session_id.as_deref(), full_page.unwrap_or(false))
+            .await
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        Ok(stdout.to_string())
+    /// The text, cleaned HTML and accessibility outline of a browser
+    /// session's page, or of `url` opened headlessly
+    #[tauri::command]
+    async fn extract_page_content(
+        url: Option<String>,
+        browser_session_id: Option<String>,
+        selector: Option<String>,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<PageContent, String> {
+        state
+            .browser_manager
+            .extract(app_handle, url.as_deref(), browser_session_id.as_deref(), selector.as_deref())
+            .await
     }
 
+    /// Console messages and failed requests kept for a browser session
     #[tauri::command]
-    async fn get_git_changed_files(
-        working_dir: String,
-    ) -> Result<Vec<String>, String> {
-        use std::process::Command;
+    async fn get_browser_session_logs(session_id: String, state: State<'_, AppState>) -> Result<BrowserLogs, String> {
+        state.browser_manager.logs(&session_id).await
+    }
 
-        let mut cmd = Command::new("git");
-        cmd.args(&["diff", "--name-only"]);
-        cmd.current_dir(&working_dir);
+    #[tauri::command]
+    async fn clear_browser_session_logs(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
+        state.browser_manager.clear_logs(&session_id).await
+    }
 
-        let output = cmd.output()
-            .map_err(|e| format!("Failed to execute git diff: {}", e))?;
+    #[tauri::command]
+    async fn close_browser_session(session_id: String, state: State<'_, AppState>) -> Result<(), String> {
+        state.browser_manager.close(&session_id).await
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Git diff failed: {}", stderr));
+    // Dev Server Process Management
+    /// Start a tracked dev server, returning its process id
+    #[tauri::command]
+    #[allow(clippy::too_many_arguments)]
+    async fn spawn_dev_server(
+        command: String,
+        working_dir: String,
+        project_id: Option<String>,
+        restart_policy: Option<RestartPolicy>,
+        env_file: Option<String>,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<u32, String> {
+        let mut env = project_env_for(&db, Some(&working_dir));
+        if let Some(env_file) = env_file {
+            env.extend(crate::projects::env::read_dotenv(&std::path::Path::new(&working_dir).join(env_file))?);
         }
-
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let files: Vec<String> = stdout
-            .lines()
-            .filter(|line| !line.is_empty())
-            .map(|line| line.to_string())
-            .collect();
-
-        Ok(files)
+        let spec = DevServerSpec {
+            command,
+            working_dir,
+            project_id,
+            env,
+            restart_policy: restart_policy.unwrap_or_default(),
+            ..Default::default()
+        };
+        let server = state.dev_server_manager.start(app_handle, spec).await?;
+        Ok(server.pid.unwrap_or_default())
     }
 
-    // Browser Automation
+    /// Start one of the project's dev server profiles, or return it if it's
+    /// already running
     #[tauri::command]
-    async fn open_browser(url: String) -> Result<(), String> {
-        use std::process::Command;
+    async fn start_dev_server_profile(
+        project_id: String,
+        name: String,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<DevServer, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let profile = crate::devserver::profiles::find(&project, &name)?;
+        if let Some(running) = state.dev_server_manager.find_profile(&project.id, &profile.name).await {
+            return Ok(running);
+        }
+        let spec = crate::devserver::profiles::spec(&project, profile)?;
+        state.dev_server_manager.start(app_handle, spec).await
+    }
 
-        // Get the path to the browser script
-        let resource_path = std::path::PathBuf::from("scripts/browser-launcher.ts");
+    /// Start every dev server profile of the project that isn't running
+    #[tauri::command]
+    async fn start_all_dev_servers(
+        project_id: String,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Vec<DevServer>, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let profiles = project.settings.as_ref().map(|s| s.dev_servers.clone()).unwrap_or_default();
+
+        let mut servers = Vec::new();
+        for profile in &profiles {
+            if let Some(running) = state.dev_server_manager.find_profile(&project.id, &profile.name).await {
+                servers.push(running);
+                continue;
+            }
+            let spec = crate::devserver::profiles::spec(&project, profile)?;
+            servers.push(state.dev_server_manager.start(app_handle.clone(), spec).await?);
+        }
+        Ok(servers)
+    }
 
-        if !resource_path.exists() {
-            return Err(format!(
-                "Browser launcher script not found at {:?}",
-                resource_path
-            ));
+    /// Stop the server started from a project's profile. None if it wasn't
+    /// running.
+    #[tauri::command]
+    async fn stop_dev_server_profile(
+        project_id: String,
+        name: String,
+        state: State<'_, AppState>,
+    ) -> Result<Option<DevServer>, String> {
+        match state.dev_server_manager.find_profile(&project_id, &name).await {
+            Some(server) => state.dev_server_manager.stop(&server.id).await.map(Some),
+            None => Ok(None),
         }
+    }
 
-        // Launch browser using npx tsx
-        Command::new("npx")
-            .arg("tsx")
-            .arg(&resource_path)
-            .arg(&url)
-            .spawn()
-            .map_err(|e| format!("Failed to launch browser: {}", e))?;
+    #[tauri::command]
+    async fn list_dev_servers(
+        project_id: Option<String>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<DevServer>, String> {
+        Ok(state.dev_server_manager.list(project_id.as_deref()).await)
+    }
 
-        Ok(())
+    #[tauri::command]
+    async fn stop_dev_server(
+        id: String,
+        state: State<'_, AppState>,
+    ) -> Result<DevServer, String> {
+        state.dev_server_manager.stop(&id).await
     }
 
     #[tauri::command]
-    async fn launch_playwright_browser(url: String, headless: bool) -> Result<(), String> {
-        use std::process::Command;
+    async fn restart_dev_server(
+        id: String,
+        app_handle: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<DevServer, String> {
+        state.dev_server_manager.restart(app_handle, &id).await
+    }
 
-        // Get the path to the browser script
-        let resource_path = std::path::PathBuf::from("scripts/browser-launcher.ts");
+    #[tauri::command]
+    async fn set_dev_server_restart_policy(
+        id: String,
+        policy: RestartPolicy,
+        state: State<'_, AppState>,
+    ) -> Result<DevServer, String> {
+        state.dev_server_manager.set_restart_policy(&id, policy).await
+    }
 
-        if !resource_path.exists() {
-            return Err(format!(
-                "Browser launcher script not found at {:?}",
-                resource_path
-            ));
-        }
+    /// Buffered output of a dev server, the last `lines` lines if given
+    #[tauri::command]
+    async fn get_dev_server_logs(
+        id: String,
+        lines: Option<usize>,
+        state: State<'_, AppState>,
+    ) -> Result<Vec<LogLine>, String> {
+        state.dev_server_manager.logs(&id, lines).await
+    }
 
-        // Launch browser using npx tsx with headless parameter
-        Command::new("npx")
-            .arg("tsx")
-            .arg(&resource_path)
-            .arg(&url)
-            .arg(headless.to_string())
-            .spawn()
-            .map_err(|e| format!("Failed to launch Playwright browser: {}", e))?;
+    #[tauri::command]
+    async fn clear_dev_server_logs(
+        id: String,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.dev_server_manager.clear_logs(&id).await
+    }
 
-        Ok(())
+    /// Stop a dev server if it's running and drop it from the list
+    #[tauri::command]
+    async fn remove_dev_server(
+        id: String,
+        state: State<'_, AppState>,
+    ) -> Result<bool, String> {
+        state.dev_server_manager.remove(&id).await
     }
 
-    // Dev Server Process Management
+    /// Run one of a project's saved commands: to completion in a shell, in
+    /// the project's tmux session, or as a background dev server
     #[tauri::command]
-    async fn spawn_dev_server(
-        command: String,
-        working_dir: String,
+    async fn execute_saved_command(
+        project_id: String,
+        name: String,
         app_handle: tauri::AppHandle,
-    ) -> Result<u32, String> {
-        use std::process::{Command, Stdio};
-
-        // Spawn the process in the background with piped stdout/stderr
-        let mut child = Command::new("sh")
-            .arg("-c")
-            .arg(&command)
-            .current_dir(&working_dir)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .map_err(|e| format!("Failed to spawn dev server: {}", e))?;
-
-        let pid = child.id();
-
-        // Stream stdout in a background task
-        if let Some(stdout) = child.stdout.take() {
-            use std::io::{BufRead, BufReader};
-            let app_handle_clone = app_handle.clone();
-            tokio::spawn(async move {
-                let reader = BufReader::new(stdout);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = app_handle_clone.emit("dev-server-output", line);
-                    }
-                }
-            });
-        }
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<SavedCommandRun, String> {
+        use crate::database::project_commands::{self, CommandTarget};
+
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+        let saved = db
+            .with_connection(|conn| project_commands::get_command(conn, &project_id, &name))
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Saved command not found: {}", name))?;
+
+        let mut run = SavedCommandRun {
+            target: saved.target,
+            output: None,
+            exit_code: None,
+            tmux_session_id: None,
+            dev_server_id: None,
+            pid: None,
+        };
 
-        // Stream stderr in a background task
-        if let Some(stderr) = child.stderr.take() {
-            use std::io::{BufRead, BufReader};
-            let app_handle_clone = app_handle.clone();
-            tokio::spawn(async move {
-                let reader = BufReader::new(stderr);
-                for line in reader.lines() {
-                    if let Ok(line) = line {
-                        let _ = app_handle_clone.emit("dev-server-error", line);
-                    }
+        match saved.target {
+            CommandTarget::Shell => {
+                let output = crate::devserver::shell::shell_command(&saved.command)
+                    .current_dir(&project.path)
+                    .envs(crate::projects::env::project_env(&project))
+                    .output()
+                    .await
+                    .map_err(|e| format!("Failed to run {}: {}", saved.name, e))?;
+
+                let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                run.output = Some(text);
+                run.exit_code = output.status.code();
+            }
+            CommandTarget::Tmux => {
+                let tmux_manager = state.tmux_manager.lock().await;
+                let session = tmux_manager.create_session(&project.path, None).await?;
+                if let Err(e) = db.with_connection(|conn| crate::database::tmux::save_session(conn, &session)) {
+                    println!("Failed to persist tmux session {}: {}", session.id, e);
                 }
-            });
+                tmux_manager.send_command(&session.id, None, &saved.command).await?;
+                run.tmux_session_id = Some(session.id);
+            }
+            CommandTarget::DevServer => {
+                let spec = DevServerSpec {
+                    env: crate::projects::env::project_env(&project),
+                    command: saved.command,
+                    working_dir: project.path,
+                    project_id: Some(project.id),
+                    ..Default::default()
+                };
+                let server = state.dev_server_manager.start(app_handle, spec).await?;
+                run.dev_server_id = Some(server.id);
+                run.pid = server.pid;
+            }
         }
 
-        // Wait for process to exit in background
-        tokio::spawn(async move {
-            let _ = child.wait();
-        });
-
-        Ok(pid)
+        Ok(run)
     }
 
     // Dev Server Terminal Spawning (legacy - opens external terminal)
@@ -691,6 +2506,10 @@ mod tauri_app {
     ) -> Result<u32, String> {
         use std::process::Command;
 
+        // Run the same way as tracked dev servers
+        #[cfg(not(target_os = "macos"))]
+        let invocation = crate::devserver::shell::invocation(&command);
+
         #[cfg(target_os = "macos")]
         {
             // Use osascript to spawn Terminal.app with the command
@@ -726,14 +2545,16 @@ end tell"#,
         {
             // Try common Linux terminal emulators
             let terminals = vec![
-                ("gnome-terminal", vec!["--working-directory", &working_dir, "--title", &title, "--", "bash", "-c", &command]),
-                ("konsole", vec!["--workdir", &working_dir, "-p", &format!("tabtitle={}", title), "-e", "bash", "-c", &command]),
-                ("xterm", vec!["-T", &title, "-e", &format!("cd {:?} && {}", working_dir, command)]),
+                ("gnome-terminal", vec!["--title".to_string(), title.clone(), "--".to_string()]),
+                ("konsole", vec!["-p".to_string(), format!("tabtitle={}", title), "-e".to_string()]),
+                ("xterm", vec!["-T".to_string(), title.clone(), "-e".to_string()]),
             ];
 
             for (terminal, args) in terminals {
-                if let Ok(mut child) = Command::new(terminal)
+                if let Ok(child) = Command::new(terminal)
                     .args(&args)
+                    .args(&invocation)
+                    .current_dir(&working_dir)
                     .spawn()
                 {
                     return Ok(child.id());
@@ -751,22 +2572,19 @@ end tell"#,
                 .arg(&working_dir)
                 .arg("--title")
                 .arg(&title)
-                .arg("cmd")
-                .arg("/k")
-                .arg(&command)
+                .args(&invocation)
                 .spawn();
 
             match result {
-                Ok(mut child) => Ok(child.id()),
+                Ok(child) => Ok(child.id()),
                 Err(_) => {
                     // Fallback to cmd
-                    let mut child = Command::new("cmd")
+                    let child = Command::new("cmd")
                         .arg("/c")
                         .arg("start")
                         .arg(&title)
-                        .arg("cmd")
-                        .arg("/k")
-                        .arg(&format!("cd /d {} && {}", working_dir, command))
+                        .args(&invocation)
+                        .current_dir(&working_dir)
                         .spawn()
                         .map_err(|e| format!("Failed to spawn terminal: {}", e))?;
 
@@ -781,73 +2599,239 @@ end tell"#,
         }
     }
 
-    // Slack Commands
+    // Slack Commands
+    #[tauri::command]
+    async fn start_slack_service(
+        app: tauri::AppHandle,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.start(&app).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn stop_slack_service(
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.stop().await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn initialize_slack(
+        config: SlackConfig,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.initialize(config).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_slack_approval(
+        request: SlackApprovalRequest,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.send_approval_request(request).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_slack_message(
+        message: SlackMessage,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.send_message(message).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_slack_task_completion(
+        task: SlackTaskCompletion,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.send_task_completion(task).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn post_slack_session_update(
+        update: SlackSessionUpdate,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.post_session_update(update).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_slack_error_alert(
+        alert: SlackErrorAlert,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.send_error_alert(alert).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn shutdown_slack(
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.slack_service.shutdown().await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_slack_status(
+        state: State<'_, AppState>,
+    ) -> Result<serde_json::Value, String> {
+        state.slack_service.status().await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_slack_approvals(
+        state: State<'_, AppState>,
+        since: u64,
+    ) -> Result<serde_json::Value, String> {
+        state.slack_service.get_approvals(since).await
+            .map_err(|e| e.to_string())
+    }
+
+    // Telegram commands
+    #[tauri::command]
+    async fn initialize_telegram(
+        config: TelegramConfig,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.telegram_service.initialize(config).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_telegram_approval(
+        request: TelegramApprovalRequest,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.telegram_service.send_approval_request(request).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn send_telegram_message(
+        message: TelegramMessage,
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.telegram_service.send_message(message).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn shutdown_telegram(
+        state: State<'_, AppState>,
+    ) -> Result<(), String> {
+        state.telegram_service.shutdown().await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_telegram_status(
+        state: State<'_, AppState>,
+    ) -> Result<serde_json::Value, String> {
+        state.telegram_service.status().await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_telegram_approvals(
+        state: State<'_, AppState>,
+        since: u64,
+    ) -> Result<serde_json::Value, String> {
+        state.telegram_service.get_approvals(since).await
+            .map_err(|e| e.to_string())
+    }
+
+    // Email notification commands
     #[tauri::command]
-    async fn start_slack_service(
-        app: tauri::AppHandle,
+    async fn get_email_config(
         state: State<'_, AppState>,
-    ) -> Result<(), String> {
-        state.slack_service.start(&app).await
+    ) -> Result<Option<EmailConfigView>, String> {
+        state.email_notifier.get_config_view().await
             .map_err(|e| e.to_string())
     }
 
+    /// `password` replaces the stored SMTP password when given
     #[tauri::command]
-    async fn stop_slack_service(
+    async fn save_email_config(
+        config: EmailConfig,
+        password: Option<String>,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        state.slack_service.stop().await
+        state.email_notifier.update_config(config, password).await
             .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn initialize_slack(
-        config: SlackConfig,
+    async fn send_test_email(
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        state.slack_service.initialize(config).await
+        state.email_notifier.send_test().await
             .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn send_slack_approval(
-        request: SlackApprovalRequest,
+    async fn notify_task_email(
+        notification: TaskNotification,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        state.slack_service.send_approval_request(request).await
+        state.email_notifier.notify_task(notification).await
             .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn send_slack_message(
-        message: SlackMessage,
+    async fn send_email_digest(
         state: State<'_, AppState>,
-    ) -> Result<(), String> {
-        state.slack_service.send_message(message).await
+    ) -> Result<usize, String> {
+        state.email_notifier.send_digest().await
             .map_err(|e| e.to_string())
     }
 
+    // Daily standup commands
     #[tauri::command]
-    async fn shutdown_slack(
+    async fn get_standup_config(
+        state: State<'_, AppState>,
+    ) -> Result<StandupConfig, String> {
+        Ok(state.standup_service.get_config().await)
+    }
+
+    #[tauri::command]
+    async fn save_standup_config(
+        config: StandupConfig,
         state: State<'_, AppState>,
     ) -> Result<(), String> {
-        state.slack_service.shutdown().await
+        state.standup_service.update_config(config).await
             .map_err(|e| e.to_string())
     }
 
+    /// Generate and post the standup for the UTC `day` (`YYYY-MM-DD`), yesterday by default
     #[tauri::command]
-    async fn get_slack_status(
+    async fn generate_standup(
+        day: Option<String>,
         state: State<'_, AppState>,
-    ) -> Result<serde_json::Value, String> {
-        state.slack_service.status().await
+    ) -> Result<StandupSummary, String> {
+        let day = match day {
+            Some(day) => chrono::NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid day {}: {}", day, e))?,
+            None => chrono::Utc::now().date_naive().pred_opt().ok_or("Invalid date")?,
+        };
+        state.standup_service.generate(day).await
             .map_err(|e| e.to_string())
     }
 
     #[tauri::command]
-    async fn get_slack_approvals(
+    async fn list_standups(
+        limit: Option<usize>,
         state: State<'_, AppState>,
-        since: u64,
-    ) -> Result<serde_json::Value, String> {
-        state.slack_service.get_approvals(since).await
+    ) -> Result<Vec<StandupSummary>, String> {
+        state.standup_service.list(limit.unwrap_or(30)).await
             .map_err(|e| e.to_string())
     }
 
@@ -923,6 +2907,22 @@ end tell"#,
         Ok(())
     }
 
+    /// Spawn a server with the given plugin, or the project's default plugin,
+    /// or the active one
+    #[tauri::command]
+    async fn spawn_plugin_server(
+        request: launch::LaunchRequest,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::plugins::types::AgentServer, String> {
+        let config = launch::resolve_for(&db, request)?;
+        let pm = state.plugin_manager.lock().await;
+        match &config.plugin_id {
+            Some(plugin_id) => pm.spawn_server_with_plugin(plugin_id, config.port, config.model, config.working_dir).await,
+            None => pm.spawn_server(config.port, config.model, config.working_dir).await,
+        }
+    }
+
     #[tauri::command]
     async fn list_plugins(
         state: State<'_, AppState>,
@@ -963,12 +2963,25 @@ end tell"#,
     // Linear-specific commands
     #[tauri::command]
     async fn update_linear_config(
-        config: serde_json::Value,
-        _state: State<'_, AppState>,
+        config: LinearConfig,
+        state: State<'_, AppState>,
     ) -> Result<(), String> {
-        println!("Linear config updated: {:?}", config);
-        // Store config securely if needed
-        Ok(())
+        state.linear_service.configure(config).await
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_linear_status(
+        state: State<'_, AppState>,
+    ) -> Result<serde_json::Value, String> {
+        Ok(state.linear_service.status().await)
+    }
+
+    #[tauri::command]
+    async fn list_linear_dispatches(
+        state: State<'_, AppState>,
+    ) -> Result<Vec<LinearDispatch>, String> {
+        Ok(state.linear_service.dispatches().await)
     }
 
     #[tauri::command]
@@ -1000,26 +3013,114 @@ end tell"#,
         Ok("Claude test ping successful".to_string())
     }
 
+    /// Start a Claude agent in every project of a workspace
+    #[tauri::command]
+    async fn spawn_workspace_agents(
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+        workspace_id: String,
+        model: Option<String>,
+    ) -> Result<Vec<crate::projects::workspaces::MemberResult>, String> {
+        let (workspace, projects) = crate::projects::workspaces::members(&db, &workspace_id)?;
+        println!("Spawning agents for workspace {} ({} projects)", workspace.name, projects.len());
+        Ok(crate::projects::workspaces::spawn_agents(&state.claude_manager, &projects, model).await)
+    }
+
+    /// Send one prompt to the agents of every project in a workspace
+    #[tauri::command]
+    async fn distribute_workspace_task(
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+        workspace_id: String,
+        prompt: String,
+    ) -> Result<Vec<crate::projects::workspaces::MemberResult>, String> {
+        let (workspace, projects) = crate::projects::workspaces::members(&db, &workspace_id)?;
+        println!("Distributing task across workspace {} ({} projects)", workspace.name, projects.len());
+        Ok(crate::projects::workspaces::distribute_task(&state.claude_manager, &projects, &prompt).await)
+    }
+
     // New Claude session management commands
+    #[tauri::command]
+    async fn claude_import_sessions(
+        db: State<'_, DatabaseManager>,
+        project_id: String,
+    ) -> Result<crate::claude::import::ImportReport, String> {
+        let project = crate::projects::manager::ProjectsManager::new(&db)
+            .get(&project_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+        crate::claude::import::import_project(&db, &project.id, &project.path)
+    }
+
     #[tauri::command]
     async fn claude_create_session(
         state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
         project_id: String,
         working_directory: Option<String>,
-        model: Option<String>
+        model: Option<String>,
+        isolated: Option<bool>,
     ) -> Result<String, String> {
         println!("[claude_create_session] Creating session for project: {}", project_id);
-        state.claude_manager.create_session(project_id, working_directory, model).await
+        if !isolated.unwrap_or(false) {
+            return state.claude_manager.create_session(project_id, working_directory, model).await;
+        }
+
+        // Isolated sessions get their own worktree and branch so they can't
+        // clobber another agent's checkout; it goes away with the session
+        let base = match working_directory {
+            Some(dir) => dir,
+            None => crate::projects::manager::ProjectsManager::new(&db)
+                .get(&project_id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Project not found: {}", project_id))?
+                .path,
+        };
+        let name = format!("claude-{}", &uuid::Uuid::new_v4().simple().to_string()[..8]);
+        let worktree = state.git_manager.create_worktree(&base, &name, None)?;
+        println!("[claude_create_session] Isolated in worktree {} ({})", worktree.path, name);
+
+        let result = state.claude_manager.create_session(project_id, Some(worktree.path.clone()), model).await;
+        if result.is_err() {
+            let _ = state.git_manager.remove_worktree_at(&worktree.path);
+        }
+        result
     }
 
     #[tauri::command]
     async fn claude_send_message(
         state: State<'_, AppState>,
         session_id: String,
-        message: String
+        message: String,
+        auto_stash: Option<bool>,
     ) -> Result<String, String> {
         println!("[claude_send_message] Session: {}, Message length: {} chars", session_id, message.len());
-        state.claude_manager.send_message(&session_id, message).await
+        if !auto_stash.unwrap_or(false) {
+            return state.claude_manager.send_message(&session_id, message).await;
+        }
+
+        // Put the user's uncommitted work aside while the agent runs and
+        // bring it back afterwards
+        let working_directory = state
+            .claude_manager
+            .get_session(&session_id)
+            .await
+            .and_then(|s| s.working_directory)
+            .ok_or_else(|| format!("Session {} has no working directory to stash", session_id))?;
+        let stash = state
+            .git_manager
+            .stash_save(&working_directory, Some("ninjasquad: before agent run"), true)?;
+
+        let result = state.claude_manager.send_message(&session_id, message).await;
+
+        if let Some(stash) = stash {
+            if let Err(e) = state.git_manager.stash_restore(&working_directory, &stash.id) {
+                // Most likely the agent touched the same files; leave it for the user
+                println!("[claude_send_message] Could not restore stash {}, left in the stash list: {}", stash.id, e);
+            }
+        }
+        result
     }
 
     #[tauri::command]
@@ -1028,7 +3129,22 @@ end tell"#,
         session_id: String
     ) -> Result<(), String> {
         println!("[claude_close_session] Closing session: {}", session_id);
-        state.claude_manager.close_session(&session_id).await
+        let working_directory = state
+            .claude_manager
+            .get_session(&session_id)
+            .await
+            .and_then(|s| s.working_directory);
+        state.claude_manager.close_session(&session_id).await?;
+
+        // Tear down the session's worktree; one with uncommitted work is kept
+        if let Some(dir) = working_directory {
+            match state.git_manager.remove_worktree_at(&dir) {
+                Ok(true) => println!("[claude_close_session] Removed worktree {}", dir),
+                Ok(false) => {}
+                Err(e) => println!("[claude_close_session] Keeping worktree {}: {}", dir, e),
+            }
+        }
+        Ok(())
     }
 
     #[tauri::command]
@@ -1087,6 +3203,119 @@ end tell"#,
         Ok(response)
     }
 
+    // Database Commands
+    #[tauri::command]
+    async fn get_schema_version(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<crate::database::migrations::SchemaVersion, String> {
+        db.schema_version().map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn list_profiles(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<Vec<crate::database::profiles::ProfileInfo>, String> {
+        Ok(crate::database::profiles::list(db.app_dir()))
+    }
+
+    #[tauri::command]
+    async fn create_profile(
+        db: State<'_, DatabaseManager>,
+        name: String,
+    ) -> Result<crate::database::profiles::ProfileInfo, String> {
+        crate::database::profiles::create(db.app_dir(), &name)
+    }
+
+    /// Reopen the app on another profile's database without restarting.
+    /// Services holding config from the old database reload it, and the
+    /// frontend reloads its state on `profile-changed`.
+    #[tauri::command]
+    async fn switch_profile(
+        app: tauri::AppHandle,
+        state: State<'_, AppState>,
+        db: State<'_, DatabaseManager>,
+        name: String,
+    ) -> Result<(), String> {
+        db.switch_profile(&name)?;
+        if let Err(e) = state.email_notifier.start(&app).await {
+            eprintln!("Failed to reload email notifier: {}", e);
+        }
+        if let Err(e) = state.standup_service.start(&app).await {
+            eprintln!("Failed to reload standup scheduler: {}", e);
+        }
+        let _ = app.emit("profile-changed", &name);
+        Ok(())
+    }
+
+    /// Run an integrity check (quick by default) and report database stats
+    #[tauri::command]
+    async fn check_database(
+        db: State<'_, DatabaseManager>,
+        full: Option<bool>,
+    ) -> Result<crate::database::health::DatabaseHealth, String> {
+        db.with_connection(|conn| crate::database::health::check(conn, full.unwrap_or(false)))
+            .map_err(|e| e.to_string())
+    }
+
+    // Settings Commands
+    #[tauri::command]
+    async fn get_setting(
+        db: State<'_, DatabaseManager>,
+        key: String,
+    ) -> Result<Option<serde_json::Value>, String> {
+        db.with_connection(|conn| crate::database::settings::get_value(conn, &key))
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn get_all_settings(
+        db: State<'_, DatabaseManager>,
+    ) -> Result<std::collections::BTreeMap<String, serde_json::Value>, String> {
+        db.with_connection(crate::database::settings::get_all)
+            .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn set_setting(
+        app: tauri::AppHandle,
+        db: State<'_, DatabaseManager>,
+        key: String,
+        value: serde_json::Value,
+    ) -> Result<(), String> {
+        db.with_connection(|conn| crate::database::settings::set_json(conn, &key, &value))
+            .map_err(|e| e.to_string())?;
+
+        if key == crate::database::BUSY_TIMEOUT_SETTING {
+            db.apply_busy_timeout().map_err(|e| e.to_string())?;
+        }
+
+        let _ = app.emit("setting-changed", crate::database::settings::SettingChanged {
+            key,
+            value: Some(value),
+        });
+        Ok(())
+    }
+
+    #[tauri::command]
+    async fn delete_setting(
+        app: tauri::AppHandle,
+        db: State<'_, DatabaseManager>,
+        key: String,
+    ) -> Result<(), String> {
+        db.with_connection(|conn| crate::database::settings::delete_setting(conn, &key))
+            .map_err(|e| e.to_string())?;
+
+        if key == crate::database::BUSY_TIMEOUT_SETTING {
+            db.apply_busy_timeout().map_err(|e| e.to_string())?;
+        }
+
+        let _ = app.emit("setting-changed", crate::database::settings::SettingChanged {
+            key,
+            value: None,
+        });
+        Ok(())
+    }
+
     // Plugin Session Management Commands
     #[tauri::command]
     async fn create_plugin_session(
@@ -1164,6 +3393,19 @@ end tell"#,
     }
 
     // Conversation History Commands
+    #[tauri::command]
+    async fn search_history(
+        db: State<'_, DatabaseManager>,
+        query: String,
+        project_id: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Vec<crate::database::history::HistoryMatch>, String> {
+        db.with_connection(|conn| {
+            crate::database::history::search_history(conn, &query, project_id.as_deref(), limit.unwrap_or(50))
+        })
+        .map_err(|e| e.to_string())
+    }
+
     #[tauri::command]
     async fn add_conversation_message(
         db: State<'_, DatabaseManager>,
@@ -1231,17 +3473,107 @@ end tell"#,
         .map_err(|e| e.to_string())
     }
 
+    #[tauri::command]
+    async fn get_conversation_messages(
+        db: State<'_, DatabaseManager>,
+        session_id: String,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<crate::database::conversation::Page<crate::database::conversation::ConversationMessage>, String> {
+        db.with_connection(|conn| {
+            crate::database::conversation::get_messages_page(conn, &session_id, limit.unwrap_or(100), offset.unwrap_or(0))
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn list_conversations(
+        db: State<'_, DatabaseManager>,
+        project_id: Option<String>,
+        include_archived: Option<bool>,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<crate::database::conversation::Page<crate::database::conversation::ConversationSummary>, String> {
+        db.with_connection(|conn| {
+            crate::database::conversation::list_conversations(
+                conn,
+                project_id.as_deref(),
+                include_archived.unwrap_or(false),
+                limit.unwrap_or(50),
+                offset.unwrap_or(0),
+            )
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn archive_conversation(
+        db: State<'_, DatabaseManager>,
+        session_id: String,
+    ) -> Result<bool, String> {
+        let manager = crate::plugins::sessions::PluginSessionManager::new(&db);
+        manager.archive(&session_id).map_err(|e| e.to_string())
+    }
+
+    // Approval audit log commands
+    #[tauri::command]
+    async fn list_approvals(
+        db: State<'_, DatabaseManager>,
+        filter: Option<crate::database::approvals::ApprovalFilter>,
+    ) -> Result<Vec<crate::database::approvals::ApprovalRecord>, String> {
+        let filter = filter.unwrap_or_default();
+        db.with_connection(|conn| {
+            crate::database::approvals::list_approvals(conn, &filter)
+        })
+        .map_err(|e| e.to_string())
+    }
+
+    #[tauri::command]
+    async fn export_approvals(
+        db: State<'_, DatabaseManager>,
+        filter: Option<crate::database::approvals::ApprovalFilter>,
+        format: String,
+        path: String,
+    ) -> Result<usize, String> {
+        let filter = filter.unwrap_or_default();
+        let records = db.with_connection(|conn| {
+            crate::database::approvals::list_approvals(conn, &filter)
+        })
+        .map_err(|e| e.to_string())?;
+
+        let content = match format.as_str() {
+            "csv" => crate::database::approvals::to_csv(&records),
+            "json" => serde_json::to_string_pretty(&records).map_err(|e| e.to_string())?,
+            other => return Err(format!("Unsupported export format: {}", other)),
+        };
+
+        std::fs::write(&path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+
+        Ok(records.len())
+    }
+
     #[cfg_attr(mobile, tauri::mobile_entry_point)]
     pub fn run() {
         let queue_config = QueueConfig::default();
-        let queue_client = crate::queue::client::create_queue_client(queue_config.clone());
+        let task_history = TaskHistory::new();
+        let usage_tracker = crate::database::usage::UsageTracker::new();
+        let queue_client: Arc<dyn QueueClient> = Arc::new(
+            RecordingQueueClient::new(
+                crate::queue::client::create_queue_client(queue_config.clone()),
+                task_history.clone(),
+            )
+            .with_usage(usage_tracker.clone()),
+        );
 
         let opencode_service = Arc::new(OpenCodeService::new().with_queue_client(queue_client.clone()));
-        let wezterm_controller = Arc::new(WezTermController::new());
-        let session_manager = Arc::new(SessionManager::new(
-            opencode_service.clone(),
-            wezterm_controller.clone(),
-        ));
+        let wezterm_cli = WezTermCli::new();
+        let wezterm_controller = Arc::new(WezTermController::with_cli(wezterm_cli.clone()));
+        let session_manager = Arc::new(
+            SessionManager::new(opencode_service.clone(), wezterm_controller.clone())
+                .with_usage(usage_tracker.clone()),
+        );
+        let linear_service = Arc::new(LinearService::new(session_manager.clone()));
         let pty_manager = Arc::new(Mutex::new(PtyManager::new()));
 
         let worker_service = Some(Arc::new(WorkerService::new(
@@ -1250,12 +3582,20 @@ end tell"#,
             queue_config,
         )));
 
-        let mirror_manager = Arc::new(AsyncMutex::new(MirrorManager::new()));
+        let mirror_manager = Arc::new(AsyncMutex::new(MirrorManager::with_cli(wezterm_cli)));
         let tmux_manager = Arc::new(AsyncMutex::new(TmuxManager::new()));
         let plugin_manager = Arc::new(AsyncMutex::new(PluginManager::new()));
-        let claude_manager = Arc::new(ClaudeProcessManager::new());
-        let slack_service = Arc::new(SlackService::new(3456));
+        let claude_manager = Arc::new(ClaudeProcessManager::new().with_usage(usage_tracker.clone()));
+        let slack_service = Arc::new(SlackService::new());
+        let email_notifier = Arc::new(EmailNotifier::new());
+        let telegram_service = Arc::new(TelegramService::new());
         let claude_agent_service = Arc::new(ClaudeAgentService::new(3457));
+        let dev_server_manager = Arc::new(
+            DevServerManager::new().with_alerts(slack_service.clone(), telegram_service.clone(), email_notifier.clone()),
+        );
+        let git_manager = Arc::new(GitManager::new());
+        let standup_service = Arc::new(StandupService::new(slack_service.clone(), git_manager.clone()));
+        let repo_watcher = Arc::new(RepoWatcher::new(git_manager.clone()));
 
         // Initialize plugins will be done after app setup when we have an async runtime
 
@@ -1267,11 +3607,19 @@ end tell"#,
             session_manager,
             claude_manager,
             pty_manager: pty_manager.clone(),
+            git_manager,
+            repo_watcher,
+            dev_server_manager,
+            browser_manager: Arc::new(BrowserManager::new()),
             queue_client,
             worker_service,
             local_test_mode: Arc::new(AsyncMutex::new(None)),
             plugin_manager,
             slack_service,
+            email_notifier,
+            standup_service,
+            telegram_service,
+            linear_service,
             claude_agent_service,
         };
 
@@ -1297,62 +3645,191 @@ end tell"#,
                 register_session,
                 list_sessions,
                 distribute_task,
+                complete_task,
                 create_terminal,
                 write_to_terminal,
+                write_to_terminal_bytes,
+                set_terminal_throttle,
                 resize_terminal,
+                get_terminal_buffer,
+                search_terminal,
+                list_terminals,
+                find_terminals,
+                kill_project_pty_terminals,
+                set_terminal_idle_timeout,
                 kill_terminal,
                 get_server_details,
                 enable_distributed_mode,
                 get_active_workers,
                 publish_task,
                 get_task_result,
+                get_task_history,
+                get_worker_history,
+                set_task_history_retention,
+                get_usage_report,
+                get_retention_policy,
+                set_retention_policy,
+                run_retention_cleanup,
+                get_setting,
+                get_all_settings,
+                set_setting,
+                delete_setting,
                 start_worker_service,
                 stop_worker_service,
                 start_local_test_mode,
                 stop_local_test_mode,
                 simulate_distributed_task,
                 get_local_test_stats,
+                get_wezterm_status,
                 spawn_wezterm_for_project,
+                get_project_terminal_layout,
+                save_project_terminal_layout,
+                get_project_window_geometry,
+                set_wezterm_window_geometry,
+                save_wezterm_window_geometry,
+                split_wezterm_pane,
+                resize_wezterm_pane,
+                activate_wezterm_pane,
                 list_project_wezterm_windows,
                 close_wezterm_window,
                 send_text_to_wezterm,
+                send_key_to_window,
                 execute_command_in_wezterm,
                 focus_wezterm_window,
                 list_all_wezterm_windows,
+                close_project_terminals,
                 start_wezterm_mirror,
                 stop_wezterm_mirror,
                 send_input_to_mirror,
+                resize_mirror,
                 get_mirror_content,
+                get_mirror_scrollback,
                 list_mirrors,
                 create_tmux_session,
                 kill_tmux_session,
                 send_tmux_keys,
+                send_tmux_special_keys,
                 send_tmux_command,
                 capture_tmux_pane,
+                search_tmux_pane,
+                get_tmux_truncated_history,
+                set_tmux_history_limit,
+                resize_tmux_session,
+                create_tmux_window,
+                split_tmux_pane,
+                kill_tmux_pane,
+                list_tmux_windows,
+                list_tmux_panes,
                 list_tmux_sessions,
+                discover_tmux_sessions,
+                attach_tmux_session,
+                set_tmux_auto_restart,
+                detach_tmux_session,
                 get_git_diff,
-                get_git_changed_files,
+                get_git_status,
+                git_commit,
+                get_diff,
+                git_log,
+                git_blame,
+                git_stash_save,
+                list_git_stashes,
+                git_stash_apply,
+                git_stash_drop,
+                git_push,
+                git_pull,
+                get_git_file_hunks,
+                stage_git_hunks,
+                unstage_git_hunks,
+                detect_git_conflicts,
+                merge_git_branch,
+                create_github_pull_request,
+                create_gitlab_merge_request,
+                list_jira_issues,
+                get_jira_issue,
+                transition_jira_issue,
+                comment_on_jira_issue,
+                list_project_issues,
+                get_project_issue,
+                comment_on_project_issue,
+                transition_project_issue,
+                dispatch_project_issue,
+                watch_project_repo,
+                unwatch_project_repo,
+                get_git_current_branch,
+                list_git_branches,
+                create_git_branch,
+                checkout_git_branch,
+                create_git_worktree,
+                list_git_worktrees,
+                remove_git_worktree,
                 open_browser,
                 launch_playwright_browser,
+                launch_browser_session,
+                list_browser_sessions,
+                navigate_browser_session,
+                evaluate_in_browser_session,
+                browser_click,
+                browser_type,
+                browser_press_key,
+                browser_wait_for_selector,
+                run_browser_script,
+                capture_screenshot,
+                extract_page_content,
+                get_browser_session_logs,
+                clear_browser_session_logs,
+                close_browser_session,
                 spawn_dev_server,
+                start_dev_server_profile,
+                start_all_dev_servers,
+                stop_dev_server_profile,
+                list_dev_servers,
+                stop_dev_server,
+                restart_dev_server,
+                set_dev_server_restart_policy,
+                remove_dev_server,
+                get_dev_server_logs,
+                clear_dev_server_logs,
+                execute_saved_command,
                 spawn_external_terminal,
                 start_slack_service,
                 stop_slack_service,
                 initialize_slack,
                 send_slack_approval,
                 send_slack_message,
+                send_slack_task_completion,
+                post_slack_session_update,
+                send_slack_error_alert,
                 shutdown_slack,
                 get_slack_status,
                 get_slack_approvals,
+                initialize_telegram,
+                send_telegram_approval,
+                send_telegram_message,
+                shutdown_telegram,
+                get_telegram_status,
+                get_telegram_approvals,
+                get_email_config,
+                save_email_config,
+                send_test_email,
+                notify_task_email,
+                send_email_digest,
+                get_standup_config,
+                save_standup_config,
+                generate_standup,
+                list_standups,
                 initialize_claude_agent,
                 get_claude_agent_health,
                 initialize_plugins,
+                spawn_plugin_server,
                 list_plugins,
                 get_active_plugin,
                 set_active_plugin,
                 check_claude_code_available,
                 execute_claude_code,
                 claude_create_session,
+                spawn_workspace_agents,
+                distribute_workspace_task,
+                claude_import_sessions,
                 claude_send_message,
                 claude_close_session,
                 claude_list_sessions,
@@ -1365,6 +3842,8 @@ end tell"#,
                 get_claude_agent_health,
                 shutdown_claude_agent,
                 update_linear_config,
+                get_linear_status,
+                list_linear_dispatches,
                 assign_issue_to_agent,
                 execute_agent_task,
                 crate::projects::create_project,
@@ -1375,8 +3854,48 @@ end tell"#,
                 crate::projects::list_recent_projects,
                 crate::projects::update_project,
                 crate::projects::update_project_last_accessed,
-                crate::projects::delete_project,
+                delete_project,
                 crate::projects::project_exists,
+                crate::projects::search_projects,
+                crate::projects::get_project_activity,
+                crate::projects::resolve_launch_config,
+                crate::projects::scan_for_projects,
+                crate::projects::register_projects,
+                crate::projects::set_project_env,
+                crate::projects::set_project_secret,
+                crate::projects::delete_project_secret,
+                crate::projects::check_project_paths,
+                crate::projects::list_project_files,
+                crate::projects::list_project_env_files,
+                crate::projects::get_project_file_outline,
+                crate::projects::relocate_project,
+                crate::projects::archive_project,
+                crate::projects::unarchive_project,
+                crate::projects::add_project_tag,
+                crate::projects::remove_project_tag,
+                crate::projects::list_project_tags,
+                crate::projects::save_project_command,
+                crate::projects::list_project_commands,
+                crate::projects::delete_project_command,
+                crate::projects::create_project_from_template,
+                crate::projects::save_project_template,
+                crate::projects::list_project_templates,
+                crate::projects::delete_project_template,
+                crate::projects::create_workspace,
+                crate::projects::get_workspace,
+                crate::projects::list_workspaces,
+                crate::projects::delete_workspace,
+                crate::projects::add_project_to_workspace,
+                crate::projects::remove_project_from_workspace,
+                crate::projects::export_project_data,
+                crate::projects::export_project,
+                crate::projects::read_project_bundle,
+                crate::projects::import_project,
+                get_schema_version,
+                check_database,
+                list_profiles,
+                create_profile,
+                switch_profile,
                 create_plugin_session,
                 get_plugin_session,
                 list_plugin_sessions,
@@ -1390,17 +3909,68 @@ end tell"#,
                 get_recent_conversation_messages,
                 count_conversation_messages,
                 delete_conversation_history,
+                get_conversation_messages,
+                list_conversations,
+                archive_conversation,
+                search_history,
+                list_approvals,
+                export_approvals,
             ])
             .setup(move |app| {
                 // Initialize database
                 let db_manager = DatabaseManager::new(&app.handle())
                     .expect("Failed to initialize database");
+                task_history.attach(db_manager.connection());
+                usage_tracker.attach(db_manager.connection());
                 app.manage(db_manager);
 
+                // Apply retention policies at startup and once a day
+                {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        let mut interval = tokio::time::interval(crate::database::retention::CLEANUP_INTERVAL);
+                        loop {
+                            interval.tick().await;
+                            let db: State<DatabaseManager> = handle.state();
+                            match db.with_connection(crate::database::retention::run) {
+                                Ok(report) => println!(
+                                    "Retention cleanup removed {} sessions, {} activity, {} approvals, {} task rows; reclaimed {} bytes",
+                                    report.archived_sessions, report.activity, report.approvals, report.task_history, report.bytes_reclaimed
+                                ),
+                                Err(e) => println!("Retention cleanup failed: {}", e),
+                            }
+                        }
+                    });
+                }
+
+                // Flag projects whose folder has moved or been deleted, at
+                // startup and periodically after
+                {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        let mut interval = tokio::time::interval(crate::projects::manager::PATH_CHECK_INTERVAL);
+                        loop {
+                            interval.tick().await;
+                            let db: State<DatabaseManager> = handle.state();
+                            match crate::projects::manager::ProjectsManager::new(&db).check_paths() {
+                                Ok(changed) if !changed.is_empty() => {
+                                    let _ = handle.emit("project-paths-changed", &changed);
+                                }
+                                Ok(_) => {}
+                                Err(e) => println!("Project path check failed: {}", e),
+                            }
+                        }
+                    });
+                }
+
                 // Manage app state
                 app.manage(app_state);
                 // Set up PTY manager with app handle
-                pty_manager.lock().unwrap().set_app_handle(app.handle().clone());
+                {
+                    let mut pty = pty_manager.lock().unwrap();
+                    pty.set_app_handle(app.handle().clone());
+                    pty.start_idle_reaper(std::time::Duration::from_secs(60));
+                }
                 // Set up MirrorManager with app handle
                 {
                     let handle = app.handle();
@@ -1410,13 +3980,41 @@ end tell"#,
                         mirror_manager.lock().await.set_app_handle(handle.clone());
                     });
                 }
+                // Probe WezTerm, then reattach windows and mirrors left open by the previous run
+                {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        let state: State<AppState> = handle.state();
+                        let status = state.wezterm_controller.cli().probe().await;
+                        match &status.error {
+                            Some(error) => println!("WezTerm probe: {}", error),
+                            None => println!("WezTerm {} found at {}",
+                                status.version.as_deref().unwrap_or("(unknown version)"),
+                                status.binary.as_deref().unwrap_or_default()),
+                        }
+                        if !status.installed {
+                            return;
+                        }
+
+                        restore_wezterm_state(&handle).await;
+                    });
+                }
                 // Set up TmuxManager with app handle
                 {
                     let handle = app.handle();
                     let state: State<AppState> = handle.state();
                     let tmux_manager = state.tmux_manager.clone();
                     tauri::async_runtime::block_on(async move {
-                        tmux_manager.lock().await.set_app_handle(handle.clone());
+                        let mut tmux_manager = tmux_manager.lock().await;
+                        tmux_manager.set_app_handle(handle.clone());
+                        tmux_manager.start_health_monitor(std::time::Duration::from_secs(5));
+                    });
+                }
+                // Resume monitoring tmux sessions that outlived the previous run
+                {
+                    let handle = app.handle().clone();
+                    tauri::async_runtime::spawn(async move {
+                        restore_tmux_sessions(&handle).await;
                     });
                 }
 
@@ -1434,6 +4032,38 @@ end tell"#,
                         }
                     });
 
+                    // Give Telegram the handle for approval events
+                    let telegram_service = state.telegram_service.clone();
+                    let handle_telegram = handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        telegram_service.set_app_handle(handle_telegram).await;
+                    });
+
+                    // Give Linear the handle for dispatch events
+                    let linear_service = state.linear_service.clone();
+                    let handle_linear = handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        linear_service.set_app_handle(handle_linear).await;
+                    });
+
+                    // Start email notifier (loads its config from the settings table)
+                    let email_notifier = state.email_notifier.clone();
+                    let handle_email = handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = email_notifier.start(&handle_email).await {
+                            eprintln!("Failed to start email notifier: {}", e);
+                        }
+                    });
+
+                    // Start the daily standup scheduler (loads its config from the settings table)
+                    let standup_service = state.standup_service.clone();
+                    let handle_standup = handle.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = standup_service.start(&handle_standup).await {
+                            eprintln!("Failed to start standup scheduler: {}", e);
+                        }
+                    });
+
                     // Start Claude Agent service
                     let claude_agent_service = state.claude_agent_service.clone();
                     let handle_claude = handle.clone();
@@ -1465,6 +4095,36 @@ end tell"#,
                                 }
                             });
 
+                            // Stop Telegram polling
+                            let telegram_service = state.telegram_service.clone();
+                            tauri::async_runtime::block_on(async move {
+                                let _ = telegram_service.shutdown().await;
+                            });
+
+                            // Stop the Linear webhook listener
+                            let linear_service = state.linear_service.clone();
+                            tauri::async_runtime::block_on(async move {
+                                linear_service.shutdown().await;
+                            });
+
+                            // Stop email digest scheduler
+                            let email_notifier = state.email_notifier.clone();
+                            tauri::async_runtime::block_on(async move {
+                                email_notifier.stop().await;
+                            });
+
+                            // Stop standup scheduler
+                            let standup_service = state.standup_service.clone();
+                            tauri::async_runtime::block_on(async move {
+                                standup_service.stop().await;
+                            });
+
+                            // Close managed browsers
+                            let browser_manager = state.browser_manager.clone();
+                            tauri::async_runtime::block_on(async move {
+                                browser_manager.close_all().await;
+                            });
+
                             // Stop Claude Agent service
                             let claude_agent_service = state.claude_agent_service.clone();
                             tauri::async_runtime::block_on(async move {
@@ -0,0 +1,139 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use super::types::{LinearIssue, WorkflowState};
+
+const ISSUE_FIELDS: &str = "id identifier title description url team { id } state { name type }";
+
+const LINEAR_API_URL: &str = "https://api.linear.app/graphql";
+
+/// Minimal Linear GraphQL API client
+#[derive(Debug, Clone)]
+pub struct LinearApiClient {
+    client: reqwest::Client,
+    url: String,
+}
+
+impl Default for LinearApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinearApiClient {
+    pub fn new() -> Self {
+        Self::with_url(LINEAR_API_URL)
+    }
+
+    pub fn with_url(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            url: url.to_string(),
+        }
+    }
+
+    /// Run a query and return its `data`, failing on GraphQL errors
+    async fn query(&self, api_key: &str, query: &str, variables: Value) -> Result<Value> {
+        let response = self.client
+            .post(&self.url)
+            // Personal API keys go in as they are, without `Bearer`
+            .header("Authorization", api_key)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Linear API request failed: {}", e.without_url()))?;
+        let status = response.status();
+        let mut value: Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Linear API response: {}", e))?;
+
+        if let Some(errors) = value["errors"].as_array().filter(|errors| !errors.is_empty()) {
+            let messages: Vec<&str> = errors.iter().filter_map(|e| e["message"].as_str()).collect();
+            return Err(anyhow::anyhow!("Linear API returned {}: {}", status, messages.join("; ")));
+        }
+        if !status.is_success() {
+            return Err(anyhow::anyhow!("Linear API returned {}", status));
+        }
+        Ok(value["data"].take())
+    }
+
+    /// Open issues assigned to the key's user, in `team_id` when given
+    pub async fn assigned_issues(&self, api_key: &str, team_id: Option<&str>) -> Result<Vec<LinearIssue>> {
+        let mut filter = json!({ "state": { "type": { "nin": ["completed", "canceled"] } } });
+        if let Some(team_id) = team_id {
+            filter["team"] = json!({ "id": { "eq": team_id } });
+        }
+        let data = self.query(
+            api_key,
+            &format!(
+                "query($filter: IssueFilter) {{ viewer {{ assignedIssues(first: 50, filter: $filter, orderBy: updatedAt) {{ nodes {{ {} }} }} }} }}",
+                ISSUE_FIELDS
+            ),
+            json!({ "filter": filter }),
+        ).await?;
+        Ok(data["viewer"]["assignedIssues"]["nodes"]
+            .as_array()
+            .map(|nodes| nodes.iter().filter_map(issue_from_node).collect())
+            .unwrap_or_default())
+    }
+
+    /// An issue by ID or identifier, e.g. `ENG-42`
+    pub async fn issue(&self, api_key: &str, id: &str) -> Result<LinearIssue> {
+        let data = self.query(
+            api_key,
+            &format!("query($id: String!) {{ issue(id: $id) {{ {} }} }}", ISSUE_FIELDS),
+            json!({ "id": id }),
+        ).await?;
+        issue_from_node(&data["issue"]).ok_or_else(|| anyhow::anyhow!("Linear issue {} not found", id))
+    }
+
+    pub async fn comment(&self, api_key: &str, issue_id: &str, body: &str) -> Result<()> {
+        let data = self.query(
+            api_key,
+            "mutation($input: CommentCreateInput!) { commentCreate(input: $input) { success } }",
+            json!({ "input": { "issueId": issue_id, "body": body } }),
+        ).await?;
+        if data["commentCreate"]["success"].as_bool() != Some(true) {
+            return Err(anyhow::anyhow!("Linear did not add the comment to {}", issue_id));
+        }
+        Ok(())
+    }
+
+    pub async fn workflow_states(&self, api_key: &str, team_id: &str) -> Result<Vec<WorkflowState>> {
+        let mut data = self.query(
+            api_key,
+            "query($id: String!) { team(id: $id) { states { nodes { id name type position } } } }",
+            json!({ "id": team_id }),
+        ).await?;
+        Ok(serde_json::from_value(data["team"]["states"]["nodes"].take())?)
+    }
+
+    pub async fn set_state(&self, api_key: &str, issue_id: &str, state_id: &str) -> Result<()> {
+        let data = self.query(
+            api_key,
+            "mutation($id: String!, $input: IssueUpdateInput!) { issueUpdate(id: $id, input: $input) { success } }",
+            json!({ "id": issue_id, "input": { "stateId": state_id } }),
+        ).await?;
+        if data["issueUpdate"]["success"].as_bool() != Some(true) {
+            return Err(anyhow::anyhow!("Linear did not update the state of {}", issue_id));
+        }
+        Ok(())
+    }
+}
+
+fn issue_from_node(node: &Value) -> Option<LinearIssue> {
+    Some(LinearIssue {
+        id: node["id"].as_str()?.to_string(),
+        identifier: node["identifier"].as_str()?.to_string(),
+        title: node["title"].as_str().unwrap_or_default().to_string(),
+        description: node["description"].as_str().filter(|d| !d.trim().is_empty()).map(str::to_string),
+        url: node["url"].as_str().map(str::to_string),
+        team_id: node["team"]["id"].as_str()?.to_string(),
+        state: node["state"]["name"].as_str().map(str::to_string),
+        state_type: node["state"]["type"].as_str().map(str::to_string),
+    })
+}
@@ -0,0 +1,278 @@
+pub mod api;
+pub mod types;
+pub mod webhook;
+
+pub use api::LinearApiClient;
+pub use types::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use anyhow::Result;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::issues::linear::LinearTracker;
+use crate::issues::pipeline::{self, TaskOutcome};
+use crate::issues::{Issue, IssueTracker};
+use crate::session::SessionManager;
+use webhook::Parsed;
+
+/// How long a client gets to send its whole request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The first state of `kind` in the team's workflow, by board position
+pub fn state_of_kind<'a>(states: &'a [WorkflowState], kind: &str) -> Option<&'a WorkflowState> {
+    states
+        .iter()
+        .filter(|state| state.kind == kind)
+        .min_by(|a, b| a.position.total_cmp(&b.position))
+}
+
+/// The running webhook listener
+struct Listener {
+    port: u16,
+    accept_loop: JoinHandle<()>,
+}
+
+/// Hands Linear issues to agents: a webhook labelling an issue with the
+/// agent label starts an orchestrator task, and the issue gets comments and
+/// moves through the team's workflow as the task goes.
+///
+/// The listener binds to localhost; Linear reaches it through a tunnel
+/// (e.g. `cloudflared` or `ngrok`) set as the webhook URL.
+#[derive(Clone)]
+pub struct LinearService {
+    config: Arc<RwLock<Option<LinearConfig>>>,
+    session_manager: Arc<SessionManager>,
+    listener: Arc<Mutex<Option<Listener>>>,
+    /// By issue ID
+    dispatches: Arc<RwLock<HashMap<String, LinearDispatch>>>,
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+}
+
+impl LinearService {
+    pub fn new(session_manager: Arc<SessionManager>) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(None)),
+            session_manager,
+            listener: Arc::new(Mutex::new(None)),
+            dispatches: Arc::new(RwLock::new(HashMap::new())),
+            app_handle: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub async fn set_app_handle(&self, app_handle: AppHandle) {
+        *self.app_handle.write().await = Some(app_handle);
+    }
+
+    /// Use `config` from now on, starting, moving or stopping the webhook
+    /// listener to match it
+    pub async fn configure(&self, config: LinearConfig) -> Result<()> {
+        let port = config.port();
+        let listen = config.enable_webhooks && !config.api_key.is_empty();
+        // The listener is exposed through a public tunnel, so unsigned
+        // deliveries could dispatch any prompt to an agent
+        let signed = config.webhook_secret.as_deref().is_some_and(|s| !s.trim().is_empty());
+        *self.config.write().await = Some(config);
+
+        let mut listener = self.listener.lock().await;
+        if listener.as_ref().is_some_and(|current| listen && current.port == port) {
+            return Ok(());
+        }
+        if let Some(current) = listener.take() {
+            current.accept_loop.abort();
+            println!("[Linear] Stopped webhook listener on port {}", current.port);
+        }
+        if !listen {
+            return Ok(());
+        }
+        if !signed {
+            return Err(anyhow::anyhow!("Set the Linear webhook signing secret to receive webhooks"));
+        }
+
+        let socket = TcpListener::bind(("127.0.0.1", port))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to listen for Linear webhooks on port {}: {}", port, e))?;
+        println!("[Linear] Listening for webhooks on http://127.0.0.1:{}", port);
+        let service = self.clone();
+        let accept_loop = tokio::spawn(async move { service.accept(socket).await });
+        *listener = Some(Listener { port, accept_loop });
+        Ok(())
+    }
+
+    pub async fn shutdown(&self) {
+        if let Some(listener) = self.listener.lock().await.take() {
+            listener.accept_loop.abort();
+        }
+        *self.config.write().await = None;
+        println!("[Linear] Linear integration shut down");
+    }
+
+    pub async fn status(&self) -> Value {
+        let port = self.listener.lock().await.as_ref().map(|listener| listener.port);
+        let config = self.config.read().await;
+        json!({
+            "configured": config.is_some(),
+            "listening": port.is_some(),
+            "port": port,
+            "agentLabel": config.as_ref().map(|c| c.label().to_string()),
+        })
+    }
+
+    /// Issues handed to agents, most recent first
+    pub async fn dispatches(&self) -> Vec<LinearDispatch> {
+        let mut dispatches: Vec<_> = self.dispatches.read().await.values().cloned().collect();
+        dispatches.sort_by(|a, b| b.dispatched_at.cmp(&a.dispatched_at));
+        dispatches
+    }
+
+    async fn accept(&self, socket: TcpListener) {
+        loop {
+            match socket.accept().await {
+                Ok((stream, _)) => {
+                    let service = self.clone();
+                    tokio::spawn(async move { service.handle_connection(stream).await });
+                }
+                Err(e) => {
+                    println!("[Linear] Failed to accept webhook connection: {}", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream) {
+        let request = tokio::time::timeout(REQUEST_TIMEOUT, async {
+            let mut buf = Vec::new();
+            let mut chunk = [0u8; 8192];
+            loop {
+                match webhook::parse_request(&buf) {
+                    Parsed::Incomplete => {}
+                    Parsed::Invalid(reason) => return Err(reason),
+                    Parsed::Complete(request) => return Ok(request),
+                }
+                match stream.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return Err("Connection closed"),
+                    Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                }
+            }
+        })
+        .await
+        .unwrap_or(Err("Request timed out"));
+
+        let (response, payload) = match request {
+            Ok(request) if request.method != "POST" => (webhook::response(405, "Method Not Allowed", "POST only"), None),
+            Ok(request) => match self.authenticate(&request).await {
+                Ok(payload) => (webhook::response(200, "OK", "ok"), Some(payload)),
+                Err(reason) => {
+                    println!("[Linear] Rejected webhook: {}", reason);
+                    (webhook::response(401, "Unauthorized", reason), None)
+                }
+            },
+            Err(reason) => (webhook::response(400, "Bad Request", reason), None),
+        };
+        // Answer before dispatching; Linear retries slow deliveries
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+
+        if let Some(payload) = payload {
+            self.handle_event(payload).await;
+        }
+    }
+
+    /// The delivery's JSON when it's signed with the configured secret
+    async fn authenticate(&self, request: &webhook::WebhookRequest) -> Result<Value, &'static str> {
+        let secret = self.config.read().await.as_ref()
+            .and_then(|c| c.webhook_secret.clone())
+            .filter(|s| !s.trim().is_empty())
+            .ok_or("No signing secret configured")?;
+        let signature = request.headers.get("linear-signature").ok_or("Missing Linear-Signature")?;
+        if !webhook::verify_signature(&secret, &request.body, signature) {
+            return Err("Bad signature");
+        }
+        let payload: Value = serde_json::from_slice(&request.body).map_err(|_| "Body is not JSON")?;
+        if !webhook::is_fresh(&payload, chrono::Utc::now().timestamp_millis()) {
+            return Err("Stale webhookTimestamp");
+        }
+        Ok(payload)
+    }
+
+    async fn handle_event(&self, payload: Value) {
+        let Some(config) = self.config.read().await.clone() else { return };
+        let Some(issue) = webhook::labelled_issue(&payload, config.label()) else { return };
+
+        let already_working = self.dispatches.read().await
+            .get(&issue.id)
+            .is_some_and(|d| d.status == DispatchStatus::Working);
+        if already_working {
+            println!("[Linear] {} is already being worked on", issue.identifier);
+            return;
+        }
+
+        let tracker: Arc<dyn IssueTracker> = Arc::new(LinearTracker::new(&config.api_key, None));
+        let issue = Issue::from(issue);
+        let Ok(task_id) = pipeline::start(tracker.as_ref(), &self.session_manager, &issue).await else { return };
+
+        let dispatch = LinearDispatch {
+            issue_id: issue.id.clone(),
+            identifier: issue.key.clone(),
+            title: issue.title.clone(),
+            task_id: task_id.clone(),
+            status: DispatchStatus::Working,
+            dispatched_at: chrono::Utc::now().to_rfc3339(),
+            finished_at: None,
+        };
+        self.dispatches.write().await.insert(issue.id.clone(), dispatch.clone());
+        self.emit(&dispatch).await;
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let outcome = pipeline::follow(tracker.as_ref(), &service.session_manager, &issue, &task_id).await;
+            service.finish(&issue.id, &task_id, outcome).await;
+        });
+    }
+
+    async fn finish(&self, issue_id: &str, task_id: &str, outcome: TaskOutcome) {
+        let dispatch = {
+            let mut dispatches = self.dispatches.write().await;
+            let Some(dispatch) = dispatches.get_mut(issue_id).filter(|d| d.task_id == task_id) else { return };
+            dispatch.status = match outcome {
+                TaskOutcome::Completed => DispatchStatus::Completed,
+                TaskOutcome::Failed(_) => DispatchStatus::Failed,
+            };
+            dispatch.finished_at = Some(chrono::Utc::now().to_rfc3339());
+            dispatch.clone()
+        };
+        self.emit(&dispatch).await;
+    }
+
+    async fn emit(&self, dispatch: &LinearDispatch) {
+        if let Some(handle) = self.app_handle.read().await.as_ref() {
+            let _ = handle.emit("linear-dispatch", dispatch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_of_kind() {
+        let state = |id: &str, kind: &str, position: f64| WorkflowState {
+            id: id.to_string(),
+            name: id.to_string(),
+            kind: kind.to_string(),
+            position,
+        };
+        let states = vec![state("In Review", "started", 3.0), state("In Progress", "started", 2.0), state("Todo", "unstarted", 1.0)];
+        assert_eq!(state_of_kind(&states, "started").map(|s| s.id.as_str()), Some("In Progress"));
+        assert_eq!(state_of_kind(&states, "unstarted").map(|s| s.id.as_str()), Some("Todo"));
+        assert!(state_of_kind(&states, "completed").is_none());
+    }
+}
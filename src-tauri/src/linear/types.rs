@@ -0,0 +1,81 @@
+use serde::{Deserialize, Serialize};
+
+/// Port the webhook listener binds to when none is configured
+pub const DEFAULT_WEBHOOK_PORT: u16 = 8787;
+/// Label that hands an issue to an agent when none is configured
+pub const DEFAULT_AGENT_LABEL: &str = "agent";
+
+/// The frontend's Linear settings; only the fields the backend uses
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearConfig {
+    pub api_key: String,
+    #[serde(default)]
+    pub enable_webhooks: bool,
+    /// Signing secret of the Linear webhook; required to receive webhooks
+    #[serde(default)]
+    pub webhook_secret: Option<String>,
+    #[serde(default)]
+    pub webhook_port: Option<u16>,
+    #[serde(default)]
+    pub agent_label: Option<String>,
+}
+
+impl LinearConfig {
+    pub fn port(&self) -> u16 {
+        self.webhook_port.unwrap_or(DEFAULT_WEBHOOK_PORT)
+    }
+
+    pub fn label(&self) -> &str {
+        self.agent_label.as_deref().filter(|label| !label.trim().is_empty()).unwrap_or(DEFAULT_AGENT_LABEL)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearIssue {
+    pub id: String,
+    /// e.g. `ENG-123`
+    pub identifier: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub url: Option<String>,
+    pub team_id: String,
+    /// Name of the workflow state, e.g. `In Progress`
+    #[serde(default)]
+    pub state: Option<String>,
+    /// `backlog`, `unstarted`, `started`, `completed` or `canceled`
+    #[serde(default)]
+    pub state_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkflowState {
+    pub id: String,
+    pub name: String,
+    /// `backlog`, `unstarted`, `started`, `completed` or `canceled`
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub position: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DispatchStatus {
+    Working,
+    Completed,
+    Failed,
+}
+
+/// A Linear issue and the orchestrator task working on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinearDispatch {
+    pub issue_id: String,
+    pub identifier: String,
+    pub title: String,
+    pub task_id: String,
+    pub status: DispatchStatus,
+    pub dispatched_at: String,
+    pub finished_at: Option<String>,
+}
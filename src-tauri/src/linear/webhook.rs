@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+
+use super::types::LinearIssue;
+
+/// Largest request body accepted by the listener
+pub const MAX_BODY_BYTES: usize = 1024 * 1024;
+/// How far a delivery's `webhookTimestamp` may be from now, to refuse replays
+pub const MAX_TIMESTAMP_SKEW_MS: i64 = 60_000;
+
+#[derive(Debug, PartialEq)]
+pub struct WebhookRequest {
+    pub method: String,
+    pub path: String,
+    /// Header names lowercased
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Parsed {
+    /// More bytes are needed
+    Incomplete,
+    Invalid(&'static str),
+    Complete(WebhookRequest),
+}
+
+/// Parse the HTTP/1.1 request read so far
+pub fn parse_request(buf: &[u8]) -> Parsed {
+    let Some(head_end) = buf.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return if buf.len() > MAX_BODY_BYTES { Parsed::Invalid("Headers too large") } else { Parsed::Incomplete };
+    };
+    let Ok(head) = std::str::from_utf8(&buf[..head_end]) else {
+        return Parsed::Invalid("Headers are not UTF-8");
+    };
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Parsed::Invalid("Malformed request line");
+    };
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length = match headers.get("content-length").map(|length| length.parse::<usize>()) {
+        Some(Ok(length)) => length,
+        Some(Err(_)) => return Parsed::Invalid("Bad Content-Length"),
+        None => 0,
+    };
+    if length > MAX_BODY_BYTES {
+        return Parsed::Invalid("Body too large");
+    }
+    let body = &buf[head_end + 4..];
+    if body.len() < length {
+        return Parsed::Incomplete;
+    }
+    Parsed::Complete(WebhookRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        headers,
+        body: body[..length].to_vec(),
+    })
+}
+
+/// A minimal HTTP response closing the connection
+pub fn response(status: u16, reason: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+}
+
+/// Whether `signature`, the hex `Linear-Signature` header, is the body's
+/// HMAC-SHA256 under the webhook's signing secret
+pub fn verify_signature(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature.trim()) else { return false };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else { return false };
+    mac.update(body);
+    // Compares in constant time
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Whether the delivery was sent recently enough to not be a replay
+pub fn is_fresh(payload: &Value, now_ms: i64) -> bool {
+    payload["webhookTimestamp"].as_i64().is_some_and(|sent| (now_ms - sent).abs() <= MAX_TIMESTAMP_SKEW_MS)
+}
+
+/// The issue of an `Issue` webhook when the event gave it `label`: it was
+/// created with the label or updated to add it
+pub fn labelled_issue(payload: &Value, label: &str) -> Option<LinearIssue> {
+    if payload["type"] != "Issue" {
+        return None;
+    }
+    let data = &payload["data"];
+    let label_id = data["labels"]
+        .as_array()?
+        .iter()
+        .find(|l| l["name"].as_str().is_some_and(|name| name.trim().eq_ignore_ascii_case(label.trim())))?["id"]
+        .as_str()?;
+
+    let added = match payload["action"].as_str()? {
+        "create" => true,
+        // `updatedFrom` holds the previous value of fields that changed
+        "update" => payload["updatedFrom"]["labelIds"]
+            .as_array()
+            .is_some_and(|previous| !previous.iter().any(|id| id == label_id)),
+        _ => false,
+    };
+    if !added {
+        return None;
+    }
+
+    Some(LinearIssue {
+        id: data["id"].as_str()?.to_string(),
+        identifier: data["identifier"].as_str()?.to_string(),
+        title: data["title"].as_str().unwrap_or_default().to_string(),
+        description: data["description"].as_str().filter(|d| !d.trim().is_empty()).map(str::to_string),
+        url: payload["url"].as_str().or(data["url"].as_str()).map(str::to_string),
+        team_id: data["teamId"].as_str().or(data["team"]["id"].as_str())?.to_string(),
+        state: data["state"]["name"].as_str().map(str::to_string),
+        state_type: data["state"]["type"].as_str().map(str::to_string),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_request() {
+        let head = "POST /linear HTTP/1.1\r\nHost: localhost\r\nContent-Length: 11\r\nLinear-Signature: ab12\r\n\r\n";
+        assert_eq!(parse_request(&head.as_bytes()[..20]), Parsed::Incomplete);
+        assert_eq!(parse_request(format!("{}{{\"a\"", head).as_bytes()), Parsed::Incomplete);
+
+        let Parsed::Complete(request) = parse_request(format!("{}{{\"a\": true}}", head).as_bytes()) else {
+            panic!("request should be complete");
+        };
+        assert_eq!((request.method.as_str(), request.path.as_str()), ("POST", "/linear"));
+        assert_eq!(request.headers.get("linear-signature").map(String::as_str), Some("ab12"));
+        assert_eq!(request.body, b"{\"a\": true}");
+
+        assert_eq!(parse_request(b"POST / HTTP/1.1\r\nContent-Length: x\r\n\r\n"), Parsed::Invalid("Bad Content-Length"));
+    }
+
+    #[test]
+    fn test_verify_signature() {
+        // RFC 4231 test case 2
+        let mac = "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843";
+        assert!(verify_signature("Jefe", b"what do ya want for nothing?", mac));
+        assert!(verify_signature("Jefe", b"what do ya want for nothing?", &mac.to_ascii_uppercase()));
+        assert!(!verify_signature("Jefe", b"what do ya want for nothing!", mac));
+        assert!(!verify_signature("Jefe", b"what do ya want for nothing?", "5bdc"));
+        assert!(!verify_signature("Jefe", b"what do ya want for nothing?", "not hex"));
+
+        assert!(is_fresh(&json!({ "webhookTimestamp": 1_000_000 }), 1_030_000));
+        assert!(!is_fresh(&json!({ "webhookTimestamp": 1_000_000 }), 1_090_000));
+        assert!(!is_fresh(&json!({}), 1_000_000));
+    }
+
+    #[test]
+    fn test_labelled_issue() {
+        let payload = |action: &str, updated_from: Value| json!({
+            "action": action,
+            "type": "Issue",
+            "url": "https://linear.app/acme/issue/ENG-42/fix-login",
+            "data": {
+                "id": "issue-1", "identifier": "ENG-42", "title": "Fix login", "description": "Redirect loops", "teamId": "team-1",
+                "labelIds": ["label-bug", "label-agent"],
+                "labels": [{ "id": "label-bug", "name": "Bug" }, { "id": "label-agent", "name": "Agent" }]
+            },
+            "updatedFrom": updated_from
+        });
+
+        let issue = labelled_issue(&payload("update", json!({ "labelIds": ["label-bug"] })), "agent").unwrap();
+        assert_eq!((issue.id.as_str(), issue.identifier.as_str(), issue.team_id.as_str()), ("issue-1", "ENG-42", "team-1"));
+        assert_eq!(issue.url.as_deref(), Some("https://linear.app/acme/issue/ENG-42/fix-login"));
+
+        assert!(labelled_issue(&payload("create", Value::Null), "agent").is_some());
+        // Already labelled, or a change to something else
+        assert!(labelled_issue(&payload("update", json!({ "labelIds": ["label-agent"] })), "agent").is_none());
+        assert!(labelled_issue(&payload("update", json!({ "title": "Login" })), "agent").is_none());
+        assert!(labelled_issue(&payload("remove", Value::Null), "agent").is_none());
+        assert!(labelled_issue(&payload("create", Value::Null), "frontend").is_none());
+    }
+}
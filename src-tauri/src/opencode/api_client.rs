@@ -41,6 +41,45 @@ impl OpenCodeApiClient {
         })
     }
 
+    /// Run `prompt` in a new session and wait for the agent's reply, returning
+    /// its text. The message endpoint only answers once the agent is done.
+    pub async fn run_prompt(&self, title: &str, prompt: &str) -> Result<String, String> {
+        let session: serde_json::Value = self.client
+            .post(format!("{}/session", self.base_url))
+            .json(&json!({ "title": title }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Failed to create session: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse session: {}", e))?;
+        let session_id = session["id"].as_str().ok_or("Session has no id")?;
+
+        let reply: serde_json::Value = self.client
+            .post(format!("{}/session/{}/message", self.base_url, session_id))
+            .json(&json!({ "parts": [{ "type": "text", "text": prompt }] }))
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| format!("Prompt failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse reply: {}", e))?;
+
+        if let Some(error) = reply["info"]["error"].as_object() {
+            let message = error.get("data").and_then(|d| d["message"].as_str())
+                .or_else(|| error.get("name").and_then(|n| n.as_str()))
+                .unwrap_or("Agent error");
+            return Err(message.to_string());
+        }
+        let text: Vec<&str> = reply["parts"]
+            .as_array()
+            .map(|parts| parts.iter().filter(|p| p["type"] == "text").filter_map(|p| p["text"].as_str()).collect())
+            .unwrap_or_default();
+        Ok(text.join("\n"))
+    }
+
     pub async fn get_openapi_spec(&self) -> Result<serde_json::Value, String> {
         let url = format!("{}/doc", self.base_url);
         match self.client.get(&url).send().await {
@@ -109,6 +148,33 @@ mod tests {
         assert!(response.data.is_some());
     }
 
+    #[tokio::test]
+    async fn test_run_prompt_returns_reply_text() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/session"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": "ses_1" })))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/session/ses_1/message"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "info": { "id": "msg_1" },
+                "parts": [
+                    { "type": "step-start" },
+                    { "type": "text", "text": "Fixed the redirect" },
+                    { "type": "text", "text": "Tests pass" }
+                ]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let client = OpenCodeApiClient::new("127.0.0.1", mock_server.address().port());
+        let reply = client.run_prompt("Task", "Fix login").await.unwrap();
+        assert_eq!(reply, "Fixed the redirect\nTests pass");
+    }
+
     #[tokio::test]
     async fn test_handle_connection_error() {
         // Use a port that's guaranteed not to be listening
@@ -76,6 +76,11 @@ impl OpenCodeService {
     }
 
     pub async fn spawn_server(&self, port: u16, working_dir: Option<String>) -> Result<OpenCodeServer, String> {
+        self.spawn_server_with_env(port, working_dir, HashMap::new()).await
+    }
+
+    /// Spawn a server with extra environment variables, e.g. a project's
+    pub async fn spawn_server_with_env(&self, port: u16, working_dir: Option<String>, env: HashMap<String, String>) -> Result<OpenCodeServer, String> {
         // Check if port is available
         if !Self::is_port_available(port).await {
             // Try to clean up the port first
@@ -109,6 +114,7 @@ impl OpenCodeService {
             .arg("-h")
             .arg("localhost")
             .current_dir(&working_dir)
+            .envs(&env)
             .kill_on_drop(true)
             .spawn()
             .map_err(|e| format!("Failed to spawn OpenCode server: {}. Make sure 'opencode' is installed and in PATH", e))?;
@@ -180,6 +186,10 @@ impl OpenCodeService {
     }
 
     pub async fn spawn_tui_server(&self, port: u16, model: Option<String>, working_dir: Option<String>) -> Result<OpenCodeServer, String> {
+        self.spawn_tui_server_with_env(port, model, working_dir, HashMap::new()).await
+    }
+
+    pub async fn spawn_tui_server_with_env(&self, port: u16, model: Option<String>, working_dir: Option<String>, env: HashMap<String, String>) -> Result<OpenCodeServer, String> {
         // Check if port is available
         if !Self::is_port_available(port).await {
             // Try to clean up the port first
@@ -227,6 +237,7 @@ impl OpenCodeService {
             .arg(port.to_string())
             .arg(&model_arg)
             .current_dir(&working_dir)
+            .envs(&env)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true)
@@ -304,6 +315,10 @@ impl OpenCodeService {
     }
 
     pub async fn spawn_sdk_server(&self, port: u16, model: Option<String>, working_dir: Option<String>) -> Result<OpenCodeServer, String> {
+        self.spawn_sdk_server_with_env(port, model, working_dir, HashMap::new()).await
+    }
+
+    pub async fn spawn_sdk_server_with_env(&self, port: u16, model: Option<String>, working_dir: Option<String>, env: HashMap<String, String>) -> Result<OpenCodeServer, String> {
         // Check if port is available
         if !Self::is_port_available(port).await {
             // Try to clean up the port first
@@ -351,6 +366,7 @@ impl OpenCodeService {
             .arg(port.to_string())
             .arg(&model_arg)
             .current_dir(&working_dir)
+            .envs(&env)
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
             .kill_on_drop(true)
@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-/// Manages all registered coding agent plugins
+/// Manages all registered coding agent plugins. Clones share the same
+/// plugins, servers and sessions.
+#[derive(Clone)]
 pub struct PluginManager {
     plugins: Arc<RwLock<HashMap<String, Box<dyn CodingAgentPlugin>>>>,
     active_plugin: Arc<RwLock<Option<String>>>,
@@ -173,6 +175,37 @@ impl PluginManager {
         plugin.send_command(session_id, command, context).await
     }
 
+    /// Send a one-off prompt to the active plugin and return its reply.
+    /// Reuses one of the plugin's running servers, or starts one on `port`
+    /// and stops it again afterwards.
+    pub async fn prompt(&self, prompt: &str, working_dir: Option<String>, port: u16) -> Result<String, String> {
+        let plugin_id = self.get_active_plugin().await?;
+        let running = self.servers.read().await
+            .values()
+            .find(|s| s.plugin_id == plugin_id && matches!(s.status, ServerStatus::Running))
+            .map(|s| s.id.clone());
+        let (server_id, started) = match running {
+            Some(id) => (id, false),
+            None => (self.spawn_server(port, None, working_dir).await?.id, true),
+        };
+
+        let reply = self.prompt_server(&server_id, prompt).await;
+
+        if started {
+            if let Err(e) = self.stop_server(&server_id).await {
+                println!("Failed to stop server {} started for a prompt: {}", server_id, e);
+            }
+        }
+        reply
+    }
+
+    async fn prompt_server(&self, server_id: &str, prompt: &str) -> Result<String, String> {
+        let session = self.create_session(server_id, HashMap::new()).await?;
+        let response = self.send_command(&session.id, prompt, None).await;
+        self.sessions.write().await.remove(&session.id);
+        response.map(|r| r.content)
+    }
+
     /// List all servers
     pub async fn list_servers(&self) -> Vec<AgentServer> {
         let servers = self.servers.read().await;
@@ -223,4 +256,150 @@ impl PluginManager {
 
         plugin.handle_tool_approval(session_id, tool_use, approved).await
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Plugin that echoes prompts and records which servers it stopped
+    struct EchoPlugin {
+        config: PluginConfig,
+        stopped: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl EchoPlugin {
+        fn new(stopped: Arc<Mutex<Vec<String>>>) -> Self {
+            Self {
+                config: PluginConfig {
+                    name: "Echo".to_string(),
+                    version: "1.0.0".to_string(),
+                    description: String::new(),
+                    author: String::new(),
+                    icon: None,
+                    supported_models: Vec::new(),
+                    default_model: "echo".to_string(),
+                    requires_api_key: false,
+                    ui_component: UiComponentType::Custom,
+                    capabilities: PluginCapabilities {
+                        file_operations: false,
+                        terminal_access: false,
+                        git_operations: false,
+                        web_search: false,
+                        code_execution: false,
+                        custom_tools: Vec::new(),
+                    },
+                },
+                stopped,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl CodingAgentPlugin for EchoPlugin {
+        fn get_config(&self) -> &PluginConfig {
+            &self.config
+        }
+
+        fn get_id(&self) -> &str {
+            "echo"
+        }
+
+        async fn initialize(&mut self, _settings: HashMap<String, String>) -> Result<(), String> {
+            Ok(())
+        }
+
+        async fn spawn_server(&self, port: u16, _model: Option<String>, working_dir: Option<String>) -> Result<AgentServer, String> {
+            Ok(AgentServer {
+                id: format!("echo-{}", port),
+                plugin_id: "echo".to_string(),
+                host: "127.0.0.1".to_string(),
+                port,
+                status: ServerStatus::Running,
+                model: "echo".to_string(),
+                working_dir: working_dir.unwrap_or_default(),
+                created_at: String::new(),
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn stop_server(&self, server_id: &str) -> Result<(), String> {
+            self.stopped.lock().unwrap().push(server_id.to_string());
+            Ok(())
+        }
+
+        async fn health_check(&self, _server_id: &str) -> Result<bool, String> {
+            Ok(true)
+        }
+
+        async fn create_session(&self, server_id: &str, _config: HashMap<String, serde_json::Value>) -> Result<AgentSession, String> {
+            Ok(AgentSession {
+                id: format!("{}-session", server_id),
+                server_id: server_id.to_string(),
+                plugin_id: "echo".to_string(),
+                created_at: String::new(),
+                status: SessionStatus::Active,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn send_command(&self, session_id: &str, command: &str, _context: Option<HashMap<String, String>>) -> Result<AgentResponse, String> {
+            if command == "fail" {
+                return Err("agent failed".to_string());
+            }
+            Ok(AgentResponse {
+                session_id: session_id.to_string(),
+                content: format!("echo: {}", command),
+                response_type: ResponseType::Message,
+                metadata: HashMap::new(),
+            })
+        }
+
+        async fn get_session_status(&self, _session_id: &str) -> Result<SessionStatus, String> {
+            Ok(SessionStatus::Active)
+        }
+
+        async fn list_sessions(&self) -> Vec<AgentSession> {
+            Vec::new()
+        }
+
+        async fn cleanup(&mut self) -> Result<(), String> {
+            Ok(())
+        }
+    }
+
+    async fn manager() -> (PluginManager, Arc<Mutex<Vec<String>>>) {
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+        let manager = PluginManager::new();
+        manager.register_plugin(Box::new(EchoPlugin::new(stopped.clone()))).await.unwrap();
+        (manager, stopped)
+    }
+
+    #[tokio::test]
+    async fn test_prompt_stops_the_server_it_started() {
+        let (manager, stopped) = manager().await;
+
+        assert_eq!(manager.prompt("hello", None, 4100).await.unwrap(), "echo: hello");
+        assert_eq!(*stopped.lock().unwrap(), vec!["echo-4100"]);
+        assert!(manager.list_servers().await.is_empty());
+        assert!(manager.list_sessions().await.is_empty());
+
+        // A failed prompt still cleans up
+        assert_eq!(manager.prompt("fail", None, 4101).await.unwrap_err(), "agent failed");
+        assert_eq!(stopped.lock().unwrap().len(), 2);
+        assert!(manager.list_servers().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_prompt_reuses_a_running_server() {
+        let (manager, stopped) = manager().await;
+        manager.spawn_server(4200, None, None).await.unwrap();
+
+        assert_eq!(manager.clone().prompt("hi", None, 4300).await.unwrap(), "echo: hi");
+        assert!(stopped.lock().unwrap().is_empty());
+        let servers = manager.list_servers().await;
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].id, "echo-4200");
+    }
+}
@@ -0,0 +1,179 @@
+use crate::database::project_commands::{self, SavedCommand};
+use crate::database::project_templates::{self, ProjectTemplate};
+use crate::database::{project_tags, DatabaseManager};
+use crate::wezterm::PaneLayout;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::manager::ProjectsManager;
+use super::types::{CreateProjectRequest, Project, UpdateProjectRequest};
+
+/// Bump when the bundle layout changes incompatibly
+pub const BUNDLE_VERSION: u32 = 1;
+
+pub const BUNDLE_EXTENSION: &str = "ninjasquad.json";
+
+/// A project's configuration in a portable form: the project row with its
+/// settings and tags, saved commands and templates and terminal layout. The code itself
+/// is not included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectBundle {
+    pub version: u32,
+    pub exported_at: String,
+    pub project: Project,
+    #[serde(default)]
+    pub commands: Vec<SavedCommand>,
+    #[serde(default)]
+    pub templates: Vec<ProjectTemplate>,
+    #[serde(default)]
+    pub terminal_layout: Option<PaneLayout>,
+}
+
+pub fn build(db: &DatabaseManager, project_id: &str) -> Result<ProjectBundle, String> {
+    let project = ProjectsManager::new(db)
+        .get(project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let (commands, templates, terminal_layout) = db
+        .with_connection(|conn| {
+            Ok((
+                project_commands::list_commands(conn, project_id)?,
+                project_templates::list_templates(conn)?,
+                crate::database::wezterm::get_layout(conn, project_id)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(ProjectBundle {
+        version: BUNDLE_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        project,
+        commands,
+        templates,
+        terminal_layout,
+    })
+}
+
+pub fn parse(content: &str) -> Result<ProjectBundle, String> {
+    let bundle: ProjectBundle =
+        serde_json::from_str(content).map_err(|e| format!("Invalid project bundle: {}", e))?;
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Project bundle version {} is newer than this app supports ({})",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+    Ok(bundle)
+}
+
+pub fn read(path: &Path) -> Result<ProjectBundle, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    parse(&content)
+}
+
+/// Recreate a bundled project at `project_path`, which defaults to the path
+/// it had on the exporting machine. Fails if that folder doesn't exist here,
+/// so the caller can ask where the project lives.
+pub fn import(db: &DatabaseManager, bundle: ProjectBundle, project_path: Option<String>) -> Result<Project, String> {
+    let path = project_path.unwrap_or_else(|| bundle.project.path.clone());
+    if !Path::new(&path).is_dir() {
+        return Err(format!("Project folder not found: {}", path));
+    }
+
+    let manager = ProjectsManager::new(db);
+    if manager.exists(&path).map_err(|e| e.to_string())? {
+        return Err(format!("A project already exists at {}", path));
+    }
+
+    let source = bundle.project;
+    let project = manager
+        .create_with_settings(
+            CreateProjectRequest {
+                name: source.name,
+                path,
+                description: source.description,
+                color: source.color,
+            },
+            source.settings.unwrap_or_default(),
+        )
+        .map_err(|e| e.to_string())?;
+
+    db.with_connection(|conn| {
+        for tag in &source.tags {
+            project_tags::add_tag(conn, &project.id, tag)?;
+        }
+        for command in &bundle.commands {
+            project_commands::save_command(conn, &project.id, &command.name, &command.command, command.target)?;
+        }
+        for template in &bundle.templates {
+            project_templates::import_template(conn, template)?;
+        }
+        if let Some(layout) = &bundle.terminal_layout {
+            crate::database::wezterm::save_layout(conn, &project.id, layout)?;
+        }
+        Ok(())
+    })
+    .map_err(|e| e.to_string())?;
+
+    if source.is_favorite {
+        manager.update(
+            &project.id,
+            UpdateProjectRequest {
+                name: None,
+                description: None,
+                color: None,
+                is_favorite: Some(true),
+                settings: None,
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    manager
+        .get(&project.id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundle_json(version: u32) -> String {
+        serde_json::json!({
+            "version": version,
+            "exported_at": "2024-01-01T00:00:00Z",
+            "project": {
+                "id": "p1",
+                "name": "api",
+                "path": "/work/api",
+                "description": null,
+                "color": "#ff0000",
+                "created_at": "2024-01-01",
+                "last_accessed": null,
+                "is_favorite": true,
+                "settings": { "default_model": "sonnet", "port_range": [4000, 5000], "auto_start_server": false },
+            },
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_bundle() {
+        let bundle = parse(&bundle_json(BUNDLE_VERSION)).unwrap();
+        assert_eq!(bundle.project.path, "/work/api");
+        assert!(bundle.project.tags.is_empty());
+        assert!(bundle.commands.is_empty());
+        assert!(bundle.templates.is_empty());
+        assert!(bundle.terminal_layout.is_none());
+
+        let settings = bundle.project.settings.unwrap();
+        assert_eq!(settings.default_model.as_deref(), Some("sonnet"));
+        assert!(settings.dev_server_command.is_none());
+
+        assert!(parse(&bundle_json(BUNDLE_VERSION + 1)).is_err());
+        assert!(parse("{}").is_err());
+    }
+}
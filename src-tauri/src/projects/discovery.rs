@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Setting holding the parent directories scanned when none are given
+pub const SCAN_ROOTS_SETTING: &str = "project_scan_roots";
+
+/// How deep below each root to look for projects
+pub const MAX_DEPTH: usize = 4;
+
+/// Files or folders that mark a project root
+const MARKERS: &[&str] = &[".git", "package.json", "Cargo.toml"];
+
+/// Folders never worth descending into
+pub const SKIP_DIRS: &[&str] = &["node_modules", "target", "dist", "build", "vendor", ".venv"];
+
+/// A directory that looks like a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectCandidate {
+    pub name: String,
+    pub path: String,
+    /// Which of `.git`, `package.json` and `Cargo.toml` were found
+    pub markers: Vec<String>,
+}
+
+fn markers(dir: &Path) -> Vec<String> {
+    MARKERS
+        .iter()
+        .filter(|marker| dir.join(marker).exists())
+        .map(|marker| marker.to_string())
+        .collect()
+}
+
+fn walk(dir: &Path, depth: usize, found: &mut Vec<ProjectCandidate>) {
+    let markers = markers(dir);
+    if !markers.is_empty() {
+        // Nested packages belong to the project already found
+        found.push(ProjectCandidate {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| dir.to_string_lossy().to_string()),
+            path: dir.to_string_lossy().to_string(),
+            markers,
+        });
+        return;
+    }
+    if depth == 0 {
+        return;
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_ref())
+        })
+        .collect();
+    children.sort();
+
+    for child in children {
+        walk(&child, depth - 1, found);
+    }
+}
+
+/// Find project roots under each of `roots`, leaving out paths in `known`.
+/// Symlinked folders are not followed.
+pub fn scan(roots: &[String], known: &HashSet<String>) -> Vec<ProjectCandidate> {
+    let mut found = Vec::new();
+    for root in roots {
+        let root = Path::new(root);
+        if root.is_dir() {
+            walk(root, MAX_DEPTH, &mut found);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    found.retain(|candidate| !known.contains(&candidate.path) && seen.insert(candidate.path.clone()));
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_finds_roots_once() {
+        let root = std::env::temp_dir().join(format!("ninjasquad-scan-{}", uuid::Uuid::new_v4()));
+        for dir in ["api/.git", "api/packages/ui", "clients/web", "clients/web/node_modules/dep", "notes"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("api/packages/ui/package.json"), "{}").unwrap();
+        std::fs::write(root.join("clients/web/package.json"), "{}").unwrap();
+        std::fs::write(root.join("clients/web/Cargo.toml"), "").unwrap();
+
+        let roots = vec![root.to_string_lossy().to_string()];
+        let candidates = scan(&roots, &HashSet::new());
+        let names: Vec<_> = candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "web"]);
+        assert_eq!(candidates[1].markers, vec!["package.json", "Cargo.toml"]);
+
+        let known = HashSet::from([candidates[0].path.clone()]);
+        assert_eq!(scan(&roots, &known).len(), 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,271 @@
+use crate::database::DatabaseManager;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::manager::ProjectsManager;
+use super::types::Project;
+
+#[cfg(feature = "keychain")]
+pub(crate) const KEYCHAIN_SERVICE: &str = "ninjasquad";
+
+#[cfg(feature = "keychain")]
+fn keychain_account(project_id: &str, name: &str) -> String {
+    format!("project:{}:{}", project_id, name)
+}
+
+/// Environment variable names: letters, digits and `_`, not starting with
+/// a digit
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid environment variable name: '{}'", name))
+    }
+}
+
+#[cfg(feature = "keychain")]
+pub fn set_secret(project_id: &str, name: &str, value: &str) -> Result<(), String> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, &keychain_account(project_id, name))
+        .and_then(|entry| entry.set_password(value))
+        .map_err(|e| format!("Failed to store secret {} in keychain: {}", name, e))
+}
+
+#[cfg(feature = "keychain")]
+pub fn get_secret(project_id: &str, name: &str) -> Result<Option<String>, String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &keychain_account(project_id, name))
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    match entry.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read secret {} from keychain: {}", name, e)),
+    }
+}
+
+#[cfg(feature = "keychain")]
+pub fn delete_secret(project_id: &str, name: &str) -> Result<(), String> {
+    let entry = keyring::Entry::new(KEYCHAIN_SERVICE, &keychain_account(project_id, name))
+        .map_err(|e| format!("Failed to open keychain entry: {}", e))?;
+    match entry.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete secret {} from keychain: {}", name, e)),
+    }
+}
+
+#[cfg(not(feature = "keychain"))]
+const NO_KEYCHAIN: &str = "Project secrets need a build with the keychain feature";
+
+#[cfg(not(feature = "keychain"))]
+pub fn set_secret(_project_id: &str, _name: &str, _value: &str) -> Result<(), String> {
+    Err(NO_KEYCHAIN.to_string())
+}
+
+#[cfg(not(feature = "keychain"))]
+pub fn get_secret(_project_id: &str, _name: &str) -> Result<Option<String>, String> {
+    Err(NO_KEYCHAIN.to_string())
+}
+
+#[cfg(not(feature = "keychain"))]
+pub fn delete_secret(_project_id: &str, _name: &str) -> Result<(), String> {
+    Ok(())
+}
+
+/// Variables to inject into processes started for `project`: its plain
+/// variables plus the secrets held in the keychain. A secret that can't be
+/// read is left out.
+pub fn project_env(project: &Project) -> HashMap<String, String> {
+    let Some(settings) = &project.settings else {
+        return HashMap::new();
+    };
+
+    let mut env: HashMap<String, String> = settings.env.clone().into_iter().collect();
+    env.extend(secret_values(project, &settings.secret_env));
+    env
+}
+
+/// The project's keychain secrets called `names`. A secret that can't be
+/// read is left out.
+pub fn secret_values(project: &Project, names: &[String]) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for name in names {
+        match get_secret(&project.id, name) {
+            Ok(Some(value)) => {
+                values.insert(name.clone(), value);
+            }
+            Ok(None) => println!("Secret {} for project {} is missing from the keychain", name, project.name),
+            Err(e) => println!("Skipping secret {} for project {}: {}", name, project.name, e),
+        }
+    }
+    values
+}
+
+/// Variables in a `.env` file: `NAME=value` lines, optionally starting with
+/// `export`. Single-quoted values are taken as-is, double-quoted ones
+/// understand `\n`, `\t`, `\"` and `\\`, and unquoted ones end at ` #`.
+/// Lines that don't parse are skipped.
+pub fn parse_dotenv(text: &str) -> Vec<(String, String)> {
+    let mut vars = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else { continue };
+        let name = name.trim();
+        if validate_name(name).is_err() {
+            continue;
+        }
+
+        let value = value.trim_start();
+        let value = if let Some(rest) = value.strip_prefix('"') {
+            let mut out = String::new();
+            let mut chars = rest.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '"' => break,
+                    '\\' => match chars.next() {
+                        Some('n') => out.push('\n'),
+                        Some('t') => out.push('\t'),
+                        Some(other) => out.push(other),
+                        None => {}
+                    },
+                    c => out.push(c),
+                }
+            }
+            out
+        } else if let Some(rest) = value.strip_prefix('\'') {
+            rest.split('\'').next().unwrap_or_default().to_string()
+        } else {
+            let end = value.find(" #").unwrap_or(value.len());
+            value[..end].trim_end().to_string()
+        };
+        vars.push((name.to_string(), value));
+    }
+    vars
+}
+
+pub fn read_dotenv(path: &Path) -> Result<HashMap<String, String>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(parse_dotenv(&text).into_iter().collect())
+}
+
+/// Names of the `.env` files directly in `dir`, e.g. `.env.local`
+pub fn env_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name == ".env" || name.starts_with(".env."))
+        .collect();
+    names.sort();
+    names
+}
+
+/// The project `dir` belongs to: the one with the longest path containing it
+pub fn project_for_dir<'a>(projects: &'a [Project], dir: &str) -> Option<&'a Project> {
+    let dir = Path::new(dir);
+    projects
+        .iter()
+        .filter(|project| dir.starts_with(&project.path))
+        .max_by_key(|project| project.path.len())
+}
+
+/// Environment for a process started in `dir`, empty outside any project
+pub fn env_for_dir(db: &DatabaseManager, dir: &str) -> HashMap<String, String> {
+    match ProjectsManager::new(db).list(true) {
+        Ok(projects) => project_for_dir(&projects, dir).map(project_env).unwrap_or_default(),
+        Err(e) => {
+            println!("Failed to look up project environment for {}: {}", dir, e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Environment for a process started for the project with `project_id`
+pub fn env_for_project(db: &DatabaseManager, project_id: &str) -> HashMap<String, String> {
+    match ProjectsManager::new(db).get(project_id) {
+        Ok(Some(project)) => project_env(&project),
+        Ok(None) => HashMap::new(),
+        Err(e) => {
+            println!("Failed to look up project environment for {}: {}", project_id, e);
+            HashMap::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::types::ProjectSettings;
+
+    fn project(id: &str, path: &str) -> Project {
+        Project {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: path.to_string(),
+            description: None,
+            color: None,
+            created_at: "2024-01-01".to_string(),
+            last_accessed: None,
+            is_favorite: false,
+            settings: Some(ProjectSettings {
+                env: [("API_URL".to_string(), format!("http://{}", id))].into(),
+                ..ProjectSettings::default()
+            }),
+            is_archived: false,
+            is_missing: false,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("DATABASE_URL").is_ok());
+        assert!(validate_name("_x1").is_ok());
+        assert!(validate_name("1PASSWORD").is_err());
+        assert!(validate_name("MY-VAR").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let text = "# local settings\n\
+                    export DATABASE_URL=postgres://localhost/shop\n\
+                    PORT = 3000 # dev only\n\
+                    GREETING=\"hello\\nworld\" # quoted\n\
+                    RAW='a \\n b'\n\
+                    EMPTY=\n\
+                    not a variable\n\
+                    1BAD=x\n";
+        let vars = parse_dotenv(text);
+        let expected: Vec<(String, String)> = [
+            ("DATABASE_URL", "postgres://localhost/shop"),
+            ("PORT", "3000"),
+            ("GREETING", "hello\nworld"),
+            ("RAW", "a \\n b"),
+            ("EMPTY", ""),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+        assert_eq!(vars, expected);
+    }
+
+    #[test]
+    fn test_project_for_dir_prefers_nested_project() {
+        let projects = vec![project("mono", "/work/mono"), project("web", "/work/mono/apps/web")];
+
+        assert_eq!(project_for_dir(&projects, "/work/mono/apps/web/src").unwrap().id, "web");
+        assert_eq!(project_for_dir(&projects, "/work/mono/apps").unwrap().id, "mono");
+        assert!(project_for_dir(&projects, "/work/monorepo").is_none());
+
+        let env = project_env(project_for_dir(&projects, "/work/mono").unwrap());
+        assert_eq!(env["API_URL"], "http://mono");
+    }
+}
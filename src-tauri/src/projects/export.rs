@@ -0,0 +1,171 @@
+use crate::database::activity::{self, AgentActivity};
+use crate::database::conversation::{self, ConversationMessage};
+use crate::database::DatabaseManager;
+use crate::plugins::sessions::{PluginSession, PluginSessionManager};
+use serde::{Deserialize, Serialize};
+
+use super::manager::ProjectsManager;
+use super::types::Project;
+
+/// A plugin session and its full conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionExport {
+    pub session: PluginSession,
+    pub messages: Vec<ConversationMessage>,
+}
+
+/// Everything stored about a project, as written by `export_project_data`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectExport {
+    pub exported_at: String,
+    pub project: Project,
+    pub sessions: Vec<SessionExport>,
+    pub activity: Vec<AgentActivity>,
+}
+
+/// Collect a project's row, sessions, conversations and task history
+pub fn gather(db: &DatabaseManager, project_id: &str) -> Result<ProjectExport, String> {
+    let project = ProjectsManager::new(db)
+        .get(project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    let sessions = PluginSessionManager::new(db)
+        .list_by_project(project_id, None)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|session| {
+            let messages = db
+                .with_connection(|conn| conversation::get_session_messages(conn, &session.id))
+                .map_err(|e| e.to_string())?;
+            Ok(SessionExport { session, messages })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let activity = db
+        .with_connection(|conn| activity::list_project_activity(conn, &project.name))
+        .map_err(|e| e.to_string())?;
+
+    Ok(ProjectExport {
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        project,
+        sessions,
+        activity,
+    })
+}
+
+/// Render an export as a readable Markdown report
+pub fn to_markdown(export: &ProjectExport) -> String {
+    let project = &export.project;
+    let mut md = format!("# {}\n\n", project.name);
+
+    md.push_str(&format!("- Path: `{}`\n", project.path));
+    if let Some(description) = &project.description {
+        md.push_str(&format!("- Description: {}\n", description));
+    }
+    md.push_str(&format!("- Created: {}\n", project.created_at));
+    md.push_str(&format!("- Exported: {}\n", export.exported_at));
+
+    md.push_str(&format!("\n## Sessions ({})\n", export.sessions.len()));
+    for SessionExport { session, messages } in &export.sessions {
+        md.push_str(&format!(
+            "\n### {}\n\n- Plugin: {} ({})\n- Status: {}\n- Created: {}\n",
+            session.title, session.plugin_id, session.model, session.status, session.created_at
+        ));
+
+        for message in messages {
+            md.push_str(&format!("\n**{}** · {}\n\n{}\n", message.role, message.timestamp, message.content));
+        }
+    }
+
+    md.push_str(&format!("\n## Task History ({})\n\n", export.activity.len()));
+    for entry in &export.activity {
+        let mark = if entry.success { "✅" } else { "❌" };
+        md.push_str(&format!("- {} {} — {}\n", mark, entry.created_at, entry.summary));
+    }
+
+    md
+}
+
+/// Default file name for an export, e.g. `my-app-20240101-120000.md`
+pub fn file_name(project: &Project, extension: &str) -> String {
+    let slug: String = project
+        .name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    format!("{}-{}.{}", slug, chrono::Utc::now().format("%Y%m%d-%H%M%S"), extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> ProjectExport {
+        ProjectExport {
+            exported_at: "2024-01-02T00:00:00Z".to_string(),
+            project: Project {
+                id: "p1".to_string(),
+                name: "My App".to_string(),
+                path: "/tmp/my-app".to_string(),
+                description: None,
+                color: None,
+                created_at: "2024-01-01".to_string(),
+                last_accessed: None,
+                is_favorite: false,
+                settings: None,
+                is_archived: false,
+            is_missing: false,
+                tags: Vec::new(),
+            },
+            sessions: vec![SessionExport {
+                session: PluginSession {
+                    id: "s1".to_string(),
+                    project_id: "p1".to_string(),
+                    plugin_id: "claude".to_string(),
+                    title: "Fix auth".to_string(),
+                    working_directory: "/tmp/my-app".to_string(),
+                    model: "sonnet".to_string(),
+                    permission_mode: "default".to_string(),
+                    created_at: "2024-01-01".to_string(),
+                    last_active: None,
+                    status: "active".to_string(),
+                    config: None,
+                },
+                messages: vec![ConversationMessage {
+                    id: "m1".to_string(),
+                    session_id: "s1".to_string(),
+                    role: "user".to_string(),
+                    content: "The login token expires early".to_string(),
+                    timestamp: "2024-01-01T10:00:00Z".to_string(),
+                }],
+            }],
+            activity: vec![AgentActivity {
+                id: "a1".to_string(),
+                project_name: "My App".to_string(),
+                session_id: Some("s1".to_string()),
+                summary: "Fixed token expiry".to_string(),
+                success: true,
+                created_at: "2024-01-01T11:00:00Z".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_markdown_report_sections() {
+        let md = to_markdown(&sample());
+        assert!(md.starts_with("# My App\n"));
+        assert!(md.contains("## Sessions (1)"));
+        assert!(md.contains("### Fix auth"));
+        assert!(md.contains("**user** · 2024-01-01T10:00:00Z\n\nThe login token expires early"));
+        assert!(md.contains("## Task History (1)"));
+        assert!(md.contains("✅ 2024-01-01T11:00:00Z — Fixed token expiry"));
+    }
+
+    #[test]
+    fn test_file_name_slug() {
+        let name = file_name(&sample().project, "md");
+        assert!(name.starts_with("my-app-"));
+        assert!(name.ends_with(".md"));
+    }
+}
@@ -0,0 +1,242 @@
+use glob::{MatchOptions, Pattern};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::discovery::SKIP_DIRS;
+
+/// Listing stops after this many files
+pub const MAX_FILES: usize = 5000;
+
+/// A file or folder in a project. Folders only appear when something
+/// below them is listed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileNode {
+    pub name: String,
+    /// Relative to the project folder
+    pub path: String,
+    /// Bytes; for folders, the total of the files listed below them
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// None for files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<FileNode>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectFiles {
+    pub files: Vec<FileNode>,
+    pub file_count: usize,
+    pub total_size: u64,
+    /// Set when `MAX_FILES` was reached
+    pub truncated: bool,
+}
+
+/// Files under `root` that git doesn't ignore, as a tree. Folders that
+/// aren't repositories skip dependency and build folders instead.
+///
+/// A `glob` containing `/` is matched against the path from the project
+/// folder, otherwise against file names, so `*.rs` finds Rust files at
+/// any depth.
+pub fn list_files(root: &Path, glob: Option<&str>) -> Result<ProjectFiles, String> {
+    if !root.is_dir() {
+        return Err(format!("Project folder not found: {}", root.display()));
+    }
+    let pattern = glob
+        .map(|g| Pattern::new(g).map_err(|e| format!("Invalid glob {}: {}", g, e)))
+        .transpose()?;
+
+    let repo = git2::Repository::discover(root).ok();
+    let workdir = repo.as_ref().and_then(|r| r.workdir()).map(Path::to_path_buf);
+    let ignored = |path: &Path| match (&repo, &workdir) {
+        (Some(repo), Some(workdir)) => path
+            .strip_prefix(workdir)
+            .map(|relative| repo.is_path_ignored(relative).unwrap_or(false))
+            .unwrap_or(false),
+        _ => path
+            .file_name()
+            .is_some_and(|name| SKIP_DIRS.contains(&name.to_string_lossy().as_ref())),
+    };
+
+    let mut listing = ProjectFiles::default();
+    listing.files = walk(root, root, pattern.as_ref(), &ignored, &mut listing);
+    Ok(listing)
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    pattern: Option<&Pattern>,
+    ignored: &dyn Fn(&Path) -> bool,
+    listing: &mut ProjectFiles,
+) -> Vec<FileNode> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    // Folders first, each group by name
+    entries.sort_by_key(|entry| (!entry.file_type().map(|t| t.is_dir()).unwrap_or(false), entry.file_name()));
+
+    let mut nodes = Vec::new();
+    for entry in entries {
+        if listing.truncated {
+            break;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name == ".git" || ignored(&path) {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else { continue };
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().to_string();
+
+        if file_type.is_dir() {
+            let children = walk(root, &path, pattern, ignored, listing);
+            if !children.is_empty() {
+                let size = children.iter().map(|c| c.size).sum();
+                nodes.push(FileNode { name, path: relative, size, language: None, children: Some(children) });
+            }
+            continue;
+        }
+
+        if !pattern.is_none_or(|p| glob_matches(p, &relative)) {
+            continue;
+        }
+        if listing.file_count >= MAX_FILES {
+            listing.truncated = true;
+            break;
+        }
+        let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        listing.file_count += 1;
+        listing.total_size += size;
+        nodes.push(FileNode {
+            language: language(&name).map(str::to_string),
+            name,
+            path: relative,
+            size,
+            children: None,
+        });
+    }
+    nodes
+}
+
+fn glob_matches(pattern: &Pattern, relative: &str) -> bool {
+    if pattern.as_str().contains('/') {
+        let options = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+        pattern.matches_with(relative, options)
+    } else {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        pattern.matches(name)
+    }
+}
+
+/// Language of a file, by its name or extension
+pub fn language(name: &str) -> Option<&'static str> {
+    match name {
+        "Dockerfile" => return Some("Dockerfile"),
+        "Makefile" => return Some("Makefile"),
+        _ => {}
+    }
+    let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+    Some(match extension.as_str() {
+        "rs" => "Rust",
+        "ts" | "mts" | "cts" => "TypeScript",
+        "tsx" => "TSX",
+        "js" | "mjs" | "cjs" => "JavaScript",
+        "jsx" => "JSX",
+        "py" => "Python",
+        "go" => "Go",
+        "rb" => "Ruby",
+        "java" => "Java",
+        "kt" | "kts" => "Kotlin",
+        "swift" => "Swift",
+        "c" | "h" => "C",
+        "cc" | "cpp" | "cxx" | "hpp" => "C++",
+        "cs" => "C#",
+        "php" => "PHP",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
+        "html" | "htm" => "HTML",
+        "css" => "CSS",
+        "scss" | "sass" => "SCSS",
+        "vue" => "Vue",
+        "svelte" => "Svelte",
+        "json" => "JSON",
+        "toml" => "TOML",
+        "yaml" | "yml" => "YAML",
+        "md" | "mdx" => "Markdown",
+        _ => return None,
+    })
+}
+
+/// The tree as indented text for an agent's context, one entry per line
+pub fn outline(files: &[FileNode]) -> String {
+    let mut text = String::new();
+    write_outline(files, 0, &mut text);
+    text
+}
+
+fn write_outline(files: &[FileNode], depth: usize, text: &mut String) {
+    for node in files {
+        let indent = "  ".repeat(depth);
+        match &node.children {
+            Some(children) => {
+                text.push_str(&format!("{}{}/\n", indent, node.name));
+                write_outline(children, depth + 1, text);
+            }
+            None => match &node.language {
+                Some(language) => text.push_str(&format!("{}{} ({}, {} B)\n", indent, node.name, language, node.size)),
+                None => text.push_str(&format!("{}{} ({} B)\n", indent, node.name, node.size)),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_builds_filtered_tree() {
+        let root = std::env::temp_dir().join(format!("ninjasquad-files-{}", uuid::Uuid::new_v4()));
+        for dir in ["src/git", "docs", "target/debug"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+        std::fs::write(root.join("src/main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("src/git/mod.rs"), "pub mod x;\n").unwrap();
+        std::fs::write(root.join("docs/guide.md"), "# Guide\n").unwrap();
+        std::fs::write(root.join("target/debug/app"), "binary").unwrap();
+
+        let skip_target = |path: &Path| path.ends_with("target");
+        let mut listing = ProjectFiles::default();
+        let files = walk(&root, &root, None, &skip_target, &mut listing);
+        assert_eq!(listing.file_count, 4);
+        assert_eq!(
+            outline(&files),
+            "docs/\n  guide.md (Markdown, 8 B)\nsrc/\n  git/\n    mod.rs (Rust, 11 B)\n  main.rs (Rust, 13 B)\nCargo.toml (TOML, 10 B)\n"
+        );
+        assert_eq!(files[1].size, 24);
+
+        let rust = Pattern::new("*.rs").unwrap();
+        let mut listing = ProjectFiles::default();
+        let files = walk(&root, &root, Some(&rust), &skip_target, &mut listing);
+        assert_eq!(outline(&files), "src/\n  git/\n    mod.rs (Rust, 11 B)\n  main.rs (Rust, 13 B)\n");
+
+        let top_level = Pattern::new("src/*.rs").unwrap();
+        let mut listing = ProjectFiles::default();
+        walk(&root, &root, Some(&top_level), &skip_target, &mut listing);
+        assert_eq!(listing.file_count, 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_language_by_name_and_extension() {
+        assert_eq!(language("lib.rs"), Some("Rust"));
+        assert_eq!(language("App.TSX"), Some("TSX"));
+        assert_eq!(language("Dockerfile"), Some("Dockerfile"));
+        assert_eq!(language("LICENSE"), None);
+        assert_eq!(language("archive.tar.gz"), None);
+    }
+}
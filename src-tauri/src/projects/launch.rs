@@ -0,0 +1,170 @@
+use crate::database::DatabaseManager;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::manager::ProjectsManager;
+use super::types::Project;
+
+/// Port range used when a project has none configured
+pub const DEFAULT_PORT_RANGE: (u16, u16) = (4000, 5000);
+
+/// Arguments a spawn command was called with; anything left out is filled
+/// in from the project's settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    pub project_id: Option<String>,
+    pub plugin_id: Option<String>,
+    pub port: Option<u16>,
+    pub model: Option<String>,
+    pub working_dir: Option<String>,
+}
+
+/// What a server spawn will actually use
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchConfig {
+    pub project_id: Option<String>,
+    /// `None` means the active plugin
+    pub plugin_id: Option<String>,
+    pub port: u16,
+    pub model: Option<String>,
+    pub working_dir: Option<String>,
+    pub server_template: Option<String>,
+    /// Names of the project variables that will be injected
+    pub env_keys: Vec<String>,
+}
+
+fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+/// Merge a request with the project's settings. Explicit arguments win;
+/// without a port the first free one in the project's range is taken.
+/// Projects whose folder is missing can't be launched.
+pub fn resolve_with(
+    request: LaunchRequest,
+    project: Option<&Project>,
+    is_free: impl Fn(u16) -> bool,
+) -> Result<LaunchConfig, String> {
+    if let Some(project) = project.filter(|p| p.is_missing) {
+        return Err(format!("Project folder is missing: {}", project.path));
+    }
+
+    let settings = project.and_then(|p| p.settings.clone()).unwrap_or_default();
+
+    let port = match request.port {
+        Some(port) => port,
+        None => {
+            let (start, end) = settings.port_range.unwrap_or(DEFAULT_PORT_RANGE);
+            (start..=end)
+                .find(|port| is_free(*port))
+                .ok_or_else(|| format!("No free port between {} and {}", start, end))?
+        }
+    };
+
+    let mut env_keys: Vec<String> = settings.env.keys().cloned().collect();
+    env_keys.extend(settings.secret_env.iter().cloned());
+
+    Ok(LaunchConfig {
+        project_id: project.map(|p| p.id.clone()).or(request.project_id),
+        plugin_id: request.plugin_id.or(settings.default_plugin),
+        port,
+        model: request.model.or(settings.default_model),
+        working_dir: request.working_dir.or_else(|| project.map(|p| p.path.clone())),
+        server_template: settings.server_template,
+        env_keys,
+    })
+}
+
+pub fn resolve(request: LaunchRequest, project: Option<&Project>) -> Result<LaunchConfig, String> {
+    resolve_with(request, project, port_is_free)
+}
+
+/// Look up the request's project, if any, and resolve against it
+pub fn resolve_for(db: &DatabaseManager, request: LaunchRequest) -> Result<LaunchConfig, String> {
+    let project = match &request.project_id {
+        Some(id) => Some(
+            ProjectsManager::new(db)
+                .get(id)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Project not found: {}", id))?,
+        ),
+        None => None,
+    };
+    resolve(request, project.as_ref())
+}
+
+/// Environment for a resolved launch: the project's, or that of the
+/// project containing the working directory
+pub fn env(db: &DatabaseManager, config: &LaunchConfig) -> HashMap<String, String> {
+    match (&config.project_id, &config.working_dir) {
+        (Some(project_id), _) => super::env::env_for_project(db, project_id),
+        (None, Some(dir)) => super::env::env_for_dir(db, dir),
+        (None, None) => HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::types::ProjectSettings;
+
+    fn project() -> Project {
+        Project {
+            id: "p1".to_string(),
+            name: "api".to_string(),
+            path: "/work/api".to_string(),
+            description: None,
+            color: None,
+            created_at: "2024-01-01".to_string(),
+            last_accessed: None,
+            is_favorite: false,
+            settings: Some(ProjectSettings {
+                default_model: Some("opus".to_string()),
+                port_range: Some((4100, 4105)),
+                default_plugin: Some("claude-code".to_string()),
+                env: [("API_URL".to_string(), "http://localhost".to_string())].into(),
+                secret_env: vec!["API_KEY".to_string()],
+                ..ProjectSettings::default()
+            }),
+            is_archived: false,
+            is_missing: false,
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_fills_from_project_settings() {
+        let project = project();
+        let config = resolve_with(LaunchRequest::default(), Some(&project), |port| port > 4101).unwrap();
+
+        assert_eq!(config.port, 4102);
+        assert_eq!(config.model.as_deref(), Some("opus"));
+        assert_eq!(config.plugin_id.as_deref(), Some("claude-code"));
+        assert_eq!(config.working_dir.as_deref(), Some("/work/api"));
+        assert_eq!(config.env_keys, vec!["API_URL", "API_KEY"]);
+    }
+
+    #[test]
+    fn test_resolve_explicit_arguments_win() {
+        let project = project();
+        let request = LaunchRequest {
+            port: Some(9000),
+            model: Some("sonnet".to_string()),
+            working_dir: Some("/work/api/sub".to_string()),
+            ..LaunchRequest::default()
+        };
+        let config = resolve_with(request, Some(&project), |_| false).unwrap();
+        assert_eq!(config.port, 9000);
+        assert_eq!(config.model.as_deref(), Some("sonnet"));
+        assert_eq!(config.working_dir.as_deref(), Some("/work/api/sub"));
+
+        assert!(resolve_with(LaunchRequest::default(), Some(&project), |_| false).is_err());
+    }
+
+    #[test]
+    fn test_resolve_rejects_missing_project() {
+        let project = Project { is_missing: true, ..project() };
+        let error = resolve_with(LaunchRequest::default(), Some(&project), |_| true).unwrap_err();
+        assert!(error.contains("/work/api"));
+    }
+}
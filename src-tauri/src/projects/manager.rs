@@ -1,9 +1,15 @@
-use crate::database::DatabaseManager;
+use crate::database::project_templates::{self, ProjectTemplate};
+use crate::database::{project_tags, DatabaseManager};
+use crate::projects::search;
 use crate::projects::types::{CreateProjectRequest, Project, ProjectSettings, UpdateProjectRequest};
 use chrono::Utc;
-use rusqlite::{params, OptionalExtension, Result, Row};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use std::path::Path;
 use uuid::Uuid;
 
+/// How often project folders are checked for having moved or disappeared
+pub const PATH_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
 pub struct ProjectsManager<'a> {
     db: &'a DatabaseManager,
 }
@@ -14,6 +20,33 @@ impl<'a> ProjectsManager<'a> {
     }
 
     pub fn create(&self, request: CreateProjectRequest) -> Result<Project> {
+        self.create_with_settings(request, ProjectSettings::default())
+    }
+
+    /// Create a project with a template's color and settings. A color given
+    /// in the request wins over the template's.
+    pub fn create_from_template(&self, template_id: &str, mut request: CreateProjectRequest) -> Result<Project> {
+        let template = self
+            .db
+            .with_connection(|conn| project_templates::get_template(conn, template_id))?
+            .ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+
+        if request.color.is_none() {
+            request.color = template.color;
+        }
+        self.create_with_settings(request, template.settings)
+    }
+
+    /// Save a project's color and settings as a named template
+    pub fn save_as_template(&self, id: &str, name: &str, description: Option<&str>) -> Result<ProjectTemplate> {
+        let project = self.get(id)?.ok_or(rusqlite::Error::QueryReturnedNoRows)?;
+        let settings = project.settings.unwrap_or_default();
+        self.db.with_connection(|conn| {
+            project_templates::save_template(conn, name, description, project.color.as_deref(), &settings)
+        })
+    }
+
+    pub fn create_with_settings(&self, request: CreateProjectRequest, settings: ProjectSettings) -> Result<Project> {
         let id = Uuid::new_v4().to_string();
         let created_at = Utc::now().to_rfc3339();
 
@@ -30,9 +63,10 @@ impl<'a> ProjectsManager<'a> {
                 &request.color,
                 &created_at,
                 false,
-                serde_json::to_string(&ProjectSettings::default()).ok()
+                serde_json::to_string(&settings).ok()
             ],
         )?;
+        drop(conn);
 
         self.get(&id)?.ok_or_else(|| {
             rusqlite::Error::QueryReturnedNoRows
@@ -43,7 +77,7 @@ impl<'a> ProjectsManager<'a> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
              FROM projects WHERE id = ?1"
         )?;
 
@@ -51,14 +85,14 @@ impl<'a> ProjectsManager<'a> {
             self.row_to_project(row)
         }).optional()?;
 
-        Ok(project)
+        self.with_tags(&conn, project.into_iter().collect()).map(|mut p| p.pop())
     }
 
     pub fn get_by_path(&self, path: &str) -> Result<Option<Project>> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
              FROM projects WHERE path = ?1"
         )?;
 
@@ -66,33 +100,35 @@ impl<'a> ProjectsManager<'a> {
             self.row_to_project(row)
         }).optional()?;
 
-        Ok(project)
+        self.with_tags(&conn, project.into_iter().collect()).map(|mut p| p.pop())
     }
 
-    pub fn list(&self) -> Result<Vec<Project>> {
+    /// List projects, leaving out archived ones unless `include_archived`
+    pub fn list(&self, include_archived: bool) -> Result<Vec<Project>> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
              FROM projects
+             WHERE ?1 OR is_archived = 0
              ORDER BY is_favorite DESC, last_accessed DESC NULLS LAST, created_at DESC"
         )?;
 
-        let projects = stmt.query_map([], |row| {
+        let projects = stmt.query_map([include_archived], |row| {
             self.row_to_project(row)
         })?
         .collect::<Result<Vec<_>>>()?;
 
-        Ok(projects)
+        self.with_tags(&conn, projects)
     }
 
     pub fn list_favorites(&self) -> Result<Vec<Project>> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
              FROM projects
-             WHERE is_favorite = 1
+             WHERE is_favorite = 1 AND is_archived = 0
              ORDER BY last_accessed DESC NULLS LAST, created_at DESC"
         )?;
 
@@ -101,26 +137,26 @@ impl<'a> ProjectsManager<'a> {
         })?
         .collect::<Result<Vec<_>>>()?;
 
-        Ok(projects)
+        self.with_tags(&conn, projects)
     }
 
-    pub fn list_recent(&self, limit: usize) -> Result<Vec<Project>> {
+    pub fn list_recent(&self, limit: usize, include_archived: bool) -> Result<Vec<Project>> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
              FROM projects
-             WHERE last_accessed IS NOT NULL
+             WHERE last_accessed IS NOT NULL AND (?2 OR is_archived = 0)
              ORDER BY last_accessed DESC
              LIMIT ?1"
         )?;
 
-        let projects = stmt.query_map([limit], |row| {
+        let projects = stmt.query_map(params![limit, include_archived], |row| {
             self.row_to_project(row)
         })?
         .collect::<Result<Vec<_>>>()?;
 
-        Ok(projects)
+        self.with_tags(&conn, projects)
     }
 
     pub fn update(&self, id: &str, request: UpdateProjectRequest) -> Result<Option<Project>> {
@@ -170,6 +206,7 @@ impl<'a> ProjectsManager<'a> {
 
         let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter().map(|p| p.as_ref()).collect();
         let rows_affected = conn.execute(&query, &params_refs[..])?;
+        drop(conn);
 
         if rows_affected > 0 {
             self.get(id)
@@ -191,6 +228,61 @@ impl<'a> ProjectsManager<'a> {
         Ok(())
     }
 
+    /// Archive or restore a project. Archived projects keep their sessions
+    /// and history but drop out of the default listings.
+    pub fn set_archived(&self, id: &str, archived: bool) -> Result<Option<Project>> {
+        let rows_affected = self.db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE projects SET is_archived = ?1 WHERE id = ?2",
+                params![archived, id],
+            )
+        })?;
+
+        if rows_affected > 0 {
+            self.get(id)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Flag projects whose folder no longer exists, and clear the flag on
+    /// those whose folder is back. Returns the projects that changed.
+    pub fn check_paths(&self) -> Result<Vec<Project>> {
+        let mut changed = Vec::new();
+        for mut project in self.list(true)? {
+            let missing = !Path::new(&project.path).is_dir();
+            if missing != project.is_missing {
+                self.db.with_connection(|conn| {
+                    conn.execute(
+                        "UPDATE projects SET is_missing = ?1 WHERE id = ?2",
+                        params![missing, &project.id],
+                    )
+                })?;
+                project.is_missing = missing;
+                changed.push(project);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Point a project at the folder it moved to, carrying its sessions' and
+    /// servers' working directories along
+    pub fn relocate(&self, id: &str, new_path: &str) -> Result<Option<Project>> {
+        let Some(project) = self.get(id)? else {
+            return Ok(None);
+        };
+
+        self.db.with_connection(|conn| {
+            conn.execute(
+                "UPDATE projects SET path = ?1, is_missing = 0 WHERE id = ?2",
+                params![new_path, id],
+            )?;
+            relocate_records(conn, id, &project.path, new_path)
+        })?;
+
+        self.get(id)
+    }
+
     pub fn delete(&self, id: &str) -> Result<bool> {
         let conn = self.db.connection();
         let conn = conn.lock().unwrap();
@@ -214,6 +306,71 @@ impl<'a> ProjectsManager<'a> {
         Ok(count > 0)
     }
 
+    /// Fuzzy-search unarchived projects by name, path, description and tags.
+    /// SQL narrows the candidates to those containing each term's characters
+    /// in order; `search::rank` then scores and orders them.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<Project>> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let haystack = "(name || ' ' || path || ' ' || COALESCE(description, '') || ' ' ||
+            COALESCE((SELECT group_concat(tag, ' ') FROM project_tags WHERE project_id = projects.id), ''))";
+        let conditions = vec![format!("{} LIKE ? ESCAPE '\\'", haystack); terms.len()];
+        let query_sql = format!(
+            "SELECT id, name, path, description, color, created_at, last_accessed, is_favorite, settings, is_archived, is_missing
+             FROM projects
+             WHERE is_archived = 0 AND {}",
+            conditions.join(" AND ")
+        );
+
+        let patterns: Vec<String> = terms.iter().map(|term| search::like_pattern(term)).collect();
+        let projects = {
+            let conn = self.db.connection();
+            let conn = conn.lock().unwrap();
+            let mut stmt = conn.prepare(&query_sql)?;
+            let projects = stmt.query_map(rusqlite::params_from_iter(&patterns), |row| {
+                self.row_to_project(row)
+            })?
+            .collect::<Result<Vec<_>>>()?;
+            self.with_tags(&conn, projects)?
+        };
+
+        Ok(search::rank(query, projects, limit))
+    }
+
+    /// List projects carrying every one of `tags` (case-insensitive)
+    pub fn list_with_tags(&self, tags: &[String], include_archived: bool) -> Result<Vec<Project>> {
+        let projects = self.list(include_archived)?;
+        Ok(projects
+            .into_iter()
+            .filter(|project| {
+                tags.iter().all(|tag| project.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)))
+            })
+            .collect())
+    }
+
+    /// Tag a project and return its tags
+    pub fn add_tag(&self, id: &str, tag: &str) -> Result<Vec<String>> {
+        self.db.with_connection(|conn| project_tags::add_tag(conn, id, tag))?;
+        Ok(self.get(id)?.map(|p| p.tags).unwrap_or_default())
+    }
+
+    /// Remove a tag from a project and return its remaining tags
+    pub fn remove_tag(&self, id: &str, tag: &str) -> Result<Vec<String>> {
+        self.db.with_connection(|conn| project_tags::remove_tag(conn, id, tag))?;
+        Ok(self.get(id)?.map(|p| p.tags).unwrap_or_default())
+    }
+
+    fn with_tags(&self, conn: &Connection, mut projects: Vec<Project>) -> Result<Vec<Project>> {
+        let mut tags = project_tags::all_project_tags(conn)?;
+        for project in &mut projects {
+            project.tags = tags.remove(&project.id).unwrap_or_default();
+        }
+        Ok(projects)
+    }
+
     fn row_to_project(&self, row: &Row) -> Result<Project> {
         let settings_json: Option<String> = row.get(8)?;
         let settings = settings_json
@@ -229,6 +386,150 @@ impl<'a> ProjectsManager<'a> {
             last_accessed: row.get(6)?,
             is_favorite: row.get(7)?,
             settings,
+            is_archived: row.get(9)?,
+            is_missing: row.get(10)?,
+            tags: Vec::new(),
         })
     }
-}
\ No newline at end of file
+}
+
+/// Rewrite working directories at or below `old_path` to sit under `new_path`
+fn relocate_records(conn: &Connection, project_id: &str, old_path: &str, new_path: &str) -> Result<()> {
+    let old_path = old_path.trim_end_matches('/');
+    let new_path = new_path.trim_end_matches('/');
+    for (table, column) in [("plugin_sessions", "working_directory"), ("servers", "working_dir")] {
+        conn.execute(
+            &format!(
+                "UPDATE {table} SET {column} = ?1 || substr({column}, length(?2) + 1)
+                 WHERE project_id = ?3 AND ({column} = ?2 OR substr({column}, 1, length(?2) + 1) = ?2 || '/')"
+            ),
+            params![new_path, old_path, project_id],
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::types::{DeleteProjectOptions, ProjectDeletionReport};
+
+    fn create(manager: &ProjectsManager, name: &str) -> Project {
+        manager
+            .create(CreateProjectRequest {
+                name: name.to_string(),
+                path: format!("/work/{}", name),
+                description: None,
+                color: None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_archived_projects_are_hidden_from_default_listings() {
+        let db = DatabaseManager::in_memory();
+        let manager = ProjectsManager::new(&db);
+        let web = create(&manager, "web");
+        let api = create(&manager, "api");
+        for project in [&web, &api] {
+            manager.update_last_accessed(&project.id).unwrap();
+        }
+        db.with_connection(|conn| conn.execute("UPDATE projects SET is_favorite = 1", [])).unwrap();
+
+        let archived = manager.set_archived(&api.id, true).unwrap().unwrap();
+        assert!(archived.is_archived);
+
+        let ids = |projects: Vec<Project>| -> Vec<String> { projects.into_iter().map(|p| p.id).collect() };
+        assert_eq!(ids(manager.list(false).unwrap()), vec![web.id.clone()]);
+        assert_eq!(manager.list(true).unwrap().len(), 2);
+        assert_eq!(ids(manager.list_recent(10, false).unwrap()), vec![web.id.clone()]);
+        assert_eq!(manager.list_recent(10, true).unwrap().len(), 2);
+        assert_eq!(ids(manager.list_favorites().unwrap()), vec![web.id.clone()]);
+        // Archived projects can still be looked up directly
+        assert!(manager.get(&api.id).unwrap().unwrap().is_archived);
+
+        assert!(!manager.set_archived(&api.id, false).unwrap().unwrap().is_archived);
+        assert_eq!(manager.list(false).unwrap().len(), 2);
+        assert!(manager.set_archived("missing", true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_delete_removes_history_and_archive_keeps_it() {
+        use crate::database::conversation;
+        use crate::plugins::sessions::PluginSessionManager;
+
+        let db = DatabaseManager::in_memory();
+        let manager = ProjectsManager::new(&db);
+        let sessions = PluginSessionManager::new(&db);
+        let kept = create(&manager, "kept");
+        let gone = create(&manager, "gone");
+        for (session_id, project) in [("s1", &kept), ("s2", &gone)] {
+            db.with_connection(|conn| {
+                conn.execute(
+                    "INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model)
+                     VALUES (?1, ?2, 'claude-agent', 'Session', ?3, 'sonnet')",
+                    params![session_id, project.id, project.path],
+                )?;
+                conversation::add_message(conn, &format!("{}-m", session_id), session_id, "user", "hi", "2024-01-01T00:00:00Z")
+            })
+            .unwrap();
+        }
+
+        // What `delete_project` does with `archive_history`
+        assert!(sessions.archive("s1").unwrap());
+        assert!(manager.set_archived(&kept.id, true).unwrap().is_some());
+        assert!(manager.delete(&gone.id).unwrap());
+
+        let archived = sessions.list_by_project(&kept.id, None).unwrap();
+        assert_eq!(archived[0].status, "archived");
+        assert!(sessions.list_by_project(&gone.id, None).unwrap().is_empty());
+        let count = |session_id: &str| db.with_connection(|conn| conversation::count_messages(conn, session_id)).unwrap();
+        assert_eq!((count("s1"), count("s2")), (1, 0));
+    }
+
+    #[test]
+    fn test_delete_options_default_to_a_full_delete() {
+        let options: DeleteProjectOptions = serde_json::from_str("{}").unwrap();
+        assert!(!options.dry_run && !options.keep_processes && !options.archive_history);
+
+        let options: DeleteProjectOptions = serde_json::from_str(r#"{"dry_run":true,"archive_history":true}"#).unwrap();
+        assert!(options.dry_run && options.archive_history && !options.keep_processes);
+
+        let report = serde_json::to_value(ProjectDeletionReport { dry_run: true, ..Default::default() }).unwrap();
+        assert_eq!(report["dry_run"], true);
+        assert_eq!(report["tmux_sessions"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_relocate_records_rewrites_paths_inside_project() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::migrations::run(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO projects (id, name, path) VALUES ('p1', 'api', '/old/api'), ('p2', 'apix', '/old/apix');
+             INSERT INTO plugin_sessions (id, project_id, plugin_id, title, working_directory, model)
+             VALUES ('s1', 'p1', 'claude', 'Root', '/old/api', 'sonnet'),
+                    ('s2', 'p1', 'claude', 'Nested', '/old/api/src', 'sonnet'),
+                    ('s3', 'p1', 'claude', 'Sibling', '/old/apix', 'sonnet'),
+                    ('s4', 'p2', 'claude', 'Other', '/old/api/src', 'sonnet');
+             INSERT INTO servers (id, project_id, host, port, working_dir)
+             VALUES ('srv', 'p1', 'localhost', 4000, '/old/api/');",
+        )
+        .unwrap();
+
+        relocate_records(&conn, "p1", "/old/api/", "/new/api").unwrap();
+
+        let dir = |id: &str| -> String {
+            conn.query_row("SELECT working_directory FROM plugin_sessions WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(dir("s1"), "/new/api");
+        assert_eq!(dir("s2"), "/new/api/src");
+        assert_eq!(dir("s3"), "/old/apix");
+        assert_eq!(dir("s4"), "/old/api/src");
+
+        let server_dir: String = conn
+            .query_row("SELECT working_dir FROM servers WHERE id = 'srv'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(server_dir, "/new/api/");
+    }
+}
@@ -1,10 +1,22 @@
+pub mod bundle;
+pub mod discovery;
+pub mod env;
+pub mod files;
+pub mod launch;
+pub mod search;
+pub mod export;
 pub mod manager;
 pub mod types;
+pub mod workspaces;
 
+use crate::database::project_commands::{self, CommandTarget, SavedCommand};
+use crate::database::project_templates::{self, ProjectTemplate};
+use crate::database::workspaces::Workspace;
 use crate::database::DatabaseManager;
 use manager::ProjectsManager;
 use tauri::State;
-use types::{CreateProjectRequest, Project, UpdateProjectRequest};
+use std::collections::BTreeMap;
+use types::{CreateProjectRequest, Project, ProjectSettings, UpdateProjectRequest};
 
 #[tauri::command]
 pub async fn create_project(
@@ -15,6 +27,58 @@ pub async fn create_project(
     manager.create(request).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn create_project_from_template(
+    db: State<'_, DatabaseManager>,
+    template_id: String,
+    request: CreateProjectRequest,
+) -> Result<Project, String> {
+    let manager = ProjectsManager::new(&db);
+    manager
+        .create_from_template(&template_id, request)
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Template not found: {}", template_id),
+            e => e.to_string(),
+        })
+}
+
+#[tauri::command]
+pub async fn save_project_template(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    name: String,
+    description: Option<String>,
+) -> Result<ProjectTemplate, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    let manager = ProjectsManager::new(&db);
+    manager
+        .save_as_template(&project_id, name, description.as_deref())
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Project not found: {}", project_id),
+            e => e.to_string(),
+        })
+}
+
+#[tauri::command]
+pub async fn list_project_templates(
+    db: State<'_, DatabaseManager>,
+) -> Result<Vec<ProjectTemplate>, String> {
+    db.with_connection(project_templates::list_templates)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_project_template(
+    db: State<'_, DatabaseManager>,
+    id: String,
+) -> Result<bool, String> {
+    db.with_connection(|conn| project_templates::delete_template(conn, &id))
+        .map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn get_project(
     db: State<'_, DatabaseManager>,
@@ -33,12 +97,32 @@ pub async fn get_project_by_path(
     manager.get_by_path(&path).map_err(|e| e.to_string())
 }
 
+/// List all projects, or only those carrying every tag in `tags`. Archived
+/// projects are left out unless `include_archived` is set.
 #[tauri::command]
 pub async fn list_projects(
     db: State<'_, DatabaseManager>,
+    tags: Option<Vec<String>>,
+    include_archived: Option<bool>,
 ) -> Result<Vec<Project>, String> {
     let manager = ProjectsManager::new(&db);
-    manager.list().map_err(|e| e.to_string())
+    let include_archived = include_archived.unwrap_or(false);
+    match tags.filter(|tags| !tags.is_empty()) {
+        Some(tags) => manager.list_with_tags(&tags, include_archived),
+        None => manager.list(include_archived),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Fuzzy-search projects for the quick switcher
+#[tauri::command]
+pub async fn search_projects(
+    db: State<'_, DatabaseManager>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<Project>, String> {
+    let manager = ProjectsManager::new(&db);
+    manager.search(&query, limit.unwrap_or(20)).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -53,9 +137,12 @@ pub async fn list_favorite_projects(
 pub async fn list_recent_projects(
     db: State<'_, DatabaseManager>,
     limit: usize,
+    include_archived: Option<bool>,
 ) -> Result<Vec<Project>, String> {
     let manager = ProjectsManager::new(&db);
-    manager.list_recent(limit).map_err(|e| e.to_string())
+    manager
+        .list_recent(limit, include_archived.unwrap_or(false))
+        .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -77,13 +164,319 @@ pub async fn update_project_last_accessed(
     manager.update_last_accessed(&id).map_err(|e| e.to_string())
 }
 
+/// Apply `change` to a project's settings and save them
+fn update_settings(
+    db: &DatabaseManager,
+    id: &str,
+    change: impl FnOnce(&mut ProjectSettings),
+) -> Result<Option<Project>, String> {
+    let manager = ProjectsManager::new(db);
+    let Some(project) = manager.get(id).map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+
+    let mut settings = project.settings.unwrap_or_default();
+    change(&mut settings);
+    manager
+        .update(
+            id,
+            UpdateProjectRequest {
+                name: None,
+                description: None,
+                color: None,
+                is_favorite: None,
+                settings: Some(settings),
+            },
+        )
+        .map_err(|e| e.to_string())
+}
+
+/// Replace a project's plain environment variables
 #[tauri::command]
-pub async fn delete_project(
+pub async fn set_project_env(
     db: State<'_, DatabaseManager>,
     id: String,
+    env: BTreeMap<String, String>,
+) -> Result<Option<Project>, String> {
+    for name in env.keys() {
+        env::validate_name(name)?;
+    }
+    update_settings(&db, &id, |settings| settings.env = env)
+}
+
+/// Store a secret variable in the keychain; only its name is saved with
+/// the project
+#[tauri::command]
+pub async fn set_project_secret(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    name: String,
+    value: String,
+) -> Result<Option<Project>, String> {
+    env::validate_name(&name)?;
+    env::set_secret(&id, &name, &value)?;
+    update_settings(&db, &id, |settings| {
+        if !settings.secret_env.contains(&name) {
+            settings.secret_env.push(name);
+        }
+    })
+}
+
+#[tauri::command]
+pub async fn delete_project_secret(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    name: String,
+) -> Result<Option<Project>, String> {
+    env::delete_secret(&id, &name)?;
+    update_settings(&db, &id, |settings| settings.secret_env.retain(|n| *n != name))
+}
+
+/// Re-check every project's folder and return those that are missing
+#[tauri::command]
+pub async fn check_project_paths(
+    db: State<'_, DatabaseManager>,
+) -> Result<Vec<Project>, String> {
+    let manager = ProjectsManager::new(&db);
+    manager.check_paths().map_err(|e| e.to_string())?;
+    let projects = manager.list(true).map_err(|e| e.to_string())?;
+    Ok(projects.into_iter().filter(|p| p.is_missing).collect())
+}
+
+/// The project's files that git doesn't ignore, optionally only those
+/// matching `glob`
+#[tauri::command]
+pub async fn list_project_files(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    glob: Option<String>,
+) -> Result<files::ProjectFiles, String> {
+    let project = ProjectsManager::new(&db)
+        .get(&project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    tokio::task::spawn_blocking(move || files::list_files(std::path::Path::new(&project.path), glob.as_deref()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// `.env` files in the project folder, for picking one for a dev server
+#[tauri::command]
+pub async fn list_project_env_files(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+) -> Result<Vec<String>, String> {
+    let project = ProjectsManager::new(&db)
+        .get(&project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+    Ok(env::env_files(std::path::Path::new(&project.path)))
+}
+
+/// `list_project_files` as an indented outline for agent prompts
+#[tauri::command]
+pub async fn get_project_file_outline(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    glob: Option<String>,
+) -> Result<String, String> {
+    let listing = list_project_files(db, project_id, glob).await?;
+    let mut outline = files::outline(&listing.files);
+    if listing.truncated {
+        outline.push_str(&format!("... (only the first {} files are listed)\n", files::MAX_FILES));
+    }
+    Ok(outline)
+}
+
+/// Point a project that moved at its new folder
+#[tauri::command]
+pub async fn relocate_project(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    new_path: String,
+) -> Result<Option<Project>, String> {
+    if !std::path::Path::new(&new_path).is_dir() {
+        return Err(format!("Project folder not found: {}", new_path));
+    }
+    let manager = ProjectsManager::new(&db);
+    if let Some(other) = manager.get_by_path(&new_path).map_err(|e| e.to_string())? {
+        if other.id != id {
+            return Err(format!("A project already exists at {}", new_path));
+        }
+    }
+    manager.relocate(&id, &new_path).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn archive_project(
+    db: State<'_, DatabaseManager>,
+    id: String,
+) -> Result<Option<Project>, String> {
+    let manager = ProjectsManager::new(&db);
+    manager.set_archived(&id, true).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unarchive_project(
+    db: State<'_, DatabaseManager>,
+    id: String,
+) -> Result<Option<Project>, String> {
+    let manager = ProjectsManager::new(&db);
+    manager.set_archived(&id, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_project_tag(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    let manager = ProjectsManager::new(&db);
+    manager.add_tag(&id, tag).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_project_tag(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    tag: String,
+) -> Result<Vec<String>, String> {
+    let manager = ProjectsManager::new(&db);
+    manager.remove_tag(&id, tag.trim()).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_project_tags(
+    db: State<'_, DatabaseManager>,
+) -> Result<Vec<crate::database::project_tags::TagCount>, String> {
+    db.with_connection(crate::database::project_tags::list_tags)
+        .map_err(|e| e.to_string())
+}
+
+/// Save a named command for the project, replacing one of the same name
+#[tauri::command]
+pub async fn save_project_command(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    name: String,
+    command: String,
+    target: CommandTarget,
+) -> Result<SavedCommand, String> {
+    let name = name.trim();
+    if name.is_empty() || command.trim().is_empty() {
+        return Err("Command name and command cannot be empty".to_string());
+    }
+    if ProjectsManager::new(&db).get(&project_id).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("Project not found: {}", project_id));
+    }
+    db.with_connection(|conn| project_commands::save_command(conn, &project_id, name, &command, target))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_project_commands(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+) -> Result<Vec<SavedCommand>, String> {
+    db.with_connection(|conn| project_commands::list_commands(conn, &project_id))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_project_command(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    name: String,
 ) -> Result<bool, String> {
+    db.with_connection(|conn| project_commands::delete_command(conn, &project_id, &name))
+        .map_err(|e| e.to_string())
+}
+
+/// Look for project roots under `root_dirs`, or the directories saved in
+/// the `project_scan_roots` setting, that aren't registered yet
+#[tauri::command]
+pub async fn scan_for_projects(
+    db: State<'_, DatabaseManager>,
+    root_dirs: Option<Vec<String>>,
+) -> Result<Vec<discovery::ProjectCandidate>, String> {
+    let roots = match root_dirs.filter(|dirs| !dirs.is_empty()) {
+        Some(dirs) => dirs,
+        None => db
+            .with_connection(|conn| crate::database::settings::get_json::<Vec<String>>(conn, discovery::SCAN_ROOTS_SETTING))
+            .map_err(|e| e.to_string())?
+            .unwrap_or_default(),
+    };
+    if roots.is_empty() {
+        return Err("No directories to scan".to_string());
+    }
+
+    let known = ProjectsManager::new(&db)
+        .list(true)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|project| project.path)
+        .collect();
+
+    tokio::task::spawn_blocking(move || discovery::scan(&roots, &known))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Register scanned candidates as projects, skipping any already added
+#[tauri::command]
+pub async fn register_projects(
+    db: State<'_, DatabaseManager>,
+    candidates: Vec<discovery::ProjectCandidate>,
+) -> Result<Vec<Project>, String> {
     let manager = ProjectsManager::new(&db);
-    manager.delete(&id).map_err(|e| e.to_string())
+    let mut created = Vec::new();
+    for candidate in candidates {
+        if manager.exists(&candidate.path).map_err(|e| e.to_string())? {
+            continue;
+        }
+        let project = manager
+            .create(CreateProjectRequest {
+                name: candidate.name,
+                path: candidate.path,
+                description: None,
+                color: None,
+            })
+            .map_err(|e| e.to_string())?;
+        created.push(project);
+    }
+    Ok(created)
+}
+
+/// Preview the port, model, working directory and environment a spawn
+/// with these arguments would use
+#[tauri::command]
+pub async fn resolve_launch_config(
+    db: State<'_, DatabaseManager>,
+    request: launch::LaunchRequest,
+) -> Result<launch::LaunchConfig, String> {
+    launch::resolve_for(&db, request)
+}
+
+/// Recent sessions, tasks and approvals for a project, newest first
+#[tauri::command]
+pub async fn get_project_activity(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<crate::database::feed::ActivityEvent>, String> {
+    let project = ProjectsManager::new(&db)
+        .get(&project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Project not found: {}", project_id))?;
+
+    db.with_connection(|conn| {
+        crate::database::feed::project_activity(conn, &project.id, &project.name, limit.unwrap_or(50))
+    })
+    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -93,4 +486,159 @@ pub async fn project_exists(
 ) -> Result<bool, String> {
     let manager = ProjectsManager::new(&db);
     manager.exists(&path).map_err(|e| e.to_string())
-}
\ No newline at end of file
+}
+#[tauri::command]
+pub async fn create_workspace(
+    db: State<'_, DatabaseManager>,
+    name: String,
+    description: Option<String>,
+) -> Result<Workspace, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Workspace name cannot be empty".to_string());
+    }
+    db.with_connection(|conn| crate::database::workspaces::create_workspace(conn, name, description.as_deref()))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_workspace(
+    db: State<'_, DatabaseManager>,
+    id: String,
+) -> Result<Option<Workspace>, String> {
+    db.with_connection(|conn| crate::database::workspaces::get_workspace(conn, &id))
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_workspaces(
+    db: State<'_, DatabaseManager>,
+) -> Result<Vec<Workspace>, String> {
+    db.with_connection(crate::database::workspaces::list_workspaces)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_workspace(
+    db: State<'_, DatabaseManager>,
+    id: String,
+) -> Result<bool, String> {
+    db.with_connection(|conn| crate::database::workspaces::delete_workspace(conn, &id))
+        .map_err(|e| e.to_string())
+}
+
+/// Add a project to a workspace and return the updated workspace
+#[tauri::command]
+pub async fn add_project_to_workspace(
+    db: State<'_, DatabaseManager>,
+    workspace_id: String,
+    project_id: String,
+) -> Result<Workspace, String> {
+    if ProjectsManager::new(&db).get(&project_id).map_err(|e| e.to_string())?.is_none() {
+        return Err(format!("Project not found: {}", project_id));
+    }
+
+    db.with_connection(|conn| {
+        if crate::database::workspaces::get_workspace(conn, &workspace_id)?.is_none() {
+            return Ok(None);
+        }
+        crate::database::workspaces::add_project(conn, &workspace_id, &project_id)?;
+        crate::database::workspaces::get_workspace(conn, &workspace_id)
+    })
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Workspace not found: {}", workspace_id))
+}
+
+/// Remove a project from a workspace and return the updated workspace
+#[tauri::command]
+pub async fn remove_project_from_workspace(
+    db: State<'_, DatabaseManager>,
+    workspace_id: String,
+    project_id: String,
+) -> Result<Workspace, String> {
+    db.with_connection(|conn| {
+        crate::database::workspaces::remove_project(conn, &workspace_id, &project_id)?;
+        crate::database::workspaces::get_workspace(conn, &workspace_id)
+    })
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| format!("Workspace not found: {}", workspace_id))
+}
+
+/// Write a project's configuration to a portable bundle and return the file
+/// path. Without `path` it goes to the profile's `exports` folder.
+#[tauri::command]
+pub async fn export_project(
+    db: State<'_, DatabaseManager>,
+    id: String,
+    path: Option<String>,
+) -> Result<String, String> {
+    let bundle = bundle::build(&db, &id)?;
+    let content = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let dir = db.data_dir().join("exports");
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            dir.join(export::file_name(&bundle.project, bundle::BUNDLE_EXTENSION))
+        }
+    };
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+/// Read a bundle without importing it, so the original path can be shown
+/// and remapped before `import_project`
+#[tauri::command]
+pub async fn read_project_bundle(path: String) -> Result<bundle::ProjectBundle, String> {
+    bundle::read(std::path::Path::new(&path))
+}
+
+/// Recreate a project from a bundle. `project_path` remaps where the code
+/// lives on this machine.
+#[tauri::command]
+pub async fn import_project(
+    db: State<'_, DatabaseManager>,
+    path: String,
+    project_path: Option<String>,
+) -> Result<Project, String> {
+    let bundle = bundle::read(std::path::Path::new(&path))?;
+    bundle::import(&db, bundle, project_path)
+}
+
+/// Write a project's data to a JSON archive or Markdown report and return
+/// the file path. Without `path` it goes to the profile's `exports` folder.
+#[tauri::command]
+pub async fn export_project_data(
+    db: State<'_, DatabaseManager>,
+    project_id: String,
+    format: String,
+    path: Option<String>,
+) -> Result<String, String> {
+    let data = export::gather(&db, &project_id)?;
+
+    let (content, extension) = match format.as_str() {
+        "json" => (serde_json::to_string_pretty(&data).map_err(|e| e.to_string())?, "json"),
+        "markdown" | "md" => (export::to_markdown(&data), "md"),
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+
+    let path = match path {
+        Some(path) => std::path::PathBuf::from(path),
+        None => {
+            let dir = db.data_dir().join("exports");
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            dir.join(export::file_name(&data.project, extension))
+        }
+    };
+
+    std::fs::write(&path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    Ok(path.to_string_lossy().to_string())
+}
@@ -0,0 +1,139 @@
+use std::cmp::Reverse;
+
+use super::types::Project;
+
+/// LIKE pattern matching any text containing `term`'s characters in order,
+/// e.g. `nsq` becomes `%n%s%q%`
+pub fn like_pattern(term: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in term.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+        pattern.push('%');
+    }
+    pattern
+}
+
+/// Score how well `term` matches `text` as a case-insensitive subsequence.
+/// Consecutive characters and matches at the start of words score higher;
+/// `None` means no match.
+pub fn fuzzy_score(term: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for c in term.to_lowercase().chars() {
+        let found = position + text[position..].iter().position(|t| *t == c)?;
+
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        if let Some(p) = previous {
+            score -= (found - p - 1).min(3) as i64;
+        }
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
+
+/// Best score of `term` across a project's fields, weighting the name
+/// over tags, and both over the path and description
+fn project_score(term: &str, project: &Project) -> Option<i64> {
+    let mut fields: Vec<(&str, i64)> = vec![(&project.name, 3), (&project.path, 1)];
+    fields.extend(project.tags.iter().map(|tag| (tag.as_str(), 2)));
+    if let Some(description) = &project.description {
+        fields.push((description, 1));
+    }
+
+    fields
+        .into_iter()
+        .filter_map(|(text, weight)| fuzzy_score(term, text).map(|score| score * weight))
+        .max()
+}
+
+/// Keep the projects matching every term of `query`, best match first and
+/// most recently used first among equals
+pub fn rank(query: &str, projects: Vec<Project>, limit: usize) -> Vec<Project> {
+    let terms: Vec<&str> = query.split_whitespace().collect();
+
+    let mut scored: Vec<(i64, Project)> = projects
+        .into_iter()
+        .filter_map(|project| {
+            let score = terms
+                .iter()
+                .map(|term| project_score(term, &project))
+                .sum::<Option<i64>>()?;
+            Some((score, project))
+        })
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| Reverse(&a.last_accessed).cmp(&Reverse(&b.last_accessed)))
+    });
+
+    scored.into_iter().take(limit).map(|(_, project)| project).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(name: &str, path: &str, tags: &[&str], last_accessed: Option<&str>) -> Project {
+        Project {
+            id: name.to_string(),
+            name: name.to_string(),
+            path: path.to_string(),
+            description: None,
+            color: None,
+            created_at: "2024-01-01".to_string(),
+            last_accessed: last_accessed.map(str::to_string),
+            is_favorite: false,
+            settings: None,
+            is_archived: false,
+            is_missing: false,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("ns"), "%n%s%");
+        assert_eq!(like_pattern("a_%"), "%a%\\_%\\%%");
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_word_starts() {
+        assert!(fuzzy_score("xyz", "ninjasquad").is_none());
+        let start = fuzzy_score("sq", "ninja-squad").unwrap();
+        let scattered = fuzzy_score("sq", "ninjas-quad").unwrap();
+        assert!(start > scattered);
+    }
+
+    #[test]
+    fn test_rank_orders_by_score_then_recency() {
+        let projects = vec![
+            project("billing", "/work/billing", &[], Some("2024-01-01")),
+            project("web", "/work/web", &["billing"], Some("2024-03-01")),
+            project("api", "/work/api", &[], Some("2024-02-01")),
+            project("bill", "/work/bill", &[], None),
+        ];
+
+        let names: Vec<_> = rank("bill", projects.clone(), 10).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["billing", "bill", "web"]);
+
+        let names: Vec<_> = rank("work api", projects, 10).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["api"]);
+    }
+}
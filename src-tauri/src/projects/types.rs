@@ -1,4 +1,8 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::database::project_commands::CommandTarget;
+use crate::devserver::RestartPolicy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Project {
@@ -11,6 +15,13 @@ pub struct Project {
     pub last_accessed: Option<String>,
     pub is_favorite: bool,
     pub settings: Option<ProjectSettings>,
+    #[serde(default)]
+    pub is_archived: bool,
+    /// The project folder no longer exists
+    #[serde(default)]
+    pub is_missing: bool,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,6 +29,61 @@ pub struct ProjectSettings {
     pub default_model: Option<String>,
     pub port_range: Option<(u16, u16)>,
     pub auto_start_server: bool,
+    /// Plugin new sessions open with, e.g. "claude" or "opencode"
+    #[serde(default)]
+    pub default_plugin: Option<String>,
+    /// OpenCode server flavour to spawn, e.g. "sdk" or "tui"
+    #[serde(default)]
+    pub server_template: Option<String>,
+    #[serde(default)]
+    pub dev_server_command: Option<String>,
+    /// Named dev servers the project runs, e.g. "web" and "api"
+    #[serde(default)]
+    pub dev_servers: Vec<DevServerProfile>,
+    /// Variables set for every process started for the project
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Names of variables whose values are kept in the OS keychain
+    #[serde(default)]
+    pub secret_env: Vec<String>,
+    /// `owner/repo` pull requests are opened against; taken from the
+    /// origin remote when unset
+    #[serde(default)]
+    pub github_repo: Option<String>,
+    #[serde(default)]
+    pub github_base_branch: Option<String>,
+    /// Self-managed GitLab instance, e.g. `https://gitlab.example.com`;
+    /// gitlab.com when unset
+    #[serde(default)]
+    pub gitlab_url: Option<String>,
+    /// `group/project` merge requests are opened against; taken from the
+    /// origin remote when unset
+    #[serde(default)]
+    pub gitlab_project: Option<String>,
+    #[serde(default)]
+    pub gitlab_target_branch: Option<String>,
+    /// Merge request description with `{{prompt}}`, `{{diff_summary}}` and
+    /// other placeholders; see `gitlab::description_from_template`
+    #[serde(default)]
+    pub gitlab_mr_template: Option<String>,
+    /// Tracker agents take issues from: `linear`, `github`, `jira` or
+    /// `gitlab`
+    #[serde(default)]
+    pub issue_tracker: Option<String>,
+    /// Limits Linear issues to one team; the API key is the project's
+    /// LINEAR_API_KEY secret
+    #[serde(default)]
+    pub linear_team_id: Option<String>,
+    /// Jira Cloud site, e.g. `acme` or `acme.atlassian.net`; the API token
+    /// is the project's JIRA_API_TOKEN secret
+    #[serde(default)]
+    pub jira_site: Option<String>,
+    /// Account the API token belongs to
+    #[serde(default)]
+    pub jira_email: Option<String>,
+    /// Limits assigned issues to this Jira project, e.g. `SHOP`
+    #[serde(default)]
+    pub jira_project_key: Option<String>,
 }
 
 impl Default for ProjectSettings {
@@ -26,10 +92,58 @@ impl Default for ProjectSettings {
             default_model: None,
             port_range: Some((4000, 5000)),
             auto_start_server: false,
+            default_plugin: None,
+            server_template: None,
+            dev_server_command: None,
+            dev_servers: Vec::new(),
+            env: BTreeMap::new(),
+            secret_env: Vec::new(),
+            github_repo: None,
+            github_base_branch: None,
+            gitlab_url: None,
+            gitlab_project: None,
+            gitlab_target_branch: None,
+            gitlab_mr_template: None,
+            issue_tracker: None,
+            linear_team_id: None,
+            jira_site: None,
+            jira_email: None,
+            jira_project_key: None,
         }
     }
 }
 
+/// A dev server a project can start by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DevServerProfile {
+    pub name: String,
+    pub command: String,
+    /// Relative to the project folder; the folder itself when unset
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// `.env` file loaded on top of the project's variables, relative to
+    /// the project folder
+    #[serde(default)]
+    pub env_file: Option<String>,
+    /// Set on top of the project's variables and the `.env` file
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+    /// Names of project secrets to set, read from the keychain when the
+    /// server starts
+    #[serde(default)]
+    pub secret_env: Vec<String>,
+    /// Regex an output line matches once the server is ready; the first
+    /// URL it prints when unset
+    #[serde(default)]
+    pub ready_pattern: Option<String>,
+    /// Polled after the server starts to track whether it's serving,
+    /// e.g. `http://localhost:3000/health`
+    #[serde(default)]
+    pub ready_url: Option<String>,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateProjectRequest {
     pub name: String,
@@ -45,4 +159,49 @@ pub struct UpdateProjectRequest {
     pub color: Option<String>,
     pub is_favorite: Option<bool>,
     pub settings: Option<ProjectSettings>,
-}
\ No newline at end of file
+}
+
+/// How far `delete_project` reaches beyond the project row
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeleteProjectOptions {
+    /// Only report what would be affected
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Leave tmux sessions, terminals and servers running
+    #[serde(default)]
+    pub keep_processes: bool,
+    /// Archive the project and its sessions instead of deleting them. Plugin
+    /// sessions and their conversations are removed with the project row.
+    #[serde(default)]
+    pub archive_history: bool,
+}
+
+/// What deleting a project closed, or would close on a dry run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectDeletionReport {
+    pub dry_run: bool,
+    pub wezterm_windows: Vec<String>,
+    pub wezterm_mirrors: Vec<String>,
+    pub terminals: Vec<String>,
+    pub tmux_sessions: Vec<String>,
+    pub servers: Vec<String>,
+    pub dev_servers: Vec<String>,
+    /// Plugin sessions archived, or deleted along with the project
+    pub plugin_sessions: Vec<String>,
+    pub archived: bool,
+    pub deleted: bool,
+}
+
+/// Result of running a saved command; which fields are set depends on the
+/// target
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedCommandRun {
+    pub target: CommandTarget,
+    /// Combined stdout and stderr of a shell command
+    pub output: Option<String>,
+    pub exit_code: Option<i32>,
+    pub tmux_session_id: Option<String>,
+    pub dev_server_id: Option<String>,
+    /// Process id of a dev server
+    pub pid: Option<u32>,
+}
@@ -0,0 +1,95 @@
+use crate::claude::ClaudeProcessManager;
+use crate::database::workspaces::{self, Workspace};
+use crate::database::DatabaseManager;
+use serde::{Deserialize, Serialize};
+
+use super::manager::ProjectsManager;
+use super::types::Project;
+
+/// Outcome of a workspace-wide action for one member project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberResult {
+    pub project_id: String,
+    pub project_name: String,
+    pub session_id: Option<String>,
+    pub response: Option<String>,
+    pub error: Option<String>,
+}
+
+impl MemberResult {
+    fn new(project: &Project) -> Self {
+        Self {
+            project_id: project.id.clone(),
+            project_name: project.name.clone(),
+            session_id: None,
+            response: None,
+            error: None,
+        }
+    }
+}
+
+/// Load a workspace and its member projects
+pub fn members(db: &DatabaseManager, workspace_id: &str) -> Result<(Workspace, Vec<Project>), String> {
+    let workspace = db
+        .with_connection(|conn| workspaces::get_workspace(conn, workspace_id))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Workspace not found: {}", workspace_id))?;
+
+    let manager = ProjectsManager::new(db);
+    let mut projects = Vec::new();
+    for id in &workspace.project_ids {
+        if let Some(project) = manager.get(id).map_err(|e| e.to_string())? {
+            projects.push(project);
+        }
+    }
+    Ok((workspace, projects))
+}
+
+/// Start (or reuse) a Claude session in every member project. `model`
+/// overrides each project's default model.
+pub async fn spawn_agents(
+    claude: &ClaudeProcessManager,
+    projects: &[Project],
+    model: Option<String>,
+) -> Vec<MemberResult> {
+    let mut results = Vec::new();
+    for project in projects {
+        let model = model
+            .clone()
+            .or_else(|| project.settings.as_ref().and_then(|s| s.default_model.clone()));
+        let mut result = MemberResult::new(project);
+        if project.is_missing {
+            result.error = Some(format!("Project folder is missing: {}", project.path));
+            results.push(result);
+            continue;
+        }
+        match claude.create_session(project.id.clone(), Some(project.path.clone()), model).await {
+            Ok(session_id) => result.session_id = Some(session_id),
+            Err(e) => result.error = Some(e),
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Send the same prompt to every member's agent at once, starting agents
+/// where needed, and collect each reply
+pub async fn distribute_task(
+    claude: &ClaudeProcessManager,
+    projects: &[Project],
+    prompt: &str,
+) -> Vec<MemberResult> {
+    let sessions = spawn_agents(claude, projects, None).await;
+
+    let sends = sessions.into_iter().map(|mut result| async move {
+        if let Some(session_id) = &result.session_id {
+            match claude.send_message(session_id, prompt.to_string()).await {
+                Ok(response) => result.response = Some(response),
+                Err(e) => result.error = Some(e),
+            }
+        }
+        result
+    });
+
+    futures::future::join_all(sends).await
+}
@@ -0,0 +1,150 @@
+use regex::RegexBuilder;
+
+use super::types::TerminalSearchMatch;
+
+/// Output kept per terminal so a remounted view can replay it
+pub const MAX_SCROLLBACK_BYTES: usize = 512 * 1024;
+
+/// Matches returned by one search at most
+pub const MAX_SEARCH_MATCHES: usize = 1000;
+
+/// Bounded byte buffer of a terminal's output. Bytes are kept raw and only
+/// decoded on read, so multi-byte characters split across reads survive.
+#[derive(Debug)]
+pub struct ScrollbackBuffer {
+    bytes: Vec<u8>,
+    capacity: usize,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self { bytes: Vec::new(), capacity }
+    }
+
+    pub fn push(&mut self, data: &[u8]) {
+        self.bytes.extend_from_slice(data);
+        if self.bytes.len() > self.capacity {
+            // Drop whole lines where possible so replay starts cleanly
+            let excess = self.bytes.len() - self.capacity;
+            let cut = self.bytes[excess..].iter()
+                .position(|b| *b == b'\n')
+                .map_or(excess, |i| excess + i + 1);
+            self.bytes.drain(..cut);
+        }
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).to_string()
+    }
+
+    /// The last `lines` lines of output, or all of it when `None`
+    pub fn tail(&self, lines: Option<usize>) -> String {
+        let text = self.text();
+        let Some(lines) = lines else {
+            return text;
+        };
+        if lines == 0 {
+            return String::new();
+        }
+
+        let trimmed = text.strip_suffix('\n').unwrap_or(&text);
+        match trimmed.rmatch_indices('\n').nth(lines - 1) {
+            Some((i, _)) => text[i + 1..].to_string(),
+            None => text,
+        }
+    }
+
+    /// Find `query`, a regular expression when `regex` is set, in the
+    /// escape-stripped scrollback
+    pub fn search(&self, query: &str, regex: bool, case_sensitive: bool) -> Result<Vec<TerminalSearchMatch>, String> {
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let pattern = if regex { query.to_string() } else { regex::escape(query) };
+        let matcher = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|e| format!("Invalid search pattern: {}", e))?;
+
+        let text = crate::tmux::capture::strip_ansi(&self.text());
+        let mut matches = Vec::new();
+
+        for (line_index, line) in text.lines().enumerate() {
+            for found in matcher.find_iter(line).filter(|m| !m.is_empty()) {
+                matches.push(TerminalSearchMatch {
+                    line: line_index,
+                    column: line[..found.start()].chars().count(),
+                    length: found.as_str().chars().count(),
+                    text: found.as_str().to_string(),
+                });
+                if matches.len() == MAX_SEARCH_MATCHES {
+                    return Ok(matches);
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_returns_last_lines() {
+        let mut buffer = ScrollbackBuffer::new(1024);
+        buffer.push(b"one\r\ntwo\r\nthr");
+        buffer.push(b"ee\r\n");
+
+        assert_eq!(buffer.tail(Some(2)), "two\r\nthree\r\n");
+        assert_eq!(buffer.tail(Some(10)), "one\r\ntwo\r\nthree\r\n");
+        assert_eq!(buffer.tail(None), "one\r\ntwo\r\nthree\r\n");
+        assert_eq!(buffer.tail(Some(0)), "");
+    }
+
+    #[test]
+    fn test_push_drops_oldest_whole_lines() {
+        let mut buffer = ScrollbackBuffer::new(8);
+        buffer.push(b"aaaa\nbbbb\ncc");
+        assert_eq!(buffer.text(), "bbbb\ncc");
+        assert!(buffer.len() <= 8);
+    }
+
+    #[test]
+    fn test_search_plain_and_regex() {
+        let mut buffer = ScrollbackBuffer::new(1024);
+        buffer.push(b"\x1b[31merror\x1b[0m: a.b failed\r\nok\r\n\xc3\xa9 Error again\r\n");
+
+        let plain = buffer.search("ERROR", false, false).unwrap();
+        assert_eq!(plain.len(), 2);
+        assert_eq!((plain[0].line, plain[0].column, plain[0].length), (0, 0, 5));
+        assert_eq!((plain[1].line, plain[1].column), (2, 2));
+
+        assert!(buffer.search("ERROR", false, true).unwrap().is_empty());
+        assert_eq!(buffer.search("a.b", false, false).unwrap()[0].text, "a.b");
+
+        let regex = buffer.search(r"\bo\w", true, true).unwrap();
+        assert_eq!(regex.iter().map(|m| m.line).collect::<Vec<_>>(), vec![1]);
+
+        assert!(buffer.search("(", true, false).is_err());
+    }
+
+    #[test]
+    fn test_split_utf8_is_decoded_whole() {
+        let mut buffer = ScrollbackBuffer::new(64);
+        let bytes = "é".as_bytes();
+        buffer.push(&bytes[..1]);
+        buffer.push(&bytes[1..]);
+        assert_eq!(buffer.text(), "é");
+    }
+}
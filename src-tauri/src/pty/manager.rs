@@ -1,19 +1,64 @@
+use super::buffer::{ScrollbackBuffer, MAX_SCROLLBACK_BYTES};
+use super::output;
 use super::types::*;
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, ExitStatus, MasterPty, PtySize};
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use uuid::Uuid;
 use std::io::{Read, Write};
 use tauri::{AppHandle, Emitter};
 
 pub struct PtySession {
     pub id: String,
-    pub reader_thread: Option<std::thread::JoinHandle<()>>,
+    /// Blocking read loop on tokio's blocking pool
+    pub reader_task: Option<tokio::task::JoinHandle<()>>,
+    /// Kept so the terminal can be resized; dropping it closes the PTY
+    pub master: Box<dyn MasterPty + Send>,
+    pub size: TerminalSize,
+    /// Kills the child; the child itself is owned by the thread waiting on it
+    pub killer: Box<dyn ChildKiller + Send + Sync>,
+    pub created_at: DateTime<Utc>,
+    /// Unix millis of the last output or input, shared with the reader
+    pub last_activity: Arc<AtomicI64>,
+    pub server_id: Option<String>,
+    pub session_id: Option<String>,
+    pub project_id: Option<String>,
+    pub cwd: Option<String>,
+    pub command: Option<String>,
+    /// Output rate limit, read by the output forwarder on every flush
+    pub throttle: Arc<Mutex<ThrottleConfig>>,
 }
 
+impl PtySession {
+    pub fn info(&self) -> TerminalInfo {
+        let last_activity = DateTime::from_timestamp_millis(self.last_activity.load(Ordering::Relaxed))
+            .unwrap_or(self.created_at);
+
+        TerminalInfo {
+            id: self.id.clone(),
+            rows: self.size.rows,
+            cols: self.size.cols,
+            created_at: self.created_at.to_rfc3339(),
+            last_activity: last_activity.to_rfc3339(),
+            server_id: self.server_id.clone(),
+            session_id: self.session_id.clone(),
+            project_id: self.project_id.clone(),
+            cwd: self.cwd.clone(),
+            command: self.command.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct PtyManager {
     sessions: Arc<Mutex<HashMap<String, PtySession>>>,
     writers: Arc<Mutex<HashMap<String, Box<dyn Write + Send>>>>,
+    buffers: Arc<Mutex<HashMap<String, ScrollbackBuffer>>>,
+    /// Terminals without activity for this long are killed; `None` keeps them
+    idle_timeout: Arc<Mutex<Option<Duration>>>,
     app_handle: Option<AppHandle>,
 }
 
@@ -22,6 +67,8 @@ impl PtyManager {
         Self {
             sessions: Arc::new(Mutex::new(HashMap::new())),
             writers: Arc::new(Mutex::new(HashMap::new())),
+            buffers: Arc::new(Mutex::new(HashMap::new())),
+            idle_timeout: Arc::new(Mutex::new(None)),
             app_handle: None,
         }
     }
@@ -34,8 +81,9 @@ impl PtyManager {
         &self,
         rows: u16,
         cols: u16,
-        _server_id: Option<String>,
-        _session_id: Option<String>,
+        server_id: Option<String>,
+        session_id: Option<String>,
+        options: TerminalOptions,
     ) -> Result<TerminalSession, String> {
         let pty_system = native_pty_system();
 
@@ -50,19 +98,19 @@ impl PtyManager {
             .openpty(pty_size)
             .map_err(|e| format!("Failed to open PTY: {}", e))?;
 
-        let mut cmd = CommandBuilder::new(std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string()));
-
-        // Set up environment
-        cmd.env("TERM", "xterm-256color");
-        cmd.env("COLORTERM", "truecolor");
+        if let Some(cwd) = &options.cwd {
+            if !std::path::Path::new(cwd).is_dir() {
+                return Err(format!("Working directory does not exist: {}", cwd));
+            }
+        }
 
-        // Unset npm_config_prefix to avoid nvm/volta conflicts
-        cmd.env_remove("npm_config_prefix");
+        let cmd = build_command(&default_shell(), &options);
 
-        let _child = pair
+        let mut child = pair
             .slave
             .spawn_command(cmd)
             .map_err(|e| format!("Failed to spawn shell: {}", e))?;
+        let killer = child.clone_killer();
 
         let terminal_id = Uuid::new_v4().to_string();
         let terminal_session = TerminalSession {
@@ -71,24 +119,35 @@ impl PtyManager {
             cols,
         };
 
-        // Set up reader thread
+        // Read on the blocking pool and coalesce output into events on the runtime
         let mut reader = pair.master.try_clone_reader()
             .map_err(|e| format!("Failed to clone reader: {}", e))?;
 
         let app_handle_clone = self.app_handle.clone();
         let terminal_id_clone = terminal_id.clone();
+        let buffers = self.buffers.clone();
+        buffers.lock().unwrap().insert(terminal_id.clone(), ScrollbackBuffer::new(MAX_SCROLLBACK_BYTES));
+        let created_at = Utc::now();
+        let last_activity = Arc::new(AtomicI64::new(created_at.timestamp_millis()));
+        let reader_activity = last_activity.clone();
+
+        let (chunks_tx, chunks_rx) = tokio::sync::mpsc::unbounded_channel();
+        let throttle = Arc::new(Mutex::new(options.throttle.clone()));
+        tokio::spawn(output::forward_output(chunks_rx, terminal_id.clone(), app_handle_clone, throttle.clone()));
 
-        let reader_thread = std::thread::spawn(move || {
+        let reader_task = tokio::task::spawn_blocking(move || {
             let mut buf = [0u8; 4096];
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => break, // EOF
                     Ok(n) => {
-                        let data = String::from_utf8_lossy(&buf[..n]).to_string();
+                        reader_activity.store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+                        if let Some(buffer) = buffers.lock().unwrap().get_mut(&terminal_id_clone) {
+                            buffer.push(&buf[..n]);
+                        }
 
-                        // Emit terminal output event
-                        if let Some(handle) = &app_handle_clone {
-                            let _ = handle.emit(&format!("terminal-output-{}", terminal_id_clone), data);
+                        if chunks_tx.send(buf[..n].to_vec()).is_err() {
+                            break;
                         }
                     }
                     Err(e) => {
@@ -108,24 +167,62 @@ impl PtyManager {
 
         let pty_session = PtySession {
             id: terminal_id.clone(),
-            reader_thread: Some(reader_thread),
+            reader_task: Some(reader_task),
+            master: pair.master,
+            size: TerminalSize { rows, cols },
+            killer,
+            created_at,
+            last_activity,
+            server_id,
+            session_id,
+            project_id: options.project_id.clone(),
+            cwd: options.cwd.clone().or_else(|| {
+                std::env::current_dir().ok().map(|dir| dir.to_string_lossy().to_string())
+            }),
+            command: options.command.clone(),
+            throttle,
         };
 
         self.sessions.lock().unwrap().insert(terminal_id.clone(), pty_session);
 
+        // Wait for the child to exit, then report it and drop the terminal
+        let sessions = self.sessions.clone();
+        let writers = self.writers.clone();
+        let buffers = self.buffers.clone();
+        let app_handle = self.app_handle.clone();
+        let exited_id = terminal_id.clone();
+        std::thread::spawn(move || {
+            let exit = terminal_exit(&exited_id, child.wait());
+
+            sessions.lock().unwrap().remove(&exited_id);
+            writers.lock().unwrap().remove(&exited_id);
+            buffers.lock().unwrap().remove(&exited_id);
+
+            if let Some(handle) = &app_handle {
+                let _ = handle.emit(&format!("terminal-exited-{}", exited_id), exit);
+            }
+        });
+
         Ok(terminal_session)
     }
 
     pub fn write_to_terminal_sync(&self, terminal_id: &str, data: &str) -> Result<(), String> {
+        self.write_bytes_sync(terminal_id, data.as_bytes())
+    }
+
+    /// Write raw bytes, which needn't be valid UTF-8, to the terminal's input
+    pub fn write_bytes_sync(&self, terminal_id: &str, data: &[u8]) -> Result<(), String> {
         // Check if session exists
-        if !self.sessions.lock().unwrap().contains_key(terminal_id) {
-            return Err(format!("Terminal session {} not found", terminal_id));
+        match self.sessions.lock().unwrap().get(terminal_id) {
+            Some(session) => session.last_activity.store(Utc::now().timestamp_millis(), Ordering::Relaxed),
+            None => return Err(format!("Terminal session {} not found", terminal_id)),
         }
 
         // Get the writer and write to it
         let mut writers = self.writers.lock().unwrap();
         if let Some(writer) = writers.get_mut(terminal_id) {
-            writer.write_all(data.as_bytes())
+            writer.write_all(data)
+                .and_then(|_| writer.flush())
                 .map_err(|e| format!("Failed to write to terminal: {}", e))?;
             Ok(())
         } else {
@@ -133,38 +230,409 @@ impl PtyManager {
         }
     }
 
-    pub fn resize_terminal_sync(&self, terminal_id: &str, _cols: u16, _rows: u16) -> Result<(), String> {
-        // For now, we'll skip resize functionality as it requires keeping the master PTY
-        // This is a limitation of the current implementation
-        if self.sessions.lock().unwrap().contains_key(terminal_id) {
-            // TODO: Implement resize when we have a better PTY management strategy
-            Ok(())
-        } else {
-            Err(format!("Terminal session {} not found", terminal_id))
+    /// Decode base64 input and write the bytes to the terminal
+    pub fn write_base64_sync(&self, terminal_id: &str, base64_data: &str) -> Result<(), String> {
+        use base64::Engine;
+
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(base64_data.trim())
+            .map_err(|e| format!("Invalid base64 data: {}", e))?;
+        self.write_bytes_sync(terminal_id, &data)
+    }
+
+    pub fn resize_terminal_sync(&self, terminal_id: &str, cols: u16, rows: u16) -> Result<(), String> {
+        if cols == 0 || rows == 0 {
+            return Err("Terminal size must be at least 1x1".to_string());
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.get_mut(terminal_id)
+            .ok_or_else(|| format!("Terminal session {} not found", terminal_id))?;
+
+        // Unchanged sizes would still send the shell a SIGWINCH
+        if session.size.cols == cols && session.size.rows == rows {
+            return Ok(());
         }
+
+        session.master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to resize terminal: {}", e))?;
+        session.size = TerminalSize { rows, cols };
+
+        Ok(())
+    }
+
+    /// Output of the terminal so far, limited to the last `lines` lines
+    pub fn get_terminal_buffer_sync(&self, terminal_id: &str, lines: Option<usize>) -> Result<String, String> {
+        self.buffers.lock().unwrap()
+            .get(terminal_id)
+            .map(|buffer| buffer.tail(lines))
+            .ok_or_else(|| format!("Terminal session {} not found", terminal_id))
+    }
+
+    pub fn search_terminal_sync(
+        &self,
+        terminal_id: &str,
+        query: &str,
+        regex: bool,
+        case_sensitive: bool,
+    ) -> Result<Vec<TerminalSearchMatch>, String> {
+        self.buffers.lock().unwrap()
+            .get(terminal_id)
+            .ok_or_else(|| format!("Terminal session {} not found", terminal_id))?
+            .search(query, regex, case_sensitive)
     }
 
     pub fn kill_terminal_sync(&self, terminal_id: &str) -> Result<(), String> {
         let mut session = self.sessions.lock().unwrap().remove(terminal_id)
             .ok_or_else(|| format!("Terminal session {} not found", terminal_id))?;
 
-        // The reader thread will exit on its own when the PTY is closed
-        if let Some(thread) = session.reader_thread.take() {
-            // We can't really wait for the thread in this context
+        // Stop the process rather than relying on the PTY hangup; the
+        // exit watcher still reports it
+        let _ = session.killer.kill();
+
+        // The reader task will exit on its own when the PTY is closed
+        if let Some(task) = session.reader_task.take() {
+            // We can't really wait for the blocking read in this context
             // but it will clean up on its own
-            drop(thread);
+            drop(task);
         }
 
-        // Remove the writer
+        // Remove the writer and scrollback
         self.writers.lock().unwrap().remove(terminal_id);
+        self.buffers.lock().unwrap().remove(terminal_id);
+
+        Ok(())
+    }
 
+    pub fn set_throttle_sync(&self, terminal_id: &str, config: ThrottleConfig) -> Result<(), String> {
+        let sessions = self.sessions.lock().unwrap();
+        let session = sessions.get(terminal_id)
+            .ok_or_else(|| format!("Terminal session {} not found", terminal_id))?;
+        *session.throttle.lock().unwrap() = config;
         Ok(())
     }
 
+    pub fn list_terminals_sync(&self) -> Vec<TerminalInfo> {
+        let mut terminals: Vec<TerminalInfo> = self.sessions.lock().unwrap()
+            .values()
+            .map(|session| session.info())
+            .collect();
+        terminals.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        terminals
+    }
+
+    /// Terminals opened for any of the given server, session or project
+    pub fn find_terminals_sync(
+        &self,
+        server_id: Option<&str>,
+        session_id: Option<&str>,
+        project_id: Option<&str>,
+    ) -> Vec<TerminalInfo> {
+        let matches = |wanted: Option<&str>, actual: &Option<String>| {
+            wanted.is_some() && wanted == actual.as_deref()
+        };
+
+        self.list_terminals_sync()
+            .into_iter()
+            .filter(|t| {
+                matches(server_id, &t.server_id)
+                    || matches(session_id, &t.session_id)
+                    || matches(project_id, &t.project_id)
+            })
+            .collect()
+    }
+
+    /// Kill every terminal running in `project_path` or below it
+    pub fn kill_project_terminals_sync(&self, project_path: &str) -> Vec<String> {
+        let ids: Vec<String> = self.sessions.lock().unwrap()
+            .values()
+            .filter(|session| session.cwd.as_deref().is_some_and(|cwd| path_within(cwd, project_path)))
+            .map(|session| session.id.clone())
+            .collect();
+
+        ids.into_iter()
+            .filter(|id| self.kill_terminal_sync(id).is_ok())
+            .collect()
+    }
+
+    pub fn set_idle_timeout(&self, timeout: Option<Duration>) {
+        *self.idle_timeout.lock().unwrap() = timeout;
+    }
+
+    /// Kill terminals idle for longer than the configured timeout
+    pub fn reap_idle_sync(&self) -> Vec<String> {
+        let Some(timeout) = *self.idle_timeout.lock().unwrap() else {
+            return Vec::new();
+        };
+        let cutoff = Utc::now().timestamp_millis() - timeout.as_millis() as i64;
+
+        let idle: Vec<String> = self.sessions.lock().unwrap()
+            .values()
+            .filter(|session| session.last_activity.load(Ordering::Relaxed) < cutoff)
+            .map(|session| session.id.clone())
+            .collect();
+
+        idle.into_iter()
+            .filter(|id| self.kill_terminal_sync(id).is_ok())
+            .inspect(|id| println!("Killed idle terminal {}", id))
+            .collect()
+    }
+
+    /// Check for idle terminals every `interval` until the app exits
+    pub fn start_idle_reaper(&self, interval: Duration) {
+        let manager = self.clone();
+
+        tauri::async_runtime::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.reap_idle_sync();
+            }
+        });
+    }
+
     pub async fn get_server_details(&self, _server_id: &str) -> Result<(String, u16), String> {
         // This would typically query the OpenCode service for server details
         // For now, returning placeholder values
         // You'll need to integrate this with your OpenCodeService
         Ok(("localhost".to_string(), 4096))
     }
-}
\ No newline at end of file
+}
+
+/// What to report for a terminal whose child exited, or couldn't be waited on
+fn terminal_exit(terminal_id: &str, status: std::io::Result<ExitStatus>) -> TerminalExit {
+    match status {
+        Ok(status) => TerminalExit {
+            terminal_id: terminal_id.to_string(),
+            exit_code: status.exit_code(),
+            success: status.success(),
+        },
+        Err(e) => {
+            eprintln!("Error waiting for terminal {}: {}", terminal_id, e);
+            TerminalExit { terminal_id: terminal_id.to_string(), exit_code: 1, success: false }
+        }
+    }
+}
+
+/// Whether `path` is `root` or inside it
+pub fn path_within(path: &str, root: &str) -> bool {
+    let path = std::path::Path::new(path.trim_end_matches('/'));
+    let root = std::path::Path::new(root.trim_end_matches('/'));
+    path.starts_with(root)
+}
+
+#[cfg(unix)]
+fn default_shell() -> String {
+    std::env::var("SHELL").unwrap_or_else(|_| "/bin/bash".to_string())
+}
+
+/// PowerShell ships with every supported Windows; ConPTY is used by
+/// portable_pty's native PTY system there
+#[cfg(windows)]
+fn default_shell() -> String {
+    "powershell.exe".to_string()
+}
+
+/// Arguments making `shell` run `command` and exit
+fn shell_command_args(shell: &str, command: &str) -> Vec<String> {
+    // Split on both separators so Windows paths are handled on any host
+    let file_name = shell.rsplit(['/', '\\']).next().unwrap_or(shell).to_lowercase();
+    let name = file_name.strip_suffix(".exe").unwrap_or(&file_name);
+
+    match name {
+        "powershell" | "pwsh" => vec!["-NoLogo".to_string(), "-Command".to_string(), command.to_string()],
+        "cmd" => vec!["/C".to_string(), command.to_string()],
+        // A login shell picks up the user's PATH for tools like npm
+        _ => vec!["-l".to_string(), "-c".to_string(), command.to_string()],
+    }
+}
+
+/// The shell, or the shell running `command` when one is given, with the
+/// terminal environment and the caller's overrides applied
+fn build_command(shell: &str, options: &TerminalOptions) -> CommandBuilder {
+    let mut cmd = CommandBuilder::new(shell);
+
+    if let Some(command) = &options.command {
+        cmd.args(shell_command_args(shell, command));
+    }
+
+    if let Some(cwd) = &options.cwd {
+        cmd.cwd(cwd);
+    }
+
+    // Set up environment
+    cmd.env("TERM", "xterm-256color");
+    cmd.env("COLORTERM", "truecolor");
+
+    // Unset npm_config_prefix to avoid nvm/volta conflicts
+    #[cfg(unix)]
+    cmd.env_remove("npm_config_prefix");
+
+    for (key, value) in &options.env {
+        cmd.env(key, value);
+    }
+
+    cmd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn(manager: &PtyManager, command: &str) -> TerminalSession {
+        let options = TerminalOptions { command: Some(command.to_string()), ..Default::default() };
+        manager.create_terminal_sync(24, 80, None, None, options).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_resize_terminal() {
+        let manager = PtyManager::new();
+        let terminal = spawn(&manager, "sleep 30");
+
+        manager.resize_terminal_sync(&terminal.id, 120, 40).unwrap();
+        let info = manager.list_terminals_sync().remove(0);
+        assert_eq!((info.cols, info.rows), (120, 40));
+        // The same size again is accepted without touching the PTY
+        manager.resize_terminal_sync(&terminal.id, 120, 40).unwrap();
+
+        assert!(manager.resize_terminal_sync(&terminal.id, 0, 40).unwrap_err().contains("at least 1x1"));
+        assert!(manager.resize_terminal_sync("missing", 80, 24).unwrap_err().contains("not found"));
+
+        manager.kill_terminal_sync(&terminal.id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_terminals_open_in_their_directory_and_are_found_by_owner() {
+        let manager = PtyManager::new();
+        let dir = std::env::temp_dir().to_string_lossy().to_string();
+        let options = TerminalOptions {
+            command: Some("sleep 30".to_string()),
+            cwd: Some(dir.clone()),
+            project_id: Some("p1".to_string()),
+            ..Default::default()
+        };
+        let server = manager
+            .create_terminal_sync(24, 80, Some("srv".to_string()), None, options)
+            .unwrap();
+        let session = manager
+            .create_terminal_sync(24, 80, None, Some("s1".to_string()), TerminalOptions {
+                command: Some("sleep 30".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let found = manager.find_terminals_sync(Some("srv"), None, None);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].id, server.id);
+        assert_eq!(found[0].cwd.as_deref(), Some(dir.as_str()));
+        assert_eq!(manager.find_terminals_sync(None, Some("s1"), None)[0].id, session.id);
+        assert_eq!(manager.find_terminals_sync(None, None, Some("p1"))[0].id, server.id);
+        assert_eq!(manager.find_terminals_sync(Some("srv"), Some("s1"), None).len(), 2);
+        assert!(manager.find_terminals_sync(None, None, None).is_empty());
+
+        let missing = TerminalOptions { cwd: Some("/definitely/not/here".to_string()), ..Default::default() };
+        let error = manager.create_terminal_sync(24, 80, None, None, missing).unwrap_err();
+        assert!(error.contains("Working directory does not exist"));
+
+        manager.kill_terminal_sync(&server.id).unwrap();
+        manager.kill_terminal_sync(&session.id).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_write_base64_input() {
+        let manager = PtyManager::new();
+        let terminal = spawn(&manager, "cat");
+
+        // "ab\xffcd\n" isn't valid UTF-8
+        manager.write_base64_sync(&terminal.id, " YWL/Y2QK\n").unwrap();
+        let mut output = String::new();
+        for _ in 0..100 {
+            output = manager.get_terminal_buffer_sync(&terminal.id, None).unwrap();
+            if output.contains("cd") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert!(output.contains("ab"), "unexpected output: {:?}", output);
+        assert!(output.contains("cd"), "unexpected output: {:?}", output);
+
+        assert!(manager.write_base64_sync(&terminal.id, "not base64!").unwrap_err().starts_with("Invalid base64 data"));
+        assert!(manager.write_base64_sync("missing", "YQ==").unwrap_err().contains("not found"));
+
+        manager.kill_terminal_sync(&terminal.id).unwrap();
+    }
+
+    #[test]
+    fn test_terminal_exit() {
+        let exit = terminal_exit("t1", Ok(ExitStatus::with_exit_code(3)));
+        assert_eq!((exit.terminal_id.as_str(), exit.exit_code, exit.success), ("t1", 3, false));
+
+        assert!(terminal_exit("t1", Ok(ExitStatus::with_exit_code(0))).success);
+
+        let exit = terminal_exit("t1", Err(std::io::Error::other("gone")));
+        assert_eq!((exit.exit_code, exit.success), (1, false));
+    }
+
+    #[tokio::test]
+    async fn test_exited_terminals_are_dropped() {
+        let manager = PtyManager::new();
+        let terminal = spawn(&manager, "exit 3");
+
+        for _ in 0..100 {
+            if manager.list_terminals_sync().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        assert!(manager.list_terminals_sync().is_empty());
+        assert!(manager.write_to_terminal_sync(&terminal.id, "ls\n").unwrap_err().contains("not found"));
+        assert!(manager.get_terminal_buffer_sync(&terminal.id, None).is_err());
+    }
+
+    #[test]
+    fn test_build_command_runs_command_through_shell() {
+        let options = TerminalOptions {
+            command: Some("npm run dev".to_string()),
+            cwd: Some("/tmp".to_string()),
+            env: HashMap::from([("PORT".to_string(), "3001".to_string())]),
+            project_id: None,
+            throttle: ThrottleConfig::default(),
+        };
+
+        let cmd = build_command("/bin/zsh", &options);
+        assert_eq!(cmd.get_argv(), &["/bin/zsh", "-l", "-c", "npm run dev"]);
+        assert_eq!(cmd.get_cwd().map(|c| c.to_string_lossy().to_string()), Some("/tmp".to_string()));
+        assert_eq!(cmd.get_env("PORT").map(|v| v.to_string_lossy().to_string()), Some("3001".to_string()));
+        assert_eq!(cmd.get_env("TERM").map(|v| v.to_string_lossy().to_string()), Some("xterm-256color".to_string()));
+    }
+
+    #[test]
+    fn test_build_command_defaults_to_interactive_shell() {
+        let cmd = build_command("/bin/bash", &TerminalOptions::default());
+        assert_eq!(cmd.get_argv(), &["/bin/bash"]);
+        #[cfg(unix)]
+        assert!(cmd.get_env("npm_config_prefix").is_none());
+    }
+
+    #[test]
+    fn test_path_within() {
+        assert!(path_within("/work/app", "/work/app/"));
+        assert!(path_within("/work/app/packages/web", "/work/app"));
+        assert!(!path_within("/work/app-2", "/work/app"));
+    }
+
+    #[test]
+    fn test_shell_command_args_per_shell() {
+        assert_eq!(shell_command_args("powershell.exe", "npm run dev"), vec!["-NoLogo", "-Command", "npm run dev"]);
+        assert_eq!(shell_command_args(r"C:\Program Files\PowerShell\7\pwsh.exe", "ls"), vec!["-NoLogo", "-Command", "ls"]);
+        assert_eq!(shell_command_args("C:/Windows/System32/cmd.exe", "dir"), vec!["/C", "dir"]);
+        assert_eq!(shell_command_args("/usr/bin/fish", "ls"), vec!["-l", "-c", "ls"]);
+    }
+}
@@ -1,5 +1,7 @@
+pub mod buffer;
 pub mod types;
 pub mod manager;
+pub mod output;
 
 pub use types::*;
 pub use manager::PtyManager;
\ No newline at end of file
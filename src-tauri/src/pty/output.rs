@@ -0,0 +1,206 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::types::{ThrottleConfig, ThrottleMode};
+
+/// Output arriving within this window of the first chunk goes out as one event
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(16);
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Applies a terminal's `ThrottleConfig` to its outgoing output
+pub struct Throttle {
+    window_start: Instant,
+    sent: usize,
+}
+
+impl Throttle {
+    pub fn new(now: Instant) -> Self {
+        Self { window_start: now, sent: 0 }
+    }
+
+    /// Take what may be sent now from `pending`, returning it with the number
+    /// of bytes dropped. In merge mode the rest stays in `pending`.
+    pub fn take(&mut self, config: &ThrottleConfig, pending: &mut Vec<u8>, now: Instant) -> (Vec<u8>, usize) {
+        let Some(limit) = config.max_bytes_per_second else {
+            return (std::mem::take(pending), 0);
+        };
+
+        if now.duration_since(self.window_start) >= RATE_WINDOW {
+            self.window_start = now;
+            self.sent = 0;
+        }
+
+        let allowed = limit.saturating_sub(self.sent).min(pending.len());
+        let out: Vec<u8> = pending.drain(..allowed).collect();
+        self.sent += allowed;
+
+        let dropped = match config.mode {
+            ThrottleMode::Drop => {
+                let dropped = pending.len();
+                pending.clear();
+                dropped
+            }
+            ThrottleMode::Merge => {
+                let dropped = pending.len().saturating_sub(limit);
+                pending.drain(..dropped);
+                dropped
+            }
+        };
+
+        (out, dropped)
+    }
+}
+
+/// Shown in place of output the throttle dropped
+pub fn truncated_marker(dropped: usize) -> String {
+    format!("\r\n\x1b[33m[output truncated: {} bytes]\x1b[0m\r\n", dropped)
+}
+
+/// Forward chunks read from a PTY as `terminal-output-{id}` events, merging
+/// bursts into one event per `FLUSH_INTERVAL` and applying the terminal's
+/// throttle. Ends when the reader hangs up.
+pub async fn forward_output(
+    mut chunks: UnboundedReceiver<Vec<u8>>,
+    terminal_id: String,
+    app_handle: Option<AppHandle>,
+    throttle_config: Arc<Mutex<ThrottleConfig>>,
+) {
+    let event = format!("terminal-output-{}", terminal_id);
+    let mut throttle = Throttle::new(Instant::now());
+    // Output read but held back by the throttle
+    let mut pending = Vec::new();
+    // Output let through but ending in an incomplete character
+    let mut undecoded = Vec::new();
+    let mut open = true;
+
+    while open {
+        // Output held back in merge mode goes out without waiting for more
+        if pending.is_empty() {
+            match chunks.recv().await {
+                Some(chunk) => pending.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+
+        let deadline = tokio::time::sleep(FLUSH_INTERVAL);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                chunk = chunks.recv() => match chunk {
+                    Some(chunk) => pending.extend_from_slice(&chunk),
+                    None => {
+                        open = false;
+                        break;
+                    }
+                },
+            }
+        }
+
+        let config = throttle_config.lock().unwrap().clone();
+        let (out, dropped) = throttle.take(&config, &mut pending, Instant::now());
+        undecoded.extend_from_slice(&out);
+
+        let mut text = take_utf8(&mut undecoded);
+        if dropped > 0 {
+            text.push_str(&truncated_marker(dropped));
+        }
+        if let (Some(handle), false) = (&app_handle, text.is_empty()) {
+            let _ = handle.emit(&event, text);
+        }
+    }
+
+    // Whatever is left can't be completed any more
+    undecoded.extend_from_slice(&pending);
+    if let (Some(handle), false) = (&app_handle, undecoded.is_empty()) {
+        let _ = handle.emit(&event, String::from_utf8_lossy(&undecoded).to_string());
+    }
+}
+
+/// Decode and remove the complete part of `pending`, leaving a multi-byte
+/// character cut off at the end for the next read. Invalid bytes are
+/// replaced rather than held back.
+pub fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        // error_len() is None only for a truncated sequence at the very end
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+
+    let text = String::from_utf8_lossy(&pending[..complete]).to_string();
+    pending.drain(..complete);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(bytes: usize, mode: ThrottleMode) -> ThrottleConfig {
+        ThrottleConfig { max_bytes_per_second: Some(bytes), mode }
+    }
+
+    #[test]
+    fn test_throttle_drop_discards_excess_until_next_window() {
+        let start = Instant::now();
+        let config = limit(4, ThrottleMode::Drop);
+        let mut throttle = Throttle::new(start);
+
+        let mut pending = b"abcdef".to_vec();
+        assert_eq!(throttle.take(&config, &mut pending, start), (b"abcd".to_vec(), 2));
+        assert!(pending.is_empty());
+
+        let mut pending = b"gh".to_vec();
+        assert_eq!(throttle.take(&config, &mut pending, start + Duration::from_millis(500)), (Vec::new(), 2));
+
+        let mut pending = b"ij".to_vec();
+        assert_eq!(throttle.take(&config, &mut pending, start + RATE_WINDOW), (b"ij".to_vec(), 0));
+    }
+
+    #[test]
+    fn test_throttle_merge_defers_and_caps_backlog() {
+        let start = Instant::now();
+        let config = limit(4, ThrottleMode::Merge);
+        let mut throttle = Throttle::new(start);
+
+        let mut pending = b"abcdefghijkl".to_vec();
+        assert_eq!(throttle.take(&config, &mut pending, start), (b"abcd".to_vec(), 4));
+        assert_eq!(pending, b"ijkl".to_vec());
+
+        assert_eq!(throttle.take(&config, &mut pending, start + RATE_WINDOW), (b"ijkl".to_vec(), 0));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_unthrottled_output_passes_through() {
+        let mut throttle = Throttle::new(Instant::now());
+        let mut pending = vec![b'x'; 10_000];
+        let (out, dropped) = throttle.take(&ThrottleConfig::default(), &mut pending, Instant::now());
+        assert_eq!((out.len(), dropped), (10_000, 0));
+    }
+
+    #[test]
+    fn test_take_utf8_keeps_partial_character() {
+        let bytes = "aé".as_bytes();
+        let mut pending = bytes[..2].to_vec();
+
+        assert_eq!(take_utf8(&mut pending), "a");
+        assert_eq!(pending, vec![bytes[1]]);
+
+        pending.push(bytes[2]);
+        assert_eq!(take_utf8(&mut pending), "é");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn test_take_utf8_replaces_invalid_bytes() {
+        let mut pending = vec![b'a', 0xff, b'b'];
+        assert_eq!(take_utf8(&mut pending), "a\u{fffd}b");
+        assert!(pending.is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalSession {
@@ -7,6 +8,77 @@ pub struct TerminalSession {
     pub cols: u16,
 }
 
+/// A running terminal as reported by `list_terminals`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalInfo {
+    pub id: String,
+    pub rows: u16,
+    pub cols: u16,
+    pub created_at: String,
+    /// Last output from or input to the terminal
+    pub last_activity: String,
+    pub server_id: Option<String>,
+    pub session_id: Option<String>,
+    pub project_id: Option<String>,
+    pub cwd: Option<String>,
+    pub command: Option<String>,
+}
+
+/// What a new terminal runs and where. By default it's the user's shell in
+/// the app's working directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerminalOptions {
+    /// Command line run through the shell instead of an interactive shell
+    pub command: Option<String>,
+    pub cwd: Option<String>,
+    pub env: HashMap<String, String>,
+    /// Project the terminal belongs to, for lookups and teardown
+    pub project_id: Option<String>,
+    pub throttle: ThrottleConfig,
+}
+
+/// What happens to output beyond a terminal's rate limit
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThrottleMode {
+    /// Discard it
+    #[default]
+    Drop,
+    /// Send it in later, larger events; a backlog over one second's budget
+    /// loses its oldest output
+    Merge,
+}
+
+/// Output rate limit of a terminal. No limit when `max_bytes_per_second` is unset.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    pub max_bytes_per_second: Option<usize>,
+    pub mode: ThrottleMode,
+}
+
+/// A match of `search_terminal` in a terminal's scrollback, which is searched
+/// with escape sequences removed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TerminalSearchMatch {
+    /// 0-based line within the retained scrollback
+    pub line: usize,
+    /// Start of the match in characters from the start of the line
+    pub column: usize,
+    /// Length of the match in characters
+    pub length: usize,
+    pub text: String,
+}
+
+/// Payload of `terminal-exited-{id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminalExit {
+    pub terminal_id: String,
+    pub exit_code: u32,
+    pub success: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalSize {
     pub rows: u16,
@@ -2,8 +2,10 @@ pub mod client;
 pub mod worker;
 pub mod types;
 pub mod local_test;
+pub mod recorder;
 
 pub use client::{QueueClient, InMemoryQueueClient};
 pub use worker::WorkerService;
 pub use types::*;
-pub use local_test::LocalTestMode;
\ No newline at end of file
+pub use local_test::LocalTestMode;
+pub use recorder::{RecordingQueueClient, TaskHistory};
\ No newline at end of file
@@ -0,0 +1,108 @@
+use super::client::QueueClient;
+use super::types::*;
+use crate::database::tasks;
+use crate::database::usage::{UsageSample, UsageTracker};
+use async_trait::async_trait;
+use rusqlite::Connection;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Database handle for task history. The queue is built before the database
+/// is opened, so the connection is attached later during app setup.
+#[derive(Clone, Default)]
+pub struct TaskHistory {
+    conn: Arc<OnceLock<Arc<Mutex<Connection>>>>,
+}
+
+impl TaskHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attach(&self, conn: Arc<Mutex<Connection>>) {
+        if self.conn.set(conn).is_err() {
+            println!("Task history database already attached");
+        }
+    }
+
+    fn record<F>(&self, what: &str, f: F)
+    where
+        F: FnOnce(&Connection) -> rusqlite::Result<()>,
+    {
+        let Some(conn) = self.conn.get() else {
+            return;
+        };
+        let conn = conn.lock().unwrap();
+        if let Err(e) = f(&conn) {
+            println!("Failed to record {}: {}", what, e);
+        }
+    }
+}
+
+/// Queue client that writes tasks, results and worker registrations to the
+/// database before handing them to the underlying queue
+pub struct RecordingQueueClient {
+    inner: Arc<dyn QueueClient>,
+    history: TaskHistory,
+    usage: UsageTracker,
+}
+
+impl RecordingQueueClient {
+    pub fn new(inner: Arc<dyn QueueClient>, history: TaskHistory) -> Self {
+        Self { inner, history, usage: UsageTracker::new() }
+    }
+
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = usage;
+        self
+    }
+}
+
+#[async_trait]
+impl QueueClient for RecordingQueueClient {
+    async fn publish_task(&self, task: TaskMessage) -> Result<(), String> {
+        self.history.record("task", |conn| tasks::record_task(conn, &task));
+        self.inner.publish_task(task).await
+    }
+
+    async fn consume_task(&self) -> Result<Option<TaskMessage>, String> {
+        self.inner.consume_task().await
+    }
+
+    async fn publish_result(&self, result: TaskResult) -> Result<(), String> {
+        self.history.record("task result", |conn| tasks::record_result(conn, &result));
+        self.usage.record(UsageSample {
+            source: "queue".to_string(),
+            tasks: 1,
+            agent_time_ms: result.execution_time_ms,
+            ..Default::default()
+        });
+        self.inner.publish_result(result).await
+    }
+
+    async fn consume_result(&self, task_id: &str) -> Result<Option<TaskResult>, String> {
+        self.inner.consume_result(task_id).await
+    }
+
+    async fn register_worker(&self, worker: WorkerInfo) -> Result<(), String> {
+        self.history.record("worker registration", |conn| tasks::upsert_worker(conn, &worker));
+        self.inner.register_worker(worker).await
+    }
+
+    async fn update_worker_heartbeat(&self, worker_id: &str) -> Result<(), String> {
+        self.history.record("worker heartbeat", |conn| {
+            tasks::touch_worker(conn, worker_id, chrono::Utc::now())
+        });
+        self.inner.update_worker_heartbeat(worker_id).await
+    }
+
+    async fn get_active_workers(&self) -> Result<Vec<WorkerInfo>, String> {
+        self.inner.get_active_workers().await
+    }
+
+    async fn remove_worker(&self, worker_id: &str) -> Result<(), String> {
+        self.history.record("worker removal", |conn| {
+            tasks::remove_worker(conn, worker_id, chrono::Utc::now())
+        });
+        self.inner.remove_worker(worker_id).await
+    }
+}
@@ -258,9 +258,16 @@ impl WorkerService {
         match language {
             "bash" | "sh" => {
                 use tokio::process::Command;
-                let output = Command::new("bash")
-                    .arg("-c")
-                    .arg(code)
+                let mut command = Command::new("bash");
+                command.arg("-c").arg(code);
+                if let Some(dir) = payload["working_dir"].as_str() {
+                    command.current_dir(dir);
+                }
+                // Project variables sent along by the publisher
+                if let Some(env) = payload["env"].as_object() {
+                    command.envs(env.iter().filter_map(|(k, v)| v.as_str().map(|v| (k, v))));
+                }
+                let output = command
                     .output()
                     .await
                     .map_err(|e| format!("Code execution failed: {}", e))?;
@@ -1,4 +1,5 @@
 use super::types::*;
+use crate::database::usage::{UsageSample, UsageTracker};
 use crate::opencode::{OpenCodeService, OpenCodeApiClient};
 use crate::wezterm::WezTermController;
 use std::collections::{HashMap, VecDeque};
@@ -8,6 +9,9 @@ use uuid::Uuid;
 use chrono::Utc;
 use rand::seq::SliceRandom;
 
+/// Completed tasks remembered after their session moves on
+const MAX_COMPLETED_TASKS: usize = 100;
+
 pub struct SessionManager {
     sessions: Arc<RwLock<HashMap<String, OrchestratorSession>>>,
     opencode_service: Arc<OpenCodeService>,
@@ -15,6 +19,8 @@ pub struct SessionManager {
     distribution_strategy: DistributionStrategy,
     round_robin_index: Arc<RwLock<usize>>,
     pending_tasks: Arc<RwLock<VecDeque<Task>>>,
+    completed_tasks: Arc<RwLock<VecDeque<Task>>>,
+    usage: UsageTracker,
 }
 
 impl SessionManager {
@@ -29,9 +35,16 @@ impl SessionManager {
             distribution_strategy: DistributionStrategy::RoundRobin,
             round_robin_index: Arc::new(RwLock::new(0)),
             pending_tasks: Arc::new(RwLock::new(VecDeque::new())),
+            completed_tasks: Arc::new(RwLock::new(VecDeque::new())),
+            usage: UsageTracker::new(),
         }
     }
 
+    pub fn with_usage(mut self, usage: UsageTracker) -> Self {
+        self.usage = usage;
+        self
+    }
+
     pub async fn register_session(&self, opencode_server_id: String) -> Result<OrchestratorSession, String> {
         println!("SessionManager: Creating session for server {}", opencode_server_id);
         let session_id = format!("session-{}", Uuid::new_v4());
@@ -70,26 +83,49 @@ impl SessionManager {
         println!("SessionManager: Found available session: {}", available_session);
 
         // Assign task to session
-        let mut sessions = self.sessions.write().await;
-        if let Some(session) = sessions.get_mut(&available_session) {
-            println!("SessionManager: Assigning task to session {}", session.id);
-            session.task = Some(task.clone());
-            session.status = SessionStatus::Working;
-
-            // Send prompt to OpenCode server
-            println!("SessionManager: Looking for OpenCode server {}", session.opencode_server_id);
-            if let Some(server) = self.opencode_service.get_server(&session.opencode_server_id).await {
+        let server_id = {
+            let mut sessions = self.sessions.write().await;
+            sessions.get_mut(&available_session).map(|session| {
+                println!("SessionManager: Assigning task to session {}", session.id);
+                session.task = Some(task.clone());
+                session.status = SessionStatus::Working;
+                session.opencode_server_id.clone()
+            })
+        };
+
+        // Run the prompt on the OpenCode server; the task completes, or its
+        // session fails, when the agent's reply comes back
+        if let Some(server_id) = server_id {
+            println!("SessionManager: Looking for OpenCode server {}", server_id);
+            if let Some(server) = self.opencode_service.get_server(&server_id).await {
                 println!("SessionManager: Found server at {}:{}", server.host, server.port);
-                let client = OpenCodeApiClient::new(&server.host, server.port);
-                match client.send_prompt(&prompt).await {
-                    Ok(_) => println!("SessionManager: Successfully sent prompt to OpenCode server"),
-                    Err(e) => println!("SessionManager: Failed to send prompt to OpenCode server: {}", e),
-                }
+                let sessions = self.sessions.clone();
+                let completed = self.completed_tasks.clone();
+                let task_id = task_id.clone();
+                tokio::spawn(async move {
+                    let client = OpenCodeApiClient::new(&server.host, server.port);
+                    let outcome = match client.run_prompt(&task_id, &prompt).await {
+                        Ok(reply) => Self::finish_task(&sessions, &completed, &task_id, Some(reply)).await.map(|_| ()),
+                        Err(e) => {
+                            println!("SessionManager: Task {} failed on OpenCode server: {}", task_id, e);
+                            Self::fail_task(&sessions, &task_id, e).await
+                        }
+                    };
+                    if let Err(e) = outcome {
+                        println!("SessionManager: {}", e);
+                    }
+                });
             } else {
-                println!("SessionManager: Could not find OpenCode server {}", session.opencode_server_id);
+                println!("SessionManager: Could not find OpenCode server {}", server_id);
             }
         }
 
+        self.usage.record(UsageSample {
+            source: "orchestrator".to_string(),
+            tasks: 1,
+            ..Default::default()
+        });
+
         println!("SessionManager: Task {} distributed successfully", task_id);
         Ok(task_id)
     }
@@ -132,9 +168,9 @@ impl SessionManager {
         let mut sessions = self.sessions.write().await;
 
         if let Some(session) = sessions.get_mut(session_id) {
+            // The task stays with the session so whoever follows it sees it failed
             let failed_task = session.task.clone();
             session.status = SessionStatus::Failed("Session failed".to_string());
-            session.task = None;
 
             // If there was an incomplete task, add it to pending tasks
             if let Some(task) = failed_task {
@@ -185,6 +221,66 @@ impl SessionManager {
         Ok(())
     }
 
+    /// Record a task's result and free its session for the next one
+    pub async fn complete_task(&self, task_id: &str, result: Option<String>) -> Result<Task, String> {
+        Self::finish_task(&self.sessions, &self.completed_tasks, task_id, result).await
+    }
+
+    async fn finish_task(
+        sessions: &RwLock<HashMap<String, OrchestratorSession>>,
+        completed: &RwLock<VecDeque<Task>>,
+        task_id: &str,
+        result: Option<String>,
+    ) -> Result<Task, String> {
+        let mut sessions = sessions.write().await;
+        let session = sessions
+            .values_mut()
+            .find(|s| s.status == SessionStatus::Working && s.task.as_ref().is_some_and(|t| t.id == task_id))
+            .ok_or_else(|| format!("Task {} is not assigned to a session", task_id))?;
+
+        let mut task = session.task.take().unwrap();
+        task.completed_at = Some(Utc::now().to_rfc3339());
+        task.result = result;
+        session.status = SessionStatus::Idle;
+        drop(sessions);
+
+        let mut completed = completed.write().await;
+        if completed.len() == MAX_COMPLETED_TASKS {
+            completed.pop_front();
+        }
+        completed.push_back(task.clone());
+        Ok(task)
+    }
+
+    /// Mark the session working on a task failed, keeping the task on it
+    async fn fail_task(
+        sessions: &RwLock<HashMap<String, OrchestratorSession>>,
+        task_id: &str,
+        reason: String,
+    ) -> Result<(), String> {
+        let mut sessions = sessions.write().await;
+        let session = sessions
+            .values_mut()
+            .find(|s| s.status == SessionStatus::Working && s.task.as_ref().is_some_and(|t| t.id == task_id))
+            .ok_or_else(|| format!("Task {} is not assigned to a session", task_id))?;
+        session.status = SessionStatus::Failed(reason);
+        Ok(())
+    }
+
+    /// A task with the status of the session working on it, or `Completed`
+    /// once it's done; None when no session holds it, e.g. after its session
+    /// failed and the prompt went out again as a new task
+    pub async fn task_status(&self, task_id: &str) -> Option<(SessionStatus, Task)> {
+        let sessions = self.sessions.read().await;
+        if let Some(session) = sessions.values().find(|s| s.task.as_ref().is_some_and(|t| t.id == task_id)) {
+            return Some((session.status.clone(), session.task.clone()?));
+        }
+        drop(sessions);
+
+        let completed = self.completed_tasks.read().await;
+        completed.iter().find(|t| t.id == task_id).map(|task| (SessionStatus::Completed, task.clone()))
+    }
+
     pub async fn get_session_state(&self, session_id: &str) -> Option<OrchestratorSession> {
         self.sessions.read().await.get(session_id).cloned()
     }
@@ -269,9 +365,35 @@ mod tests {
         let failed_session = manager.get_session_state(&session.id).await.unwrap();
         assert!(matches!(failed_session.status, SessionStatus::Failed(_)));
 
+        // The failed task is still reported as failed
+        let task_id = failed_session.task.as_ref().unwrap().id.clone();
+        assert!(matches!(manager.task_status(&task_id).await, Some((SessionStatus::Failed(_), _))));
+        assert!(manager.complete_task(&task_id, None).await.is_err());
+
         // Task should be reassigned to another session if available
     }
 
+    #[tokio::test]
+    async fn test_complete_task() {
+        let manager = setup_manager().await;
+
+        let session = manager.register_session("server-done".to_string()).await.unwrap();
+        let task_id = manager.distribute_task("Finish me".to_string()).await.unwrap();
+        let (status, _) = manager.task_status(&task_id).await.unwrap();
+        assert_eq!(status, SessionStatus::Working);
+
+        let task = manager.complete_task(&task_id, Some("Done".to_string())).await.unwrap();
+        assert!(task.completed_at.is_some());
+        let (status, task) = manager.task_status(&task_id).await.unwrap();
+        assert_eq!((status, task.result.as_deref()), (SessionStatus::Completed, Some("Done")));
+
+        // The session is free for the next task
+        let freed = manager.get_session_state(&session.id).await.unwrap();
+        assert_eq!(freed.status, SessionStatus::Idle);
+        assert!(manager.complete_task(&task_id, None).await.is_err());
+        assert!(manager.task_status("task-unknown").await.is_none());
+    }
+
     #[tokio::test]
     #[ignore = "Requires opencode binary"]
     async fn test_rebalance_on_instance_change() {
@@ -0,0 +1,242 @@
+use anyhow::Result;
+use serde_json::{json, Value};
+
+use super::blocks::Block;
+
+const SLACK_API_BASE: &str = "https://slack.com/api";
+
+/// Minimal Slack Web API client
+#[derive(Debug, Clone)]
+pub struct SlackApiClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for SlackApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SlackApiClient {
+    pub fn new() -> Self {
+        Self::with_base_url(SLACK_API_BASE)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Call a Web API method with a JSON body and return the response when `ok` is true
+    pub async fn call(&self, method: &str, token: &str, body: Value) -> Result<Value> {
+        let url = format!("{}/{}", self.base_url, method);
+
+        let response = self.client
+            .post(&url)
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Slack API request {} failed: {}", method, e))?;
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Slack API response for {}: {}", method, e))?;
+
+        if value["ok"].as_bool() != Some(true) {
+            let error = value["error"].as_str().unwrap_or("unknown_error");
+            return Err(anyhow::anyhow!("Slack API {} returned error: {}", method, error));
+        }
+
+        Ok(value)
+    }
+
+    /// Verify a bot token and return the bot's user id
+    pub async fn auth_test(&self, bot_token: &str) -> Result<String> {
+        let response = self.call("auth.test", bot_token, json!({})).await?;
+        Ok(response["user_id"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Request a Socket Mode WebSocket URL using the app-level token
+    pub async fn open_connection(&self, app_token: &str) -> Result<String> {
+        let response = self.call("apps.connections.open", app_token, json!({})).await?;
+        response["url"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow::anyhow!("apps.connections.open did not return a URL"))
+    }
+
+    /// Post a message and return its timestamp
+    pub async fn post_message(
+        &self,
+        bot_token: &str,
+        channel: &str,
+        text: &str,
+        blocks: Option<&[Block]>,
+        thread_ts: Option<&str>,
+    ) -> Result<String> {
+        let mut body = json!({
+            "channel": channel,
+            "text": text,
+        });
+        if let Some(blocks) = blocks {
+            body["blocks"] = json!(blocks);
+        }
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = json!(ts);
+        }
+
+        let response = self.call("chat.postMessage", bot_token, body).await?;
+        Ok(response["ts"].as_str().unwrap_or_default().to_string())
+    }
+
+    /// Reply in a thread, optionally also showing the reply in the channel
+    pub async fn post_thread_reply(
+        &self,
+        bot_token: &str,
+        channel: &str,
+        thread_ts: &str,
+        text: &str,
+        blocks: Option<&[Block]>,
+        broadcast: bool,
+    ) -> Result<String> {
+        let mut body = json!({
+            "channel": channel,
+            "thread_ts": thread_ts,
+            "text": text,
+            "reply_broadcast": broadcast,
+        });
+        if let Some(blocks) = blocks {
+            body["blocks"] = json!(blocks);
+        }
+
+        let response = self.call("chat.postMessage", bot_token, body).await?;
+        Ok(response["ts"].as_str().unwrap_or_default().to_string())
+    }
+
+    pub async fn update_message(
+        &self,
+        bot_token: &str,
+        channel: &str,
+        ts: &str,
+        text: &str,
+        blocks: &[Block],
+    ) -> Result<()> {
+        self.call("chat.update", bot_token, json!({
+            "channel": channel,
+            "ts": ts,
+            "text": text,
+            "blocks": blocks,
+        })).await?;
+        Ok(())
+    }
+
+    /// Upload a file into a channel, or a thread when `thread_ts` is given
+    pub async fn upload_file(
+        &self,
+        bot_token: &str,
+        channel: &str,
+        thread_ts: Option<&str>,
+        filename: &str,
+        bytes: Vec<u8>,
+        title: &str,
+    ) -> Result<()> {
+        // files.getUploadURLExternal only takes form parameters
+        let url = format!("{}/files.getUploadURLExternal", self.base_url);
+        let length = bytes.len().to_string();
+        let response: Value = self.client
+            .post(&url)
+            .bearer_auth(bot_token)
+            .form(&[("filename", filename), ("length", length.as_str())])
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Slack API request files.getUploadURLExternal failed: {}", e))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Slack API response for files.getUploadURLExternal: {}", e))?;
+        if response["ok"].as_bool() != Some(true) {
+            let error = response["error"].as_str().unwrap_or("unknown_error");
+            return Err(anyhow::anyhow!("Slack API files.getUploadURLExternal returned error: {}", error));
+        }
+        let (Some(upload_url), Some(file_id)) = (response["upload_url"].as_str(), response["file_id"].as_str()) else {
+            return Err(anyhow::anyhow!("files.getUploadURLExternal did not return an upload URL"));
+        };
+
+        self.client
+            .post(upload_url)
+            .body(bytes)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| anyhow::anyhow!("Failed to upload {} to Slack: {}", filename, e))?;
+
+        let mut body = json!({
+            "files": [{ "id": file_id, "title": title }],
+            "channel_id": channel,
+        });
+        if let Some(ts) = thread_ts {
+            body["thread_ts"] = json!(ts);
+        }
+        self.call("files.completeUploadExternal", bot_token, body).await?;
+        Ok(())
+    }
+
+    pub async fn add_reaction(&self, bot_token: &str, channel: &str, ts: &str, name: &str) -> Result<()> {
+        self.call("reactions.add", bot_token, json!({
+            "channel": channel,
+            "timestamp": ts,
+            "name": name,
+        })).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn respond(server: &MockServer, api_method: &str, body: Value) {
+        Mock::given(method("POST"))
+            .and(path(format!("/{}", api_method)))
+            .and(header("authorization", "Bearer xoxb-test"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_post_message_returns_ts() {
+        let server = MockServer::start().await;
+        respond(&server, "chat.postMessage", json!({ "ok": true, "ts": "1700000000.000100" })).await;
+
+        let client = SlackApiClient::with_base_url(&server.uri());
+        let ts = client.post_message("xoxb-test", "C1", "hi", None, Some("1.0")).await.unwrap();
+        assert_eq!(ts, "1700000000.000100");
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body, json!({ "channel": "C1", "text": "hi", "thread_ts": "1.0" }));
+    }
+
+    #[tokio::test]
+    async fn test_errors_name_the_method() {
+        let server = MockServer::start().await;
+        respond(&server, "auth.test", json!({ "ok": false, "error": "invalid_auth" })).await;
+        respond(&server, "apps.connections.open", json!({ "ok": true })).await;
+
+        let client = SlackApiClient::with_base_url(&server.uri());
+        let error = client.auth_test("xoxb-test").await.unwrap_err().to_string();
+        assert_eq!(error, "Slack API auth.test returned error: invalid_auth");
+        let error = client.open_connection("xoxb-test").await.unwrap_err().to_string();
+        assert!(error.contains("did not return a URL"));
+    }
+}
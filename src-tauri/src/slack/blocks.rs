@@ -0,0 +1,283 @@
+use serde::{Deserialize, Serialize};
+
+use super::types::SlackMessage;
+
+/// Text composition object
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TextObject {
+    PlainText {
+        text: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        emoji: Option<bool>,
+    },
+    Mrkdwn {
+        text: String,
+    },
+}
+
+impl TextObject {
+    pub fn plain(text: impl Into<String>) -> Self {
+        TextObject::PlainText { text: text.into(), emoji: Some(true) }
+    }
+
+    pub fn mrkdwn(text: impl Into<String>) -> Self {
+        TextObject::Mrkdwn { text: text.into() }
+    }
+
+    pub fn text(&self) -> &str {
+        match self {
+            TextObject::PlainText { text, .. } | TextObject::Mrkdwn { text } => text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ButtonStyle {
+    Primary,
+    Danger,
+}
+
+/// Interactive elements usable in `actions` blocks and section accessories
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Element {
+    Button {
+        text: TextObject,
+        action_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        value: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        style: Option<ButtonStyle>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    /// Any other element (select menus, date pickers, ...), passed through
+    /// as received
+    #[serde(untagged)]
+    Raw(serde_json::Value),
+}
+
+/// Builder for a button element
+#[derive(Debug, Clone)]
+pub struct Button {
+    text: String,
+    action_id: String,
+    value: Option<String>,
+    style: Option<ButtonStyle>,
+    url: Option<String>,
+}
+
+impl Button {
+    pub fn new(text: impl Into<String>, action_id: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            action_id: action_id.into(),
+            value: None,
+            style: None,
+            url: None,
+        }
+    }
+
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn primary(mut self) -> Self {
+        self.style = Some(ButtonStyle::Primary);
+        self
+    }
+
+    pub fn danger(mut self) -> Self {
+        self.style = Some(ButtonStyle::Danger);
+        self
+    }
+
+    pub fn build(self) -> Element {
+        Element::Button {
+            text: TextObject::plain(self.text),
+            action_id: self.action_id,
+            value: self.value,
+            style: self.style,
+            url: self.url,
+        }
+    }
+}
+
+/// Layout blocks the app builds itself. Other block types, e.g. from a
+/// template made in Block Kit Builder, are kept as raw JSON.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Header {
+        text: TextObject,
+    },
+    Section {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        text: Option<TextObject>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fields: Option<Vec<TextObject>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        accessory: Option<Element>,
+    },
+    Divider,
+    Context {
+        elements: Vec<TextObject>,
+    },
+    Actions {
+        elements: Vec<Element>,
+    },
+    #[serde(untagged)]
+    Raw(serde_json::Value),
+}
+
+/// Fluent builder producing a `SlackMessage` with fallback text and typed blocks
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    text: String,
+    blocks: Vec<Block>,
+}
+
+impl MessageBuilder {
+    /// `text` is the notification fallback shown when blocks can't be rendered
+    pub fn new(text: impl Into<String>) -> Self {
+        Self { text: text.into(), blocks: Vec::new() }
+    }
+
+    pub fn header(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Header { text: TextObject::plain(text) });
+        self
+    }
+
+    pub fn section(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Section {
+            text: Some(TextObject::mrkdwn(text)),
+            fields: None,
+            accessory: None,
+        });
+        self
+    }
+
+    pub fn section_with_button(mut self, text: impl Into<String>, button: Button) -> Self {
+        self.blocks.push(Block::Section {
+            text: Some(TextObject::mrkdwn(text)),
+            fields: None,
+            accessory: Some(button.build()),
+        });
+        self
+    }
+
+    /// Two-column field layout, each rendered as `*label*\nvalue`
+    pub fn fields<L, V>(mut self, fields: impl IntoIterator<Item = (L, V)>) -> Self
+    where
+        L: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let fields: Vec<TextObject> = fields
+            .into_iter()
+            .map(|(label, value)| TextObject::mrkdwn(format!("*{}*\n{}", label.as_ref(), value.as_ref())))
+            .collect();
+        self.blocks.push(Block::Section { text: None, fields: Some(fields), accessory: None });
+        self
+    }
+
+    pub fn divider(mut self) -> Self {
+        self.blocks.push(Block::Divider);
+        self
+    }
+
+    pub fn context(mut self, text: impl Into<String>) -> Self {
+        self.blocks.push(Block::Context { elements: vec![TextObject::mrkdwn(text)] });
+        self
+    }
+
+    pub fn actions(mut self, buttons: impl IntoIterator<Item = Button>) -> Self {
+        self.blocks.push(Block::Actions {
+            elements: buttons.into_iter().map(Button::build).collect(),
+        });
+        self
+    }
+
+    pub fn block(mut self, block: Block) -> Self {
+        self.blocks.push(block);
+        self
+    }
+
+    pub fn build(self) -> SlackMessage {
+        SlackMessage {
+            text: self.text,
+            blocks: Some(self.blocks),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_builder_serializes_to_block_kit() {
+        let message = MessageBuilder::new("fallback")
+            .header("Title")
+            .fields([("Project", "demo")])
+            .actions([Button::new("Approve", "approve").value("id-1").primary()])
+            .build();
+
+        let blocks = serde_json::to_value(message.blocks.unwrap()).unwrap();
+        assert_eq!(blocks, json!([
+            { "type": "header", "text": { "type": "plain_text", "text": "Title", "emoji": true } },
+            { "type": "section", "fields": [{ "type": "mrkdwn", "text": "*Project*\ndemo" }] },
+            { "type": "actions", "elements": [{
+                "type": "button",
+                "text": { "type": "plain_text", "text": "Approve", "emoji": true },
+                "action_id": "approve",
+                "value": "id-1",
+                "style": "primary",
+            }] },
+        ]));
+    }
+
+    #[test]
+    fn test_blocks_deserialize_from_frontend_json() {
+        let block: Block = serde_json::from_value(json!({
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": "hello" },
+        })).unwrap();
+
+        assert_eq!(block, Block::Section {
+            text: Some(TextObject::mrkdwn("hello")),
+            fields: None,
+            accessory: None,
+        });
+    }
+
+    #[test]
+    fn test_unknown_blocks_and_elements_pass_through() {
+        let blocks = json!([
+            { "type": "image", "image_url": "https://example.com/a.png", "alt_text": "chart" },
+            { "type": "actions", "elements": [
+                { "type": "static_select", "action_id": "pick", "options": [] },
+                { "type": "button", "text": { "type": "plain_text", "text": "Go" }, "action_id": "go" },
+            ] },
+        ]);
+
+        let parsed: Vec<Block> = serde_json::from_value(blocks.clone()).unwrap();
+        assert!(matches!(&parsed[0], Block::Raw(raw) if raw["type"] == "image"));
+        match &parsed[1] {
+            Block::Actions { elements } => {
+                assert!(matches!(&elements[0], Element::Raw(raw) if raw["action_id"] == "pick"));
+                assert!(matches!(&elements[1], Element::Button { action_id, .. } if action_id == "go"));
+            }
+            other => panic!("expected actions, got {:?}", other),
+        }
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), blocks);
+    }
+}
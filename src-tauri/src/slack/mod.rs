@@ -1,335 +1,615 @@
-use std::process::{Child, Command};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-use serde::{Deserialize, Serialize};
-use anyhow::Result;
+pub mod api;
+pub mod blocks;
+pub mod socket_mode;
+pub mod templates;
+pub mod types;
 
-#[derive(Debug, Clone)]
-pub struct SlackService {
-    process: Arc<Mutex<Option<Child>>>,
-    port: u16,
-}
+pub use api::SlackApiClient;
+pub use blocks::{Block, Button, MessageBuilder};
+pub use types::*;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SlackConfig {
-    pub bot_token: String,
-    pub signing_secret: String,
-    pub app_token: String,
-    pub channel: String,
-    pub enabled: bool,
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use serde_json::{json, Value};
+use anyhow::Result;
+use tauri::{AppHandle, Emitter};
+
+use crate::approvals::{ApprovalQueue, RecentApproval};
+use crate::database::{approvals, slack_threads};
+
+#[derive(Default)]
+struct SlackState {
+    config: Option<SlackConfig>,
+    initialized: bool,
+    running: bool,
+    connected: bool,
+    approvals: ApprovalQueue<PendingApproval>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SlackApprovalRequest {
-    pub recommendation: serde_json::Value,
-    pub session_id: String,
-    pub server_id: String,
-    pub project_name: Option<String>,
+/// Native Slack integration using the Web API for posting and Socket Mode for
+/// receiving button clicks and thread replies.
+#[derive(Clone)]
+pub struct SlackService {
+    state: Arc<RwLock<SlackState>>,
+    api: SlackApiClient,
+    socket_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+    /// Serialises looking up and creating session threads
+    thread_lock: Arc<Mutex<()>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct SlackMessage {
-    pub text: String,
-    pub blocks: Option<Vec<serde_json::Value>>,
+impl Default for SlackService {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl SlackService {
-    pub fn new(port: u16) -> Self {
+    pub fn new() -> Self {
         Self {
-            process: Arc::new(Mutex::new(None)),
-            port,
+            state: Arc::new(RwLock::new(SlackState::default())),
+            api: SlackApiClient::new(),
+            socket_task: Arc::new(Mutex::new(None)),
+            app_handle: Arc::new(RwLock::new(None)),
+            thread_lock: Arc::new(Mutex::new(())),
         }
     }
 
-    pub async fn start(&self, _app_handle: &tauri::AppHandle) -> Result<()> {
-        let mut process_guard = self.process.lock().await;
-
-        // Kill existing process if any
-        if let Some(mut child) = process_guard.take() {
-            let _ = child.kill();
-        }
+    pub async fn start(&self, app_handle: &AppHandle) -> Result<()> {
+        *self.app_handle.write().await = Some(app_handle.clone());
 
-        // Port cleanup: kill any existing process using this port
-        let port = self.port;
-        tokio::spawn(async move {
-            let _ = tokio::process::Command::new("sh")
-                .arg("-c")
-                .arg(format!("lsof -ti:{} | xargs kill -9 2>/dev/null || true", port))
-                .output()
-                .await;
-            println!("[Slack] Port cleanup completed for {}", port);
-        });
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-        // Get the path to the slack-service.ts script
-        // Working directory is typically src-tauri, so use relative path from there
-        let resource_path = std::path::PathBuf::from("scripts/slack-service.ts");
-
-        // Check if the script exists
-        if !resource_path.exists() {
-            return Err(anyhow::anyhow!(
-                "Slack service script not found at {:?}. Current dir: {:?}",
-                resource_path.canonicalize().unwrap_or_else(|_| resource_path.clone()),
-                std::env::current_dir().unwrap_or_default()
-            ));
-        }
-
-        // Start the Node.js Slack service using tsx (TypeScript runner)
-        // Use inherit for stdio so we can see output in the terminal
-        let mut cmd = Command::new("npx");
-        cmd.arg("tsx")
-            .arg(&resource_path)
-            .env("SLACK_SERVICE_PORT", self.port.to_string())
-            .stdout(std::process::Stdio::inherit())
-            .stderr(std::process::Stdio::inherit());
-
-        let mut child = cmd.spawn()
-            .map_err(|e| anyhow::anyhow!("Failed to spawn Slack service: {} (script path: {:?})", e, resource_path))?;
-
-        println!("[Slack] Service process spawned with PID: {:?}", child.id());
-
-        // Check if process started successfully
-        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        match child.try_wait() {
-            Ok(Some(status)) => {
-                println!("[Slack] Service failed to start. Exit status: {:?}", status);
-                return Err(anyhow::anyhow!("Slack service exited immediately with status {:?}", status));
-            }
-            Ok(None) => {
-                println!("[Slack] Service process is running after startup check");
-            }
-            Err(e) => {
-                println!("[Slack] Error checking process: {}", e);
-            }
-        }
+        let initialized = {
+            let mut state = self.state.write().await;
+            state.running = true;
+            state.initialized
+        };
 
-        *process_guard = Some(child);
-
-        println!("[Slack] Slack service started on port {}", self.port);
-
-        // Wait longer for TypeScript service to fully start (tsx needs time to compile)
-        println!("[Slack] Waiting for service to initialize...");
-        tokio::time::sleep(tokio::time::Duration::from_millis(3000)).await;
-
-        // Final check that process is still running
-        let mut final_guard = self.process.lock().await;
-        if let Some(ref mut child) = *final_guard {
-            match child.try_wait() {
-                Ok(Some(status)) => {
-                    println!("[Slack] WARNING: Service exited during startup with status: {:?}", status);
-                    *final_guard = None;
-                    return Err(anyhow::anyhow!("Slack service crashed during startup with status {:?}", status));
-                }
-                Ok(None) => {
-                    println!("[Slack] Process check: Service is still running");
-                }
-                Err(e) => {
-                    println!("[Slack] Error in final check: {}", e);
-                }
-            }
-        }
-        drop(final_guard);
-
-        // Try to verify the HTTP server is responding
-        let url = format!("http://localhost:{}/status", self.port);
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_millis(2000))
-            .build()
-            .unwrap();
-
-        println!("[Slack] Attempting to verify service at {}", url);
-        match client.get(&url).send().await {
-            Ok(response) => {
-                println!("[Slack] ✓ Service is responding! Status code: {}", response.status());
-            }
-            Err(e) => {
-                println!("[Slack] ⚠ Service process is running but HTTP server not responding yet: {}", e);
-                println!("[Slack] This may be normal - the service might need more time to start");
-            }
+        // Reconnect Socket Mode if we were initialized before a stop
+        if initialized {
+            self.spawn_socket_loop().await;
         }
 
+        println!("[Slack] Slack service started");
         Ok(())
     }
 
     pub async fn stop(&self) -> Result<()> {
-        let mut process_guard = self.process.lock().await;
-
-        if let Some(mut child) = process_guard.take() {
-            child.kill()
-                .map_err(|e| anyhow::anyhow!("Failed to kill Slack service: {}", e))?;
+        if let Some(task) = self.socket_task.lock().await.take() {
+            task.abort();
         }
 
+        let mut state = self.state.write().await;
+        state.running = false;
+        state.connected = false;
+
+        println!("[Slack] Slack service stopped");
         Ok(())
     }
 
     pub async fn initialize(&self, config: SlackConfig) -> Result<()> {
-        let url = format!("http://localhost:{}/initialize", self.port);
-        let client = reqwest::Client::new();
+        if !config.enabled {
+            return Err(anyhow::anyhow!("Slack is disabled"));
+        }
 
-        let response = client
-            .post(&url)
-            .json(&config)
-            .send()
-            .await
+        if config.bot_token.is_empty() || config.app_token.is_empty() {
+            return Err(anyhow::anyhow!("Slack bot token and app token are required"));
+        }
+
+        let bot_user = self.api.auth_test(&config.bot_token).await
             .map_err(|e| anyhow::anyhow!("Failed to initialize Slack: {}", e))?;
+        println!("[Slack] Authenticated as bot user {}", bot_user);
 
-        if !response.status().is_success() {
-            let error = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow::anyhow!("Slack initialization failed: {}", error));
+        {
+            let mut state = self.state.write().await;
+            state.config = Some(config);
+            state.initialized = true;
+            state.running = true;
         }
 
+        self.spawn_socket_loop().await;
+
+        println!("[Slack] Slack integration initialized");
         Ok(())
     }
 
-    pub async fn send_approval_request(&self, request: SlackApprovalRequest) -> Result<()> {
-        let url = format!("http://localhost:{}/send-approval", self.port);
-        let client = reqwest::Client::new();
+    async fn spawn_socket_loop(&self) {
+        let mut task_guard = self.socket_task.lock().await;
+        if let Some(task) = task_guard.take() {
+            task.abort();
+        }
 
-        let response = client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to send approval request: {}", e))?;
+        let service = self.clone();
+        *task_guard = Some(tokio::spawn(async move {
+            socket_mode::run(service).await;
+        }));
+    }
+
+    async fn app_token(&self) -> Option<String> {
+        let state = self.state.read().await;
+        state.config.as_ref().map(|c| c.app_token.clone())
+    }
+
+    async fn set_connected(&self, connected: bool) {
+        self.state.write().await.connected = connected;
+    }
 
-        if !response.status().is_success() {
-            let error = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow::anyhow!("Failed to send approval: {}", error));
+    async fn active_config(&self) -> Result<SlackConfig> {
+        let state = self.state.read().await;
+        match (&state.config, state.initialized) {
+            (Some(config), true) => Ok(config.clone()),
+            _ => Err(anyhow::anyhow!("Slack not initialized")),
         }
+    }
+
+    pub async fn send_approval_request(&self, request: SlackApprovalRequest) -> Result<()> {
+        let config = self.active_config().await?;
+
+        let action_id = format!(
+            "approval_{}_{}",
+            chrono::Utc::now().timestamp_millis(),
+            uuid::Uuid::new_v4().simple().to_string().chars().take(9).collect::<String>()
+        );
+
+        let message = templates::approval_request(&request, &action_id);
+        let message_ts = self.post(&config, &message).await
+            .map_err(|e| anyhow::anyhow!("Failed to send approval: {}", e))?;
+
+        println!("[Slack] Sent approval request {} with message ts: {}", action_id, message_ts);
+
+        let record = approvals::ApprovalRecord {
+            id: action_id.clone(),
+            channel: "slack".to_string(),
+            session_id: request.session_id.clone(),
+            server_id: request.server_id.clone(),
+            project_name: request.project_name.clone(),
+            recommendation: request.recommendation.clone(),
+            tool_use: request.tool_use.clone(),
+            requested_at: chrono::Utc::now().timestamp_millis(),
+            decision: None,
+            decided_by: None,
+            decided_at: None,
+            latency_ms: None,
+        };
+        approvals::audit(&self.app_handle, "Slack", |conn| approvals::record_request(conn, &record)).await;
+
+        self.state.write().await.approvals.add_pending(action_id, PendingApproval {
+            recommendation: request.recommendation,
+            session_id: request.session_id,
+            server_id: request.server_id,
+            project_name: request.project_name,
+            channel: config.channel,
+            message_ts,
+            message,
+        });
 
         Ok(())
     }
 
     pub async fn send_message(&self, message: SlackMessage) -> Result<()> {
-        let url = format!("http://localhost:{}/send-message", self.port);
-        let client = reqwest::Client::new();
+        let config = self.active_config().await?;
 
-        let response = client
-            .post(&url)
-            .json(&message)
-            .send()
-            .await
+        self.post(&config, &message).await
             .map_err(|e| anyhow::anyhow!("Failed to send message: {}", e))?;
 
-        if !response.status().is_success() {
-            let error = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow::anyhow!("Failed to send message: {}", error));
-        }
+        Ok(())
+    }
 
+    /// Post a completion into the session's thread, also broadcast to the
+    /// channel, followed by its screenshot if it has one
+    pub async fn send_task_completion(&self, task: SlackTaskCompletion) -> Result<()> {
+        let config = self.active_config().await?;
+        let thread = self.post_update(&config, &SlackSessionUpdate {
+            session_id: task.session_id.clone(),
+            project_name: task.project_name.clone(),
+            update: SessionUpdate::Completion {
+                summary: task.summary,
+                success: task.success,
+                duration_ms: task.duration_ms,
+            },
+        }).await?;
+
+        let Some(path) = task.screenshot_path else { return Ok(()) };
+        let bytes = tokio::fs::read(&path).await
+            .map_err(|e| anyhow::anyhow!("Failed to read screenshot {}: {}", path, e))?;
+        let (channel, thread_ts) = match &thread {
+            Some(thread) => (thread.channel.as_str(), Some(thread.thread_ts.as_str())),
+            None => (config.channel.as_str(), None),
+        };
+        self.api
+            .upload_file(&config.bot_token, channel, thread_ts, "screenshot.png", bytes, "Screenshot")
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to attach screenshot: {}", e))
+    }
+
+    /// Post an update into the session's thread, creating the thread on first use
+    pub async fn post_session_update(&self, update: SlackSessionUpdate) -> Result<()> {
+        let config = self.active_config().await?;
+        self.post_update(&config, &update).await?;
         Ok(())
     }
 
-    pub async fn shutdown(&self) -> Result<()> {
-        let url = format!("http://localhost:{}/shutdown", self.port);
-        let client = reqwest::Client::new();
+    /// Post an update into its session's thread, or straight to the channel
+    /// when there's no database to keep threads in. Returns the thread used.
+    async fn post_update(&self, config: &SlackConfig, update: &SlackSessionUpdate) -> Result<Option<slack_threads::SlackThread>> {
+        let thread = self.session_thread(config, &update.session_id, update.project_name.as_deref()).await?;
+
+        let message = templates::session_update(&update.session_id, update.project_name.as_deref(), &update.update);
+        let broadcast = matches!(update.update, SessionUpdate::Completion { .. });
+
+        let posted = match &thread {
+            Some(thread) => self.api
+                .post_thread_reply(
+                    &config.bot_token,
+                    &thread.channel,
+                    &thread.thread_ts,
+                    &message.text,
+                    message.blocks.as_deref(),
+                    broadcast,
+                )
+                .await,
+            None => self.post(config, &message).await,
+        };
+        posted.map_err(|e| anyhow::anyhow!("Failed to post session update: {}", e))?;
+
+        Ok(thread)
+    }
 
-        let _ = client
-            .post(&url)
-            .send()
-            .await;
+    /// The session's thread in the configured channel, posting its root
+    /// message on first use. `None` when the database can't be read.
+    async fn session_thread(
+        &self,
+        config: &SlackConfig,
+        session_id: &str,
+        project_name: Option<&str>,
+    ) -> Result<Option<slack_threads::SlackThread>> {
+        // Held until the new thread is saved so concurrent updates for a
+        // session don't each start a thread
+        let _creating = self.thread_lock.lock().await;
+
+        let existing = match self.with_db(|conn| slack_threads::get_thread(conn, session_id)).await {
+            Ok(existing) => existing,
+            Err(e) => {
+                println!("[Slack] Can't look up thread for session {}, posting unthreaded: {}", session_id, e);
+                return Ok(None);
+            }
+        };
+        if let Some(thread) = existing.filter(|t| t.channel == config.channel) {
+            return Ok(Some(thread));
+        }
+
+        let root = templates::session_thread_root(session_id, project_name);
+        let thread_ts = self.post(config, &root).await
+            .map_err(|e| anyhow::anyhow!("Failed to create session thread: {}", e))?;
+
+        let thread = slack_threads::SlackThread {
+            session_id: session_id.to_string(),
+            channel: config.channel.clone(),
+            thread_ts,
+            project_name: project_name.map(|p| p.to_string()),
+            created_at: chrono::Utc::now().to_rfc3339(),
+        };
+        // The root is already posted, so use the thread even if it can't be saved
+        if let Err(e) = self.with_db(|conn| slack_threads::save_thread(conn, &thread)).await {
+            println!("[Slack] Failed to save thread for session {}: {}", session_id, e);
+        }
+
+        println!("[Slack] Created thread {} for session {}", thread.thread_ts, session_id);
+        Ok(Some(thread))
+    }
+
+    pub async fn send_error_alert(&self, alert: SlackErrorAlert) -> Result<()> {
+        self.send_message(templates::error_alert(&alert)).await
+    }
 
+    async fn post(&self, config: &SlackConfig, message: &SlackMessage) -> Result<String> {
+        self.api
+            .post_message(&config.bot_token, &config.channel, &message.text, message.blocks.as_deref(), None)
+            .await
+    }
+
+    pub async fn shutdown(&self) -> Result<()> {
         self.stop().await?;
+
+        let mut state = self.state.write().await;
+        state.config = None;
+        state.initialized = false;
+        state.approvals.clear_pending();
         Ok(())
     }
 
-    pub async fn get_approvals(&self, since: u64) -> Result<serde_json::Value> {
-        let url = format!("http://localhost:{}/approvals?since={}", self.port, since);
-        let client = reqwest::Client::new();
+    pub async fn get_approvals(&self, since: u64) -> Result<Value> {
+        let state = self.state.read().await;
+        let approvals = state.approvals.decided_since(since);
 
-        let response = client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to get approvals: {}", e))?;
+        Ok(json!({ "approvals": approvals }))
+    }
 
-        let approvals = response
-            .json()
-            .await
-            .map_err(|e| anyhow::anyhow!("Failed to parse approvals: {}", e))?;
+    pub async fn is_running(&self) -> bool {
+        self.state.read().await.running
+    }
 
-        Ok(approvals)
+    pub async fn status(&self) -> Result<Value> {
+        let state = self.state.read().await;
+
+        Ok(json!({
+            "initialized": state.initialized,
+            "service_running": state.running,
+            "socket_connected": state.connected,
+            "connected_channels": if state.initialized { 1 } else { 0 },
+            "pendingApprovals": state.approvals.pending_count(),
+            "config": state.config.as_ref().map(|c| json!({
+                "channel": c.channel,
+                "enabled": c.enabled,
+            })),
+        }))
     }
 
-    pub async fn is_process_running(&self) -> bool {
-        let mut process_guard = self.process.lock().await;
-
-        if let Some(child) = process_guard.as_mut() {
-            // Try to check if the process is still alive
-            match child.try_wait() {
-                Ok(Some(status)) => {
-                    // Process has exited
-                    println!("[Slack] Process has exited with status: {:?}", status);
-                    *process_guard = None;
-                    false
-                }
-                Ok(None) => {
-                    // Process is still running
-                    true
-                }
-                Err(e) => {
-                    println!("[Slack] Error checking process status: {}", e);
-                    // Assume not running if we can't check
-                    *process_guard = None;
-                    false
-                }
-            }
-        } else {
-            false
+    async fn with_db<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<R>,
+    {
+        crate::database::with_app_db(&self.app_handle, "Slack service", f).await
+    }
+
+    async fn record_decision(&self, approval: RecentApproval) {
+        self.state.write().await.approvals.record(approval.clone());
+
+        if let Some(handle) = self.app_handle.read().await.as_ref() {
+            let _ = handle.emit("slack-approval", approval);
         }
     }
 
-    pub async fn status(&self) -> Result<serde_json::Value> {
-        println!("[Slack Health Check] Starting status check...");
+    /// Handle `block_actions` payloads from the Approve/Deny buttons
+    async fn handle_interactive(&self, payload: &Value) {
+        if payload["type"].as_str() != Some("block_actions") {
+            return;
+        }
 
-        // First check if process is running
-        let process_running = self.is_process_running().await;
-        println!("[Slack Health Check] Process in memory: {}", process_running);
+        let Ok(config) = self.active_config().await else { return };
+        let user_id = payload["user"]["id"].as_str().unwrap_or("unknown");
+        let channel = payload["channel"]["id"].as_str().unwrap_or(&config.channel).to_string();
+        let message_ts = payload["message"]["ts"].as_str().unwrap_or_default().to_string();
+
+        for action in payload["actions"].as_array().into_iter().flatten() {
+            let approved = match action["action_id"].as_str() {
+                Some("approve_recommendation") => true,
+                Some("decline_recommendation") => false,
+                _ => continue,
+            };
+            let Some(action_id) = action["value"].as_str() else { continue };
+
+            let pending = self.state.write().await.approvals.take_pending(action_id);
+            let Some(pending) = pending else {
+                let _ = self.api.post_message(
+                    &config.bot_token,
+                    &channel,
+                    "❌ This approval request has expired or was already processed.",
+                    None,
+                    Some(&message_ts),
+                ).await;
+                continue;
+            };
+
+            // Replace the action buttons with who decided and when
+            let resolved = templates::approval_resolved(&pending.message, approved, user_id);
+            if let Err(e) = self.api.update_message(
+                &config.bot_token,
+                &channel,
+                &message_ts,
+                &resolved.text,
+                resolved.blocks.as_deref().unwrap_or_default(),
+            ).await {
+                println!("[Slack] Failed to update approval message: {}", e);
+            }
+
+            println!("[Slack] Approval {} {} by {}", action_id, if approved { "approved" } else { "denied" }, user_id);
+
+            let decided_at = chrono::Utc::now().timestamp_millis();
+            approvals::audit(&self.app_handle, "Slack", |conn| approvals::record_decision(conn, action_id, approved, user_id, decided_at).map(|_| ())).await;
 
-        if !process_running {
-            println!("[Slack Health Check] No process found, returning offline status");
-            return Ok(serde_json::json!({
-                "initialized": false,
-                "service_running": false,
-                "port": self.port
-            }));
+            self.record_decision(RecentApproval {
+                action_id: action_id.to_string(),
+                approved,
+                session_id: pending.session_id,
+                server_id: pending.server_id,
+                project_name: pending.project_name,
+                recommendation: pending.recommendation,
+                timestamp: chrono::Utc::now().timestamp_millis() as u64,
+            }).await;
         }
+    }
 
-        // Try to get status from the service
-        let url = format!("http://localhost:{}/status", self.port);
-        println!("[Slack Health Check] Attempting HTTP request to {}", url);
-
-        let client = reqwest::Client::builder()
-            .timeout(std::time::Duration::from_millis(1000))
-            .build()
-            .unwrap();
-
-        match client.get(&url).send().await {
-            Ok(response) => {
-                println!("[Slack Health Check] HTTP request succeeded, status code: {}", response.status());
-                match response.json().await {
-                    Ok(status) => {
-                        println!("[Slack Health Check] Successfully parsed JSON status: {:?}", status);
-                        Ok(status)
-                    },
-                    Err(e) => {
-                        println!("[Slack Health Check] Failed to parse JSON: {}", e);
-                        Ok(serde_json::json!({
-                            "initialized": false,
-                            "service_running": true,
-                            "port": self.port
-                        }))
-                    }
-                }
-            },
-            Err(e) => {
-                println!("[Slack Health Check] HTTP request failed: {}", e);
-                // Service not responding, but process exists
-                Ok(serde_json::json!({
-                    "initialized": false,
-                    "service_running": false,
-                    "port": self.port
-                }))
+    /// Treat human replies in an approval thread as an approved instruction.
+    /// The request itself stays pending, so its buttons still decide it.
+    async fn handle_event(&self, event: &Value) {
+        if event["type"].as_str() != Some("message") {
+            return;
+        }
+        if event.get("bot_id").is_some() || event["subtype"].as_str() == Some("bot_message") {
+            return;
+        }
+        let (Some(thread_ts), Some(text)) = (event["thread_ts"].as_str(), event["text"].as_str()) else {
+            return;
+        };
+
+        let matching = {
+            let state = self.state.read().await;
+            state.approvals
+                .pending()
+                .find(|(_, p)| p.message_ts == thread_ts)
+                .map(|(id, p)| (id.clone(), p.clone()))
+        };
+        let Some((action_id, pending)) = matching else { return };
+        let Ok(config) = self.active_config().await else { return };
+
+        println!("[Slack] Thread reply detected on approval {}", action_id);
+
+        let now = chrono::Utc::now().timestamp_millis() as u64;
+        self.record_decision(RecentApproval {
+            action_id: format!("thread_{}_{}", action_id, now),
+            approved: true,
+            session_id: pending.session_id,
+            server_id: pending.server_id,
+            project_name: pending.project_name,
+            recommendation: json!({
+                "recommendation": text,
+                "confidence": 1.0,
+            }),
+            timestamp: now,
+        }).await;
+
+        let channel = event["channel"].as_str().unwrap_or(&pending.channel);
+        if let Some(ts) = event["ts"].as_str() {
+            if let Err(e) = self.api.add_reaction(&config.bot_token, channel, ts, "white_check_mark").await {
+                println!("[Slack] Failed to add reaction: {}", e);
+            }
+        }
+        if let Err(e) = self.api.post_message(
+            &config.bot_token,
+            channel,
+            "✅ Got it! Sending this to the agent...",
+            None,
+            Some(thread_ts),
+        ).await {
+            println!("[Slack] Failed to post thread confirmation: {}", e);
+        }
+    }
+
+    async fn slash_command_response(&self, payload: &Value) -> Value {
+        let state = self.state.read().await;
+        let pending = state.approvals.pending_count();
+
+        match payload["command"].as_str() {
+            Some("/sensei-status") => {
+                let message = MessageBuilder::new(format!("SensAI Status: {} pending approvals", pending))
+                    .section(format!(
+                        "*SensAI Status*\n• Pending Approvals: {}\n• Integration: {}",
+                        pending,
+                        if state.initialized { "✅ Active" } else { "❌ Inactive" }
+                    ))
+                    .build();
+                serde_json::to_value(message).unwrap_or_default()
             }
+            _ => json!({ "text": "Unknown command" }),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn service(server: &MockServer) -> SlackService {
+        let service = SlackService {
+            api: SlackApiClient::with_base_url(&server.uri()),
+            ..SlackService::new()
+        };
+        let mut state = service.state.write().await;
+        state.config = Some(SlackConfig {
+            bot_token: "xoxb-test".to_string(),
+            signing_secret: String::new(),
+            app_token: String::new(),
+            channel: "C123".to_string(),
+            enabled: true,
+        });
+        state.initialized = true;
+        drop(state);
+        service
+    }
+
+    #[tokio::test]
+    async fn test_completion_posts_unthreaded_without_database() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat.postMessage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "ts": "1.2" })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // No app handle, so there's no database to keep the thread in
+        let service = service(&server).await;
+        service.send_task_completion(SlackTaskCompletion {
+            session_id: "session-1".to_string(),
+            project_name: Some("demo".to_string()),
+            summary: "Done".to_string(),
+            success: true,
+            duration_ms: Some(1500),
+            screenshot_path: None,
+        }).await.unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        let body: Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["channel"], "C123");
+        assert!(body.get("thread_ts").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_update_fails_when_slack_rejects_it() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/chat.postMessage"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": false, "error": "channel_not_found" })))
+            .mount(&server)
+            .await;
+
+        let service = service(&server).await;
+        let error = service.post_session_update(SlackSessionUpdate {
+            session_id: "session-1".to_string(),
+            project_name: None,
+            update: SessionUpdate::Completion { summary: "Done".to_string(), success: true, duration_ms: None },
+        }).await.unwrap_err();
+
+        assert!(error.to_string().contains("channel_not_found"));
+    }
+
+    #[tokio::test]
+    async fn test_thread_reply_leaves_the_request_to_its_buttons() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "ok": true, "ts": "1.3" })))
+            .mount(&server)
+            .await;
+
+        let service = service(&server).await;
+        service.state.write().await.approvals.add_pending("a1".to_string(), PendingApproval {
+            recommendation: json!({ "recommendation": "Restart the server" }),
+            session_id: "session-1".to_string(),
+            server_id: "server-1".to_string(),
+            project_name: None,
+            channel: "C123".to_string(),
+            message_ts: "1.2".to_string(),
+            message: SlackMessage { text: "Approve?".to_string(), blocks: None },
+        });
+
+        service.handle_event(&json!({
+            "type": "message",
+            "user": "U1",
+            "channel": "C123",
+            "ts": "1.4",
+            "thread_ts": "1.2",
+            "text": "Only restart the API",
+        })).await;
+
+        let recent: Vec<_> = service.state.read().await.approvals.decided_since(0).into_iter().cloned().collect();
+        assert_eq!(recent.len(), 1);
+        assert!(recent[0].action_id.starts_with("thread_a1_"));
+        assert_eq!(recent[0].recommendation["recommendation"], "Only restart the API");
+
+        service.handle_interactive(&json!({
+            "type": "block_actions",
+            "user": { "id": "U2" },
+            "channel": { "id": "C123" },
+            "message": { "ts": "1.2" },
+            "actions": [{ "action_id": "decline_recommendation", "value": "a1" }],
+        })).await;
+
+        let state = service.state.read().await;
+        assert_eq!(state.approvals.pending_count(), 0);
+        let declined = state.approvals.decided_since(0).into_iter().find(|a| a.action_id == "a1").unwrap();
+        assert!(!declined.approved);
+    }
+}
@@ -0,0 +1,95 @@
+use super::types::SocketEnvelope;
+use super::SlackService;
+use anyhow::Result;
+use futures::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+const MAX_BACKOFF_SECS: u64 = 30;
+
+/// Keep a Socket Mode connection open until the service is shut down,
+/// reconnecting with exponential backoff whenever Slack drops it.
+pub(super) async fn run(service: SlackService) {
+    let mut backoff = 1;
+
+    loop {
+        let app_token = match service.app_token().await {
+            Some(token) => token,
+            None => {
+                println!("[Slack] No app token configured, stopping socket loop");
+                break;
+            }
+        };
+
+        match service.api.open_connection(&app_token).await {
+            Ok(url) => {
+                match connect_async(url.as_str()).await {
+                    Ok((stream, _)) => {
+                        println!("[Slack] Socket Mode connected");
+                        service.set_connected(true).await;
+                        backoff = 1;
+
+                        if let Err(e) = handle_connection(&service, stream).await {
+                            println!("[Slack] Socket Mode connection error: {}", e);
+                        }
+
+                        service.set_connected(false).await;
+                        println!("[Slack] Socket Mode disconnected");
+                    }
+                    Err(e) => println!("[Slack] Failed to open Socket Mode WebSocket: {}", e),
+                }
+            }
+            Err(e) => println!("[Slack] Failed to request Socket Mode URL: {}", e),
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_secs(backoff)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+async fn handle_connection<S>(service: &SlackService, stream: tokio_tungstenite::WebSocketStream<S>) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut write, mut read) = stream.split();
+
+    while let Some(message) = read.next().await {
+        match message? {
+            Message::Text(text) => {
+                let envelope: SocketEnvelope = match serde_json::from_str(&text) {
+                    Ok(envelope) => envelope,
+                    Err(e) => {
+                        println!("[Slack] Ignoring unparseable envelope: {}", e);
+                        continue;
+                    }
+                };
+
+                // Every envelope with an id must be acknowledged within 3 seconds.
+                // Slash commands carry their response in the acknowledgement.
+                if let Some(envelope_id) = &envelope.envelope_id {
+                    let mut ack = json!({ "envelope_id": envelope_id });
+                    if envelope.envelope_type == "slash_commands" {
+                        ack["payload"] = service.slash_command_response(&envelope.payload).await;
+                    }
+                    write.send(Message::Text(ack.to_string())).await?;
+                }
+
+                match envelope.envelope_type.as_str() {
+                    "hello" => println!("[Slack] Received hello from Socket Mode"),
+                    "disconnect" => {
+                        println!("[Slack] Slack requested reconnect");
+                        return Ok(());
+                    }
+                    "interactive" => service.handle_interactive(&envelope.payload).await,
+                    "events_api" => service.handle_event(&envelope.payload["event"]).await,
+                    _ => {}
+                }
+            }
+            Message::Ping(payload) => write.send(Message::Pong(payload)).await?,
+            Message::Close(_) => return Ok(()),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
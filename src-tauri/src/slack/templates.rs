@@ -0,0 +1,248 @@
+use super::blocks::{Block, Button, MessageBuilder};
+use super::types::{SessionUpdate, SlackApprovalRequest, SlackErrorAlert, SlackMessage, SlackTaskCompletion};
+
+pub const APPROVE_ACTION_ID: &str = "approve_recommendation";
+pub const DECLINE_ACTION_ID: &str = "decline_recommendation";
+
+const MAX_RECOMMENDATION_CHARS: usize = 1000;
+// Section text is capped at 3000 characters by Slack
+const MAX_DIFF_CHARS: usize = 2800;
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() > max_chars {
+        format!("{}...", text.chars().take(max_chars).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+fn now() -> String {
+    chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Approval request with Approve/Deny buttons whose value is `action_id`.
+/// Analysis failures are reported without buttons.
+pub fn approval_request(request: &SlackApprovalRequest, action_id: &str) -> SlackMessage {
+    let recommendation_text = request.recommendation["recommendation"].as_str().unwrap_or_default();
+    let command = request.recommendation["command"].as_str();
+    let confidence = request.recommendation["confidence"].as_f64().unwrap_or(0.0);
+    let project_name = request.project_name.as_deref().unwrap_or("Unknown");
+
+    if confidence == 0.0 && recommendation_text.starts_with("Analysis error:") {
+        return MessageBuilder::new("⚠️ SensAI analysis failed")
+            .header("⚠️ SensAI Analysis Failed")
+            .section(format!(
+                "*Project:* {}\n*Session:* `{}`\n*Time:* {}",
+                project_name, request.session_id, now()
+            ))
+            .section(format!("*Error:*\n```{}```", recommendation_text))
+            .context("_SensAI encountered an error while analyzing the agent response. Please check the configuration and try again._")
+            .build();
+    }
+
+    let confidence_percent = (confidence * 100.0).round() as u32;
+    let confidence_emoji = if confidence > 0.7 { "🟢" } else if confidence > 0.4 { "🟡" } else { "🔴" };
+
+    let mut builder = MessageBuilder::new(format!("🧠 SensAI approval required ({}% confidence)", confidence_percent))
+        .header("🧠 SensAI Approval Required")
+        .section(format!(
+            "*Project:* {}\n*Session:* `{}`\n*Confidence:* {} {}%\n*Time:* {}",
+            project_name, request.session_id, confidence_emoji, confidence_percent, now()
+        ))
+        .section(format!(
+            "*Recommendation:*\n{}",
+            truncate(recommendation_text, MAX_RECOMMENDATION_CHARS)
+        ));
+
+    if let Some(command) = command {
+        builder = builder.section(format!("*Suggested Command:*\n```{}```", command));
+    }
+
+    builder
+        .actions([
+            Button::new("✅ Approve", APPROVE_ACTION_ID).value(action_id).primary(),
+            Button::new("❌ Deny", DECLINE_ACTION_ID).value(action_id).danger(),
+        ])
+        .build()
+}
+
+/// Replace the action buttons of a posted approval request with the decision
+pub fn approval_resolved(original: &SlackMessage, approved: bool, user_id: &str) -> SlackMessage {
+    let (status_emoji, status_text) = if approved { ("✅", "Approved") } else { ("❌", "Denied") };
+    let now = chrono::Utc::now();
+
+    let blocks = original.blocks.iter()
+        .flatten()
+        .filter(|block| !matches!(block, Block::Actions { .. }))
+        .cloned()
+        .fold(
+            MessageBuilder::new(format!("{} Recommendation {}", status_emoji, status_text.to_lowercase())),
+            MessageBuilder::block,
+        );
+
+    blocks
+        .context(format!(
+            "_{} {} by <@{}> at <!date^{}^{{date_short}} {{time}}|{}>_",
+            status_emoji, status_text, user_id, now.timestamp(), now.to_rfc3339()
+        ))
+        .build()
+}
+
+pub fn task_completion(task: &SlackTaskCompletion) -> SlackMessage {
+    let (emoji, status) = if task.success { ("✅", "Completed") } else { ("❌", "Failed") };
+    let project_name = task.project_name.as_deref().unwrap_or("Unknown");
+
+    let mut fields = vec![
+        ("Project", project_name.to_string()),
+        ("Session", format!("`{}`", task.session_id)),
+        ("Status", format!("{} {}", emoji, status)),
+    ];
+    if let Some(duration_ms) = task.duration_ms {
+        fields.push(("Duration", format_duration(duration_ms)));
+    }
+
+    MessageBuilder::new(format!("{} Task {} in {}", emoji, status.to_lowercase(), project_name))
+        .header(format!("{} Task {}", emoji, status))
+        .fields(fields)
+        .section(truncate(&task.summary, MAX_RECOMMENDATION_CHARS))
+        .context(format!("_{}_", now()))
+        .build()
+}
+
+/// Root message of a session thread
+pub fn session_thread_root(session_id: &str, project_name: Option<&str>) -> SlackMessage {
+    let project_name = project_name.unwrap_or("Unknown");
+
+    MessageBuilder::new(format!("🥷 Session started in {}", project_name))
+        .header("🥷 Agent Session")
+        .fields([
+            ("Project", project_name.to_string()),
+            ("Session", format!("`{}`", session_id)),
+        ])
+        .context(format!("_Started {} • updates follow in this thread_", now()))
+        .build()
+}
+
+/// Message for one update inside a session thread
+pub fn session_update(session_id: &str, project_name: Option<&str>, update: &SessionUpdate) -> SlackMessage {
+    match update {
+        SessionUpdate::Progress { text } => MessageBuilder::new(truncate(text, 150))
+            .section(truncate(text, MAX_DIFF_CHARS))
+            .build(),
+        SessionUpdate::Diff { file, diff } => {
+            let title = match file {
+                Some(file) => format!("📝 Changes in `{}`", file),
+                None => "📝 Changes".to_string(),
+            };
+            MessageBuilder::new(title.clone())
+                .section(format!("*{}*\n```{}```", title, truncate(diff, MAX_DIFF_CHARS)))
+                .build()
+        }
+        SessionUpdate::Completion { summary, success, duration_ms } => task_completion(&SlackTaskCompletion {
+            session_id: session_id.to_string(),
+            project_name: project_name.map(|p| p.to_string()),
+            summary: summary.clone(),
+            success: *success,
+            duration_ms: *duration_ms,
+            screenshot_path: None,
+        }),
+    }
+}
+
+pub fn error_alert(alert: &SlackErrorAlert) -> SlackMessage {
+    let mut fields = Vec::new();
+    if let Some(project_name) = &alert.project_name {
+        fields.push(("Project", project_name.clone()));
+    }
+    if let Some(session_id) = &alert.session_id {
+        fields.push(("Session", format!("`{}`", session_id)));
+    }
+
+    let mut builder = MessageBuilder::new(format!("🚨 {}", alert.title))
+        .header(format!("🚨 {}", alert.title));
+    if !fields.is_empty() {
+        builder = builder.fields(fields);
+    }
+
+    builder
+        .section(format!("```{}```", truncate(&alert.error, MAX_RECOMMENDATION_CHARS)))
+        .context(format!("_{}_", now()))
+        .build()
+}
+
+fn format_duration(duration_ms: u64) -> String {
+    let secs = duration_ms / 1000;
+    if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request(recommendation: serde_json::Value) -> SlackApprovalRequest {
+        SlackApprovalRequest {
+            recommendation,
+            session_id: "session-1".to_string(),
+            server_id: "server-1".to_string(),
+            project_name: Some("demo".to_string()),
+            tool_use: None,
+        }
+    }
+
+    #[test]
+    fn test_approval_request_has_buttons_with_action_id() {
+        let message = approval_request(&request(json!({
+            "recommendation": "Run the tests",
+            "command": "npm test",
+            "confidence": 0.9,
+        })), "approval_1");
+
+        let blocks = message.blocks.unwrap();
+        assert_eq!(blocks.len(), 5);
+        match blocks.last().unwrap() {
+            Block::Actions { elements } => assert_eq!(elements.len(), 2),
+            other => panic!("expected actions block, got {:?}", other),
+        }
+        assert!(message.text.contains("90%"));
+    }
+
+    #[test]
+    fn test_analysis_error_has_no_buttons() {
+        let message = approval_request(&request(json!({
+            "recommendation": "Analysis error: timeout",
+            "confidence": 0.0,
+        })), "approval_1");
+
+        assert!(!message.blocks.unwrap().iter().any(|b| matches!(b, Block::Actions { .. })));
+    }
+
+    #[test]
+    fn test_approval_resolved_replaces_actions() {
+        let original = approval_request(&request(json!({
+            "recommendation": "Run the tests",
+            "confidence": 0.5,
+        })), "approval_1");
+
+        let resolved = approval_resolved(&original, true, "U123");
+        let blocks = resolved.blocks.unwrap();
+        assert!(!blocks.iter().any(|b| matches!(b, Block::Actions { .. })));
+        match blocks.last().unwrap() {
+            Block::Context { elements } => assert!(elements[0].text().contains("Approved by <@U123>")),
+            other => panic!("expected context block, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(5_000), "5s");
+        assert_eq!(format_duration(125_000), "2m 5s");
+        assert_eq!(format_duration(7_260_000), "2h 1m");
+    }
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+
+use super::blocks::Block;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    pub bot_token: String,
+    pub signing_secret: String,
+    pub app_token: String,
+    pub channel: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackApprovalRequest {
+    pub recommendation: serde_json::Value,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    /// The tool_use block awaiting approval, recorded in the audit log
+    #[serde(default)]
+    pub tool_use: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackMessage {
+    pub text: String,
+    pub blocks: Option<Vec<Block>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackTaskCompletion {
+    pub session_id: String,
+    pub project_name: Option<String>,
+    pub summary: String,
+    pub success: bool,
+    pub duration_ms: Option<u64>,
+    /// PNG uploaded into the thread after the message, e.g. from
+    /// `capture_screenshot`
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+}
+
+/// Progress posted into a session's thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionUpdate {
+    Progress {
+        text: String,
+    },
+    Diff {
+        file: Option<String>,
+        diff: String,
+    },
+    Completion {
+        summary: String,
+        success: bool,
+        duration_ms: Option<u64>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackSessionUpdate {
+    pub session_id: String,
+    pub project_name: Option<String>,
+    pub update: SessionUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackErrorAlert {
+    pub title: String,
+    pub error: String,
+    pub project_name: Option<String>,
+    pub session_id: Option<String>,
+}
+
+/// An approval request that has been posted to Slack and is waiting for a decision
+#[derive(Debug, Clone)]
+pub struct PendingApproval {
+    pub recommendation: serde_json::Value,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    pub channel: String,
+    pub message_ts: String,
+    pub message: SlackMessage,
+}
+
+/// Envelope received over a Socket Mode connection
+#[derive(Debug, Clone, Deserialize)]
+pub struct SocketEnvelope {
+    #[serde(rename = "type")]
+    pub envelope_type: String,
+    pub envelope_id: Option<String>,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_round_trips_with_blocks() {
+        let value = json!({
+            "text": "fallback",
+            "blocks": [
+                { "type": "section", "text": { "type": "mrkdwn", "text": "*hi*" } },
+                { "type": "divider" },
+            ],
+        });
+
+        let message: SlackMessage = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(message.text, "fallback");
+        assert_eq!(message.blocks.as_ref().map(Vec::len), Some(2));
+        assert_eq!(serde_json::to_value(&message).unwrap(), value);
+
+        let plain: SlackMessage = serde_json::from_value(json!({ "text": "plain" })).unwrap();
+        assert!(plain.blocks.is_none());
+    }
+
+    #[test]
+    fn test_requests_from_the_frontend() {
+        let completion: SlackTaskCompletion = serde_json::from_value(json!({
+            "session_id": "s1",
+            "project_name": null,
+            "summary": "Done",
+            "success": true,
+            "duration_ms": 1200,
+        })).unwrap();
+        assert_eq!(completion.duration_ms, Some(1200));
+        assert!(completion.screenshot_path.is_none());
+
+        let approval: SlackApprovalRequest = serde_json::from_value(json!({
+            "recommendation": { "recommendation": "Allow", "confidence": 0.5 },
+            "session_id": "s1",
+            "server_id": "srv",
+            "project_name": "demo",
+        })).unwrap();
+        assert!(approval.tool_use.is_none());
+        assert_eq!(approval.recommendation["confidence"], 0.5);
+    }
+
+    #[test]
+    fn test_session_update_is_tagged_by_kind() {
+        let update = SlackSessionUpdate {
+            session_id: "s1".to_string(),
+            project_name: Some("demo".to_string()),
+            update: SessionUpdate::Diff { file: Some("src/main.rs".to_string()), diff: "+x".to_string() },
+        };
+
+        let value = serde_json::to_value(&update).unwrap();
+        assert_eq!(value["update"], json!({ "kind": "diff", "file": "src/main.rs", "diff": "+x" }));
+
+        let parsed: SlackSessionUpdate = serde_json::from_value(value).unwrap();
+        assert!(matches!(parsed.update, SessionUpdate::Diff { file: Some(ref f), .. } if f == "src/main.rs"));
+        assert!(serde_json::from_value::<SessionUpdate>(json!({ "kind": "unknown" })).is_err());
+    }
+
+    #[test]
+    fn test_socket_envelopes() {
+        let hello: SocketEnvelope = serde_json::from_str(r#"{"type":"hello","num_connections":1}"#).unwrap();
+        assert_eq!(hello.envelope_type, "hello");
+        assert!(hello.envelope_id.is_none());
+        assert!(hello.payload.is_null());
+
+        let interactive: SocketEnvelope = serde_json::from_value(json!({
+            "type": "interactive",
+            "envelope_id": "e1",
+            "payload": { "type": "block_actions", "actions": [] },
+            "accepts_response_payload": false,
+        })).unwrap();
+        assert_eq!(interactive.envelope_id.as_deref(), Some("e1"));
+        assert_eq!(interactive.payload["type"], "block_actions");
+    }
+}
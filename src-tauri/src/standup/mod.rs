@@ -0,0 +1,224 @@
+pub mod report;
+pub mod types;
+
+pub use types::*;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate, Timelike, Utc};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::database::standups::{self, StandupSummary};
+use crate::database::usage::{self, UsageGrouping, UsageRange};
+use crate::database::{self, activity, settings, DatabaseManager};
+use crate::git::GitManager;
+use crate::projects::manager::ProjectsManager;
+use crate::slack::{SlackMessage, SlackService};
+
+pub const STANDUP_CONFIG_KEY: &str = "standup_config";
+/// Most commits read from each project's history when looking for the day's
+const COMMIT_SCAN_LIMIT: usize = 500;
+
+/// Posts a summary of the previous day's agent work to Slack and Discord
+/// once a day, keeping each one in the database
+#[derive(Clone)]
+pub struct StandupService {
+    config: Arc<RwLock<StandupConfig>>,
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+    slack: Arc<SlackService>,
+    git: Arc<GitManager>,
+    client: reqwest::Client,
+    scheduler_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl StandupService {
+    pub fn new(slack: Arc<SlackService>, git: Arc<GitManager>) -> Self {
+        Self {
+            config: Arc::new(RwLock::new(StandupConfig::default())),
+            app_handle: Arc::new(RwLock::new(None)),
+            slack,
+            git,
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(30))
+                .build()
+                .unwrap(),
+            scheduler_task: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Load the stored config and start the scheduler
+    pub async fn start(&self, app_handle: &AppHandle) -> Result<()> {
+        *self.app_handle.write().await = Some(app_handle.clone());
+
+        let db = app_handle.state::<DatabaseManager>();
+        let config: Option<StandupConfig> = db.with_connection(|conn| settings::get_json(conn, STANDUP_CONFIG_KEY))?;
+        *self.config.write().await = config.unwrap_or_default();
+
+        let service = self.clone();
+        let mut task = self.scheduler_task.lock().await;
+        if let Some(existing) = task.take() {
+            existing.abort();
+        }
+        *task = Some(tokio::spawn(async move {
+            service.run_scheduler().await;
+        }));
+
+        println!("[Standup] Standup scheduler started");
+        Ok(())
+    }
+
+    pub async fn stop(&self) {
+        if let Some(task) = self.scheduler_task.lock().await.take() {
+            task.abort();
+        }
+    }
+
+    pub async fn get_config(&self) -> StandupConfig {
+        self.config.read().await.clone()
+    }
+
+    pub async fn update_config(&self, config: StandupConfig) -> Result<()> {
+        if config.hour > 23 {
+            return Err(anyhow::anyhow!("Standup hour must be between 0 and 23"));
+        }
+        self.with_db(|conn| settings::set_json(conn, STANDUP_CONFIG_KEY, &config)).await?;
+        *self.config.write().await = config;
+        Ok(())
+    }
+
+    pub async fn list(&self, limit: usize) -> Result<Vec<StandupSummary>> {
+        self.with_db(|conn| standups::list_summaries(conn, limit)).await
+    }
+
+    /// Summarise `day` (a UTC day, like usage statistics), post it wherever
+    /// configured and store it, replacing any earlier standup for that day
+    pub async fn generate(&self, day: NaiveDate) -> Result<StandupSummary> {
+        let (start, end) = day_bounds(day);
+        let day_key = day.format("%Y-%m-%d").to_string();
+        let handle = database::app_with_database(&self.app_handle, "Standup service").await?;
+        let db = handle.state::<DatabaseManager>();
+
+        let projects = ProjectsManager::new(&db).list(false)?;
+        let entries: Vec<_> = db
+            .with_connection(|conn| activity::list_activity_since(conn, &start.to_rfc3339()))?
+            .into_iter()
+            .filter(|entry| report::within(&entry.created_at, start, end))
+            .collect();
+        let range = UsageRange { start: Some(day_key.clone()), end: Some(day_key.clone()) };
+        let costs = db.with_connection(|conn| usage::get_report(conn, &range, UsageGrouping::Project))?.rows;
+
+        let mut commits = HashMap::new();
+        for project in projects.iter().filter(|p| !p.is_missing) {
+            // Folders that aren't repositories just have no commits
+            let Ok(log) = self.git.log(&project.path, None, COMMIT_SCAN_LIMIT) else { continue };
+            let day_commits: Vec<_> = log.into_iter().filter(|c| report::within(&c.time, start, end)).collect();
+            if !day_commits.is_empty() {
+                commits.insert(project.id.clone(), day_commits);
+            }
+        }
+
+        let projects = report::aggregate(&projects, &entries, &commits, &costs);
+        let text = report::render_summary(&day_key, &projects);
+        let posted_to = self.post(&text).await;
+
+        let summary = StandupSummary {
+            day: day_key,
+            projects,
+            text,
+            posted_to,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        db.with_connection(|conn| standups::save_summary(conn, &summary))?;
+
+        println!(
+            "[Standup] Standup for {} covers {} projects, posted to {:?}",
+            summary.day,
+            summary.projects.len(),
+            summary.posted_to
+        );
+        Ok(summary)
+    }
+
+    /// Post to each configured destination, returning those that took it
+    async fn post(&self, text: &str) -> Vec<String> {
+        let config = self.get_config().await;
+        let mut posted_to = Vec::new();
+
+        if config.post_to_slack {
+            match self.slack.send_message(SlackMessage { text: text.to_string(), blocks: None }).await {
+                Ok(()) => posted_to.push("slack".to_string()),
+                Err(e) => println!("[Standup] Failed to post to Slack: {}", e),
+            }
+        }
+
+        if let Some(url) = config.discord_webhook_url.as_deref().filter(|url| !url.trim().is_empty()) {
+            match self.post_discord(url, text).await {
+                Ok(()) => posted_to.push("discord".to_string()),
+                Err(e) => println!("[Standup] Failed to post to Discord: {}", e),
+            }
+        }
+
+        posted_to
+    }
+
+    async fn post_discord(&self, url: &str, text: &str) -> Result<()> {
+        let response = self.client
+            .post(url)
+            .json(&json!({ "content": report::truncate_message(text, report::DISCORD_MESSAGE_LIMIT) }))
+            .send()
+            .await
+            // The webhook URL carries its token
+            .map_err(|e| anyhow::anyhow!("Discord webhook request failed: {}", e.without_url()))?;
+        if !response.status().is_success() {
+            return Err(anyhow::anyhow!("Discord webhook returned {}", response.status()));
+        }
+        Ok(())
+    }
+
+    async fn run_scheduler(&self) {
+        let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+
+        loop {
+            interval.tick().await;
+
+            let config = self.get_config().await;
+            if !config.enabled || Local::now().hour() != config.hour {
+                continue;
+            }
+
+            // Only one standup per day, including ones generated by hand. One
+            // that reached none of its destinations is tried again.
+            let Some(yesterday) = Utc::now().date_naive().pred_opt() else { continue };
+            let day_key = yesterday.format("%Y-%m-%d").to_string();
+            match self.with_db(|conn| standups::get_summary(conn, &day_key)).await {
+                Ok(Some(summary)) if !summary.posted_to.is_empty() || !config.has_destination() => continue,
+                Ok(_) => {}
+                Err(e) => {
+                    println!("[Standup] Failed to check for an existing standup: {}", e);
+                    continue;
+                }
+            }
+
+            if let Err(e) = self.generate(yesterday).await {
+                println!("[Standup] Failed to generate standup: {}", e);
+            }
+        }
+    }
+
+    async fn with_db<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&rusqlite::Connection) -> rusqlite::Result<R>,
+    {
+        database::with_app_db(&self.app_handle, "Standup service", f).await
+    }
+}
+
+/// Start of `day` and of the day after, in UTC
+fn day_bounds(day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let midnight = |day: NaiveDate| day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+    (midnight(day), midnight(day.succ_opt().unwrap_or(day)))
+}
@@ -0,0 +1,253 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Utc};
+
+use crate::database::activity::AgentActivity;
+use crate::database::standups::ProjectStandup;
+use crate::database::usage::UsageRow;
+use crate::git::CommitInfo;
+use crate::projects::types::Project;
+
+/// Discord rejects messages longer than this
+pub const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Whether an RFC 3339 timestamp falls in `[start, end)`
+pub fn within(timestamp: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t >= start && t < end)
+        .unwrap_or(false)
+}
+
+/// Per-project standups from a day's activity, commits (by project id) and
+/// usage grouped by project id. Activity only carries a project name, so
+/// it's credited to the project when exactly one has that name and listed
+/// on its own otherwise. Projects with nothing to report are left out.
+pub fn aggregate(
+    projects: &[Project],
+    activity: &[AgentActivity],
+    commits: &HashMap<String, Vec<CommitInfo>>,
+    costs: &[UsageRow],
+) -> Vec<ProjectStandup> {
+    let mut by_id: HashMap<String, ProjectStandup> = projects
+        .iter()
+        .map(|p| (p.id.clone(), ProjectStandup {
+            project_id: Some(p.id.clone()),
+            project_name: p.name.clone(),
+            ..Default::default()
+        }))
+        .collect();
+    let mut ids_by_name: HashMap<&str, Vec<&str>> = HashMap::new();
+    for project in projects {
+        ids_by_name.entry(project.name.as_str()).or_default().push(project.id.as_str());
+    }
+    // Activity that can't be pinned to a single project, by name
+    let mut unmatched: BTreeMap<String, ProjectStandup> = BTreeMap::new();
+
+    for entry in activity {
+        let standup = match ids_by_name.get(entry.project_name.as_str()).map(Vec::as_slice) {
+            Some([id]) => by_id.get_mut(*id).expect("every project has a standup"),
+            _ => unmatched.entry(entry.project_name.clone()).or_insert_with(|| ProjectStandup {
+                project_name: entry.project_name.clone(),
+                ..Default::default()
+            }),
+        };
+        let summary = entry.summary.lines().next().unwrap_or_default().to_string();
+        if entry.success {
+            standup.completed.push(summary);
+        } else {
+            standup.failed.push(summary);
+        }
+    }
+
+    for (id, standup) in by_id.iter_mut() {
+        if let Some(commits) = commits.get(id) {
+            standup.commits = commits
+                .iter()
+                .map(|c| format!("{} {} ({})", &c.id[..c.id.len().min(7)], c.summary, c.author))
+                .collect();
+        }
+        if let Some(row) = costs.iter().find(|row| &row.key == id) {
+            standup.cost_usd = row.totals.cost_usd;
+            standup.tokens = row.totals.input_tokens + row.totals.output_tokens;
+        }
+    }
+
+    let mut standups: Vec<ProjectStandup> = by_id.into_values().chain(unmatched.into_values()).collect();
+    standups.retain(|s| !s.completed.is_empty() || !s.failed.is_empty() || !s.commits.is_empty() || s.cost_usd > 0.0);
+    standups.sort_by(|a, b| a.project_name.cmp(&b.project_name).then_with(|| a.project_id.cmp(&b.project_id)));
+    standups
+}
+
+/// Render the standup as Markdown that reads the same in Slack and Discord
+pub fn render_summary(day: &str, projects: &[ProjectStandup]) -> String {
+    if projects.is_empty() {
+        return format!("*Standup for {}*\nNo agent activity, commits or costs.\n", day);
+    }
+
+    let tasks: usize = projects.iter().map(|p| p.completed.len() + p.failed.len()).sum();
+    let commits: usize = projects.iter().map(|p| p.commits.len()).sum();
+    let cost: f64 = projects.iter().map(|p| p.cost_usd).sum();
+    let mut text = format!(
+        "*Standup for {}* - {} tasks, {} commits, ${:.2} across {} projects\n",
+        day,
+        tasks,
+        commits,
+        cost,
+        projects.len()
+    );
+
+    for project in projects {
+        text.push_str(&format!(
+            "\n*{}* - {} completed, {} failed, {} commits, ${:.2}\n",
+            project.project_name,
+            project.completed.len(),
+            project.failed.len(),
+            project.commits.len(),
+            project.cost_usd
+        ));
+        for task in &project.completed {
+            text.push_str(&format!("• ✅ {}\n", task));
+        }
+        for task in &project.failed {
+            text.push_str(&format!("• ❌ {}\n", task));
+        }
+        for commit in &project.commits {
+            text.push_str(&format!("• `{}`\n", commit));
+        }
+    }
+
+    text
+}
+
+/// `text` cut to `limit` characters at a line break, marking what was left out
+pub fn truncate_message(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    const MORE: &str = "…\n";
+    let cut: String = text.chars().take(limit - MORE.chars().count()).collect();
+    let cut = match cut.rfind('\n') {
+        Some(end) => &cut[..=end],
+        None => cut.as_str(),
+    };
+    format!("{}{}", cut, MORE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::usage::UsageTotals;
+
+    fn project(id: &str, name: &str) -> Project {
+        Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            path: format!("/tmp/{}", name),
+            description: None,
+            color: None,
+            created_at: Utc::now().to_rfc3339(),
+            last_accessed: None,
+            is_favorite: false,
+            settings: None,
+            is_archived: false,
+            is_missing: false,
+            tags: Vec::new(),
+        }
+    }
+
+    fn activity(project: &str, summary: &str, success: bool) -> AgentActivity {
+        AgentActivity {
+            id: uuid::Uuid::new_v4().to_string(),
+            project_name: project.to_string(),
+            session_id: None,
+            summary: summary.to_string(),
+            success,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_and_render() {
+        let projects = [project("p1", "web"), project("p2", "api"), project("p3", "idle")];
+        let commits = HashMap::from([(
+            "p2".to_string(),
+            vec![CommitInfo {
+                id: "0123456789abcdef".to_string(),
+                summary: "Fix redirect loop".to_string(),
+                author: "Sam".to_string(),
+                email: "sam@example.com".to_string(),
+                time: Utc::now().to_rfc3339(),
+            }],
+        )]);
+        let costs = [UsageRow {
+            key: "p1".to_string(),
+            totals: UsageTotals { tasks: 1, input_tokens: 300, output_tokens: 100, cost_usd: 1.25, agent_time_ms: 0 },
+        }];
+        let standups = aggregate(
+            &projects,
+            &[
+                activity("api", "Fix login\nwith details", true),
+                activity("api", "Deploy", false),
+                activity("gone", "Old work", true),
+            ],
+            &commits,
+            &costs,
+        );
+
+        let names: Vec<_> = standups.iter().map(|s| s.project_name.as_str()).collect();
+        assert_eq!(names, vec!["api", "gone", "web"]);
+        assert_eq!(standups[0].project_id.as_deref(), Some("p2"));
+        assert_eq!(standups[0].commits, vec!["0123456 Fix redirect loop (Sam)"]);
+        assert_eq!(standups[1].project_id, None);
+        assert_eq!((standups[2].cost_usd, standups[2].tokens), (1.25, 400));
+
+        let text = render_summary("2024-01-01", &standups);
+        assert!(text.starts_with("*Standup for 2024-01-01* - 3 tasks, 1 commits, $1.25 across 3 projects"));
+        assert!(text.contains("*api* - 1 completed, 1 failed, 1 commits, $0.00\n• ✅ Fix login\n• ❌ Deploy\n"));
+        assert!(!text.contains("with details"));
+        assert!(render_summary("2024-01-01", &[]).contains("No agent activity"));
+    }
+
+    #[test]
+    fn test_aggregate_keeps_projects_with_the_same_name_apart() {
+        let projects = [project("p1", "app"), project("p2", "app")];
+        let commit = |summary: &str| CommitInfo {
+            id: "abcdef0".to_string(),
+            summary: summary.to_string(),
+            author: "Sam".to_string(),
+            email: "sam@example.com".to_string(),
+            time: Utc::now().to_rfc3339(),
+        };
+        let commits = HashMap::from([
+            ("p1".to_string(), vec![commit("Web change")]),
+            ("p2".to_string(), vec![commit("Mobile change")]),
+        ]);
+        let costs = [UsageRow {
+            key: "p2".to_string(),
+            totals: UsageTotals { tasks: 1, input_tokens: 10, output_tokens: 5, cost_usd: 0.5, agent_time_ms: 0 },
+        }];
+        let standups = aggregate(&projects, &[activity("app", "Ambiguous", true)], &commits, &costs);
+
+        assert_eq!(standups.len(), 3);
+        let p1 = standups.iter().find(|s| s.project_id.as_deref() == Some("p1")).unwrap();
+        let p2 = standups.iter().find(|s| s.project_id.as_deref() == Some("p2")).unwrap();
+        assert_eq!((p1.commits.len(), p1.cost_usd), (1, 0.0));
+        assert_eq!((p2.commits.len(), p2.cost_usd), (1, 0.5));
+        assert!(p1.completed.is_empty() && p2.completed.is_empty());
+        let unmatched = standups.iter().find(|s| s.project_id.is_none()).unwrap();
+        assert_eq!(unmatched.completed, vec!["Ambiguous"]);
+    }
+
+    #[test]
+    fn test_within_and_truncate() {
+        let start = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+00:00").unwrap().with_timezone(&Utc);
+        let end = start + chrono::Duration::days(1);
+        assert!(within("2024-01-01T00:00:00+00:00", start, end));
+        assert!(within("2024-01-01T20:00:00-03:00", start, end));
+        assert!(!within("2024-01-02T00:00:00Z", start, end));
+        assert!(!within("yesterday", start, end));
+
+        assert_eq!(truncate_message("short", 10), "short");
+        assert_eq!(truncate_message("line one\nline two\nline three", 20), "line one\nline two\n…\n");
+    }
+}
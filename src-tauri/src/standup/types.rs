@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// Daily standup configuration, stored in the settings table under `standup_config`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StandupConfig {
+    pub enabled: bool,
+    /// Local hour (0-23) at which the standup for the previous UTC day is
+    /// posted; days are UTC to match the usage statistics
+    #[serde(default = "default_hour")]
+    pub hour: u32,
+    /// Post to the channel the Slack service is configured with
+    #[serde(default = "default_true")]
+    pub post_to_slack: bool,
+    /// Discord incoming webhook to post to as well
+    #[serde(default)]
+    pub discord_webhook_url: Option<String>,
+}
+
+impl Default for StandupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            hour: default_hour(),
+            post_to_slack: true,
+            discord_webhook_url: None,
+        }
+    }
+}
+
+impl StandupConfig {
+    pub fn has_destination(&self) -> bool {
+        self.post_to_slack || self.discord_webhook_url.as_deref().is_some_and(|url| !url.trim().is_empty())
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_hour() -> u32 {
+    9
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::types::Update;
+
+const TELEGRAM_API_BASE: &str = "https://api.telegram.org";
+
+/// Long-poll timeout passed to getUpdates, in seconds
+pub const POLL_TIMEOUT_SECS: u64 = 30;
+
+/// Minimal Telegram Bot API client
+#[derive(Debug, Clone)]
+pub struct TelegramApiClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for TelegramApiClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TelegramApiClient {
+    pub fn new() -> Self {
+        Self::with_base_url(TELEGRAM_API_BASE)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                // Must outlast the getUpdates long poll
+                .timeout(std::time::Duration::from_secs(POLL_TIMEOUT_SECS + 10))
+                .build()
+                .unwrap(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Call a Bot API method and return its `result` when `ok` is true
+    pub async fn call<T: DeserializeOwned>(&self, token: &str, method: &str, body: Value) -> Result<T> {
+        let url = format!("{}/bot{}/{}", self.base_url, token, method);
+
+        let response = self.client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Telegram API request {} failed: {}", method, e.without_url()))?;
+
+        let value: Value = response
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to parse Telegram API response for {}: {}", method, e))?;
+
+        if value["ok"].as_bool() != Some(true) {
+            let description = value["description"].as_str().unwrap_or("unknown error");
+            return Err(anyhow::anyhow!("Telegram API {} returned error: {}", method, description));
+        }
+
+        Ok(serde_json::from_value(value["result"].clone())?)
+    }
+
+    /// Verify the bot token and return the bot's username
+    pub async fn get_me(&self, token: &str) -> Result<String> {
+        let me: Value = self.call(token, "getMe", json!({})).await?;
+        Ok(me["username"].as_str().unwrap_or_default().to_string())
+    }
+
+    pub async fn get_updates(&self, token: &str, offset: i64) -> Result<Vec<Update>> {
+        self.call(token, "getUpdates", json!({
+            "offset": offset,
+            "timeout": POLL_TIMEOUT_SECS,
+            "allowed_updates": ["callback_query"],
+        })).await
+    }
+
+    /// Send an HTML-formatted message and return its message id
+    pub async fn send_message(
+        &self,
+        token: &str,
+        chat_id: i64,
+        text: &str,
+        reply_markup: Option<Value>,
+    ) -> Result<i64> {
+        let mut body = json!({
+            "chat_id": chat_id,
+            "text": text,
+            "parse_mode": "HTML",
+            "disable_web_page_preview": true,
+        });
+        if let Some(markup) = reply_markup {
+            body["reply_markup"] = markup;
+        }
+
+        let message: Value = self.call(token, "sendMessage", body).await?;
+        Ok(message["message_id"].as_i64().unwrap_or_default())
+    }
+
+    /// Replace a message's text, removing its inline keyboard
+    pub async fn edit_message_text(&self, token: &str, chat_id: i64, message_id: i64, text: &str) -> Result<()> {
+        let _: Value = self.call(token, "editMessageText", json!({
+            "chat_id": chat_id,
+            "message_id": message_id,
+            "text": text,
+            "parse_mode": "HTML",
+        })).await?;
+        Ok(())
+    }
+
+    pub async fn answer_callback_query(&self, token: &str, callback_query_id: &str, text: &str) -> Result<()> {
+        let _: Value = self.call(token, "answerCallbackQuery", json!({
+            "callback_query_id": callback_query_id,
+            "text": text,
+        })).await?;
+        Ok(())
+    }
+}
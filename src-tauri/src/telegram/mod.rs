@@ -0,0 +1,360 @@
+pub mod api;
+pub mod types;
+
+pub use api::TelegramApiClient;
+pub use types::*;
+
+use std::sync::Arc;
+use anyhow::Result;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+
+use crate::approvals::{ApprovalQueue, RecentApproval};
+use crate::database::approvals;
+const MAX_RECOMMENDATION_CHARS: usize = 1000;
+
+#[derive(Default)]
+struct TelegramState {
+    config: Option<TelegramConfig>,
+    initialized: bool,
+    polling: bool,
+    approvals: ApprovalQueue<PendingApproval>,
+}
+
+/// Telegram bot channel: plain notifications plus approvals via inline keyboard
+/// buttons, received by long polling `getUpdates`.
+#[derive(Clone)]
+pub struct TelegramService {
+    state: Arc<RwLock<TelegramState>>,
+    api: TelegramApiClient,
+    poll_task: Arc<Mutex<Option<JoinHandle<()>>>>,
+    app_handle: Arc<RwLock<Option<AppHandle>>>,
+}
+
+impl Default for TelegramService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TelegramService {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(TelegramState::default())),
+            api: TelegramApiClient::new(),
+            poll_task: Arc::new(Mutex::new(None)),
+            app_handle: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub async fn set_app_handle(&self, app_handle: AppHandle) {
+        *self.app_handle.write().await = Some(app_handle);
+    }
+
+    pub async fn initialize(&self, config: TelegramConfig) -> Result<()> {
+        if !config.enabled {
+            return Err(anyhow::anyhow!("Telegram is disabled"));
+        }
+
+        let username = self.api.get_me(&config.bot_token).await
+            .map_err(|e| anyhow::anyhow!("Failed to initialize Telegram: {}", e))?;
+        println!("[Telegram] Authenticated as @{}", username);
+
+        {
+            let mut state = self.state.write().await;
+            state.config = Some(config);
+            state.initialized = true;
+        }
+
+        let mut task_guard = self.poll_task.lock().await;
+        if let Some(task) = task_guard.take() {
+            task.abort();
+        }
+        let service = self.clone();
+        *task_guard = Some(tokio::spawn(async move {
+            service.run_polling().await;
+        }));
+
+        Ok(())
+    }
+
+    pub async fn shutdown(&self) -> Result<()> {
+        if let Some(task) = self.poll_task.lock().await.take() {
+            task.abort();
+        }
+
+        let mut state = self.state.write().await;
+        state.config = None;
+        state.initialized = false;
+        state.polling = false;
+        state.approvals.clear_pending();
+
+        println!("[Telegram] Telegram integration shut down");
+        Ok(())
+    }
+
+    async fn active_config(&self) -> Result<TelegramConfig> {
+        let state = self.state.read().await;
+        match (&state.config, state.initialized) {
+            (Some(config), true) => Ok(config.clone()),
+            _ => Err(anyhow::anyhow!("Telegram not initialized")),
+        }
+    }
+
+    pub async fn send_message(&self, message: TelegramMessage) -> Result<()> {
+        let config = self.active_config().await?;
+        self.api.send_message(&config.bot_token, config.chat_id, &escape_html(&message.text), None).await?;
+        Ok(())
+    }
+
+    pub async fn send_approval_request(&self, request: TelegramApprovalRequest) -> Result<()> {
+        let config = self.active_config().await?;
+        // Anyone in the chat could press the buttons otherwise
+        if config.approver_ids.is_empty() {
+            return Err(anyhow::anyhow!("No Telegram approvers configured"));
+        }
+
+        // callback_data is limited to 64 bytes, so keep ids short
+        let action_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+        let text = approval_text(&request);
+        let keyboard = json!({
+            "inline_keyboard": [[
+                { "text": "✅ Approve", "callback_data": format!("approve:{}", action_id) },
+                { "text": "❌ Deny", "callback_data": format!("deny:{}", action_id) },
+            ]],
+        });
+
+        let message_id = self.api
+            .send_message(&config.bot_token, config.chat_id, &text, Some(keyboard))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to send approval: {}", e))?;
+
+        println!("[Telegram] Sent approval request {} as message {}", action_id, message_id);
+
+        let record = approvals::ApprovalRecord {
+            id: action_id.clone(),
+            channel: "telegram".to_string(),
+            session_id: request.session_id.clone(),
+            server_id: request.server_id.clone(),
+            project_name: request.project_name.clone(),
+            recommendation: request.recommendation.clone(),
+            tool_use: request.tool_use.clone(),
+            requested_at: chrono::Utc::now().timestamp_millis(),
+            decision: None,
+            decided_by: None,
+            decided_at: None,
+            latency_ms: None,
+        };
+        approvals::audit(&self.app_handle, "Telegram", |conn| approvals::record_request(conn, &record)).await;
+
+        self.state.write().await.approvals.add_pending(action_id, PendingApproval {
+            recommendation: request.recommendation,
+            session_id: request.session_id,
+            server_id: request.server_id,
+            project_name: request.project_name,
+            message_id,
+            text,
+        });
+
+        Ok(())
+    }
+
+    pub async fn get_approvals(&self, since: u64) -> Result<Value> {
+        let state = self.state.read().await;
+        let approvals = state.approvals.decided_since(since);
+
+        Ok(json!({ "approvals": approvals }))
+    }
+
+    pub async fn status(&self) -> Result<Value> {
+        let state = self.state.read().await;
+
+        Ok(json!({
+            "initialized": state.initialized,
+            "polling": state.polling,
+            "pendingApprovals": state.approvals.pending_count(),
+            "config": state.config.as_ref().map(|c| json!({
+                "chat_id": c.chat_id,
+                "enabled": c.enabled,
+            })),
+        }))
+    }
+
+    async fn run_polling(&self) {
+        let mut offset = 0;
+        let mut backoff = 1;
+
+        loop {
+            let Ok(config) = self.active_config().await else { break };
+            self.state.write().await.polling = true;
+
+            match self.api.get_updates(&config.bot_token, offset).await {
+                Ok(updates) => {
+                    backoff = 1;
+                    for update in updates {
+                        offset = offset.max(update.update_id + 1);
+                        if let Some(callback) = update.callback_query {
+                            self.handle_callback(&config, callback).await;
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("[Telegram] Polling error: {}", e);
+                    self.state.write().await.polling = false;
+                    tokio::time::sleep(tokio::time::Duration::from_secs(backoff)).await;
+                    backoff = (backoff * 2).min(30);
+                }
+            }
+        }
+    }
+
+    async fn handle_callback(&self, config: &TelegramConfig, callback: CallbackQuery) {
+        let Some(message) = &callback.message else { return };
+        if message.chat.id != config.chat_id {
+            println!("[Telegram] Ignoring callback from unexpected chat {}", message.chat.id);
+            return;
+        }
+
+        let Some((decision, action_id)) = callback.data.as_deref().and_then(|d| d.split_once(':')) else { return };
+        let approved = match decision {
+            "approve" => true,
+            "deny" => false,
+            _ => return,
+        };
+
+        if !config.is_approver(callback.from.id) {
+            println!("[Telegram] Ignoring approval {} from user {} who isn't an approver", action_id, callback.from.id);
+            let _ = self.api.answer_callback_query(
+                &config.bot_token,
+                &callback.id,
+                "You're not allowed to decide approval requests.",
+            ).await;
+            return;
+        }
+
+        let pending = self.state.write().await.approvals.take_pending(action_id);
+        let Some(pending) = pending else {
+            let _ = self.api.answer_callback_query(
+                &config.bot_token,
+                &callback.id,
+                "This approval request has expired or was already processed.",
+            ).await;
+            return;
+        };
+
+        let decider = callback.from.username
+            .as_ref()
+            .map(|u| format!("@{}", u))
+            .unwrap_or_else(|| callback.from.first_name.clone());
+        let status = if approved { "✅ Approved" } else { "❌ Denied" };
+
+        let _ = self.api.answer_callback_query(&config.bot_token, &callback.id, status).await;
+        if let Err(e) = self.api.edit_message_text(
+            &config.bot_token,
+            config.chat_id,
+            pending.message_id,
+            &format!(
+                "{}\n\n<i>{} by {} at {}</i>",
+                pending.text,
+                status,
+                escape_html(&decider),
+                chrono::Local::now().format("%H:%M:%S")
+            ),
+        ).await {
+            println!("[Telegram] Failed to update approval message: {}", e);
+        }
+
+        println!("[Telegram] Approval {} {} by {}", action_id, if approved { "approved" } else { "denied" }, decider);
+
+        let decided_at = chrono::Utc::now().timestamp_millis();
+        approvals::audit(&self.app_handle, "Telegram", |conn| approvals::record_decision(conn, action_id, approved, &decider, decided_at).map(|_| ())).await;
+
+        let approval = RecentApproval {
+            action_id: action_id.to_string(),
+            approved,
+            session_id: pending.session_id,
+            server_id: pending.server_id,
+            project_name: pending.project_name,
+            recommendation: pending.recommendation,
+            timestamp: chrono::Utc::now().timestamp_millis() as u64,
+        };
+
+        self.state.write().await.approvals.record(approval.clone());
+
+        if let Some(handle) = self.app_handle.read().await.as_ref() {
+            let _ = handle.emit("telegram-approval", approval);
+        }
+    }
+}
+
+/// Escape text for Telegram's HTML parse mode
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn approval_text(request: &TelegramApprovalRequest) -> String {
+    let recommendation = request.recommendation["recommendation"].as_str().unwrap_or_default();
+    let confidence = request.recommendation["confidence"].as_f64().unwrap_or(0.0);
+    let confidence_emoji = if confidence > 0.7 { "🟢" } else if confidence > 0.4 { "🟡" } else { "🔴" };
+
+    let mut truncated: String = recommendation.chars().take(MAX_RECOMMENDATION_CHARS).collect();
+    if recommendation.chars().count() > MAX_RECOMMENDATION_CHARS {
+        truncated.push_str("...");
+    }
+
+    let mut text = format!(
+        "🧠 <b>SensAI Approval Required</b>\n\n<b>Project:</b> {}\n<b>Session:</b> <code>{}</code>\n<b>Confidence:</b> {} {}%\n\n<b>Recommendation:</b>\n{}",
+        escape_html(request.project_name.as_deref().unwrap_or("Unknown")),
+        escape_html(&request.session_id),
+        confidence_emoji,
+        (confidence * 100.0).round() as u32,
+        escape_html(&truncated),
+    );
+
+    if let Some(command) = request.recommendation["command"].as_str() {
+        text.push_str(&format!("\n\n<b>Suggested Command:</b>\n<pre>{}</pre>", escape_html(command)));
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_approval_text_escapes_html() {
+        let text = approval_text(&TelegramApprovalRequest {
+            recommendation: json!({
+                "recommendation": "Use Vec<String> & friends",
+                "command": "echo <hi>",
+                "confidence": 0.8,
+            }),
+            session_id: "s1".to_string(),
+            server_id: "srv".to_string(),
+            project_name: Some("demo".to_string()),
+            tool_use: None,
+        });
+
+        assert!(text.contains("Vec&lt;String&gt; &amp; friends"));
+        assert!(text.contains("<pre>echo &lt;hi&gt;</pre>"));
+        assert!(text.contains("🟢 80%"));
+    }
+
+    #[test]
+    fn test_approvers_come_from_config() {
+        let config: TelegramConfig = serde_json::from_value(json!({
+            "bot_token": "token",
+            "chat_id": -100,
+            "enabled": true,
+        })).unwrap();
+        assert!(config.approver_ids.is_empty());
+        assert!(!config.is_approver(42));
+
+        let config = TelegramConfig { approver_ids: vec![42], ..config };
+        assert!(config.is_approver(42));
+        assert!(!config.is_approver(7));
+    }
+}
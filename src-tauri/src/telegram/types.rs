@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramConfig {
+    pub bot_token: String,
+    /// Chat that receives notifications; callbacks from other chats are ignored
+    pub chat_id: i64,
+    pub enabled: bool,
+    /// Telegram user ids allowed to approve or deny; approval requests
+    /// aren't sent while this is empty
+    #[serde(default)]
+    pub approver_ids: Vec<i64>,
+}
+
+impl TelegramConfig {
+    pub fn is_approver(&self, user_id: i64) -> bool {
+        self.approver_ids.contains(&user_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramApprovalRequest {
+    pub recommendation: serde_json::Value,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    /// The tool_use block awaiting approval, recorded in the audit log
+    #[serde(default)]
+    pub tool_use: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelegramMessage {
+    pub text: String,
+}
+
+/// An approval request posted to Telegram and waiting for a button press
+#[derive(Debug, Clone)]
+pub struct PendingApproval {
+    pub recommendation: serde_json::Value,
+    pub session_id: String,
+    pub server_id: String,
+    pub project_name: Option<String>,
+    pub message_id: i64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Update {
+    pub update_id: i64,
+    pub callback_query: Option<CallbackQuery>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallbackQuery {
+    pub id: String,
+    pub from: User,
+    pub message: Option<Message>,
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct User {
+    pub id: i64,
+    pub username: Option<String>,
+    pub first_name: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub message_id: i64,
+    pub chat: Chat,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chat {
+    pub id: i64,
+}
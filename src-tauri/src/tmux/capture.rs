@@ -0,0 +1,127 @@
+use super::types::PaneSearchMatch;
+
+/// Remove terminal escape sequences and control characters, keeping
+/// newlines and tabs. Carriage returns are dropped so `\r\n` becomes `\n`.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters and intermediates up to a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC and other strings end with BEL or ST (ESC \)
+                Some(']' | 'P' | '_' | '^') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.peek() == Some(&'\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                // Charset designation takes one more character
+                Some('(' | ')' | '*' | '+') => {
+                    chars.next();
+                }
+                _ => {}
+            },
+            '\n' | '\t' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Case-insensitive search of `text` for `pattern`, returning the most recent
+/// `max_matches` matching lines with `context` lines either side
+pub fn search_lines(text: &str, pattern: &str, max_matches: usize, context: usize) -> Vec<PaneSearchMatch> {
+    if pattern.is_empty() || max_matches == 0 {
+        return Vec::new();
+    }
+
+    let pattern = pattern.to_lowercase();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut hits: Vec<usize> = lines.iter()
+        .enumerate()
+        .filter(|(_, line)| line.to_lowercase().contains(&pattern))
+        .map(|(i, _)| i)
+        .collect();
+    if hits.len() > max_matches {
+        hits.drain(..hits.len() - max_matches);
+    }
+
+    let owned = |range: &[&str]| range.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+    hits.into_iter()
+        .map(|i| PaneSearchMatch {
+            line_number: i + 1,
+            line: lines[i].to_string(),
+            context_before: owned(&lines[i.saturating_sub(context)..i]),
+            context_after: owned(&lines[i + 1..(i + 1 + context).min(lines.len())]),
+        })
+        .collect()
+}
+
+/// The last `max_lines` lines of `text`
+pub fn tail_lines(text: &str, max_lines: usize) -> &str {
+    if max_lines == 0 {
+        return "";
+    }
+
+    let trimmed = text.strip_suffix('\n').unwrap_or(text);
+    match trimmed.rmatch_indices('\n').nth(max_lines - 1) {
+        Some((i, _)) => &text[i + 1..],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_lines_keeps_most_recent_matches() {
+        let text = "start\nerror: one\nok\nok\nERROR: two\nend\n";
+
+        let matches = search_lines(text, "error", 1, 2);
+        assert_eq!(matches, vec![PaneSearchMatch {
+            line_number: 5,
+            line: "ERROR: two".to_string(),
+            context_before: vec!["ok".to_string(), "ok".to_string()],
+            context_after: vec!["end".to_string()],
+        }]);
+
+        let matches = search_lines(text, "Error", 10, 1);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].line_number, 2);
+        assert_eq!(matches[0].context_before, vec!["start"]);
+        assert!(search_lines(text, "", 10, 1).is_empty());
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;32mok\x1b[0m\r\n"), "ok\n");
+        assert_eq!(strip_ansi("\x1b]0;title\x07prompt"), "prompt");
+        assert_eq!(strip_ansi("\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip_ansi("\x1b(Bplain\x1b=\x08\tend"), "plain\tend");
+        assert_eq!(strip_ansi("│ box ─"), "│ box ─");
+    }
+
+    #[test]
+    fn test_tail_lines() {
+        assert_eq!(tail_lines("a\nb\nc\n", 2), "b\nc\n");
+        assert_eq!(tail_lines("a\nb\nc", 2), "b\nc");
+        assert_eq!(tail_lines("a\nb\nc", 5), "a\nb\nc");
+        assert_eq!(tail_lines("a\nb", 0), "");
+    }
+}
@@ -0,0 +1,213 @@
+use chrono::{TimeZone, Utc};
+use tokio::process::Command;
+
+use super::types::{TmuxPane, TmuxSessionInfo, TmuxWindow};
+
+/// Fields printed per session by `list-sessions`, tab separated
+pub const LIST_SESSIONS_FORMAT: &str =
+    "#{session_name}\t#{session_windows}\t#{session_attached}\t#{session_created}\t#{session_path}";
+
+pub const LIST_WINDOWS_FORMAT: &str =
+    "#{window_id}\t#{window_index}\t#{window_active}\t#{window_panes}\t#{window_name}";
+
+pub const LIST_PANES_FORMAT: &str =
+    "#{pane_id}\t#{window_index}\t#{pane_index}\t#{pane_active}\t#{pane_width}\t#{pane_height}\t#{pane_current_command}";
+
+/// Build `tmux <args>`, or `ssh <host> tmux <args>` for a remote server.
+/// ssh hands the command line to the remote shell, so arguments are quoted.
+pub fn command(host: Option<&str>, args: &[&str]) -> Command {
+    match host {
+        Some(host) => {
+            let mut remote = vec!["tmux".to_string()];
+            remote.extend(args.iter().map(|arg| shell_quote(arg)));
+
+            let mut command = Command::new("ssh");
+            // Never stop for a password prompt the app can't answer
+            command.args(["-o", "BatchMode=yes", "-T", host, "--"]).arg(remote.join(" "));
+            command
+        }
+        None => {
+            let mut command = Command::new("tmux");
+            command.args(args);
+            command
+        }
+    }
+}
+
+/// Quote an argument for a POSIX shell
+pub fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:%@=,+".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+/// Run `tmux <args>`, on `host` if given, and return stdout, or stderr as the error
+pub async fn run(host: Option<&str>, args: &[&str]) -> Result<String, String> {
+    let output = command(host, args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run tmux {}: {}", args.first().unwrap_or(&""), e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "tmux {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Whether an error from `run` means no tmux server is running, which
+/// tmux reports as a failure even for read-only commands
+pub fn is_no_server(error: &str) -> bool {
+    error.contains("no server running") || error.contains("error connecting to")
+}
+
+pub async fn has_session(host: Option<&str>, name: &str) -> bool {
+    run(host, &["has-session", "-t", name]).await.is_ok()
+}
+
+pub async fn list_sessions(host: Option<&str>) -> Result<Vec<TmuxSessionInfo>, String> {
+    match run(host, &["list-sessions", "-F", LIST_SESSIONS_FORMAT]).await {
+        Ok(output) => Ok(parse_list_sessions(&output)),
+        Err(e) if is_no_server(&e) => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Parse `list-sessions -F LIST_SESSIONS_FORMAT` output. Malformed lines are skipped.
+pub fn parse_list_sessions(output: &str) -> Vec<TmuxSessionInfo> {
+    output.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            let name = fields.next()?.to_string();
+            let windows = fields.next()?.parse().ok()?;
+            let attached = fields.next()?.parse::<u32>().ok()?;
+            let created = fields.next()?.parse::<i64>().ok()?;
+            let path = fields.next().unwrap_or_default().to_string();
+
+            Some(TmuxSessionInfo {
+                name,
+                windows,
+                attached_clients: attached,
+                created_at: Utc.timestamp_opt(created, 0).single()?.to_rfc3339(),
+                path,
+                tracked: false,
+            })
+        })
+        .collect()
+}
+
+/// Windows of a session
+pub async fn list_windows(host: Option<&str>, session: &str) -> Result<Vec<TmuxWindow>, String> {
+    let output = run(host, &["list-windows", "-t", session, "-F", LIST_WINDOWS_FORMAT]).await?;
+    Ok(parse_list_windows(&output))
+}
+
+/// Panes of every window of a session
+pub async fn list_panes(host: Option<&str>, session: &str) -> Result<Vec<TmuxPane>, String> {
+    let output = run(host, &["list-panes", "-s", "-t", session, "-F", LIST_PANES_FORMAT]).await?;
+    Ok(parse_list_panes(&output))
+}
+
+pub fn parse_list_windows(output: &str) -> Vec<TmuxWindow> {
+    output.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            Some(TmuxWindow {
+                window_id: fields.next()?.to_string(),
+                index: fields.next()?.parse().ok()?,
+                active: fields.next()? == "1",
+                pane_count: fields.next()?.parse().ok()?,
+                name: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_list_panes(output: &str) -> Vec<TmuxPane> {
+    output.lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(7, '\t');
+            Some(TmuxPane {
+                pane_id: fields.next()?.to_string(),
+                window_index: fields.next()?.parse().ok()?,
+                pane_index: fields.next()?.parse().ok()?,
+                active: fields.next()? == "1",
+                width: fields.next()?.parse().ok()?,
+                height: fields.next()?.parse().ok()?,
+                current_command: fields.next().unwrap_or_default().to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_sessions() {
+        let output = "main\t3\t1\t1700000000\t/Users/dev/project\n\
+                      tmux-1a2b3c4d\t1\t0\t1700000100\t/tmp\n\
+                      garbage line\n";
+
+        let sessions = parse_list_sessions(output);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "main");
+        assert_eq!(sessions[0].windows, 3);
+        assert_eq!(sessions[0].attached_clients, 1);
+        assert_eq!(sessions[0].path, "/Users/dev/project");
+        assert_eq!(sessions[1].created_at, "2023-11-14T22:15:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_list_windows_and_panes() {
+        let windows = parse_list_windows("@1\t0\t1\t2\tagent\n@4\t1\t0\t1\tdev server\n");
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[1], TmuxWindow {
+            window_id: "@4".to_string(),
+            index: 1,
+            name: "dev server".to_string(),
+            active: false,
+            pane_count: 1,
+        });
+
+        let panes = parse_list_panes("%0\t0\t0\t1\t120\t40\topencode\n%3\t0\t1\t0\t60\t40\tzsh\n");
+        assert_eq!(panes.len(), 2);
+        assert_eq!(panes[0].pane_id, "%0");
+        assert!(panes[0].active);
+        assert_eq!((panes[1].width, panes[1].height), (60, 40));
+        assert_eq!(panes[1].current_command, "zsh");
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("ninja-app"), "ninja-app");
+        assert_eq!(shell_quote("%3"), "%3");
+        assert_eq!(shell_quote("#{pane_id}"), "'#{pane_id}'");
+        assert_eq!(shell_quote("unset x && opencode"), "'unset x && opencode'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_remote_command_wraps_tmux_in_ssh() {
+        let command = command(Some("build-box"), &["send-keys", "-t", "ninja-app", "echo hi"]);
+        let std = command.as_std();
+        assert_eq!(std.get_program(), "ssh");
+        let args: Vec<_> = std.get_args().map(|a| a.to_string_lossy().to_string()).collect();
+        assert_eq!(args, vec!["-o", "BatchMode=yes", "-T", "build-box", "--", "tmux send-keys -t ninja-app 'echo hi'"]);
+    }
+
+    #[test]
+    fn test_is_no_server() {
+        assert!(is_no_server("tmux list-sessions failed: no server running on /tmp/tmux-501/default"));
+        assert!(!is_no_server("tmux list-sessions failed: unknown option"));
+    }
+}
@@ -0,0 +1,156 @@
+use std::process::Stdio;
+
+use tokio::io::{AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout};
+
+use super::cli;
+
+/// A notification from a control-mode client
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlEvent {
+    /// Bytes a pane wrote to its terminal
+    Output { pane_id: String, data: Vec<u8> },
+    WindowAdded { window_id: String },
+    WindowClosed { window_id: String },
+    LayoutChanged { window_id: String },
+    /// The client was detached or the session ended
+    Exit { reason: Option<String> },
+}
+
+/// A `tmux -C` client attached to one session. Control mode (rather than
+/// `-CC`, which wraps everything in a DCS sequence for terminal emulators)
+/// prints one notification per line on stdout and reads commands on stdin.
+pub struct ControlClient {
+    child: Child,
+    stdin: ChildStdin,
+}
+
+impl ControlClient {
+    /// Attach to `session`, on `host` if given, and return the client with its
+    /// notification stream. Lines are read as bytes since pane output needn't be UTF-8.
+    pub fn attach(host: Option<&str>, session: &str) -> Result<(Self, BufReader<ChildStdout>), String> {
+        let mut child = cli::command(host, &["-C", "attach-session", "-t", session])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("Failed to start tmux control client: {}", e))?;
+
+        let stdin = child.stdin.take().ok_or("Failed to open tmux control client stdin")?;
+        let stdout = child.stdout.take().ok_or("Failed to open tmux control client stdout")?;
+
+        Ok((Self { child, stdin }, BufReader::new(stdout)))
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Send a tmux command; its reply arrives as a `%begin`/`%end` block
+    pub async fn send_command(&mut self, command: &str) -> Result<(), String> {
+        self.stdin.write_all(format!("{}\n", command).as_bytes()).await
+            .map_err(|e| format!("Failed to write to tmux control client: {}", e))
+    }
+
+    /// Detach without touching the session
+    pub async fn detach(mut self) {
+        let _ = self.send_command("detach-client").await;
+        let _ = self.child.kill().await;
+    }
+}
+
+/// Parse one line of control-mode output. Command replies and notifications
+/// the app doesn't use yield `None`.
+pub fn parse_line(line: &[u8]) -> Option<ControlEvent> {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    let (kind, rest) = split_word(line);
+
+    if kind == b"%output" {
+        let (pane_id, data) = split_word(rest);
+        return Some(ControlEvent::Output {
+            pane_id: String::from_utf8_lossy(pane_id).to_string(),
+            data: unescape_output(data),
+        });
+    }
+
+    let rest = String::from_utf8_lossy(rest);
+    match kind {
+        b"%window-add" => Some(ControlEvent::WindowAdded { window_id: rest.to_string() }),
+        b"%window-close" | b"%unlinked-window-close" => {
+            Some(ControlEvent::WindowClosed { window_id: rest.to_string() })
+        }
+        b"%layout-change" => {
+            let window_id = rest.split(' ').next().unwrap_or_default();
+            Some(ControlEvent::LayoutChanged { window_id: window_id.to_string() })
+        }
+        b"%exit" => Some(ControlEvent::Exit {
+            reason: (!rest.is_empty()).then(|| rest.to_string()),
+        }),
+        _ => None,
+    }
+}
+
+fn split_word(line: &[u8]) -> (&[u8], &[u8]) {
+    match line.iter().position(|b| *b == b' ') {
+        Some(i) => (&line[..i], &line[i + 1..]),
+        None => (line, &[]),
+    }
+}
+
+/// Undo control mode's escaping of `%output` data, where backslashes and
+/// bytes below 0x20 are written as `\ooo` octal escapes
+pub fn unescape_output(bytes: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).filter(|digits| digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        if let (b'\\', Some(digits)) = (bytes[i], octal) {
+            let value = digits.iter().fold(0u16, |acc, d| acc * 8 + (d - b'0') as u16);
+            if let Ok(byte) = u8::try_from(value) {
+                out.push(byte);
+                i += 4;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output_notification() {
+        assert_eq!(
+            parse_line(br"%output %3 hello\015\012\033[1mworld\134"),
+            Some(ControlEvent::Output {
+                pane_id: "%3".to_string(),
+                data: b"hello\r\n\x1b[1mworld\\".to_vec(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_window_and_exit_notifications() {
+        assert_eq!(parse_line(b"%window-add @4"), Some(ControlEvent::WindowAdded { window_id: "@4".to_string() }));
+        assert_eq!(
+            parse_line(b"%layout-change @1 b25d,80x24,0,0,0 b25d,80x24,0,0,0 *"),
+            Some(ControlEvent::LayoutChanged { window_id: "@1".to_string() })
+        );
+        assert_eq!(parse_line(b"%exit"), Some(ControlEvent::Exit { reason: None }));
+        assert_eq!(parse_line(b"%exit detached"), Some(ControlEvent::Exit { reason: Some("detached".to_string()) }));
+        assert_eq!(parse_line(b"%begin 1700000000 12 1"), None);
+    }
+
+    #[test]
+    fn test_unescape_leaves_partial_escapes_alone() {
+        assert_eq!(unescape_output(br"a\01"), b"a\\01".to_vec());
+        assert_eq!(unescape_output(br"\999"), b"\\999".to_vec());
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use tokio::io::AsyncWriteExt;
+
+/// Default cap on the output history kept in memory per session
+pub const DEFAULT_LIMIT_BYTES: usize = 1024 * 1024;
+
+/// Smallest configurable cap, so rotation doesn't run on every write
+pub const MIN_LIMIT_BYTES: usize = 64 * 1024;
+
+/// Once `buffer` grows past `limit`, cut it back to its newest three quarters
+/// of `limit` and return the bytes removed. Trimming below the limit means
+/// rotation happens in chunks rather than on every write.
+pub fn trim_to_limit(buffer: &mut Vec<u8>, limit: usize) -> Option<Vec<u8>> {
+    if buffer.len() <= limit {
+        return None;
+    }
+    let retain = limit / 4 * 3;
+    Some(buffer.drain(..buffer.len() - retain).collect())
+}
+
+/// File holding output trimmed from a session's in-memory history
+pub fn archive_path(session_id: &str) -> PathBuf {
+    let file_name: String = session_id.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    std::env::temp_dir().join("ninjasquad-tmux").join(format!("{}.log", file_name))
+}
+
+/// Append trimmed history to the session's archive, which is itself kept
+/// under `limit` bytes by dropping its oldest output
+pub async fn archive(session_id: &str, overflow: &[u8], limit: usize) -> Result<(), String> {
+    let path = archive_path(session_id);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    file.write_all(overflow).await
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    let size = file.metadata().await.map(|m| m.len() as usize).unwrap_or(0);
+    drop(file);
+
+    if size > limit {
+        let mut contents = read_archive(session_id).await?;
+        trim_to_limit(&mut contents, limit);
+        tokio::fs::write(&path, contents).await
+            .map_err(|e| format!("Failed to rotate {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Archived history of a session, empty when nothing was trimmed yet
+pub async fn read_archive(session_id: &str) -> Result<Vec<u8>, String> {
+    let path = archive_path(session_id);
+    match tokio::fs::read(&path).await {
+        Ok(contents) => Ok(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+pub async fn remove_archive(session_id: &str) {
+    let _ = tokio::fs::remove_file(archive_path(session_id)).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_to_limit_keeps_newest_bytes() {
+        let mut buffer = b"0123456789".to_vec();
+        assert_eq!(trim_to_limit(&mut buffer, 16), None);
+
+        let removed = trim_to_limit(&mut buffer, 8).unwrap();
+        assert_eq!(removed, b"0123".to_vec());
+        assert_eq!(buffer, b"456789".to_vec());
+    }
+
+    #[test]
+    fn test_archive_path_is_sanitized() {
+        let path = archive_path("ninja-app/../x");
+        assert_eq!(path.file_name().unwrap(), "ninja-app____x.log");
+    }
+}
@@ -1,14 +1,31 @@
-use super::types::{TmuxSession, TmuxOutput};
+use super::capture;
+use super::cli;
+use super::control::{self, ControlClient, ControlEvent};
+use super::history;
+use super::types::{TmuxSession, TmuxSessionInfo, TmuxOutput, TmuxPane, TmuxWindow, TmuxKey, CaptureOptions, PaneCapture, TmuxHealthEvent, TmuxHealthStatus, PaneSearchMatch};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncBufReadExt;
 use tokio::sync::RwLock;
-use tokio::process::Command;
-use uuid::Uuid;
 use chrono::Utc;
 use tauri::{AppHandle, Emitter};
 
+/// Lines shown either side of a search match
+const SEARCH_CONTEXT_LINES: usize = 2;
+
+/// Command run in a session's agent pane
+const AGENT_COMMAND: &str = "unset npm_config_prefix && opencode";
+
+#[derive(Clone)]
 pub struct TmuxManager {
     sessions: Arc<RwLock<HashMap<String, TmuxSession>>>,
+    controls: Arc<RwLock<HashMap<String, ControlClient>>>,
+    history: Arc<RwLock<HashMap<String, Vec<u8>>>>,
+    /// Output history kept in memory per session for AI context; older
+    /// output is rotated into an archive file
+    history_limit: Arc<AtomicUsize>,
     app_handle: Option<AppHandle>,
 }
 
@@ -16,6 +33,9 @@ impl TmuxManager {
     pub fn new() -> Self {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
+            controls: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(HashMap::new())),
+            history_limit: Arc::new(AtomicUsize::new(history::DEFAULT_LIMIT_BYTES)),
             app_handle: None,
         }
     }
@@ -24,26 +44,52 @@ impl TmuxManager {
         self.app_handle = Some(handle);
     }
 
-    pub async fn create_session(&self, project_path: &str) -> Result<TmuxSession, String> {
-        let session_id = format!("tmux-{}", Uuid::new_v4().to_string().chars().take(8).collect::<String>());
-        let session_name = session_id.clone();
+    /// Start an opencode session for the project, named `ninja-<project-slug>`,
+    /// on `host` over SSH or locally. If one is already running for the
+    /// project it is returned instead.
+    pub async fn create_session(&self, project_path: &str, host: Option<&str>) -> Result<TmuxSession, String> {
+        let trimmed_path = project_path.trim_end_matches('/');
+        let tracked = self.sessions.read().await.values()
+            .find(|s| s.project_path.trim_end_matches('/') == trimmed_path && s.host.as_deref() == host)
+            .cloned();
+        if let Some(session) = tracked {
+            return Ok(session);
+        }
 
-        // Create a new tmux session in detached mode running opencode
-        let output = Command::new("tmux")
-            .args(&[
-                "new-session",
-                "-d",
-                "-s", &session_name,
-                "-c", project_path,
-                "unset npm_config_prefix && opencode"
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to create tmux session: {}", e))?;
+        // Reuse a session left running for this project, and avoid clashing
+        // with a same-named project elsewhere on disk
+        let mut session_name = session_name_for(project_path);
+        if let Some(existing) = cli::list_sessions(host).await?.into_iter().find(|s| s.name == session_name) {
+            if existing.path.trim_end_matches('/') == trimmed_path {
+                let mut session = self.attach_session(&session_name, host).await?;
+                session.external = false;
+                if let Some(tracked) = self.sessions.write().await.get_mut(&session.id) {
+                    tracked.external = false;
+                }
+                return Ok(session);
+            }
+            session_name = format!("{}-{}", session_name, path_hash(trimmed_path));
+        }
+        // Session names double as ids, so one tracked for another server can't be reused
+        if self.sessions.read().await.contains_key(&session_name) {
+            session_name = format!("{}-{}", session_name, path_hash(&format!("{}:{}", host.unwrap_or_default(), trimmed_path)));
+        }
+        let session_id = session_name.clone();
 
-        if !output.status.success() {
-            return Err(format!("Failed to create tmux session: {}",
-                String::from_utf8_lossy(&output.stderr)));
+        // Create a new tmux session in detached mode running opencode
+        cli::run(host, &[
+            "new-session",
+            "-d",
+            "-s", &session_name,
+            "-c", project_path,
+            AGENT_COMMAND
+        ])
+        .await
+        .map_err(|e| format!("Failed to create tmux session: {}", e))?;
+
+        // Keep the pane around when opencode exits so the health monitor can respawn it
+        if let Err(e) = cli::run(host, &["set-window-option", "-t", &session_name, "remain-on-exit", "on"]).await {
+            println!("Failed to set remain-on-exit for {}: {}", session_name, e);
         }
 
         let session = TmuxSession {
@@ -54,184 +100,320 @@ impl TmuxManager {
             is_active: true,
             window_count: 1,
             pane_count: 1,
+            external: false,
+            cols: None,
+            rows: None,
+            host: host.map(str::to_string),
+            agent_pane: None,
+            auto_restart: false,
         };
 
         // Store the session
         self.sessions.write().await.insert(session_id.clone(), session.clone());
 
-        // Start control mode monitoring
+        // Start control mode monitoring, which also records the agent pane
         self.start_control_mode(&session_id).await?;
 
+        Ok(self.sessions.read().await.get(&session_id).cloned().unwrap_or(session))
+    }
+
+    /// Sessions on the local or a remote tmux server, including ones the app didn't create
+    pub async fn discover_sessions(&self, host: Option<&str>) -> Result<Vec<TmuxSessionInfo>, String> {
+        let sessions = self.sessions.read().await;
+        let mut discovered = cli::list_sessions(host).await?;
+        for info in &mut discovered {
+            info.tracked = sessions.get(&info.name).is_some_and(|s| s.host.as_deref() == host);
+        }
+        Ok(discovered)
+    }
+
+    /// Register an existing tmux session and start monitoring its output
+    pub async fn attach_session(&self, name: &str, host: Option<&str>) -> Result<TmuxSession, String> {
+        if let Some(session) = self.sessions.read().await.get(name) {
+            if session.host.as_deref() != host {
+                return Err(format!("A tmux session named {} is already tracked on another host", name));
+            }
+            return Ok(session.clone());
+        }
+
+        let info = cli::list_sessions(host).await?
+            .into_iter()
+            .find(|s| s.name == name)
+            .ok_or_else(|| format!("tmux session {} not found", name))?;
+
+        let pane_count = cli::run(host, &["list-panes", "-s", "-t", name, "-F", "#{pane_id}"]).await
+            .map(|output| output.lines().count() as u32)
+            .unwrap_or(1);
+
+        let session = TmuxSession {
+            id: info.name.clone(),
+            name: info.name,
+            project_path: info.path,
+            created_at: info.created_at,
+            is_active: true,
+            window_count: info.windows,
+            pane_count,
+            external: true,
+            cols: None,
+            rows: None,
+            host: host.map(str::to_string),
+            agent_pane: None,
+            auto_restart: false,
+        };
+
+        self.sessions.write().await.insert(session.id.clone(), session.clone());
+        self.start_control_mode(&session.id).await?;
+
         Ok(session)
     }
 
+    /// Track sessions saved by a previous run that are still alive and
+    /// resume monitoring them. Returns the sessions that were restored.
+    pub async fn restore_sessions(&self, saved: Vec<TmuxSession>) -> Vec<TmuxSession> {
+        let mut restored = Vec::new();
+
+        for mut session in saved {
+            if !cli::has_session(session.host.as_deref(), &session.name).await {
+                continue;
+            }
+
+            session.is_active = true;
+            self.sessions.write().await.insert(session.id.clone(), session.clone());
+
+            if let Err(e) = self.start_control_mode(&session.id).await {
+                println!("Failed to resume monitoring tmux session {}: {}", session.id, e);
+            }
+            let _ = self.refresh_counts(&session.id).await;
+
+            if let Some(session) = self.sessions.read().await.get(&session.id) {
+                restored.push(session.clone());
+            }
+        }
+
+        restored
+    }
+
+    /// Stop monitoring a session without killing it
+    pub async fn detach_session(&self, session_id: &str) -> Result<(), String> {
+        if self.sessions.write().await.remove(session_id).is_none() {
+            return Err(format!("Session {} not found", session_id));
+        }
+
+        self.stop_control_mode(session_id).await;
+        Ok(())
+    }
+
+    /// Attach a control-mode client to the session and forward pane output
+    /// as `tmux-output` events. Output of the session's first pane is also
+    /// kept in memory as context history for `capture_pane`.
     async fn start_control_mode(&self, session_id: &str) -> Result<(), String> {
-        // Use a different approach - write to a file with tail -f monitoring
-        let output_file = format!("/tmp/tmux-{}.log", session_id);
-
-        // Remove old file if exists
-        let _ = tokio::fs::remove_file(&output_file).await;
-
-        // Create the output file
-        tokio::fs::write(&output_file, b"").await
-            .map_err(|e| format!("Failed to create output file: {}", e))?;
-
-        // Start piping the pane output directly to a file
-        // This should be unbuffered by default
-        Command::new("tmux")
-            .args(&[
-                "pipe-pane",
-                "-t", session_id,
-                "-o",  // Output mode
-                &format!("cat >> {}", output_file)
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to start pipe-pane: {}", e))?;
+        let host = self.host(session_id).await;
+        let primary_pane = cli::run(host.as_deref(), &["display-message", "-p", "-t", session_id, "#{pane_id}"]).await?
+            .trim()
+            .to_string();
+
+        // The first pane of a session the app started is the one running opencode
+        if let Some(session) = self.sessions.write().await.get_mut(session_id) {
+            if !session.external && session.agent_pane.is_none() {
+                session.agent_pane = Some(primary_pane.clone());
+            }
+        }
+
+        let (mut client, mut reader) = ControlClient::attach(host.as_deref(), session_id)?;
+        // Don't let the control client's default 80x24 shrink the session's windows
+        let _ = client.send_command("refresh-client -f ignore-size").await;
+        let client_pid = client.pid();
 
-        // Start monitoring the output file
-        let session_id_clone = session_id.to_string();
+        if let Some(previous) = self.controls.write().await.insert(session_id.to_string(), client) {
+            previous.detach().await;
+        }
+        self.history.write().await.insert(session_id.to_string(), Vec::new());
+
+        let session_id = session_id.to_string();
         let app_handle = self.app_handle.clone();
-        let output_file_clone = output_file.clone();
+        let sessions = self.sessions.clone();
+        let controls = self.controls.clone();
+        let history = self.history.clone();
+        let history_limit = self.history_limit.clone();
 
         tokio::spawn(async move {
-            use tokio::process::Command;
-            use tokio::io::{AsyncBufReadExt, BufReader};
-
-            // Use tail -F to follow the file with immediate updates
-            let mut child = match Command::new("tail")
-                .args(&["-F", "-n", "0", &output_file_clone])
-                .stdout(std::process::Stdio::piped())
-                .spawn()
-            {
-                Ok(c) => c,
-                Err(e) => {
-                    println!("Failed to start tail: {}", e);
-                    return;
-                }
-            };
-
-            let stdout = child.stdout.take().expect("Failed to get stdout");
-            let mut reader = BufReader::new(stdout);
-            let mut line = String::new();
+            let mut line = Vec::new();
 
             loop {
-                // Read line by line from tail output
-                match reader.read_line(&mut line).await {
+                line.clear();
+                match reader.read_until(b'\n', &mut line).await {
                     Ok(0) => break, // EOF
-                    Ok(_) => {
-                        if !line.is_empty() {
-                            if let Some(handle) = &app_handle {
-                                let output = TmuxOutput {
-                                    session_id: session_id_clone.clone(),
-                                    content: line.clone(),
-                                    pane_id: "0".to_string(),
-                                    timestamp: Utc::now().to_rfc3339(),
-                                };
-
-                                let _ = handle.emit("tmux-output", output);
+                    Ok(_) => {}
+                    Err(e) => {
+                        println!("Error reading tmux control client output: {}", e);
+                        break;
+                    }
+                }
+
+                match control::parse_line(&line) {
+                    Some(ControlEvent::Output { pane_id, data }) => {
+                        if pane_id == primary_pane {
+                            let limit = history_limit.load(Ordering::Relaxed);
+                            let overflow = history.write().await.get_mut(&session_id).and_then(|buffer| {
+                                buffer.extend_from_slice(&data);
+                                history::trim_to_limit(buffer, limit)
+                            });
+                            if let Some(overflow) = overflow {
+                                if let Err(e) = history::archive(&session_id, &overflow, limit).await {
+                                    println!("Failed to rotate tmux history for {}: {}", session_id, e);
+                                }
                             }
                         }
-                        line.clear();
+
+                        if let Some(handle) = &app_handle {
+                            let output = TmuxOutput {
+                                session_id: session_id.clone(),
+                                content: String::from_utf8_lossy(&data).to_string(),
+                                pane_id,
+                                timestamp: Utc::now().to_rfc3339(),
+                            };
+
+                            let _ = handle.emit("tmux-output", output);
+                        }
                     }
-                    Err(e) => {
-                        println!("Error reading from tail: {}", e);
+                    Some(ControlEvent::WindowAdded { .. })
+                    | Some(ControlEvent::WindowClosed { .. })
+                    | Some(ControlEvent::LayoutChanged { .. }) => {
+                        let _ = refresh_counts(&sessions, &session_id).await;
+                    }
+                    Some(ControlEvent::Exit { reason }) => {
+                        println!("tmux control client for {} exited: {}", session_id, reason.as_deref().unwrap_or("session closed"));
                         break;
                     }
+                    None => {}
                 }
             }
 
-            // Clean up
-            let _ = child.kill().await;
-            let _ = tokio::fs::remove_file(&output_file_clone).await;
-            println!("Output monitoring stopped for session {}", session_id_clone);
+            // Leave a client that has since replaced this one alone
+            let mut controls = controls.write().await;
+            if controls.get(&session_id).is_some_and(|c| c.pid() == client_pid) {
+                controls.remove(&session_id);
+            }
+            drop(controls);
+            println!("Output monitoring stopped for session {}", session_id);
         });
 
         Ok(())
     }
 
-    pub async fn send_keys(&self, session_id: &str, keys: &str) -> Result<(), String> {
-        // Send keys to the tmux session
-        let output = Command::new("tmux")
-            .args(&[
-                "send-keys",
-                "-t", session_id,
-                keys
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to send keys: {}", e))?;
-
-        if !output.status.success() {
-            return Err(format!("Failed to send keys: {}",
-                String::from_utf8_lossy(&output.stderr)));
+    /// Detach the session's control-mode client, if any
+    async fn stop_control_mode(&self, session_id: &str) {
+        if let Some(client) = self.controls.write().await.remove(session_id) {
+            client.detach().await;
         }
+        self.history.write().await.remove(session_id);
+        history::remove_archive(session_id).await;
+    }
+
+    pub async fn send_keys(&self, session_id: &str, pane: Option<&str>, keys: &str) -> Result<(), String> {
+        // Send keys to the tmux session, or one of its panes
+        let target = pane_target(session_id, pane);
+        cli::run(self.host(session_id).await.as_deref(), &[
+            "send-keys",
+            "-t", &target,
+            keys
+        ])
+        .await
+        .map_err(|e| format!("Failed to send keys: {}", e))?;
 
         Ok(())
     }
 
-    pub async fn capture_pane(&self, session_id: &str) -> Result<String, String> {
-        // ALWAYS use capture-pane to get the current terminal state
-        // This gives us what's actually displayed, not the accumulated log
-        let output = Command::new("tmux")
-            .args(&[
-                "capture-pane",
-                "-t", session_id,
-                "-p",  // Print to stdout
-                "-e",  // Include escape sequences (we'll clean them in frontend)
-                "-S", "-1000",  // Get last 1000 lines of scrollback
-                "-E", "-"   // End at last line
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to capture pane: {}", e))?;
+    /// Send a sequence of named keys and literal text. Consecutive named keys
+    /// go out in one `send-keys` call; literal text uses `send-keys -l`.
+    pub async fn send_special_keys(&self, session_id: &str, pane: Option<&str>, keys: &[TmuxKey]) -> Result<(), String> {
+        let target = pane_target(session_id, pane);
+        let host = self.host(session_id).await;
 
-        if !output.status.success() {
-            return Err(format!("Failed to capture pane: {}",
-                String::from_utf8_lossy(&output.stderr)));
+        for batch in key_batches(keys)? {
+            let mut args = vec!["send-keys", "-t", &target];
+            if batch.literal {
+                args.push("-l");
+            }
+            // Keep text such as `-n` from being read as an option
+            args.push("--");
+            args.extend(batch.args.iter().map(|a| a.as_str()));
+            cli::run(host.as_deref(), &args).await?;
         }
+        Ok(())
+    }
 
-        let content = String::from_utf8_lossy(&output.stdout).to_string();
+    /// Capture what the pane currently shows, plus the session's output
+    /// history for AI context when asked for
+    pub async fn capture_pane(&self, session_id: &str, pane: Option<&str>, options: &CaptureOptions) -> Result<PaneCapture, String> {
+        // capture-pane gives what's actually displayed, not the accumulated history
+        let target = pane_target(session_id, pane);
+        let start = match options.max_lines {
+            Some(lines) => format!("-{}", lines),
+            None => "-".to_string(),
+        };
 
-        // For AI context during generation, read from the log file
-        // This gives us the FULL history for context
-        let log_file = format!("/tmp/tmux-{}.log", session_id);
-        if let Ok(log_content) = tokio::fs::read_to_string(&log_file).await {
-            // Return both: current display + separator + full log for context
-            // Frontend will parse this
-            Ok(format!("{}<<<TMUX_SEPARATOR>>>{}", content, log_content))
-        } else {
-            // No log file, just return the capture
-            Ok(content)
+        let mut args = vec!["capture-pane", "-t", &target, "-p", "-S", &start, "-E", "-"];
+        if !options.strip_ansi {
+            args.push("-e"); // Include escape sequences
         }
+
+        let screen = cli::run(self.host(session_id).await.as_deref(), &args)
+            .await
+            .map_err(|e| format!("Failed to capture pane: {}", e))?;
+
+        // The history only follows the session's first pane
+        let history = if options.include_history && pane.is_none() {
+            self.history.read().await.get(session_id).map(|bytes| {
+                let text = String::from_utf8_lossy(bytes);
+                let text = if options.strip_ansi { capture::strip_ansi(&text) } else { text.to_string() };
+                match options.max_lines {
+                    Some(lines) => capture::tail_lines(&text, lines as usize).to_string(),
+                    None => text,
+                }
+            })
+        } else {
+            None
+        };
+
+        Ok(PaneCapture { screen, history })
     }
 
-    pub async fn kill_session(&self, session_id: &str) -> Result<(), String> {
-        // Stop the pipe-pane first
-        let _ = Command::new("tmux")
-            .args(&["pipe-pane", "-t", session_id])
-            .output()
-            .await;
+    /// Cap the in-memory output history of each session at `bytes`
+    pub fn set_history_limit(&self, bytes: usize) -> usize {
+        let limit = bytes.max(history::MIN_LIMIT_BYTES);
+        self.history_limit.store(limit, Ordering::Relaxed);
+        limit
+    }
 
-        // Clean up the output file
-        let output_file = format!("/tmp/tmux-{}.log", session_id);
-        let _ = tokio::fs::remove_file(&output_file).await;
+    /// Output rotated out of the in-memory history, oldest first. At most
+    /// the newest `max_bytes` are returned when given.
+    pub async fn get_truncated_history(&self, session_id: &str, max_bytes: Option<usize>) -> Result<String, String> {
+        let archived = history::read_archive(session_id).await?;
+        let start = max_bytes.map_or(0, |max| archived.len().saturating_sub(max));
+        Ok(String::from_utf8_lossy(&archived[start..]).to_string())
+    }
 
-        // Kill the tmux session
-        let output = Command::new("tmux")
-            .args(&[
-                "kill-session",
-                "-t", session_id
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("Failed to kill session: {}", e))?;
+    /// Search the session's output history, ANSI-stripped, for `pattern`
+    pub async fn search_pane(&self, session_id: &str, pattern: &str, max_matches: usize) -> Result<Vec<PaneSearchMatch>, String> {
+        let history = self.history.read().await;
+        let bytes = history.get(session_id)
+            .ok_or_else(|| format!("Session {} has no output history", session_id))?;
 
-        if !output.status.success() {
+        let text = capture::strip_ansi(&String::from_utf8_lossy(bytes));
+        Ok(capture::search_lines(&text, pattern, max_matches, SEARCH_CONTEXT_LINES))
+    }
+
+    pub async fn kill_session(&self, session_id: &str) -> Result<(), String> {
+        // Detach the control client first
+        self.stop_control_mode(session_id).await;
+
+        // Kill the tmux session
+        let host = self.host(session_id).await;
+        if let Err(e) = cli::run(host.as_deref(), &["kill-session", "-t", session_id]).await {
             // Session might already be gone, which is okay
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if !stderr.contains("session not found") {
-                return Err(format!("Failed to kill session: {}", stderr));
+            if !e.contains("session not found") && !e.contains("can't find session") {
+                return Err(format!("Failed to kill session: {}", e));
             }
         }
 
@@ -249,10 +431,407 @@ impl TmuxManager {
         self.sessions.read().await.contains_key(session_id)
     }
 
-    pub async fn send_command(&self, session_id: &str, command: &str) -> Result<(), String> {
+    pub async fn send_command(&self, session_id: &str, pane: Option<&str>, command: &str) -> Result<(), String> {
         // Send command followed by Enter key
-        self.send_keys(session_id, command).await?;
-        self.send_keys(session_id, "Enter").await?;
+        self.send_keys(session_id, pane, command).await?;
+        self.send_keys(session_id, pane, "Enter").await?;
+        Ok(())
+    }
+
+    /// Open a new window in the session, optionally running `command`
+    pub async fn create_window(&self, session_id: &str, name: Option<&str>, command: Option<&str>) -> Result<TmuxWindow, String> {
+        let project_path = self.project_path(session_id).await?;
+        let target = format!("{}:", session_id);
+
+        let mut args = vec!["new-window", "-d", "-t", &target, "-c", &project_path, "-P", "-F", cli::LIST_WINDOWS_FORMAT];
+        if let Some(name) = name {
+            args.extend(["-n", name]);
+        }
+        if let Some(command) = command {
+            args.push(command);
+        }
+
+        let host = self.host(session_id).await;
+        let output = cli::run(host.as_deref(), &args).await?;
+        let window = cli::parse_list_windows(&output).pop()
+            .ok_or_else(|| format!("Unexpected tmux new-window output: {}", output.trim()))?;
+
+        // Keep new windows at the size the frontend asked for
+        let size = self.sessions.read().await.get(session_id).and_then(|s| s.cols.zip(s.rows));
+        if let Some((cols, rows)) = size {
+            resize_window(host.as_deref(), &window.window_id, cols, rows).await?;
+        }
+
+        self.refresh_counts(session_id).await?;
+        Ok(window)
+    }
+
+    /// Split a pane of the session (its active pane by default). `horizontal`
+    /// places the new pane to the right instead of below.
+    pub async fn split_pane(
+        &self,
+        session_id: &str,
+        pane: Option<&str>,
+        horizontal: bool,
+        percent: Option<u8>,
+        command: Option<&str>,
+    ) -> Result<TmuxPane, String> {
+        let project_path = self.project_path(session_id).await?;
+        let target = pane_target(session_id, pane);
+        let size = percent.map(|p| format!("{}%", p.clamp(1, 99)));
+
+        let mut args = vec!["split-window", "-d", "-t", &target, "-c", &project_path, "-P", "-F", cli::LIST_PANES_FORMAT];
+        if horizontal {
+            args.push("-h");
+        }
+        if let Some(size) = &size {
+            args.extend(["-l", size.as_str()]);
+        }
+        if let Some(command) = command {
+            args.push(command);
+        }
+
+        let output = cli::run(self.host(session_id).await.as_deref(), &args).await?;
+        let pane = cli::parse_list_panes(&output).pop()
+            .ok_or_else(|| format!("Unexpected tmux split-window output: {}", output.trim()))?;
+
+        self.refresh_counts(session_id).await?;
+        Ok(pane)
+    }
+
+    /// Check every active session every `interval` until the app exits
+    pub fn start_health_monitor(&self, interval: Duration) {
+        let manager = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                manager.check_health().await;
+            }
+        });
+    }
+
+    /// Find active sessions whose opencode exited or that died, restarting
+    /// opencode where enabled and marking the rest inactive. Each finding is
+    /// emitted as a `tmux-health` event and returned.
+    pub async fn check_health(&self) -> Vec<TmuxHealthEvent> {
+        let active: Vec<TmuxSession> = self.sessions.read().await.values()
+            .filter(|s| s.is_active)
+            .cloned()
+            .collect();
+        let mut events = Vec::new();
+
+        for session in active {
+            let host = session.host.as_deref();
+            let status = if !cli::has_session(host, &session.name).await {
+                TmuxHealthStatus::SessionDead
+            } else {
+                match &session.agent_pane {
+                    Some(pane) => {
+                        let dead = cli::run(host, &["display-message", "-p", "-t", pane, "#{pane_dead}"]).await;
+                        if !pane_exited(&dead) {
+                            continue;
+                        }
+                        TmuxHealthStatus::AgentExited
+                    }
+                    None => continue,
+                }
+            };
+
+            let restarted = status == TmuxHealthStatus::AgentExited
+                && session.auto_restart
+                && match self.restart_agent(&session).await {
+                    Ok(()) => true,
+                    Err(e) => {
+                        println!("Failed to restart opencode in {}: {}", session.id, e);
+                        false
+                    }
+                };
+
+            if !restarted {
+                if let Some(tracked) = self.sessions.write().await.get_mut(&session.id) {
+                    tracked.is_active = false;
+                }
+                if status == TmuxHealthStatus::SessionDead {
+                    self.stop_control_mode(&session.id).await;
+                }
+            }
+
+            let event = TmuxHealthEvent {
+                session_id: session.id.clone(),
+                status,
+                restarted,
+                timestamp: Utc::now().to_rfc3339(),
+            };
+            if let Some(handle) = &self.app_handle {
+                let _ = handle.emit("tmux-health", event.clone());
+            }
+            events.push(event);
+        }
+
+        events
+    }
+
+    /// Respawn opencode in the session's agent pane
+    async fn restart_agent(&self, session: &TmuxSession) -> Result<(), String> {
+        let pane = session.agent_pane.as_deref()
+            .ok_or_else(|| format!("Session {} has no agent pane", session.id))?;
+
+        cli::run(session.host.as_deref(), &[
+            "respawn-pane", "-k",
+            "-t", pane,
+            "-c", &session.project_path,
+            AGENT_COMMAND
+        ]).await?;
+        Ok(())
+    }
+
+    pub async fn set_auto_restart(&self, session_id: &str, enabled: bool) -> Result<TmuxSession, String> {
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        session.auto_restart = enabled;
+        Ok(session.clone())
+    }
+
+    /// Resize every window of the session so panes match the frontend terminal
+    pub async fn resize_session(&self, session_id: &str, cols: u16, rows: u16) -> Result<(), String> {
+        if cols == 0 || rows == 0 {
+            return Err("Session size must be at least 1x1".to_string());
+        }
+
+        let host = self.host(session_id).await;
+        for window in cli::list_windows(host.as_deref(), session_id).await? {
+            resize_window(host.as_deref(), &window.window_id, cols, rows).await?;
+        }
+
+        let mut sessions = self.sessions.write().await;
+        let session = sessions.get_mut(session_id)
+            .ok_or_else(|| format!("Session {} not found", session_id))?;
+        session.cols = Some(cols);
+        session.rows = Some(rows);
         Ok(())
     }
+
+    pub async fn kill_pane(&self, session_id: &str, pane: &str) -> Result<(), String> {
+        let host = self.host(session_id).await;
+        cli::run(host.as_deref(), &["kill-pane", "-t", &pane_target(session_id, Some(pane))]).await?;
+        self.refresh_counts(session_id).await
+    }
+
+    pub async fn list_windows(&self, session_id: &str) -> Result<Vec<TmuxWindow>, String> {
+        cli::list_windows(self.host(session_id).await.as_deref(), session_id).await
+    }
+
+    pub async fn list_panes(&self, session_id: &str) -> Result<Vec<TmuxPane>, String> {
+        cli::list_panes(self.host(session_id).await.as_deref(), session_id).await
+    }
+
+    /// Remote host of a tracked session; untracked sessions are taken to be local
+    async fn host(&self, session_id: &str) -> Option<String> {
+        self.sessions.read().await.get(session_id).and_then(|s| s.host.clone())
+    }
+
+    async fn project_path(&self, session_id: &str) -> Result<String, String> {
+        self.sessions.read().await.get(session_id)
+            .map(|s| s.project_path.clone())
+            .ok_or_else(|| format!("Session {} not found", session_id))
+    }
+
+    async fn refresh_counts(&self, session_id: &str) -> Result<(), String> {
+        refresh_counts(&self.sessions, session_id).await
+    }
+}
+
+/// Re-read window and pane counts from tmux
+async fn refresh_counts(sessions: &RwLock<HashMap<String, TmuxSession>>, session_id: &str) -> Result<(), String> {
+    let host = sessions.read().await.get(session_id).and_then(|s| s.host.clone());
+    let windows = cli::list_windows(host.as_deref(), session_id).await?;
+
+    if let Some(session) = sessions.write().await.get_mut(session_id) {
+        session.window_count = windows.len() as u32;
+        session.pane_count = windows.iter().map(|w| w.pane_count).sum();
+    }
+    Ok(())
+}
+
+/// Whether `display-message #{pane_dead}` shows the pane's process exited,
+/// or that the pane is gone altogether
+fn pane_exited(display: &Result<String, String>) -> bool {
+    match display {
+        Ok(output) => output.trim() == "1",
+        Err(e) => e.contains("can't find pane"),
+    }
+}
+
+struct KeyBatch {
+    literal: bool,
+    args: Vec<String>,
+}
+
+fn key_batches(keys: &[TmuxKey]) -> Result<Vec<KeyBatch>, String> {
+    let mut batches: Vec<KeyBatch> = Vec::new();
+
+    for key in keys {
+        if key.literal {
+            batches.push(KeyBatch { literal: true, args: vec![key.key.clone()] });
+            continue;
+        }
+
+        let name = key.tmux_name()?;
+        match batches.last_mut() {
+            Some(batch) if !batch.literal => batch.args.push(name),
+            _ => batches.push(KeyBatch { literal: false, args: vec![name] }),
+        }
+    }
+    Ok(batches)
+}
+
+/// `resize-window` also switches the window to a manual size, so attached
+/// clients no longer shrink it back
+async fn resize_window(host: Option<&str>, window_id: &str, cols: u16, rows: u16) -> Result<(), String> {
+    let args = resize_window_args(window_id, cols, rows);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    cli::run(host, &args).await.map(|_| ())
+}
+
+fn resize_window_args(window_id: &str, cols: u16, rows: u16) -> Vec<String> {
+    ["resize-window", "-t", window_id, "-x", &cols.to_string(), "-y", &rows.to_string()]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// Deterministic session name for a project directory
+fn session_name_for(project_path: &str) -> String {
+    let dir_name = std::path::Path::new(project_path.trim_end_matches('/'))
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let slug = dir_name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() { "ninja-project".to_string() } else { format!("ninja-{}", slug) }
+}
+
+/// Short stable suffix distinguishing projects with the same directory name
+fn path_hash(path: &str) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    path.hash(&mut hasher);
+    format!("{:06x}", hasher.finish() & 0xff_ffff)
+}
+
+/// tmux target for a pane of a session. Pane ids like `%3` are unique across
+/// the server; anything else (`1.0`, `2`) is taken relative to the session.
+fn pane_target(session_id: &str, pane: Option<&str>) -> String {
+    match pane {
+        Some(pane) if pane.starts_with('%') => pane.to_string(),
+        Some(pane) => format!("{}:{}", session_id, pane),
+        None => session_id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_name_for() {
+        assert_eq!(session_name_for("/Users/dev/My Project.v2/"), "ninja-my-project-v2");
+        assert_eq!(session_name_for("/srv/api"), "ninja-api");
+        assert_eq!(session_name_for("/"), "ninja-project");
+    }
+
+    #[test]
+    fn test_path_hash_is_stable() {
+        assert_eq!(path_hash("/a/api"), path_hash("/a/api"));
+        assert_ne!(path_hash("/a/api"), path_hash("/b/api"));
+        assert_eq!(path_hash("/a/api").len(), 6);
+    }
+
+    #[test]
+    fn test_pane_exited() {
+        assert!(pane_exited(&Ok("1\n".to_string())));
+        assert!(!pane_exited(&Ok("0\n".to_string())));
+        assert!(pane_exited(&Err("tmux display-message failed: can't find pane: %9".to_string())));
+        assert!(!pane_exited(&Err("tmux display-message failed: lost server".to_string())));
+    }
+
+    fn key(name: &str) -> TmuxKey {
+        TmuxKey { key: name.to_string(), ctrl: false, alt: false, shift: false, literal: false }
+    }
+
+    #[test]
+    fn test_tmux_key_names() {
+        assert_eq!(TmuxKey { ctrl: true, ..key("c") }.tmux_name().unwrap(), "C-c");
+        assert_eq!(TmuxKey { alt: true, ..key("left") }.tmux_name().unwrap(), "M-Left");
+        assert_eq!(TmuxKey { shift: true, ..key("Up") }.tmux_name().unwrap(), "S-Up");
+        assert_eq!(TmuxKey { shift: true, ..key("tab") }.tmux_name().unwrap(), "BTab");
+        assert_eq!(TmuxKey { shift: true, ..key("a") }.tmux_name().unwrap(), "A");
+        assert_eq!(key("PageUp").tmux_name().unwrap(), "PPage");
+        assert_eq!(key("f10").tmux_name().unwrap(), "F10");
+        assert!(key("Hyper").tmux_name().is_err());
+    }
+
+    #[test]
+    fn test_key_batches_group_named_keys() {
+        let keys = vec![
+            TmuxKey { ctrl: true, ..key("u") },
+            TmuxKey { literal: true, ..key("Enter") },
+            key("Escape"),
+            key("Enter"),
+        ];
+
+        let batches = key_batches(&keys).unwrap();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].args, vec!["C-u"]);
+        assert!(batches[1].literal);
+        assert_eq!(batches[1].args, vec!["Enter"]);
+        assert_eq!(batches[2].args, vec!["Escape", "Enter"]);
+    }
+
+    #[test]
+    fn test_resize_window_args() {
+        assert_eq!(
+            resize_window_args("@3", 120, 40),
+            vec!["resize-window", "-t", "@3", "-x", "120", "-y", "40"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resize_session_rejects_empty_sizes() {
+        let manager = TmuxManager::new();
+
+        for (cols, rows) in [(0, 40), (120, 0)] {
+            let error = manager.resize_session("ninja", cols, rows).await.unwrap_err();
+            assert_eq!(error, "Session size must be at least 1x1");
+        }
+    }
+
+    #[test]
+    fn test_session_size_round_trips() {
+        let json = r#"{"id":"ninja","name":"ninja","project_path":"/work","created_at":"","is_active":true,"window_count":1,"pane_count":1}"#;
+        let mut session: TmuxSession = serde_json::from_str(json).unwrap();
+        assert_eq!((session.cols, session.rows), (None, None));
+
+        session.cols = Some(120);
+        session.rows = Some(40);
+        let session: TmuxSession = serde_json::from_str(&serde_json::to_string(&session).unwrap()).unwrap();
+        assert_eq!((session.cols, session.rows), (Some(120), Some(40)));
+    }
+
+    #[test]
+    fn test_pane_target() {
+        assert_eq!(pane_target("ninja", None), "ninja");
+        assert_eq!(pane_target("ninja", Some("%7")), "%7");
+        assert_eq!(pane_target("ninja", Some("1.2")), "ninja:1.2");
+    }
 }
\ No newline at end of file
@@ -1,3 +1,7 @@
+pub mod capture;
+pub mod cli;
+pub mod control;
+pub mod history;
 pub mod manager;
 pub mod types;
 
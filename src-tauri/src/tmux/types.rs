@@ -9,6 +9,163 @@ pub struct TmuxSession {
     pub is_active: bool,
     pub window_count: u32,
     pub pane_count: u32,
+    /// Session started outside the app and attached to; detaching leaves it running
+    #[serde(default)]
+    pub external: bool,
+    /// Window size requested by the frontend, applied to every window
+    #[serde(default)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    pub rows: Option<u16>,
+    /// SSH destination of a remote tmux server; `None` for the local one
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Pane running opencode, watched by the health monitor
+    #[serde(default)]
+    pub agent_pane: Option<String>,
+    /// Respawn opencode in its pane when it exits
+    #[serde(default)]
+    pub auto_restart: bool,
+}
+
+/// One key press for `send-keys`, or a run of literal text when `literal` is set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TmuxKey {
+    /// Key name such as `Enter`, `Escape`, `Up`, `F5` or a single character
+    pub key: String,
+    #[serde(default)]
+    pub ctrl: bool,
+    #[serde(default)]
+    pub alt: bool,
+    #[serde(default)]
+    pub shift: bool,
+    /// Send `key` as text rather than interpreting it as a key name
+    #[serde(default)]
+    pub literal: bool,
+}
+
+impl TmuxKey {
+    /// The tmux key name, e.g. `C-c`, `M-Left` or `PPage`
+    pub fn tmux_name(&self) -> Result<String, String> {
+        let base = match self.key.to_lowercase().as_str() {
+            "enter" | "return" => "Enter".to_string(),
+            "escape" | "esc" => "Escape".to_string(),
+            "tab" if self.shift => return Ok("BTab".to_string()),
+            "tab" => "Tab".to_string(),
+            "backspace" => "BSpace".to_string(),
+            "space" => "Space".to_string(),
+            "up" | "arrowup" => "Up".to_string(),
+            "down" | "arrowdown" => "Down".to_string(),
+            "left" | "arrowleft" => "Left".to_string(),
+            "right" | "arrowright" => "Right".to_string(),
+            "home" => "Home".to_string(),
+            "end" => "End".to_string(),
+            "pageup" => "PPage".to_string(),
+            "pagedown" => "NPage".to_string(),
+            "insert" => "IC".to_string(),
+            "delete" => "DC".to_string(),
+            name if name.len() > 1 && name.starts_with('f') && name[1..].parse::<u8>().is_ok_and(|n| (1..=12).contains(&n)) => {
+                name.to_uppercase()
+            }
+            _ if self.key.chars().count() == 1 => self.key.clone(),
+            _ => return Err(format!("Unknown key: {}", self.key)),
+        };
+
+        let mut name = String::new();
+        if self.ctrl {
+            name.push_str("C-");
+        }
+        if self.alt {
+            name.push_str("M-");
+        }
+        // Shift is part of the character itself for single characters
+        if self.shift && base.chars().count() > 1 {
+            name.push_str("S-");
+        }
+        if self.shift && base.chars().count() == 1 {
+            name.push_str(&base.to_uppercase());
+        } else {
+            name.push_str(&base);
+        }
+        Ok(name)
+    }
+}
+
+/// How `capture_pane` should capture a pane
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureOptions {
+    /// Remove escape sequences and control characters
+    pub strip_ansi: bool,
+    /// Also return the session's accumulated output history
+    pub include_history: bool,
+    /// Lines of scrollback to capture, and of history to return
+    pub max_lines: Option<u32>,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            strip_ansi: false,
+            include_history: true,
+            max_lines: Some(1000),
+        }
+    }
+}
+
+/// Result of `capture_pane`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaneCapture {
+    /// The pane's visible screen and scrollback
+    pub screen: String,
+    /// Output history of the session's first pane, when requested and monitored
+    pub history: Option<String>,
+}
+
+/// A line of a session's output history matching a search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaneSearchMatch {
+    /// 1-based line number within the retained history
+    pub line_number: usize,
+    pub line: String,
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
+}
+
+/// A window of a tmux session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TmuxWindow {
+    /// Server-wide window id such as `@2`
+    pub window_id: String,
+    pub index: u32,
+    pub name: String,
+    pub active: bool,
+    pub pane_count: u32,
+}
+
+/// A pane of a tmux session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TmuxPane {
+    /// Server-wide pane id such as `%5`, usable as a target on its own
+    pub pane_id: String,
+    pub window_index: u32,
+    pub pane_index: u32,
+    pub active: bool,
+    pub current_command: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A session reported by `tmux list-sessions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmuxSessionInfo {
+    pub name: String,
+    pub windows: u32,
+    pub attached_clients: u32,
+    pub created_at: String,
+    pub path: String,
+    /// Already registered with the TmuxManager
+    pub tracked: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +176,25 @@ pub struct TmuxOutput {
     pub timestamp: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TmuxHealthStatus {
+    /// opencode exited but the session is still running
+    AgentExited,
+    /// The tmux session no longer exists
+    SessionDead,
+}
+
+/// Emitted as `tmux-health` when the watchdog finds a session unhealthy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmuxHealthEvent {
+    pub session_id: String,
+    pub status: TmuxHealthStatus,
+    /// opencode was respawned and the session is active again
+    pub restarted: bool,
+    pub timestamp: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TmuxEvent {
     OutputUpdate(TmuxOutput),
@@ -0,0 +1,70 @@
+/// Build the line to type into the pane for `command`. The command runs
+/// between two `printf` calls that assemble unique sentinels at runtime, so the
+/// echoed command line itself never matches them.
+pub fn wrap_command(command: &str, marker_id: &str) -> String {
+    format!(
+        "printf '__NS_%s_%s__\\n' START {id}; {command}; printf '\\n__NS_%s_%s__:%s\\n' END {id} \"$?\"\r",
+        id = marker_id,
+        command = command.trim_end(),
+    )
+}
+
+/// Extract the output and exit code between the sentinels for `marker_id`.
+/// Returns `None` until the end sentinel has been printed.
+pub fn extract_output(pane_text: &str, marker_id: &str) -> Option<(String, i32)> {
+    let start_marker = format!("__NS_START_{}__", marker_id);
+    let end_marker = format!("__NS_END_{}__:", marker_id);
+
+    let lines: Vec<&str> = pane_text.lines().collect();
+    let start = lines.iter().rposition(|l| l.trim_end() == start_marker)?;
+    let end_offset = lines[start + 1..].iter().position(|l| l.starts_with(&end_marker))?;
+    let end = start + 1 + end_offset;
+
+    let exit_code = lines[end][end_marker.len()..].trim().parse().unwrap_or(-1);
+
+    let mut output = lines[start + 1..end].join("\n");
+    // The end printf starts with a newline so output without one still ends cleanly
+    while output.ends_with('\n') {
+        output.pop();
+    }
+
+    Some((output, exit_code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapped_command_does_not_contain_markers() {
+        let wrapped = wrap_command("ls -la", "abc123");
+        assert!(!wrapped.contains("__NS_START_abc123__"));
+        assert!(!wrapped.contains("__NS_END_abc123__"));
+        assert!(wrapped.ends_with('\r'));
+    }
+
+    #[test]
+    fn test_extract_output_between_markers() {
+        let pane = "\
+$ printf '__NS_%s_%s__\\n' START abc; ls; printf ...
+__NS_START_abc__
+file1
+file2
+
+__NS_END_abc__:0
+$ ";
+        assert_eq!(extract_output(pane, "abc"), Some(("file1\nfile2".to_string(), 0)));
+    }
+
+    #[test]
+    fn test_extract_output_waits_for_end_marker() {
+        let pane = "__NS_START_abc__\nstill running\n";
+        assert_eq!(extract_output(pane, "abc"), None);
+    }
+
+    #[test]
+    fn test_extract_output_reports_exit_code() {
+        let pane = "__NS_START_abc__\nnot found\n\n__NS_END_abc__:127\n";
+        assert_eq!(extract_output(pane, "abc"), Some(("not found".to_string(), 127)));
+    }
+}
@@ -0,0 +1,370 @@
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+use super::mux::{self, MuxSocket};
+use super::probe::{self, WezTermStatus};
+use super::types::{PaneDirection, SplitDirection};
+
+/// Pane dimensions as reported by `wezterm cli list --format json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CliPaneSize {
+    pub rows: u16,
+    pub cols: u16,
+    #[serde(default)]
+    pub pixel_width: u32,
+    #[serde(default)]
+    pub pixel_height: u32,
+}
+
+/// One entry of `wezterm cli list --format json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CliPaneInfo {
+    pub window_id: u64,
+    pub tab_id: u64,
+    pub pane_id: u64,
+    #[serde(default)]
+    pub workspace: String,
+    #[serde(default)]
+    pub size: CliPaneSize,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub cwd: String,
+    #[serde(default)]
+    pub cursor_x: u16,
+    /// Stable row index of the cursor, counted from the start of the scrollback
+    #[serde(default)]
+    pub cursor_y: i64,
+    #[serde(default)]
+    pub left_col: u16,
+    /// Stable row index of the first visible row
+    #[serde(default)]
+    pub top_row: i64,
+    #[serde(default)]
+    pub tab_title: String,
+    #[serde(default)]
+    pub window_title: String,
+    #[serde(default)]
+    pub is_active: bool,
+    #[serde(default)]
+    pub is_zoomed: bool,
+    #[serde(default)]
+    pub tty_name: Option<String>,
+}
+
+impl CliPaneInfo {
+    /// Cursor position relative to the top-left of the visible screen
+    pub fn viewport_cursor(&self) -> (u16, u16) {
+        let row = (self.cursor_y - self.top_row).clamp(0, self.size.rows.saturating_sub(1) as i64);
+        (self.cursor_x, row as u16)
+    }
+
+    /// Visible stable rows as a half-open range
+    pub fn viewport(&self) -> (i64, i64) {
+        (self.top_row, self.top_row + self.size.rows as i64)
+    }
+
+    /// Local path of the pane's cwd, which WezTerm reports as a `file://host/path` URL
+    pub fn cwd_path(&self) -> &str {
+        match self.cwd.strip_prefix("file://") {
+            Some(rest) => rest.find('/').map(|i| &rest[i..]).unwrap_or(rest),
+            None => &self.cwd,
+        }
+    }
+}
+
+/// Options for `wezterm cli spawn`
+#[derive(Debug, Clone, Default)]
+pub struct SpawnOptions {
+    pub new_window: bool,
+    pub cwd: Option<String>,
+    pub domain_name: Option<String>,
+    /// Spawn a new tab in the window containing this pane
+    pub pane_id: Option<String>,
+    pub command: Vec<String>,
+}
+
+/// Parse the JSON printed by `wezterm cli list --format json`
+pub fn parse_list_output(json: &str) -> Result<Vec<CliPaneInfo>, String> {
+    serde_json::from_str(json).map_err(|e| format!("Failed to parse wezterm cli list output: {}", e))
+}
+
+/// Whether `pane_id` is one of the live panes
+pub fn pane_exists(panes: &[CliPaneInfo], pane_id: &str) -> bool {
+    panes.iter().any(|p| p.pane_id.to_string() == pane_id)
+}
+
+/// Typed wrapper around the `wezterm cli` subcommands. Each call runs the
+/// CLI pinned to the mux socket once it has been located.
+#[derive(Debug, Clone)]
+pub struct WezTermCli {
+    binary: String,
+    socket: MuxSocket,
+    /// Set by `probe`; until then every feature is assumed available
+    status: Arc<RwLock<Option<WezTermStatus>>>,
+}
+
+impl Default for WezTermCli {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WezTermCli {
+    pub fn new() -> Self {
+        match probe::locate_binary() {
+            Some(binary) => Self::with_binary(&binary.to_string_lossy()),
+            None => Self::with_binary("wezterm"),
+        }
+    }
+
+    pub fn with_binary(binary: &str) -> Self {
+        Self {
+            binary: binary.to_string(),
+            socket: MuxSocket::new(),
+            status: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn binary(&self) -> &str {
+        &self.binary
+    }
+
+    /// Detect whether the binary exists and which subcommands it supports.
+    /// The result is shared with every clone of this client.
+    pub async fn probe(&self) -> WezTermStatus {
+        let binary = Some(PathBuf::from(&self.binary))
+            .filter(|path| path.is_file())
+            .or_else(probe::locate_binary);
+        let status = match binary {
+            Some(binary) => probe::probe(&binary).await,
+            None => WezTermStatus::not_installed(),
+        };
+
+        *self.status.write().unwrap() = Some(status.clone());
+        status
+    }
+
+    pub fn status(&self) -> Option<WezTermStatus> {
+        self.status.read().unwrap().clone()
+    }
+
+    /// Fail early with a readable error when the probe found no WezTerm
+    pub fn ensure_installed(&self) -> Result<(), String> {
+        match self.status.read().unwrap().as_ref() {
+            Some(status) if !status.installed => {
+                Err(status.error.clone().unwrap_or_else(|| probe::NOT_INSTALLED.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Run `wezterm cli <args>` and return stdout, or stderr as the error
+    pub async fn run(&self, args: &[&str]) -> Result<String, String> {
+        self.ensure_installed()?;
+
+        let mut command = Command::new(&self.binary);
+        if let Some(socket) = self.socket.resolve().await {
+            command.env(mux::SOCKET_ENV, socket);
+        }
+
+        let output = command
+            .arg("cli")
+            .args(args)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run wezterm cli {}: {}", args.first().unwrap_or(&""), e))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).to_string())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // The mux may have restarted on a new socket
+            if mux::is_connection_error(&stderr) {
+                self.socket.invalidate();
+            }
+
+            Err(format!("wezterm cli {} failed: {}", args.first().unwrap_or(&""), stderr.trim()))
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<CliPaneInfo>, String> {
+        let stdout = self.run(&["list", "--format", "json"]).await?;
+        parse_list_output(&stdout)
+    }
+
+    /// Whether the mux server is reachable
+    pub async fn is_running(&self) -> bool {
+        self.list().await.is_ok()
+    }
+
+    pub async fn find_pane(&self, pane_id: &str) -> Result<Option<CliPaneInfo>, String> {
+        Ok(self.list().await?.into_iter().find(|p| p.pane_id.to_string() == pane_id))
+    }
+
+    /// Spawn a pane and return its pane id
+    pub async fn spawn(&self, options: &SpawnOptions) -> Result<String, String> {
+        let mut args = vec!["spawn"];
+        if options.new_window {
+            args.push("--new-window");
+        }
+        if let Some(cwd) = &options.cwd {
+            args.extend(["--cwd", cwd.as_str()]);
+        }
+        if let Some(domain_name) = &options.domain_name {
+            args.extend(["--domain-name", domain_name.as_str()]);
+        }
+        if let Some(pane_id) = &options.pane_id {
+            args.extend(["--pane-id", pane_id.as_str()]);
+        }
+        if !options.command.is_empty() {
+            args.push("--");
+            args.extend(options.command.iter().map(|s| s.as_str()));
+        }
+
+        let pane_id = self.run(&args).await?.trim().to_string();
+        if pane_id.is_empty() {
+            return Err("wezterm cli spawn did not return a pane id".to_string());
+        }
+        Ok(pane_id)
+    }
+
+    /// Send text to a pane. With `no_paste` the text is sent as if typed
+    /// rather than as a bracketed paste.
+    pub async fn send_text(&self, pane_id: &str, text: &str, no_paste: bool) -> Result<(), String> {
+        let mut args = vec!["send-text", "--pane-id", pane_id];
+        if no_paste {
+            args.push("--no-paste");
+        }
+        args.push(text);
+        self.run(&args).await.map(|_| ())
+    }
+
+    /// Capture pane text. Line numbers are relative to the top of the
+    /// viewport; negative values reach into the scrollback.
+    pub async fn get_text(
+        &self,
+        pane_id: &str,
+        escapes: bool,
+        start_line: Option<i64>,
+        end_line: Option<i64>,
+    ) -> Result<String, String> {
+        let start = start_line.map(|l| l.to_string());
+        let end = end_line.map(|l| l.to_string());
+
+        // Older releases don't know --escapes; fall back to plain text
+        let escapes = escapes && self.status().is_none_or(|s| s.capabilities.get_text_escapes);
+
+        let mut args = vec!["get-text", "--pane-id", pane_id];
+        if escapes {
+            args.push("--escapes");
+        }
+        if let Some(start) = &start {
+            args.extend(["--start-line", start.as_str()]);
+        }
+        if let Some(end) = &end {
+            args.extend(["--end-line", end.as_str()]);
+        }
+        self.run(&args).await
+    }
+
+    /// Split a pane and return the new pane's id
+    pub async fn split_pane(
+        &self,
+        pane_id: &str,
+        direction: SplitDirection,
+        percent: u8,
+        cwd: Option<&str>,
+        command: &[String],
+    ) -> Result<String, String> {
+        let percent = percent.clamp(1, 99).to_string();
+        let mut args = vec!["split-pane", "--pane-id", pane_id, direction.cli_flag(), "--percent", percent.as_str()];
+        if let Some(cwd) = cwd {
+            args.extend(["--cwd", cwd]);
+        }
+        if !command.is_empty() {
+            args.push("--");
+            args.extend(command.iter().map(|s| s.as_str()));
+        }
+
+        let new_pane_id = self.run(&args).await?.trim().to_string();
+        if new_pane_id.is_empty() {
+            return Err("wezterm cli split-pane did not return a pane id".to_string());
+        }
+        Ok(new_pane_id)
+    }
+
+    /// Grow the pane by `amount` cells in `direction`
+    pub async fn adjust_pane_size(&self, pane_id: &str, direction: PaneDirection, amount: u16) -> Result<(), String> {
+        let amount = amount.to_string();
+        self.run(&["adjust-pane-size", "--pane-id", pane_id, "--amount", amount.as_str(), direction.cli_arg()])
+            .await
+            .map(|_| ())
+    }
+
+    pub async fn activate_pane(&self, pane_id: &str) -> Result<(), String> {
+        self.run(&["activate-pane", "--pane-id", pane_id]).await.map(|_| ())
+    }
+
+    pub async fn kill_pane(&self, pane_id: &str) -> Result<(), String> {
+        self.run(&["kill-pane", "--pane-id", pane_id]).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIST_OUTPUT: &str = r#"[
+      {
+        "window_id": 0, "tab_id": 0, "pane_id": 0, "workspace": "default",
+        "size": { "rows": 24, "cols": 80, "pixel_width": 640, "pixel_height": 384, "dpi": 96 },
+        "title": "zsh", "cwd": "file://my-host/Users/dev/project",
+        "cursor_x": 5, "cursor_y": 3, "cursor_shape": "Default", "cursor_visibility": "Visible",
+        "left_col": 0, "top_row": 0, "tab_title": "", "window_title": "zsh",
+        "is_active": true, "is_zoomed": false, "tty_name": "/dev/ttys001"
+      },
+      { "window_id": 1, "tab_id": 2, "pane_id": 3, "size": { "rows": 40, "cols": 120 } }
+    ]"#;
+
+    #[test]
+    fn test_parse_list_output() {
+        let panes = parse_list_output(LIST_OUTPUT).unwrap();
+
+        assert_eq!(panes.len(), 2);
+        assert_eq!(panes[0].size.cols, 80);
+        assert_eq!((panes[0].cursor_x, panes[0].cursor_y), (5, 3));
+        assert_eq!(panes[0].viewport(), (0, 24));
+        assert_eq!(panes[0].cwd_path(), "/Users/dev/project");
+        assert_eq!((panes[1].window_id, panes[1].tab_id, panes[1].pane_id), (1, 2, 3));
+        assert!(!panes[1].is_active);
+    }
+
+    #[test]
+    fn test_viewport_cursor_is_relative_to_top_row() {
+        let mut pane = parse_list_output(LIST_OUTPUT).unwrap().remove(0);
+        pane.top_row = 500;
+        pane.cursor_y = 510;
+
+        assert_eq!(pane.viewport_cursor(), (5, 10));
+        assert_eq!(pane.viewport(), (500, 524));
+    }
+
+    #[test]
+    fn test_pane_exists() {
+        let panes = parse_list_output(LIST_OUTPUT).unwrap();
+
+        assert!(pane_exists(&panes, "0"));
+        assert!(pane_exists(&panes, "3"));
+        assert!(!pane_exists(&panes, "1"));
+        assert!(!pane_exists(&[], "0"));
+    }
+
+    #[test]
+    fn test_parse_list_output_rejects_garbage() {
+        assert!(parse_list_output("not json").is_err());
+    }
+}
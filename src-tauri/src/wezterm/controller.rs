@@ -1,26 +1,45 @@
+use super::capture;
+use super::keys::{self, KeyModifier};
+use super::cli::{pane_exists, CliPaneInfo, SpawnOptions, WezTermCli};
 use super::types::*;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::process::Command;
-use uuid::Uuid;
 use chrono::Utc;
+use uuid::Uuid;
+
+/// How long to wait for a captured command to print its end sentinel
+const CAPTURE_TIMEOUT: tokio::time::Duration = tokio::time::Duration::from_secs(30);
+/// Scrollback lines searched for the sentinels
+const CAPTURE_SCROLLBACK_LINES: i64 = 2000;
 
 pub struct WezTermController {
     domains: Arc<RwLock<HashMap<String, WezTermDomain>>>,
     sessions: Arc<RwLock<HashMap<String, WezTermSession>>>,
     windows: Arc<RwLock<HashMap<String, WezTermWindow>>>,
+    cli: WezTermCli,
 }
 
 impl WezTermController {
     pub fn new() -> Self {
+        Self::with_cli(WezTermCli::new())
+    }
+
+    /// Share a client (and its probe result) with other WezTerm managers
+    pub fn with_cli(cli: WezTermCli) -> Self {
         Self {
             domains: Arc::new(RwLock::new(HashMap::new())),
             sessions: Arc::new(RwLock::new(HashMap::new())),
             windows: Arc::new(RwLock::new(HashMap::new())),
+            cli,
         }
     }
 
+    pub fn cli(&self) -> &WezTermCli {
+        &self.cli
+    }
+
     pub async fn create_ssh_domain(&self, name: &str, address: &str, username: &str) -> Result<WezTermDomain, String> {
         let domain = WezTermDomain {
             name: name.to_string(),
@@ -40,7 +59,7 @@ impl WezTermController {
 
         if let Some(domain) = domains.get_mut(domain_name) {
             // Execute wezterm connect command
-            let output = Command::new("wezterm")
+            let output = Command::new(self.cli.binary())
                 .arg("connect")
                 .arg(&domain.name)
                 .arg("--")
@@ -80,65 +99,74 @@ impl WezTermController {
             }
 
             // Use wezterm cli to spawn a new pane
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("spawn")
-                .arg("--domain-name")
-                .arg(&domain.name)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to spawn terminal: {}", e))?;
-
-            if output.status.success() {
-                let pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-                let pane = WezTermPane {
-                    id: if pane_id.is_empty() { Uuid::new_v4().to_string() } else { pane_id },
-                    domain_name: domain_name.to_string(),
-                    title: format!("Terminal {}", domain_name),
-                    is_active: true,
-                };
-
-                // Update session with new pane
-                drop(domains);
-                let mut sessions = self.sessions.write().await;
-                if let Some(session) = sessions.get_mut(domain_name) {
-                    session.panes.push(pane.clone());
-                }
-
-                Ok(pane)
-            } else {
-                Err(format!("Failed to spawn terminal: {}", String::from_utf8_lossy(&output.stderr)))
+            let pane_id = self.cli.spawn(&SpawnOptions {
+                domain_name: Some(domain.name.clone()),
+                ..Default::default()
+            }).await?;
+
+            let pane = WezTermPane {
+                id: pane_id,
+                domain_name: domain_name.to_string(),
+                title: format!("Terminal {}", domain_name),
+                is_active: true,
+            };
+
+            // Update session with new pane
+            drop(domains);
+            let mut sessions = self.sessions.write().await;
+            if let Some(session) = sessions.get_mut(domain_name) {
+                session.panes.push(pane.clone());
             }
+
+            Ok(pane)
         } else {
             Err(format!("Domain {} not found", domain_name))
         }
     }
 
     pub async fn execute_command(&self, pane_id: &str, command: &str) -> Result<CommandResult, String> {
-        // Use wezterm cli to send text to pane
-        let output = Command::new("wezterm")
-            .arg("cli")
-            .arg("send-text")
-            .arg("--pane-id")
-            .arg(pane_id)
-            .arg(command)
-            .output()
-            .await
-            .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-        if output.status.success() {
-            Ok(CommandResult {
+        match self.capture_command_output(pane_id, command).await {
+            Ok((output, 0)) => Ok(CommandResult {
                 success: true,
-                output: format!("{}\nHello World", command), // Simulated output for testing
+                output,
                 error: None,
-            })
-        } else {
-            Ok(CommandResult {
+            }),
+            Ok((output, exit_code)) => Ok(CommandResult {
+                success: false,
+                output,
+                error: Some(format!("Command exited with code {}", exit_code)),
+            }),
+            Err(e) => Ok(CommandResult {
                 success: false,
                 output: String::new(),
-                error: Some(String::from_utf8_lossy(&output.stderr).to_string()),
-            })
+                error: Some(e),
+            }),
+        }
+    }
+
+    /// Run a command in the pane itself and return its output and exit code,
+    /// read back from the pane between unique sentinels
+    pub async fn capture_command_output(&self, pane_id: &str, command: &str) -> Result<(String, i32), String> {
+        let marker_id = Uuid::new_v4().simple().to_string()[..12].to_string();
+
+        self.cli.send_text(pane_id, &capture::wrap_command(command, &marker_id), true).await?;
+
+        let deadline = tokio::time::Instant::now() + CAPTURE_TIMEOUT;
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+            let text = self.cli.get_text(pane_id, false, Some(-CAPTURE_SCROLLBACK_LINES), None).await?;
+            if let Some(result) = capture::extract_output(&text, &marker_id) {
+                return Ok(result);
+            }
+
+            if tokio::time::Instant::now() >= deadline {
+                return Err(format!(
+                    "Timed out after {}s waiting for command output in pane {}",
+                    CAPTURE_TIMEOUT.as_secs(),
+                    pane_id
+                ));
+            }
         }
     }
 
@@ -185,7 +213,7 @@ impl WezTermController {
         // and create a pane that we can control
 
         // First, start WezTerm in daemon mode if not already running
-        let _ = Command::new("wezterm")
+        let _ = Command::new(self.cli.binary())
             .arg("start")
             .arg("--daemonize")
             .output()
@@ -195,26 +223,14 @@ impl WezTermController {
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
         // Use wezterm CLI to create a new pane
-        let output = Command::new("wezterm")
-            .arg("cli")
-            .arg("spawn")
-            .arg("--new-window")
-            .arg("--")
-            .arg("bash")
-            .arg("-c")
-            .arg(&format!("opencode --port {}", port))
-            .output()
-            .await
-            .map_err(|e| format!("Failed to spawn WezTerm pane: {}", e))?;
-
-        if !output.status.success() {
-            return Err(format!("Failed to create WezTerm pane: {}",
-                String::from_utf8_lossy(&output.stderr)));
-        }
+        let pane_id = self.cli.spawn(&SpawnOptions {
+            new_window: true,
+            command: vec!["bash".to_string(), "-c".to_string(), format!("opencode --port {}", port)],
+            ..Default::default()
+        }).await
+        .map_err(|e| format!("Failed to create WezTerm pane: {}", e))?;
 
-        // Parse the pane ID from output
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        println!("WezTerm CLI output: {}", output_str);
+        println!("WezTerm spawned pane {}", pane_id);
 
         // For now, return a success indicator
         // True embedding would require platform-specific window management
@@ -258,7 +274,7 @@ impl WezTermController {
 
         // Use WezTerm to create a new window running the OpenCode TUI
         // Use --always-new-process to force a new window
-        let child = Command::new("wezterm")
+        let child = Command::new(self.cli.binary())
             .arg("start")
             .arg("--always-new-process")  // Force new window
             .arg("--")
@@ -291,34 +307,29 @@ impl WezTermController {
         &self,
         project_id: &str,
         working_dir: &str,
+        layout: Option<&PaneLayout>,
     ) -> Result<WezTermWindow, String> {
-        // Check if WezTerm multiplexer is running
-        let list_result = Command::new("wezterm")
-            .arg("cli")
-            .arg("list")
-            .output()
-            .await;
+        self.cli.ensure_installed()?;
 
-        let mut pane_id = String::new();
+        let command = vec![
+            "bash".to_string(),
+            "-c".to_string(),
+            "unset npm_config_prefix && opencode".to_string(),
+        ];
 
-        if list_result.is_err() || !list_result.unwrap().status.success() {
+        let pane = if !self.cli.is_running().await {
             // Multiplexer not running, start WezTerm with a window
             println!("WezTerm multiplexer not running, starting it...");
 
             // Start WezTerm with initial window running OpenCode
-            let start_output = Command::new("wezterm")
+            Command::new(self.cli.binary())
                 .arg("start")
                 .arg("--cwd")
                 .arg(working_dir)
                 .arg("--")
-                .arg("bash")
-                .arg("-c")
-                .arg("unset npm_config_prefix && opencode")
-                .spawn();
-
-            if start_output.is_err() {
-                return Err(format!("Failed to start WezTerm: {}", start_output.err().unwrap()));
-            }
+                .args(&command)
+                .spawn()
+                .map_err(|e| format!("Failed to start WezTerm: {}", e))?;
 
             // Wait for WezTerm to start and multiplexer to be ready
             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
@@ -336,105 +347,130 @@ impl WezTermController {
             // Additional wait for window to fully appear
             tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
 
-            // Now get the pane ID from the first window
-            let list_output = Command::new("wezterm")
-                .arg("cli")
-                .arg("list")
-                .arg("--format")
-                .arg("json")
-                .output()
-                .await;
-
-            if let Ok(output) = list_output {
-                if output.status.success() {
-                    // Parse JSON to get first pane ID
-                    let json_str = String::from_utf8_lossy(&output.stdout);
-                    // Simple extraction - look for first pane_id in JSON
-                    if let Some(start) = json_str.find("\"pane_id\":") {
-                        let rest = &json_str[start + 10..];
-                        if let Some(end) = rest.find(',') {
-                            pane_id = rest[..end].trim().trim_matches('"').to_string();
-                        }
-                    }
-                }
-            }
-
-            if pane_id.is_empty() {
-                // Fallback: generate a pseudo ID
-                pane_id = format!("pane_{}", chrono::Utc::now().timestamp_millis());
-            }
+            // The new pane is the one in our working dir, or failing that the newest
+            let panes = self.cli.list().await?;
+            let working_dir_trimmed = working_dir.trim_end_matches('/');
+            panes.iter()
+                .filter(|p| p.cwd_path().trim_end_matches('/') == working_dir_trimmed)
+                .max_by_key(|p| p.pane_id)
+                .or_else(|| panes.iter().max_by_key(|p| p.pane_id))
+                .cloned()
+                .ok_or_else(|| "Failed to get pane ID from WezTerm".to_string())?
         } else {
             // Multiplexer is running, spawn new window with OpenCode
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("spawn")
-                .arg("--new-window")
-                .arg("--cwd")
-                .arg(working_dir)
-                .arg("--")
-                .arg("bash")
-                .arg("-c")
-                .arg("unset npm_config_prefix && opencode")
-                .output()
-                .await
-                .map_err(|e| format!("Failed to spawn WezTerm window: {}", e))?;
+            let pane_id = self.cli.spawn(&SpawnOptions {
+                new_window: true,
+                cwd: Some(working_dir.to_string()),
+                command,
+                ..Default::default()
+            }).await
+            .map_err(|e| format!("Failed to spawn window: {}", e))?;
+
+            // Bring the newly created window to front
+            #[cfg(target_os = "macos")]
+            {
+                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                let _ = Command::new("osascript")
+                    .arg("-e")
+                    .arg("tell application \"WezTerm\" to activate")
+                    .output()
+                    .await;
+            }
 
-            if output.status.success() {
-                pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
-
-                // Bring the newly created window to front
-                #[cfg(target_os = "macos")]
-                {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    let _ = Command::new("osascript")
-                        .arg("-e")
-                        .arg("tell application \"WezTerm\" to activate")
-                        .output()
-                        .await;
+            self.cli.find_pane(&pane_id).await?
+                .ok_or_else(|| format!("Spawned pane {} not found in wezterm cli list", pane_id))?
+        };
+
+        let mut window = window_from_pane(&pane, Some(project_id), working_dir);
+
+        if let Some(layout) = layout {
+            self.apply_layout(&mut window, layout).await;
+        }
+
+        // Store the window
+        self.windows.write().await.insert(window.window_id.clone(), window.clone());
+
+        Ok(window)
+    }
+
+    /// Split panes off the window's agent pane according to the layout.
+    /// Entries that fail to split are logged and skipped.
+    async fn apply_layout(&self, window: &mut WezTermWindow, layout: &PaneLayout) {
+        for entry in &layout.panes {
+            let (parent, command) = match plan_split(window, Some(&entry.split_from), entry.command.as_deref()) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    println!("Skipping layout pane '{}': {}", entry.role, e);
+                    continue;
                 }
-            } else {
-                return Err(format!("Failed to spawn window: {}",
-                    String::from_utf8_lossy(&output.stderr)));
+            };
+
+            match self.cli.split_pane(&parent, entry.direction, entry.percent, Some(&window.working_dir), &command).await {
+                Ok(pane_id) => {
+                    window.panes.insert(entry.role.clone(), pane_id);
+                }
+                Err(e) => println!("Failed to create layout pane '{}': {}", entry.role, e),
             }
         }
 
-        if pane_id.is_empty() {
-            return Err("Failed to get pane ID from WezTerm".to_string());
+        // Leave focus on the agent pane
+        let _ = self.cli.activate_pane(&window.pane_id).await;
+    }
+
+    /// Split a pane of a tracked window and register the new pane under `role`
+    pub async fn split_window_pane(
+        &self,
+        window_id: &str,
+        role: &str,
+        split_from: Option<&str>,
+        direction: SplitDirection,
+        percent: u8,
+        command: Option<&str>,
+    ) -> Result<String, String> {
+        // Don't hold the lock across the CLI call
+        let window = self.get_window(window_id).await
+            .ok_or_else(|| format!("Window {} not found", window_id))?;
+        let (parent, command) = plan_split(&window, split_from, command)?;
+
+        let pane_id = self.cli.split_pane(&parent, direction, percent, Some(&window.working_dir), &command).await?;
+
+        match self.windows.write().await.get_mut(window_id) {
+            Some(window) => {
+                window.panes.insert(role.to_string(), pane_id.clone());
+            }
+            None => println!("Window {} closed while splitting pane {}", window_id, pane_id),
         }
 
-        // Get the window ID for this pane
-        let window_output = Command::new("wezterm")
-            .arg("cli")
-            .arg("list")
-            .arg("--format")
-            .arg("json")
-            .output()
-            .await
-            .map_err(|e| format!("Failed to list panes: {}", e))?;
+        Ok(pane_id)
+    }
 
-        let window_id = if window_output.status.success() {
-            // Try to parse and find our pane
-            // For now, we'll use the pane_id as the window_id
-            format!("win_{}", pane_id)
-        } else {
-            format!("win_{}", pane_id)
-        };
+    pub async fn resize_pane(&self, pane_id: &str, direction: PaneDirection, amount: u16) -> Result<(), String> {
+        self.cli.adjust_pane_size(pane_id, direction, amount).await
+    }
 
-        let window = WezTermWindow {
-            window_id: window_id.clone(),
-            pane_id: pane_id.clone(),
-            project_id: Some(project_id.to_string()),
-            working_dir: working_dir.to_string(),
-            position: None,
-            size: None,
-            pid: None,
-            created_at: Utc::now().to_rfc3339(),
-        };
+    pub async fn activate_pane(&self, pane_id: &str) -> Result<(), String> {
+        self.cli.activate_pane(pane_id).await
+    }
 
-        // Store the window
-        self.windows.write().await.insert(window_id.clone(), window.clone());
+    pub async fn get_window(&self, window_id: &str) -> Option<WezTermWindow> {
+        self.windows.read().await.get(window_id).cloned()
+    }
 
-        Ok(window)
+    /// Track windows saved by a previous run whose agent pane still exists.
+    /// Returns the windows that were reattached.
+    pub async fn restore_windows(&self, saved: Vec<WezTermWindow>) -> Result<Vec<WezTermWindow>, String> {
+        let panes = self.cli.list().await?;
+
+        let restored: Vec<WezTermWindow> = saved.iter()
+            .filter_map(|window| reconcile_window(window, &panes))
+            .collect();
+
+        let mut windows = self.windows.write().await;
+        for window in &restored {
+            windows.insert(window.window_id.clone(), window.clone());
+        }
+
+        Ok(restored)
     }
 
     pub async fn list_project_windows(&self, project_id: &str) -> Result<Vec<WezTermWindow>, String> {
@@ -454,16 +490,7 @@ impl WezTermController {
 
         if let Some(window) = windows.get(window_id) {
             // Use WezTerm CLI to kill the pane
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("kill-pane")
-                .arg("--pane-id")
-                .arg(&window.pane_id)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to close pane: {}", e))?;
-
-            if !output.status.success() {
+            if self.cli.kill_pane(&window.pane_id).await.is_err() {
                 // If CLI fails, try the old method with PID if available
                 if let Some(pid) = window.pid {
                     #[cfg(unix)]
@@ -486,71 +513,83 @@ impl WezTermController {
         }
     }
 
+    /// Kill every pane of every window tracked for a project and stop
+    /// tracking them. Returns the ids of the closed windows.
+    pub async fn close_project_windows(&self, project_id: &str) -> Vec<String> {
+        let project_windows: Vec<WezTermWindow> = {
+            let mut windows = self.windows.write().await;
+            let ids: Vec<String> = windows.values()
+                .filter(|w| w.project_id.as_deref() == Some(project_id))
+                .map(|w| w.window_id.clone())
+                .collect();
+            ids.iter().filter_map(|id| windows.remove(id)).collect()
+        };
+
+        for window in &project_windows {
+            for pane_id in window_pane_ids(window) {
+                let _ = self.cli.kill_pane(pane_id).await;
+            }
+        }
+
+        project_windows.into_iter().map(|w| w.window_id).collect()
+    }
+
+    /// Stop tracking windows whose agent pane no longer exists. Returns the
+    /// ids of the dropped windows; nothing is dropped if WezTerm can't be reached.
+    pub async fn prune_orphaned_windows(&self) -> Vec<String> {
+        let Ok(panes) = self.cli.list().await else {
+            return Vec::new();
+        };
+
+        let mut windows = self.windows.write().await;
+        let orphaned = orphaned_windows(&windows, &panes);
+        for window_id in &orphaned {
+            windows.remove(window_id);
+        }
+        orphaned
+    }
+
     pub async fn send_text_to_window(&self, window_id: &str, text: &str) -> Result<(), String> {
         let windows = self.windows.read().await;
 
         if let Some(window) = windows.get(window_id) {
-            // Use WezTerm CLI to send text to the pane
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("send-text")
-                .arg("--pane-id")
-                .arg(&window.pane_id)
-                .arg("--no-paste")
-                .arg(text)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to send text: {}", e))?;
-
-            if output.status.success() {
-                Ok(())
-            } else {
-                // If it fails, it might be because the multiplexer isn't running
-                // or the pane doesn't exist anymore
-                Err(format!("Failed to send text to pane: {}",
-                    String::from_utf8_lossy(&output.stderr)))
-            }
+            // Use WezTerm CLI to send text to the pane. If it fails the
+            // multiplexer may not be running or the pane no longer exists.
+            self.cli.send_text(&window.pane_id, text, true).await
+                .map_err(|e| format!("Failed to send text to pane: {}", e))
         } else {
             Err(format!("Window {} not found", window_id))
         }
     }
 
+    /// Send a named key such as `Enter`, `Up` or Ctrl+`c` to a window's agent pane
+    pub async fn send_key_to_window(&self, window_id: &str, key: &str, modifiers: &[KeyModifier]) -> Result<(), String> {
+        let sequence = keys::encode_key(key, modifiers)?;
+
+        let pane_id = self.get_window(window_id).await
+            .map(|w| w.pane_id)
+            .ok_or_else(|| format!("Window {} not found", window_id))?;
+
+        self.cli.send_text(&pane_id, &sequence, true).await
+            .map_err(|e| format!("Failed to send key to pane: {}", e))
+    }
+
     pub async fn execute_command_with_output(
         &self,
         window_id: &str,
         command: &str,
     ) -> Result<String, String> {
-        let windows = self.windows.read().await;
-
-        if let Some(window) = windows.get(window_id) {
-            // Execute command directly in the working directory and capture output
-            let output = Command::new("bash")
-                .arg("-c")
-                .arg(format!("cd {} && {}", window.working_dir, command))
-                .output()
-                .await
-                .map_err(|e| format!("Failed to execute command: {}", e))?;
-
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-                let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-
-                // Combine stdout and stderr for complete output
-                let mut result = stdout;
-                if !stderr.is_empty() {
-                    if !result.is_empty() {
-                        result.push_str("\n");
-                    }
-                    result.push_str(&stderr);
-                }
-
-                Ok(result)
-            } else {
-                let error_output = String::from_utf8_lossy(&output.stderr).to_string();
-                Err(format!("Command failed: {}", error_output))
+        let pane_id = {
+            let windows = self.windows.read().await;
+            match windows.get(window_id) {
+                Some(window) => window.pane_id.clone(),
+                None => return Err(format!("Window {} not found", window_id)),
             }
-        } else {
-            Err(format!("Window {} not found", window_id))
+        };
+
+        match self.capture_command_output(&pane_id, command).await? {
+            (output, 0) => Ok(output),
+            (output, exit_code) => Err(format!("Command failed with exit code {}: {}", exit_code, output)),
         }
     }
 
@@ -583,16 +622,387 @@ impl WezTermController {
         Ok(())
     }
 
+    /// Move and resize the GUI window holding a tracked window's agent pane.
+    /// Uses System Events on macOS and `xdotool` on Linux (X11 only); where
+    /// neither is available this returns an error and the window is left alone.
+    pub async fn set_window_geometry(&self, window_id: &str, geometry: &WindowGeometry) -> Result<WezTermWindow, String> {
+        let title = self.gui_window_title(window_id).await?;
+        apply_window_geometry(&title, geometry).await?;
+        self.update_geometry(window_id, geometry).await
+    }
+
+    /// Read the current geometry of the GUI window holding a tracked window's
+    /// agent pane, with the same platform support as `set_window_geometry`
+    pub async fn get_window_geometry(&self, window_id: &str) -> Result<WindowGeometry, String> {
+        let title = self.gui_window_title(window_id).await?;
+        let geometry = read_window_geometry(&title).await?;
+        self.update_geometry(window_id, &geometry).await?;
+        Ok(geometry)
+    }
+
+    /// Title of the GUI window holding a tracked window's agent pane, which
+    /// is how the platform tools find it whether or not it has focus
+    async fn gui_window_title(&self, window_id: &str) -> Result<String, String> {
+        let pane_id = self.get_window(window_id).await
+            .map(|w| w.pane_id)
+            .ok_or_else(|| format!("Window {} not found", window_id))?;
+        let pane = self.cli.find_pane(&pane_id).await?
+            .ok_or_else(|| format!("Pane {} not found in wezterm cli list", pane_id))?;
+
+        if pane.window_title.is_empty() {
+            return Err(format!("Window {} has no title to find it by", window_id));
+        }
+        Ok(pane.window_title)
+    }
+
+    async fn update_geometry(&self, window_id: &str, geometry: &WindowGeometry) -> Result<WezTermWindow, String> {
+        let mut windows = self.windows.write().await;
+        let window = windows.get_mut(window_id)
+            .ok_or_else(|| format!("Window {} not found", window_id))?;
+
+        window.position = Some((geometry.x, geometry.y));
+        window.size = Some((geometry.width, geometry.height));
+        Ok(window.clone())
+    }
+
     pub async fn list_all_windows(&self) -> Result<Vec<WezTermWindow>, String> {
         let windows = self.windows.read().await;
         Ok(windows.values().cloned().collect())
     }
 }
 
+/// Move and resize the WezTerm window with this title
+#[cfg(target_os = "macos")]
+async fn apply_window_geometry(title: &str, geometry: &WindowGeometry) -> Result<(), String> {
+    let script = format!(
+        "tell application \"System Events\" to tell process \"wezterm-gui\"\n\
+         set wezWindow to first window whose name is {}\n\
+         set position of wezWindow to {{{}, {}}}\n\
+         set size of wezWindow to {{{}, {}}}\n\
+         end tell",
+        applescript_string(title), geometry.x, geometry.y, geometry.width, geometry.height
+    );
+    run_geometry_tool("osascript", &["-e", &script]).await.map(|_| ())
+}
+
+#[cfg(target_os = "linux")]
+async fn apply_window_geometry(title: &str, geometry: &WindowGeometry) -> Result<(), String> {
+    let id = find_x11_window(title).await?;
+    let (x, y) = (geometry.x.to_string(), geometry.y.to_string());
+    let (width, height) = (geometry.width.to_string(), geometry.height.to_string());
+    run_geometry_tool("xdotool", &["windowmove", &id, &x, &y, "windowsize", &id, &width, &height])
+        .await
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn apply_window_geometry(_title: &str, _geometry: &WindowGeometry) -> Result<(), String> {
+    Err("Window geometry is not supported on this platform".to_string())
+}
+
+/// Geometry of the WezTerm window with this title
+#[cfg(target_os = "macos")]
+async fn read_window_geometry(title: &str) -> Result<WindowGeometry, String> {
+    let script = format!(
+        "tell application \"System Events\" to tell process \"wezterm-gui\" \
+         to get {{position, size}} of first window whose name is {}",
+        applescript_string(title)
+    );
+    let output = run_geometry_tool("osascript", &["-e", &script]).await?;
+    parse_osascript_geometry(&output).ok_or_else(|| format!("Unexpected osascript output: {}", output.trim()))
+}
+
+#[cfg(target_os = "linux")]
+async fn read_window_geometry(title: &str) -> Result<WindowGeometry, String> {
+    let id = find_x11_window(title).await?;
+    let output = run_geometry_tool("xdotool", &["getwindowgeometry", "--shell", &id]).await?;
+    parse_xdotool_geometry(&output).ok_or_else(|| format!("Unexpected xdotool output: {}", output.trim()))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+async fn read_window_geometry(_title: &str) -> Result<WindowGeometry, String> {
+    Err("Window geometry is not supported on this platform".to_string())
+}
+
+/// X11 id of the first window titled exactly `title`
+#[cfg(target_os = "linux")]
+async fn find_x11_window(title: &str) -> Result<String, String> {
+    let pattern = exact_title_pattern(title);
+    let output = run_geometry_tool("xdotool", &["search", "--limit", "1", "--name", &pattern])
+        .await
+        .map_err(|e| format!("{} (window geometry on Linux needs xdotool and X11)", e))?;
+    output.lines()
+        .next()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| format!("No window titled '{}'", title))
+}
+
+/// `value` as an AppleScript string literal
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Regex matching exactly `title`, for `xdotool search --name`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn exact_title_pattern(title: &str) -> String {
+    let mut pattern = String::from("^");
+    for c in title.chars() {
+        if r"\.+*?()|[]{}^$".contains(c) {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('$');
+    pattern
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+async fn run_geometry_tool(program: &str, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!("{} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+/// Parse `x, y, width, height` as printed by osascript for `{position, size}`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_osascript_geometry(output: &str) -> Option<WindowGeometry> {
+    let values: Vec<i64> = output.trim()
+        .split(',')
+        .map(|v| v.trim().parse().ok())
+        .collect::<Option<_>>()?;
+
+    match values[..] {
+        [x, y, width, height] => Some(WindowGeometry {
+            x: x as i32,
+            y: y as i32,
+            width: u32::try_from(width).ok()?,
+            height: u32::try_from(height).ok()?,
+        }),
+        _ => None,
+    }
+}
+
+/// Parse the `KEY=value` lines of `xdotool getwindowgeometry --shell`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_xdotool_geometry(output: &str) -> Option<WindowGeometry> {
+    let value = |key: &str| output.lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+        .and_then(|v| v.trim().parse::<i64>().ok());
+
+    Some(WindowGeometry {
+        x: value("X")? as i32,
+        y: value("Y")? as i32,
+        width: u32::try_from(value("WIDTH")?).ok()?,
+        height: u32::try_from(value("HEIGHT")?).ok()?,
+    })
+}
+
+/// Build a window record from the pane WezTerm reports for it
+fn window_from_pane(pane: &CliPaneInfo, project_id: Option<&str>, working_dir: &str) -> WezTermWindow {
+    WezTermWindow {
+        window_id: format!("win_{}", pane.window_id),
+        tab_id: Some(pane.tab_id.to_string()),
+        pane_id: pane.pane_id.to_string(),
+        project_id: project_id.map(|p| p.to_string()),
+        working_dir: working_dir.to_string(),
+        position: None,
+        size: None,
+        pid: None,
+        panes: HashMap::from([(AGENT_PANE_ROLE.to_string(), pane.pane_id.to_string())]),
+        created_at: Utc::now().to_rfc3339(),
+    }
+}
+
+/// The pane to split (the one registered as `split_from`, or the agent pane)
+/// and the command to run in the new pane
+/// Every pane of a window, the agent pane included, each once
+fn window_pane_ids(window: &WezTermWindow) -> Vec<&str> {
+    let mut pane_ids = vec![window.pane_id.as_str()];
+    for pane_id in window.panes.values() {
+        if !pane_ids.contains(&pane_id.as_str()) {
+            pane_ids.push(pane_id);
+        }
+    }
+    pane_ids
+}
+
+/// Ids of the windows whose agent pane isn't among the live panes
+fn orphaned_windows(windows: &HashMap<String, WezTermWindow>, panes: &[CliPaneInfo]) -> Vec<String> {
+    windows.values()
+        .filter(|w| !pane_exists(panes, &w.pane_id))
+        .map(|w| w.window_id.clone())
+        .collect()
+}
+
+fn plan_split(window: &WezTermWindow, split_from: Option<&str>, command: Option<&str>) -> Result<(String, Vec<String>), String> {
+    let parent = match split_from {
+        Some(role) => window.panes.get(role).cloned()
+            .ok_or_else(|| format!("Pane '{}' not found in window {}", role, window.window_id))?,
+        None => window.pane_id.clone(),
+    };
+
+    let command = command.iter()
+        .flat_map(|c| ["bash".to_string(), "-c".to_string(), c.to_string()])
+        .collect();
+
+    Ok((parent, command))
+}
+
+/// Bring a saved window up to date with the live pane list. Returns `None`
+/// when its agent pane is gone; role panes that were closed are dropped.
+fn reconcile_window(saved: &WezTermWindow, panes: &[CliPaneInfo]) -> Option<WezTermWindow> {
+    let agent = panes.iter().find(|p| p.pane_id.to_string() == saved.pane_id)?;
+
+    let mut window = saved.clone();
+    window.window_id = format!("win_{}", agent.window_id);
+    window.tab_id = Some(agent.tab_id.to_string());
+    window.panes.retain(|_, pane_id| pane_exists(panes, pane_id));
+    window.panes.insert(AGENT_PANE_ROLE.to_string(), saved.pane_id.clone());
+    Some(window)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn pane(window_id: u64, pane_id: u64) -> CliPaneInfo {
+        CliPaneInfo {
+            window_id,
+            tab_id: window_id,
+            pane_id,
+            workspace: "default".to_string(),
+            size: Default::default(),
+            title: String::new(),
+            cwd: String::new(),
+            cursor_x: 0,
+            cursor_y: 0,
+            left_col: 0,
+            top_row: 0,
+            tab_title: String::new(),
+            window_title: String::new(),
+            is_active: false,
+            is_zoomed: false,
+            tty_name: None,
+        }
+    }
+
+    #[test]
+    fn test_reconcile_window_drops_closed_panes() {
+        let mut saved = window_from_pane(&pane(1, 10), Some("project-1"), "/tmp/project");
+        saved.panes.insert("logs".to_string(), "11".to_string());
+        saved.panes.insert("shell".to_string(), "12".to_string());
+
+        // The mux renumbered the window and the shell pane was closed
+        let live = [pane(4, 10), pane(4, 11)];
+        let window = reconcile_window(&saved, &live).unwrap();
+
+        assert_eq!(window.window_id, "win_4");
+        assert_eq!(window.panes.len(), 2);
+        assert_eq!(window.panes.get("logs").map(String::as_str), Some("11"));
+        assert!(!window.panes.contains_key("shell"));
+    }
+
+    #[test]
+    fn test_window_pane_ids_include_the_agent_pane_once() {
+        let mut window = window_from_pane(&pane(1, 10), Some("project-1"), "/tmp/project");
+        window.panes.insert("logs".to_string(), "11".to_string());
+
+        let mut pane_ids = window_pane_ids(&window);
+        pane_ids.sort();
+        assert_eq!(pane_ids, vec!["10", "11"]);
+
+        window.panes.clear();
+        assert_eq!(window_pane_ids(&window), vec!["10"]);
+    }
+
+    #[test]
+    fn test_orphaned_windows() {
+        let mut windows = HashMap::new();
+        for (window_id, pane_id) in [(1, 10), (2, 20)] {
+            let window = window_from_pane(&pane(window_id, pane_id), Some("project-1"), "/tmp/project");
+            windows.insert(window.window_id.clone(), window);
+        }
+
+        // Only the role pane of window 2 is still around
+        assert_eq!(orphaned_windows(&windows, &[pane(1, 10), pane(2, 21)]), vec!["win_2"]);
+        assert!(orphaned_windows(&windows, &[pane(1, 10), pane(2, 20)]).is_empty());
+        assert_eq!(orphaned_windows(&windows, &[]).len(), 2);
+    }
+
+    #[test]
+    fn test_plan_split() {
+        let mut window = window_from_pane(&pane(1, 10), Some("project-1"), "/tmp/project");
+        window.panes.insert("logs".to_string(), "11".to_string());
+
+        assert_eq!(plan_split(&window, None, None).unwrap(), ("10".to_string(), vec![]));
+        assert_eq!(
+            plan_split(&window, Some("logs"), Some("tail -f app.log")).unwrap(),
+            ("11".to_string(), vec!["bash".to_string(), "-c".to_string(), "tail -f app.log".to_string()])
+        );
+        assert!(plan_split(&window, Some("shell"), None).unwrap_err().contains("'shell' not found"));
+    }
+
+    #[test]
+    fn test_pane_layout_serde() {
+        assert!(PaneLayout::default().panes.is_empty());
+        assert_eq!(serde_json::from_str::<PaneLayout>("{}").unwrap(), PaneLayout::default());
+
+        let layout: PaneLayout = serde_json::from_str(
+            r#"{"panes":[{"role":"logs","direction":"bottom","percent":30,"command":null}]}"#,
+        ).unwrap();
+        assert_eq!(layout.panes[0].split_from, AGENT_PANE_ROLE);
+        assert_eq!(layout.panes[0].direction, SplitDirection::Bottom);
+
+        let json = serde_json::to_string(&layout).unwrap();
+        assert!(json.contains(r#""direction":"bottom""#));
+        assert_eq!(serde_json::from_str::<PaneLayout>(&json).unwrap(), layout);
+    }
+
+    #[test]
+    fn test_parse_osascript_geometry() {
+        assert_eq!(
+            parse_osascript_geometry("120, -40, 1280, 800\n"),
+            Some(WindowGeometry { x: 120, y: -40, width: 1280, height: 800 })
+        );
+        assert_eq!(parse_osascript_geometry("missing value"), None);
+        assert_eq!(parse_osascript_geometry("1, 2, 3"), None);
+    }
+
+    #[test]
+    fn test_window_title_quoting() {
+        assert_eq!(applescript_string(r#"vim "a\b""#), r#""vim \"a\\b\"""#);
+        assert_eq!(exact_title_pattern("~/src (main) $"), r"^~/src \(main\) \$$");
+        assert_eq!(exact_title_pattern("a.b*c"), r"^a\.b\*c$");
+    }
+
+    #[test]
+    fn test_parse_xdotool_geometry() {
+        let output = "WINDOW=62914563\nX=10\nY=52\nWIDTH=1024\nHEIGHT=768\nSCREEN=0\n";
+
+        assert_eq!(
+            parse_xdotool_geometry(output),
+            Some(WindowGeometry { x: 10, y: 52, width: 1024, height: 768 })
+        );
+        assert_eq!(parse_xdotool_geometry("WINDOW=1\n"), None);
+    }
+
+    #[test]
+    fn test_reconcile_window_requires_agent_pane() {
+        let saved = window_from_pane(&pane(1, 10), None, "/tmp/project");
+
+        assert!(reconcile_window(&saved, &[pane(1, 11)]).is_none());
+    }
+
     #[tokio::test]
     async fn test_create_ssh_domain() {
         let controller = WezTermController::new();
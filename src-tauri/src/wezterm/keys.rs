@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyModifier {
+    Shift,
+    Alt,
+    Ctrl,
+}
+
+/// Encode a named key (`Enter`, `Up`, `F5`, `c`, ...) with modifiers as the
+/// bytes a terminal sends for it, suitable for `wezterm cli send-text --no-paste`
+pub fn encode_key(key: &str, modifiers: &[KeyModifier]) -> Result<String, String> {
+    let shift = modifiers.contains(&KeyModifier::Shift);
+    let alt = modifiers.contains(&KeyModifier::Alt);
+    let ctrl = modifiers.contains(&KeyModifier::Ctrl);
+    // xterm modifier parameter: 1 + shift + 2*alt + 4*ctrl
+    let param = 1 + shift as u8 + 2 * alt as u8 + 4 * ctrl as u8;
+
+    let csi = |final_char: char| {
+        if param > 1 { format!("\x1b[1;{}{}", param, final_char) } else { format!("\x1b[{}", final_char) }
+    };
+    let tilde = |code: u8| {
+        if param > 1 { format!("\x1b[{};{}~", code, param) } else { format!("\x1b[{}~", code) }
+    };
+    let ss3 = |final_char: char| {
+        if param > 1 { format!("\x1b[1;{}{}", param, final_char) } else { format!("\x1bO{}", final_char) }
+    };
+
+    let sequence = match key.to_lowercase().as_str() {
+        "up" | "arrowup" => csi('A'),
+        "down" | "arrowdown" => csi('B'),
+        "right" | "arrowright" => csi('C'),
+        "left" | "arrowleft" => csi('D'),
+        "home" => csi('H'),
+        "end" => csi('F'),
+        "insert" => tilde(2),
+        "delete" => tilde(3),
+        "pageup" => tilde(5),
+        "pagedown" => tilde(6),
+        "f1" => ss3('P'),
+        "f2" => ss3('Q'),
+        "f3" => ss3('R'),
+        "f4" => ss3('S'),
+        "f5" => tilde(15),
+        "f6" => tilde(17),
+        "f7" => tilde(18),
+        "f8" => tilde(19),
+        "f9" => tilde(20),
+        "f10" => tilde(21),
+        "f11" => tilde(23),
+        "f12" => tilde(24),
+        "enter" | "return" => with_alt(alt, "\r"),
+        "tab" if shift => "\x1b[Z".to_string(),
+        "tab" => with_alt(alt, "\t"),
+        "backspace" => with_alt(alt, if ctrl { "\x08" } else { "\x7f" }),
+        "escape" | "esc" => with_alt(alt, "\x1b"),
+        "space" => with_alt(alt, if ctrl { "\0" } else { " " }),
+        _ => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => encode_char(c, ctrl, alt, shift)?,
+                _ => return Err(format!("Unknown key: {}", key)),
+            }
+        }
+    };
+
+    Ok(sequence)
+}
+
+fn with_alt(alt: bool, text: &str) -> String {
+    if alt { format!("\x1b{}", text) } else { text.to_string() }
+}
+
+fn encode_char(c: char, ctrl: bool, alt: bool, shift: bool) -> Result<String, String> {
+    let c = if shift { c.to_ascii_uppercase() } else { c };
+
+    let text = if ctrl {
+        // Ctrl maps @, A-Z and [\]^_ onto 0x00-0x1f
+        match c.to_ascii_uppercase() {
+            upper @ ('@'..='_') => ((upper as u8) & 0x1f) as char,
+            '?' => '\x7f',
+            _ => return Err(format!("Ctrl+{} has no terminal encoding", c)),
+        }
+        .to_string()
+    } else {
+        c.to_string()
+    };
+
+    Ok(with_alt(alt, &text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_control_characters() {
+        assert_eq!(encode_key("c", &[KeyModifier::Ctrl]).unwrap(), "\x03");
+        assert_eq!(encode_key("[", &[KeyModifier::Ctrl]).unwrap(), "\x1b");
+        assert_eq!(encode_key("x", &[KeyModifier::Alt]).unwrap(), "\x1bx");
+        assert_eq!(encode_key("a", &[KeyModifier::Shift]).unwrap(), "A");
+        assert_eq!(encode_key("Escape", &[]).unwrap(), "\x1b");
+        assert_eq!(encode_key("Enter", &[]).unwrap(), "\r");
+    }
+
+    #[test]
+    fn test_encode_cursor_and_function_keys() {
+        assert_eq!(encode_key("Up", &[]).unwrap(), "\x1b[A");
+        assert_eq!(encode_key("Left", &[KeyModifier::Ctrl]).unwrap(), "\x1b[1;5D");
+        assert_eq!(encode_key("PageDown", &[KeyModifier::Shift]).unwrap(), "\x1b[6;2~");
+        assert_eq!(encode_key("F1", &[]).unwrap(), "\x1bOP");
+        assert_eq!(encode_key("F5", &[]).unwrap(), "\x1b[15~");
+        assert_eq!(encode_key("Tab", &[KeyModifier::Shift]).unwrap(), "\x1b[Z");
+    }
+
+    #[test]
+    fn test_unknown_keys_are_rejected() {
+        assert!(encode_key("Hyper", &[]).is_err());
+        assert!(encode_key("1", &[KeyModifier::Ctrl]).is_err());
+    }
+}
@@ -1,4 +1,6 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::process::Command;
@@ -8,14 +10,48 @@ use chrono::Utc;
 use tauri::{AppHandle, Emitter};
 use serde::{Deserialize, Serialize};
 
+use super::cli::{pane_exists, CliPaneSize, SpawnOptions, WezTermCli};
+use super::types::PaneDirection;
+
+/// Poll interval right after the pane changed
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Poll interval ceiling while the pane is idle
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MirrorLine {
+    pub index: usize,
+    pub text: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MirrorUpdate {
     pub mirror_id: String,
+    /// When true `content` holds the whole screen and `changed_lines` is empty
+    pub full: bool,
     pub content: String,
+    pub changed_lines: Vec<MirrorLine>,
+    pub line_count: usize,
+    /// Cursor position relative to the visible screen
     pub cursor_x: u16,
     pub cursor_y: u16,
-    pub viewport_start: i32,
-    pub viewport_end: i32,
+    /// Visible stable rows, `viewport_start..viewport_end`
+    pub viewport_start: i64,
+    pub viewport_end: i64,
+    pub rows: u16,
+    pub cols: u16,
+}
+
+/// One page of a mirror's scrollback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MirrorScrollback {
+    pub mirror_id: String,
+    pub content: String,
+    /// Line range relative to the top of the visible screen, both inclusive
+    pub start_line: i64,
+    pub end_line: i64,
+    /// Whether older lines exist above `start_line`
+    pub has_more: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,18 +63,30 @@ pub struct WezTermMirror {
     pub last_content: String,
     pub is_active: bool,
     pub created_at: String,
+    /// Size requested by the frontend view; captures are cropped to `rows`
+    #[serde(default)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    pub rows: Option<u16>,
 }
 
 pub struct MirrorManager {
     mirrors: Arc<RwLock<HashMap<String, WezTermMirror>>>,
     app_handle: Option<AppHandle>,
+    cli: WezTermCli,
 }
 
 impl MirrorManager {
     pub fn new() -> Self {
+        Self::with_cli(WezTermCli::new())
+    }
+
+    /// Share a client (and its probe result) with other WezTerm managers
+    pub fn with_cli(cli: WezTermCli) -> Self {
         Self {
             mirrors: Arc::new(RwLock::new(HashMap::new())),
             app_handle: None,
+            cli,
         }
     }
 
@@ -47,70 +95,49 @@ impl MirrorManager {
     }
 
     pub async fn create_mirror(&self, project_path: &str) -> Result<WezTermMirror, String> {
-        // First spawn a minimized WezTerm window
-        let output = Command::new("wezterm")
-            .arg("cli")
-            .arg("spawn")
-            .arg("--new-window")
-            .arg("--cwd")
-            .arg(project_path)
-            .arg("--")
-            .arg("bash")
-            .arg("-c")
-            .arg("unset npm_config_prefix && opencode")
-            .output()
-            .await;
-
-        let output = match output {
-            Ok(o) => o,
-            Err(_) => {
-                // If CLI fails, try starting WezTerm first
-                println!("Starting WezTerm multiplexer...");
-
-                let _ = Command::new("wezterm")
-                    .arg("start")
-                    .arg("--cwd")
-                    .arg(project_path)
-                    .arg("--")
-                    .arg("bash")
-                    .arg("-c")
-                    .arg("unset npm_config_prefix && opencode")
-                    .spawn()
-                    .map_err(|e| format!("Failed to start WezTerm: {}", e))?;
-
-                // Wait for it to start
-                sleep(Duration::from_secs(2)).await;
-
-                // Try spawning again
-                Command::new("wezterm")
-                    .arg("cli")
-                    .arg("spawn")
-                    .arg("--new-window")
-                    .arg("--cwd")
-                    .arg(project_path)
-                    .arg("--")
-                    .arg("bash")
-                    .arg("-c")
-                    .arg("unset npm_config_prefix && opencode")
-                    .output()
-                    .await
-                    .map_err(|e| format!("Failed to spawn after starting: {}", e))?
-            }
+        self.cli.ensure_installed()?;
+
+        let spawn_options = SpawnOptions {
+            new_window: true,
+            cwd: Some(project_path.to_string()),
+            command: vec![
+                "bash".to_string(),
+                "-c".to_string(),
+                "unset npm_config_prefix && opencode".to_string(),
+            ],
+            ..Default::default()
         };
 
-        if !output.status.success() {
-            return Err(format!("Failed to spawn WezTerm: {}",
-                String::from_utf8_lossy(&output.stderr)));
-        }
-
-        let pane_id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let pane_id = if self.cli.is_running().await {
+            self.cli.spawn(&spawn_options).await
+                .map_err(|e| format!("Failed to spawn WezTerm: {}", e))?
+        } else {
+            // If the mux isn't running, start WezTerm first
+            println!("Starting WezTerm multiplexer...");
+
+            let _ = Command::new(self.cli.binary())
+                .arg("start")
+                .arg("--cwd")
+                .arg(project_path)
+                .arg("--")
+                .args(&spawn_options.command)
+                .spawn()
+                .map_err(|e| format!("Failed to start WezTerm: {}", e))?;
+
+            // Wait for it to start
+            sleep(Duration::from_secs(2)).await;
+
+            // Try spawning again
+            self.cli.spawn(&spawn_options).await
+                .map_err(|e| format!("Failed to spawn after starting: {}", e))?
+        };
 
-        if pane_id.is_empty() {
-            return Err("Failed to get pane ID from WezTerm".to_string());
-        }
+        let window_id = match self.cli.find_pane(&pane_id).await {
+            Ok(Some(pane)) => format!("win_{}", pane.window_id),
+            _ => format!("win_{}", pane_id),
+        };
 
         let mirror_id = Uuid::new_v4().to_string();
-        let window_id = format!("win_{}", pane_id);
 
         let mirror = WezTermMirror {
             id: mirror_id.clone(),
@@ -120,6 +147,8 @@ impl MirrorManager {
             last_content: String::new(),
             is_active: true,
             created_at: Utc::now().to_rfc3339(),
+            cols: None,
+            rows: None,
         };
 
         // Store the mirror
@@ -134,82 +163,86 @@ impl MirrorManager {
     async fn start_polling(&self, mirror_id: String) {
         let mirrors = self.mirrors.clone();
         let app_handle = self.app_handle.clone();
+        let cli = self.cli.clone();
 
         tokio::spawn(async move {
+            let mut interval = MIN_POLL_INTERVAL;
+            let mut last_hash: Option<u64> = None;
+            let mut last_lines: Vec<String> = Vec::new();
+            let mut last_size: (Option<u16>, Option<u16>) = (None, None);
+
             loop {
-                // Check if mirror still exists and is active
-                let should_continue = {
+                // Check if mirror still exists and is active, and get its pane
+                let (pane_id, size) = {
                     let mirrors_lock = mirrors.read().await;
-                    if let Some(mirror) = mirrors_lock.get(&mirror_id) {
-                        mirror.is_active
-                    } else {
-                        false
+                    match mirrors_lock.get(&mirror_id) {
+                        Some(mirror) if mirror.is_active => (mirror.pane_id.clone(), (mirror.cols, mirror.rows)),
+                        _ => break,
                     }
                 };
 
-                if !should_continue {
-                    break;
+                // A resize rewraps the whole screen, so start over with a full update
+                if size != last_size {
+                    last_size = size;
+                    last_hash = None;
+                    last_lines.clear();
                 }
 
-                // Get the pane_id
-                let pane_id = {
-                    let mirrors_lock = mirrors.read().await;
-                    if let Some(mirror) = mirrors_lock.get(&mirror_id) {
-                        mirror.pane_id.clone()
+                // Get terminal content with escape sequences
+                let end_line = size.1.map(|rows| rows as i64 - 1);
+                if let Ok(content) = cli.get_text(&pane_id, true, None, end_line).await {
+                    let hash = content_hash(&content);
+
+                    if last_hash == Some(hash) {
+                        // Idle pane: back off gradually
+                        interval = (interval * 2).min(MAX_POLL_INTERVAL);
                     } else {
-                        break;
-                    }
-                };
+                        interval = MIN_POLL_INTERVAL;
+                        last_hash = Some(hash);
 
-                // Get terminal content with escape sequences
-                let output = Command::new("wezterm")
-                    .arg("cli")
-                    .arg("get-text")
-                    .arg("--pane-id")
-                    .arg(&pane_id)
-                    .arg("--escapes")
-                    .output()
-                    .await;
-
-                if let Ok(output) = output {
-                    if output.status.success() {
-                        let content = String::from_utf8_lossy(&output.stdout).to_string();
-
-                        // Check if content changed
-                        let changed = {
-                            let mut mirrors_lock = mirrors.write().await;
-                            if let Some(mirror) = mirrors_lock.get_mut(&mirror_id) {
-                                if mirror.last_content != content {
-                                    mirror.last_content = content.clone();
-                                    true
-                                } else {
-                                    false
-                                }
-                            } else {
-                                false
-                            }
-                        };
-
-                        if changed {
-                            // Emit update event
-                            if let Some(handle) = &app_handle {
-                                let update = MirrorUpdate {
-                                    mirror_id: mirror_id.clone(),
-                                    content,
-                                    cursor_x: 0, // TODO: Get actual cursor position
-                                    cursor_y: 0,
-                                    viewport_start: 0,
-                                    viewport_end: 24, // TODO: Get actual viewport
-                                };
-
-                                let _ = handle.emit("wezterm-mirror-update", update);
-                            }
+                        let lines: Vec<String> = content.lines().map(|l| l.to_string()).collect();
+                        let changed_lines = diff_lines(&last_lines, &lines);
+                        // Send the whole screen on the first update or when most of it changed
+                        let full = last_lines.is_empty() || changed_lines.len() * 2 > lines.len();
+
+                        if let Some(mirror) = mirrors.write().await.get_mut(&mirror_id) {
+                            mirror.last_content = content.clone();
+                        }
+
+                        // Cursor and viewport come from the pane listing
+                        let pane = cli.find_pane(&pane_id).await.ok().flatten();
+                        let (cursor_x, cursor_y) = pane.as_ref().map(|p| p.viewport_cursor()).unwrap_or((0, 0));
+                        let (viewport_start, viewport_end) = pane.as_ref()
+                            .map(|p| p.viewport())
+                            .unwrap_or((0, lines.len() as i64));
+                        let (rows, cols) = pane.as_ref()
+                            .map(|p| (p.size.rows, p.size.cols))
+                            .unwrap_or((lines.len() as u16, 0));
+
+                        // Emit update event
+                        if let Some(handle) = &app_handle {
+                            let update = MirrorUpdate {
+                                mirror_id: mirror_id.clone(),
+                                full,
+                                content: if full { content } else { String::new() },
+                                changed_lines: if full { Vec::new() } else { changed_lines },
+                                line_count: lines.len(),
+                                cursor_x,
+                                cursor_y,
+                                viewport_start,
+                                viewport_end,
+                                rows,
+                                cols,
+                            };
+
+                            let _ = handle.emit("wezterm-mirror-update", update);
                         }
+
+                        last_lines = lines;
                     }
                 }
 
-                // Poll every 100ms
-                sleep(Duration::from_millis(100)).await;
+                sleep(interval).await;
             }
 
             println!("Polling stopped for mirror {}", mirror_id);
@@ -220,28 +253,50 @@ impl MirrorManager {
         let mirrors = self.mirrors.read().await;
 
         if let Some(mirror) = mirrors.get(mirror_id) {
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("send-text")
-                .arg("--pane-id")
-                .arg(&mirror.pane_id)
-                .arg("--no-paste")
-                .arg(text)
-                .output()
-                .await
-                .map_err(|e| format!("Failed to send input: {}", e))?;
-
-            if output.status.success() {
-                Ok(())
-            } else {
-                Err(format!("Failed to send text: {}",
-                    String::from_utf8_lossy(&output.stderr)))
-            }
+            self.cli.send_text(&mirror.pane_id, text, true).await
+                .map_err(|e| format!("Failed to send input: {}", e))
         } else {
             Err(format!("Mirror {} not found", mirror_id))
         }
     }
 
+    /// Resize the mirrored pane to match the frontend view. Returns the size
+    /// the pane ended up with, which can differ when the pane fills its window
+    /// and has no neighbour to give or take space from.
+    pub async fn resize_mirror(&self, mirror_id: &str, cols: u16, rows: u16) -> Result<CliPaneSize, String> {
+        if cols == 0 || rows == 0 {
+            return Err("Mirror size must be at least 1x1".to_string());
+        }
+
+        let pane_id = {
+            let mut mirrors = self.mirrors.write().await;
+            let mirror = mirrors.get_mut(mirror_id)
+                .ok_or_else(|| format!("Mirror {} not found", mirror_id))?;
+            mirror.cols = Some(cols);
+            mirror.rows = Some(rows);
+            mirror.pane_id.clone()
+        };
+
+        let pane = self.cli.find_pane(&pane_id).await?
+            .ok_or_else(|| format!("Pane {} no longer exists", pane_id))?;
+
+        for (direction, amount) in resize_steps(&pane.size, cols, rows) {
+            self.cli.adjust_pane_size(&pane_id, direction, amount).await
+                .map_err(|e| format!("Failed to resize mirror: {}", e))?;
+        }
+
+        let size = self.cli.find_pane(&pane_id).await?
+            .map(|p| p.size)
+            .unwrap_or(pane.size);
+        if (size.cols, size.rows) != (cols, rows) {
+            println!(
+                "Mirror {} pane is {}x{} after resize to {}x{}",
+                mirror_id, size.cols, size.rows, cols, rows
+            );
+        }
+        Ok(size)
+    }
+
     pub async fn stop_mirror(&self, mirror_id: &str) -> Result<(), String> {
         let mut mirrors = self.mirrors.write().await;
 
@@ -249,46 +304,216 @@ impl MirrorManager {
             mirror.is_active = false;
 
             // Kill the WezTerm pane
-            let _ = Command::new("wezterm")
-                .arg("cli")
-                .arg("kill-pane")
-                .arg("--pane-id")
-                .arg(&mirror.pane_id)
-                .output()
-                .await;
+            let _ = self.cli.kill_pane(&mirror.pane_id).await;
         }
 
         mirrors.remove(mirror_id);
         Ok(())
     }
 
+    /// Stop every mirror opened on a project directory. Returns the stopped mirror ids.
+    pub async fn stop_project_mirrors(&self, project_path: &str) -> Vec<String> {
+        let mirror_ids: Vec<String> = self.mirrors.read().await
+            .values()
+            .filter(|m| same_path(&m.project_path, project_path))
+            .map(|m| m.id.clone())
+            .collect();
+
+        for mirror_id in &mirror_ids {
+            let _ = self.stop_mirror(mirror_id).await;
+        }
+        mirror_ids
+    }
+
+    /// Drop mirrors whose pane no longer exists, which also ends their polling.
+    /// Nothing is dropped if WezTerm can't be reached.
+    pub async fn prune_orphaned_mirrors(&self) -> Vec<String> {
+        let Ok(panes) = self.cli.list().await else {
+            return Vec::new();
+        };
+
+        let mut mirrors = self.mirrors.write().await;
+        let orphaned: Vec<String> = mirrors.values()
+            .filter(|m| !pane_exists(&panes, &m.pane_id))
+            .map(|m| m.id.clone())
+            .collect();
+        for mirror_id in &orphaned {
+            mirrors.remove(mirror_id);
+        }
+        orphaned
+    }
+
     pub async fn get_mirror_content(&self, mirror_id: &str) -> Result<String, String> {
         let mirrors = self.mirrors.read().await;
 
         if let Some(mirror) = mirrors.get(mirror_id) {
             // Get fresh content
-            let output = Command::new("wezterm")
-                .arg("cli")
-                .arg("get-text")
-                .arg("--pane-id")
-                .arg(&mirror.pane_id)
-                .arg("--escapes")
-                .output()
-                .await
-                .map_err(|e| format!("Failed to get content: {}", e))?;
-
-            if output.status.success() {
-                Ok(String::from_utf8_lossy(&output.stdout).to_string())
-            } else {
-                Err(format!("Failed to get text: {}",
-                    String::from_utf8_lossy(&output.stderr)))
-            }
+            self.cli.get_text(&mirror.pane_id, true, None, None).await
+                .map_err(|e| format!("Failed to get content: {}", e))
         } else {
             Err(format!("Mirror {} not found", mirror_id))
         }
     }
 
+    pub async fn get_mirror(&self, mirror_id: &str) -> Option<WezTermMirror> {
+        self.mirrors.read().await.get(mirror_id).cloned()
+    }
+
+    /// Resume mirrors saved by a previous run whose pane still exists.
+    /// Returns the mirrors that were reattached.
+    pub async fn restore_mirrors(&self, saved: Vec<WezTermMirror>) -> Result<Vec<WezTermMirror>, String> {
+        let panes = self.cli.list().await?;
+
+        let mut restored = Vec::new();
+        for mut mirror in saved {
+            let Some(pane) = panes.iter().find(|p| p.pane_id.to_string() == mirror.pane_id) else {
+                continue;
+            };
+
+            mirror.window_id = format!("win_{}", pane.window_id);
+            mirror.last_content = String::new();
+            mirror.is_active = true;
+
+            self.mirrors.write().await.insert(mirror.id.clone(), mirror.clone());
+            self.start_polling(mirror.id.clone()).await;
+            restored.push(mirror);
+        }
+
+        Ok(restored)
+    }
+
+    /// Read `lines` lines of scrollback, skipping the `offset` lines directly
+    /// above the visible screen. Page back by increasing `offset` by `lines`.
+    pub async fn get_mirror_scrollback(&self, mirror_id: &str, offset: u32, lines: u32) -> Result<MirrorScrollback, String> {
+        if lines == 0 {
+            return Err("lines must be at least 1".to_string());
+        }
+
+        let pane_id = self.mirrors.read().await.get(mirror_id)
+            .map(|m| m.pane_id.clone())
+            .ok_or_else(|| format!("Mirror {} not found", mirror_id))?;
+
+        // top_row counts the scrollback rows above the screen
+        let available = self.cli.find_pane(&pane_id).await?
+            .map(|p| p.top_row.max(0) as u64)
+            .unwrap_or(0);
+
+        let (start_line, end_line) = scrollback_range(offset, lines);
+        let start_line = start_line.max(-(available as i64));
+        let content = if (offset as u64) < available {
+            self.cli.get_text(&pane_id, true, Some(start_line), Some(end_line)).await
+                .map_err(|e| format!("Failed to get scrollback: {}", e))?
+        } else {
+            String::new()
+        };
+
+        Ok(MirrorScrollback {
+            mirror_id: mirror_id.to_string(),
+            content,
+            start_line,
+            end_line,
+            has_more: (offset as u64 + lines as u64) < available,
+        })
+    }
+
     pub async fn list_mirrors(&self) -> Vec<WezTermMirror> {
         self.mirrors.read().await.values().cloned().collect()
     }
-}
\ No newline at end of file
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether two directories are the same, ignoring trailing slashes
+fn same_path(a: &str, b: &str) -> bool {
+    a.trim_end_matches('/') == b.trim_end_matches('/')
+}
+
+/// Lines of `new` that differ from `old` at the same index
+fn diff_lines(old: &[String], new: &[String]) -> Vec<MirrorLine> {
+    new.iter()
+        .enumerate()
+        .filter(|(i, line)| old.get(*i) != Some(*line))
+        .map(|(index, text)| MirrorLine { index, text: text.clone() })
+        .collect()
+}
+
+/// `get-text` line range for a scrollback page: `lines` lines ending
+/// `offset` lines above the top of the screen
+fn scrollback_range(offset: u32, lines: u32) -> (i64, i64) {
+    let end_line = -(offset as i64) - 1;
+    (end_line - lines as i64 + 1, end_line)
+}
+
+/// `adjust-pane-size` calls needed to take a pane from `current` to `cols`x`rows`
+fn resize_steps(current: &CliPaneSize, cols: u16, rows: u16) -> Vec<(PaneDirection, u16)> {
+    let mut steps = Vec::new();
+    if cols > current.cols {
+        steps.push((PaneDirection::Right, cols - current.cols));
+    } else if cols < current.cols {
+        steps.push((PaneDirection::Left, current.cols - cols));
+    }
+    if rows > current.rows {
+        steps.push((PaneDirection::Down, rows - current.rows));
+    } else if rows < current.rows {
+        steps.push((PaneDirection::Up, current.rows - rows));
+    }
+    steps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<String> {
+        text.lines().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_diff_lines_reports_changed_and_added_lines() {
+        let diff = diff_lines(&lines("a\nb\nc"), &lines("a\nB\nc\nd"));
+
+        assert_eq!(diff, vec![
+            MirrorLine { index: 1, text: "B".to_string() },
+            MirrorLine { index: 3, text: "d".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_resize_steps() {
+        let current = CliPaneSize { rows: 24, cols: 80, ..Default::default() };
+
+        assert!(resize_steps(&current, 80, 24).is_empty());
+        assert_eq!(resize_steps(&current, 100, 20), vec![
+            (PaneDirection::Right, 20),
+            (PaneDirection::Up, 4),
+        ]);
+        assert_eq!(resize_steps(&current, 60, 30), vec![
+            (PaneDirection::Left, 20),
+            (PaneDirection::Down, 6),
+        ]);
+    }
+
+    #[test]
+    fn test_same_path_ignores_trailing_slashes() {
+        assert!(same_path("/work/app/", "/work/app"));
+        assert!(same_path("/work/app", "/work/app//"));
+        assert!(!same_path("/work/app", "/work/app2"));
+        assert!(!same_path("/work/app", "/work"));
+    }
+
+    #[test]
+    fn test_scrollback_range() {
+        assert_eq!(scrollback_range(0, 100), (-100, -1));
+        assert_eq!(scrollback_range(100, 100), (-200, -101));
+    }
+
+    #[test]
+    fn test_content_hash_detects_changes() {
+        assert_eq!(content_hash("same"), content_hash("same"));
+        assert_ne!(content_hash("before"), content_hash("after"));
+    }
+}
@@ -1,7 +1,15 @@
+pub mod capture;
+pub mod cli;
 pub mod controller;
+pub mod keys;
 pub mod mirror;
+pub mod mux;
+pub mod probe;
 pub mod types;
 
+pub use cli::{CliPaneInfo, CliPaneSize, WezTermCli};
 pub use controller::WezTermController;
-pub use mirror::{MirrorManager, MirrorUpdate, WezTermMirror};
+pub use keys::KeyModifier;
+pub use mirror::{MirrorManager, MirrorScrollback, MirrorUpdate, WezTermMirror};
+pub use probe::{WezTermCapabilities, WezTermStatus};
 pub use types::*;
\ No newline at end of file
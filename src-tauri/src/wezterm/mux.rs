@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+/// Environment variable `wezterm cli` reads to pick the mux socket
+pub const SOCKET_ENV: &str = "WEZTERM_UNIX_SOCKET";
+
+/// Locates the unix socket of the running WezTerm mux and pins it for every
+/// `wezterm cli` call, so the CLI skips its own discovery and can't pick a
+/// different GUI instance. The resolved path is cached and shared between
+/// clones.
+///
+/// This is not a mux client: pane IO does not go over the socket. Mirror
+/// polls (`get_text`, `find_pane`) and `send_text` each still spawn a
+/// `wezterm cli` process. Speaking the mux protocol directly would mean
+/// tracking WezTerm's codec, which changes between releases and
+/// zstd-compresses larger replies, and `wezterm cli` has no long-lived
+/// mode besides the raw `proxy` pipe.
+#[derive(Debug, Clone, Default)]
+pub struct MuxSocket {
+    cached: Arc<RwLock<Option<PathBuf>>>,
+}
+
+impl MuxSocket {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached socket, otherwise a freshly discovered one. The cache is
+    /// trusted until `invalidate`; a stale socket shows up as a connection
+    /// error from the CLI.
+    pub async fn resolve(&self) -> Option<PathBuf> {
+        let cached = self.cached.read().unwrap().clone();
+        if cached.is_some() {
+            return cached;
+        }
+
+        let path = discover().await;
+        *self.cached.write().unwrap() = path.clone();
+        path
+    }
+
+    /// Forget the cached socket, e.g. after the mux went away
+    pub fn invalidate(&self) {
+        *self.cached.write().unwrap() = None;
+    }
+}
+
+/// Whether `wezterm cli` failed because it couldn't reach the mux, rather
+/// than because of the command itself
+pub fn is_connection_error(stderr: &str) -> bool {
+    let stderr = stderr.to_ascii_lowercase();
+    ["failed to connect", "connection refused", "broken pipe", "no running wezterm"]
+        .iter()
+        .any(|needle| stderr.contains(needle))
+}
+
+/// Find a listening mux socket: `$WEZTERM_UNIX_SOCKET` first, then the
+/// GUI and mux-server sockets in WezTerm's runtime directories
+pub async fn discover() -> Option<PathBuf> {
+    if let Ok(path) = std::env::var(SOCKET_ENV) {
+        let path = PathBuf::from(path);
+        if is_listening(&path).await {
+            return Some(path);
+        }
+    }
+
+    for dir in runtime_dirs() {
+        for path in candidates_in(&dir).await {
+            if is_listening(&path).await {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+fn runtime_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(runtime_dir) = dirs::runtime_dir() {
+        dirs.push(runtime_dir.join("wezterm"));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".local").join("share").join("wezterm"));
+    }
+    dirs
+}
+
+async fn candidates_in(dir: &Path) -> Vec<PathBuf> {
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+
+    let mut named = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        if let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) {
+            named.push((entry.file_name().to_string_lossy().to_string(), modified));
+        }
+    }
+
+    order_candidates(named).into_iter().map(|name| dir.join(name)).collect()
+}
+
+/// Socket file names worth trying, newest GUI socket first and the
+/// standalone mux server socket last
+fn order_candidates(mut entries: Vec<(String, SystemTime)>) -> Vec<String> {
+    entries.retain(|(name, _)| name.starts_with("gui-sock-") || name == "sock");
+    entries.sort_by(|(a_name, a_time), (b_name, b_time)| {
+        (a_name == "sock").cmp(&(b_name == "sock")).then(b_time.cmp(a_time))
+    });
+    entries.into_iter().map(|(name, _)| name).collect()
+}
+
+#[cfg(unix)]
+async fn is_listening(path: &Path) -> bool {
+    tokio::net::UnixStream::connect(path).await.is_ok()
+}
+
+#[cfg(not(unix))]
+async fn is_listening(path: &Path) -> bool {
+    tokio::fs::try_exists(path).await.unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_order_candidates_prefers_newest_gui_socket() {
+        let t = SystemTime::UNIX_EPOCH;
+        let entries = vec![
+            ("sock".to_string(), t + Duration::from_secs(30)),
+            ("gui-sock-100".to_string(), t + Duration::from_secs(10)),
+            ("wezterm.log".to_string(), t + Duration::from_secs(40)),
+            ("gui-sock-200".to_string(), t + Duration::from_secs(20)),
+        ];
+
+        assert_eq!(order_candidates(entries), vec!["gui-sock-200", "gui-sock-100", "sock"]);
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        assert!(is_connection_error(
+            "Error: failed to connect to Socket(\"/run/user/1000/wezterm/gui-sock-42\")\n\nCaused by:\n    Connection refused (os error 111)"
+        ));
+        assert!(!is_connection_error("Error: pane 42 not found"));
+        assert!(!is_connection_error("error: unexpected argument '--escapes' found"));
+    }
+}
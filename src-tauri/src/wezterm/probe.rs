@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+
+pub const NOT_INSTALLED: &str = "WezTerm is not installed";
+
+/// `wezterm cli` features the app relies on
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WezTermCapabilities {
+    /// `wezterm cli list --format json`
+    pub list_json: bool,
+    pub get_text: bool,
+    /// `wezterm cli get-text --escapes`
+    pub get_text_escapes: bool,
+    pub split_pane: bool,
+    pub adjust_pane_size: bool,
+    pub activate_pane: bool,
+}
+
+/// Result of the startup probe, shown by the UI when WezTerm is missing or too old
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WezTermStatus {
+    pub installed: bool,
+    pub binary: Option<String>,
+    pub version: Option<String>,
+    pub capabilities: WezTermCapabilities,
+    pub error: Option<String>,
+}
+
+impl WezTermStatus {
+    pub fn not_installed() -> Self {
+        Self { error: Some(NOT_INSTALLED.to_string()), ..Default::default() }
+    }
+}
+
+/// Find the wezterm binary on PATH or in the usual install locations. GUI
+/// apps on macOS don't inherit the shell PATH, so those are checked too.
+pub fn locate_binary() -> Option<PathBuf> {
+    let name = if cfg!(windows) { "wezterm.exe" } else { "wezterm" };
+
+    let path_dirs = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut known_dirs = vec![
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/usr/local/bin"),
+        PathBuf::from("/Applications/WezTerm.app/Contents/MacOS"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        known_dirs.push(home.join("Applications/WezTerm.app/Contents/MacOS"));
+    }
+
+    path_dirs.into_iter()
+        .chain(known_dirs)
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Run the binary's version and help output to find out what it supports
+pub async fn probe(binary: &Path) -> WezTermStatus {
+    let version = match run(binary, &["--version"]).await {
+        Ok(output) => parse_version(&output),
+        Err(e) => {
+            return WezTermStatus {
+                binary: Some(binary.display().to_string()),
+                error: Some(format!("Failed to run {}: {}", binary.display(), e)),
+                ..Default::default()
+            };
+        }
+    };
+
+    let subcommands = run(binary, &["cli", "--help"]).await
+        .map(|help| parse_subcommands(&help))
+        .unwrap_or_default();
+    let has = |name: &str| subcommands.iter().any(|s| s == name);
+
+    let list_json = has("list") && run(binary, &["cli", "list", "--help"]).await
+        .map(|help| help.contains("--format"))
+        .unwrap_or(false);
+    let get_text_escapes = has("get-text") && run(binary, &["cli", "get-text", "--help"]).await
+        .map(|help| help.contains("--escapes"))
+        .unwrap_or(false);
+
+    let capabilities = WezTermCapabilities {
+        list_json,
+        get_text: has("get-text"),
+        get_text_escapes,
+        split_pane: has("split-pane"),
+        adjust_pane_size: has("adjust-pane-size"),
+        activate_pane: has("activate-pane"),
+    };
+
+    let error = if !capabilities.list_json || !capabilities.get_text {
+        Some(format!(
+            "WezTerm {} is too old; please upgrade to a release with `wezterm cli get-text`",
+            version.as_deref().unwrap_or("(unknown version)")
+        ))
+    } else {
+        None
+    };
+
+    WezTermStatus {
+        installed: true,
+        binary: Some(binary.display().to_string()),
+        version,
+        capabilities,
+        error,
+    }
+}
+
+async fn run(binary: &Path, args: &[&str]) -> Result<String, String> {
+    let output = Command::new(binary)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// `wezterm 20240203-110809-5046fc22` -> `20240203-110809-5046fc22`
+fn parse_version(output: &str) -> Option<String> {
+    let line = output.lines().next()?.trim();
+    let version = line.strip_prefix("wezterm").unwrap_or(line).trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// Subcommand names from the `Commands:` section of clap help output
+fn parse_subcommands(help: &str) -> Vec<String> {
+    help.lines()
+        .skip_while(|line| !line.trim_end().ends_with("Commands:"))
+        .skip(1)
+        .take_while(|line| line.starts_with(' '))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| name.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("wezterm 20240203-110809-5046fc22\n").as_deref(), Some("20240203-110809-5046fc22"));
+        assert_eq!(parse_version(""), None);
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        let help = "Interact with experimental mux server\n\n\
+                    Usage: wezterm cli [OPTIONS] <COMMAND>\n\n\
+                    Commands:\n  \
+                    list        list windows, tabs and panes\n  \
+                    split-pane  split the current pane\n  \
+                    get-text    Retrieves the textual content of a pane\n  \
+                    help        Print this message\n\n\
+                    Options:\n  \
+                    --no-auto-start  Don't automatically start the server\n";
+
+        assert_eq!(parse_subcommands(help), vec!["list", "split-pane", "get-text", "help"]);
+    }
+}
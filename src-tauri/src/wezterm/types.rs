@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WezTermDomain {
@@ -32,12 +33,17 @@ pub struct CommandResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WezTermWindow {
     pub window_id: String,
+    #[serde(default)]
+    pub tab_id: Option<String>,
     pub pane_id: String,
     pub project_id: Option<String>,
     pub working_dir: String,
     pub position: Option<(i32, i32)>,
     pub size: Option<(u32, u32)>,
     pub pid: Option<u32>,
+    /// Pane ids by layout role (`agent`, `logs`, `shell`, ...)
+    #[serde(default)]
+    pub panes: HashMap<String, String>,
     pub created_at: String,
 }
 
@@ -51,4 +57,93 @@ pub struct WindowPosition {
 pub struct WindowSize {
     pub width: u32,
     pub height: u32,
-}
\ No newline at end of file
+}
+
+/// What `close_project_terminals` tore down
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectTerminalCleanup {
+    pub closed_windows: Vec<String>,
+    pub stopped_mirrors: Vec<String>,
+    /// Tracked windows and mirrors whose pane had already disappeared
+    pub pruned_windows: Vec<String>,
+    pub pruned_mirrors: Vec<String>,
+    /// Embedded PTY terminals running in the project
+    pub killed_terminals: Vec<String>,
+}
+
+/// Screen position and pixel size of a GUI window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Side of the existing pane the new pane is placed on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl SplitDirection {
+    pub fn cli_flag(&self) -> &'static str {
+        match self {
+            SplitDirection::Left => "--left",
+            SplitDirection::Right => "--right",
+            SplitDirection::Top => "--top",
+            SplitDirection::Bottom => "--bottom",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaneDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl PaneDirection {
+    pub fn cli_arg(&self) -> &'static str {
+        match self {
+            PaneDirection::Up => "Up",
+            PaneDirection::Down => "Down",
+            PaneDirection::Left => "Left",
+            PaneDirection::Right => "Right",
+        }
+    }
+}
+
+/// One pane split off an existing pane when a project window opens
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaneLayoutEntry {
+    pub role: String,
+    /// Role of the pane to split; the window's first pane is `agent`
+    #[serde(default = "default_split_from")]
+    pub split_from: String,
+    pub direction: SplitDirection,
+    /// Size of the new pane as a percentage of the split pane
+    pub percent: u8,
+    pub command: Option<String>,
+}
+
+fn default_split_from() -> String {
+    AGENT_PANE_ROLE.to_string()
+}
+
+pub const AGENT_PANE_ROLE: &str = "agent";
+
+/// Pane arrangement recreated every time a project window is spawned. The
+/// default is the agent pane on its own.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PaneLayout {
+    #[serde(default)]
+    pub panes: Vec<PaneLayoutEntry>,
+}
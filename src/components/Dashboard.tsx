@@ -15,7 +15,7 @@ interface DashboardProps {
 interface SlackStatus {
   initialized: boolean;
   service_running: boolean;
-  port?: number;
+  socket_connected?: boolean;
   connected_channels?: number;
 }
 
@@ -52,22 +52,16 @@ const Dashboard: React.FC<DashboardProps> = ({ servers, sessions, onProjectSelec
   };
 
   const loadServiceStatuses = async () => {
-    // Load Slack status - call service directly instead of through Rust
+    // Load Slack status
     try {
-      const response = await fetch('http://localhost:3456/status');
-      if (response.ok) {
-        const status = await response.json();
-        setSlackStatus({
-          initialized: status.initialized || false,
-          service_running: status.service_running || false,
-          port: status.port || 3456,
-          connected_channels: status.connected_channels || 0
-        });
-      } else {
-        setSlackStatus({ initialized: false, service_running: false });
-      }
+      const status = await invoke<any>('get_slack_status');
+      setSlackStatus({
+        initialized: status.initialized || false,
+        service_running: status.service_running || false,
+        socket_connected: status.socket_connected || false,
+        connected_channels: status.connected_channels || 0
+      });
     } catch (error) {
-      // Slack service not running - this is expected if not configured
       setSlackStatus({ initialized: false, service_running: false });
     } finally {
       setLoadingSlack(false);
@@ -219,7 +213,7 @@ const Dashboard: React.FC<DashboardProps> = ({ servers, sessions, onProjectSelec
                 <h4 className="font-bold text-sm mb-1">Slack Service</h4>
                 <p className="text-xs text-gray-600 mb-3">
                   {slackStatus.service_running && slackStatus.initialized
-                    ? `Port ${slackStatus.port || 3456} • ${slackStatus.connected_channels || 0} channels`
+                    ? `${slackStatus.socket_connected ? 'Socket connected' : 'Reconnecting'} • ${slackStatus.connected_channels || 0} channels`
                     : slackStatus.service_running
                     ? 'Service running - configure in Admin → Notifications'
                     : 'Approval and notification service'
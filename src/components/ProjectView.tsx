@@ -21,6 +21,11 @@ import ClaudeAgentDirectUI from './plugins/ClaudeAgentDirectUI';
 import { getDevCommand, getAllScripts } from '../utils/packageManager';
 import { ollamaService } from '../services/OllamaService';
 
+interface PaneCapture {
+  screen: string;
+  history: string | null;
+}
+
 interface TmuxSession {
   id: string;
   name: string;
@@ -801,19 +806,13 @@ const ProjectView: React.FC<ProjectViewProps> = ({ project, onBack, onEdit, onDe
 
   const captureTmuxContent = async (sessionId: string) => {
     try {
-      const rawContent = await invoke<string>('capture_tmux_pane', {
-        sessionId
+      const capture = await invoke<PaneCapture>('capture_tmux_pane', {
+        sessionId,
+        options: { include_history: true }
       });
 
-      // Parse the response - it may contain both display and log content
-      let displayContent = rawContent;
-      let logContent = rawContent;
-
-      if (rawContent.includes('<<<TMUX_SEPARATOR>>>')) {
-        const parts = rawContent.split('<<<TMUX_SEPARATOR>>>');
-        displayContent = parts[0]; // Current terminal display
-        logContent = parts[1]; // Full log for AI context
-      }
+      const displayContent = capture.screen; // Current terminal display
+      const logContent = capture.history ?? capture.screen; // Full history for AI context
 
       // DEBUG: Log raw content during generation
       if (isOpenCodeGenerating || hasSeenWorkingState) {
@@ -16,13 +16,23 @@ interface TerminalProps {
   mirrorId?: string;  // If set, terminal will display mirrored WezTerm content
 }
 
+interface MirrorLine {
+  index: number;
+  text: string;
+}
+
 interface MirrorUpdate {
   mirror_id: string;
+  full: boolean;
   content: string;
+  changed_lines: MirrorLine[];
+  line_count: number;
   cursor_x: number;
   cursor_y: number;
   viewport_start: number;
   viewport_end: number;
+  rows: number;
+  cols: number;
 }
 
 interface TerminalSession {
@@ -133,7 +143,11 @@ export const Terminal: React.FC<TerminalProps> = ({ serverId, sessionId, isNewSe
     const resizeObserver = new ResizeObserver(() => {
       try {
         if (fitAddonRef.current && xtermRef.current) {
+          const { cols, rows } = xtermRef.current;
           fitAddonRef.current.fit();
+          if (mirrorId && (xtermRef.current.cols !== cols || xtermRef.current.rows !== rows)) {
+            resizeMirror(xtermRef.current.cols, xtermRef.current.rows);
+          }
         }
       } catch (error) {
         console.error('Error fitting terminal:', error);
@@ -184,7 +198,9 @@ export const Terminal: React.FC<TerminalProps> = ({ serverId, sessionId, isNewSe
             return;
           }
           fitAddonRef.current.fit();
-          if (terminalSessionId) {
+          if (mirrorId) {
+            resizeMirror(xtermRef.current.cols, xtermRef.current.rows);
+          } else if (terminalSessionId) {
             resizeTerminal(xtermRef.current.cols, xtermRef.current.rows);
           }
         }
@@ -362,8 +378,20 @@ export const Terminal: React.FC<TerminalProps> = ({ serverId, sessionId, isNewSe
         }
       });
 
+      // The backend drops the terminal once its process exits
+      const unlistenExit = await listen<{ exit_code: number; success: boolean }>(`terminal-exited-${terminalId}`, (event) => {
+        xtermRef.current?.writeln(`\r\n\x1b[90m[Process exited with code ${event.payload.exit_code}]\x1b[0m`);
+        setIsConnected(false);
+        unlisten();
+        unlistenExit();
+        delete (window as any)[`unlisten-${terminalId}`];
+      });
+
       // Store unlisten function for cleanup
-      (window as any)[`unlisten-${terminalId}`] = unlisten;
+      (window as any)[`unlisten-${terminalId}`] = () => {
+        unlisten();
+        unlistenExit();
+      };
     } catch (error) {
       console.error('Failed to set up terminal output listener:', error);
     }
@@ -399,6 +427,16 @@ export const Terminal: React.FC<TerminalProps> = ({ serverId, sessionId, isNewSe
     }
   };
 
+  const resizeMirror = async (cols: number, rows: number) => {
+    if (!mirrorId) return;
+
+    try {
+      await invoke('resize_mirror', { mirrorId, cols, rows });
+    } catch (error) {
+      console.error('Failed to resize mirror:', error);
+    }
+  };
+
   const killTerminal = async () => {
     if (!terminalSessionId) return;
 
@@ -429,9 +467,18 @@ export const Terminal: React.FC<TerminalProps> = ({ serverId, sessionId, isNewSe
       mirrorUnlistenRef.current = await listen<MirrorUpdate>('wezterm-mirror-update', (event) => {
         const update = event.payload;
         if (update.mirror_id === mirrorId && xtermRef.current) {
-          // Clear and write the new content
-          xtermRef.current.clear();
-          xtermRef.current.write(update.content);
+          if (update.full) {
+            // Redraw the whole screen
+            xtermRef.current.write('\x1b[H\x1b[2J' + update.content.split('\n').join('\r\n'));
+          } else {
+            // Rewrite only the lines that changed, then clear any trailing rows
+            let patch = '';
+            for (const line of update.changed_lines) {
+              patch += `\x1b[${line.index + 1};1H\x1b[2K${line.text}`;
+            }
+            patch += `\x1b[${update.line_count + 1};1H\x1b[J`;
+            xtermRef.current.write(patch);
+          }
 
           // Position cursor if provided
           if (update.cursor_x !== undefined && update.cursor_y !== undefined) {
@@ -4,6 +4,11 @@ import { listen, UnlistenFn } from '@tauri-apps/api/event';
 import Terminal from './Terminal';
 import { Play, Square, Terminal as TerminalIcon, Send, RefreshCw, X, Trash2, Keyboard, KeyboardOff } from 'lucide-react';
 
+interface PaneCapture {
+  screen: string;
+  history: string | null;
+}
+
 interface TmuxSession {
   id: string;
   name: string;
@@ -78,7 +83,7 @@ const TmuxExperiment: React.FC = () => {
       // Set up interval to refresh every 100ms
       autoRefreshIntervalRef.current = setInterval(async () => {
         try {
-          const content = await invoke<string>('capture_tmux_pane', {
+          const { screen: content } = await invoke<PaneCapture>('capture_tmux_pane', {
             sessionId: activeSession.id
           });
           // Clean up the output by removing all border characters and box drawing
@@ -274,7 +279,7 @@ const TmuxExperiment: React.FC = () => {
       // Small delay to allow tmux to process the input
       setTimeout(async () => {
         try {
-          const content = await invoke<string>('capture_tmux_pane', {
+          const { screen: content } = await invoke<PaneCapture>('capture_tmux_pane', {
             sessionId: activeSession.id
           });
           // Clean up the output by removing all border characters and box drawing
@@ -328,7 +333,7 @@ const TmuxExperiment: React.FC = () => {
     if (!id) return;
 
     try {
-      const content = await invoke<string>('capture_tmux_pane', {
+      const { screen: content } = await invoke<PaneCapture>('capture_tmux_pane', {
         sessionId: id
       });
       // Clean up the output by removing all border characters and box drawing
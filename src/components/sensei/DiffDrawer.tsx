@@ -42,9 +42,10 @@ export const DiffDrawer: React.FC<DiffDrawerProps> = ({
     setLoading(true);
     setError(null);
     try {
-      const files = await invoke<string[]>('get_git_changed_files', {
+      const status = await invoke<{ unstaged: { path: string }[]; conflicted: { path: string }[] }>('get_git_status', {
         workingDir: workingDirectory
       });
+      const files = [...status.unstaged, ...status.conflicted].map((file) => file.path);
       setChangedFiles(files);
 
       // Auto-select first file if available
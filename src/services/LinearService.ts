@@ -43,6 +43,10 @@ class LinearService {
     if (saved) {
       try {
         this.config = JSON.parse(saved);
+        // The backend only keeps the config in memory
+        invoke('update_linear_config', { config: this.config }).catch(error => {
+          console.error('Failed to apply Linear config:', error);
+        });
       } catch (error) {
         console.error('Failed to load Linear config:', error);
       }
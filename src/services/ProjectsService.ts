@@ -1,12 +1,100 @@
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
-import type { Project, CreateProjectRequest, UpdateProjectRequest } from '../types/project';
+import type { Project, CreateProjectRequest, UpdateProjectRequest, ProjectTemplate, Workspace, WorkspaceMemberResult, ProjectCandidate, LaunchRequest, LaunchConfig, ProjectActivityEvent, DeleteProjectOptions, ProjectDeletionReport, CommandTarget, SavedCommand, SavedCommandRun } from '../types/project';
 
 class ProjectsService {
   async createProject(request: CreateProjectRequest): Promise<Project> {
     return await invoke<Project>('create_project', { request });
   }
 
+  async createProjectFromTemplate(templateId: string, request: CreateProjectRequest): Promise<Project> {
+    return await invoke<Project>('create_project_from_template', { templateId, request });
+  }
+
+  async saveProjectTemplate(projectId: string, name: string, description?: string): Promise<ProjectTemplate> {
+    return await invoke<ProjectTemplate>('save_project_template', { projectId, name, description });
+  }
+
+  async listProjectTemplates(): Promise<ProjectTemplate[]> {
+    return await invoke<ProjectTemplate[]>('list_project_templates');
+  }
+
+  async deleteProjectTemplate(id: string): Promise<boolean> {
+    return await invoke<boolean>('delete_project_template', { id });
+  }
+
+  async createWorkspace(name: string, description?: string): Promise<Workspace> {
+    return await invoke<Workspace>('create_workspace', { name, description });
+  }
+
+  async listWorkspaces(): Promise<Workspace[]> {
+    return await invoke<Workspace[]>('list_workspaces');
+  }
+
+  async deleteWorkspace(id: string): Promise<boolean> {
+    return await invoke<boolean>('delete_workspace', { id });
+  }
+
+  async addProjectToWorkspace(workspaceId: string, projectId: string): Promise<Workspace> {
+    return await invoke<Workspace>('add_project_to_workspace', { workspaceId, projectId });
+  }
+
+  async removeProjectFromWorkspace(workspaceId: string, projectId: string): Promise<Workspace> {
+    return await invoke<Workspace>('remove_project_from_workspace', { workspaceId, projectId });
+  }
+
+  async spawnWorkspaceAgents(workspaceId: string, model?: string): Promise<WorkspaceMemberResult[]> {
+    return await invoke<WorkspaceMemberResult[]>('spawn_workspace_agents', { workspaceId, model });
+  }
+
+  async distributeWorkspaceTask(workspaceId: string, prompt: string): Promise<WorkspaceMemberResult[]> {
+    return await invoke<WorkspaceMemberResult[]>('distribute_workspace_task', { workspaceId, prompt });
+  }
+
+  async exportProject(id: string, path?: string): Promise<string> {
+    return await invoke<string>('export_project', { id, path });
+  }
+
+  async readProjectBundle(path: string): Promise<{ version: number; exportedAt: string; project: Project }> {
+    return await invoke('read_project_bundle', { path });
+  }
+
+  async importProject(path: string, projectPath?: string): Promise<Project> {
+    return await invoke<Project>('import_project', { path, projectPath });
+  }
+
+  async scanForProjects(rootDirs?: string[]): Promise<ProjectCandidate[]> {
+    return await invoke<ProjectCandidate[]>('scan_for_projects', { rootDirs });
+  }
+
+  async registerProjects(candidates: ProjectCandidate[]): Promise<Project[]> {
+    return await invoke<Project[]>('register_projects', { candidates });
+  }
+
+  async setProjectEnv(id: string, env: Record<string, string>): Promise<Project | null> {
+    return await invoke<Project | null>('set_project_env', { id, env });
+  }
+
+  async setProjectSecret(id: string, name: string, value: string): Promise<Project | null> {
+    return await invoke<Project | null>('set_project_secret', { id, name, value });
+  }
+
+  async deleteProjectSecret(id: string, name: string): Promise<Project | null> {
+    return await invoke<Project | null>('delete_project_secret', { id, name });
+  }
+
+  async resolveLaunchConfig(request: LaunchRequest): Promise<LaunchConfig> {
+    return await invoke<LaunchConfig>('resolve_launch_config', { request });
+  }
+
+  async searchProjects(query: string, limit?: number): Promise<Project[]> {
+    return await invoke<Project[]>('search_projects', { query, limit });
+  }
+
+  async getProjectActivity(projectId: string, limit?: number): Promise<ProjectActivityEvent[]> {
+    return await invoke<ProjectActivityEvent[]>('get_project_activity', { projectId, limit });
+  }
+
   async getProject(id: string): Promise<Project | null> {
     return await invoke<Project | null>('get_project', { id });
   }
@@ -15,16 +103,60 @@ class ProjectsService {
     return await invoke<Project | null>('get_project_by_path', { path });
   }
 
-  async listProjects(): Promise<Project[]> {
-    return await invoke<Project[]>('list_projects');
+  async listProjects(tags?: string[], includeArchived?: boolean): Promise<Project[]> {
+    return await invoke<Project[]>('list_projects', { tags, includeArchived });
+  }
+
+  async checkProjectPaths(): Promise<Project[]> {
+    return await invoke<Project[]>('check_project_paths');
+  }
+
+  async relocateProject(id: string, newPath: string): Promise<Project | null> {
+    return await invoke<Project | null>('relocate_project', { id, newPath });
+  }
+
+  async archiveProject(id: string): Promise<Project | null> {
+    return await invoke<Project | null>('archive_project', { id });
+  }
+
+  async unarchiveProject(id: string): Promise<Project | null> {
+    return await invoke<Project | null>('unarchive_project', { id });
+  }
+
+  async addProjectTag(id: string, tag: string): Promise<string[]> {
+    return await invoke<string[]>('add_project_tag', { id, tag });
+  }
+
+  async removeProjectTag(id: string, tag: string): Promise<string[]> {
+    return await invoke<string[]>('remove_project_tag', { id, tag });
+  }
+
+  async listProjectTags(): Promise<{ tag: string; projects: number }[]> {
+    return await invoke<{ tag: string; projects: number }[]>('list_project_tags');
+  }
+
+  async saveProjectCommand(projectId: string, name: string, command: string, target: CommandTarget): Promise<SavedCommand> {
+    return await invoke<SavedCommand>('save_project_command', { projectId, name, command, target });
+  }
+
+  async listProjectCommands(projectId: string): Promise<SavedCommand[]> {
+    return await invoke<SavedCommand[]>('list_project_commands', { projectId });
+  }
+
+  async deleteProjectCommand(projectId: string, name: string): Promise<boolean> {
+    return await invoke<boolean>('delete_project_command', { projectId, name });
+  }
+
+  async executeSavedCommand(projectId: string, name: string): Promise<SavedCommandRun> {
+    return await invoke<SavedCommandRun>('execute_saved_command', { projectId, name });
   }
 
   async listFavoriteProjects(): Promise<Project[]> {
     return await invoke<Project[]>('list_favorite_projects');
   }
 
-  async listRecentProjects(limit: number): Promise<Project[]> {
-    return await invoke<Project[]>('list_recent_projects', { limit });
+  async listRecentProjects(limit: number, includeArchived?: boolean): Promise<Project[]> {
+    return await invoke<Project[]>('list_recent_projects', { limit, includeArchived });
   }
 
   async updateProject(id: string, request: UpdateProjectRequest): Promise<Project | null> {
@@ -35,8 +167,8 @@ class ProjectsService {
     await invoke('update_project_last_accessed', { id });
   }
 
-  async deleteProject(id: string): Promise<boolean> {
-    return await invoke<boolean>('delete_project', { id });
+  async deleteProject(id: string, options?: DeleteProjectOptions): Promise<ProjectDeletionReport> {
+    return await invoke<ProjectDeletionReport>('delete_project', { id, options });
   }
 
   async projectExists(path: string): Promise<boolean> {
@@ -82,35 +82,7 @@ class SlackService {
 
       this.config = config;
 
-      // Check if service is already running by checking the status endpoint
-      console.log('🔍 Checking if Slack service is already running...');
-      try {
-        const statusResponse = await fetch('http://localhost:3456/status');
-        if (statusResponse.ok) {
-          console.log('✅ Slack service is already running');
-          this.serviceStarted = true;
-        } else {
-          throw new Error('Service not responding');
-        }
-      } catch (error) {
-        // Service not running, try to start it
-        console.log('🚀 Starting Slack service process...');
-        try {
-          await invoke('start_slack_service');
-          this.serviceStarted = true;
-          console.log('⏳ Waiting for Slack service to be ready (3s)...');
-          // Give the service time to start
-          await new Promise(resolve => setTimeout(resolve, 3000));
-          console.log('✅ Slack service process started');
-        } catch (startError) {
-          console.error('❌ Failed to start Slack service process:', startError);
-          console.error('💡 Make sure Node.js is installed and dependencies are installed:');
-          console.error('   cd src-tauri/scripts && npm install');
-          return false;
-        }
-      }
-
-      // Initialize Slack with config - call service directly
+      // Initialize Slack with config
       try {
         console.log('📤 Sending initialization request to Slack service...');
         const payload = {
@@ -128,37 +100,11 @@ class SlackService {
           enabled: config.enabled
         });
 
-        const response = await fetch('http://localhost:3456/initialize', {
-          method: 'POST',
-          headers: { 'Content-Type': 'application/json' },
-          body: JSON.stringify(payload)
-        });
-
-        console.log('📬 Response status:', response.status);
-
-        if (response.ok) {
-          const result = await response.json();
-          console.log('📬 Response body:', result);
-          if (result.success) {
-            this.isInitialized = true;
-            console.log('⚡️ Slack integration initialized successfully!');
-            return true;
-          } else {
-            const errorMsg = result.error || result.message || 'Unknown error';
-            console.error('❌ Slack initialization failed:', errorMsg);
-            throw new Error(errorMsg);
-          }
-        } else {
-          let errorMsg = 'Unknown error';
-          try {
-            const result = await response.json();
-            errorMsg = result.error || result.message || `HTTP ${response.status}`;
-          } catch {
-            errorMsg = await response.text() || `HTTP ${response.status}`;
-          }
-          console.error('❌ Slack initialization request failed:', response.status, errorMsg);
-          throw new Error(errorMsg);
-        }
+        await invoke('initialize_slack', { config: payload });
+        this.isInitialized = true;
+        this.serviceStarted = true;
+        console.log('⚡️ Slack integration initialized successfully!');
+        return true;
       } catch (error) {
         console.error('❌ Failed to initialize Slack:', error);
         this.isInitialized = false;
@@ -207,20 +153,9 @@ class SlackService {
         project_name: request.projectName || 'Unknown Project' // snake_case for Rust
       };
 
-      // Call service directly instead of through Rust
-      const response = await fetch('http://localhost:3456/send-approval', {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify(serializedRequest)
-      });
-
-      if (response.ok) {
-        console.log('Slack approval request sent');
-        return true;
-      } else {
-        console.error('Failed to send Slack approval, status:', response.status);
-        return false;
-      }
+      await invoke('send_slack_approval', { request: serializedRequest });
+      console.log('Slack approval request sent');
+      return true;
     } catch (error) {
       console.error('Failed to send Slack approval:', error);
       return false;
@@ -233,18 +168,8 @@ class SlackService {
     }
 
     try {
-      // Call service directly instead of through Rust
-      const response = await fetch('http://localhost:3456/send-message', {
-        method: 'POST',
-        headers: { 'Content-Type': 'application/json' },
-        body: JSON.stringify(options)
-      });
-
-      if (response.ok) {
-        const result = await response.json();
-        return result.success;
-      }
-      return false;
+      await invoke('send_slack_message', { message: { text: options.text, blocks: options.blocks ?? null } });
+      return true;
     } catch (error) {
       console.error('Failed to send Slack message:', error);
       return false;
@@ -297,19 +222,9 @@ class SlackService {
 
   async getStatus(): Promise<any> {
     try {
-      // Call service directly instead of through Rust
-      const response = await fetch('http://localhost:3456/status', {
-        signal: AbortSignal.timeout(2000) // 2 second timeout
-      });
-      if (response.ok) {
-        return await response.json();
-      }
-      return null;
+      return await invoke('get_slack_status');
     } catch (error) {
-      // Don't log timeout/connection errors during startup - they're expected
-      if (error.name !== 'AbortError' && error.name !== 'TypeError') {
-        console.error('Failed to get Slack status:', error);
-      }
+      console.error('Failed to get Slack status:', error);
       return null;
     }
   }
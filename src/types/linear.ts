@@ -165,6 +165,8 @@ export interface LinearConfig {
   syncInterval?: number; // in minutes
   enableWebhooks?: boolean;
   webhookSecret?: string;
+  webhookPort?: number; // local port the webhook listener binds to, default 8787
+  agentLabel?: string; // label that hands an issue to an agent, default "agent"
   defaultUsername?: string; // Default username to filter by
 }
 
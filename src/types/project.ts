@@ -2,6 +2,40 @@ export interface ProjectSettings {
   defaultModel?: string;
   portRange?: [number, number];
   autoStartServer: boolean;
+  defaultPlugin?: string;
+  serverTemplate?: string;
+  devServerCommand?: string;
+  devServers?: DevServerProfile[];
+  env?: Record<string, string>;
+  secretEnv?: string[];
+  githubRepo?: string;
+  githubBaseBranch?: string;
+  gitlabUrl?: string;
+  gitlabProject?: string;
+  gitlabTargetBranch?: string;
+  gitlabMrTemplate?: string;
+  issueTracker?: 'linear' | 'github' | 'jira' | 'gitlab';
+  linearTeamId?: string;
+  jiraSite?: string;
+  jiraEmail?: string;
+  jiraProjectKey?: string;
+}
+
+export type RestartPolicy =
+  | { mode: 'never' }
+  | { mode: 'on_failure'; maxRetries: number }
+  | { mode: 'always' };
+
+export interface DevServerProfile {
+  name: string;
+  command: string;
+  cwd?: string;
+  envFile?: string;
+  env?: Record<string, string>;
+  secretEnv?: string[];
+  readyPattern?: string;
+  readyUrl?: string;
+  restartPolicy?: RestartPolicy;
 }
 
 export interface Project {
@@ -14,6 +48,105 @@ export interface Project {
   lastAccessed?: string;
   isFavorite: boolean;
   settings?: ProjectSettings;
+  isArchived: boolean;
+  isMissing: boolean;
+  tags: string[];
+}
+
+export interface ProjectTemplate {
+  id: string;
+  name: string;
+  description?: string;
+  color?: string;
+  settings: ProjectSettings;
+  createdAt: string;
+}
+
+export interface Workspace {
+  id: string;
+  name: string;
+  description?: string;
+  createdAt: string;
+  projectIds: string[];
+}
+
+export interface WorkspaceMemberResult {
+  projectId: string;
+  projectName: string;
+  sessionId?: string;
+  response?: string;
+  error?: string;
+}
+
+export interface ProjectCandidate {
+  name: string;
+  path: string;
+  markers: string[];
+}
+
+export interface LaunchRequest {
+  projectId?: string;
+  pluginId?: string;
+  port?: number;
+  model?: string;
+  workingDir?: string;
+}
+
+export interface LaunchConfig {
+  projectId?: string;
+  pluginId?: string;
+  port: number;
+  model?: string;
+  workingDir?: string;
+  serverTemplate?: string;
+  envKeys: string[];
+}
+
+export interface ProjectActivityEvent {
+  kind: 'session_created' | 'task_completed' | 'task_failed' | 'approval_requested' | 'approval_decided';
+  title: string;
+  detail?: string;
+  sessionId?: string;
+  timestamp: string;
+}
+
+export interface DeleteProjectOptions {
+  dryRun?: boolean;
+  keepProcesses?: boolean;
+  archiveHistory?: boolean;
+}
+
+export interface ProjectDeletionReport {
+  dryRun: boolean;
+  weztermWindows: string[];
+  weztermMirrors: string[];
+  terminals: string[];
+  tmuxSessions: string[];
+  servers: string[];
+  devServers: string[];
+  pluginSessions: string[];
+  archived: boolean;
+  deleted: boolean;
+}
+
+export type CommandTarget = 'shell' | 'tmux' | 'dev_server';
+
+export interface SavedCommand {
+  id: string;
+  projectId: string;
+  name: string;
+  command: string;
+  target: CommandTarget;
+  createdAt: string;
+}
+
+export interface SavedCommandRun {
+  target: CommandTarget;
+  output?: string;
+  exitCode?: number;
+  tmuxSessionId?: string;
+  devServerId?: string;
+  pid?: number;
 }
 
 export interface CreateProjectRequest {